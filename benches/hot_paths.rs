@@ -0,0 +1,121 @@
+//! Benchmarks for the hot paths
+//!
+//! Floor generation for each biome, field of view on a large open map, an
+//! AI tick with 200 enemies awake, drawing a full frame into an offscreen
+//! buffer, and writing out a save. Run with `cargo bench`, before and after
+//! a change made for speed.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use crossterm::event::{KeyCode, KeyEvent};
+use hecs::World;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+use hollowdeep::data::DataManager;
+use hollowdeep::entities::{enemies_for_biome, spawn_enemy};
+use hollowdeep::game::Game;
+use hollowdeep::progression::{CurseSigils, Difficulty};
+use hollowdeep::save::save_game::extract_save_data;
+use hollowdeep::ui::App;
+use hollowdeep::world::generation::generate_floor;
+use hollowdeep::world::{compute_fov, Biome, Map, TileType};
+use hollowdeep::{ecs, Position};
+
+/// Enemies awake for the AI tick
+const AI_ENEMIES: usize = 200;
+
+fn bench_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_floor");
+    for (i, biome) in Biome::ALL.into_iter().enumerate() {
+        let floor = 1 + 5 * i as u32;
+        group.bench_function(biome.name(), |b| {
+            let mut rng = StdRng::seed_from_u64(7);
+            b.iter(|| generate_floor(&mut rng, floor, biome));
+        });
+    }
+    group.finish();
+}
+
+/// A wide hall broken up by pillars, so sight lines are long and shadowed
+fn large_open_map() -> Map {
+    let mut map = Map::new(200, 200, 1, Biome::HollowCathedral);
+    for y in 1..map.height - 1 {
+        for x in 1..map.width - 1 {
+            let pillar = x % 7 == 3 && y % 5 == 2;
+            map.set_tile(x, y, if pillar { TileType::Wall } else { TileType::Floor });
+        }
+    }
+    map
+}
+
+fn bench_fov(c: &mut Criterion) {
+    let mut map = large_open_map();
+    let center = Position::new(100, 100);
+    c.bench_function("fov_large_map", |b| b.iter(|| compute_fov(&mut map, center, 40)));
+}
+
+fn bench_ai_tick(c: &mut Criterion) {
+    let data = DataManager::default();
+    let mut rng = StdRng::seed_from_u64(7);
+    let map = generate_floor(&mut rng, 3, Biome::SunkenCatacombs);
+    let player = map.start_pos;
+    let defs = enemies_for_biome(Biome::SunkenCatacombs);
+    // Spread the enemies across the floor, nearest the player first
+    let mut spots: Vec<Position> = (0..map.tiles.len())
+        .map(|idx| map.idx_to_xy(idx))
+        .filter(|&(x, y)| map.is_walkable(x, y))
+        .map(|(x, y)| Position::new(x, y))
+        .filter(|pos| *pos != player)
+        .collect();
+    spots.sort_by_key(|pos| (pos.x - player.x).abs() + (pos.y - player.y).abs());
+    spots.truncate(AI_ENEMIES);
+
+    c.bench_function("ai_tick_200_enemies", |b| {
+        b.iter_batched(
+            || {
+                let mut world = World::new();
+                for (i, pos) in spots.iter().enumerate() {
+                    spawn_enemy(&mut world, defs[i % defs.len()], *pos);
+                }
+                (world, StdRng::seed_from_u64(11))
+            },
+            |(mut world, mut rng)| ecs::run_enemy_ai(&mut world, &map, player, data.behavior_trees(), false, &mut rng),
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+/// A headless game a few steps into its first floor, and an interface
+/// looking at it
+fn game_under_way() -> (Game, App) {
+    let mut game = Game::headless();
+    game.start_new_run(Some(7), Difficulty::Normal, CurseSigils::default());
+    game.skip_tutorial();
+    let mut app = App::new();
+    app.handle_resize(160);
+    // Any key brings the camera to the player
+    let _ = app.handle_input(KeyEvent::from(KeyCode::Null), &mut game);
+    (game, app)
+}
+
+fn bench_render(c: &mut Criterion) {
+    let (game, app) = game_under_way();
+    let mut terminal = Terminal::new(TestBackend::new(160, 50)).unwrap();
+    c.bench_function("render_full_frame", |b| {
+        b.iter(|| {
+            terminal.draw(|frame| app.render(frame, &game)).unwrap();
+        });
+    });
+}
+
+fn bench_save(c: &mut Criterion) {
+    let (game, _) = game_under_way();
+    c.bench_function("save_serialization", |b| {
+        b.iter(|| serde_json::to_string_pretty(&extract_save_data(&game).unwrap()).unwrap());
+    });
+}
+
+criterion_group!(benches, bench_generation, bench_fov, bench_ai_tick, bench_render, bench_save);
+criterion_main!(benches);
@@ -12,6 +12,7 @@ use kira::{
 };
 
 use super::sounds::SoundId;
+use crate::error::Error;
 
 /// Audio manager that handles all sound playback
 pub struct AudioManager {
@@ -30,15 +31,22 @@ pub struct AudioManager {
 impl AudioManager {
     /// Create a new audio manager
     pub fn new() -> Self {
-        let manager = match KiraManager::<DefaultBackend>::new(AudioManagerSettings::default()) {
+        let (audio, error) = Self::open();
+        if let Some(e) = error {
+            e.log();
+        }
+        audio
+    }
+
+    /// Create a new audio manager on the default sound device. Without
+    /// one it plays nothing, and why comes back alongside.
+    pub fn open() -> (Self, Option<Error>) {
+        let (manager, error) = match KiraManager::<DefaultBackend>::new(AudioManagerSettings::default()) {
             Ok(m) => {
                 log::info!("Audio manager initialized successfully");
-                Some(m)
-            }
-            Err(e) => {
-                log::warn!("Failed to initialize audio manager: {}. Audio disabled.", e);
-                None
+                (Some(m), None)
             }
+            Err(e) => (None, Some(Error::Audio(e.to_string()))),
         };
 
         let mut audio = Self {
@@ -52,7 +60,18 @@ impl AudioManager {
         // Try to preload common sounds
         audio.preload_sounds();
 
-        audio
+        (audio, error)
+    }
+
+    /// An audio manager with no sound device, that plays nothing
+    pub fn silent() -> Self {
+        Self {
+            manager: None,
+            sounds: HashMap::new(),
+            master_volume: 1.0,
+            sfx_volume: 0.7,
+            enabled: false,
+        }
     }
 
     /// Preload commonly used sounds
@@ -63,6 +82,7 @@ impl AudioManager {
             SoundId::Miss,
             SoundId::Critical,
             SoundId::Dodge,
+            SoundId::Block,
             SoundId::EnemyDeath,
             SoundId::PlayerHurt,
             SoundId::ItemPickup,
@@ -16,6 +16,8 @@ pub enum SoundId {
     Critical,
     /// Player or enemy dodges
     Dodge,
+    /// Attack blocked by a shield
+    Block,
     /// Enemy dies
     EnemyDeath,
     /// Player takes damage
@@ -78,6 +80,8 @@ pub enum SoundId {
     Descend,
     /// Door opened
     DoorOpen,
+    /// Secret door found
+    SecretFound,
     /// Footstep (walking)
     Footstep,
 
@@ -88,6 +92,8 @@ pub enum SoundId {
     NewFloor,
     /// Low health warning
     LowHealth,
+    /// Deep rumble under every turn of a collapsing floor
+    Rumble,
 }
 
 impl SoundId {
@@ -99,6 +105,7 @@ impl SoundId {
             SoundId::Miss => "assets/sounds/combat/miss.ogg",
             SoundId::Critical => "assets/sounds/combat/critical.ogg",
             SoundId::Dodge => "assets/sounds/combat/dodge.ogg",
+            SoundId::Block => "assets/sounds/combat/block.ogg",
             SoundId::EnemyDeath => "assets/sounds/combat/enemy_death.ogg",
             SoundId::PlayerHurt => "assets/sounds/combat/player_hurt.ogg",
             SoundId::PlayerDeath => "assets/sounds/combat/player_death.ogg",
@@ -135,12 +142,14 @@ impl SoundId {
             SoundId::ShrineUse => "assets/sounds/environment/shrine_use.ogg",
             SoundId::Descend => "assets/sounds/environment/descend.ogg",
             SoundId::DoorOpen => "assets/sounds/environment/door.ogg",
+            SoundId::SecretFound => "assets/sounds/environment/secret.ogg",
             SoundId::Footstep => "assets/sounds/environment/footstep.ogg",
 
             // Ambient
             SoundId::LevelUp => "assets/sounds/ambient/level_up.ogg",
             SoundId::NewFloor => "assets/sounds/ambient/new_floor.ogg",
             SoundId::LowHealth => "assets/sounds/ambient/low_health.ogg",
+            SoundId::Rumble => "assets/sounds/ambient/rumble.ogg",
         }
     }
 
@@ -152,7 +161,7 @@ impl SoundId {
             SoundId::MenuMove => 0.4,
 
             // Normal volume
-            SoundId::Hit | SoundId::Miss | SoundId::Dodge => 0.6,
+            SoundId::Hit | SoundId::Miss | SoundId::Dodge | SoundId::Block => 0.6,
             SoundId::ItemPickup | SoundId::ItemDrop => 0.5,
             SoundId::GoldPickup => 0.5,
             SoundId::MenuSelect | SoundId::MenuBack => 0.5,
@@ -194,7 +203,7 @@ impl SoundId {
     /// Get the category for this sound
     pub fn category(&self) -> SoundCategory {
         match self {
-            SoundId::Hit | SoundId::Miss | SoundId::Critical | SoundId::Dodge |
+            SoundId::Hit | SoundId::Miss | SoundId::Critical | SoundId::Dodge | SoundId::Block |
             SoundId::EnemyDeath | SoundId::PlayerHurt | SoundId::PlayerDeath |
             SoundId::BossDefeat => SoundCategory::Combat,
 
@@ -211,9 +220,10 @@ impl SoundId {
             SoundId::SkillMovement => SoundCategory::Skills,
 
             SoundId::ShrineApproach | SoundId::ShrineUse | SoundId::Descend |
-            SoundId::DoorOpen | SoundId::Footstep => SoundCategory::Environment,
+            SoundId::DoorOpen | SoundId::SecretFound | SoundId::Footstep => SoundCategory::Environment,
 
-            SoundId::LevelUp | SoundId::NewFloor | SoundId::LowHealth => SoundCategory::Ambient,
+            SoundId::LevelUp | SoundId::NewFloor | SoundId::LowHealth |
+            SoundId::Rumble => SoundCategory::Ambient,
         }
     }
 }
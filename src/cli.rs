@@ -0,0 +1,154 @@
+//! Command line
+//!
+//! Everything the binary takes on the command line. Most of it skips the
+//! menus: straight into a new run or a save, or no terminal at all for
+//! scripts and CI.
+
+use clap::Parser;
+use log::LevelFilter;
+
+use crate::progression::Difficulty;
+use crate::render::preview::PreviewSpec;
+use crate::render::RenderMode;
+use crate::save::AUTOSAVE_SLOT;
+
+/// A grimdark terminal roguelike
+#[derive(Debug, Parser)]
+#[command(name = "hollowdeep", version)]
+pub struct Cli {
+    /// Seed the dungeon of the first run started
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Load a save slot straight away: 1 to 3, or `auto` for the autosave
+    #[arg(long, value_name = "SLOT", value_parser = parse_slot, conflicts_with = "new_run")]
+    pub load: Option<u8>,
+
+    /// Start a new run straight away on a difficulty: easy, normal, hard
+    /// or nightmare
+    #[arg(long, value_name = "DIFFICULTY", value_parser = parse_difficulty)]
+    pub new_run: Option<Difficulty>,
+
+    /// How the map is drawn: auto, ascii, unicode, nerd or kitty. Overrides
+    /// the saved setting; `auto` goes by what the terminal was detected as.
+    #[arg(long, value_name = "MODE", value_parser = parse_render_mode)]
+    pub render_mode: Option<RenderModeChoice>,
+
+    /// Don't open the terminal: set up the run the other flags ask for,
+    /// print where it stands and quit. The profile is left alone.
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Load the data files, print any problems and quit, failing if there
+    /// were any
+    #[arg(long)]
+    pub validate_data: bool,
+
+    /// Draw the layout the generator makes for a floor, and quit. Takes
+    /// `floor=N`, `seed=S`, `format=text|ansi|png` and `out=PATH`; a bare
+    /// number is the floor. Without a seed `--seed` is used, or a random one.
+    #[arg(long, value_name = "SETTING", num_args = 1.., value_parser = parse_preview_setting)]
+    pub gen_preview: Option<Vec<String>>,
+
+    /// Log at this level and above: off, error, warn, info, debug or trace
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<LevelFilter>,
+
+    /// Wizard mode: the look cursor inspects entities, F3 shows frame times
+    #[arg(long)]
+    pub wizard: bool,
+
+    /// Print how standard builds fare against every enemy, and quit
+    #[arg(long)]
+    pub balance_report: bool,
+
+    /// Generate floors per biome, check their invariants and quit
+    #[arg(long, value_name = "COUNT", num_args = 0..=1, default_missing_value = "100")]
+    pub gen_check: Option<u64>,
+}
+
+impl Cli {
+    /// The floor preview asked for, if any
+    pub fn preview(&self) -> Option<PreviewSpec> {
+        let words = self.gen_preview.as_ref()?;
+        let mut spec = PreviewSpec::parse(words).expect("preview words were checked as they were parsed");
+        spec.seed = spec.seed.or(self.seed);
+        Some(spec)
+    }
+}
+
+/// A render mode picked on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderModeChoice {
+    /// Whatever the terminal was detected as, even over a saved setting
+    Auto,
+    Mode(RenderMode),
+}
+
+impl RenderModeChoice {
+    /// The mode to draw with, or `None` for the detected one
+    pub fn mode(self) -> Option<RenderMode> {
+        match self {
+            RenderModeChoice::Auto => None,
+            RenderModeChoice::Mode(mode) => Some(mode),
+        }
+    }
+}
+
+/// Save slots as the load screen numbers them, from 1
+fn parse_slot(slot: &str) -> Result<u8, String> {
+    match slot {
+        "auto" | "autosave" => Ok(AUTOSAVE_SLOT),
+        _ => match slot.parse::<u8>() {
+            Ok(n @ 1..=3) => Ok(n - 1),
+            _ => Err("expected 1, 2, 3 or auto".to_string()),
+        },
+    }
+}
+
+fn parse_difficulty(name: &str) -> Result<Difficulty, String> {
+    [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Nightmare]
+        .into_iter()
+        .find(|difficulty| difficulty.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| "expected easy, normal, hard or nightmare".to_string())
+}
+
+/// Checks each preview word on its own so mistakes are reported as usage
+/// errors; the words are put together by [`Cli::preview`].
+fn parse_preview_setting(word: &str) -> Result<String, String> {
+    PreviewSpec::parse(&[word]).map(|_| word.to_string())
+}
+
+fn parse_render_mode(name: &str) -> Result<RenderModeChoice, String> {
+    if name.eq_ignore_ascii_case("auto") {
+        return Ok(RenderModeChoice::Auto);
+    }
+    RenderMode::parse(name)
+        .map(RenderModeChoice::Mode)
+        .ok_or_else(|| "expected auto, ascii, unicode, nerd or kitty".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_reads_what_scripts_pass() {
+        let cli = Cli::try_parse_from(["hollowdeep", "--seed", "42", "--new-run", "hard", "--headless", "--render-mode", "ascii"]).unwrap();
+        assert_eq!((cli.seed, cli.new_run, cli.headless), (Some(42), Some(Difficulty::Hard), true));
+        assert_eq!(cli.render_mode.and_then(RenderModeChoice::mode), Some(RenderMode::Ascii));
+
+        // Slots are numbered from 1, as on the load screen
+        let cli = Cli::try_parse_from(["hollowdeep", "--load", "2", "--gen-check"]).unwrap();
+        assert_eq!((cli.load, cli.gen_check), (Some(1), Some(100)));
+        assert_eq!(Cli::try_parse_from(["hollowdeep", "--load", "auto"]).unwrap().load, Some(AUTOSAVE_SLOT));
+
+        assert!(Cli::try_parse_from(["hollowdeep", "--load", "4"]).is_err());
+        assert!(Cli::try_parse_from(["hollowdeep", "--load", "1", "--new-run", "easy"]).is_err());
+        assert!(Cli::try_parse_from(["hollowdeep", "--render-mode", "sixel"]).is_err());
+
+        let preview = Cli::try_parse_from(["hollowdeep", "--seed", "7", "--gen-preview", "floor=4", "format=png"]).unwrap().preview().unwrap();
+        assert_eq!((preview.floor, preview.seed), (4, Some(7)));
+        assert!(Cli::try_parse_from(["hollowdeep", "--gen-preview", "floor=deep"]).is_err());
+    }
+}
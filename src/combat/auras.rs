@@ -0,0 +1,213 @@
+//! Auras and passive effects
+//!
+//! Lasting effects an entity carries rather than casts: thorns that hurt
+//! whoever strikes it, steady regeneration, and a fear aura that shakes the
+//! nerve of nearby enemies. Gear grants them through affixes, and skills
+//! grant them for a while through the `Auras` component. The aura system
+//! resolves them all once a turn, so attack code only notes who hit whom.
+
+use hecs::{Entity, World};
+use serde::{Deserialize, Serialize};
+use crate::ecs::{Enemy, EquipmentComponent, Health, Position};
+use crate::items::AffixType;
+use super::stats::DerivedStats;
+
+/// A lasting effect around an entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Aura {
+    /// Share of each blow taken dealt back to the attacker (percent)
+    Thorns(i32),
+    /// HP regained each turn
+    Regeneration(i32),
+    /// Enemies within `radius` lose `morale` percent of their nerve
+    Fear { radius: i32, morale: i32 },
+}
+
+/// An aura granted for a number of turns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedAura {
+    pub aura: Aura,
+    pub turns: u32,
+}
+
+/// Auras an entity has been granted, by skills for instance
+#[derive(Debug, Clone, Default)]
+pub struct Auras(pub Vec<TimedAura>);
+
+impl Auras {
+    /// Grant an aura, refreshing one of the same kind
+    pub fn grant(&mut self, aura: Aura, turns: u32) {
+        let same_kind = |a: &TimedAura| std::mem::discriminant(&a.aura) == std::mem::discriminant(&aura);
+        self.0.retain(|a| !same_kind(a));
+        self.0.push(TimedAura { aura, turns });
+    }
+
+    /// Count down a turn, dropping auras that have run out
+    pub fn tick(&mut self) {
+        for aura in &mut self.0 {
+            aura.turns = aura.turns.saturating_sub(1);
+        }
+        self.0.retain(|a| a.turns > 0);
+    }
+}
+
+/// An enemy whose nerve a fear aura has shaken. It breaks and flees that
+/// many percent of health sooner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shaken(pub i32);
+
+/// A blow that landed this turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub damage: i32,
+}
+
+/// What the aura system did this turn
+#[derive(Debug, Clone, Default)]
+pub struct AuraOutcome {
+    /// HP regained by each regenerating entity
+    pub regenerated: Vec<(Entity, i32)>,
+    /// Thorns damage dealt: (attacker hurt, thorns holder, damage)
+    pub thorns: Vec<(Entity, Entity, i32)>,
+    /// Enemies newly shaken by a fear aura
+    pub shaken: Vec<Entity>,
+}
+
+/// Every aura an entity carries, from gear as well as granted ones
+pub fn auras_of(world: &World, entity: Entity) -> Vec<Aura> {
+    let mut auras: Vec<Aura> = world.get::<&Auras>(entity)
+        .map(|granted| granted.0.iter().map(|a| a.aura).collect())
+        .unwrap_or_default();
+    if let Ok(eq) = world.get::<&EquipmentComponent>(entity) {
+        let thorns = eq.equipment.stat_bonus(AffixType::Thorns);
+        if thorns > 0 {
+            auras.push(Aura::Thorns(thorns));
+        }
+        let regeneration = eq.equipment.stat_bonus(AffixType::Regeneration);
+        if regeneration > 0 {
+            auras.push(Aura::Regeneration(regeneration));
+        }
+    }
+    auras
+}
+
+/// Resolve every aura for a turn: regeneration, fear around its holders and
+/// thorns against this turn's `hits`. Granted auras then count down.
+pub fn run_auras(world: &mut World, hits: &[Hit]) -> AuraOutcome {
+    let mut outcome = AuraOutcome::default();
+
+    let holders: Vec<(Entity, Vec<Aura>)> = world
+        .query::<&Health>()
+        .iter()
+        .map(|(entity, _)| (entity, auras_of(world, entity)))
+        .filter(|(_, auras)| !auras.is_empty())
+        .collect();
+
+    // Regeneration, up to the holder's effective max HP
+    for (entity, auras) in &holders {
+        let amount: i32 = auras.iter()
+            .map(|a| if let Aura::Regeneration(hp) = a { *hp } else { 0 })
+            .sum();
+        if amount <= 0 {
+            continue;
+        }
+        let Some(max_hp) = DerivedStats::compute(world, *entity).map(|d| d.max_hp) else { continue };
+        if let Ok(mut health) = world.get::<&mut Health>(*entity) {
+            let healed = amount.min(max_hp - health.current).max(0);
+            if healed > 0 && !health.is_dead() {
+                health.current += healed;
+                outcome.regenerated.push((*entity, healed));
+            }
+        }
+    }
+
+    // Fear shakes the enemies near anyone who isn't one of them
+    let fears: Vec<(Position, i32, i32)> = holders.iter()
+        .filter(|(entity, _)| world.get::<&Enemy>(*entity).is_err())
+        .filter_map(|(entity, auras)| world.get::<&Position>(*entity).ok().map(|pos| (*pos, auras)))
+        .flat_map(|(pos, auras)| auras.iter().filter_map(move |a| match a {
+            Aura::Fear { radius, morale } => Some((pos, *radius, *morale)),
+            _ => None,
+        }))
+        .collect();
+    let enemies: Vec<(Entity, Position)> = world.query::<(&Enemy, &Position)>()
+        .iter()
+        .map(|(entity, (_, pos))| (entity, *pos))
+        .collect();
+    for (enemy, pos) in enemies {
+        let morale = fears.iter()
+            .filter(|(center, radius, _)| pos.chebyshev_distance(center) <= *radius)
+            .map(|(_, _, morale)| *morale)
+            .max();
+        let was_shaken = world.get::<&Shaken>(enemy).is_ok();
+        match morale {
+            Some(morale) => {
+                let _ = world.insert_one(enemy, Shaken(morale));
+                if !was_shaken {
+                    outcome.shaken.push(enemy);
+                }
+            }
+            None if was_shaken => {
+                let _ = world.remove_one::<Shaken>(enemy);
+            }
+            None => {}
+        }
+    }
+
+    // Thorns strike back at whoever landed a blow
+    for hit in hits {
+        let percent: i32 = auras_of(world, hit.target).iter()
+            .map(|a| if let Aura::Thorns(p) = a { *p } else { 0 })
+            .sum();
+        if percent <= 0 || hit.damage <= 0 {
+            continue;
+        }
+        let damage = (hit.damage * percent / 100).max(1);
+        if let Ok(mut health) = world.get::<&mut Health>(hit.attacker) {
+            if !health.is_dead() {
+                health.take_damage(damage);
+                outcome.thorns.push((hit.attacker, hit.target, damage));
+            }
+        }
+    }
+
+    for (_, auras) in world.query_mut::<&mut Auras>() {
+        auras.tick();
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{EnemyArchetype, Stats};
+
+    #[test]
+    fn test_auras() {
+        let mut world = World::new();
+        let mut auras = Auras::default();
+        auras.grant(Aura::Thorns(50), 2);
+        auras.grant(Aura::Regeneration(3), 1);
+        auras.grant(Aura::Fear { radius: 3, morale: 30 }, 2);
+        let hero = world.spawn((Position::new(0, 0), Stats::new(10, 10, 10, 10), Health { current: 10, max: 20 }, auras));
+        let rat = world.spawn((Position::new(2, 0), Stats::new(5, 5, 5, 5), Health::new(20), Enemy { archetype: EnemyArchetype::Melee }));
+        let far = world.spawn((Position::new(9, 0), Stats::new(5, 5, 5, 5), Health::new(20), Enemy { archetype: EnemyArchetype::Melee }));
+
+        let outcome = run_auras(&mut world, &[Hit { attacker: rat, target: hero, damage: 8 }]);
+        assert_eq!(outcome.regenerated, vec![(hero, 3)]);
+        assert_eq!(outcome.thorns, vec![(rat, hero, 4)]);
+        assert_eq!(world.get::<&Health>(rat).unwrap().current, 16);
+        assert_eq!(world.get::<&Shaken>(rat).map(|s| s.0).ok(), Some(30));
+        assert!(world.get::<&Shaken>(far).is_err());
+
+        // Regeneration has run out; the rest lasts one more turn
+        assert_eq!(world.get::<&Auras>(hero).unwrap().0.len(), 2);
+        run_auras(&mut world, &[]);
+        run_auras(&mut world, &[]);
+        assert!(world.get::<&Auras>(hero).unwrap().0.is_empty());
+        assert!(world.get::<&Shaken>(rat).is_err());
+    }
+}
@@ -4,6 +4,15 @@
 
 use rand::Rng;
 use crate::ecs::Stats;
+use crate::data::{WeaponProcs, WeaponProcDef};
+use crate::items::WeaponType;
+
+/// Extra crit chance (percentage points) when backstabbing an unaware enemy
+pub const BACKSTAB_CRIT_BONUS: f32 = 50.0;
+/// Damage lost while exhausted (percent)
+pub const EXHAUSTION_DAMAGE_PENALTY: i32 = 25;
+/// Dodge chance lost while exhausted (percentage points)
+pub const EXHAUSTION_DODGE_PENALTY: f32 = 10.0;
 
 /// Result of a combat attack
 #[derive(Debug, Clone)]
@@ -104,6 +113,20 @@ pub struct EquipmentBonuses {
     pub dex_bonus: i32,
     /// Weapon crit bonus (percentage points)
     pub crit_bonus: f32,
+    /// Share of the defender's armor ignored (percent)
+    pub armor_penetration: i32,
+    /// Dodge chance modifier from armor weight (percentage points)
+    pub dodge_bonus: f32,
+    /// Damage dealt raised or lowered by this percent (set bonuses, buffs, exhaustion)
+    pub damage_percent: i32,
+}
+
+impl EquipmentBonuses {
+    /// Base damage of a blow from `strength`, before crits and armor
+    fn blow_damage(&self, strength: i32) -> i32 {
+        let flat = base_physical_damage(strength + self.str_bonus) + self.weapon_damage;
+        (flat * (100 + self.damage_percent).max(0) / 100).max(1)
+    }
 }
 
 /// Calculate a full attack
@@ -130,26 +153,25 @@ pub fn calculate_attack_with_equipment(
     rng: &mut impl Rng,
 ) -> AttackResult {
     // Effective stats with equipment bonuses
-    let attacker_str = attacker_stats.strength + attacker_equipment.str_bonus;
     let attacker_dex = attacker_stats.dexterity + attacker_equipment.dex_bonus;
     let defender_dex = defender_stats.dexterity + defender_equipment.dex_bonus;
     let defender_vit = defender_stats.vitality;
 
     // Check for dodge first
     let hit_roll = rng.gen_range(0.0..100.0);
-    let hit_pct = hit_chance(attacker_dex, defender_dex);
+    let hit_pct = (hit_chance(attacker_dex, defender_dex) - defender_equipment.dodge_bonus).clamp(20.0, 99.0);
 
     if hit_roll >= hit_pct {
         // Check if it was a dodge or miss
-        if dodge_chance(defender_dex) > 10.0 && rng.gen_bool(0.7) {
+        if dodge_chance(defender_dex) + defender_equipment.dodge_bonus > 10.0 && rng.gen_bool(0.7) {
             return AttackResult::dodged();
         } else {
             return AttackResult::missed();
         }
     }
 
-    // Calculate base damage (STR bonus + weapon damage)
-    let base_damage = base_physical_damage(attacker_str) + attacker_equipment.weapon_damage;
+    // Calculate base damage (STR bonus + weapon damage, then percent modifiers)
+    let base_damage = attacker_equipment.blow_damage(attacker_stats.strength);
 
     // Check for crit (base crit + weapon crit bonus)
     let crit_roll = rng.gen_range(0.0..100.0);
@@ -165,7 +187,8 @@ pub fn calculate_attack_with_equipment(
 
     // Apply armor reduction (VIT-based armor + equipment armor)
     // Uses percentage-based reduction with diminishing returns
-    let total_armor = armor_from_vit(defender_vit) + defender_equipment.armor;
+    let total_armor = (armor_from_vit(defender_vit) + defender_equipment.armor)
+        * (100 - attacker_equipment.armor_penetration.clamp(0, 100)) / 100;
     let reduction_pct = damage_reduction_percent(total_armor);
     let damage_reduced = (damage_after_crit as f32 * (1.0 - reduction_pct)).round() as i32;
     let final_damage = damage_reduced.max(1); // Always at least 1 damage
@@ -186,6 +209,41 @@ pub fn calculate_attack_with_equipment(
     }
 }
 
+/// Average damage one swing deals, counting misses, crits and armor
+pub fn expected_damage(
+    attacker_stats: &Stats,
+    defender_stats: &Stats,
+    attacker_equipment: &EquipmentBonuses,
+    defender_equipment: &EquipmentBonuses,
+) -> f32 {
+    let attacker_dex = attacker_stats.dexterity + attacker_equipment.dex_bonus;
+    let defender_dex = defender_stats.dexterity + defender_equipment.dex_bonus;
+    let hit = (hit_chance(attacker_dex, defender_dex) - defender_equipment.dodge_bonus).clamp(20.0, 99.0) / 100.0;
+    let crit = ((crit_chance(attacker_dex) + attacker_equipment.crit_bonus) / 100.0).min(1.0);
+
+    let base = attacker_equipment.blow_damage(attacker_stats.strength);
+    let armor = (armor_from_vit(defender_stats.vitality) + defender_equipment.armor)
+        * (100 - attacker_equipment.armor_penetration.clamp(0, 100)) / 100;
+    let landed = (base as f32 * (1.0 + crit) * (1.0 - damage_reduction_percent(armor))).max(1.0);
+    hit * landed
+}
+
+/// Damage multiplier for a dagger backstab (the Shadow set sharpens it)
+pub fn backstab_multiplier(shadow_synergy: bool) -> f32 {
+    if shadow_synergy { 2.0 } else { 1.5 }
+}
+
+/// Roll a weapon's on-hit procs, returning the ones that trigger
+pub fn roll_weapon_procs<'a>(
+    procs: &'a WeaponProcs,
+    weapon: WeaponType,
+    rng: &mut impl Rng,
+) -> Vec<&'a WeaponProcDef> {
+    procs.for_weapon(weapon)
+        .filter(|p| rng.gen_range(0..100) < p.chance)
+        .collect()
+}
+
 /// Simplified attack calculation for enemies (they have stats too)
 pub fn calculate_enemy_attack(
     attacker_stats: &Stats,
@@ -207,6 +265,33 @@ mod tests {
         assert!(crit_chance(100) <= 50.0); // Capped
     }
 
+    #[test]
+    fn test_armor_penetration() {
+        use rand::SeedableRng;
+        let attacker = Stats::new(10, 100, 10, 10); // High DEX so the blow lands
+        let defender = Stats::new(10, 0, 10, 10);
+        let armored = EquipmentBonuses { armor: 40, ..Default::default() };
+        let blunt = EquipmentBonuses { weapon_damage: 20, ..Default::default() };
+        let crushing = EquipmentBonuses { armor_penetration: 50, ..blunt.clone() };
+
+        let hit = |bonuses: &EquipmentBonuses| {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+            calculate_attack_with_equipment(&attacker, &defender, bonuses, &armored, &mut rng)
+        };
+        let (plain, pierced) = (hit(&blunt), hit(&crushing));
+        assert_eq!(plain.is_crit, pierced.is_crit);
+        assert!(pierced.final_damage > plain.final_damage);
+    }
+
+    #[test]
+    fn test_expected_damage() {
+        let stats = Stats::new(10, 10, 10, 10);
+        let plain = expected_damage(&stats, &stats, &EquipmentBonuses::default(), &EquipmentBonuses::default());
+        let armed = EquipmentBonuses { weapon_damage: 10, ..Default::default() };
+        assert!(plain > 0.0 && plain < base_physical_damage(10) as f32 * 2.0);
+        assert!(expected_damage(&stats, &stats, &armed, &EquipmentBonuses::default()) > plain);
+    }
+
     #[test]
     fn test_base_damage() {
         assert_eq!(base_physical_damage(10), 7); // 2 + 10/2 = 7
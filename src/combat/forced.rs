@@ -0,0 +1,140 @@
+//! Forced movement
+//!
+//! Knockbacks, pulls and yanks all shove an entity across the map one tile
+//! at a time, stopping at the first wall, body or hazard in its way.
+
+use hecs::{Entity, World};
+use crate::ecs::{BlocksMovement, Player, Position};
+use crate::world::{Map, TileType};
+
+/// Damage per tile of momentum left when slamming into a wall or body
+pub const SLAM_DAMAGE_PER_TILE: i32 = 5;
+/// Damage a boss takes instead of falling into a hazard
+pub const HAZARD_BOSS_DAMAGE: i32 = 30;
+/// Damage the player takes when dragged into a hazard
+pub const HAZARD_PLAYER_DAMAGE: i32 = 20;
+
+/// What stopped a forced movement early
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collision {
+    /// A wall, closed door or the map edge
+    Wall,
+    /// Another creature in the way
+    Entity(Entity),
+    /// Lava or a pit
+    Hazard(TileType),
+}
+
+/// Result of a forced movement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForcedMove {
+    /// Where the entity came to rest
+    pub to: Position,
+    /// Tiles actually travelled
+    pub moved: i32,
+    /// Tiles of momentum left when something stopped it
+    pub remaining: i32,
+    /// What stopped it, if anything
+    pub collision: Option<Collision>,
+}
+
+impl ForcedMove {
+    /// Bonus damage from being slammed into a wall or another creature
+    pub fn slam_damage(&self) -> i32 {
+        match self.collision {
+            Some(Collision::Wall) | Some(Collision::Entity(_)) => self.remaining * SLAM_DAMAGE_PER_TILE,
+            _ => 0,
+        }
+    }
+}
+
+/// Shove `entity` up to `distance` tiles in `direction`.
+///
+/// The entity never enters a hazard tile; it stops on the brink and the
+/// hazard is reported so the caller can decide its fate.
+pub fn force_move(
+    world: &mut World,
+    map: &Map,
+    entity: Entity,
+    direction: (i32, i32),
+    distance: i32,
+) -> ForcedMove {
+    let start = world.get::<&Position>(entity).map(|p| *p).unwrap_or(Position::new(0, 0));
+    let mut current = start;
+    let mut moved = 0;
+    let mut collision = None;
+
+    while moved < distance {
+        let next = Position::new(current.x + direction.0, current.y + direction.1);
+        let tile = map.get_tile(next.x, next.y).map(|t| t.tile_type);
+
+        collision = match tile {
+            Some(t) if t.is_lethal() => Some(Collision::Hazard(t)),
+            Some(t) if t.is_walkable() => occupant(world, next, entity).map(Collision::Entity),
+            _ => Some(Collision::Wall),
+        };
+        if collision.is_some() {
+            break;
+        }
+
+        current = next;
+        moved += 1;
+    }
+
+    if let Ok(mut pos) = world.get::<&mut Position>(entity) {
+        *pos = current;
+    }
+
+    ForcedMove {
+        to: current,
+        moved,
+        remaining: distance - moved,
+        collision,
+    }
+}
+
+/// Direction (one step per axis) from `from` toward `to`
+pub fn direction_toward(from: Position, to: Position) -> (i32, i32) {
+    ((to.x - from.x).signum(), (to.y - from.y).signum())
+}
+
+/// Creature standing at a position, other than the one being moved
+fn occupant(world: &World, pos: Position, mover: Entity) -> Option<Entity> {
+    world
+        .query::<&Position>()
+        .iter()
+        .filter(|(e, p)| *e != mover && **p == pos)
+        .find(|(e, _)| world.get::<&BlocksMovement>(*e).is_ok() || world.get::<&Player>(*e).is_ok())
+        .map(|(e, _)| e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Biome;
+
+    #[test]
+    fn test_knockback_stops_at_walls_and_hazards() {
+        // A corridor of floor from x=1..=5 with a pit at x=6
+        let mut map = Map::new(10, 3, 1, Biome::SunkenCatacombs);
+        for x in 1..=5 {
+            map.set_tile(x, 1, TileType::Floor);
+        }
+        map.set_tile(6, 1, TileType::Pit);
+
+        let mut world = World::new();
+        let enemy = world.spawn((Position::new(3, 1), BlocksMovement));
+
+        // Shoved west into the wall with a tile of momentum to spare
+        let result = force_move(&mut world, &map, enemy, (-1, 0), 3);
+        assert_eq!(result.to, Position::new(1, 1));
+        assert_eq!(result.collision, Some(Collision::Wall));
+        assert_eq!(result.slam_damage(), SLAM_DAMAGE_PER_TILE);
+
+        // Shoved east, it stops on the brink of the pit
+        let result = force_move(&mut world, &map, enemy, (1, 0), 10);
+        assert_eq!(result.to, Position::new(5, 1));
+        assert_eq!(result.collision, Some(Collision::Hazard(TileType::Pit)));
+        assert_eq!(*world.get::<&Position>(enemy).unwrap(), Position::new(5, 1));
+    }
+}
@@ -4,6 +4,13 @@ pub mod stats;
 pub mod damage;
 pub mod abilities;
 pub mod status;
+pub mod forced;
+pub mod auras;
+pub mod resistances;
 
-pub use damage::{calculate_attack, calculate_attack_with_equipment, calculate_enemy_attack, AttackResult, EquipmentBonuses, crit_chance, dodge_chance};
-pub use status::{StatusTickResult, apply_status_damage};
+pub use damage::{calculate_attack, calculate_attack_with_equipment, calculate_enemy_attack, expected_damage, AttackResult, EquipmentBonuses, crit_chance, dodge_chance, backstab_multiplier, roll_weapon_procs, BACKSTAB_CRIT_BONUS, EXHAUSTION_DAMAGE_PENALTY, EXHAUSTION_DODGE_PENALTY};
+pub use stats::{DerivedStats, StatSources, StatBreakdown, Contribution};
+pub use status::{run_status_effects, StatusEvent};
+pub use resistances::{afflict, Resistance, Resistances};
+pub use auras::{auras_of, run_auras, Aura, AuraOutcome, Auras, Hit, Shaken, TimedAura};
+pub use forced::{force_move, direction_toward, Collision, ForcedMove, SLAM_DAMAGE_PER_TILE, HAZARD_BOSS_DAMAGE, HAZARD_PLAYER_DAMAGE};
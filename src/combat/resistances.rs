@@ -0,0 +1,102 @@
+//! Resistances and immunities
+//!
+//! Some creatures shrug off what would harm others: bones don't bleed, and
+//! fire feeds a fire elemental rather than burning it. Enemy templates
+//! declare these per status effect, and the `Resistances` component holds
+//! them on the entity, where both applying a status and its damage each
+//! turn respect them.
+
+use hecs::{Entity, World};
+use serde::{Deserialize, Serialize};
+use crate::ecs::{StatusEffects, StatusEffectType};
+
+/// How a creature takes one kind of harm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resistance {
+    /// Takes this many percent less damage from it
+    Resists(i32),
+    /// Can't be afflicted by it at all
+    Immune,
+    /// Is healed by its damage instead of hurt
+    Absorbs,
+}
+
+/// Everything a creature resists
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Resistances(pub Vec<(StatusEffectType, Resistance)>);
+
+impl Resistances {
+    /// How this creature takes an effect, if it resists it at all
+    pub fn against(&self, effect: StatusEffectType) -> Option<Resistance> {
+        self.0.iter().find(|(e, _)| *e == effect).map(|(_, r)| *r)
+    }
+
+    /// Whether an effect can't take hold at all
+    pub fn is_immune(&self, effect: StatusEffectType) -> bool {
+        self.against(effect) == Some(Resistance::Immune)
+    }
+
+    /// HP an effect's tick of `amount` damage really costs (negative heals)
+    pub fn adjust(&self, effect: StatusEffectType, amount: i32) -> i32 {
+        if amount <= 0 {
+            return amount;
+        }
+        match self.against(effect) {
+            None => amount,
+            Some(Resistance::Resists(percent)) => amount * (100 - percent.clamp(0, 100)) / 100,
+            Some(Resistance::Immune) => 0,
+            Some(Resistance::Absorbs) => -amount,
+        }
+    }
+
+    /// One line per resistance, for examining the creature
+    pub fn describe(&self) -> Vec<String> {
+        self.0.iter().map(|(effect, resistance)| {
+            let name = effect.name().to_lowercase();
+            match resistance {
+                Resistance::Resists(percent) => format!("Resists {} ({}%)", name, percent),
+                Resistance::Immune => format!("Immune to {}", name),
+                Resistance::Absorbs => format!("Healed by {}", name),
+            }
+        }).collect()
+    }
+}
+
+/// Afflict an entity with a status effect unless it's immune. Returns
+/// whether the effect took hold.
+pub fn afflict(world: &mut World, entity: Entity, effect: StatusEffectType, duration: f32, intensity: i32) -> bool {
+    if world.get::<&Resistances>(entity).is_ok_and(|r| r.is_immune(effect)) {
+        return false;
+    }
+    if let Ok(mut effects) = world.get::<&mut StatusEffects>(entity) {
+        effects.add_effect(effect, duration, intensity);
+        return true;
+    }
+    let mut effects = StatusEffects::default();
+    effects.add_effect(effect, duration, intensity);
+    world.insert_one(entity, effects).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resistances() {
+        let resistances = Resistances(vec![
+            (StatusEffectType::Bleed, Resistance::Immune),
+            (StatusEffectType::Poison, Resistance::Resists(50)),
+            (StatusEffectType::Burn, Resistance::Absorbs),
+        ]);
+        assert_eq!(resistances.adjust(StatusEffectType::Poison, 5), 2);
+        assert_eq!(resistances.adjust(StatusEffectType::Burn, 3), -3);
+        assert_eq!(resistances.adjust(StatusEffectType::Bleed, 4), 0);
+        assert_eq!(resistances.adjust(StatusEffectType::Regeneration, -4), -4);
+
+        let mut world = World::new();
+        let skeleton = world.spawn((resistances, StatusEffects::default()));
+        assert!(!afflict(&mut world, skeleton, StatusEffectType::Bleed, 3.0, 2));
+        assert!(afflict(&mut world, skeleton, StatusEffectType::Burn, 3.0, 2));
+        assert_eq!(world.get::<&StatusEffects>(skeleton).unwrap().effects.len(), 1);
+    }
+}
@@ -1,2 +1,323 @@
 //! Combat stat calculations
-pub fn placeholder() {}
+//!
+//! The derived numbers a fight turns on (damage, armor, crit and dodge,
+//! effective max HP and MP), worked out with the same formulas attacks are
+//! rolled with. Each keeps the parts it was built from, so the character
+//! sheet can show its working. `DerivedStats::compute` gathers them for any
+//! entity, so combat, heals and the UI all agree on gear, set bonuses,
+//! mutations, buffs and exhaustion.
+
+use hecs::{Entity, World};
+use crate::ecs::{EquipmentComponent, Exhausted, Health, Mana, StatusEffectType, StatusEffects, Stats};
+use crate::items::{AffixType, WeightClass, DUAL_WIELD_DEX_PENALTY};
+use crate::progression::Mutations;
+use super::damage::{
+    armor_from_vit, base_physical_damage, crit_chance, damage_reduction_percent, dodge_chance,
+    EquipmentBonuses, EXHAUSTION_DAMAGE_PENALTY, EXHAUSTION_DODGE_PENALTY,
+};
+
+/// Share of each blow healed back per point of the LifeSteal affix (percent)
+const LIFESTEAL_PER_POINT: f32 = 5.0;
+
+/// Everything that feeds the player's derived stats
+#[derive(Debug, Clone, Copy)]
+pub struct StatSources {
+    /// Attributes as allocated
+    pub base: Stats,
+    /// Attributes added by gear
+    pub gear: Stats,
+    pub weapon_damage: i32,
+    pub gear_armor: i32,
+    /// Crit chance the weapon adds (percentage points)
+    pub weapon_crit: f32,
+    /// Dodge the armor's weight class adds or takes (percentage points)
+    pub weight_dodge: f32,
+    pub mutation_damage: i32,
+    pub mutation_armor: i32,
+    /// Flat damage from active item sets
+    pub set_damage: i32,
+    /// Damage percent from active item sets
+    pub set_damage_percent: i32,
+    pub set_armor: i32,
+    /// Crit chance from active item sets (percentage points)
+    pub set_crit: f32,
+    /// Damage percent from Strength less Weakness
+    pub buff_damage_percent: i32,
+    /// Armor from Shield
+    pub buff_armor: i32,
+    pub exhausted: bool,
+}
+
+/// One part of a derived stat and where it comes from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contribution {
+    pub source: String,
+    pub value: f32,
+}
+
+/// A derived stat, its formula and the parts adding up to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatBreakdown {
+    pub name: &'static str,
+    pub formula: &'static str,
+    pub parts: Vec<Contribution>,
+    pub total: f32,
+    /// Shown as a percentage
+    pub percent: bool,
+}
+
+impl StatBreakdown {
+    fn new(name: &'static str, formula: &'static str, percent: bool) -> Self {
+        Self { name, formula, parts: Vec::new(), total: 0.0, percent }
+    }
+
+    /// Add a part, leaving out ones worth nothing
+    fn part(mut self, source: impl Into<String>, value: f32) -> Self {
+        if value != 0.0 {
+            self.total += value;
+            self.parts.push(Contribution { source: source.into(), value });
+        }
+        self
+    }
+}
+
+impl StatSources {
+    pub fn strength(&self) -> i32 {
+        self.base.strength + self.gear.strength
+    }
+
+    pub fn dexterity(&self) -> i32 {
+        self.base.dexterity + self.gear.dexterity
+    }
+
+    pub fn vitality(&self) -> i32 {
+        self.base.vitality + self.gear.vitality
+    }
+
+    /// Percent every blow's damage is raised or lowered by
+    pub fn damage_percent(&self) -> i32 {
+        let exhaustion = if self.exhausted { EXHAUSTION_DAMAGE_PENALTY } else { 0 };
+        self.set_damage_percent + self.buff_damage_percent - exhaustion
+    }
+
+    /// Physical damage of a main-hand blow before the target's armor
+    pub fn damage(&self) -> StatBreakdown {
+        let strength = self.strength();
+        let flat = base_physical_damage(strength) + self.weapon_damage + self.mutation_damage + self.set_damage;
+        let share = |percent: i32| flat as f32 * percent as f32 / 100.0;
+        let exhaustion = if self.exhausted { -share(EXHAUSTION_DAMAGE_PENALTY) } else { 0.0 };
+        StatBreakdown::new("Damage", "(2 + STR / 2 + weapon + mutations + sets) x (set, buff and exhaustion %)", false)
+            .part("Base", 2.0)
+            .part(format!("STR {} / 2", strength), (strength / 2) as f32)
+            .part("Weapon", self.weapon_damage as f32)
+            .part("Mutations", self.mutation_damage as f32)
+            .part("Set bonuses", self.set_damage as f32)
+            .part(format!("Set bonuses {:+}%", self.set_damage_percent), share(self.set_damage_percent))
+            .part(format!("Buffs {:+}%", self.buff_damage_percent), share(self.buff_damage_percent))
+            .part(format!("Exhausted -{}%", EXHAUSTION_DAMAGE_PENALTY), exhaustion)
+    }
+
+    /// Armor, before it's turned into damage reduction
+    pub fn armor(&self) -> StatBreakdown {
+        let vitality = self.vitality();
+        StatBreakdown::new("Armor", "VIT / 4 + gear armor + mutations + sets + buffs", false)
+            .part(format!("VIT {} / 4", vitality), armor_from_vit(vitality) as f32)
+            .part("Gear", self.gear_armor as f32)
+            .part("Mutations", self.mutation_armor as f32)
+            .part("Set bonuses", self.set_armor as f32)
+            .part("Buffs", self.buff_armor as f32)
+    }
+
+    /// Share of each blow armor soaks up
+    pub fn damage_reduction(&self) -> StatBreakdown {
+        let armor = self.armor().total as i32;
+        StatBreakdown::new("Damage reduction", "armor / (armor + 20)", true)
+            .part(format!("Armor {}", armor), damage_reduction_percent(armor) * 100.0)
+    }
+
+    pub fn crit(&self) -> StatBreakdown {
+        let dexterity = self.dexterity();
+        StatBreakdown::new("Crit chance", "5% + 1.5% per DEX over 10 (max 50%) + weapon + sets", true)
+            .part("Base", crit_chance(0))
+            .part(format!("DEX {}", dexterity), crit_chance(dexterity) - crit_chance(0))
+            .part("Weapon", self.weapon_crit)
+            .part("Set bonuses", self.set_crit)
+    }
+
+    pub fn dodge(&self) -> StatBreakdown {
+        let dexterity = self.dexterity();
+        let exhaustion = if self.exhausted { -EXHAUSTION_DODGE_PENALTY } else { 0.0 };
+        StatBreakdown::new("Dodge chance", "3% + 1% per DEX over 10 (max 40%) + armor weight", true)
+            .part("Base", dodge_chance(0))
+            .part(format!("DEX {}", dexterity), dodge_chance(dexterity) - dodge_chance(0))
+            .part("Armor weight", self.weight_dodge)
+            .part("Exhausted", exhaustion)
+    }
+
+    /// Every derived stat, in the order the character sheet lists them
+    pub fn breakdowns(&self) -> Vec<StatBreakdown> {
+        vec![self.damage(), self.armor(), self.damage_reduction(), self.crit(), self.dodge()]
+    }
+}
+
+/// An entity's stats with everything that modifies them applied
+#[derive(Debug, Clone, Copy)]
+pub struct DerivedStats {
+    pub sources: StatSources,
+    /// Max HP including gear and set bonuses
+    pub max_hp: i32,
+    /// Max MP including gear and set bonuses
+    pub max_mp: i32,
+    /// Share of damage dealt healed back (percent)
+    pub lifesteal: f32,
+    armor_penetration: i32,
+    /// Off-hand weapon's damage, crit and armor penetration when dual wielding
+    offhand: Option<(i32, f32, i32)>,
+}
+
+impl DerivedStats {
+    /// Work out an entity's derived stats, or None if it has no stats.
+    /// Gear, mutations and exhaustion count when present, so this works
+    /// for enemies as well as the player.
+    pub fn compute(world: &World, entity: Entity) -> Option<Self> {
+        let base = world.get::<&Stats>(entity).map(|s| *s).ok()?;
+        let equipment = world.get::<&EquipmentComponent>(entity).ok();
+        let equipment = equipment.as_ref().map(|eq| &eq.equipment);
+        let gear = |f: fn(&crate::items::Equipment) -> i32| equipment.map(f).unwrap_or(0);
+        let sets = equipment.map(|eq| eq.synergy_bonuses()).unwrap_or_default();
+        let (mutation_damage, mutation_armor) = world.get::<&Mutations>(entity)
+            .map(|m| (m.damage_bonus(), m.armor_modifier()))
+            .unwrap_or((0, 0));
+        let effect = |effect_type| world.get::<&StatusEffects>(entity)
+            .map(|effects| effects.effect_intensity(effect_type))
+            .unwrap_or(0);
+
+        let sources = StatSources {
+            base,
+            gear: Stats::new(
+                gear(|e| e.strength_bonus()),
+                gear(|e| e.dexterity_bonus()),
+                gear(|e| e.intelligence_bonus()),
+                gear(|e| e.vitality_bonus()),
+            ),
+            weapon_damage: gear(|e| e.weapon_damage()),
+            gear_armor: gear(|e| e.total_armor()),
+            weapon_crit: equipment.map(|eq| eq.weapon_crit_bonus()).unwrap_or(0.0),
+            weight_dodge: equipment.map(|eq| eq.weight_class()).unwrap_or(WeightClass::Light).dodge_modifier(),
+            mutation_damage,
+            mutation_armor,
+            set_damage: sets.bonus_damage,
+            set_damage_percent: (sets.damage_percent * 100.0).round() as i32,
+            set_armor: sets.bonus_armor,
+            set_crit: sets.crit_chance,
+            buff_damage_percent: effect(StatusEffectType::Strength) - effect(StatusEffectType::Weakness),
+            buff_armor: effect(StatusEffectType::Shield),
+            exhausted: world.get::<&Exhausted>(entity).is_ok(),
+        };
+
+        let max = |own: Option<i32>, gear_bonus: i32, set_bonus: i32| own.map(|m| m + gear_bonus + set_bonus).unwrap_or(0);
+        Some(Self {
+            sources,
+            max_hp: max(world.get::<&Health>(entity).map(|h| h.max).ok(), gear(|e| e.hp_bonus()), sets.bonus_hp),
+            max_mp: max(world.get::<&Mana>(entity).map(|m| m.max).ok(), gear(|e| e.mp_bonus()), sets.bonus_mp),
+            lifesteal: gear(|e| e.stat_bonus(AffixType::LifeSteal)) as f32 * LIFESTEAL_PER_POINT + sets.lifesteal * 100.0,
+            armor_penetration: equipment.and_then(|eq| eq.weapon_type()).map(|wt| wt.armor_penetration()).unwrap_or(0),
+            offhand: equipment.filter(|eq| eq.is_dual_wielding()).map(|eq| (
+                eq.offhand_damage(),
+                eq.offhand_crit_bonus(),
+                eq.offhand_weapon().and_then(|w| w.weapon_type).map(|wt| wt.armor_penetration()).unwrap_or(0),
+            )),
+        })
+    }
+
+    /// What the entity brings to a main-hand attack
+    pub fn attack_bonuses(&self) -> EquipmentBonuses {
+        let s = &self.sources;
+        EquipmentBonuses {
+            weapon_damage: s.weapon_damage + s.mutation_damage + s.set_damage,
+            str_bonus: s.gear.strength,
+            dex_bonus: s.gear.dexterity,
+            crit_bonus: s.weapon_crit + s.set_crit,
+            armor_penetration: self.armor_penetration,
+            damage_percent: s.damage_percent(),
+            ..Default::default()
+        }
+    }
+
+    /// What the entity brings to an off-hand follow-up, or None if it
+    /// isn't dual wielding
+    pub fn offhand_bonuses(&self) -> Option<EquipmentBonuses> {
+        let (damage, crit, armor_penetration) = self.offhand?;
+        let s = &self.sources;
+        Some(EquipmentBonuses {
+            weapon_damage: damage + s.mutation_damage + s.set_damage,
+            dex_bonus: s.gear.dexterity - DUAL_WIELD_DEX_PENALTY,
+            crit_bonus: crit + s.set_crit,
+            armor_penetration,
+            ..self.attack_bonuses()
+        })
+    }
+
+    /// What the entity brings to taking a blow
+    pub fn defense_bonuses(&self) -> EquipmentBonuses {
+        let s = &self.sources;
+        EquipmentBonuses {
+            // Natural armor from base VIT is worked out by the attack itself
+            armor: s.armor().total as i32 - armor_from_vit(s.base.vitality),
+            str_bonus: s.gear.strength,
+            dex_bonus: s.gear.dexterity,
+            dodge_bonus: s.dodge().total - dodge_chance(s.dexterity()),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stat_breakdowns() {
+        let sources = StatSources {
+            base: Stats::new(14, 20, 10, 12),
+            gear: Stats::new(2, 0, 0, 0),
+            weapon_damage: 6,
+            gear_armor: 10,
+            weapon_crit: 5.0,
+            weight_dodge: -5.0,
+            mutation_damage: 0,
+            mutation_armor: 0,
+            set_damage: 0,
+            set_damage_percent: 0,
+            set_armor: 0,
+            set_crit: 0.0,
+            buff_damage_percent: 0,
+            buff_armor: 0,
+            exhausted: false,
+        };
+
+        // The totals are what combat rolls against
+        assert_eq!(sources.damage().total, (base_physical_damage(16) + 6) as f32);
+        assert_eq!(sources.crit().total, crit_chance(20) + 5.0);
+        assert_eq!(sources.dodge().total, dodge_chance(20) - 5.0);
+        assert_eq!(sources.armor().total, 13.0);
+
+        // Nothing worth zero is listed
+        assert!(sources.damage().parts.iter().all(|p| p.source != "Mutations"));
+        let tired = StatSources { exhausted: true, ..sources };
+        assert_eq!(tired.damage().total, sources.damage().total * 0.75);
+
+        // Buffs and exhaustion reach combat the same way for anyone
+        let mut world = World::new();
+        let mut effects = StatusEffects::default();
+        effects.add_effect(StatusEffectType::Shield, 5.0, 8);
+        effects.add_effect(StatusEffectType::Weakness, 5.0, 20);
+        let rat = world.spawn((Stats::new(10, 10, 10, 10), Health::new(50), effects, Exhausted));
+        let derived = DerivedStats::compute(&world, rat).unwrap();
+        assert_eq!(derived.max_hp, 50);
+        assert_eq!(derived.defense_bonuses().armor, 8);
+        assert_eq!(derived.defense_bonuses().dodge_bonus, WeightClass::Light.dodge_modifier() - EXHAUSTION_DODGE_PENALTY);
+        assert_eq!(derived.attack_bonuses().damage_percent, -20 - EXHAUSTION_DAMAGE_PENALTY);
+        assert!(derived.offhand_bonuses().is_none());
+    }
+}
@@ -1,18 +1,40 @@
 //! Status effects system
 //!
-//! Handles DoT effects (poison, burn, bleed) and buff/debuff application.
+//! Handles DoT effects (poison, burn, bleed), regeneration and buff/debuff
+//! application. `run_status_effects` ticks every entity once a turn and
+//! reports what each effect did, for the message log and floating numbers.
 
+use hecs::{Entity, World};
 use crate::ecs::{StatusEffects, StatusEffect, StatusEffectType, Health};
+use super::resistances::Resistances;
+use super::stats::DerivedStats;
+
+/// What one effect did to one entity on a turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusEvent {
+    pub entity: Entity,
+    pub effect_type: StatusEffectType,
+    /// HP lost, or gained when negative
+    pub amount: i32,
+}
+
+impl StatusEvent {
+    /// Message log line, naming the entity as `name`
+    pub fn message(&self, name: &str) -> String {
+        match self.effect_type {
+            StatusEffectType::Poison if self.amount > 0 => format!("{} takes {} poison damage!", name, self.amount),
+            StatusEffectType::Burn if self.amount > 0 => format!("{} burns for {} damage!", name, self.amount),
+            StatusEffectType::Bleed if self.amount > 0 => format!("{} bleeds for {} damage!", name, self.amount),
+            StatusEffectType::Regeneration => format!("{} regenerates {} HP!", name, -self.amount),
+            other if self.amount < 0 => format!("{} feeds on the {}, regaining {} HP!", name, other.name().to_lowercase(), -self.amount),
+            other => format!("{} is affected by {}.", name, other.name()),
+        }
+    }
 
-/// Result of ticking status effects
-#[derive(Debug, Clone, Default)]
-pub struct StatusTickResult {
-    /// Total damage dealt from DoT effects
-    pub damage_dealt: i32,
-    /// Messages to display
-    pub messages: Vec<String>,
-    /// Whether any effects expired
-    pub effects_expired: bool,
+    /// Text floated over the entity
+    pub fn floating_text(&self) -> String {
+        if self.amount > 0 { format!("-{}", self.amount) } else { format!("+{}", -self.amount) }
+    }
 }
 
 impl StatusEffects {
@@ -51,48 +73,30 @@ impl StatusEffects {
             .unwrap_or(0)
     }
 
-    /// Tick all status effects (call on movement/turn)
-    /// Returns total DoT damage and messages
-    pub fn tick(&mut self, entity_name: &str) -> StatusTickResult {
-        let mut result = StatusTickResult::default();
+    /// Tick all status effects (once per turn), returning the HP each
+    /// damaging or healing effect would take (negative to heal)
+    pub fn tick(&mut self) -> Vec<(StatusEffectType, i32)> {
+        let mut amounts = Vec::new();
 
         for effect in &mut self.effects {
             // Reduce duration (1.0 per tick = 1 second worth)
             effect.duration -= 1.0;
 
-            // Apply DoT damage based on effect type
-            match effect.effect_type {
-                StatusEffectType::Poison => {
-                    result.damage_dealt += effect.intensity;
-                    result.messages.push(format!("{} takes {} poison damage!", entity_name, effect.intensity));
-                }
-                StatusEffectType::Burn => {
-                    let burn_dmg = effect.intensity + 1; // Burns hit a bit harder
-                    result.damage_dealt += burn_dmg;
-                    result.messages.push(format!("{} burns for {} damage!", entity_name, burn_dmg));
-                }
-                StatusEffectType::Bleed => {
-                    result.damage_dealt += effect.intensity;
-                    result.messages.push(format!("{} bleeds for {} damage!", entity_name, effect.intensity));
-                }
-                StatusEffectType::Regeneration => {
-                    // Negative damage = healing
-                    result.damage_dealt -= effect.intensity;
-                    result.messages.push(format!("{} regenerates {} HP!", entity_name, effect.intensity));
-                }
+            let amount = match effect.effect_type {
+                StatusEffectType::Poison | StatusEffectType::Bleed => effect.intensity,
+                StatusEffectType::Burn => effect.intensity + 1, // Burns hit a bit harder
+                StatusEffectType::Regeneration => -effect.intensity,
                 // Other effects don't do damage per tick
-                _ => {}
+                _ => 0,
+            };
+            if amount != 0 {
+                amounts.push((effect.effect_type, amount));
             }
         }
 
         // Remove expired effects
-        let before_count = self.effects.len();
         self.effects.retain(|e| e.duration > 0.0);
-        if self.effects.len() < before_count {
-            result.effects_expired = true;
-        }
-
-        result
+        amounts
     }
 
     /// Clear all effects
@@ -109,6 +113,7 @@ impl StatusEffectType {
             StatusEffectType::Burn => "Burn",
             StatusEffectType::Bleed => "Bleed",
             StatusEffectType::Slow => "Slow",
+            StatusEffectType::Stun => "Stun",
             StatusEffectType::Weakness => "Weakness",
             StatusEffectType::Curse => "Curse",
             StatusEffectType::Regeneration => "Regen",
@@ -125,6 +130,7 @@ impl StatusEffectType {
             StatusEffectType::Burn => (255, 100, 50),      // Orange-red
             StatusEffectType::Bleed => (200, 50, 50),      // Dark red
             StatusEffectType::Slow => (100, 100, 200),     // Blue
+            StatusEffectType::Stun => (230, 230, 120),     // Pale yellow
             StatusEffectType::Weakness => (150, 100, 150), // Purple-gray
             StatusEffectType::Curse => (150, 50, 150),     // Dark purple
             StatusEffectType::Regeneration => (100, 255, 100), // Bright green
@@ -154,16 +160,63 @@ impl StatusEffectType {
     }
 }
 
-/// Apply status effect tick to an entity's health
-/// Returns the actual damage/healing applied
-pub fn apply_status_damage(health: &mut Health, tick_result: &StatusTickResult) -> i32 {
-    if tick_result.damage_dealt > 0 {
-        // Damage
-        health.take_damage(tick_result.damage_dealt)
-    } else if tick_result.damage_dealt < 0 {
-        // Healing (negative damage)
-        health.heal(-tick_result.damage_dealt)
-    } else {
-        0
+/// Tick the status effects of everything with health, applying their
+/// damage and healing (up to the effective max HP) as their resistances
+/// allow. Entities are left in the world even if an effect kills them;
+/// that's for the caller.
+pub fn run_status_effects(world: &mut World) -> Vec<StatusEvent> {
+    let ticks: Vec<(Entity, Vec<(StatusEffectType, i32)>)> = world
+        .query_mut::<(&mut StatusEffects, &Health)>()
+        .into_iter()
+        .filter(|(_, (_, health))| !health.is_dead())
+        .map(|(entity, (effects, _))| (entity, effects.tick()))
+        .filter(|(_, amounts)| !amounts.is_empty())
+        .collect();
+
+    let mut events = Vec::new();
+    for (entity, amounts) in ticks {
+        let max_hp = DerivedStats::compute(world, entity).map(|d| d.max_hp);
+        let resistances = world.get::<&Resistances>(entity).map(|r| (*r).clone()).unwrap_or_default();
+        let Ok(mut health) = world.get::<&mut Health>(entity) else { continue };
+        let max_hp = max_hp.unwrap_or(health.max);
+        for (effect_type, amount) in amounts {
+            let amount = resistances.adjust(effect_type, amount);
+            let amount = if amount > 0 {
+                health.take_damage(amount)
+            } else {
+                let healed = (-amount).min(max_hp - health.current).max(0);
+                health.current += healed;
+                -healed
+            };
+            if amount != 0 {
+                events.push(StatusEvent { entity, effect_type, amount });
+            }
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_status_effects() {
+        let mut world = World::new();
+        let mut effects = StatusEffects::default();
+        effects.add_effect(StatusEffectType::Poison, 1.0, 3);
+        effects.add_effect(StatusEffectType::Regeneration, 3.0, 5);
+        let rat = world.spawn((Health { current: 10, max: 12 }, effects));
+
+        let events = run_status_effects(&mut world);
+        // Regeneration stops at full health
+        assert_eq!(events.iter().map(|e| e.amount).collect::<Vec<_>>(), vec![3, -5]);
+        assert_eq!(world.get::<&Health>(rat).unwrap().current, 12);
+        assert_eq!(events[0].floating_text(), "-3");
+
+        // The poison has run out, and there's nothing left to regenerate
+        let events = run_status_effects(&mut world);
+        assert!(events.is_empty());
+        assert_eq!(world.get::<&StatusEffects>(rat).unwrap().effects.len(), 1);
     }
 }
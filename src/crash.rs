@@ -0,0 +1,123 @@
+//! Crash reports
+//!
+//! A panic mid-frame would leave the terminal raw and on the alternate
+//! screen. The panic hook puts the terminal back first, then writes a report
+//! to the crashes directory: the panic and its backtrace, with the run's
+//! seed, floor and turn and the last messages the player saw. It leaves a
+//! note behind, so the next launch can own up to the crash and offer the
+//! autosave.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, TryLockError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Messages a crash report ends with
+pub const RECENT_EVENTS: usize = 50;
+
+static CONTEXT: Mutex<Option<CrashContext>> = Mutex::new(None);
+
+/// Where the run stood, noted every frame for a crash report
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrashContext {
+    pub seed: u64,
+    pub floor: u32,
+    pub turn: u32,
+    /// The last messages the player saw, oldest first
+    pub events: Vec<String>,
+}
+
+/// Note where the run stands, in case the next frame crashes
+pub fn set_context(context: CrashContext) {
+    *CONTEXT.lock().unwrap_or_else(|e| e.into_inner()) = Some(context);
+}
+
+/// Where crash reports are kept
+pub fn crash_directory() -> PathBuf {
+    use directories::ProjectDirs;
+
+    if let Some(proj_dirs) = ProjectDirs::from("com", "hollowdeep", "Hollowdeep") {
+        let mut path = proj_dirs.data_local_dir().to_path_buf();
+        path.push("crashes");
+        path
+    } else {
+        PathBuf::from("./crashes")
+    }
+}
+
+/// The note naming the report of a crash the next launch hasn't seen
+fn pending_path(dir: &Path) -> PathBuf {
+    dir.join("pending")
+}
+
+/// The text of a crash report
+pub fn format_report(panic: &str, backtrace: &str, context: Option<&CrashContext>) -> String {
+    let mut report = format!("Hollowdeep v{} crashed\n\n{}\n\n", env!("CARGO_PKG_VERSION"), panic);
+    match context {
+        Some(context) => {
+            let _ = writeln!(report, "Seed: {}", context.seed);
+            let _ = writeln!(report, "Floor: {}", context.floor);
+            let _ = writeln!(report, "Turn: {}", context.turn);
+            let _ = writeln!(report, "\nLast {} events:", context.events.len());
+            for event in &context.events {
+                let _ = writeln!(report, "  {}", event);
+            }
+        }
+        None => report.push_str("Crashed before a run was under way\n"),
+    }
+    let _ = write!(report, "\nBacktrace:\n{}\n", backtrace);
+    report
+}
+
+/// Write a report for a panic with whatever the run last noted, and leave
+/// word for the next launch. Returns where the report went.
+pub fn write_report(panic: &str, backtrace: &str) -> io::Result<PathBuf> {
+    // The panic may have struck with the context locked; don't wait on it
+    let context = match CONTEXT.try_lock() {
+        Ok(context) => context.clone(),
+        Err(TryLockError::Poisoned(e)) => e.into_inner().clone(),
+        Err(TryLockError::WouldBlock) => None,
+    };
+
+    let dir = crash_directory();
+    fs::create_dir_all(&dir)?;
+    let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let path = dir.join(format!("crash-{}.txt", stamp));
+    fs::write(&path, format_report(panic, backtrace, context.as_ref()))?;
+    fs::write(pending_path(&dir), path.to_string_lossy().as_bytes())?;
+    Ok(path)
+}
+
+/// The report of the crash the last launch ended in, if it did. The note
+/// is cleared, so each crash is only brought up once.
+pub fn take_pending() -> Option<PathBuf> {
+    let pending = pending_path(&crash_directory());
+    let report = fs::read_to_string(&pending).ok()?;
+    let _ = fs::remove_file(&pending);
+    Some(PathBuf::from(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_report_says_where_the_run_stood() {
+        let context = CrashContext {
+            seed: 1234,
+            floor: 7,
+            turn: 815,
+            events: vec!["The Bone Hound bites you for 6.".to_string(), "You descend to the catacombs...".to_string()],
+        };
+        let report = format_report("panicked at src/game/state.rs:42:5", "0: hollowdeep::main", Some(&context));
+        assert!(report.contains("panicked at src/game/state.rs:42:5"));
+        assert!(report.contains("Seed: 1234\nFloor: 7\nTurn: 815\n"));
+        assert!(report.contains("Last 2 events:\n  The Bone Hound bites you for 6.\n  You descend"));
+        assert!(report.ends_with("Backtrace:\n0: hollowdeep::main\n"));
+
+        let early = format_report("panicked at src/main.rs:1:1", "", None);
+        assert!(early.contains("before a run was under way"));
+    }
+}
@@ -0,0 +1,148 @@
+//! Balance curves for floor and difficulty scaling
+//!
+//! How fast enemies grow per floor, what each difficulty multiplies, and how
+//! much the game eases off after repeated deaths on the same floor. Loaded
+//! from RON so balance can be tuned without recompiling.
+
+use serde::{Deserialize, Serialize};
+use crate::progression::Difficulty;
+
+/// Multipliers and bonuses for one difficulty
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyCurve {
+    /// Enemy damage (via STR/INT) multiplier
+    pub enemy_damage: f32,
+    /// Enemy HP multiplier
+    pub enemy_health: f32,
+    /// XP reward multiplier
+    pub xp: f32,
+    /// Extra enemies that may spawn per floor
+    pub extra_enemies: usize,
+    /// Bonus to item drop quality
+    pub loot_bonus: f32,
+    /// Whether every floor has an elite
+    pub elite_every_floor: bool,
+}
+
+impl DifficultyCurve {
+    /// Built-in curve for a difficulty
+    pub fn default_for(difficulty: Difficulty) -> Self {
+        let (extra_enemies, loot_bonus) = match difficulty {
+            Difficulty::Easy => (0, -0.1),
+            Difficulty::Normal => (0, 0.0),
+            Difficulty::Hard => (1, 0.1),
+            Difficulty::Nightmare => (2, 0.2),
+        };
+        Self {
+            enemy_damage: difficulty.enemy_damage_mult(),
+            enemy_health: difficulty.enemy_health_mult(),
+            xp: difficulty.xp_mult(),
+            extra_enemies,
+            loot_bonus,
+            elite_every_floor: difficulty == Difficulty::Nightmare,
+        }
+    }
+}
+
+/// Easier spawns after dying on the same floor again and again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RubberBand {
+    /// Difficulties that ease off
+    pub difficulties: Vec<Difficulty>,
+    /// Enemy HP and power shaved off per death on the floor
+    pub per_death: f32,
+    /// Most that can be shaved off
+    pub max: f32,
+}
+
+impl Default for RubberBand {
+    fn default() -> Self {
+        Self {
+            difficulties: vec![Difficulty::Easy, Difficulty::Normal],
+            per_death: 0.05,
+            max: 0.2,
+        }
+    }
+}
+
+impl RubberBand {
+    /// Multiplier for enemy HP and power after some deaths on a floor
+    pub fn ease(&self, difficulty: Difficulty, deaths: u32) -> f32 {
+        if !self.difficulties.contains(&difficulty) {
+            return 1.0;
+        }
+        1.0 - (self.per_death * deaths as f32).clamp(0.0, self.max)
+    }
+}
+
+/// Floor and difficulty scaling configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceConfig {
+    /// Enemy strength and XP added per floor below the first
+    pub floor_growth: f32,
+    /// HP and power multiplier for elite zone enemies
+    pub elite_power: f32,
+    /// XP multiplier for elite zone enemies
+    pub elite_xp: f32,
+    pub easy: DifficultyCurve,
+    pub normal: DifficultyCurve,
+    pub hard: DifficultyCurve,
+    pub nightmare: DifficultyCurve,
+    #[serde(default)]
+    pub rubber_band: RubberBand,
+}
+
+impl BalanceConfig {
+    /// Curve for a difficulty
+    pub fn curve(&self, difficulty: Difficulty) -> &DifficultyCurve {
+        match difficulty {
+            Difficulty::Easy => &self.easy,
+            Difficulty::Normal => &self.normal,
+            Difficulty::Hard => &self.hard,
+            Difficulty::Nightmare => &self.nightmare,
+        }
+    }
+}
+
+impl Default for BalanceConfig {
+    fn default() -> Self {
+        Self {
+            floor_growth: 0.05,
+            elite_power: 1.5,
+            elite_xp: 2.0,
+            easy: DifficultyCurve::default_for(Difficulty::Easy),
+            normal: DifficultyCurve::default_for(Difficulty::Normal),
+            hard: DifficultyCurve::default_for(Difficulty::Hard),
+            nightmare: DifficultyCurve::default_for(Difficulty::Nightmare),
+            rubber_band: RubberBand::default(),
+        }
+    }
+}
+
+/// Create default balance configuration
+pub fn default_balance() -> BalanceConfig {
+    BalanceConfig::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progression::FloorScaling;
+
+    #[test]
+    fn test_rubber_band() {
+        let balance = BalanceConfig::default();
+        let band = &balance.rubber_band;
+        assert_eq!(band.ease(Difficulty::Normal, 0), 1.0);
+        assert!(band.ease(Difficulty::Normal, 2) < 1.0);
+        // Capped, and never on the hard settings
+        assert_eq!(band.ease(Difficulty::Easy, 100), 1.0 - band.max);
+        assert_eq!(band.ease(Difficulty::Nightmare, 5), 1.0);
+
+        // Easing softens enemies but not their XP
+        let scaling = FloorScaling::with_balance(3, Difficulty::Normal, &balance);
+        let eased = scaling.clone().eased(band.ease(Difficulty::Normal, 3));
+        assert!(eased.scale_enemy_hp(100) < scaling.scale_enemy_hp(100));
+        assert_eq!(eased.scale_xp(10), scaling.scale_xp(10));
+    }
+}
@@ -0,0 +1,160 @@
+//! Enemy behavior trees
+//!
+//! Each enemy archetype thinks with a small behavior tree declared in
+//! `assets/data/enemies/*.ron`. Selectors try children until one works,
+//! sequences run children until one fails, checks test the situation and
+//! actions decide what the enemy does this turn. The interpreter lives with
+//! the rest of the AI in `ecs::systems`.
+
+use serde::{Deserialize, Serialize};
+use crate::ecs::EnemyArchetype;
+
+/// Something an enemy can check about its situation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Condition {
+    /// The player is within detection range
+    PlayerDetected,
+    /// The player is within this many tiles
+    PlayerWithin(i32),
+    /// Own health is at or below this percent (higher for a shaken enemy)
+    HealthAtMost(i32),
+    /// Another enemy within `range` tiles is at or below `percent` health
+    AllyHurt { range: i32, percent: i32 },
+    /// Random roll succeeds (0-100)
+    Chance(u32),
+}
+
+/// Something an enemy can do with its turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Behavior {
+    /// Melee the player; fails when not adjacent
+    Attack,
+    /// Close in on the player, striking followers and bashing doors on the way
+    Chase,
+    /// Back away from the player; fails when cornered
+    Flee,
+    /// Stay put, watching the player
+    Hold,
+    /// Mend the most wounded ally within `range` tiles; fails if nobody is hurt
+    HealAlly { range: i32, amount: i32 },
+    /// Walk the enemy's patrol route; fails for enemies without one
+    Patrol,
+}
+
+/// A node in a behavior tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BehaviorNode {
+    /// Run children in order until one succeeds
+    Selector(Vec<BehaviorNode>),
+    /// Run children in order until one fails
+    Sequence(Vec<BehaviorNode>),
+    /// Succeed if the condition holds
+    Check(Condition),
+    /// Take an action
+    Do(Behavior),
+}
+
+/// A named tree and the archetypes that think with it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BehaviorTree {
+    pub name: String,
+    pub archetypes: Vec<EnemyArchetype>,
+    pub root: BehaviorNode,
+}
+
+/// Collection of behavior trees
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BehaviorTrees {
+    pub trees: Vec<BehaviorTree>,
+}
+
+impl BehaviorTrees {
+    /// Tree for an archetype. Archetypes without one stand idle.
+    pub fn for_archetype(&self, archetype: EnemyArchetype) -> Option<&BehaviorNode> {
+        self.trees.iter()
+            .find(|t| t.archetypes.contains(&archetype))
+            .map(|t| &t.root)
+    }
+}
+
+/// Create default behavior trees
+pub fn default_behavior_trees() -> BehaviorTrees {
+    use BehaviorNode::{Check, Do, Selector, Sequence};
+
+    // Every tree ends the same way: hit the player when adjacent, else close
+    // in, else walk the beat
+    let fight = || vec![
+        Sequence(vec![Check(Condition::PlayerWithin(1)), Do(Behavior::Attack)]),
+        Sequence(vec![Check(Condition::PlayerDetected), Do(Behavior::Chase)]),
+        Do(Behavior::Patrol),
+    ];
+    let flee_below = |percent| Sequence(vec![
+        Check(Condition::HealthAtMost(percent)),
+        Check(Condition::PlayerDetected),
+        Do(Behavior::Flee),
+    ]);
+    let tree = |name: &str, archetypes: Vec<EnemyArchetype>, root| BehaviorTree {
+        name: name.to_string(),
+        archetypes,
+        root,
+    };
+
+    BehaviorTrees {
+        trees: vec![
+            // Rank and file lose their nerve when badly wounded
+            tree("soldier", vec![EnemyArchetype::Melee], Selector(
+                std::iter::once(flee_below(20)).chain(fight()).collect(),
+            )),
+            // Fights to the death
+            tree("brute", vec![EnemyArchetype::Tank, EnemyArchetype::Elite, EnemyArchetype::Boss], Selector(fight())),
+            // Darts in and out of reach, waiting for the player to come to it
+            tree("skirmisher", vec![EnemyArchetype::Ranged], Selector(vec![
+                Sequence(vec![
+                    Check(Condition::PlayerWithin(1)),
+                    Check(Condition::Chance(40)),
+                    Do(Behavior::Flee),
+                ]),
+                Sequence(vec![Check(Condition::PlayerWithin(1)), Do(Behavior::Attack)]),
+                Sequence(vec![Check(Condition::PlayerWithin(2)), Do(Behavior::Hold)]),
+                Sequence(vec![Check(Condition::PlayerDetected), Do(Behavior::Chase)]),
+                Do(Behavior::Patrol),
+            ])),
+            // Bolts at the first real wound
+            tree("coward", vec![EnemyArchetype::Swarm], Selector(
+                std::iter::once(flee_below(50)).chain(fight()).collect(),
+            )),
+            // Keeps its allies standing, fighting only when it must
+            tree("healer", vec![EnemyArchetype::Caster], Selector(
+                [
+                    Sequence(vec![
+                        Check(Condition::AllyHurt { range: 4, percent: 60 }),
+                        Do(Behavior::HealAlly { range: 4, amount: 8 }),
+                    ]),
+                    flee_below(30),
+                ].into_iter().chain(fight()).collect(),
+            )),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_behavior_trees() {
+        let trees = default_behavior_trees();
+        for archetype in [
+            EnemyArchetype::Melee, EnemyArchetype::Ranged, EnemyArchetype::Caster, EnemyArchetype::Tank,
+            EnemyArchetype::Swarm, EnemyArchetype::Elite, EnemyArchetype::Boss,
+        ] {
+            assert!(trees.for_archetype(archetype).is_some(), "{:?} has no tree", archetype);
+        }
+
+        // Trees survive a trip through RON
+        for tree in &trees.trees {
+            let ron = ron::to_string(tree).unwrap();
+            assert_eq!(&ron::from_str::<BehaviorTree>(&ron).unwrap(), tree);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Scripted cutscenes
+//!
+//! Short sequences played over the map for story beats: the camera pans to
+//! somewhere on the floor, banners come and go, and characters speak until
+//! the player reads on. Each cutscene names the moment that plays it, so mods
+//! can add or rewrite scenes by editing the RON file alone.
+
+use serde::{Deserialize, Serialize};
+use crate::entities::BossType;
+use crate::world::Biome;
+
+/// A place on the floor the camera can pan to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CutsceneAnchor {
+    Player,
+    /// Where the player entered the floor
+    Start,
+    /// The stairs down
+    Exit,
+    /// The floor's boss, or the stairs it guards if it can't be found
+    Boss,
+}
+
+/// One beat of a cutscene
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CutsceneStep {
+    /// Move the camera to an anchor over some seconds
+    Pan { to: CutsceneAnchor, seconds: f32 },
+    /// Show a banner for some seconds
+    Banner { title: String, text: String, seconds: f32 },
+    /// Someone speaks; each line waits for the player
+    Dialogue { speaker: String, lines: Vec<String> },
+    /// Hold still for some seconds
+    Wait { seconds: f32 },
+}
+
+/// The moment a cutscene plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CutsceneTrigger {
+    /// Arriving on a boss's floor
+    BossIntro(BossType),
+    /// Descending into a new biome
+    EnterBiome(Biome),
+    /// Winning the run, before the victory screen
+    Ending,
+}
+
+/// A named cutscene
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CutsceneDef {
+    pub id: String,
+    /// What plays it; None for scenes only started by id
+    pub trigger: Option<CutsceneTrigger>,
+    pub steps: Vec<CutsceneStep>,
+}
+
+/// Every cutscene
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CutsceneDefs {
+    pub cutscenes: Vec<CutsceneDef>,
+}
+
+impl CutsceneDefs {
+    /// The cutscene a moment plays, if any
+    pub fn for_trigger(&self, trigger: CutsceneTrigger) -> Option<&CutsceneDef> {
+        self.cutscenes.iter().find(|c| c.trigger == Some(trigger))
+    }
+
+    /// A cutscene by id
+    pub fn get(&self, id: &str) -> Option<&CutsceneDef> {
+        self.cutscenes.iter().find(|c| c.id == id)
+    }
+}
+
+/// Create default cutscenes
+pub fn default_cutscene_defs() -> CutsceneDefs {
+    use CutsceneStep::*;
+
+    let banner = |title: &str, text: &str, seconds| Banner { title: title.to_string(), text: text.to_string(), seconds };
+    let say = |speaker: &str, lines: &[&str]| Dialogue {
+        speaker: speaker.to_string(),
+        lines: lines.iter().map(|l| l.to_string()).collect(),
+    };
+    let scene = |id: &str, trigger, steps| CutsceneDef { id: id.to_string(), trigger: Some(trigger), steps };
+    let boss_intro = |id: &str, boss, title: &str, speaker: &str, lines: &[&str]| scene(id, CutsceneTrigger::BossIntro(boss), vec![
+        Pan { to: CutsceneAnchor::Boss, seconds: 1.5 },
+        banner(title, "", 2.0),
+        say(speaker, lines),
+        Pan { to: CutsceneAnchor::Player, seconds: 1.0 },
+    ]);
+    let biome_intro = |id: &str, biome, title: &str, text: &str| scene(id, CutsceneTrigger::EnterBiome(biome), vec![
+        banner(title, text, 3.5),
+    ]);
+
+    CutsceneDefs {
+        cutscenes: vec![
+            boss_intro("crypt_lord_intro", BossType::CryptLord, "The Crypt Lord", "The Crypt Lord", &[
+                "Another thief come to rob the dead?",
+                "Kneel, and I will let you join them.",
+            ]),
+            boss_intro("blood_mother_intro", BossType::BloodMother, "The Blood Mother", "The Blood Mother", &[
+                "Such warm blood. My children are so very hungry.",
+            ]),
+            boss_intro("fallen_seraph_intro", BossType::FallenSeraph, "The Fallen Seraph", "Fallen Seraph", &[
+                "I guarded this place when it was holy.",
+                "I guard it still. Turn back.",
+            ]),
+            boss_intro("gardener_intro", BossType::Gardener, "The Gardener", "The Gardener", &[
+                "Hush. You will be beautiful here.",
+                "Lie still, and let me plant you.",
+            ]),
+            boss_intro("void_harbinger_intro", BossType::VoidHarbinger, "The Void Harbinger", "Void Harbinger", &[
+                "At last. The last mouth the deep needs to feed.",
+            ]),
+            biome_intro("bleeding_crypts", Biome::BleedingCrypts, "The Bleeding Crypts", "The walls here weep, and the floor is never quite dry."),
+            biome_intro("hollow_cathedral", Biome::HollowCathedral, "The Hollow Cathedral", "Vast, silent and long forsaken. Something still answers prayers here."),
+            biome_intro("flesh_gardens", Biome::FleshGardens, "The Flesh Gardens", "The stone gives way to something warm that breathes. Paths here do not stay open long."),
+            biome_intro("the_abyss", Biome::TheAbyss, "The Abyss", "There is no stone below you any more. Only the dark, and what lives in it."),
+            scene("ending", CutsceneTrigger::Ending, vec![
+                Wait { seconds: 1.0 },
+                banner("The Harbinger Falls", "The Hollowdeep shudders, and goes still.", 3.0),
+                Pan { to: CutsceneAnchor::Start, seconds: 2.0 },
+                say("You", &["It's over.", "Time to climb."]),
+            ]),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cutscene_triggers() {
+        let defs = default_cutscene_defs();
+        for boss in BossType::ALL {
+            assert!(defs.for_trigger(CutsceneTrigger::BossIntro(boss)).is_some(), "{:?} has no intro", boss);
+        }
+        assert!(defs.for_trigger(CutsceneTrigger::EnterBiome(Biome::SunkenCatacombs)).is_none());
+        assert_eq!(defs.get("ending").and_then(|c| c.trigger), Some(CutsceneTrigger::Ending));
+    }
+}
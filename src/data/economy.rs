@@ -0,0 +1,232 @@
+//! Economy
+//!
+//! What items are worth, what shops charge and pay for them, and how much
+//! gold the dungeon drops. Shop prices inflate with depth so a purse filled
+//! on the early floors doesn't buy out the late shops. Loaded from RON so the
+//! economy can be tuned without recompiling, and checked on load so a bad
+//! edit can't turn the shops into a gold mine.
+
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::items::{Item, Rarity};
+use crate::items::item::templates;
+
+/// A multiplier for each rarity
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RarityTable {
+    pub common: f32,
+    pub uncommon: f32,
+    pub rare: f32,
+    pub epic: f32,
+    pub legendary: f32,
+    pub mythic: f32,
+}
+
+impl RarityTable {
+    pub fn get(&self, rarity: Rarity) -> f32 {
+        match rarity {
+            Rarity::Common => self.common,
+            Rarity::Uncommon => self.uncommon,
+            Rarity::Rare => self.rare,
+            Rarity::Epic => self.epic,
+            Rarity::Legendary => self.legendary,
+            Rarity::Mythic => self.mythic,
+        }
+    }
+
+    /// Rarities in the table, commonest first
+    fn entries(&self) -> [(Rarity, f32); 6] {
+        [
+            (Rarity::Common, self.common),
+            (Rarity::Uncommon, self.uncommon),
+            (Rarity::Rare, self.rare),
+            (Rarity::Epic, self.epic),
+            (Rarity::Legendary, self.legendary),
+            (Rarity::Mythic, self.mythic),
+        ]
+    }
+}
+
+/// How much gold something drops, growing with depth
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GoldDrop {
+    pub base: u32,
+    /// Added for each floor down
+    pub per_floor: u32,
+    /// Up to this fraction of the drop may be added on top
+    pub variance: f32,
+}
+
+impl GoldDrop {
+    pub fn roll(&self, floor: u32, rng: &mut impl Rng) -> u32 {
+        let amount = self.base + self.per_floor * floor;
+        amount + rng.gen_range(0..=(amount as f32 * self.variance) as u32)
+    }
+}
+
+/// Item values, shop prices and gold drops
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconomyConfig {
+    /// What each kind of item is worth before loot rolls, by base name
+    pub base_values: BTreeMap<String, u32>,
+    /// What shops charge for each rarity, as a multiple of its worth
+    pub rarity_markup: RarityTable,
+    /// Applied to every shop price on top of the rarity markup
+    pub shop_markup: f32,
+    /// Cheapest anything sells for
+    pub min_buy_price: u32,
+    /// Fraction added to shop prices for each floor below the first
+    pub inflation_per_floor: f32,
+    /// Fraction of an item's worth a shop pays for it
+    pub sell_rate: f32,
+    /// Gold dropped by enemies, chests and corpses
+    pub gold_drop: GoldDrop,
+    /// Gold dropped by bosses
+    pub boss_gold_drop: GoldDrop,
+    /// Worth the templates were made with, to scale by
+    #[serde(skip, default = "template_values")]
+    template_values: BTreeMap<String, u32>,
+}
+
+impl EconomyConfig {
+    /// What an item is worth. Rarity and affixes are already in its value,
+    /// so a reworked base value scales the whole item.
+    pub fn value(&self, item: &Item) -> u32 {
+        let base = self.base_values.get(&item.base_name);
+        let made_with = self.template_values.get(&item.base_name);
+        match (base, made_with) {
+            (Some(&base), Some(&made_with)) if made_with > 0 && base != made_with => {
+                (item.value as u64 * base as u64 / made_with as u64) as u32
+            }
+            _ => item.value,
+        }
+    }
+
+    /// Shop price multiplier on a floor
+    pub fn inflation(&self, floor: u32) -> f32 {
+        1.0 + self.inflation_per_floor * floor.saturating_sub(1) as f32
+    }
+
+    /// What a shop on a floor charges for an item
+    pub fn buy_price(&self, item: &Item, floor: u32) -> u32 {
+        let markup = self.rarity_markup.get(item.rarity) * self.shop_markup * self.inflation(floor);
+        ((self.value(item) as f32 * markup) as u32).max(self.min_buy_price)
+    }
+
+    /// What a shop pays for an item
+    pub fn sell_price(&self, item: &Item) -> u32 {
+        ((self.value(item) as f32) * self.sell_rate).max(1.0) as u32
+    }
+
+    /// Everything wrong with the config, if anything
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.sell_rate <= 0.0 || self.sell_rate > 1.0 {
+            problems.push(format!("sell_rate {} must be above 0 and at most 1", self.sell_rate));
+        }
+        if self.shop_markup <= 0.0 {
+            problems.push(format!("shop_markup {} must be above 0", self.shop_markup));
+        }
+        if self.inflation_per_floor < 0.0 {
+            problems.push(format!("inflation_per_floor {} can't be negative", self.inflation_per_floor));
+        }
+        let mut previous = 0.0;
+        for (rarity, markup) in self.rarity_markup.entries() {
+            if markup * self.shop_markup <= self.sell_rate {
+                problems.push(format!("{} items would sell for more than they cost", rarity.name()));
+            }
+            if markup < previous {
+                problems.push(format!("{} items are marked up less than commoner ones", rarity.name()));
+            }
+            previous = markup;
+        }
+        for (name, drop) in [("gold_drop", &self.gold_drop), ("boss_gold_drop", &self.boss_gold_drop)] {
+            if drop.base == 0 || drop.variance < 0.0 {
+                problems.push(format!("{} needs a base above 0 and a variance of at least 0", name));
+            }
+        }
+        if self.boss_gold_drop.base < self.gold_drop.base {
+            problems.push("bosses drop less gold than everything else".to_string());
+        }
+        for (name, value) in &self.base_values {
+            if *value == 0 {
+                problems.push(format!("{} is worth nothing", name));
+            }
+            if !self.template_values.contains_key(name) {
+                problems.push(format!("no item is called {}", name));
+            }
+        }
+        problems
+    }
+}
+
+impl Default for EconomyConfig {
+    fn default() -> Self {
+        Self {
+            base_values: template_values(),
+            rarity_markup: RarityTable {
+                common: 1.0,
+                uncommon: 1.5,
+                rare: 2.5,
+                epic: 4.0,
+                legendary: 8.0,
+                mythic: 15.0,
+            },
+            shop_markup: 1.2,
+            min_buy_price: 5,
+            inflation_per_floor: 0.05,
+            sell_rate: 0.4,
+            gold_drop: GoldDrop { base: 5, per_floor: 3, variance: 0.5 },
+            boss_gold_drop: GoldDrop { base: 50, per_floor: 20, variance: 0.5 },
+            template_values: template_values(),
+        }
+    }
+}
+
+/// The worth each item template is made with
+fn template_values() -> BTreeMap<String, u32> {
+    templates::catalogue().into_iter().map(|item| (item.base_name, item.value)).collect()
+}
+
+/// Create the default economy
+pub fn default_economy() -> EconomyConfig {
+    EconomyConfig::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_prices_inflate_and_bad_configs_are_caught() {
+        let mut economy = EconomyConfig::default();
+        assert!(economy.validate().is_empty());
+
+        let sword = templates::iron_sword(1);
+        assert_eq!(economy.value(&sword), 50);
+        assert_eq!(economy.sell_price(&sword), 20);
+        assert_eq!(economy.buy_price(&sword, 1), 60);
+        assert!(economy.buy_price(&sword, 15) > economy.buy_price(&sword, 1));
+
+        // Reworking a base value carries through rarity and affixes
+        let mut rare_sword = templates::iron_sword(2);
+        rare_sword.value = 200;
+        economy.base_values.insert("Iron Sword".to_string(), 75);
+        assert_eq!(economy.value(&rare_sword), 300);
+
+        let drop = economy.gold_drop;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(9);
+        assert!((0..50).all(|_| (20..=30).contains(&drop.roll(5, &mut rng))));
+
+        economy.rarity_markup.epic = 0.3;
+        economy.base_values.insert("Sword of Nothing".to_string(), 0);
+        let problems = economy.validate();
+        assert!(problems.contains(&"Epic items would sell for more than they cost".to_string()));
+        assert!(problems.contains(&"Sword of Nothing is worth nothing".to_string()));
+        assert_eq!(problems.len(), 4);
+    }
+}
@@ -3,7 +3,8 @@
 //! These templates are loaded from RON files and used to spawn enemies.
 
 use serde::{Deserialize, Serialize};
-use crate::ecs::{EnemyArchetype, Stats};
+use crate::combat::Resistance;
+use crate::ecs::{EnemyArchetype, Stats, StatusEffectType};
 use crate::world::Biome;
 
 /// A template for creating enemies from external data
@@ -29,6 +30,9 @@ pub struct EnemyTemplate {
     pub biomes: Vec<Biome>,
     /// Optional description/lore
     pub description: Option<String>,
+    /// Status effects it resists, shrugs off or feeds on
+    #[serde(default)]
+    pub resistances: Vec<(StatusEffectType, Resistance)>,
 }
 
 /// Collection of enemy templates
@@ -74,6 +78,10 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 15,
                 biomes: vec![Biome::SunkenCatacombs, Biome::BleedingCrypts],
                 description: Some("Reanimated bones held together by dark magic.".to_string()),
+                resistances: vec![
+                    (StatusEffectType::Bleed, Resistance::Immune),
+                    (StatusEffectType::Poison, Resistance::Immune),
+                ],
             },
             EnemyTemplate {
                 id: "zombie".to_string(),
@@ -86,6 +94,7 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 20,
                 biomes: vec![Biome::SunkenCatacombs],
                 description: Some("A shambling corpse driven by hunger.".to_string()),
+                resistances: vec![(StatusEffectType::Poison, Resistance::Resists(50))],
             },
             EnemyTemplate {
                 id: "ghost".to_string(),
@@ -98,6 +107,10 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 25,
                 biomes: vec![Biome::SunkenCatacombs],
                 description: Some("A restless spirit bound to these halls.".to_string()),
+                resistances: vec![
+                    (StatusEffectType::Bleed, Resistance::Immune),
+                    (StatusEffectType::Poison, Resistance::Immune),
+                ],
             },
             EnemyTemplate {
                 id: "rat_swarm".to_string(),
@@ -110,6 +123,7 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 8,
                 biomes: vec![Biome::SunkenCatacombs],
                 description: Some("Dozens of rats moving as one hungry mass.".to_string()),
+                resistances: Vec::new(),
             },
 
             // === BLEEDING CRYPTS (Floors 6-10) ===
@@ -124,6 +138,7 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 35,
                 biomes: vec![Biome::BleedingCrypts, Biome::HollowCathedral],
                 description: Some("A devoted follower of the crimson faith.".to_string()),
+                resistances: Vec::new(),
             },
             EnemyTemplate {
                 id: "crimson_hound".to_string(),
@@ -136,6 +151,7 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 30,
                 biomes: vec![Biome::BleedingCrypts],
                 description: Some("A twisted beast bred in blood.".to_string()),
+                resistances: Vec::new(),
             },
             EnemyTemplate {
                 id: "flesh_golem".to_string(),
@@ -146,8 +162,22 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 stats: Stats { strength: 16, dexterity: 4, intelligence: 2, vitality: 18 },
                 hp: 80,
                 xp_value: 50,
-                biomes: vec![Biome::BleedingCrypts],
+                biomes: vec![Biome::BleedingCrypts, Biome::FleshGardens],
                 description: Some("A hulking monstrosity stitched from corpses.".to_string()),
+                resistances: vec![(StatusEffectType::Bleed, Resistance::Resists(50))],
+            },
+            EnemyTemplate {
+                id: "necromancer".to_string(),
+                name: "Necromancer".to_string(),
+                glyph: 'N',
+                fg: (120, 200, 140),
+                archetype: EnemyArchetype::Caster,
+                stats: Stats { strength: 5, dexterity: 7, intelligence: 14, vitality: 7 },
+                hp: 30,
+                xp_value: 35,
+                biomes: vec![Biome::BleedingCrypts, Biome::HollowCathedral],
+                description: Some("Hangs back and raises the fallen to fight again.".to_string()),
+                resistances: Vec::new(),
             },
 
             // === HOLLOW CATHEDRAL (Floors 11-15) ===
@@ -162,6 +192,7 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 60,
                 biomes: vec![Biome::HollowCathedral],
                 description: Some("Once a guardian, now corrupted by darkness.".to_string()),
+                resistances: Vec::new(),
             },
             EnemyTemplate {
                 id: "corrupted_angel".to_string(),
@@ -174,6 +205,7 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 70,
                 biomes: vec![Biome::HollowCathedral, Biome::TheAbyss],
                 description: Some("Divine grace twisted into unholy wrath.".to_string()),
+                resistances: Vec::new(),
             },
             EnemyTemplate {
                 id: "gargoyle".to_string(),
@@ -186,9 +218,83 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 45,
                 biomes: vec![Biome::HollowCathedral],
                 description: Some("Stone given malevolent life.".to_string()),
+                resistances: vec![
+                    (StatusEffectType::Bleed, Resistance::Immune),
+                    (StatusEffectType::Poison, Resistance::Immune),
+                ],
             },
 
-            // === THE ABYSS (Floors 16-20) ===
+            // === FLESH GARDENS (Floors 16-20) ===
+            EnemyTemplate {
+                id: "graftling".to_string(),
+                name: "Graftling".to_string(),
+                glyph: 'w',
+                fg: (200, 110, 120),
+                archetype: EnemyArchetype::Swarm,
+                stats: Stats { strength: 9, dexterity: 14, intelligence: 3, vitality: 6 },
+                hp: 24,
+                xp_value: 35,
+                biomes: vec![Biome::FleshGardens],
+                description: Some("A scrap of someone, grafted to a scrap of something else.".to_string()),
+                resistances: Vec::new(),
+            },
+            EnemyTemplate {
+                id: "thorned_husk".to_string(),
+                name: "Thorned Husk".to_string(),
+                glyph: 'H',
+                fg: (150, 90, 70),
+                archetype: EnemyArchetype::Melee,
+                stats: Stats { strength: 15, dexterity: 8, intelligence: 2, vitality: 12 },
+                hp: 55,
+                xp_value: 45,
+                biomes: vec![Biome::FleshGardens],
+                description: Some("An explorer the garden took root in. The thorns grow from the inside.".to_string()),
+                resistances: vec![(StatusEffectType::Bleed, Resistance::Resists(50))],
+            },
+            EnemyTemplate {
+                id: "spore_mother".to_string(),
+                name: "Spore Mother".to_string(),
+                glyph: 'm',
+                fg: (190, 170, 90),
+                archetype: EnemyArchetype::Caster,
+                stats: Stats { strength: 6, dexterity: 8, intelligence: 17, vitality: 10 },
+                hp: 40,
+                xp_value: 50,
+                biomes: vec![Biome::FleshGardens],
+                description: Some("A swollen bloom that breathes out sickness.".to_string()),
+                resistances: vec![(StatusEffectType::Poison, Resistance::Absorbs)],
+            },
+            EnemyTemplate {
+                id: "bloated_tender".to_string(),
+                name: "Bloated Tender".to_string(),
+                glyph: 'T',
+                fg: (210, 120, 140),
+                archetype: EnemyArchetype::Tank,
+                stats: Stats { strength: 17, dexterity: 5, intelligence: 4, vitality: 20 },
+                hp: 110,
+                xp_value: 90,
+                biomes: vec![Biome::FleshGardens],
+                description: Some("It waters the garden with itself, and there is a great deal of it.".to_string()),
+                resistances: vec![(StatusEffectType::Poison, Resistance::Immune)],
+            },
+            EnemyTemplate {
+                id: "heart_node".to_string(),
+                name: "Heart Node".to_string(),
+                glyph: '♥',
+                fg: (230, 60, 90),
+                archetype: EnemyArchetype::Tank,
+                stats: Stats { strength: 1, dexterity: 1, intelligence: 1, vitality: 14 },
+                hp: 60,
+                xp_value: 40,
+                biomes: vec![Biome::FleshGardens],
+                description: Some("A pulsing knot of muscle. Every beat mends the garden's children.".to_string()),
+                resistances: vec![
+                    (StatusEffectType::Stun, Resistance::Immune),
+                    (StatusEffectType::Slow, Resistance::Immune),
+                ],
+            },
+
+            // === THE ABYSS (Floors 21-25) ===
             EnemyTemplate {
                 id: "void_spawn".to_string(),
                 name: "Void Spawn".to_string(),
@@ -200,6 +306,7 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 40,
                 biomes: vec![Biome::TheAbyss],
                 description: Some("A fragment of the endless void.".to_string()),
+                resistances: Vec::new(),
             },
             EnemyTemplate {
                 id: "eldritch_horror".to_string(),
@@ -212,6 +319,7 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 100,
                 biomes: vec![Biome::TheAbyss],
                 description: Some("An abomination from beyond reality.".to_string()),
+                resistances: Vec::new(),
             },
             EnemyTemplate {
                 id: "tentacle".to_string(),
@@ -224,6 +332,24 @@ pub fn default_enemy_templates() -> EnemyTemplates {
                 xp_value: 35,
                 biomes: vec![Biome::TheAbyss],
                 description: Some("A grasping appendage of something vast.".to_string()),
+                resistances: Vec::new(),
+            },
+            EnemyTemplate {
+                id: "fire_elemental".to_string(),
+                name: "Fire Elemental".to_string(),
+                glyph: 'f',
+                fg: (255, 120, 40),
+                archetype: EnemyArchetype::Melee,
+                stats: Stats { strength: 12, dexterity: 10, intelligence: 10, vitality: 8 },
+                hp: 40,
+                xp_value: 45,
+                biomes: vec![Biome::TheAbyss],
+                description: Some("Living flame risen from the Abyss' lava. Fire only feeds it.".to_string()),
+                resistances: vec![
+                    (StatusEffectType::Burn, Resistance::Absorbs),
+                    (StatusEffectType::Bleed, Resistance::Immune),
+                    (StatusEffectType::Poison, Resistance::Immune),
+                ],
             },
         ],
     }
@@ -0,0 +1,181 @@
+//! Victory epilogues
+//!
+//! What the player finds when they climb out of the Hollowdeep depends on how
+//! they got there. One ending is chosen, the first in the list whose
+//! requirements the run meets, and then every coda that fits is added after
+//! it: the prisoners led to safety, the vows kept. Loaded from RON so endings
+//! can be written without touching code.
+
+use serde::{Deserialize, Serialize};
+use crate::game::{Deity, FAVOR_MAJOR_BOON};
+use crate::save::Conduct;
+
+/// Everything about a finished run the epilogues can turn on
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunOutcome {
+    /// How far corruption has taken the player, 0 to 100
+    pub corruption: u32,
+    /// God the player was dedicated to
+    pub patron: Option<Deity>,
+    /// Favor with that god
+    pub patron_favor: i32,
+    /// Conducts the run kept
+    pub conducts: Vec<Conduct>,
+    /// Survivors escorted to the stairs
+    pub escorts_rescued: u32,
+    /// Survivors left behind
+    pub escorts_lost: u32,
+}
+
+/// What a run needs for an ending or coda. Anything left unset always holds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EpilogueRequirements {
+    pub min_corruption: Option<u32>,
+    pub max_corruption: Option<u32>,
+    pub patron: Option<Deity>,
+    pub min_favor: Option<i32>,
+    pub conduct: Option<Conduct>,
+    pub min_rescued: Option<u32>,
+    pub min_lost: Option<u32>,
+}
+
+impl EpilogueRequirements {
+    pub fn met_by(&self, outcome: &RunOutcome) -> bool {
+        self.min_corruption.is_none_or(|min| outcome.corruption >= min)
+            && self.max_corruption.is_none_or(|max| outcome.corruption <= max)
+            && self.patron.is_none_or(|deity| outcome.patron == Some(deity))
+            && self.min_favor.is_none_or(|min| outcome.patron_favor >= min)
+            && self.conduct.is_none_or(|conduct| outcome.conducts.contains(&conduct))
+            && self.min_rescued.is_none_or(|min| outcome.escorts_rescued >= min)
+            && self.min_lost.is_none_or(|min| outcome.escorts_lost >= min)
+    }
+}
+
+/// An ending or a coda
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpilogueDef {
+    pub id: String,
+    /// Heading shown over the ending (unused for codas)
+    pub title: String,
+    pub requires: EpilogueRequirements,
+    /// Paragraphs, in order
+    pub text: Vec<String>,
+}
+
+/// Every ending and coda
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpilogueDefs {
+    /// Endings in priority order; the first that fits is told
+    pub endings: Vec<EpilogueDef>,
+    /// Closing paragraphs added after the ending whenever they fit
+    pub codas: Vec<EpilogueDef>,
+}
+
+/// A resolved epilogue, ready to show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Epilogue {
+    /// Id of the ending told
+    pub id: String,
+    pub title: String,
+    pub paragraphs: Vec<String>,
+}
+
+impl EpilogueDefs {
+    /// The ending a run earned, with its codas
+    pub fn resolve(&self, outcome: &RunOutcome) -> Option<Epilogue> {
+        let ending = self.endings.iter().find(|e| e.requires.met_by(outcome))?;
+        let paragraphs = ending.text.iter()
+            .chain(self.codas.iter().filter(|c| c.requires.met_by(outcome)).flat_map(|c| &c.text))
+            .cloned()
+            .collect();
+        Some(Epilogue { id: ending.id.clone(), title: ending.title.clone(), paragraphs })
+    }
+}
+
+/// Create default epilogues
+pub fn default_epilogue_defs() -> EpilogueDefs {
+    let def = |id: &str, title: &str, requires, text: &[&str]| EpilogueDef {
+        id: id.to_string(),
+        title: title.to_string(),
+        requires,
+        text: text.iter().map(|t| t.to_string()).collect(),
+    };
+    let devotee = |deity| EpilogueRequirements { patron: Some(deity), min_favor: Some(FAVOR_MAJOR_BOON), ..Default::default() };
+
+    EpilogueDefs {
+        endings: vec![
+            def("hollow_crown", "The Hollow Crown", EpilogueRequirements { min_corruption: Some(100), ..Default::default() }, &[
+                "The Harbinger falls, and the dark it held pours into the only vessel left standing.",
+                "You do not climb back to the surface. There is nothing up there you remember wanting.",
+                "The Hollowdeep has a new master, and it is hungrier than the last.",
+            ]),
+            def("red_maw", "Champion of the Red Maw", devotee(Deity::RedMaw), &[
+                "Vhorath's laughter shakes the stone as the last guardian dies.",
+                "You walk out of the Hollowdeep soaked to the elbows, and the Red Maw walks with you.",
+                "Wherever you go now, there will be war. You find you do not mind.",
+            ]),
+            def("ashen_widow", "The Widow's Steward", devotee(Deity::AshenWidow), &[
+                "Ysolde takes her tithe from the Harbinger's hoard before you have caught your breath.",
+                "What is left is still more gold than the village above has ever seen.",
+                "You build her a temple of ash-grey stone, and the offerings never stop.",
+            ]),
+            def("drowned_eye", "What the Eye Saw", devotee(Deity::DrownedEye), &[
+                "At the very bottom Oth's eye opens, and for a moment you see everything it has seen.",
+                "You return to the surface, but you never stop listening for the water.",
+                "Some nights you walk back to the entrance and stand there, waiting to be called.",
+            ]),
+            def("tainted", "The Changed Return", EpilogueRequirements { min_corruption: Some(50), ..Default::default() }, &[
+                "You climb into the daylight, and the daylight hurts.",
+                "The villagers bar their doors when they see what the deep made of you.",
+                "You have won, you tell yourself. It is harder to believe with every passing year.",
+            ]),
+            def("surface", "Daylight", EpilogueRequirements::default(), &[
+                "The Harbinger falls, and the Hollowdeep goes quiet for the first time in an age.",
+                "You climb for what feels like days before you see the sky again.",
+                "The village will tell this story long after you are gone.",
+            ]),
+        ],
+        codas: vec![
+            def("rescued", "", EpilogueRequirements { min_rescued: Some(1), ..Default::default() }, &[
+                "Those you led out of their cages are waiting at the entrance, and they do not let you forget it.",
+            ]),
+            def("abandoned", "", EpilogueRequirements { min_lost: Some(1), ..Default::default() }, &[
+                "You still think of the ones you left behind in the dark.",
+            ]),
+            def("shrineless", "", EpilogueRequirements { conduct: Some(Conduct::Shrineless), ..Default::default() }, &[
+                "You never knelt at a single shrine. Whatever power you carried out, it was your own.",
+            ]),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_epilogue() {
+        let defs = default_epilogue_defs();
+
+        // A plain run gets the plain ending and no codas
+        let plain = defs.resolve(&RunOutcome::default()).unwrap();
+        assert_eq!(plain.id, "surface");
+        assert_eq!(plain.paragraphs.len(), 3);
+
+        // Full corruption wins out over a devoted patron
+        let fallen = RunOutcome {
+            corruption: 100,
+            patron: Some(Deity::RedMaw),
+            patron_favor: 100,
+            escorts_rescued: 2,
+            ..Default::default()
+        };
+        let ending = defs.resolve(&fallen).unwrap();
+        assert_eq!(ending.id, "hollow_crown");
+        assert_eq!(ending.paragraphs.len(), 4);
+
+        let devoted = RunOutcome { corruption: 0, ..fallen };
+        assert_eq!(defs.resolve(&devoted).unwrap().id, "red_maw");
+    }
+}
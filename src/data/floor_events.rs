@@ -0,0 +1,138 @@
+//! Floor events
+//!
+//! Now and then a floor is not quite what it should be: the passages have
+//! caved in, a merchant caravan is under attack, a cluster of corrupted
+//! shrines has surfaced, or every light has gone out. One event at most is
+//! rolled on each descent, weighted by biome. Loaded from RON so weights and
+//! wording can be tuned without code.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::world::Biome;
+
+/// What happened to a floor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FloorEventKind {
+    /// Corridors have caved in, cutting some routes
+    CollapsedPassages,
+    /// A merchant caravan is being attacked somewhere on the floor
+    AmbushedCaravan,
+    /// Several corruption shrines stand together in one room
+    CorruptedShrines,
+    /// Sight shrinks to a few tiles
+    TotalDarkness,
+}
+
+/// An event and where it happens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FloorEventDef {
+    pub kind: FloorEventKind,
+    /// Shown in the floor-intro banner
+    pub name: String,
+    /// Shown under the name in the banner and in the message log
+    pub announcement: String,
+    /// Shallowest floor the event happens on
+    pub min_floor: u32,
+    /// Roll weight in each biome; biomes left out never see the event
+    pub weights: Vec<(Biome, u32)>,
+}
+
+impl FloorEventDef {
+    pub fn weight(&self, biome: Biome) -> u32 {
+        self.weights.iter().find(|(b, _)| *b == biome).map_or(0, |(_, w)| *w)
+    }
+}
+
+/// Every floor event, and how often nothing happens
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FloorEventDefs {
+    /// Roll weight of a floor without an event
+    pub quiet_weight: u32,
+    pub events: Vec<FloorEventDef>,
+}
+
+impl FloorEventDefs {
+    /// Roll the event for a floor, if any
+    pub fn roll(&self, biome: Biome, floor: u32, rng: &mut impl Rng) -> Option<&FloorEventDef> {
+        let candidates: Vec<(&FloorEventDef, u32)> = self.events.iter()
+            .filter(|e| floor >= e.min_floor)
+            .map(|e| (e, e.weight(biome)))
+            .filter(|(_, w)| *w > 0)
+            .collect();
+        let total: u32 = self.quiet_weight + candidates.iter().map(|(_, w)| w).sum::<u32>();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for (event, weight) in candidates {
+            if roll < weight {
+                return Some(event);
+            }
+            roll -= weight;
+        }
+        None
+    }
+
+    /// Definition of an event kind
+    pub fn get(&self, kind: FloorEventKind) -> Option<&FloorEventDef> {
+        self.events.iter().find(|e| e.kind == kind)
+    }
+}
+
+/// Create default floor events
+pub fn default_floor_event_defs() -> FloorEventDefs {
+    use Biome::*;
+
+    FloorEventDefs {
+        quiet_weight: 60,
+        events: vec![
+            FloorEventDef {
+                kind: FloorEventKind::CollapsedPassages,
+                name: "Collapsed Passages".to_string(),
+                announcement: "Dust still hangs in the air. Some of the old ways are buried.".to_string(),
+                min_floor: 2,
+                weights: vec![(SunkenCatacombs, 12), (BleedingCrypts, 8), (HollowCathedral, 6), (FleshGardens, 4), (TheAbyss, 4)],
+            },
+            FloorEventDef {
+                kind: FloorEventKind::AmbushedCaravan,
+                name: "Caravan Under Attack".to_string(),
+                announcement: "Shouts and the clash of steel echo from somewhere ahead.".to_string(),
+                min_floor: 2,
+                weights: vec![(SunkenCatacombs, 8), (BleedingCrypts, 10), (HollowCathedral, 8)],
+            },
+            FloorEventDef {
+                kind: FloorEventKind::CorruptedShrines,
+                name: "Corrupted Shrines".to_string(),
+                announcement: "A cluster of dark shrines has risen from the floor, humming with promise.".to_string(),
+                min_floor: 4,
+                weights: vec![(BleedingCrypts, 8), (HollowCathedral, 10), (FleshGardens, 10), (TheAbyss, 12)],
+            },
+            FloorEventDef {
+                kind: FloorEventKind::TotalDarkness,
+                name: "Total Darkness".to_string(),
+                announcement: "Your light gutters and shrinks. You can barely see your own hands.".to_string(),
+                min_floor: 3,
+                weights: vec![(SunkenCatacombs, 6), (BleedingCrypts, 6), (HollowCathedral, 8), (FleshGardens, 10), (TheAbyss, 12)],
+            },
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_roll_floor_event() {
+        let defs = default_floor_event_defs();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+
+        // Floor 1 is always quiet, and the Abyss has no caravans
+        assert!((0..50).all(|_| defs.roll(Biome::SunkenCatacombs, 1, &mut rng).is_none()));
+        let abyss: Vec<_> = (0..200).filter_map(|_| defs.roll(Biome::TheAbyss, 12, &mut rng)).collect();
+        assert!(!abyss.is_empty());
+        assert!(abyss.iter().all(|e| e.kind != FloorEventKind::AmbushedCaravan));
+    }
+}
@@ -0,0 +1,112 @@
+//! Gold sinks
+//!
+//! Gold piles up late in a run, so the dungeon keeps finding ways to take it
+//! back: offerings at the gods' altars, dice with the gambler, tolls on the
+//! quicker passages, and shrines that charge more every time they're used.
+//! Loaded from RON so the prices can be tuned without recompiling.
+
+use serde::{Deserialize, Serialize};
+
+/// A price that climbs with depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceCurve {
+    pub base: u32,
+    /// Added for each floor down
+    pub per_floor: u32,
+}
+
+impl PriceCurve {
+    pub fn at(&self, floor: u32) -> u32 {
+        self.base + self.per_floor * floor
+    }
+}
+
+/// The gambler's dice table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiceConfig {
+    /// Smallest stake the gambler takes
+    pub stake: PriceCurve,
+    /// Stakes on offer, as multiples of the smallest
+    pub multiples: Vec<u32>,
+    /// Paid back on a win, as a multiple of the stake
+    pub payout: u32,
+    /// Paid back on a win with double sixes
+    pub jackpot: u32,
+}
+
+/// What the dungeon charges for its services
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldSinkConfig {
+    /// Gold given to a god in one offering
+    pub offering: PriceCurve,
+    /// Gold fed to a gambling shrine in one offering
+    pub shrine_gamble: PriceCurve,
+    /// Fraction added to shrine prices for each paid shrine service this run
+    pub shrine_escalation: f32,
+    /// Most a shrine's price can be multiplied by
+    pub max_shrine_markup: f32,
+    pub dice: DiceConfig,
+    /// Fee to open a toll gate
+    pub toll: PriceCurve,
+}
+
+impl GoldSinkConfig {
+    /// Shrine price multiplier after some paid services this run
+    pub fn shrine_markup(&self, services: u32) -> ShrineMarkup {
+        ShrineMarkup((1.0 + self.shrine_escalation * services as f32).min(self.max_shrine_markup))
+    }
+
+    /// Stakes the gambler takes on a floor, smallest first
+    pub fn dice_stakes(&self, floor: u32) -> Vec<u32> {
+        let base = self.dice.stake.at(floor);
+        self.dice.multiples.iter().map(|m| base * m).collect()
+    }
+}
+
+impl Default for GoldSinkConfig {
+    fn default() -> Self {
+        Self {
+            offering: PriceCurve { base: 25, per_floor: 5 },
+            shrine_gamble: PriceCurve { base: 40, per_floor: 10 },
+            shrine_escalation: 0.1,
+            max_shrine_markup: 2.5,
+            dice: DiceConfig {
+                stake: PriceCurve { base: 20, per_floor: 10 },
+                multiples: vec![1, 2, 5],
+                payout: 2,
+                jackpot: 4,
+            },
+            toll: PriceCurve { base: 30, per_floor: 12 },
+        }
+    }
+}
+
+/// How much more shrines charge than their list price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShrineMarkup(pub f32);
+
+impl ShrineMarkup {
+    pub fn price(&self, base: u32) -> u32 {
+        (base as f32 * self.0).round() as u32
+    }
+}
+
+/// Create default gold sink prices
+pub fn default_gold_sinks() -> GoldSinkConfig {
+    GoldSinkConfig::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrine_prices_escalate_to_a_cap() {
+        let sinks = GoldSinkConfig::default();
+        assert_eq!(sinks.shrine_markup(0).price(200), 200);
+        assert_eq!(sinks.shrine_markup(3).price(200), 260);
+        assert_eq!(sinks.shrine_markup(1000).price(200), 500);
+        assert_eq!(sinks.dice_stakes(2), vec![40, 80, 200]);
+        assert!(sinks.toll.at(10) > sinks.toll.at(1));
+    }
+}
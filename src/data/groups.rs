@@ -0,0 +1,87 @@
+//! Enemy group templates
+//!
+//! Packs that spawn together around a leader: a Bone Shepherd with its hounds,
+//! a cult cell gathered around a zealot. While the leader lives the pack
+//! shares aggro and fights harder. Loaded from RON so new packs need no code.
+
+use serde::{Deserialize, Serialize};
+use crate::world::Biome;
+
+/// How a pack arranges itself around its leader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Formation {
+    /// Spread out in a ring two tiles from the leader
+    Ring,
+    /// Packed tight against the leader
+    Cluster,
+}
+
+/// A group that spawns together
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupDef {
+    /// Display name for logging
+    pub name: String,
+    /// Enemy name of the leader
+    pub leader: String,
+    /// Enemy names of the pack, one entry per member
+    pub members: Vec<String>,
+    /// Biomes where the group can appear
+    pub biomes: Vec<Biome>,
+    /// Shallowest floor the group appears on
+    pub min_floor: u32,
+    pub formation: Formation,
+    /// Strength the leader lends its pack's attacks
+    pub rally_strength: i32,
+}
+
+/// Collection of group templates
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupDefs {
+    pub groups: Vec<GroupDef>,
+}
+
+impl GroupDefs {
+    /// Groups that can appear on a floor
+    pub fn for_floor(&self, biome: Biome, floor: u32) -> Vec<&GroupDef> {
+        self.groups.iter()
+            .filter(|g| g.biomes.contains(&biome) && floor >= g.min_floor)
+            .collect()
+    }
+}
+
+/// Create default group templates
+pub fn default_group_defs() -> GroupDefs {
+    let names = |name: &str, count| vec![name.to_string(); count];
+
+    GroupDefs {
+        groups: vec![
+            GroupDef {
+                name: "Bone Shepherd's Pack".to_string(),
+                leader: "Bone Shepherd".to_string(),
+                members: names("Bone Hound", 3),
+                biomes: vec![Biome::SunkenCatacombs],
+                min_floor: 2,
+                formation: Formation::Ring,
+                rally_strength: 3,
+            },
+            GroupDef {
+                name: "Cult Cell".to_string(),
+                leader: "Cult Zealot".to_string(),
+                members: names("Cult Acolyte", 3),
+                biomes: vec![Biome::BleedingCrypts, Biome::HollowCathedral],
+                min_floor: 6,
+                formation: Formation::Cluster,
+                rally_strength: 4,
+            },
+            GroupDef {
+                name: "Tender's Brood".to_string(),
+                leader: "Bloated Tender".to_string(),
+                members: names("Graftling", 3),
+                biomes: vec![Biome::FleshGardens],
+                min_floor: 16,
+                formation: Formation::Ring,
+                rally_strength: 4,
+            },
+        ],
+    }
+}
@@ -5,10 +5,23 @@
 use std::path::Path;
 use std::fs;
 
+use serde::de::DeserializeOwned;
+
+use crate::error::{Area, Error};
 use crate::progression::{Skill, SkillRarity};
 use super::items::{ItemTemplates, default_item_templates};
 use super::enemies::{EnemyTemplates, default_enemy_templates};
 use super::synergies::{SynergyDefs, default_synergy_defs};
+use super::weapons::{WeaponProcs, default_weapon_procs};
+use super::balance::{BalanceConfig, default_balance};
+use super::behaviors::{BehaviorTree, BehaviorTrees, default_behavior_trees};
+use super::groups::{GroupDefs, default_group_defs};
+use super::epilogues::{EpilogueDefs, default_epilogue_defs};
+use super::floor_events::{FloorEventDefs, default_floor_event_defs};
+use super::cutscenes::{CutsceneDefs, default_cutscene_defs};
+use super::tutorial::{TutorialDefs, default_tutorial_defs};
+use super::gold_sinks::{GoldSinkConfig, default_gold_sinks};
+use super::economy::{EconomyConfig, default_economy};
 
 /// Manages all external game data
 #[derive(Debug, Clone)]
@@ -21,6 +34,26 @@ pub struct DataManager {
     pub synergies: SynergyDefs,
     /// Skill definitions
     pub skills: SkillCollection,
+    /// On-hit procs per weapon type
+    pub weapons: WeaponProcs,
+    /// Floor and difficulty scaling curves
+    pub balance: BalanceConfig,
+    /// Enemy behavior trees
+    pub behaviors: BehaviorTrees,
+    /// Packs that spawn around a leader
+    pub groups: GroupDefs,
+    /// Victory endings
+    pub epilogues: EpilogueDefs,
+    /// Events that can befall a floor
+    pub floor_events: FloorEventDefs,
+    /// Scripted story beats
+    pub cutscenes: CutsceneDefs,
+    /// Steps of the tutorial floor
+    pub tutorial: TutorialDefs,
+    /// Offerings, dice, tolls and shrine prices
+    pub gold_sinks: GoldSinkConfig,
+    /// Item values, shop prices and gold drops
+    pub economy: EconomyConfig,
 }
 
 /// Collection of skill definitions
@@ -52,96 +85,79 @@ impl SkillCollection {
 impl DataManager {
     /// Create a new DataManager, loading from files or using defaults
     pub fn new() -> Self {
-        Self::load_from_assets().unwrap_or_else(|e| {
-            eprintln!("Warning: Failed to load data files: {}. Using defaults.", e);
-            Self::default()
-        })
+        let (data, problems) = Self::load_from_assets();
+        for problem in &problems {
+            problem.log();
+        }
+        data
     }
 
-    /// Load data from assets/data/ directory
-    pub fn load_from_assets() -> Result<Self, String> {
-        let base_path = Path::new("assets/data");
-
-        // Try to load each file, fall back to defaults if missing
-        let items = Self::load_items(base_path);
-        let enemies = Self::load_enemies(base_path);
-        let synergies = Self::load_synergies(base_path);
-        let skills = Self::load_skills(base_path);
-
-        Ok(Self {
-            items,
-            enemies,
-            synergies,
-            skills,
-        })
+    /// Load data from the assets/data/ directory. Missing files fall back to
+    /// the defaults; files that are there but won't load fall back too, and
+    /// come back as errors to tell the player about.
+    pub fn load_from_assets() -> (Self, Vec<Error>) {
+        Self::load_from(Path::new("assets/data"))
     }
 
-    /// Load item templates from RON file
-    fn load_items(base_path: &Path) -> ItemTemplates {
-        let path = base_path.join("items.ron");
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => {
-                    match ron::from_str(&content) {
-                        Ok(templates) => return templates,
-                        Err(e) => eprintln!("Warning: Failed to parse items.ron: {}", e),
-                    }
-                }
-                Err(e) => eprintln!("Warning: Failed to read items.ron: {}", e),
-            }
-        }
-        default_item_templates()
+    /// Load data from the data files in `base_path`, as [`Self::load_from_assets`]
+    pub fn load_from(base_path: &Path) -> (Self, Vec<Error>) {
+        let mut problems = Vec::new();
+        let file = |name: &str| base_path.join(name);
+
+        let data = Self {
+            items: load_file(&file("items.ron"), &mut problems).unwrap_or_else(default_item_templates),
+            enemies: load_file(&file("enemies.ron"), &mut problems).unwrap_or_else(default_enemy_templates),
+            synergies: load_file(&file("synergies.ron"), &mut problems).unwrap_or_else(default_synergy_defs),
+            skills: load_file(&file("skills.ron"), &mut problems)
+                .map(|skills| SkillCollection { skills })
+                .unwrap_or_else(default_skills),
+            weapons: load_file(&file("weapons.ron"), &mut problems).unwrap_or_else(default_weapon_procs),
+            balance: load_file(&file("balance.ron"), &mut problems).unwrap_or_else(default_balance),
+            behaviors: Self::load_behaviors(base_path, &mut problems),
+            groups: load_file(&file("groups.ron"), &mut problems).unwrap_or_else(default_group_defs),
+            epilogues: load_file(&file("epilogues.ron"), &mut problems).unwrap_or_else(default_epilogue_defs),
+            floor_events: load_file(&file("floor_events.ron"), &mut problems).unwrap_or_else(default_floor_event_defs),
+            cutscenes: load_file(&file("cutscenes.ron"), &mut problems).unwrap_or_else(default_cutscene_defs),
+            tutorial: load_file(&file("tutorial.ron"), &mut problems).unwrap_or_else(default_tutorial_defs),
+            gold_sinks: load_file(&file("gold_sinks.ron"), &mut problems).unwrap_or_else(default_gold_sinks),
+            economy: Self::load_economy(base_path, &mut problems),
+        };
+        (data, problems)
     }
 
-    /// Load enemy templates from RON file
-    fn load_enemies(base_path: &Path) -> EnemyTemplates {
-        let path = base_path.join("enemies.ron");
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => {
-                    match ron::from_str(&content) {
-                        Ok(templates) => return templates,
-                        Err(e) => eprintln!("Warning: Failed to parse enemies.ron: {}", e),
-                    }
-                }
-                Err(e) => eprintln!("Warning: Failed to read enemies.ron: {}", e),
-            }
+    /// Load the economy, falling back to the defaults if it doesn't pass
+    /// validation
+    fn load_economy(base_path: &Path, problems: &mut Vec<Error>) -> EconomyConfig {
+        let path = base_path.join("economy.ron");
+        let Some(economy) = load_file::<EconomyConfig>(&path, problems) else {
+            return default_economy();
+        };
+        let invalid = economy.validate();
+        if invalid.is_empty() {
+            return economy;
         }
-        default_enemy_templates()
+        problems.push(Error::parse(Area::Data, &path, invalid.join("; ")));
+        default_economy()
     }
 
-    /// Load synergy definitions from RON file
-    fn load_synergies(base_path: &Path) -> SynergyDefs {
-        let path = base_path.join("synergies.ron");
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => {
-                    match ron::from_str(&content) {
-                        Ok(defs) => return defs,
-                        Err(e) => eprintln!("Warning: Failed to parse synergies.ron: {}", e),
-                    }
-                }
-                Err(e) => eprintln!("Warning: Failed to read synergies.ron: {}", e),
-            }
+    /// Load behavior trees, one per RON file in the enemies/ directory
+    fn load_behaviors(base_path: &Path, problems: &mut Vec<Error>) -> BehaviorTrees {
+        let dir = base_path.join("enemies");
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return default_behavior_trees();
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "ron"))
+            .collect();
+        paths.sort();
+
+        let trees: Vec<BehaviorTree> = paths.iter().filter_map(|path| load_file(path, problems)).collect();
+        if trees.is_empty() {
+            return default_behavior_trees();
         }
-        default_synergy_defs()
-    }
-
-    /// Load skills from RON file (skills are already serializable)
-    fn load_skills(base_path: &Path) -> SkillCollection {
-        let path = base_path.join("skills.ron");
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => {
-                    match ron::from_str::<Vec<Skill>>(&content) {
-                        Ok(skills) => return SkillCollection { skills },
-                        Err(e) => eprintln!("Warning: Failed to parse skills.ron: {}", e),
-                    }
-                }
-                Err(e) => eprintln!("Warning: Failed to read skills.ron: {}", e),
-            }
-        }
-        default_skills()
+        BehaviorTrees { trees }
     }
 
     /// Get item templates
@@ -163,6 +179,68 @@ impl DataManager {
     pub fn skill_collection(&self) -> &SkillCollection {
         &self.skills
     }
+
+    /// Get weapon proc tables
+    pub fn weapon_procs(&self) -> &WeaponProcs {
+        &self.weapons
+    }
+
+    /// Get balance curves
+    pub fn balance(&self) -> &BalanceConfig {
+        &self.balance
+    }
+
+    /// Get enemy behavior trees
+    pub fn behavior_trees(&self) -> &BehaviorTrees {
+        &self.behaviors
+    }
+
+    /// Get enemy group templates
+    pub fn group_defs(&self) -> &GroupDefs {
+        &self.groups
+    }
+
+    /// Get victory epilogues
+    pub fn epilogue_defs(&self) -> &EpilogueDefs {
+        &self.epilogues
+    }
+
+    /// Get floor events
+    pub fn floor_event_defs(&self) -> &FloorEventDefs {
+        &self.floor_events
+    }
+
+    /// Get cutscenes
+    pub fn cutscene_defs(&self) -> &CutsceneDefs {
+        &self.cutscenes
+    }
+
+    /// Get the tutorial
+    pub fn tutorial_defs(&self) -> &TutorialDefs {
+        &self.tutorial
+    }
+
+    /// Get gold sink prices
+    pub fn gold_sinks(&self) -> &GoldSinkConfig {
+        &self.gold_sinks
+    }
+
+    /// Get the economy
+    pub fn economy(&self) -> &EconomyConfig {
+        &self.economy
+    }
+}
+
+/// Read a RON data file, if it's there. One that's there but can't be read
+/// or parsed is noted in `problems`.
+fn load_file<T: DeserializeOwned>(path: &Path, problems: &mut Vec<Error>) -> Option<T> {
+    if !path.exists() {
+        return None;
+    }
+    let loaded = fs::read_to_string(path)
+        .map_err(|e| Error::io(Area::Data, "read", path, e))
+        .and_then(|content| ron::from_str(&content).map_err(|e| Error::parse(Area::Data, path, e)));
+    loaded.map_err(|e| problems.push(e)).ok()
 }
 
 impl Default for DataManager {
@@ -172,6 +250,16 @@ impl Default for DataManager {
             enemies: default_enemy_templates(),
             synergies: default_synergy_defs(),
             skills: default_skills(),
+            weapons: default_weapon_procs(),
+            balance: default_balance(),
+            behaviors: default_behavior_trees(),
+            groups: default_group_defs(),
+            epilogues: default_epilogue_defs(),
+            floor_events: default_floor_event_defs(),
+            cutscenes: default_cutscene_defs(),
+            tutorial: default_tutorial_defs(),
+            gold_sinks: default_gold_sinks(),
+            economy: default_economy(),
         }
     }
 }
@@ -197,6 +285,9 @@ pub fn default_skills() -> SkillCollection {
             skill_burning_strike(),
             skill_battle_cry(),
             skill_recuperate(),
+            skill_shield_bash(),
+            skill_grappling_hook(),
+            skill_devour_the_dead(),
 
             // Rare
             skill_whirlwind(),
@@ -204,6 +295,7 @@ pub fn default_skills() -> SkillCollection {
             skill_frost_nova(),
             skill_life_drain(),
             skill_executioner(),
+            skill_corpse_explosion(),
 
             // Epic
             skill_berserker_rage(),
@@ -221,12 +313,16 @@ pub fn default_skills() -> SkillCollection {
 
 /// Export all default data to RON files for easy editing
 pub fn export_default_data() -> Result<(), String> {
-    let base_path = Path::new("assets/data");
+    export_default_data_to(Path::new("assets/data"))
+}
 
+/// Export all default data to RON files in `base_path`. Overwrites whatever
+/// data files are there, so the shipped ones are only ever written on purpose.
+pub fn export_default_data_to(base_path: &Path) -> Result<(), String> {
     // Create directory if it doesn't exist
     if !base_path.exists() {
         fs::create_dir_all(base_path)
-            .map_err(|e| format!("Failed to create assets/data directory: {}", e))?;
+            .map_err(|e| format!("Failed to create {} directory: {}", base_path.display(), e))?;
     }
 
     // Export items
@@ -257,39 +353,146 @@ pub fn export_default_data() -> Result<(), String> {
     fs::write(base_path.join("skills.ron"), skills_ron)
         .map_err(|e| format!("Failed to write skills.ron: {}", e))?;
 
+    // Export weapon procs
+    let weapons = default_weapon_procs();
+    let weapons_ron = ron::ser::to_string_pretty(&weapons, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize weapon procs: {}", e))?;
+    fs::write(base_path.join("weapons.ron"), weapons_ron)
+        .map_err(|e| format!("Failed to write weapons.ron: {}", e))?;
+
+    // Export balance curves
+    let balance = default_balance();
+    let balance_ron = ron::ser::to_string_pretty(&balance, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize balance: {}", e))?;
+    fs::write(base_path.join("balance.ron"), balance_ron)
+        .map_err(|e| format!("Failed to write balance.ron: {}", e))?;
+
+    // Export enemy groups
+    let groups = default_group_defs();
+    let groups_ron = ron::ser::to_string_pretty(&groups, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize groups: {}", e))?;
+    fs::write(base_path.join("groups.ron"), groups_ron)
+        .map_err(|e| format!("Failed to write groups.ron: {}", e))?;
+
+    // Export victory epilogues
+    let epilogues = default_epilogue_defs();
+    let epilogues_ron = ron::ser::to_string_pretty(&epilogues, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize epilogues: {}", e))?;
+    fs::write(base_path.join("epilogues.ron"), epilogues_ron)
+        .map_err(|e| format!("Failed to write epilogues.ron: {}", e))?;
+
+    // Export floor events
+    let floor_events = default_floor_event_defs();
+    let floor_events_ron = ron::ser::to_string_pretty(&floor_events, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize floor events: {}", e))?;
+    fs::write(base_path.join("floor_events.ron"), floor_events_ron)
+        .map_err(|e| format!("Failed to write floor_events.ron: {}", e))?;
+
+    // Export cutscenes
+    let cutscenes = default_cutscene_defs();
+    let cutscenes_ron = ron::ser::to_string_pretty(&cutscenes, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize cutscenes: {}", e))?;
+    fs::write(base_path.join("cutscenes.ron"), cutscenes_ron)
+        .map_err(|e| format!("Failed to write cutscenes.ron: {}", e))?;
+
+    // Export the tutorial
+    let tutorial = default_tutorial_defs();
+    let tutorial_ron = ron::ser::to_string_pretty(&tutorial, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize tutorial: {}", e))?;
+    fs::write(base_path.join("tutorial.ron"), tutorial_ron)
+        .map_err(|e| format!("Failed to write tutorial.ron: {}", e))?;
+
+    // Export gold sink prices
+    let gold_sinks = default_gold_sinks();
+    let gold_sinks_ron = ron::ser::to_string_pretty(&gold_sinks, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize gold sinks: {}", e))?;
+    fs::write(base_path.join("gold_sinks.ron"), gold_sinks_ron)
+        .map_err(|e| format!("Failed to write gold_sinks.ron: {}", e))?;
+
+    // Export the economy
+    let economy = default_economy();
+    let economy_ron = ron::ser::to_string_pretty(&economy, ron::ser::PrettyConfig::default())
+        .map_err(|e| format!("Failed to serialize economy: {}", e))?;
+    fs::write(base_path.join("economy.ron"), economy_ron)
+        .map_err(|e| format!("Failed to write economy.ron: {}", e))?;
+
+    // Export behavior trees, one file each
+    let behaviors_path = base_path.join("enemies");
+    fs::create_dir_all(&behaviors_path)
+        .map_err(|e| format!("Failed to create enemies directory: {}", e))?;
+    for tree in default_behavior_trees().trees {
+        let tree_ron = ron::ser::to_string_pretty(&tree, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("Failed to serialize {} behavior: {}", tree.name, e))?;
+        fs::write(behaviors_path.join(format!("{}.ron", tree.name)), tree_ron)
+            .map_err(|e| format!("Failed to write {}.ron: {}", tree.name, e))?;
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
+
+    /// A scratch data directory, so tests never overwrite the shipped files
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hollowdeep-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
 
     #[test]
     fn test_export_default_data() {
         // Export default data to RON files
-        let result = export_default_data();
+        let dir = scratch_dir("export");
+        let result = export_default_data_to(&dir);
         assert!(result.is_ok(), "Failed to export default data: {:?}", result.err());
 
         // Verify files were created
-        let base_path = Path::new("assets/data");
+        let base_path = dir.as_path();
         assert!(base_path.join("items.ron").exists(), "items.ron not created");
         assert!(base_path.join("enemies.ron").exists(), "enemies.ron not created");
         assert!(base_path.join("synergies.ron").exists(), "synergies.ron not created");
         assert!(base_path.join("skills.ron").exists(), "skills.ron not created");
+        assert!(base_path.join("weapons.ron").exists(), "weapons.ron not created");
+        assert!(base_path.join("balance.ron").exists(), "balance.ron not created");
+        assert!(base_path.join("enemies/soldier.ron").exists(), "enemies/soldier.ron not created");
+        assert!(base_path.join("groups.ron").exists(), "groups.ron not created");
+        assert!(base_path.join("epilogues.ron").exists(), "epilogues.ron not created");
+        assert!(base_path.join("floor_events.ron").exists(), "floor_events.ron not created");
+        assert!(base_path.join("cutscenes.ron").exists(), "cutscenes.ron not created");
+        assert!(base_path.join("tutorial.ron").exists(), "tutorial.ron not created");
+        assert!(base_path.join("gold_sinks.ron").exists(), "gold_sinks.ron not created");
+        assert!(base_path.join("economy.ron").exists(), "economy.ron not created");
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
     fn test_load_default_data() {
         // First export the data
-        let _ = export_default_data();
+        let dir = scratch_dir("round-trip");
+        export_default_data_to(&dir).unwrap();
 
         // Then load it back
-        let manager = DataManager::new();
+        let (manager, problems) = DataManager::load_from(&dir);
+        let _ = fs::remove_dir_all(&dir);
+        assert!(problems.is_empty(), "Exported data didn't load: {:?}", problems);
 
         // Verify data was loaded
         assert!(!manager.items.templates.is_empty(), "No item templates loaded");
         assert!(!manager.enemies.templates.is_empty(), "No enemy templates loaded");
         assert!(!manager.synergies.synergies.is_empty(), "No synergy definitions loaded");
         assert!(!manager.skills.skills.is_empty(), "No skills loaded");
+        assert!(!manager.weapons.procs.is_empty(), "No weapon procs loaded");
+        assert_eq!(manager.balance, BalanceConfig::default(), "Balance curves didn't round-trip");
+        assert_eq!(manager.behaviors.trees.len(), default_behavior_trees().trees.len(), "Behavior trees didn't load");
+        assert!(!manager.groups.groups.is_empty(), "No enemy groups loaded");
+        assert_eq!(manager.epilogues, default_epilogue_defs(), "Epilogues didn't round-trip");
+        assert_eq!(manager.floor_events, default_floor_event_defs(), "Floor events didn't round-trip");
+        assert_eq!(manager.cutscenes, default_cutscene_defs(), "Cutscenes didn't round-trip");
+        assert_eq!(manager.tutorial, default_tutorial_defs(), "Tutorial didn't round-trip");
+        assert_eq!(manager.gold_sinks, default_gold_sinks(), "Gold sinks didn't round-trip");
+        assert_eq!(manager.economy, default_economy(), "Economy didn't round-trip");
     }
 }
@@ -7,8 +7,28 @@ pub mod loader;
 pub mod items;
 pub mod enemies;
 pub mod synergies;
+pub mod weapons;
+pub mod balance;
+pub mod behaviors;
+pub mod groups;
+pub mod epilogues;
+pub mod floor_events;
+pub mod cutscenes;
+pub mod tutorial;
+pub mod gold_sinks;
+pub mod economy;
 
 pub use loader::DataManager;
 pub use items::ItemTemplate;
-pub use enemies::EnemyTemplate;
+pub use enemies::{EnemyTemplate, EnemyTemplates};
 pub use synergies::SynergyDef;
+pub use weapons::{WeaponProcs, WeaponProcDef, ProcEffect};
+pub use balance::{BalanceConfig, DifficultyCurve, RubberBand};
+pub use behaviors::{BehaviorTree, BehaviorTrees, BehaviorNode, Behavior, Condition};
+pub use groups::{GroupDef, GroupDefs, Formation};
+pub use epilogues::{EpilogueDef, EpilogueDefs, EpilogueRequirements, Epilogue, RunOutcome};
+pub use floor_events::{FloorEventDef, FloorEventDefs, FloorEventKind};
+pub use cutscenes::{CutsceneDef, CutsceneDefs, CutsceneStep, CutsceneAnchor, CutsceneTrigger};
+pub use tutorial::{TutorialDefs, TutorialStep, TutorialTrigger};
+pub use gold_sinks::{GoldSinkConfig, DiceConfig, PriceCurve, ShrineMarkup};
+pub use economy::{EconomyConfig, GoldDrop, RarityTable};
@@ -0,0 +1,99 @@
+//! Tutorial script
+//!
+//! New profiles start on a guided floor 0. Each step of the tutorial shows
+//! a prompt and waits for the player to do what it asks before moving on to
+//! the next. Steps are loaded from RON, so the wording and order can change
+//! without touching code.
+
+use serde::{Deserialize, Serialize};
+
+/// What the player does to finish a tutorial step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TutorialTrigger {
+    Moved,
+    EnemyKilled,
+    SkillUsed,
+    PickedUp,
+    ShrineUsed,
+    Descended,
+}
+
+/// One prompt of the tutorial
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TutorialStep {
+    pub title: String,
+    pub text: String,
+    /// What moves the tutorial on to the next step
+    pub trigger: TutorialTrigger,
+}
+
+/// The tutorial, in order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TutorialDefs {
+    pub steps: Vec<TutorialStep>,
+}
+
+impl TutorialDefs {
+    /// Step after `current` once `trigger` has happened, or None if the
+    /// tutorial is over. Triggers other than the one awaited change nothing.
+    pub fn advance(&self, current: usize, trigger: TutorialTrigger) -> Option<usize> {
+        match self.steps.get(current) {
+            Some(step) if step.trigger == trigger => (current + 1 < self.steps.len()).then_some(current + 1),
+            _ => Some(current),
+        }
+    }
+}
+
+/// Create the default tutorial
+pub fn default_tutorial_defs() -> TutorialDefs {
+    let step = |title: &str, text: &str, trigger| TutorialStep { title: title.to_string(), text: text.to_string(), trigger };
+
+    TutorialDefs {
+        steps: vec![
+            step(
+                "Getting around",
+                "Move with the arrow keys or h j k l, and y u b n for diagonals. Head east.",
+                TutorialTrigger::Moved,
+            ),
+            step(
+                "Fighting",
+                "Something skitters in the next room. Walk into an enemy to attack it, and keep at it until it falls.",
+                TutorialTrigger::EnemyKilled,
+            ),
+            step(
+                "Skills",
+                "Skills sit on keys 1-5 and cost mana or stamina. Press 2 to patch yourself up with First Aid.",
+                TutorialTrigger::SkillUsed,
+            ),
+            step(
+                "Loot",
+                "Something glints on the floor ahead. Stand on or next to it and press g to pick it up; i opens your pack.",
+                TutorialTrigger::PickedUp,
+            ),
+            step(
+                "Shrines",
+                "Shrines lend their power once each. Stand on the shrine and press e to rest at it.",
+                TutorialTrigger::ShrineUsed,
+            ),
+            step(
+                "Going deeper",
+                "The way down is marked >. Stand on the stairs and press > to descend into the Hollowdeep.",
+                TutorialTrigger::Descended,
+            ),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tutorial_advance() {
+        let defs = default_tutorial_defs();
+        assert_eq!(defs.advance(0, TutorialTrigger::Moved), Some(1));
+        // Killing something before it's asked for doesn't skip ahead
+        assert_eq!(defs.advance(0, TutorialTrigger::EnemyKilled), Some(0));
+        assert_eq!(defs.advance(defs.steps.len() - 1, TutorialTrigger::Descended), None);
+    }
+}
@@ -0,0 +1,70 @@
+//! Weapon proc tables for data-driven on-hit effects
+//!
+//! Each weapon type can roll a set of effects whenever it lands a hit.
+//! These tables are loaded from RON files so procs can be tuned without
+//! touching combat code.
+
+use serde::{Deserialize, Serialize};
+use crate::items::WeaponType;
+
+/// Effect a weapon can inflict on hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProcEffect {
+    /// Target loses its turns
+    Stun,
+    /// Target bleeds each turn
+    Bleed,
+    /// Target is entangled and often loses its turn
+    Slow,
+}
+
+/// A single on-hit proc entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeaponProcDef {
+    /// Weapon type that can trigger the proc
+    pub weapon: WeaponType,
+    /// Effect applied to the target
+    pub effect: ProcEffect,
+    /// Chance to trigger on hit (0-100)
+    pub chance: i32,
+    /// Effect strength
+    pub intensity: i32,
+    /// Effect duration in turns
+    pub duration: i32,
+}
+
+/// Collection of weapon procs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeaponProcs {
+    pub procs: Vec<WeaponProcDef>,
+}
+
+impl WeaponProcs {
+    /// Procs a weapon type can trigger
+    pub fn for_weapon(&self, weapon: WeaponType) -> impl Iterator<Item = &WeaponProcDef> {
+        self.procs.iter().filter(move |p| p.weapon == weapon)
+    }
+}
+
+/// Create default weapon proc table
+pub fn default_weapon_procs() -> WeaponProcs {
+    let proc = |weapon, effect, chance, intensity, duration| WeaponProcDef {
+        weapon,
+        effect,
+        chance,
+        intensity,
+        duration,
+    };
+
+    WeaponProcs {
+        procs: vec![
+            proc(WeaponType::Sword, ProcEffect::Bleed, 10, 2, 3),
+            proc(WeaponType::Axe, ProcEffect::Bleed, 20, 3, 3),
+            proc(WeaponType::Dagger, ProcEffect::Bleed, 15, 2, 4),
+            proc(WeaponType::Mace, ProcEffect::Stun, 15, 1, 2),
+            proc(WeaponType::Staff, ProcEffect::Slow, 10, 1, 2),
+            proc(WeaponType::Spear, ProcEffect::Slow, 10, 1, 2),
+            proc(WeaponType::Whip, ProcEffect::Slow, 25, 1, 3),
+        ],
+    }
+}
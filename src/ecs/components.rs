@@ -43,7 +43,7 @@ pub struct Velocity {
 // ============================================================================
 
 /// Visual representation of an entity
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Renderable {
     /// Character to display
     pub glyph: char,
@@ -95,7 +95,7 @@ impl Name {
 pub struct Player;
 
 /// Marks an entity as an enemy
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Enemy {
     pub archetype: EnemyArchetype,
 }
@@ -250,6 +250,10 @@ impl Stamina {
     }
 }
 
+/// Marks the player as exhausted after running out of stamina
+#[derive(Debug, Clone, Copy)]
+pub struct Exhausted;
+
 /// Experience and level
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Experience {
@@ -297,14 +301,14 @@ pub struct CombatStats {
 }
 
 /// Faction for determining hostility
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Faction {
     Player,
     Enemy,
     Neutral,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FactionComponent(pub Faction);
 
 // ============================================================================
@@ -376,20 +380,21 @@ pub enum EquipmentSlot {
 // ============================================================================
 
 /// A status effect on an entity
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatusEffect {
     pub effect_type: StatusEffectType,
     pub duration: f32,      // Remaining duration in seconds
     pub intensity: i32,     // Effect strength
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StatusEffectType {
     // Debuffs
     Poison,
     Burn,
     Bleed,
     Slow,
+    Stun,
     Weakness,
     Curse,
     // Buffs
@@ -400,7 +405,7 @@ pub enum StatusEffectType {
 }
 
 /// Collection of active status effects
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StatusEffects {
     pub effects: Vec<StatusEffect>,
 }
@@ -410,20 +415,117 @@ pub struct StatusEffects {
 // ============================================================================
 
 /// AI behavior component
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AI {
     pub state: AIState,
     pub target: Option<Position>,
     pub home: Position,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AIState {
     Idle,
     Patrol,
     Chase,
     Attack,
     Flee,
+    /// Asleep until the player comes close or wakes it with a blow
+    Sleeping,
+}
+
+/// Turns a patroller stays blocked before giving up on a tile
+const PATROL_STUCK_TURNS: u32 = 3;
+
+/// Walks a route back and forth instead of standing idle. Both ends of the
+/// route are posts where the walker lingers before turning back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Patrol {
+    /// Every tile walked, out to the far post and back again
+    pub route: Vec<Position>,
+    /// Index of the far post in the route
+    pub turnaround: usize,
+    /// Route index being walked to
+    pub next: usize,
+    /// Turns spent at each post
+    pub linger: u32,
+    waited: u32,
+    stuck: u32,
+}
+
+impl Patrol {
+    /// Patrol out along a path and back, lingering at both ends
+    pub fn new(path: Vec<Position>, linger: u32) -> Self {
+        let turnaround = path.len().saturating_sub(1);
+        let back: Vec<Position> = path.iter().rev().skip(1).take(turnaround.saturating_sub(1)).copied().collect();
+        let mut route = path;
+        route.extend(back);
+        Self { route, turnaround, next: 0, linger, waited: 0, stuck: 0 }
+    }
+
+    /// Whether a route index is one of the posts
+    pub fn is_post(&self, index: usize) -> bool {
+        index == 0 || index == self.turnaround
+    }
+
+    /// Tile the walker is heading for
+    pub fn target(&self) -> Option<Position> {
+        self.route.get(self.next).copied()
+    }
+
+    /// Note where the walker stands at the start of its turn
+    pub fn arrive(&mut self, pos: Position) {
+        let Some(target) = self.target() else { return };
+        if pos == target {
+            self.stuck = 0;
+            if self.is_post(self.next) && self.waited < self.linger {
+                self.waited += 1;
+                return;
+            }
+            self.waited = 0;
+            self.next = (self.next + 1) % self.route.len();
+        } else if pos.chebyshev_distance(&target) > 2 {
+            // Drawn off the route by a chase: rejoin it at the nearest tile
+            self.next = self.route.iter()
+                .enumerate()
+                .min_by_key(|(_, p)| pos.chebyshev_distance(p))
+                .map_or(0, |(i, _)| i);
+        } else {
+            self.stuck += 1;
+            if self.stuck > PATROL_STUCK_TURNS {
+                self.stuck = 0;
+                self.next = (self.next + 1) % self.route.len();
+            }
+        }
+    }
+}
+
+/// Sleeps through the night while nothing disturbs it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Sleeper;
+
+/// Leads a pack; the pack shares aggro and fights harder while it lives
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PackLeader;
+
+/// Belongs to a leader's pack
+#[derive(Debug, Clone, Copy)]
+pub struct PackMember {
+    pub leader: hecs::Entity,
+    /// Strength added to attacks while the leader is alive and near
+    pub rally_strength: i32,
+}
+
+/// Saved with the leader's place in the snapshot
+impl crate::ecs::Linked for PackMember {
+    type Saved = (usize, i32);
+
+    fn unlink(&self, index: impl Fn(hecs::Entity) -> Option<usize>) -> Option<(usize, i32)> {
+        Some((index(self.leader)?, self.rally_strength))
+    }
+
+    fn relink((leader, rally_strength): (usize, i32), entity: impl Fn(usize) -> Option<hecs::Entity>) -> Option<Self> {
+        Some(PackMember { leader: entity(leader)?, rally_strength })
+    }
 }
 
 // ============================================================================
@@ -431,9 +533,13 @@ pub enum AIState {
 // ============================================================================
 
 /// Marks an entity as blocking movement
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct BlocksMovement;
 
+/// Marks an enemy that crosses lava, pits and corrupted blood unharmed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct HazardImmune;
+
 /// Marks an entity as blocking line of sight
 #[derive(Debug, Clone, Copy, Default)]
 pub struct BlocksSight;
@@ -455,7 +561,7 @@ pub struct EquipmentComponent {
 }
 
 /// Marks an entity as an item that can be picked up from the ground
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroundItem {
     pub item: crate::items::Item,
 }
@@ -465,7 +571,7 @@ pub struct GroundItem {
 // ============================================================================
 
 /// XP reward for killing this entity
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct XpReward(pub u32);
 
 /// Unspent stat points from leveling up
@@ -543,9 +649,90 @@ impl ChestRarity {
     }
 }
 
+/// Trap rigged to a chest's lid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChestTrap {
+    /// Pricks the hand with poison
+    PoisonNeedle,
+    /// Bursts into flame
+    FireBurst,
+}
+
+impl ChestTrap {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChestTrap::PoisonNeedle => "poison needle",
+            ChestTrap::FireBurst => "fire rune",
+        }
+    }
+}
+
+/// What a chest is besides a box of loot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChestKind {
+    Plain,
+    /// Springs a trap when opened, unless it is spotted and disarmed first
+    Trapped(ChestTrap),
+    /// A monster wearing a chest's shape; attacks whoever reaches for it
+    Mimic,
+    /// Takes a key or a picked lock
+    Locked,
+    /// Opens only for the right answer to a riddle (index into `entities::RIDDLES`)
+    Puzzle(usize),
+}
+
+impl ChestKind {
+    /// Loot multiplier: the more a chest asks of the player, the more it holds
+    pub fn loot_multiplier(&self) -> f32 {
+        match self {
+            ChestKind::Plain => 1.0,
+            ChestKind::Trapped(_) | ChestKind::Locked => 1.5,
+            ChestKind::Mimic => 2.0,
+            ChestKind::Puzzle(_) => 2.5,
+        }
+    }
+
+    /// Glyph that gives the chest away, if it has one. Traps and mimics
+    /// only show theirs once spotted.
+    pub fn glyph(&self, revealed: bool) -> Option<char> {
+        match self {
+            ChestKind::Plain => None,
+            ChestKind::Trapped(_) => revealed.then_some('⊗'),
+            ChestKind::Mimic => revealed.then_some('⊛'),
+            ChestKind::Locked => Some('⊠'),
+            ChestKind::Puzzle(_) => Some('⊡'),
+        }
+    }
+}
+
 /// Marks an entity as a chest that can be opened
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chest {
     pub rarity: ChestRarity,
     pub opened: bool,
+    pub kind: ChestKind,
+    /// The trap is armed, the lock shut, or the riddle unanswered
+    pub secured: bool,
+    /// The player has looked it over for traps and teeth
+    pub searched: bool,
+    /// A trap or mimic has been spotted
+    pub revealed: bool,
+}
+
+impl Chest {
+    pub fn new(rarity: ChestRarity, kind: ChestKind) -> Self {
+        Self {
+            rarity,
+            opened: false,
+            kind,
+            secured: kind != ChestKind::Plain,
+            searched: false,
+            revealed: false,
+        }
+    }
+
+    /// Glyph the chest is drawn with
+    pub fn glyph(&self) -> char {
+        self.kind.glyph(self.revealed).unwrap_or(self.rarity.glyph())
+    }
 }
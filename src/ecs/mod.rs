@@ -5,6 +5,8 @@
 pub mod components;
 pub mod systems;
 pub mod resources;
+pub mod snapshot;
 
 pub use components::*;
-pub use systems::{run_enemy_ai, execute_ai_actions, AIAction};
+pub use snapshot::{ComponentRegistry, Linked, WorldSnapshot};
+pub use systems::{Flow, Schedule, Stage, StageTimes, run_enemy_ai, run_stalker_ai, run_follower_ai, execute_ai_actions, AIAction, AIOutcome};
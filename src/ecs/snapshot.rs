@@ -0,0 +1,257 @@
+//! World snapshots
+//!
+//! Serializes entities through a registry of component types. Each component
+//! an entity carries is stored under the name it was registered with, so
+//! registering a component is all it takes to make it persist. Components
+//! that aren't registered are left behind, and a snapshot naming a component
+//! that has since been dropped from the registry still loads without it.
+//!
+//! Entity ids don't survive a load, so components pointing at other entities
+//! are registered as [`Linked`]: they're written with the snapshot index of
+//! the entity they point at, and pointed at its new id once every entity in
+//! the snapshot has been spawned.
+
+use std::collections::{BTreeMap, HashMap};
+
+use hecs::{Component, Entity, EntityBuilder, EntityRef, World};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One entity's components, by registered name
+pub type EntitySnapshot = BTreeMap<String, Value>;
+
+/// Entities taken out of a world, ready to be written out or spawned back
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub entities: Vec<EntitySnapshot>,
+}
+
+impl WorldSnapshot {
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+}
+
+/// A component that points at other entities
+pub trait Linked: Component + Sized {
+    /// The component as written out, with snapshot indices for entities
+    type Saved: Serialize + DeserializeOwned;
+
+    /// The component with each entity it points at replaced by its index in
+    /// the snapshot, or `None` if one of them isn't in it
+    fn unlink(&self, index: impl Fn(Entity) -> Option<usize>) -> Option<Self::Saved>;
+
+    /// The component back again, pointing at the entities spawned for the
+    /// indices, or `None` if one of them wasn't
+    fn relink(saved: Self::Saved, entity: impl Fn(usize) -> Option<Entity>) -> Option<Self>;
+}
+
+/// Where each entity in a snapshot sits in it
+type Indices = HashMap<Entity, usize>;
+
+/// How to write out and read back one component type
+struct Registration {
+    name: &'static str,
+    save: fn(&EntityRef<'_>, &Indices) -> Option<serde_json::Result<Value>>,
+    load: Load,
+}
+
+enum Load {
+    /// Built along with the rest of the entity
+    Plain(fn(&mut EntityBuilder, Value) -> serde_json::Result<()>),
+    /// Added once everything in the snapshot has been spawned
+    Linked(fn(&mut World, Entity, Value, &[Entity]) -> serde_json::Result<()>),
+}
+
+/// The component types snapshots carry
+#[derive(Default)]
+pub struct ComponentRegistry {
+    components: Vec<Registration>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Carry a component type in snapshots under a name. The name is what
+    /// gets written out, so it has to stay put once saves hold it.
+    pub fn register<T: Component + Serialize + DeserializeOwned>(mut self, name: &'static str) -> Self {
+        debug_assert!(
+            self.components.iter().all(|c| c.name != name),
+            "component {} registered twice", name
+        );
+        self.components.push(Registration { name, save: save_component::<T>, load: Load::Plain(load_component::<T>) });
+        self
+    }
+
+    /// Carry a component that points at other entities. It's only kept
+    /// while the entities it points at are in the snapshot too.
+    pub fn register_linked<T: Linked>(mut self, name: &'static str) -> Self {
+        debug_assert!(
+            self.components.iter().all(|c| c.name != name),
+            "component {} registered twice", name
+        );
+        self.components.push(Registration { name, save: save_linked::<T>, load: Load::Linked(load_linked::<T>) });
+        self
+    }
+
+    /// Every registered component an entity carries. Taken on its own, an
+    /// entity has nothing for linked components to point at, so they're left out.
+    pub fn snapshot_entity(&self, entity: &EntityRef<'_>) -> serde_json::Result<EntitySnapshot> {
+        self.snapshot_entity_in(entity, &Indices::new())
+    }
+
+    fn snapshot_entity_in(&self, entity: &EntityRef<'_>, indices: &Indices) -> serde_json::Result<EntitySnapshot> {
+        let mut components = EntitySnapshot::new();
+        for registration in &self.components {
+            if let Some(value) = (registration.save)(entity, indices) {
+                components.insert(registration.name.to_string(), value?);
+            }
+        }
+        Ok(components)
+    }
+
+    /// Snapshot every entity in the world that passes the filter
+    pub fn snapshot(&self, world: &World, keep: impl Fn(&EntityRef<'_>) -> bool) -> serde_json::Result<WorldSnapshot> {
+        let kept: Vec<EntityRef<'_>> = world.iter().filter(|entity| keep(entity)).collect();
+        let indices: Indices = kept.iter().enumerate().map(|(i, entity)| (entity.entity(), i)).collect();
+        let entities = kept.iter()
+            .map(|entity| self.snapshot_entity_in(entity, &indices))
+            .collect::<serde_json::Result<_>>()?;
+        Ok(WorldSnapshot { entities })
+    }
+
+    /// Spawn a snapshot's entities into a world, returning them in the
+    /// snapshot's order
+    pub fn restore(&self, world: &mut World, snapshot: &WorldSnapshot) -> serde_json::Result<Vec<Entity>> {
+        let mut builder = EntityBuilder::new();
+        let mut spawned = Vec::with_capacity(snapshot.len());
+        for components in &snapshot.entities {
+            for registration in &self.components {
+                if let (Load::Plain(load), Some(value)) = (&registration.load, components.get(registration.name)) {
+                    load(&mut builder, value.clone())?;
+                }
+            }
+            spawned.push(world.spawn(builder.build()));
+        }
+
+        // Links once everything they could point at is there
+        for (components, &entity) in snapshot.entities.iter().zip(&spawned) {
+            for registration in &self.components {
+                if let (Load::Linked(load), Some(value)) = (&registration.load, components.get(registration.name)) {
+                    load(world, entity, value.clone(), &spawned)?;
+                }
+            }
+        }
+        Ok(spawned)
+    }
+}
+
+fn save_component<T: Component + Serialize>(entity: &EntityRef<'_>, _: &Indices) -> Option<serde_json::Result<Value>> {
+    entity.get::<&T>().map(|component| serde_json::to_value(&*component))
+}
+
+fn save_linked<T: Linked>(entity: &EntityRef<'_>, indices: &Indices) -> Option<serde_json::Result<Value>> {
+    let saved = entity.get::<&T>()?.unlink(|target| indices.get(&target).copied())?;
+    Some(serde_json::to_value(saved))
+}
+
+fn load_linked<T: Linked>(world: &mut World, entity: Entity, value: Value, spawned: &[Entity]) -> serde_json::Result<()> {
+    let saved = serde_json::from_value::<T::Saved>(value)?;
+    if let Some(component) = T::relink(saved, |i| spawned.get(i).copied()) {
+        let _ = world.insert_one(entity, component);
+    }
+    Ok(())
+}
+
+fn load_component<T: Component + DeserializeOwned>(builder: &mut EntityBuilder, value: Value) -> serde_json::Result<()> {
+    builder.add(serde_json::from_value::<T>(value)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{BlocksMovement, Health, Name, Position, Velocity};
+
+    #[test]
+    fn test_snapshot_round_trips_registered_components() {
+        let registry = ComponentRegistry::new()
+            .register::<Position>("position")
+            .register::<Name>("name")
+            .register::<Health>("health")
+            .register::<BlocksMovement>("blocks_movement");
+
+        let mut world = World::new();
+        let mut health = Health::new(40);
+        health.current = 12;
+        world.spawn((Position::new(3, 4), Name::new("Bone Hound"), health, BlocksMovement, Velocity::default()));
+        world.spawn((Position::new(9, 9), Name::new("Left Behind")));
+
+        let snapshot = registry
+            .snapshot(&world, |entity| entity.has::<Health>())
+            .unwrap();
+        assert_eq!(snapshot.len(), 1);
+
+        // Through JSON and back, with a component the registry has since lost
+        let mut json: WorldSnapshot = serde_json::from_str(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+        json.entities[0].insert("retired".to_string(), Value::Null);
+
+        let mut loaded = World::new();
+        let spawned = registry.restore(&mut loaded, &json).unwrap();
+        let entity = loaded.entity(spawned[0]).unwrap();
+        assert_eq!(*entity.get::<&Position>().unwrap(), Position::new(3, 4));
+        assert_eq!(entity.get::<&Name>().unwrap().0, "Bone Hound");
+        assert_eq!(entity.get::<&Health>().unwrap().current, 12);
+        assert!(entity.has::<BlocksMovement>());
+        assert!(!entity.has::<Velocity>());
+    }
+
+    /// Points at another entity, as pack members do at their leader
+    struct Follows(Entity);
+
+    impl Linked for Follows {
+        type Saved = usize;
+
+        fn unlink(&self, index: impl Fn(Entity) -> Option<usize>) -> Option<usize> {
+            index(self.0)
+        }
+
+        fn relink(saved: usize, entity: impl Fn(usize) -> Option<Entity>) -> Option<Self> {
+            entity(saved).map(Follows)
+        }
+    }
+
+    #[test]
+    fn test_linked_components_point_at_the_restored_entities() {
+        let registry = ComponentRegistry::new()
+            .register::<Name>("name")
+            .register_linked::<Follows>("follows");
+
+        let mut world = World::new();
+        let gone = world.spawn((Name::new("Gone"),));
+        let leader = world.spawn((Name::new("Leader"),));
+        world.spawn((Name::new("Member"), Follows(leader)));
+        world.spawn((Name::new("Stray"), Follows(gone)));
+        world.despawn(gone).unwrap();
+
+        let snapshot = registry.snapshot(&world, |_| true).unwrap();
+        // The stray's leader isn't in the snapshot, so it's saved without one
+        assert!(!snapshot.entities[2].contains_key("follows"));
+
+        // Spawned after something else, so the ids move
+        let mut loaded = World::new();
+        loaded.spawn((Name::new("Already here"),));
+        let spawned = registry.restore(&mut loaded, &snapshot).unwrap();
+        let follows = loaded.get::<&Follows>(spawned[1]).unwrap().0;
+        assert_eq!(follows, spawned[0]);
+        assert_eq!(loaded.get::<&Name>(follows).unwrap().0, "Leader");
+        assert!(loaded.get::<&Follows>(spawned[2]).is_err());
+    }
+}
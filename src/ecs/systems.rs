@@ -2,39 +2,201 @@
 //!
 //! Game logic systems that operate on entities with specific components.
 
+use std::time::{Duration, Instant};
+
 use hecs::World;
-use rand::Rng;
-use crate::ecs::{Position, AI, AIState, Enemy, Health, Name, BlocksMovement, StatusEffects, StatusEffectType};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use crate::ecs::{Position, AI, AIState, Enemy, EnemyArchetype, Health, Name, BlocksMovement, HazardImmune, StatusEffects, StatusEffectType, PackLeader, PackMember, Patrol, Sleeper};
+use crate::data::{BehaviorTrees, BehaviorNode, Behavior, Condition};
 use crate::world::Map;
 
 /// Detection range for enemies to notice the player
 const DETECTION_RANGE: i32 = 8;
+/// Range at which combatant followers engage enemies
+const FOLLOWER_ENGAGE_RANGE: i32 = 5;
+/// Range within which a pack leader rallies its pack
+const RALLY_RANGE: i32 = 6;
+/// Sleepers wake when the player comes this close
+const SLEEPER_WAKE_RANGE: i32 = 2;
+
+/// Stages of a turn, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    /// Closing out what the player just did: cooldowns, regeneration, searching
+    PlayerAction,
+    /// Damage over time and effects wearing off
+    StatusTick,
+    /// Enemies, bosses and followers act
+    Ai,
+    /// Mechanisms, hazardous ground and the floor giving way
+    Hazards,
+    /// The dead are reaped and what the turn left behind is settled
+    Cleanup,
+    /// Clocks, presence and hints catch up with the turn
+    Events,
+}
+
+impl Stage {
+    pub const COUNT: usize = 6;
+}
+
+/// How long each stage of a turn took
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StageTimes([Duration; Stage::COUNT]);
+
+impl StageTimes {
+    pub fn get(&self, stage: Stage) -> Duration {
+        self.0[stage as usize]
+    }
+
+    /// The whole turn
+    pub fn total(&self) -> Duration {
+        self.0.iter().sum()
+    }
+}
+
+/// Whether the turn goes on after a system runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    /// The turn is over early (the player died, or left the floor)
+    Stop,
+}
 
-/// Run AI for all enemies
+/// A system run once per turn on some state
+pub type System<C> = fn(&mut C) -> Flow;
+
+/// Systems grouped into stages. Stages run in order, and the systems in a
+/// stage run in the order they were added.
+pub struct Schedule<C> {
+    systems: Vec<(Stage, &'static str, System<C>)>,
+}
+
+impl<C> Schedule<C> {
+    pub fn new() -> Self {
+        Self { systems: Vec::new() }
+    }
+
+    /// Add a system to a stage
+    pub fn with(mut self, stage: Stage, name: &'static str, system: System<C>) -> Self {
+        let at = self.systems.partition_point(|(s, _, _)| *s <= stage);
+        self.systems.insert(at, (stage, name, system));
+        self
+    }
+
+    /// Each system's stage and name, in the order they run
+    pub fn order(&self) -> impl Iterator<Item = (Stage, &'static str)> + '_ {
+        self.systems.iter().map(|(stage, name, _)| (*stage, *name))
+    }
+
+    /// Run every system in order, until one stops the turn
+    pub fn run(&self, state: &mut C) -> Flow {
+        for (_, _, system) in &self.systems {
+            if system(state) == Flow::Stop {
+                return Flow::Stop;
+            }
+        }
+        Flow::Continue
+    }
+
+    /// Run every system in order like `run`, noting how long each stage took
+    pub fn run_timed(&self, state: &mut C, times: &mut StageTimes) -> Flow {
+        *times = StageTimes::default();
+        for (stage, _, system) in &self.systems {
+            let start = Instant::now();
+            let flow = system(state);
+            times.0[*stage as usize] += start.elapsed();
+            if flow == Flow::Stop {
+                return Flow::Stop;
+            }
+        }
+        Flow::Continue
+    }
+}
+
+impl<C> Default for Schedule<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run AI for all enemies, each thinking with its archetype's behavior tree.
+/// Decisions are made in parallel against the world as it stands, then
+/// applied in entity order, so a seed plays out the same however many
+/// threads there are.
 pub fn run_enemy_ai(
     world: &mut World,
     map: &Map,
     player_pos: Position,
+    trees: &BehaviorTrees,
+    night: bool,
     rng: &mut impl Rng,
 ) -> Vec<AIAction> {
     let mut actions = Vec::new();
 
-    // Collect all enemies with AI and their slow status (need to collect first to avoid borrow issues)
-    let enemies: Vec<(hecs::Entity, Position, AIState, i32)> = world
+    // Watching eyes and clanking armor draw enemies from further away
+    let detection_range = DETECTION_RANGE + world
+        .query::<&crate::progression::Mutations>()
+        .iter()
+        .map(|(_, m)| m.detection_bonus())
+        .max()
+        .unwrap_or(0)
+        + world
+            .query::<&crate::ecs::EquipmentComponent>()
+            .iter()
+            .map(|(_, eq)| eq.equipment.weight_class().noise())
+            .max()
+            .unwrap_or(0);
+
+    // Collect all enemies with AI and their slow/stun status (need to collect first to avoid borrow issues)
+    let mut enemies: Vec<(hecs::Entity, Position, EnemyArchetype, i32, Option<hecs::Entity>)> = world
         .query::<(&Position, &AI, &Enemy)>()
         .iter()
-        .map(|(entity, (pos, ai, _))| {
+        .filter(|(entity, _)| {
+            // Stunned enemies lose their turn outright, the dead wait to be
+            // reaped, and the Warden hunts by its own rules
+            !is_stunned(world, *entity) && !is_slain(world, *entity)
+                && world.get::<&crate::entities::Stalker>(*entity).is_err()
+        })
+        .map(|(entity, (pos, _, enemy))| {
             // Check if enemy is slowed
             let slow_intensity = world
                 .get::<&StatusEffects>(entity)
                 .ok()
                 .map(|effects| effects.effect_intensity(StatusEffectType::Slow))
                 .unwrap_or(0);
-            (entity, *pos, ai.state, slow_intensity)
+            // Bosses think like bosses whatever their archetype
+            let archetype = if world.get::<&crate::entities::BossComponent>(entity).is_ok() {
+                EnemyArchetype::Boss
+            } else {
+                enemy.archetype
+            };
+            (entity, *pos, archetype, slow_intensity, pack_of(world, entity))
         })
         .collect();
+    // Enemies act in entity order, however the world happens to store them
+    enemies.sort_by_key(|(entity, ..)| entity.id());
+
+    // A pack that spots the player hunts as one
+    let alerted_packs: std::collections::HashSet<hecs::Entity> = enemies.iter()
+        .filter(|(_, pos, _, _, _)| pos.chebyshev_distance(&player_pos) <= detection_range)
+        .filter_map(|(_, _, _, _, pack)| *pack)
+        .collect();
+
+    let followers: Vec<(hecs::Entity, Position)> = world
+        .query::<(&Position, &crate::entities::Follower)>()
+        .iter()
+        .map(|(entity, (pos, _))| (entity, *pos))
+        .collect();
 
-    for (entity, enemy_pos, _current_state, slow_intensity) in enemies {
+    // Serial phase: the rolls and bookkeeping that touch the world or the
+    // shared generator, in entity order. Each enemy left to think gets a
+    // generator of its own, seeded here, so thinking in parallel comes out
+    // the same on every run.
+    let mut thinkers = Vec::with_capacity(enemies.len());
+    for (entity, enemy_pos, archetype, slow_intensity, pack) in enemies {
         // If slowed, chance to skip turn based on intensity
         // Intensity 1 = 50% skip, intensity 2 = 66% skip, intensity 3+ = 75% skip
         if slow_intensity > 0 {
@@ -48,39 +210,330 @@ pub fn run_enemy_ai(
             }
         }
 
-        let distance = enemy_pos.chebyshev_distance(&player_pos);
+        // Sleepers doze through the night until the player is on top of
+        // them or something hurts them
+        if night && world.get::<&Sleeper>(entity).is_ok() {
+            let undisturbed = enemy_pos.chebyshev_distance(&player_pos) > SLEEPER_WAKE_RANGE
+                && world.get::<&Health>(entity).is_ok_and(|h| h.current >= h.max)
+                && world.get::<&AI>(entity).is_ok_and(|ai| matches!(ai.state, AIState::Idle | AIState::Patrol | AIState::Sleeping));
+            if undisturbed {
+                if let Ok(mut ai) = world.get::<&mut AI>(entity) {
+                    ai.state = AIState::Sleeping;
+                    ai.target = None;
+                }
+                continue;
+            }
+        }
 
-        // Update AI state based on distance
-        let new_state = if distance <= 1 {
-            AIState::Attack
-        } else if distance <= DETECTION_RANGE {
-            AIState::Chase
-        } else {
-            AIState::Idle
+        if let Ok(mut patrol) = world.get::<&mut Patrol>(entity) {
+            patrol.arrive(enemy_pos);
+        }
+
+        let Some(tree) = trees.for_archetype(archetype) else { continue };
+        let mind = EnemyMind {
+            entity,
+            pos: enemy_pos,
+            player_pos,
+            detection_range,
+            pack_alerted: pack.is_some_and(|p| alerted_packs.contains(&p)),
+            followers: &followers,
         };
+        thinkers.push((tree, mind, rng.gen::<u64>()));
+    }
 
-        // Update the entity's AI state
+    // Parallel phase: every enemy decides against the world as the turn
+    // found it. Results keep the thinkers' order.
+    let world_ref: &World = world;
+    let decisions: Vec<(hecs::Entity, AIState, Option<AIAction>)> = thinkers
+        .par_iter()
+        .map(|(tree, mind, seed)| {
+            let mut rng = StdRng::seed_from_u64(*seed);
+            let (state, action) = match think(tree, mind, map, world_ref, &mut rng) {
+                Tick::Act(state, action) => (state, action),
+                Tick::Success | Tick::Failure => (AIState::Idle, None),
+            };
+            (mind.entity, state, action)
+        })
+        .collect();
+
+    // Serial phase: apply the decisions in entity order
+    for (entity, new_state, action) in decisions {
         if let Ok(mut ai) = world.get::<&mut AI>(entity) {
             ai.state = new_state;
-            ai.target = if new_state != AIState::Idle {
+            ai.target = if matches!(new_state, AIState::Chase | AIState::Attack | AIState::Flee) {
                 Some(player_pos)
             } else {
                 None
             };
         }
+        actions.extend(action);
+    }
+
+    actions
+}
+
+/// Run AI for stalkers, which always know where the player is and follow
+/// the shortest way there, bashing through doors in the path
+pub fn run_stalker_ai(world: &World, map: &Map, player_pos: Position) -> Vec<AIAction> {
+    let stalkers: Vec<(hecs::Entity, Position)> = world
+        .query::<(&Position, &crate::entities::Stalker)>()
+        .iter()
+        .filter(|(entity, _)| !is_stunned(world, *entity) && !is_slain(world, *entity))
+        .map(|(entity, (pos, _))| (entity, *pos))
+        .collect();
+    if stalkers.is_empty() {
+        return Vec::new();
+    }
+
+    let scent = crate::world::DijkstraMap::new(map, player_pos);
+    let mut actions = Vec::new();
+    for (entity, pos) in stalkers {
+        if pos.chebyshev_distance(&player_pos) <= 1 {
+            actions.push(AIAction::Attack { attacker: entity, target_pos: player_pos });
+            continue;
+        }
+        let downhill = scent.downhill(pos);
+        let action = if let Some(&to) = downhill.iter().find(|to| is_valid_move(entity, **to, map, world)) {
+            Some(AIAction::Move { entity, to })
+        } else {
+            downhill.into_iter()
+                .find(|to| map.get_tile(to.x, to.y).is_some_and(|t| t.tile_type.is_breachable()))
+                .map(|door| AIAction::BashDoor { entity, door })
+        };
+        actions.extend(action);
+    }
+    actions
+}
+
+/// Whether an entity is stunned and loses its turn
+fn is_stunned(world: &World, entity: hecs::Entity) -> bool {
+    world
+        .get::<&StatusEffects>(entity)
+        .is_ok_and(|effects| effects.has_effect(StatusEffectType::Stun))
+}
+
+/// Dead, but not yet reaped
+fn is_slain(world: &World, entity: hecs::Entity) -> bool {
+    world.get::<&Health>(entity).is_ok_and(|health| health.is_dead())
+}
+
+/// What an enemy knows while walking its behavior tree
+struct EnemyMind<'a> {
+    entity: hecs::Entity,
+    pos: Position,
+    player_pos: Position,
+    detection_range: i32,
+    /// Another member of the enemy's pack has spotted the player
+    pack_alerted: bool,
+    followers: &'a [(hecs::Entity, Position)],
+}
+
+/// Result of running a behavior tree node
+enum Tick {
+    Failure,
+    Success,
+    /// An action was chosen; the tree stops here
+    Act(AIState, Option<AIAction>),
+}
+
+/// Walk a behavior tree node
+fn think(node: &BehaviorNode, mind: &EnemyMind, map: &Map, world: &World, rng: &mut impl Rng) -> Tick {
+    match node {
+        BehaviorNode::Selector(children) => {
+            for child in children {
+                match think(child, mind, map, world, rng) {
+                    Tick::Failure => continue,
+                    tick => return tick,
+                }
+            }
+            Tick::Failure
+        }
+        BehaviorNode::Sequence(children) => {
+            for child in children {
+                match think(child, mind, map, world, rng) {
+                    Tick::Success => continue,
+                    tick => return tick,
+                }
+            }
+            Tick::Success
+        }
+        BehaviorNode::Check(condition) => {
+            if check(*condition, mind, world, rng) { Tick::Success } else { Tick::Failure }
+        }
+        BehaviorNode::Do(behavior) => act(*behavior, mind, map, world),
+    }
+}
+
+/// Test a behavior tree condition
+fn check(condition: Condition, mind: &EnemyMind, world: &World, rng: &mut impl Rng) -> bool {
+    let distance = mind.pos.chebyshev_distance(&mind.player_pos);
+    match condition {
+        Condition::PlayerDetected => distance <= mind.detection_range || mind.pack_alerted,
+        Condition::PlayerWithin(range) => distance <= range,
+        Condition::HealthAtMost(percent) => {
+            // A shaken enemy loses its nerve sooner
+            let shaken = world.get::<&crate::combat::Shaken>(mind.entity).map(|s| s.0).unwrap_or(0);
+            world
+                .get::<&Health>(mind.entity)
+                .is_ok_and(|h| h.current * 100 <= h.max * (percent + shaken))
+        }
+        Condition::AllyHurt { range, percent } => hurt_ally(mind, world, range, percent).is_some(),
+        Condition::Chance(percent) => rng.gen_range(0..100) < percent,
+    }
+}
+
+/// Turn a behavior tree action into what the enemy does
+fn act(behavior: Behavior, mind: &EnemyMind, map: &Map, world: &World) -> Tick {
+    let entity = mind.entity;
+    match behavior {
+        Behavior::Attack => {
+            if mind.pos.chebyshev_distance(&mind.player_pos) > 1 {
+                return Tick::Failure;
+            }
+            Tick::Act(AIState::Attack, Some(AIAction::Attack { attacker: entity, target_pos: mind.player_pos }))
+        }
+        Behavior::Chase => {
+            // Strike a follower in reach rather than walking past it
+            let adjacent_follower = mind.followers.iter()
+                .find(|(_, pos)| mind.pos.chebyshev_distance(pos) <= 1)
+                .map(|(follower, _)| *follower);
+            let action = if let Some(target) = adjacent_follower {
+                Some(AIAction::AttackAlly { attacker: entity, target })
+            } else if let Some(move_to) = calculate_chase_move(entity, mind.pos, mind.player_pos, map, world) {
+                Some(AIAction::Move { entity, to: move_to })
+            } else {
+                door_in_the_way(mind.pos, mind.player_pos, map).map(|door| AIAction::BashDoor { entity, door })
+            };
+            Tick::Act(AIState::Chase, action)
+        }
+        Behavior::Flee => match calculate_flee_move(entity, mind.pos, mind.player_pos, map, world) {
+            Some(move_to) => Tick::Act(AIState::Flee, Some(AIAction::Move { entity, to: move_to })),
+            // Cornered: let the rest of the tree decide
+            None => Tick::Failure,
+        },
+        Behavior::Hold => Tick::Act(AIState::Chase, None),
+        Behavior::HealAlly { range, amount } => match hurt_ally(mind, world, range, 99) {
+            Some(target) => Tick::Act(AIState::Chase, Some(AIAction::Heal { healer: entity, target, amount })),
+            None => Tick::Failure,
+        },
+        Behavior::Patrol => {
+            let Some(target) = world.get::<&Patrol>(entity).ok().and_then(|p| p.target()) else {
+                return Tick::Failure;
+            };
+            let action = (target != mind.pos)
+                .then(|| calculate_chase_move(entity, mind.pos, target, map, world))
+                .flatten()
+                .map(|to| AIAction::Move { entity, to });
+            Tick::Act(AIState::Patrol, action)
+        }
+    }
+}
+
+/// What an NG+ elite's modifiers do when it lands a blow
+fn elite_on_hit(world: &World, attacker: hecs::Entity, target: hecs::Entity, name: &str, damage: i32) -> Vec<String> {
+    use crate::progression::{EliteModifier, EliteModifiers, new_game_plus::{VAMPIRIC_DRAIN_PERCENT, VENOM_POISON}};
+
+    let Ok(mods) = world.get::<&EliteModifiers>(attacker) else { return Vec::new() };
+    let mut messages = Vec::new();
+    if mods.has(EliteModifier::Vampiric) {
+        let drained = world.get::<&mut Health>(attacker)
+            .map(|mut h| h.heal(damage * VAMPIRIC_DRAIN_PERCENT / 100))
+            .unwrap_or(0);
+        if drained > 0 {
+            messages.push(format!("The {} drinks your blood. (+{} HP)", name, drained));
+        }
+    }
+    if mods.has(EliteModifier::Venomous) {
+        if let Ok(mut effects) = world.get::<&mut StatusEffects>(target) {
+            let (duration, intensity) = VENOM_POISON;
+            effects.add_effect(StatusEffectType::Poison, duration, intensity);
+            messages.push(format!("The {}'s venom seeps into the wound!", name));
+        }
+    }
+    messages
+}
+
+/// Leader of the pack an enemy runs with, if any
+fn pack_of(world: &World, entity: hecs::Entity) -> Option<hecs::Entity> {
+    if world.get::<&PackLeader>(entity).is_ok() {
+        return Some(entity);
+    }
+    world.get::<&PackMember>(entity).ok().map(|m| m.leader)
+}
+
+/// Strength a pack member draws from its leader while the leader lives nearby
+fn rally_bonus(world: &World, entity: hecs::Entity) -> i32 {
+    let Ok(member) = world.get::<&PackMember>(entity) else { return 0 };
+    let leader_alive = world.get::<&Health>(member.leader).is_ok_and(|h| !h.is_dead());
+    let leader_near = match (world.get::<&Position>(entity), world.get::<&Position>(member.leader)) {
+        (Ok(pos), Ok(leader_pos)) => pos.chebyshev_distance(&leader_pos) <= RALLY_RANGE,
+        _ => false,
+    };
+    if leader_alive && leader_near { member.rally_strength } else { 0 }
+}
+
+/// Most wounded other enemy within range at or below a health percent
+fn hurt_ally(mind: &EnemyMind, world: &World, range: i32, percent: i32) -> Option<hecs::Entity> {
+    world.query::<(&Position, &Health, &Enemy)>()
+        .iter()
+        .filter(|(e, (pos, health, _))| {
+            *e != mind.entity
+                && !health.is_dead()
+                && mind.pos.chebyshev_distance(pos) <= range
+                && health.current * 100 <= health.max * percent
+        })
+        .min_by_key(|(_, (_, health, _))| health.current * 100 / health.max.max(1))
+        .map(|(e, _)| e)
+}
 
-        // Generate action based on state
-        match new_state {
-            AIState::Attack => {
-                actions.push(AIAction::Attack { attacker: entity, target_pos: player_pos });
+/// Run AI for all followers (allies follow the player and fight nearby enemies)
+pub fn run_follower_ai(world: &World, map: &Map, player_pos: Position) -> Vec<AIAction> {
+    use crate::entities::{Follower, BossComponent};
+
+    let mut actions = Vec::new();
+
+    // Followers won't pick fights with bosses
+    let enemies: Vec<(hecs::Entity, Position)> = world
+        .query::<(&Position, &Enemy)>()
+        .without::<&BossComponent>()
+        .iter()
+        .map(|(entity, (pos, _))| (entity, *pos))
+        .collect();
+
+    let followers: Vec<(hecs::Entity, Position, bool)> = world
+        .query::<(&Position, &Follower)>()
+        .iter()
+        .map(|(entity, (pos, follower))| (entity, *pos, follower.combatant))
+        .collect();
+
+    for (entity, pos, combatant) in followers {
+        if combatant {
+            // Closest enemy near both the follower and the player
+            let target = enemies.iter()
+                .filter(|(_, e)| {
+                    pos.chebyshev_distance(e) <= FOLLOWER_ENGAGE_RANGE
+                        && player_pos.chebyshev_distance(e) <= FOLLOWER_ENGAGE_RANGE
+                })
+                .min_by_key(|(_, e)| pos.chebyshev_distance(e));
+
+            if let Some(&(target, target_pos)) = target {
+                if pos.chebyshev_distance(&target_pos) <= 1 {
+                    actions.push(AIAction::AllyAttack { attacker: entity, target });
+                } else if let Some(move_to) = calculate_chase_move(entity, pos, target_pos, map, world) {
+                    actions.push(AIAction::Move { entity, to: move_to });
+                }
+                continue;
             }
-            AIState::Chase => {
-                // Calculate move towards player
-                if let Some(move_to) = calculate_chase_move(enemy_pos, player_pos, map, world) {
+        }
+
+        // Stay close to the player (escorts keep tighter than fighters)
+        let keep_within = if combatant { 2 } else { 1 };
+        if pos.chebyshev_distance(&player_pos) > keep_within {
+            if let Some(move_to) = calculate_chase_move(entity, pos, player_pos, map, world) {
+                if move_to != player_pos {
                     actions.push(AIAction::Move { entity, to: move_to });
                 }
             }
-            _ => {}
         }
     }
 
@@ -89,6 +542,7 @@ pub fn run_enemy_ai(
 
 /// Calculate the best move for chasing the player
 fn calculate_chase_move(
+    mover: hecs::Entity,
     from: Position,
     target: Position,
     map: &Map,
@@ -123,7 +577,7 @@ fn calculate_chase_move(
 
     // Find first valid move
     for pos in candidates {
-        if is_valid_move(pos, map, world) {
+        if is_valid_move(mover, pos, map, world) {
             return Some(pos);
         }
     }
@@ -131,10 +585,51 @@ fn calculate_chase_move(
     None
 }
 
+/// A shut door on the direct route toward the target
+fn door_in_the_way(from: Position, target: Position, map: &Map) -> Option<Position> {
+    let dx = (target.x - from.x).signum();
+    let dy = (target.y - from.y).signum();
+
+    [
+        Position::new(from.x + dx, from.y + dy),
+        Position::new(from.x + dx, from.y),
+        Position::new(from.x, from.y + dy),
+    ]
+    .into_iter()
+    .filter(|pos| *pos != from)
+    .find(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type.is_breachable()))
+}
+
+/// Calculate the move that puts the most distance between a fleeing enemy and the player
+fn calculate_flee_move(
+    mover: hecs::Entity,
+    from: Position,
+    threat: Position,
+    map: &Map,
+    world: &World,
+) -> Option<Position> {
+    let current = from.chebyshev_distance(&threat);
+
+    (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| Position::new(from.x + dx, from.y + dy)))
+        .filter(|pos| *pos != from && pos.chebyshev_distance(&threat) > current)
+        .filter(|pos| is_valid_move(mover, *pos, map, world))
+        .max_by_key(|pos| (pos.chebyshev_distance(&threat), (pos.x - threat.x).abs() + (pos.y - threat.y).abs()))
+}
+
 /// Check if a position is valid for an enemy to move to
-fn is_valid_move(pos: Position, map: &Map, world: &World) -> bool {
-    // Check map walkability
-    if !map.is_walkable(pos.x, pos.y) {
+fn is_valid_move(mover: hecs::Entity, pos: Position, map: &Map, world: &World) -> bool {
+    // Hazards are avoided by everything that can't float over them
+    let tile = match map.get_tile(pos.x, pos.y) {
+        Some(tile) => tile.tile_type,
+        None => return false,
+    };
+    let passable = if tile.is_hazard() {
+        world.get::<&HazardImmune>(mover).is_ok()
+    } else {
+        tile.is_walkable()
+    };
+    if !passable {
         return false;
     }
 
@@ -153,39 +648,93 @@ fn is_valid_move(pos: Position, map: &Map, world: &World) -> bool {
 pub enum AIAction {
     Move { entity: hecs::Entity, to: Position },
     Attack { attacker: hecs::Entity, target_pos: Position },
+    /// An enemy attacks one of the player's followers
+    AttackAlly { attacker: hecs::Entity, target: hecs::Entity },
+    /// A follower attacks an enemy
+    AllyAttack { attacker: hecs::Entity, target: hecs::Entity },
+    /// An enemy batters a door standing in its way
+    BashDoor { entity: hecs::Entity, door: Position },
+    /// An enemy mends another enemy's wounds
+    Heal { healer: hecs::Entity, target: hecs::Entity, amount: i32 },
+}
+
+/// What happened while executing AI actions
+#[derive(Debug, Default)]
+pub struct AIOutcome {
+    /// Combat log messages
+    pub messages: Vec<String>,
+    /// Enemy attacks the player blocked with a shield
+    pub blocks: u32,
+    /// Enemies that stepped out of the player's reach and provoked a free attack
+    pub provoked: Vec<hecs::Entity>,
+    /// Doors enemies battered this turn (the map is updated by the caller)
+    pub door_bashes: Vec<(hecs::Entity, Position)>,
+    /// First enemy to step onto a tile the player is watching with a prepared action
+    pub overwatch_triggered: Option<hecs::Entity>,
+    /// Melee blows that landed, for the aura system
+    pub hits: Vec<crate::combat::Hit>,
 }
 
-/// Execute AI actions after collecting them
+/// Execute AI actions after collecting them. `watched` holds the tiles the
+/// player covers with a prepared action; the first enemy to move onto one
+/// from outside them springs it.
 pub fn execute_ai_actions(
     world: &mut World,
     actions: Vec<AIAction>,
     player_entity: Option<hecs::Entity>,
+    watched: Option<&std::collections::HashSet<Position>>,
     rng: &mut impl rand::Rng,
-) -> Vec<String> {
-    use crate::combat::{calculate_attack_with_equipment, EquipmentBonuses};
+) -> AIOutcome {
+    use crate::combat::{calculate_attack_with_equipment, DerivedStats};
     use crate::ecs::{Stats, EquipmentComponent};
 
     let mut messages = Vec::new();
+    let mut blocks = 0;
+    let mut provoked = Vec::new();
+    let mut door_bashes = Vec::new();
+    let mut overwatch_triggered = None;
+    let mut hits = Vec::new();
 
-    // Get player equipment bonuses once for all attacks
-    let player_equipment = player_entity
+    let player_pos = player_entity.and_then(|p| world.get::<&Position>(p).ok().map(|p| *p));
+
+    // Shield held by the player (name, block chance)
+    let shield = player_entity
         .and_then(|p| world.get::<&EquipmentComponent>(p).ok())
-        .map(|eq| EquipmentBonuses {
-            weapon_damage: 0, // Not used for defense
-            armor: eq.equipment.total_armor(),
-            str_bonus: eq.equipment.strength_bonus(),
-            dex_bonus: eq.equipment.dexterity_bonus(),
-            crit_bonus: 0.0, // Not used for defense
-        })
+        .and_then(|eq| eq.equipment.get(crate::items::EquipSlot::OffHand)
+            .filter(|i| i.is_shield())
+            .map(|i| (i.name.clone(), i.block_chance())));
+
+    // The player's defenses, worked out once for all attacks
+    let player_defense = player_entity
+        .and_then(|p| DerivedStats::compute(world, p))
+        .map(|d| d.defense_bonuses())
         .unwrap_or_default();
 
     for action in actions {
         match action {
             AIAction::Move { entity, to } => {
                 // Update position
-                if let Ok(mut pos) = world.get::<&mut Position>(entity) {
+                let from = world.get::<&mut Position>(entity).ok().map(|mut pos| {
+                    let from = *pos;
                     pos.x = to.x;
                     pos.y = to.y;
+                    from
+                });
+
+                // Enemies backing away from the player leave themselves open
+                if let (Some(from), Some(player_pos)) = (from, player_pos) {
+                    if world.get::<&Enemy>(entity).is_ok()
+                        && crate::game::leaves_reach(from, to, player_pos)
+                    {
+                        provoked.push(entity);
+                    }
+                }
+
+                // Enemies walking into the player's prepared action spring it
+                if let (Some(from), Some(watched), None) = (from, watched, overwatch_triggered) {
+                    if world.get::<&Enemy>(entity).is_ok() && watched.contains(&to) && !watched.contains(&from) {
+                        overwatch_triggered = Some(entity);
+                    }
                 }
             }
             AIAction::Attack { attacker, target_pos: _ } => {
@@ -195,22 +744,26 @@ pub fn execute_ai_actions(
                     .map(|n| n.0.clone())
                     .unwrap_or_else(|_| "Enemy".to_string());
 
-                let attacker_stats = world
+                let mut attacker_stats = world
                     .get::<&Stats>(attacker)
                     .map(|s| *s)
                     .unwrap_or(Stats::new(8, 8, 8, 8));
+                attacker_stats.strength += rally_bonus(world, attacker);
 
                 // Get player stats for defense calculation
                 let player_stats = player_entity
                     .and_then(|p| world.get::<&Stats>(p).ok().map(|s| *s))
                     .unwrap_or(Stats::player_base());
 
-                // Calculate attack with equipment bonuses
+                // Calculate attack with the attacker's buffs and the player's gear
+                let attacker_bonuses = DerivedStats::compute(world, attacker)
+                    .map(|d| d.attack_bonuses())
+                    .unwrap_or_default();
                 let result = calculate_attack_with_equipment(
                     &attacker_stats,
                     &player_stats,
-                    &EquipmentBonuses::default(), // Enemies don't have equipment
-                    &player_equipment,            // Player armor reduces damage
+                    &attacker_bonuses,
+                    &player_defense,
                     rng,
                 );
 
@@ -224,6 +777,15 @@ pub fn execute_ai_actions(
                     continue;
                 }
 
+                // A shield can stop the blow entirely
+                if let Some((shield_name, chance)) = &shield {
+                    if rng.gen_range(0..100) < *chance {
+                        messages.push(format!("You block the {}'s attack with your {}!", attacker_name, shield_name));
+                        blocks += 1;
+                        continue;
+                    }
+                }
+
                 // Apply damage to player
                 if let Some(player) = player_entity {
                     if let Ok(mut health) = world.get::<&mut Health>(player) {
@@ -235,10 +797,136 @@ pub fn execute_ai_actions(
                         };
                         messages.push(msg);
                     }
+                    hits.push(crate::combat::Hit { attacker, target: player, damage: result.final_damage });
+                    messages.extend(elite_on_hit(world, attacker, player, &attacker_name, result.final_damage));
+                }
+            }
+            AIAction::BashDoor { entity, door } => {
+                door_bashes.push((entity, door));
+            }
+            AIAction::Heal { healer, target, amount } => {
+                let healed = world.get::<&mut Health>(target).map(|mut h| h.heal(amount)).unwrap_or(0);
+                if healed > 0 {
+                    let healer_name = world.get::<&Name>(healer).map(|n| n.0.clone()).unwrap_or_default();
+                    let target_name = world.get::<&Name>(target).map(|n| n.0.clone()).unwrap_or_default();
+                    messages.push(format!("The {} mends the {}'s wounds. (+{} HP)", healer_name, target_name, healed));
+                }
+            }
+            AIAction::AttackAlly { attacker, target } | AIAction::AllyAttack { attacker, target } => {
+                if let Some((msg, hit)) = resolve_melee(world, attacker, target, rng) {
+                    messages.push(msg);
+                    hits.extend(hit);
                 }
             }
         }
     }
 
-    messages
+    AIOutcome { messages, blocks, provoked, door_bashes, overwatch_triggered, hits }
+}
+
+/// Resolve a melee attack between two non-player entities, with the blow
+/// if it landed
+fn resolve_melee(
+    world: &mut World,
+    attacker: hecs::Entity,
+    target: hecs::Entity,
+    rng: &mut impl rand::Rng,
+) -> Option<(String, Option<crate::combat::Hit>)> {
+    use crate::combat::{calculate_attack_with_equipment, DerivedStats, Hit};
+    use crate::ecs::Stats;
+
+    // Target may have died earlier this turn
+    if world.get::<&Health>(target).map(|h| h.is_dead()).unwrap_or(true) {
+        return None;
+    }
+
+    let attacker_name = world.get::<&Name>(attacker).map(|n| n.0.clone()).ok()?;
+    let target_name = world.get::<&Name>(target).map(|n| n.0.clone()).ok()?;
+    let mut attacker_stats = world.get::<&Stats>(attacker).map(|s| *s).unwrap_or(Stats::new(8, 8, 8, 8));
+    attacker_stats.strength += rally_bonus(world, attacker);
+    let target_stats = world.get::<&Stats>(target).map(|s| *s).unwrap_or(Stats::new(8, 8, 8, 8));
+
+    let bonuses = |entity, attack: bool| DerivedStats::compute(world, entity)
+        .map(|d| if attack { d.attack_bonuses() } else { d.defense_bonuses() })
+        .unwrap_or_default();
+    let result = calculate_attack_with_equipment(
+        &attacker_stats,
+        &target_stats,
+        &bonuses(attacker, true),
+        &bonuses(target, false),
+        rng,
+    );
+
+    if result.is_dodge || result.is_miss {
+        return Some((format!("{} misses {}.", attacker_name, target_name), None));
+    }
+
+    if let Ok(mut health) = world.get::<&mut Health>(target) {
+        health.take_damage(result.final_damage);
+    }
+    Some((
+        format!("{} hits {} for {} damage.", attacker_name, target_name, result.final_damage),
+        Some(Hit { attacker, target, damage: result.final_damage }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_runs_stages_in_order_and_stops() {
+        type Log = Vec<&'static str>;
+        let schedule: Schedule<Log> = Schedule::new()
+            .with(Stage::Events, "events", |log: &mut Log| { log.push("events"); Flow::Continue })
+            .with(Stage::Ai, "ai", |log: &mut Log| { log.push("ai"); Flow::Continue })
+            .with(Stage::PlayerAction, "cooldowns", |log: &mut Log| { log.push("cooldowns"); Flow::Continue })
+            .with(Stage::Ai, "followers", |log: &mut Log| { log.push("followers"); Flow::Continue })
+            .with(Stage::Cleanup, "reap", |log: &mut Log| {
+                log.push("reap");
+                if log.contains(&"dead") { Flow::Stop } else { Flow::Continue }
+            });
+
+        let mut log = Vec::new();
+        assert_eq!(schedule.run(&mut log), Flow::Continue);
+        assert_eq!(log, ["cooldowns", "ai", "followers", "reap", "events"]);
+        assert_eq!(schedule.order().next(), Some((Stage::PlayerAction, "cooldowns")));
+
+        // A death stops the turn before anything later runs
+        let mut log = vec!["dead"];
+        assert_eq!(schedule.run(&mut log), Flow::Stop);
+        assert_eq!(log, ["dead", "cooldowns", "ai", "followers", "reap"]);
+    }
+
+    #[test]
+    fn test_enemy_ai_is_the_same_on_any_number_of_threads() {
+        use crate::entities::{enemies_for_biome, spawn_enemy};
+        use crate::world::{generation::generate_floor, Biome};
+
+        let data = crate::data::DataManager::default();
+        let map = generate_floor(&mut StdRng::seed_from_u64(3), 3, Biome::SunkenCatacombs);
+        let player = map.start_pos;
+        let defs = enemies_for_biome(Biome::SunkenCatacombs);
+        let mut spots: Vec<Position> = (0..map.tiles.len())
+            .map(|idx| map.idx_to_xy(idx))
+            .filter(|&(x, y)| map.is_walkable(x, y) && Position::new(x, y) != player)
+            .map(|(x, y)| Position::new(x, y))
+            .collect();
+        spots.sort_by_key(|pos| pos.chebyshev_distance(&player));
+
+        let decide = |threads: usize| {
+            let mut world = World::new();
+            for (i, pos) in spots.iter().take(40).enumerate() {
+                spawn_enemy(&mut world, defs[i % defs.len()], *pos);
+            }
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            let actions = pool.install(|| {
+                run_enemy_ai(&mut world, &map, player, data.behavior_trees(), false, &mut StdRng::seed_from_u64(11))
+            });
+            format!("{:?}", actions)
+        };
+        let alone = decide(1);
+        assert!(alone.contains("Move") || alone.contains("Attack"));
+        assert_eq!(alone, decide(4));
+    }
 }
@@ -3,6 +3,7 @@
 //! Bosses are powerful multi-phase enemies that appear at the end of each biome.
 
 use hecs::{World, Entity};
+use serde::{Deserialize, Serialize};
 use crate::ecs::{
     Position, Renderable, Name, Enemy, EnemyArchetype, Stats, Health,
     FactionComponent, Faction, AI, AIState, BlocksMovement, XpReward,
@@ -10,7 +11,7 @@ use crate::ecs::{
 use crate::world::Biome;
 
 /// Boss-specific component tracking phase and abilities
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BossComponent {
     /// Which boss this is
     pub boss_type: BossType,
@@ -20,10 +21,44 @@ pub struct BossComponent {
     pub special_cooldown: u8,
     /// Whether the boss has been defeated
     pub defeated: bool,
+    /// Turns spent fighting the player
+    pub engaged_turns: u32,
+    /// Out of patience: hits harder for the rest of the fight
+    pub enraged: bool,
+}
+
+/// Strength bonus (percent) of an enraged boss
+pub const ENRAGE_STRENGTH_PERCENT: i32 = 50;
+
+impl BossComponent {
+    /// Turns of fighting left before it enrages, None once it has
+    pub fn enrage_in(&self) -> Option<u32> {
+        (!self.enraged).then(|| self.boss_type.enrage_turns().saturating_sub(self.engaged_turns))
+    }
+
+    /// Count a turn of fighting. Returns true on the turn it enrages.
+    pub fn tick_engaged(&mut self) -> bool {
+        self.engaged_turns += 1;
+        if !self.enraged && self.engaged_turns >= self.boss_type.enrage_turns() {
+            self.enraged = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// A boss being fought, as shown on the boss banner
+#[derive(Debug, Clone, Copy)]
+pub struct BossFight {
+    pub boss_type: BossType,
+    pub phase: u8,
+    pub health: Health,
+    /// Turns left before it enrages, None once it has
+    pub enrage_in: Option<u32>,
 }
 
 /// Types of bosses, one per biome
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BossType {
     /// Floor 5 - Sunken Catacombs boss
     CryptLord,
@@ -31,25 +66,48 @@ pub enum BossType {
     BloodMother,
     /// Floor 15 - Hollow Cathedral boss
     FallenSeraph,
-    /// Floor 20 - The Abyss boss
+    /// Floor 20 - Flesh Gardens boss
+    Gardener,
+    /// Floor 25 - The Abyss boss
     VoidHarbinger,
 }
 
 impl BossType {
+    /// Every boss, shallowest first
+    pub const ALL: [BossType; 5] = [
+        BossType::CryptLord,
+        BossType::BloodMother,
+        BossType::FallenSeraph,
+        BossType::Gardener,
+        BossType::VoidHarbinger,
+    ];
+
+    /// Floor this boss guards
+    pub fn floor(&self) -> u32 {
+        match self {
+            BossType::CryptLord => 5,
+            BossType::BloodMother => 10,
+            BossType::FallenSeraph => 15,
+            BossType::Gardener => 20,
+            BossType::VoidHarbinger => 25,
+        }
+    }
+
     /// Get boss for a given floor (only boss floors)
     pub fn for_floor(floor: u32) -> Option<Self> {
         match floor {
             5 => Some(BossType::CryptLord),
             10 => Some(BossType::BloodMother),
             15 => Some(BossType::FallenSeraph),
-            20 => Some(BossType::VoidHarbinger),
+            20 => Some(BossType::Gardener),
+            25 => Some(BossType::VoidHarbinger),
             _ => None,
         }
     }
 
     /// Check if a floor is a boss floor
     pub fn is_boss_floor(floor: u32) -> bool {
-        matches!(floor, 5 | 10 | 15 | 20)
+        matches!(floor, 5 | 10 | 15 | 20 | 25)
     }
 
     /// Get the boss name
@@ -58,6 +116,7 @@ impl BossType {
             BossType::CryptLord => "The Crypt Lord",
             BossType::BloodMother => "The Blood Mother",
             BossType::FallenSeraph => "Fallen Seraph",
+            BossType::Gardener => "The Gardener",
             BossType::VoidHarbinger => "Void Harbinger",
         }
     }
@@ -68,6 +127,7 @@ impl BossType {
             BossType::CryptLord => 'L',
             BossType::BloodMother => 'M',
             BossType::FallenSeraph => 'S',
+            BossType::Gardener => 'Y',
             BossType::VoidHarbinger => 'V',
         }
     }
@@ -78,6 +138,7 @@ impl BossType {
             BossType::CryptLord => (200, 180, 150),
             BossType::BloodMother => (200, 50, 50),
             BossType::FallenSeraph => (220, 200, 255),
+            BossType::Gardener => (210, 90, 110),
             BossType::VoidHarbinger => (100, 50, 200),
         }
     }
@@ -103,6 +164,12 @@ impl BossType {
                 intelligence: 20,
                 vitality: 18,
             },
+            BossType::Gardener => Stats {
+                strength: 18,
+                dexterity: 10,
+                intelligence: 20,
+                vitality: 24,
+            },
             BossType::VoidHarbinger => Stats {
                 strength: 22,
                 dexterity: 14,
@@ -118,6 +185,7 @@ impl BossType {
             BossType::CryptLord => 200,
             BossType::BloodMother => 250,
             BossType::FallenSeraph => 300,
+            BossType::Gardener => 350,
             BossType::VoidHarbinger => 400,
         }
     }
@@ -128,6 +196,7 @@ impl BossType {
             BossType::CryptLord => 200,
             BossType::BloodMother => 400,
             BossType::FallenSeraph => 600,
+            BossType::Gardener => 800,
             BossType::VoidHarbinger => 1000,
         }
     }
@@ -144,10 +213,66 @@ impl BossType {
             BossType::CryptLord => 4,
             BossType::BloodMother => 3,
             BossType::FallenSeraph => 3,
+            BossType::Gardener => 6,
             BossType::VoidHarbinger => 2,
         }
     }
 
+    /// Turns of fighting before this boss loses patience and enrages
+    pub fn enrage_turns(&self) -> u32 {
+        match self {
+            BossType::CryptLord => 60,
+            BossType::BloodMother => 55,
+            BossType::FallenSeraph => 50,
+            BossType::Gardener => 50,
+            BossType::VoidHarbinger => 45,
+        }
+    }
+
+    /// Message shown when the boss enrages
+    pub fn enrage_message(&self) -> &'static str {
+        match self {
+            BossType::CryptLord => "The Crypt Lord's patience is spent. His blows come down like falling masonry!",
+            BossType::BloodMother => "The Blood Mother shrieks, her veins bursting with borrowed fury!",
+            BossType::FallenSeraph => "The Seraph's broken halo blazes white. It will end this now!",
+            BossType::Gardener => "The Gardener's roots tear free of the floor. It means to prune you now!",
+            BossType::VoidHarbinger => "The Harbinger tires of you. The dark behind it surges forward!",
+        }
+    }
+
+    /// Whether this boss drags the player toward it as its special
+    pub fn yanks_player(&self) -> bool {
+        matches!(self, BossType::BloodMother | BossType::VoidHarbinger)
+    }
+
+    /// Whether this boss grows heart nodes as its special
+    pub fn grows_hearts(&self) -> bool {
+        matches!(self, BossType::Gardener)
+    }
+
+    /// Whether the boss's floor starts to collapse once it falls
+    pub fn collapses_floor(&self) -> bool {
+        matches!(self, BossType::FallenSeraph | BossType::Gardener)
+    }
+
+    /// What the player sees as the floor starts to give way
+    pub fn collapse_message(&self) -> &'static str {
+        match self {
+            BossType::FallenSeraph => "The Seraph's fall cracks the cathedral's foundations. The vaults above begin to give way!",
+            BossType::Gardener => "With the Gardener dead, the garden rots from beneath. The ground begins to sag and split!",
+            _ => "The ground shudders and begins to give way!",
+        }
+    }
+
+    /// Message shown when the boss drags the player in
+    pub fn yank_message(&self) -> &'static str {
+        match self {
+            BossType::BloodMother => "Blood tendrils coil around you and drag you toward the Blood Mother!",
+            BossType::VoidHarbinger => "The void folds and you are wrenched toward the Harbinger!",
+            _ => "An unseen force drags you forward!",
+        }
+    }
+
     /// Get description for current phase
     pub fn phase_description(&self, phase: u8) -> &'static str {
         match (self, phase) {
@@ -163,6 +288,10 @@ impl BossType {
             (BossType::FallenSeraph, 2) => "Corrupted light radiates from the Seraph's broken wings!",
             (BossType::FallenSeraph, 3) => "In divine fury, the Seraph unleashes forbidden powers!",
 
+            (BossType::Gardener, 1) => "The garden stirs. Something vast and patient tends it.",
+            (BossType::Gardener, 2) => "The Gardener sows its hearts faster. The walls beat in time!",
+            (BossType::Gardener, 3) => "Bleeding sap, the Gardener feeds the last of itself to the garden!",
+
             (BossType::VoidHarbinger, 1) => "Reality tears as the Harbinger manifests.",
             (BossType::VoidHarbinger, 2) => "The void spreads. Your vision warps and twists!",
             (BossType::VoidHarbinger, 3) => "The Harbinger prepares to unmake everything!",
@@ -196,6 +325,8 @@ pub fn spawn_boss(world: &mut World, boss_type: BossType, pos: Position) -> Enti
             phase: 1,
             special_cooldown: boss_type.special_cooldown(),
             defeated: false,
+            engaged_turns: 0,
+            enraged: false,
         },
     ))
 }
@@ -227,6 +358,25 @@ pub fn boss_for_biome(biome: Biome) -> BossType {
         Biome::SunkenCatacombs => BossType::CryptLord,
         Biome::BleedingCrypts => BossType::BloodMother,
         Biome::HollowCathedral => BossType::FallenSeraph,
+        Biome::FleshGardens => BossType::Gardener,
         Biome::TheAbyss => BossType::VoidHarbinger,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boss_enrages_once() {
+        let mut world = World::new();
+        let entity = spawn_boss(&mut world, BossType::VoidHarbinger, Position::new(0, 0));
+        let mut boss = world.get::<&mut BossComponent>(entity).unwrap();
+
+        let turns = BossType::VoidHarbinger.enrage_turns();
+        assert_eq!(boss.enrage_in(), Some(turns));
+        let enraged_on: Vec<u32> = (1..=turns + 5).filter(|_| boss.tick_engaged()).collect();
+        assert_eq!(enraged_on, vec![turns]);
+        assert_eq!(boss.enrage_in(), None);
+    }
+}
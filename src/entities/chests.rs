@@ -1,13 +1,121 @@
 //! Chest entity creation
 //!
-//! Chests spawn on floors and contain loot based on their rarity.
+//! Chests spawn on floors and contain loot based on their rarity. Deeper
+//! down, not every chest is what it seems: some are trapped, some locked,
+//! some ask a riddle, and some are mimics waiting for a hand to reach in.
+//! The harder a chest is to get into, the more it holds.
 
 use hecs::{World, Entity};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::ecs::{Position, Renderable, Chest, ChestRarity};
+use crate::ecs::{EnemyArchetype, Position, Renderable, Chest, ChestKind, ChestRarity, ChestTrap, Stats};
+use crate::data::GoldDrop;
+use crate::game::Interactable;
 use crate::items::{Item, loot};
+use crate::progression::FloorScaling;
 use crate::world::Biome;
+use super::enemies::{spawn_enemy_scaled, EnemyDef};
+
+pub const MIMIC: EnemyDef = EnemyDef {
+    name: "Mimic",
+    glyph: 'M',
+    fg: (200, 150, 90),
+    archetype: EnemyArchetype::Tank,
+    stats: Stats { strength: 14, dexterity: 6, intelligence: 4, vitality: 12 },
+    hp: 60,
+    xp_value: 60,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+/// A mimic still holding the loot of the chest it pretended to be
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MimicHoard {
+    pub rarity: ChestRarity,
+}
+
+/// What happens when the player reaches for a chest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChestApproach {
+    /// Nothing stands in the way any more
+    Open,
+    /// Locked, or sealed for good
+    Shut,
+    /// It asks a riddle first (index into `RIDDLES`)
+    Riddle(usize),
+    /// It was a mimic, and now it is awake
+    Ambush,
+}
+
+/// A riddle asked by a puzzle chest
+#[derive(Debug, Clone, Copy)]
+pub struct Riddle {
+    pub question: &'static str,
+    pub answers: [&'static str; 3],
+    /// Index of the right answer
+    pub correct: usize,
+}
+
+pub const RIDDLES: &[Riddle] = &[
+    Riddle {
+        question: "The more you take, the more you leave behind. What am I?",
+        answers: ["Gold", "Footsteps", "Breath"],
+        correct: 1,
+    },
+    Riddle {
+        question: "I have keys but open no locks, space but no room. What am I?",
+        answers: ["A keyboard", "A prison", "A tomb"],
+        correct: 0,
+    },
+    Riddle {
+        question: "Feed me and I live, give me a drink and I die. What am I?",
+        answers: ["A plant", "A vampire", "Fire"],
+        correct: 2,
+    },
+    Riddle {
+        question: "What has a neck but no head, and wears a cork for a crown?",
+        answers: ["A bottle", "A ghost", "A sword"],
+        correct: 0,
+    },
+    Riddle {
+        question: "The one who makes me sells me; the one who buys me never uses me. What am I?",
+        answers: ["A shield", "A coffin", "A map"],
+        correct: 1,
+    },
+    Riddle {
+        question: "I speak without a mouth and answer without being asked twice. What am I?",
+        answers: ["An echo", "A shade", "A bell"],
+        correct: 0,
+    },
+];
+
+/// Chance to spot a trap or a mimic when standing next to it
+pub fn spot_chance(intelligence: i32, dexterity: i32) -> f64 {
+    (0.3 + (intelligence - 10) as f64 * 0.03 + (dexterity - 10) as f64 * 0.02).clamp(0.1, 0.9)
+}
+
+/// Chance to disarm a spotted trap
+pub fn disarm_chance(dexterity: i32) -> f64 {
+    (0.5 + (dexterity - 10) as f64 * 0.05).clamp(0.2, 0.95)
+}
+
+/// Roll what kind of chest spawns; the first floor only has plain ones
+pub fn roll_chest_kind(floor: u32, rng: &mut impl Rng) -> ChestKind {
+    if floor < 2 {
+        return ChestKind::Plain;
+    }
+    match rng.gen_range(0..100) {
+        0..=3 if floor >= 4 => ChestKind::Puzzle(rng.gen_range(0..RIDDLES.len())),
+        4..=11 if floor >= 3 => ChestKind::Mimic,
+        12..=26 => {
+            let trap = if rng.gen_bool(0.5) { ChestTrap::PoisonNeedle } else { ChestTrap::FireBurst };
+            ChestKind::Trapped(trap)
+        }
+        27..=41 => ChestKind::Locked,
+        _ => ChestKind::Plain,
+    }
+}
 
 /// Roll a chest rarity based on floor depth
 pub fn roll_chest_rarity(floor: u32, rng: &mut impl Rng) -> ChestRarity {
@@ -64,12 +172,54 @@ pub fn roll_chest_rarity(floor: u32, rng: &mut impl Rng) -> ChestRarity {
 }
 
 /// Spawn a chest at a position
-pub fn spawn_chest(world: &mut World, pos: Position, rarity: ChestRarity) -> Entity {
-    world.spawn((
+pub fn spawn_chest(world: &mut World, pos: Position, rarity: ChestRarity, kind: ChestKind) -> Entity {
+    place_chest(world, pos, Chest::new(rarity, kind))
+}
+
+/// Set a chest down as it is, opened or not (used when loading a save)
+pub fn place_chest(world: &mut World, pos: Position, chest: Chest) -> Entity {
+    let opened = chest.opened;
+    let entity = world.spawn((
         pos,
-        Renderable::new(rarity.glyph(), rarity.color()).with_order(70),
-        Chest { rarity, opened: false },
-    ))
+        Renderable::new(chest.glyph(), chest.rarity.color()).with_order(70),
+        chest,
+        Interactable::Chest,
+    ));
+    if opened {
+        mark_chest_opened(world, entity);
+    }
+    entity
+}
+
+/// Spawn the hoard hidden in a secret room or puzzle vault: always a plain
+/// chest, and never worse than rare
+pub fn spawn_secret_chest(world: &mut World, floor: u32, pos: Position, rng: &mut impl Rng) -> Entity {
+    let rarity = match roll_chest_rarity(floor, rng) {
+        ChestRarity::Common => ChestRarity::Rare,
+        rarity => rarity,
+    };
+    spawn_chest(world, pos, rarity, ChestKind::Plain)
+}
+
+/// Spawn the mimic a chest turns out to be, already awake
+pub fn spawn_mimic(world: &mut World, pos: Position, rarity: ChestRarity, scaling: &FloorScaling) -> Entity {
+    let mimic = spawn_enemy_scaled(world, &MIMIC, pos, scaling);
+    let _ = world.insert_one(mimic, MimicHoard { rarity });
+    if let Ok(mut ai) = world.get::<&mut crate::ecs::AI>(mimic) {
+        ai.state = crate::ecs::AIState::Chase;
+    }
+    mimic
+}
+
+/// How many chests a floor gets, fewest to most
+pub fn chest_count_range(floor: u32) -> (usize, usize) {
+    match floor {
+        1 => (1, 2),      // Tutorial floor - fewer chests
+        2..=5 => (2, 4),
+        6..=10 => (3, 5),
+        11..=15 => (3, 6),
+        _ => (4, 7),
+    }
 }
 
 /// Spawn chests for a floor
@@ -82,15 +232,7 @@ pub fn spawn_chests_for_floor(
 ) -> Vec<Entity> {
     use rand::seq::SliceRandom;
 
-    // Determine chest count based on floor
-    let (min_chests, max_chests) = match floor {
-        1 => (1, 2),      // Tutorial floor - fewer chests
-        2..=5 => (2, 4),
-        6..=10 => (3, 5),
-        11..=15 => (3, 6),
-        _ => (4, 7),
-    };
-
+    let (min_chests, max_chests) = chest_count_range(floor);
     let count = rng.gen_range(min_chests..=max_chests);
     let count = count.min(valid_positions.len());
 
@@ -103,18 +245,25 @@ pub fn spawn_chests_for_floor(
     for i in 0..count {
         let pos = positions[i];
         let rarity = roll_chest_rarity(floor, rng);
-        let entity = spawn_chest(world, pos, rarity);
+        let kind = roll_chest_kind(floor, rng);
+        let entity = spawn_chest(world, pos, rarity, kind);
         spawned.push(entity);
     }
 
     spawned
 }
 
-/// Generate loot from a chest
-pub fn generate_chest_loot(chest_rarity: ChestRarity, floor: u32, rng: &mut impl Rng) -> (Vec<Item>, u32) {
+/// Generate loot from a chest, with item count and gold scaled by a multiplier
+pub fn generate_chest_loot(
+    chest_rarity: ChestRarity,
+    floor: u32,
+    multiplier: f32,
+    drops: GoldDrop,
+    rng: &mut impl Rng,
+) -> (Vec<Item>, u32) {
     let min_item_rarity = chest_rarity.min_item_rarity();
     let (min_items, max_items) = chest_rarity.item_count();
-    let item_count = rng.gen_range(min_items..=max_items);
+    let item_count = (rng.gen_range(min_items..=max_items) as f32 * multiplier).round() as usize;
 
     let mut items = Vec::with_capacity(item_count);
 
@@ -133,8 +282,8 @@ pub fn generate_chest_loot(chest_rarity: ChestRarity, floor: u32, rng: &mut impl
     }
 
     // Gold based on floor and chest rarity
-    let base_gold = loot::generate_gold_drop(floor, rng);
-    let gold = (base_gold as f32 * chest_rarity.gold_multiplier()) as u32;
+    let base_gold = drops.roll(floor, rng);
+    let gold = (base_gold as f32 * chest_rarity.gold_multiplier() * multiplier) as u32;
 
     (items, gold)
 }
@@ -149,6 +298,14 @@ pub fn get_chest_at(world: &World, pos: Position) -> Option<Entity> {
     None
 }
 
+/// Redraw a chest after what it is has come to light
+pub fn refresh_chest_glyph(world: &mut World, entity: Entity) {
+    let Ok(glyph) = world.get::<&Chest>(entity).map(|c| c.glyph()) else { return };
+    if let Ok(mut renderable) = world.get::<&mut Renderable>(entity) {
+        renderable.glyph = glyph;
+    }
+}
+
 /// Mark a chest as opened
 pub fn mark_chest_opened(world: &mut World, entity: Entity) {
     if let Ok(mut chest) = world.get::<&mut Chest>(entity) {
@@ -160,3 +317,48 @@ pub fn mark_chest_opened(world: &mut World, entity: Entity) {
         renderable.fg = (100, 100, 100); // Gray out
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_chest_kinds() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+
+        // Nothing tricky on the first floor, everything turns up deeper
+        assert!((0..100).all(|_| roll_chest_kind(1, &mut rng) == ChestKind::Plain));
+        let deep: Vec<ChestKind> = (0..500).map(|_| roll_chest_kind(10, &mut rng)).collect();
+        assert!(deep.contains(&ChestKind::Mimic) && deep.contains(&ChestKind::Locked));
+        assert!(deep.iter().any(|k| matches!(k, ChestKind::Trapped(_))));
+        assert!(deep.iter().all(|k| !matches!(k, ChestKind::Puzzle(i) if *i >= RIDDLES.len())));
+
+        // Tricky chests hide their nature until spotted, except to the eye
+        let trapped = Chest::new(ChestRarity::Common, ChestKind::Trapped(ChestTrap::PoisonNeedle));
+        assert!(trapped.secured);
+        assert_eq!(trapped.glyph(), ChestRarity::Common.glyph());
+        assert_ne!(Chest::new(ChestRarity::Common, ChestKind::Locked).glyph(), ChestRarity::Common.glyph());
+    }
+
+    #[test]
+    fn test_chests_come_back_from_a_save_as_left() {
+        let mut world = World::new();
+        let pos = Position::new(4, 7);
+        let entity = spawn_chest(&mut world, pos, ChestRarity::Epic, ChestKind::Trapped(ChestTrap::PoisonNeedle));
+        if let Ok(mut chest) = world.get::<&mut Chest>(entity) {
+            chest.searched = true;
+            chest.revealed = true;
+        }
+        mark_chest_opened(&mut world, entity);
+
+        let saved = serde_json::to_string(&*world.get::<&Chest>(entity).unwrap()).unwrap();
+        let mut loaded = World::new();
+        let restored = place_chest(&mut loaded, pos, serde_json::from_str(&saved).unwrap());
+        let chest = loaded.get::<&Chest>(restored).unwrap();
+        assert!(chest.opened && chest.searched && chest.revealed);
+        assert_eq!(chest.kind, ChestKind::Trapped(ChestTrap::PoisonNeedle));
+        assert_eq!(loaded.get::<&Renderable>(restored).unwrap().glyph, '○');
+        assert_eq!(get_chest_at(&loaded, pos), None);
+    }
+}
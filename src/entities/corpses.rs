@@ -0,0 +1,170 @@
+//! Corpses
+//!
+//! Slain enemies leave their bodies behind for a while. A corpse can be
+//! searched once for scraps, necromancers raise the ones near them to fight
+//! again, and the player's occult skills feed on them. A body blown apart by
+//! a heavy critical hit is too far gone for any of that but searching. Every
+//! corpse rots away after enough turns.
+
+use hecs::{Entity, World};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::ecs::{EnemyArchetype, Name, Position, Renderable, Stats};
+use crate::data::GoldDrop;
+use crate::items::{loot, Item};
+use crate::progression::FloorScaling;
+use super::enemies::{spawn_enemy_scaled, EnemyDef};
+
+/// Turns before a corpse rots away
+pub const CORPSE_DECAY_TURNS: u32 = 100;
+/// Share of its max HP (percent) a critical killing blow must deal to gib an enemy
+pub const GIB_DAMAGE_PERCENT: i32 = 60;
+/// How far a necromancer reaches for a corpse to raise
+pub const RAISE_RANGE: i32 = 5;
+/// Turns between a necromancer's raisings
+pub const RAISE_COOLDOWN: u32 = 8;
+/// How far the player's occult skills reach for a corpse
+pub const CORPSE_SKILL_RANGE: i32 = 4;
+
+/// Body of a slain enemy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Corpse {
+    /// Name of whoever it was
+    pub name: String,
+    pub turns_left: u32,
+    /// Blown apart: can't be raised or consumed
+    pub gibbed: bool,
+    pub searched: bool,
+}
+
+impl Corpse {
+    /// Whether there's enough left to raise or consume
+    pub fn intact(&self) -> bool {
+        !self.gibbed
+    }
+}
+
+/// Raises corpses near it as undead
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Necromancer {
+    /// Turns until it can raise again
+    pub cooldown: u32,
+}
+
+/// Raised from a corpse; it leaves no body of its own
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Risen;
+
+pub const RISEN_DEAD: EnemyDef = EnemyDef {
+    name: "Risen Dead",
+    glyph: 'z',
+    fg: (150, 170, 120),
+    archetype: EnemyArchetype::Melee,
+    stats: Stats { strength: 9, dexterity: 5, intelligence: 1, vitality: 6 },
+    hp: 22,
+    xp_value: 8,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+/// Whether a killing blow is heavy enough to blow the body apart
+pub fn is_gib(is_crit: bool, damage: i32, max_hp: i32) -> bool {
+    is_crit && damage * 100 >= max_hp * GIB_DAMAGE_PERCENT
+}
+
+/// Lay a corpse down where something died
+pub fn spawn_corpse(world: &mut World, pos: Position, name: &str, gibbed: bool) -> Entity {
+    place_corpse(world, pos, Corpse { name: name.to_string(), turns_left: CORPSE_DECAY_TURNS, gibbed, searched: false })
+}
+
+/// Lay a corpse down as it was, half-rotted or searched (used when loading a save)
+pub fn place_corpse(world: &mut World, pos: Position, corpse: Corpse) -> Entity {
+    let (glyph, fg) = if corpse.gibbed { ('~', (150, 30, 30)) } else { ('%', (140, 70, 60)) };
+    world.spawn((pos, Renderable::new(glyph, fg).with_order(5), corpse))
+}
+
+/// Age every corpse by a turn, removing the ones that have rotted away.
+/// Returns where they lay.
+pub fn decay_corpses(world: &mut World) -> Vec<Position> {
+    let mut rotted = Vec::new();
+    for (entity, (pos, corpse)) in world.query_mut::<(&Position, &mut Corpse)>() {
+        corpse.turns_left = corpse.turns_left.saturating_sub(1);
+        if corpse.turns_left == 0 {
+            rotted.push((entity, *pos));
+        }
+    }
+    for (entity, _) in &rotted {
+        let _ = world.despawn(*entity);
+    }
+    rotted.into_iter().map(|(_, pos)| pos).collect()
+}
+
+/// Nearest intact corpse within range of a position
+pub fn nearest_corpse(world: &World, pos: Position, range: i32) -> Option<Entity> {
+    world.query::<(&Position, &Corpse)>()
+        .iter()
+        .filter(|(_, (p, corpse))| corpse.intact() && p.chebyshev_distance(&pos) <= range)
+        .min_by_key(|(_, (p, _))| p.chebyshev_distance(&pos))
+        .map(|(e, _)| e)
+}
+
+/// Raise a corpse as an undead hunting the player. Returns the risen enemy,
+/// or None if the corpse is too far gone.
+pub fn raise_corpse(world: &mut World, corpse: Entity, scaling: &FloorScaling) -> Option<Entity> {
+    let (pos, name) = {
+        let mut query = world.query_one::<(&Position, &Corpse)>(corpse).ok()?;
+        let (pos, body) = query.get()?;
+        if !body.intact() {
+            return None;
+        }
+        (*pos, body.name.clone())
+    };
+    let _ = world.despawn(corpse);
+
+    let risen = spawn_enemy_scaled(world, &RISEN_DEAD, pos, scaling);
+    let _ = world.insert(risen, (Risen, Name::new(format!("Risen {}", name))));
+    if let Ok(mut ai) = world.get::<&mut crate::ecs::AI>(risen) {
+        ai.state = crate::ecs::AIState::Chase;
+    }
+    Some(risen)
+}
+
+/// Scraps found searching a corpse: maybe a consumable, maybe some coin
+pub fn search_corpse_loot(floor: u32, drops: GoldDrop, rng: &mut impl Rng) -> (Option<Item>, u32) {
+    let item = rng.gen_bool(0.15).then(|| loot::generate_consumable(rng));
+    let gold = if rng.gen_bool(0.4) { drops.roll(floor, rng) / 2 } else { 0 };
+    (item, gold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progression::Difficulty;
+
+    #[test]
+    fn test_corpses_rise_and_rot() {
+        let mut world = World::new();
+        let scaling = FloorScaling::new(5, Difficulty::Normal);
+        let here = Position::new(5, 5);
+
+        // Only a heavy critical blow gibs
+        assert!(is_gib(true, 30, 40));
+        assert!(!is_gib(false, 40, 40) && !is_gib(true, 10, 40));
+
+        // Gibbed remains can't be raised, whole bodies can
+        let gibbed = spawn_corpse(&mut world, here, "Skeleton", true);
+        let whole = spawn_corpse(&mut world, Position::new(7, 5), "Skeleton", false);
+        assert_eq!(nearest_corpse(&world, here, RAISE_RANGE), Some(whole));
+        assert!(raise_corpse(&mut world, gibbed, &scaling).is_none());
+        let risen = raise_corpse(&mut world, whole, &scaling).unwrap();
+        assert_eq!(world.get::<&Name>(risen).unwrap().0, "Risen Skeleton");
+        assert!(world.get::<&Risen>(risen).is_ok());
+
+        // The rest rots away in time
+        for _ in 0..CORPSE_DECAY_TURNS {
+            decay_corpses(&mut world);
+        }
+        assert!(!world.contains(gibbed));
+    }
+}
@@ -7,8 +7,10 @@ use hecs::{World, Entity};
 use crate::ecs::{
     Position, Renderable, Name, Enemy, EnemyArchetype, Stats, Health,
     FactionComponent, Faction, AI, AIState, BlocksMovement, XpReward,
-    StatusEffects,
+    StatusEffects, HazardImmune,
 };
+use crate::combat::Resistances;
+use crate::data::EnemyTemplates;
 use crate::world::Biome;
 use crate::progression::FloorScaling;
 
@@ -21,6 +23,10 @@ pub struct EnemyDef {
     pub stats: Stats,
     pub hp: i32,
     pub xp_value: u32,
+    /// Floats over lava, pits and corrupted blood unharmed
+    pub hazard_immune: bool,
+    /// Raises nearby corpses to fight again
+    pub raises_dead: bool,
 }
 
 // =============================================================================
@@ -35,6 +41,8 @@ pub const SKELETON: EnemyDef = EnemyDef {
     stats: Stats { strength: 8, dexterity: 6, intelligence: 2, vitality: 5 },
     hp: 25,
     xp_value: 15,
+    hazard_immune: false,
+    raises_dead: false,
 };
 
 pub const ZOMBIE: EnemyDef = EnemyDef {
@@ -45,6 +53,8 @@ pub const ZOMBIE: EnemyDef = EnemyDef {
     stats: Stats { strength: 10, dexterity: 3, intelligence: 1, vitality: 8 },
     hp: 40,
     xp_value: 20,
+    hazard_immune: false,
+    raises_dead: false,
 };
 
 pub const GHOST: EnemyDef = EnemyDef {
@@ -55,6 +65,8 @@ pub const GHOST: EnemyDef = EnemyDef {
     stats: Stats { strength: 4, dexterity: 8, intelligence: 12, vitality: 4 },
     hp: 20,
     xp_value: 25,
+    hazard_immune: true,
+    raises_dead: false,
 };
 
 pub const RAT_SWARM: EnemyDef = EnemyDef {
@@ -65,6 +77,8 @@ pub const RAT_SWARM: EnemyDef = EnemyDef {
     stats: Stats { strength: 4, dexterity: 12, intelligence: 1, vitality: 3 },
     hp: 12,
     xp_value: 8,
+    hazard_immune: false,
+    raises_dead: false,
 };
 
 // =============================================================================
@@ -79,6 +93,8 @@ pub const BLOOD_CULTIST: EnemyDef = EnemyDef {
     stats: Stats { strength: 6, dexterity: 10, intelligence: 14, vitality: 8 },
     hp: 35,
     xp_value: 35,
+    hazard_immune: false,
+    raises_dead: false,
 };
 
 pub const CRIMSON_HOUND: EnemyDef = EnemyDef {
@@ -89,6 +105,8 @@ pub const CRIMSON_HOUND: EnemyDef = EnemyDef {
     stats: Stats { strength: 12, dexterity: 14, intelligence: 3, vitality: 7 },
     hp: 30,
     xp_value: 30,
+    hazard_immune: false,
+    raises_dead: false,
 };
 
 pub const FLESH_GOLEM: EnemyDef = EnemyDef {
@@ -99,6 +117,8 @@ pub const FLESH_GOLEM: EnemyDef = EnemyDef {
     stats: Stats { strength: 16, dexterity: 4, intelligence: 2, vitality: 18 },
     hp: 80,
     xp_value: 50,
+    hazard_immune: false,
+    raises_dead: false,
 };
 
 // =============================================================================
@@ -113,6 +133,8 @@ pub const FALLEN_KNIGHT: EnemyDef = EnemyDef {
     stats: Stats { strength: 14, dexterity: 10, intelligence: 6, vitality: 14 },
     hp: 70,
     xp_value: 60,
+    hazard_immune: false,
+    raises_dead: false,
 };
 
 pub const CORRUPTED_ANGEL: EnemyDef = EnemyDef {
@@ -123,6 +145,8 @@ pub const CORRUPTED_ANGEL: EnemyDef = EnemyDef {
     stats: Stats { strength: 8, dexterity: 12, intelligence: 18, vitality: 10 },
     hp: 55,
     xp_value: 70,
+    hazard_immune: true,
+    raises_dead: false,
 };
 
 pub const GARGOYLE: EnemyDef = EnemyDef {
@@ -133,10 +157,64 @@ pub const GARGOYLE: EnemyDef = EnemyDef {
     stats: Stats { strength: 10, dexterity: 8, intelligence: 4, vitality: 12 },
     hp: 50,
     xp_value: 45,
+    hazard_immune: true,
+    raises_dead: false,
 };
 
 // =============================================================================
-// The Abyss Enemies (Floors 16-20)
+// Flesh Gardens Enemies (Floors 16-20)
+// =============================================================================
+
+pub const GRAFTLING: EnemyDef = EnemyDef {
+    name: "Graftling",
+    glyph: 'w',
+    fg: (200, 110, 120),
+    archetype: EnemyArchetype::Swarm,
+    stats: Stats { strength: 9, dexterity: 14, intelligence: 3, vitality: 6 },
+    hp: 24,
+    xp_value: 35,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const THORNED_HUSK: EnemyDef = EnemyDef {
+    name: "Thorned Husk",
+    glyph: 'H',
+    fg: (150, 90, 70),
+    archetype: EnemyArchetype::Melee,
+    stats: Stats { strength: 15, dexterity: 8, intelligence: 2, vitality: 12 },
+    hp: 55,
+    xp_value: 45,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const SPORE_MOTHER: EnemyDef = EnemyDef {
+    name: "Spore Mother",
+    glyph: 'm',
+    fg: (190, 170, 90),
+    archetype: EnemyArchetype::Caster,
+    stats: Stats { strength: 6, dexterity: 8, intelligence: 17, vitality: 10 },
+    hp: 40,
+    xp_value: 50,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const BLOATED_TENDER: EnemyDef = EnemyDef {
+    name: "Bloated Tender",
+    glyph: 'T',
+    fg: (210, 120, 140),
+    archetype: EnemyArchetype::Tank,
+    stats: Stats { strength: 17, dexterity: 5, intelligence: 4, vitality: 20 },
+    hp: 110,
+    xp_value: 90,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+// =============================================================================
+// The Abyss Enemies (Floors 21-25)
 // =============================================================================
 
 pub const VOID_SPAWN: EnemyDef = EnemyDef {
@@ -147,6 +225,8 @@ pub const VOID_SPAWN: EnemyDef = EnemyDef {
     stats: Stats { strength: 8, dexterity: 16, intelligence: 8, vitality: 6 },
     hp: 25,
     xp_value: 40,
+    hazard_immune: true,
+    raises_dead: false,
 };
 
 pub const ELDRITCH_HORROR: EnemyDef = EnemyDef {
@@ -157,6 +237,20 @@ pub const ELDRITCH_HORROR: EnemyDef = EnemyDef {
     stats: Stats { strength: 18, dexterity: 8, intelligence: 20, vitality: 16 },
     hp: 100,
     xp_value: 100,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const FIRE_ELEMENTAL: EnemyDef = EnemyDef {
+    name: "Fire Elemental",
+    glyph: 'f',
+    fg: (255, 120, 40),
+    archetype: EnemyArchetype::Melee,
+    stats: Stats { strength: 12, dexterity: 10, intelligence: 10, vitality: 8 },
+    hp: 40,
+    xp_value: 45,
+    hazard_immune: true,
+    raises_dead: false,
 };
 
 pub const TENTACLE: EnemyDef = EnemyDef {
@@ -167,15 +261,97 @@ pub const TENTACLE: EnemyDef = EnemyDef {
     stats: Stats { strength: 14, dexterity: 6, intelligence: 4, vitality: 10 },
     hp: 45,
     xp_value: 35,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const NECROMANCER: EnemyDef = EnemyDef {
+    name: "Necromancer",
+    glyph: 'N',
+    fg: (120, 200, 140),
+    archetype: EnemyArchetype::Caster,
+    stats: Stats { strength: 5, dexterity: 7, intelligence: 14, vitality: 7 },
+    hp: 30,
+    xp_value: 35,
+    hazard_immune: false,
+    raises_dead: true,
 };
 
+// =============================================================================
+// Pack Enemies (only spawn as part of a group)
+// =============================================================================
+
+pub const BONE_SHEPHERD: EnemyDef = EnemyDef {
+    name: "Bone Shepherd",
+    glyph: 'S',
+    fg: (220, 210, 170),
+    archetype: EnemyArchetype::Caster,
+    stats: Stats { strength: 6, dexterity: 6, intelligence: 12, vitality: 8 },
+    hp: 35,
+    xp_value: 40,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const BONE_HOUND: EnemyDef = EnemyDef {
+    name: "Bone Hound",
+    glyph: 'd',
+    fg: (210, 200, 170),
+    archetype: EnemyArchetype::Melee,
+    stats: Stats { strength: 7, dexterity: 12, intelligence: 1, vitality: 4 },
+    hp: 16,
+    xp_value: 10,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const CULT_ZEALOT: EnemyDef = EnemyDef {
+    name: "Cult Zealot",
+    glyph: 'Z',
+    fg: (220, 40, 40),
+    archetype: EnemyArchetype::Elite,
+    stats: Stats { strength: 13, dexterity: 9, intelligence: 10, vitality: 12 },
+    hp: 60,
+    xp_value: 55,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const CULT_ACOLYTE: EnemyDef = EnemyDef {
+    name: "Cult Acolyte",
+    glyph: 'a',
+    fg: (170, 70, 70),
+    archetype: EnemyArchetype::Caster,
+    stats: Stats { strength: 5, dexterity: 8, intelligence: 11, vitality: 6 },
+    hp: 22,
+    xp_value: 18,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+/// Every enemy definition, for lookup by name from data
+const ALL_ENEMIES: &[&EnemyDef] = &[
+    &SKELETON, &ZOMBIE, &GHOST, &RAT_SWARM,
+    &BLOOD_CULTIST, &CRIMSON_HOUND, &FLESH_GOLEM,
+    &FALLEN_KNIGHT, &CORRUPTED_ANGEL, &GARGOYLE,
+    &GRAFTLING, &THORNED_HUSK, &SPORE_MOTHER, &BLOATED_TENDER, &super::hearts::HEART_NODE,
+    &VOID_SPAWN, &ELDRITCH_HORROR, &TENTACLE, &FIRE_ELEMENTAL,
+    &NECROMANCER,
+    &BONE_SHEPHERD, &BONE_HOUND, &CULT_ZEALOT, &CULT_ACOLYTE,
+];
+
+/// Find an enemy definition by its name
+pub fn enemy_def(name: &str) -> Option<&'static EnemyDef> {
+    ALL_ENEMIES.iter().copied().find(|def| def.name == name)
+}
+
 // =============================================================================
 // Spawning Functions
 // =============================================================================
 
 /// Spawn an enemy from a definition at a given position (no scaling)
 pub fn spawn_enemy(world: &mut World, def: &EnemyDef, pos: Position) -> Entity {
-    world.spawn((
+    let entity = world.spawn((
         Name::new(def.name),
         pos,
         Renderable::new(def.glyph, def.fg).with_order(50),
@@ -191,7 +367,14 @@ pub fn spawn_enemy(world: &mut World, def: &EnemyDef, pos: Position) -> Entity {
         BlocksMovement,
         XpReward(def.xp_value),
         StatusEffects::default(),
-    ))
+    ));
+    if def.hazard_immune {
+        let _ = world.insert_one(entity, HazardImmune);
+    }
+    if def.raises_dead {
+        let _ = world.insert_one(entity, super::corpses::Necromancer::default());
+    }
+    entity
 }
 
 /// Spawn an enemy with floor-based difficulty scaling applied
@@ -213,7 +396,7 @@ pub fn spawn_enemy_scaled(
     let scaled_hp = scaling.scale_enemy_hp(def.hp);
     let scaled_xp = scaling.scale_xp(def.xp_value);
 
-    world.spawn((
+    let entity = world.spawn((
         Name::new(def.name),
         pos,
         Renderable::new(def.glyph, def.fg).with_order(50),
@@ -229,19 +412,61 @@ pub fn spawn_enemy_scaled(
         BlocksMovement,
         XpReward(scaled_xp),
         StatusEffects::default(),
-    ))
+    ));
+    if def.hazard_immune {
+        let _ = world.insert_one(entity, HazardImmune);
+    }
+    if def.raises_dead {
+        let _ = world.insert_one(entity, super::corpses::Necromancer::default());
+    }
+    entity
+}
+
+/// Give enemies that have none yet the resistances their template declares
+/// (none at all, if they have no template)
+pub fn attach_resistances(world: &mut World, templates: &EnemyTemplates) {
+    let newcomers: Vec<(Entity, Resistances)> = world
+        .query::<(&Enemy, &Name)>()
+        .without::<&Resistances>()
+        .iter()
+        .map(|(entity, (_, name))| {
+            let resistances = templates.templates.iter()
+                .find(|t| t.name == name.0)
+                .map(|t| t.resistances.clone())
+                .unwrap_or_default();
+            (entity, Resistances(resistances))
+        })
+        .collect();
+    for (entity, resistances) in newcomers {
+        let _ = world.insert_one(entity, resistances);
+    }
 }
 
 /// Get the enemy pool for a given biome
 pub fn enemies_for_biome(biome: Biome) -> Vec<&'static EnemyDef> {
     match biome {
         Biome::SunkenCatacombs => vec![&SKELETON, &ZOMBIE, &GHOST, &RAT_SWARM],
-        Biome::BleedingCrypts => vec![&BLOOD_CULTIST, &CRIMSON_HOUND, &FLESH_GOLEM, &SKELETON],
-        Biome::HollowCathedral => vec![&FALLEN_KNIGHT, &CORRUPTED_ANGEL, &GARGOYLE, &BLOOD_CULTIST],
-        Biome::TheAbyss => vec![&VOID_SPAWN, &ELDRITCH_HORROR, &TENTACLE, &CORRUPTED_ANGEL],
+        Biome::BleedingCrypts => vec![&BLOOD_CULTIST, &CRIMSON_HOUND, &FLESH_GOLEM, &SKELETON, &NECROMANCER],
+        Biome::HollowCathedral => vec![&FALLEN_KNIGHT, &CORRUPTED_ANGEL, &GARGOYLE, &BLOOD_CULTIST, &NECROMANCER],
+        Biome::FleshGardens => vec![&GRAFTLING, &THORNED_HUSK, &SPORE_MOTHER, &BLOATED_TENDER, &FLESH_GOLEM],
+        Biome::TheAbyss => vec![&VOID_SPAWN, &ELDRITCH_HORROR, &TENTACLE, &CORRUPTED_ANGEL, &FIRE_ELEMENTAL],
     }
 }
 
+/// Get the enemy pool for a floor: its biome's, joined on depth floors by
+/// the creatures of a second biome
+pub fn enemies_for_floor(biome: Biome, floor: u32) -> Vec<&'static EnemyDef> {
+    let mut pool = enemies_for_biome(biome);
+    if let Some(mashup) = crate::world::generation::mashup_biome(floor) {
+        for def in enemies_for_biome(mashup) {
+            if !pool.iter().any(|d| d.name == def.name) {
+                pool.push(def);
+            }
+        }
+    }
+    pool
+}
+
 /// Get enemy count range for a floor
 pub fn enemy_count_for_floor(floor: u32) -> (usize, usize) {
     match floor {
@@ -258,20 +483,19 @@ pub fn enemy_count_for_floor(floor: u32) -> (usize, usize) {
 pub fn spawn_enemies_for_floor(
     world: &mut World,
     biome: Biome,
-    floor: u32,
     valid_positions: &[Position],
     rng: &mut impl rand::Rng,
-    difficulty: crate::progression::Difficulty,
+    scaling: &FloorScaling,
 ) -> Vec<Entity> {
     use rand::seq::SliceRandom;
 
-    let scaling = FloorScaling::new(floor, difficulty);
-    let enemy_pool = enemies_for_biome(biome);
+    let floor = scaling.floor;
+    let enemy_pool = enemies_for_floor(biome, floor);
     let (min_count, max_count) = enemy_count_for_floor(floor);
 
     // Apply difficulty scaling to enemy count
     let (count_bonus_min, count_bonus_max) = scaling.enemy_count_bonus();
-    let count = rng.gen_range(min_count + count_bonus_min..=max_count + count_bonus_max);
+    let count = scaling.adjust_enemy_count(rng.gen_range(min_count + count_bonus_min..=max_count + count_bonus_max));
 
     // Don't spawn more enemies than we have positions
     let count = count.min(valid_positions.len());
@@ -289,15 +513,15 @@ pub fn spawn_enemies_for_floor(
         .collect();
 
     for i in 0..count {
-        // First enemy is elite if required
-        let enemy_def = if i == 0 && spawn_elite && !elite_enemies.is_empty() {
+        // The first enemies are elite if required
+        let enemy_def = if i < scaling.elites_per_zone() && spawn_elite && !elite_enemies.is_empty() {
             **elite_enemies.choose(rng).unwrap()
         } else {
             *enemy_pool.choose(rng).unwrap()
         };
 
         // Use scaled spawning
-        let entity = spawn_enemy_scaled(world, enemy_def, positions[i], &scaling);
+        let entity = spawn_enemy_scaled(world, enemy_def, positions[i], scaling);
         spawned.push(entity);
     }
 
@@ -309,21 +533,20 @@ pub fn spawn_enemies_for_floor(
 pub fn spawn_enemies_for_floor_with_zones(
     world: &mut World,
     biome: Biome,
-    floor: u32,
     valid_positions: &[Position],
     map: &crate::world::Map,
     rng: &mut impl rand::Rng,
-    difficulty: crate::progression::Difficulty,
+    scaling: &FloorScaling,
 ) -> Vec<Entity> {
     use rand::seq::SliceRandom;
 
-    let scaling = FloorScaling::new(floor, difficulty);
-    let enemy_pool = enemies_for_biome(biome);
+    let floor = scaling.floor;
+    let enemy_pool = enemies_for_floor(biome, floor);
     let (min_count, max_count) = enemy_count_for_floor(floor);
 
     // Apply difficulty scaling to enemy count
     let (count_bonus_min, count_bonus_max) = scaling.enemy_count_bonus();
-    let count = rng.gen_range(min_count + count_bonus_min..=max_count + count_bonus_max);
+    let count = scaling.adjust_enemy_count(rng.gen_range(min_count + count_bonus_min..=max_count + count_bonus_max));
 
     // Separate positions into elite zone and regular positions
     let mut elite_positions: Vec<Position> = valid_positions.iter()
@@ -345,10 +568,10 @@ pub fn spawn_enemies_for_floor_with_zones(
         .filter(|e| e.archetype == EnemyArchetype::Elite || e.archetype == EnemyArchetype::Tank)
         .collect();
 
-    // FIRST: Ensure each elite zone has at least one enemy
+    // FIRST: Ensure each elite zone has its elites
     // Group elite positions by their elite room center
     let elite_rooms = map.elite_rooms();
-    for room_center in elite_rooms {
+    for room_center in elite_rooms.iter().flat_map(|c| std::iter::repeat_n(c, scaling.elites_per_zone())) {
         // Find positions near this elite room center
         let nearby_elite_pos = elite_positions.iter()
             .find(|pos| {
@@ -369,7 +592,7 @@ pub fn spawn_enemies_for_floor_with_zones(
                 *enemy_pool.choose(rng).unwrap()
             };
 
-            let elite_scaling = FloorScaling::elite_scaled(floor, difficulty);
+            let elite_scaling = scaling.elite();
             let entity = spawn_enemy_scaled(world, enemy_def, pos, &elite_scaling);
             spawned.push(entity);
         }
@@ -395,7 +618,7 @@ pub fn spawn_enemies_for_floor_with_zones(
 
         // Create an elite scaling for elite zones (more HP/damage, more XP)
         let actual_scaling = if is_elite_zone {
-            FloorScaling::elite_scaled(floor, difficulty)
+            scaling.elite()
         } else {
             scaling.clone()
         };
@@ -0,0 +1,98 @@
+//! Heart nodes
+//!
+//! The Flesh Gardens grow pulsating hearts out of their floors. A heart
+//! can't move or strike back, but every beat mends every enemy on the
+//! floor, so a fight there goes on getting harder until the hearts are cut
+//! out. The Gardener grows fresh ones as it fights.
+
+use hecs::{Entity, World};
+use serde::{Deserialize, Serialize};
+use crate::ecs::{Enemy, EnemyArchetype, Health, Position, Stats, AI};
+use crate::progression::FloorScaling;
+use super::enemies::{spawn_enemy_scaled, EnemyDef};
+
+/// HP each beating heart mends on every wounded enemy per turn
+pub const HEART_HEAL: i32 = 1;
+
+/// Rooted in place, mending the enemies around it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HeartNode;
+
+pub const HEART_NODE: EnemyDef = EnemyDef {
+    name: "Heart Node",
+    glyph: '♥',
+    fg: (230, 60, 90),
+    archetype: EnemyArchetype::Tank,
+    stats: Stats { strength: 1, dexterity: 1, intelligence: 1, vitality: 14 },
+    hp: 60,
+    xp_value: 40,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+/// Grow a heart node
+pub fn spawn_heart_node(world: &mut World, pos: Position, scaling: &FloorScaling) -> Entity {
+    let heart = spawn_enemy_scaled(world, &HEART_NODE, pos, scaling);
+    root_heart(world, heart);
+    heart
+}
+
+/// Turn an enemy into a heart node: it stops thinking and starts beating
+pub fn root_heart(world: &mut World, entity: Entity) {
+    let _ = world.remove_one::<AI>(entity);
+    let _ = world.insert_one(entity, HeartNode);
+}
+
+/// Living heart nodes on the floor
+pub fn heart_count(world: &World) -> usize {
+    world.query::<(&HeartNode, &Health)>()
+        .iter()
+        .filter(|(_, (_, health))| !health.is_dead())
+        .count()
+}
+
+/// Every living heart beats once, mending every other enemy on the floor.
+/// Returns how much HP was mended in all.
+pub fn hearts_beat(world: &mut World) -> i32 {
+    let heal = HEART_HEAL * heart_count(world) as i32;
+    if heal == 0 {
+        return 0;
+    }
+    world.query_mut::<(&Enemy, &mut Health)>()
+        .without::<&HeartNode>()
+        .into_iter()
+        .filter(|(_, (_, health))| !health.is_dead())
+        .map(|(_, (_, health))| health.heal(heal))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progression::Difficulty;
+    use super::super::enemies::GRAFTLING;
+
+    #[test]
+    fn test_hearts_mend_enemies() {
+        let mut world = World::new();
+        let scaling = FloorScaling::new(16, Difficulty::Normal);
+        let graftling = spawn_enemy_scaled(&mut world, &GRAFTLING, Position::new(3, 3), &scaling);
+        world.get::<&mut Health>(graftling).unwrap().current = 5;
+
+        // No hearts, no mending
+        assert_eq!(hearts_beat(&mut world), 0);
+
+        // Each heart adds to the beat, and never thinks for itself
+        let heart = spawn_heart_node(&mut world, Position::new(8, 8), &scaling);
+        spawn_heart_node(&mut world, Position::new(9, 8), &scaling);
+        assert!(world.get::<&AI>(heart).is_err());
+        world.get::<&mut Health>(heart).unwrap().current = 10;
+        assert_eq!(hearts_beat(&mut world), HEART_HEAL * 2);
+        assert_eq!(world.get::<&Health>(graftling).unwrap().current, 5 + HEART_HEAL * 2);
+        assert_eq!(world.get::<&Health>(heart).unwrap().current, 10);
+
+        // A dead heart stops beating
+        world.get::<&mut Health>(heart).unwrap().current = 0;
+        assert_eq!(heart_count(&world), 1);
+    }
+}
@@ -5,10 +5,23 @@ pub mod enemies;
 pub mod bosses;
 pub mod npcs;
 pub mod chests;
+pub mod prisoners;
 pub mod spawner;
+pub mod stalker;
+pub mod corpses;
+pub mod hearts;
+pub mod props;
+pub mod peddler;
 
 pub use player::spawn_player;
-pub use enemies::{spawn_enemy, spawn_enemy_scaled, spawn_enemies_for_floor, spawn_enemies_for_floor_with_zones, enemies_for_biome};
-pub use bosses::{BossType, BossComponent, spawn_boss, boss_for_biome, update_boss_phase};
+pub use enemies::{attach_resistances, spawn_enemy, spawn_enemy_scaled, spawn_enemies_for_floor, spawn_enemies_for_floor_with_zones, enemies_for_biome, enemies_for_floor, enemy_def};
+pub use spawner::{spawn_group, spawn_groups_for_floor, formation_tiles, assign_patrols, patrol_path, empower_elites, spawn_ambushed_caravan};
+pub use corpses::{Corpse, Necromancer, Risen, spawn_corpse, place_corpse, decay_corpses, nearest_corpse, raise_corpse, search_corpse_loot, is_gib, RAISE_RANGE, RAISE_COOLDOWN, CORPSE_SKILL_RANGE};
+pub use hearts::{HeartNode, spawn_heart_node, root_heart, heart_count, hearts_beat, HEART_NODE};
+pub use props::{Prop, Pushable, PropMove, spawn_prop, get_prop_at, shove_prop, hurl_prop, ignite, THROW_RANGE, THROW_DAMAGE};
+pub use peddler::{Peddler, PeddlerHoard, spawn_peddler, wander_peddlers, rob_peddler, PEDDLER_CHANCE, PEDDLER_ARRIVAL, PEDDLER_STAY_TURNS, PEDDLER_WARNING_TURNS, ROBBERY_CORRUPTION, ROBBED_PEDDLER};
+pub use stalker::{Stalker, spawn_stalker, STALKER_TURNS, STALKER_WARNING_TURNS, STALKER_LOOT_DEPTH};
+pub use bosses::{BossType, BossComponent, BossFight, spawn_boss, boss_for_biome, update_boss_phase};
 pub use npcs::{NpcType, NpcComponent, NpcMarker, ShopItem, spawn_npc, spawn_npcs_for_floor, get_npc_at};
-pub use chests::{spawn_chest, spawn_chests_for_floor, generate_chest_loot, get_chest_at, mark_chest_opened};
+pub use chests::{chest_count_range, spawn_chest, place_chest, spawn_chests_for_floor, spawn_secret_chest, spawn_mimic, generate_chest_loot, get_chest_at, mark_chest_opened, refresh_chest_glyph, spot_chance, disarm_chance, ChestApproach, MimicHoard, Riddle, RIDDLES, MIMIC};
+pub use prisoners::{PrisonerKind, Prisoner, Follower, RescueOutcome, spawn_prisoner, spawn_prisoner_for_floor, make_follower, get_prisoner_at, get_follower_at};
@@ -5,13 +5,16 @@
 use hecs::{World, Entity};
 use rand::Rng;
 use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 use crate::ecs::{Position, Renderable};
+use crate::game::Interactable;
 use crate::items::{Item, ItemId, Rarity, generate_weapon, generate_armor};
+use crate::items::loot::{generate_weapon_with_min_rarity, generate_armor_with_min_rarity, generate_jewelry_with_min_rarity};
 use crate::items::item::templates;
 use crate::world::Biome;
 
 /// Types of NPCs
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NpcType {
     /// Sells weapons, armor, consumables
     Merchant,
@@ -23,6 +26,12 @@ pub enum NpcType {
     Storyteller,
     /// Mysterious figure with risky trades
     Collector,
+    /// Trades from their pack as they pass through, then moves on
+    Peddler,
+    /// Plays dice for gold
+    Gambler,
+    /// Sells rings and amulets (once unlocked)
+    Jeweler,
 }
 
 impl NpcType {
@@ -33,6 +42,9 @@ impl NpcType {
             NpcType::Healer => "Field Healer",
             NpcType::Storyteller => "Storyteller",
             NpcType::Collector => "Strange Collector",
+            NpcType::Peddler => "Traveling Peddler",
+            NpcType::Gambler => "Grinning Gambler",
+            NpcType::Jeweler => "Jeweler",
         }
     }
 
@@ -43,6 +55,9 @@ impl NpcType {
             NpcType::Healer => '+',
             NpcType::Storyteller => '?',
             NpcType::Collector => '%',
+            NpcType::Peddler => '$',
+            NpcType::Gambler => '¤',
+            NpcType::Jeweler => '◊',
         }
     }
 
@@ -53,6 +68,9 @@ impl NpcType {
             NpcType::Healer => (100, 255, 100),    // Green
             NpcType::Storyteller => (180, 180, 255), // Light blue
             NpcType::Collector => (200, 100, 200), // Purple
+            NpcType::Peddler => (230, 160, 60),    // Amber
+            NpcType::Gambler => (255, 120, 160),   // Pink
+            NpcType::Jeweler => (120, 220, 255),   // Pale blue
         }
     }
 
@@ -63,6 +81,23 @@ impl NpcType {
             NpcType::Healer => "Let me tend to your wounds.",
             NpcType::Storyteller => "Ah, another soul braving the depths...",
             NpcType::Collector => "I seek... unusual items. Perhaps we can trade.",
+            NpcType::Peddler => "Quickly now, I can't linger down here long!",
+            NpcType::Gambler => "Fancy a throw? The dice don't care who you are.",
+            NpcType::Jeweler => "Every stone down here remembers a throat. Have a look.",
+        }
+    }
+
+    /// How hard they are to pickpocket
+    pub fn perception(&self) -> i32 {
+        match self {
+            NpcType::Merchant => 13,
+            NpcType::Blacksmith => 11,
+            NpcType::Healer => 9,
+            NpcType::Storyteller => 8,
+            NpcType::Collector => 16,
+            NpcType::Peddler => 12,
+            NpcType::Gambler => 15,
+            NpcType::Jeweler => 14,
         }
     }
 
@@ -77,6 +112,7 @@ impl NpcType {
             (NpcType::Blacksmith, _) => 0.5,
             // Healers more common in dangerous areas
             (NpcType::Healer, Biome::BleedingCrypts) => 0.9,
+            (NpcType::Healer, Biome::FleshGardens) => 0.9,
             (NpcType::Healer, Biome::TheAbyss) => 1.0,
             (NpcType::Healer, _) => 0.6,
             // Storytellers everywhere
@@ -85,6 +121,14 @@ impl NpcType {
             (NpcType::Collector, Biome::BleedingCrypts) => 0.8,
             (NpcType::Collector, Biome::TheAbyss) => 1.0,
             (NpcType::Collector, _) => 0.3,
+            // Peddlers turn up on their own schedule, never at floor start
+            (NpcType::Peddler, _) => 0.0,
+            // Gamblers go where the gold is
+            (NpcType::Gambler, Biome::SunkenCatacombs) => 0.2,
+            (NpcType::Gambler, _) => 0.5,
+
+            (NpcType::Jeweler, Biome::HollowCathedral) => 0.8,
+            (NpcType::Jeweler, _) => 0.4,
         }
     }
 }
@@ -103,34 +147,82 @@ pub struct NpcComponent {
     pub dialogue_state: u8,
 }
 
-/// Item for sale in a shop
+/// Items a merchant holds for buyback before they go on general sale
+pub const BUYBACK_SLOTS: usize = 8;
+/// Rings and amulets a jeweler sets out
+const JEWELER_WARES: usize = 3;
+
+/// Paid services a merchant offers besides buying and selling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MerchantService {
+    /// Reveal an unidentified item's name and affixes
+    Identify,
+    /// Buy an unidentified weapon sight unseen
+    GambleWeapon,
+    /// Buy an unidentified piece of armor sight unseen
+    GambleArmor,
+}
+
+impl MerchantService {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MerchantService::Identify => "Identify",
+            MerchantService::GambleWeapon => "Mystery Weapon",
+            MerchantService::GambleArmor => "Mystery Armor",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            MerchantService::Identify => "Learn what an unidentified item really is. Its affixes wake up.",
+            MerchantService::GambleWeapon => "A wrapped blade, never worse than Uncommon. Better the deeper you are.",
+            MerchantService::GambleArmor => "A sealed crate of armor, never worse than Uncommon. Better the deeper you are.",
+        }
+    }
+}
+
+/// Services offered on a floor. The merchant's specialty follows the same
+/// floor cycle as their stock.
+pub fn merchant_services(floor: u32) -> Vec<MerchantService> {
+    match floor % 4 {
+        0 => vec![MerchantService::Identify, MerchantService::GambleWeapon, MerchantService::GambleArmor],
+        1 => vec![MerchantService::Identify, MerchantService::GambleWeapon],
+        2 => vec![MerchantService::Identify, MerchantService::GambleArmor],
+        _ => vec![MerchantService::Identify],
+    }
+}
+
+/// Cost to identify an item, by how rare it turns out to be
+pub fn identify_price(item: &Item) -> u32 {
+    20 + 30 * item.rarity.sort_value() as u32
+}
+
+/// Cost of a mystery item on a floor
+pub fn gamble_price(floor: u32) -> u32 {
+    75 + 25 * floor
+}
+
+/// Roll the concealed item a gamble service sells
+pub fn gamble_item(service: MerchantService, floor: u32, rng: &mut impl Rng) -> Option<Item> {
+    let mut item = match service {
+        MerchantService::GambleWeapon => generate_weapon_with_min_rarity(floor, Rarity::Uncommon, rng),
+        MerchantService::GambleArmor => generate_armor_with_min_rarity(floor, Rarity::Uncommon, rng),
+        MerchantService::Identify => return None,
+    };
+    item.conceal();
+    Some(item)
+}
+
+/// Item for sale in a shop. Priced by the economy config when shown or
+/// bought, so prices follow the floor and the player's standing.
 #[derive(Debug, Clone)]
 pub struct ShopItem {
     pub item: Item,
-    pub buy_price: u32,
-    pub sell_price: u32,
 }
 
 impl ShopItem {
     pub fn new(item: Item) -> Self {
-        let base_value = item.value;
-        let rarity_mult = match item.rarity {
-            Rarity::Common => 1.0,
-            Rarity::Uncommon => 1.5,
-            Rarity::Rare => 2.5,
-            Rarity::Epic => 4.0,
-            Rarity::Legendary => 8.0,
-            Rarity::Mythic => 15.0, // Extremely valuable
-        };
-
-        let buy_price = ((base_value as f32) * rarity_mult * 1.2) as u32;
-        let sell_price = ((base_value as f32) * 0.4) as u32;
-
-        Self {
-            item,
-            buy_price: buy_price.max(5),
-            sell_price: sell_price.max(1),
-        }
+        Self { item }
     }
 }
 
@@ -143,6 +235,55 @@ pub enum MerchantType {
     Alchemist,      // Mostly consumables
 }
 
+/// Merchant type is determined by floor number to ensure variety
+/// Each floor gets a different merchant type in a cycle
+fn merchant_type_for_floor(floor: u32) -> MerchantType {
+    match floor % 4 {
+        0 => MerchantType::GeneralStore,
+        1 => MerchantType::Weaponsmith,
+        2 => MerchantType::Armorer,
+        _ => MerchantType::Alchemist,
+    }
+}
+
+/// Roll one ware from a merchant's specialty
+fn roll_ware(rng: &mut StdRng, floor: u32, merchant_type: MerchantType, id: ItemId) -> Item {
+    match merchant_type {
+        MerchantType::GeneralStore => {
+            // Mix of everything
+            match rng.gen_range(0..10) {
+                0..=3 => generate_consumable_for_shop(rng, id),
+                4..=6 => generate_weapon(floor, rng),
+                _ => generate_armor(floor, rng),
+            }
+        }
+        MerchantType::Weaponsmith => {
+            // 70% weapons, 20% armor, 10% consumables
+            match rng.gen_range(0..10) {
+                0..=6 => generate_weapon(floor, rng),
+                7..=8 => generate_armor(floor, rng),
+                _ => generate_consumable_for_shop(rng, id),
+            }
+        }
+        MerchantType::Armorer => {
+            // 70% armor, 20% weapons, 10% consumables
+            match rng.gen_range(0..10) {
+                0..=6 => generate_armor(floor, rng),
+                7..=8 => generate_weapon(floor, rng),
+                _ => generate_consumable_for_shop(rng, id),
+            }
+        }
+        MerchantType::Alchemist => {
+            // 80% consumables, 10% each weapons/armor
+            match rng.gen_range(0..10) {
+                0..=7 => generate_consumable_for_shop(rng, id),
+                8 => generate_weapon(floor, rng),
+                _ => generate_armor(floor, rng),
+            }
+        }
+    }
+}
+
 /// Generate shop inventory for a merchant
 pub fn generate_shop_inventory(
     rng: &mut StdRng,
@@ -151,61 +292,28 @@ pub fn generate_shop_inventory(
     item_id_counter: &mut u64,
 ) -> Vec<ShopItem> {
     let mut items = Vec::new();
-
-    // Merchant type is determined by floor number to ensure variety
-    // Each floor gets a different merchant type in a cycle
-    let merchant_type = match floor % 4 {
-        0 => MerchantType::GeneralStore,
-        1 => MerchantType::Weaponsmith,
-        2 => MerchantType::Armorer,
-        _ => MerchantType::Alchemist,
-    };
+    let merchant_type = merchant_type_for_floor(floor);
 
     // Number of items scales with floor
     let num_items = 4 + (floor / 3).min(4) as usize;
 
     for _ in 0..num_items {
-        let item = match merchant_type {
-            MerchantType::GeneralStore => {
-                // Mix of everything
-                match rng.gen_range(0..10) {
-                    0..=3 => generate_consumable_for_shop(rng, *item_id_counter),
-                    4..=6 => generate_weapon(floor, rng),
-                    _ => generate_armor(floor, rng),
-                }
-            }
-            MerchantType::Weaponsmith => {
-                // 70% weapons, 20% armor, 10% consumables
-                match rng.gen_range(0..10) {
-                    0..=6 => generate_weapon(floor, rng),
-                    7..=8 => generate_armor(floor, rng),
-                    _ => generate_consumable_for_shop(rng, *item_id_counter),
-                }
-            }
-            MerchantType::Armorer => {
-                // 70% armor, 20% weapons, 10% consumables
-                match rng.gen_range(0..10) {
-                    0..=6 => generate_armor(floor, rng),
-                    7..=8 => generate_weapon(floor, rng),
-                    _ => generate_consumable_for_shop(rng, *item_id_counter),
-                }
-            }
-            MerchantType::Alchemist => {
-                // 80% consumables, 10% each weapons/armor
-                match rng.gen_range(0..10) {
-                    0..=7 => generate_consumable_for_shop(rng, *item_id_counter),
-                    8 => generate_weapon(floor, rng),
-                    _ => generate_armor(floor, rng),
-                }
-            }
-        };
+        let item = roll_ware(rng, floor, merchant_type, *item_id_counter);
         *item_id_counter += 1;
         items.push(ShopItem::new(item));
     }
 
-    // Always have at least one health potion
+    // Always have at least one health potion and something to eat
     items.push(ShopItem::new(templates::health_potion(*item_id_counter)));
     *item_id_counter += 1;
+    items.push(ShopItem::new(templates::travel_ration(*item_id_counter)));
+    *item_id_counter += 1;
+
+    // General stores sell bag upgrades
+    if matches!(merchant_type, MerchantType::GeneralStore) {
+        items.push(ShopItem::new(templates::sturdy_satchel(*item_id_counter)));
+        *item_id_counter += 1;
+    }
 
     // Alchemists have more potions
     if matches!(merchant_type, MerchantType::Alchemist) {
@@ -213,6 +321,8 @@ pub fn generate_shop_inventory(
         *item_id_counter += 1;
         items.push(ShopItem::new(templates::health_potion(*item_id_counter)));
         *item_id_counter += 1;
+        items.push(ShopItem::new(templates::bonesetters_salve(*item_id_counter)));
+        *item_id_counter += 1;
     }
 
     // Biome-specific items - alternate based on floor to ensure variety
@@ -256,6 +366,15 @@ pub fn generate_shop_inventory(
                 *item_id_counter += 1;
             }
         }
+        Biome::FleshGardens => {
+            // Whatever grows here changes those who stay
+            items.push(ShopItem::new(templates::mutagenic_vial(*item_id_counter)));
+            *item_id_counter += 1;
+            if rng.gen_bool(0.25) {
+                items.push(ShopItem::new(templates::bonesetters_salve(*item_id_counter)));
+                *item_id_counter += 1;
+            }
+        }
         Biome::TheAbyss => {
             if floor_is_even {
                 items.push(ShopItem::new(templates::corrupted_gauntlets(*item_id_counter)));
@@ -340,6 +459,23 @@ pub fn generate_shop_inventory(
     items
 }
 
+/// Generate a jeweler's rings and amulets, never worse than Uncommon
+pub fn generate_jeweler_inventory(rng: &mut StdRng, floor: u32) -> Vec<ShopItem> {
+    (0..JEWELER_WARES)
+        .map(|_| ShopItem::new(generate_jewelry_with_min_rarity(floor, Rarity::Uncommon, rng)))
+        .collect()
+}
+
+/// Set out one more ware in a shop, from the same trade as the rest
+pub fn add_shop_slot(npc: &mut NpcComponent, rng: &mut StdRng, floor: u32, item_id_counter: &mut u64) {
+    let item = match npc.npc_type {
+        NpcType::Jeweler => generate_jewelry_with_min_rarity(floor, Rarity::Uncommon, rng),
+        _ => roll_ware(rng, floor, merchant_type_for_floor(floor), *item_id_counter),
+    };
+    *item_id_counter += 1;
+    npc.shop_items.push(ShopItem::new(item));
+}
+
 fn generate_consumable_for_shop(rng: &mut StdRng, id: ItemId) -> Item {
     // More variety in consumables based on random roll
     match rng.gen_range(0..10) {
@@ -359,10 +495,10 @@ pub fn spawn_npc(
     biome: Biome,
     item_id_counter: &mut u64,
 ) -> Entity {
-    let shop_items = if matches!(npc_type, NpcType::Merchant) {
-        generate_shop_inventory(rng, floor, biome, item_id_counter)
-    } else {
-        Vec::new()
+    let shop_items = match npc_type {
+        NpcType::Merchant | NpcType::Peddler => generate_shop_inventory(rng, floor, biome, item_id_counter),
+        NpcType::Jeweler => generate_jeweler_inventory(rng, floor),
+        _ => Vec::new(),
     };
 
     let npc = NpcComponent {
@@ -386,6 +522,7 @@ pub fn spawn_npc(
         npc,
         NpcMarker,
         renderable,
+        Interactable::Npc,
     ))
 }
 
@@ -401,6 +538,7 @@ pub fn choose_npc_for_biome(rng: &mut StdRng, biome: Biome) -> NpcType {
         NpcType::Healer,
         NpcType::Storyteller,
         NpcType::Collector,
+        NpcType::Gambler,
     ];
 
     // Weight by affinity
@@ -421,7 +559,8 @@ pub fn choose_npc_for_biome(rng: &mut StdRng, biome: Biome) -> NpcType {
     NpcType::Merchant // Default
 }
 
-/// Spawn NPCs for a floor (multiple different types, no duplicates)
+/// Spawn NPCs for a floor (multiple different types, no duplicates). The
+/// Jeweler only turns up once the profile has unlocked them.
 pub fn spawn_npcs_for_floor(
     world: &mut World,
     biome: Biome,
@@ -429,6 +568,7 @@ pub fn spawn_npcs_for_floor(
     spawn_positions: &[Position],
     rng: &mut StdRng,
     item_id_counter: &mut u64,
+    jeweler: bool,
 ) -> Vec<Entity> {
     use rand::seq::SliceRandom;
 
@@ -453,13 +593,17 @@ pub fn spawn_npcs_for_floor(
     };
 
     // Get all NPC types weighted by biome affinity
-    let all_types = [
+    let mut all_types = vec![
         NpcType::Merchant,
         NpcType::Blacksmith,
         NpcType::Healer,
         NpcType::Storyteller,
         NpcType::Collector,
+        NpcType::Gambler,
     ];
+    if jeweler {
+        all_types.push(NpcType::Jeweler);
+    }
 
     // Sort by biome affinity (higher affinity = more likely to be picked first)
     let mut weighted_types: Vec<(NpcType, f32)> = all_types.iter()
@@ -528,3 +672,49 @@ pub fn get_npc_at(world: &World, pos: Position) -> Option<Entity> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::items::ItemCategory;
+
+    #[test]
+    fn test_gambled_gear_is_concealed_until_identified() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut item = gamble_item(MerchantService::GambleWeapon, 5, &mut rng).unwrap();
+
+        assert!(!item.is_identified());
+        assert!(item.affixes.is_empty());
+        assert!(item.name.starts_with("Unidentified"));
+        assert!(item.rarity.sort_value() >= Rarity::Uncommon.sort_value());
+
+        assert!(item.identify());
+        assert!(item.is_identified());
+        assert!(!item.affixes.is_empty());
+        assert!(!item.identify());
+
+        assert!(gamble_item(MerchantService::Identify, 5, &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_jeweler_sells_jewelry_and_shops_take_an_extra_slot() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut counter = 1;
+        let stock = generate_jeweler_inventory(&mut rng, 8);
+        assert_eq!(stock.len(), JEWELER_WARES);
+        assert!(stock.iter().all(|s| s.item.category == ItemCategory::Accessory));
+        assert!(stock.iter().all(|s| s.item.rarity.sort_value() >= Rarity::Uncommon.sort_value()));
+
+        let mut jeweler = NpcComponent {
+            npc_type: NpcType::Jeweler,
+            shop_items: stock,
+            gold: 0,
+            interacted: false,
+            dialogue_state: 0,
+        };
+        add_shop_slot(&mut jeweler, &mut rng, 8, &mut counter);
+        assert_eq!(jeweler.shop_items.len(), JEWELER_WARES + 1);
+        assert_eq!(jeweler.shop_items[JEWELER_WARES].item.category, ItemCategory::Accessory);
+    }
+}
@@ -0,0 +1,171 @@
+//! The traveling peddler
+//!
+//! Now and then a peddler turns up partway through a floor, wanders its
+//! rooms trading from their pack, and moves on again after a while, so
+//! whatever they carry has to be bought while they're about. They stop to
+//! trade whenever the player is beside them. Anyone desperate enough can rob
+//! them instead: the peddler fights back, and the blood of an innocent
+//! taints whoever spills it.
+
+use std::collections::HashSet;
+use hecs::{Entity, World};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use crate::ecs::{EnemyArchetype, Position, Stats};
+use crate::items::Item;
+use crate::progression::FloorScaling;
+use crate::world::{Biome, DijkstraMap, Map};
+use super::enemies::{spawn_enemy_scaled, EnemyDef};
+use super::npcs::{spawn_npc, NpcComponent, NpcType};
+
+/// Chance a floor gets a visit from the peddler
+pub const PEDDLER_CHANCE: f64 = 0.3;
+/// Turns into a floor the peddler may turn up, earliest to latest
+pub const PEDDLER_ARRIVAL: (u32, u32) = (60, 400);
+/// Turns the peddler stays before moving on
+pub const PEDDLER_STAY_TURNS: u32 = 150;
+/// Turns left at which the peddler starts packing up
+pub const PEDDLER_WARNING_TURNS: u32 = 20;
+/// Corruption taken for robbing the peddler
+pub const ROBBERY_CORRUPTION: u32 = 25;
+
+/// A peddler passing through, and how long until they leave
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Peddler {
+    pub turns_left: u32,
+    /// Where they're wandering to
+    pub heading: Position,
+}
+
+/// A robbed peddler still carrying their pack and purse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeddlerHoard {
+    pub items: Vec<Item>,
+    pub gold: u32,
+}
+
+pub const ROBBED_PEDDLER: EnemyDef = EnemyDef {
+    name: "Robbed Peddler",
+    glyph: '$',
+    fg: (230, 120, 60),
+    archetype: EnemyArchetype::Melee,
+    stats: Stats { strength: 12, dexterity: 14, intelligence: 10, vitality: 12 },
+    hp: 70,
+    xp_value: 40,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+/// Bring the peddler onto the floor, stocked for it
+pub fn spawn_peddler(
+    world: &mut World,
+    pos: Position,
+    rng: &mut StdRng,
+    floor: u32,
+    biome: Biome,
+    item_id_counter: &mut u64,
+) -> Entity {
+    let peddler = spawn_npc(world, NpcType::Peddler, pos, rng, floor, biome, item_id_counter);
+    let _ = world.insert_one(peddler, Peddler { turns_left: PEDDLER_STAY_TURNS, heading: pos });
+    peddler
+}
+
+/// Walk every peddler a step toward where they're heading, picking somewhere
+/// new once they're there or stuck. They hold still beside the player to
+/// trade. Returns the peddlers whose time is up.
+pub fn wander_peddlers(world: &mut World, map: &Map, player_pos: Position, rng: &mut impl Rng) -> Vec<Entity> {
+    let blocked: HashSet<Position> = world.query::<&Position>()
+        .with::<&crate::ecs::BlocksMovement>()
+        .iter()
+        .map(|(_, pos)| *pos)
+        .chain(world.query::<&Position>().with::<&super::NpcMarker>().iter().map(|(_, pos)| *pos))
+        .chain(std::iter::once(player_pos))
+        .collect();
+
+    let mut leaving = Vec::new();
+    for (entity, (pos, peddler)) in world.query_mut::<(&mut Position, &mut Peddler)>() {
+        peddler.turns_left = peddler.turns_left.saturating_sub(1);
+        if peddler.turns_left == 0 {
+            leaving.push(entity);
+            continue;
+        }
+        if pos.chebyshev_distance(&player_pos) <= 1 {
+            continue;
+        }
+
+        let step = DijkstraMap::new(map, peddler.heading)
+            .downhill(*pos)
+            .into_iter()
+            .find(|step| map.is_walkable(step.x, step.y) && !blocked.contains(step));
+        match step {
+            Some(step) => *pos = step,
+            None => {
+                if let Some(&heading) = map.get_walkable_positions().choose(rng) {
+                    peddler.heading = heading;
+                }
+            }
+        }
+    }
+    leaving
+}
+
+/// Turn on the peddler: they drop their wares and fight for their life.
+/// Returns the peddler as an enemy, carrying what they had.
+pub fn rob_peddler(world: &mut World, peddler: Entity, scaling: &FloorScaling) -> Option<Entity> {
+    let pos = *world.get::<&Position>(peddler).ok()?;
+    let hoard = {
+        let npc = world.get::<&NpcComponent>(peddler).ok()?;
+        PeddlerHoard {
+            items: npc.shop_items.iter().map(|s| s.item.clone()).collect(),
+            gold: npc.gold,
+        }
+    };
+    let _ = world.despawn(peddler);
+
+    let robbed = spawn_enemy_scaled(world, &ROBBED_PEDDLER, pos, scaling);
+    let _ = world.insert_one(robbed, hoard);
+    if let Ok(mut ai) = world.get::<&mut crate::ecs::AI>(robbed) {
+        ai.state = crate::ecs::AIState::Chase;
+    }
+    Some(robbed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_peddler_wanders_and_leaves() {
+        let mut map = Map::new(20, 5, 4, Biome::SunkenCatacombs);
+        for x in 1..19 {
+            map.set_tile(x, 2, crate::world::TileType::Floor);
+        }
+        let mut world = World::new();
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut ids = 0;
+        let peddler = spawn_peddler(&mut world, Position::new(2, 2), &mut rng, 4, Biome::SunkenCatacombs, &mut ids);
+        world.get::<&mut Peddler>(peddler).unwrap().heading = Position::new(10, 2);
+        assert!(!world.get::<&NpcComponent>(peddler).unwrap().shop_items.is_empty());
+
+        // Heads off, but stops to trade once the player is beside them
+        let player = Position::new(18, 2);
+        wander_peddlers(&mut world, &map, player, &mut rng);
+        assert_eq!(*world.get::<&Position>(peddler).unwrap(), Position::new(3, 2));
+        let beside = Position::new(4, 2);
+        wander_peddlers(&mut world, &map, beside, &mut rng);
+        assert_eq!(*world.get::<&Position>(peddler).unwrap(), Position::new(3, 2));
+
+        // Packs up when their time is up
+        world.get::<&mut Peddler>(peddler).unwrap().turns_left = 1;
+        assert_eq!(wander_peddlers(&mut world, &map, player, &mut rng), vec![peddler]);
+
+        // Robbed, they fight with their pack still on them
+        let robbed = rob_peddler(&mut world, peddler, &FloorScaling::new(4, crate::progression::Difficulty::Normal)).unwrap();
+        assert!(!world.contains(peddler));
+        assert!(world.get::<&crate::ecs::Enemy>(robbed).is_ok());
+        assert!(!world.get::<&PeddlerHoard>(robbed).unwrap().items.is_empty());
+    }
+}
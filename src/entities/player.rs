@@ -9,7 +9,7 @@ use crate::ecs::{
 };
 use crate::items::{Inventory, Equipment, item::templates};
 use crate::items::loot::next_item_id;
-use crate::progression::{EquippedSkills, skill_power_strike, skill_first_aid};
+use crate::progression::{EquippedSkills, Injuries, Mutations, skill_power_strike, skill_first_aid};
 
 /// Spawn the player entity
 pub fn spawn_player(world: &mut World, pos: Position) -> hecs::Entity {
@@ -20,6 +20,11 @@ pub fn spawn_player(world: &mut World, pos: Position) -> hecs::Entity {
     inventory.add_item(templates::health_potion(next_item_id()));
     inventory.add_item(templates::health_potion(next_item_id()));
     inventory.add_item(templates::mana_potion(next_item_id()));
+    inventory.add_item(templates::travel_ration(next_item_id()));
+    inventory.add_item(templates::travel_ration(next_item_id()));
+    // Potions start on the belt (keys 6 and 7)
+    inventory.belt_mut().toggle(0, &templates::health_potion(0));
+    inventory.belt_mut().toggle(1, &templates::mana_potion(0));
 
     // Create equipment with starting weapon
     let mut equipment = Equipment::new();
@@ -58,6 +63,8 @@ pub fn spawn_player(world: &mut World, pos: Position) -> hecs::Entity {
     let _ = world.insert(entity, (
         SkillsComponent { skills },
         StatusEffects::default(),
+        Mutations::default(),
+        Injuries::default(),
     ));
 
     entity
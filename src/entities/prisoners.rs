@@ -0,0 +1,203 @@
+//! Caged prisoners and escort quests
+//!
+//! Prisoners are occasionally found locked in cages. Freeing one grants a
+//! reward: a hidden stash, a sellsword who fights alongside the player for a
+//! while, or a survivor who must be escorted to the stairs. Escorted survivors
+//! set up camp at the dungeon entrance in future runs.
+
+use hecs::{World, Entity};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::ecs::{Position, Renderable, Name, Stats, Health, BlocksMovement, FactionComponent, Faction};
+use super::npcs::NpcType;
+
+/// Chance for a regular floor to contain a caged prisoner
+const PRISONER_CHANCE: f64 = 0.3;
+/// How long a freed sellsword fights for the player (in turns)
+const SELLSWORD_TURNS: u32 = 150;
+/// Glyph used for a locked cage
+const CAGE_GLYPH: char = '¤';
+
+/// NPCs that can be rescued and will appear at the entrance in future runs
+pub const RESCUABLE_NPCS: [NpcType; 3] = [NpcType::Merchant, NpcType::Healer, NpcType::Blacksmith];
+
+/// Who is locked in the cage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrisonerKind {
+    /// Knows where a stash is hidden
+    Scavenger,
+    /// Fights for the player for a while
+    Sellsword,
+    /// Must be escorted to the stairs; unlocks an entrance NPC for future runs
+    Survivor(NpcType),
+}
+
+impl PrisonerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrisonerKind::Scavenger => "Chained Scavenger",
+            PrisonerKind::Sellsword => "Captured Sellsword",
+            PrisonerKind::Survivor(NpcType::Merchant) => "Captive Merchant",
+            PrisonerKind::Survivor(NpcType::Healer) => "Captive Healer",
+            PrisonerKind::Survivor(NpcType::Blacksmith) => "Captive Blacksmith",
+            PrisonerKind::Survivor(_) => "Captive Survivor",
+        }
+    }
+
+    /// What the prisoner says when freed
+    pub fn plea(&self) -> &'static str {
+        match self {
+            PrisonerKind::Scavenger => "Thank you! Take this - I won't need it where I'm going.",
+            PrisonerKind::Sellsword => "Free at last. My blade is yours, for a time.",
+            PrisonerKind::Survivor(_) => "Please, get me to the stairs! I'll repay you, I swear it.",
+        }
+    }
+}
+
+/// A prisoner locked in a cage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prisoner {
+    pub kind: PrisonerKind,
+}
+
+/// Marks an entity as an ally that follows the player
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Follower {
+    /// Turns left before the follower departs (None = stays until the escort ends)
+    pub turns_left: Option<u32>,
+    /// Whether this follower attacks enemies
+    pub combatant: bool,
+    /// NPC unlocked for future runs when escorted to the stairs
+    pub escort_unlock: Option<NpcType>,
+}
+
+/// What happened when a prisoner was freed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RescueOutcome {
+    /// The prisoner handed over a stash (gold amount)
+    Stash(u32),
+    /// The prisoner joined as a follower
+    Follower,
+}
+
+/// Roll which prisoner is in a cage, skipping survivors already rescued
+pub fn roll_prisoner_kind(rescued: &[NpcType], rng: &mut impl Rng) -> PrisonerKind {
+    let survivors: Vec<NpcType> = RESCUABLE_NPCS.iter()
+        .copied()
+        .filter(|npc| !rescued.contains(npc))
+        .collect();
+
+    let roll = rng.gen_range(0..10);
+    match roll {
+        0..=3 if !survivors.is_empty() => {
+            PrisonerKind::Survivor(survivors[rng.gen_range(0..survivors.len())])
+        }
+        0..=5 => PrisonerKind::Sellsword,
+        _ => PrisonerKind::Scavenger,
+    }
+}
+
+/// Spawn a caged prisoner at a position
+pub fn spawn_prisoner(world: &mut World, kind: PrisonerKind, pos: Position) -> Entity {
+    world.spawn((
+        pos,
+        Name::new(kind.name()),
+        Renderable::new(CAGE_GLYPH, (170, 150, 120)).with_order(60),
+        Prisoner { kind },
+    ))
+}
+
+/// Maybe spawn a caged prisoner on a floor
+pub fn spawn_prisoner_for_floor(
+    world: &mut World,
+    floor: u32,
+    valid_positions: &[Position],
+    rescued: &[NpcType],
+    rng: &mut impl Rng,
+) -> Option<Entity> {
+    // No prisoners on the first floor
+    if floor < 2 || valid_positions.is_empty() || !rng.gen_bool(PRISONER_CHANCE) {
+        return None;
+    }
+
+    let pos = valid_positions[rng.gen_range(0..valid_positions.len())];
+    let kind = roll_prisoner_kind(rescued, rng);
+    log::info!("Spawned {} on floor {}", kind.name(), floor);
+    Some(spawn_prisoner(world, kind, pos))
+}
+
+/// Turn a freed prisoner into a follower
+pub fn make_follower(world: &mut World, entity: Entity, kind: PrisonerKind, floor: u32) {
+    let (stats, hp, glyph, color, follower) = match kind {
+        PrisonerKind::Sellsword => (
+            Stats::new(12, 10, 6, 10),
+            40 + floor as i32 * 5,
+            '@',
+            (120, 200, 255),
+            Follower { turns_left: Some(SELLSWORD_TURNS), combatant: true, escort_unlock: None },
+        ),
+        PrisonerKind::Survivor(npc_type) => (
+            Stats::new(6, 8, 8, 6),
+            25 + floor as i32 * 3,
+            npc_type.glyph(),
+            npc_type.color(),
+            Follower { turns_left: None, combatant: false, escort_unlock: Some(npc_type) },
+        ),
+        PrisonerKind::Scavenger => return,
+    };
+
+    let _ = world.remove_one::<Prisoner>(entity);
+    let _ = world.insert(entity, (
+        Renderable::new(glyph, color).with_order(50),
+        stats,
+        Health::new(hp),
+        FactionComponent(Faction::Player),
+        BlocksMovement,
+        follower,
+    ));
+}
+
+/// Get the caged prisoner at a position
+pub fn get_prisoner_at(world: &World, pos: Position) -> Option<Entity> {
+    world.query::<(&Position, &Prisoner)>()
+        .iter()
+        .find(|(_, (p, _))| p.x == pos.x && p.y == pos.y)
+        .map(|(entity, _)| entity)
+}
+
+/// Get the follower at a position
+pub fn get_follower_at(world: &World, pos: Position) -> Option<Entity> {
+    world.query::<(&Position, &Follower)>()
+        .iter()
+        .find(|(_, (p, _))| p.x == pos.x && p.y == pos.y)
+        .map(|(entity, _)| entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_rescued_survivors_not_rolled() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..200 {
+            let kind = roll_prisoner_kind(&RESCUABLE_NPCS, &mut rng);
+            assert!(!matches!(kind, PrisonerKind::Survivor(_)));
+        }
+    }
+
+    #[test]
+    fn test_make_follower() {
+        let mut world = World::new();
+        let entity = spawn_prisoner(&mut world, PrisonerKind::Sellsword, Position::new(3, 4));
+        assert_eq!(get_prisoner_at(&world, Position::new(3, 4)), Some(entity));
+
+        make_follower(&mut world, entity, PrisonerKind::Sellsword, 2);
+        assert!(get_prisoner_at(&world, Position::new(3, 4)).is_none());
+        assert_eq!(get_follower_at(&world, Position::new(3, 4)), Some(entity));
+        assert!(world.get::<&Follower>(entity).unwrap().combatant);
+    }
+}
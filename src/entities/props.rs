@@ -0,0 +1,203 @@
+//! Movable props
+//!
+//! Bone piles, standing braziers and statues clutter the floors, and every
+//! one of them can be shifted. Walking into a prop pushes it ahead of you,
+//! and pulling drags it along as you step back. Bone piles are light enough
+//! to hurl, braziers tip over when shoved and spill coals that set any oil
+//! nearby alight, and statues take real strength to move at all.
+
+use hecs::{Entity, World};
+use serde::{Deserialize, Serialize};
+use crate::combat::{force_move, Collision, ForcedMove};
+use crate::ecs::{BlocksMovement, Name, Position, Renderable};
+use crate::world::{Map, TileType};
+
+/// Tiles a thrown bone pile flies
+pub const THROW_RANGE: i32 = 4;
+/// Damage a thrown bone pile does to whatever it hits
+pub const THROW_DAMAGE: i32 = 8;
+
+/// Kinds of movable prop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Prop {
+    BonePile,
+    Brazier,
+    Statue,
+}
+
+impl Prop {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Prop::BonePile => "Bone Pile",
+            Prop::Brazier => "Standing Brazier",
+            Prop::Statue => "Statue",
+        }
+    }
+
+    pub fn glyph(&self) -> char {
+        match self {
+            Prop::BonePile => '%',
+            Prop::Brazier => '♨',
+            Prop::Statue => '♜',
+        }
+    }
+
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            Prop::BonePile => (200, 190, 160),
+            Prop::Brazier => (255, 150, 50),
+            Prop::Statue => (160, 160, 170),
+        }
+    }
+
+    /// Strength needed to shift it at all
+    pub fn min_strength(&self) -> i32 {
+        match self {
+            Prop::Statue => 14,
+            _ => 0,
+        }
+    }
+
+    /// Light enough to pick up and hurl
+    pub fn throwable(&self) -> bool {
+        *self == Prop::BonePile
+    }
+}
+
+/// Can be pushed and pulled about, and maybe thrown
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Pushable(pub Prop);
+
+/// What came of shoving a prop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropMove {
+    /// Slid into a new spot
+    Moved(Position),
+    /// Tipped over, spilling onto this tile
+    Toppled(Position),
+    /// Swallowed by lava, a pit or deep water
+    Lost(TileType),
+    /// Something stopped it dead
+    Stuck,
+}
+
+/// Set a prop down
+pub fn spawn_prop(world: &mut World, pos: Position, prop: Prop) -> Entity {
+    world.spawn((
+        pos,
+        Name::new(prop.name()),
+        Renderable::new(prop.glyph(), prop.color()).with_order(60),
+        Pushable(prop),
+        BlocksMovement,
+    ))
+}
+
+/// Get the prop standing at a position
+pub fn get_prop_at(world: &World, pos: Position) -> Option<(Entity, Prop)> {
+    world.query::<(&Position, &Pushable)>()
+        .iter()
+        .find(|(_, (p, _))| **p == pos)
+        .map(|(entity, (_, pushable))| (entity, pushable.0))
+}
+
+/// Shove a prop one tile in `direction`. Braziers are too top-heavy to slide
+/// and always topple, spilling onto the tile ahead (or their own, against a
+/// wall).
+pub fn shove_prop(world: &mut World, map: &Map, entity: Entity, direction: (i32, i32)) -> PropMove {
+    let Some((from, prop)) = world.query_one_mut::<(&Position, &Pushable)>(entity).ok().map(|(p, k)| (*p, k.0)) else {
+        return PropMove::Stuck;
+    };
+
+    if prop == Prop::Brazier {
+        let ahead = Position::new(from.x + direction.0, from.y + direction.1);
+        let spill = if map.is_walkable(ahead.x, ahead.y) { ahead } else { from };
+        let _ = world.despawn(entity);
+        return PropMove::Toppled(spill);
+    }
+
+    match force_move(world, map, entity, direction, 1).collision {
+        None => PropMove::Moved(Position::new(from.x + direction.0, from.y + direction.1)),
+        Some(Collision::Hazard(hazard)) => {
+            let _ = world.despawn(entity);
+            PropMove::Lost(hazard)
+        }
+        Some(_) => PropMove::Stuck,
+    }
+}
+
+/// Hurl a light prop along `direction`. It flies until something stops it
+/// and breaks apart where it lands.
+pub fn hurl_prop(world: &mut World, map: &Map, entity: Entity, direction: (i32, i32)) -> ForcedMove {
+    let flight = force_move(world, map, entity, direction, THROW_RANGE);
+    let _ = world.despawn(entity);
+    flight
+}
+
+/// Set fire to any oil touching `pos`. The whole slick burns away to ash in
+/// one go; returns the tiles that burned.
+pub fn ignite(map: &mut Map, pos: Position) -> Vec<Position> {
+    let mut burned = Vec::new();
+    let mut frontier = vec![pos];
+    while let Some(at) = frontier.pop() {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let next = Position::new(at.x + dx, at.y + dy);
+                if map.get_tile(next.x, next.y).is_some_and(|t| t.tile_type == TileType::Oil) {
+                    map.set_tile(next.x, next.y, TileType::Ashes);
+                    burned.push(next);
+                    frontier.push(next);
+                }
+            }
+        }
+    }
+    burned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Biome;
+
+    #[test]
+    fn test_props_shift_and_burn() {
+        // A strip of floor from x=1..=8 with a pit at x=9
+        let mut map = Map::new(12, 3, 1, Biome::SunkenCatacombs);
+        for x in 1..=8 {
+            map.set_tile(x, 1, TileType::Floor);
+        }
+        map.set_tile(9, 1, TileType::Pit);
+
+        let mut world = World::new();
+        let bones = spawn_prop(&mut world, Position::new(2, 1), Prop::BonePile);
+
+        // Bones slide along open floor, but not through a wall
+        assert_eq!(shove_prop(&mut world, &map, bones, (1, 0)), PropMove::Moved(Position::new(3, 1)));
+        assert_eq!(get_prop_at(&world, Position::new(3, 1)), Some((bones, Prop::BonePile)));
+        assert_eq!(shove_prop(&mut world, &map, bones, (0, 1)), PropMove::Stuck);
+
+        // A statue shoved over the brink is gone for good
+        let statue = spawn_prop(&mut world, Position::new(8, 1), Prop::Statue);
+        assert_eq!(shove_prop(&mut world, &map, statue, (1, 0)), PropMove::Lost(TileType::Pit));
+        assert!(!world.contains(statue));
+
+        // A brazier tips over instead, and its coals light the slick ahead
+        // but not the separate one further on
+        map.set_tile(6, 1, TileType::Oil);
+        map.set_tile(7, 1, TileType::Oil);
+        map.set_tile(1, 1, TileType::Oil);
+        let brazier = spawn_prop(&mut world, Position::new(4, 1), Prop::Brazier);
+        let PropMove::Toppled(spill) = shove_prop(&mut world, &map, brazier, (1, 0)) else {
+            panic!("braziers always topple");
+        };
+        assert_eq!(spill, Position::new(5, 1));
+        assert_eq!(ignite(&mut map, spill), vec![Position::new(6, 1), Position::new(7, 1)]);
+        assert_eq!(map.get_tile(7, 1).unwrap().tile_type, TileType::Ashes);
+        assert_eq!(map.get_tile(1, 1).unwrap().tile_type, TileType::Oil);
+
+        // Thrown bones fly until they hit the wall, and break apart there
+        let flight = hurl_prop(&mut world, &map, bones, (-1, 0));
+        assert_eq!(flight.to, Position::new(1, 1));
+        assert_eq!(flight.collision, Some(Collision::Wall));
+        assert!(!world.contains(bones));
+    }
+}
@@ -1,4 +1,326 @@
-//! Entity spawning logic
+//! Enemy group spawning and patrols
+//!
+//! Places packs from the group templates in `data::groups`: the leader
+//! first, then its pack in formation around it. Pack members remember their
+//! leader, which is what lets them share aggro and rally around it.
+//!
+//! Lone enemies are then given something to do while the player is away:
+//! some walk the corridors, some stand guard and rotate between posts, and
+//! some sleep through the night. In New Game Plus, elites pick up modifiers.
+//!
+//! Also spawns the merchant caravan some floor events leave under attack.
 
-// Spawning logic will be implemented here
-pub fn placeholder() {}
+use std::collections::{HashMap, VecDeque};
+
+use hecs::{Entity, World};
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::data::{Formation, GroupDef, GroupDefs};
+use crate::ecs::{BlocksMovement, ChestKind, ChestRarity, Enemy, EnemyArchetype, Health, Name, PackLeader, PackMember, Patrol, Position, Sleeper, Stats};
+use crate::progression::{EliteModifier, EliteModifiers, FloorScaling};
+use crate::world::{Map, TileType};
+use super::bosses::BossComponent;
+use super::chests::spawn_chest;
+use super::enemies::{enemies_for_biome, enemy_def, spawn_enemy_scaled};
+use super::npcs::{spawn_npc, NpcType};
+
+/// Chance a floor gets a pack
+const GROUP_CHANCE: f64 = 0.5;
+/// Chance a lone enemy walks the corridors
+const PATROL_CHANCE: f64 = 0.35;
+/// Chance a lone enemy guards a pair of posts
+const GUARD_CHANCE: f64 = 0.15;
+/// Chance a lone enemy without a route sleeps at night
+const SLEEPER_CHANCE: f64 = 0.35;
+/// Furthest a route runs from where the enemy spawned, in steps
+const PATROL_REACH: usize = 12;
+/// Turns a patroller pauses at each end of its beat
+const PATROL_LINGER: u32 = 1;
+/// Turns a guard holds each post
+const GUARD_LINGER: u32 = 12;
+/// Enemies ambushing a caravan
+const CARAVAN_AMBUSHERS: usize = 4;
+
+/// Tiles around a leader in the order a formation fills them
+pub fn formation_tiles(formation: Formation, leader: Position) -> Vec<Position> {
+    // Cardinals, then diagonals, so small packs spread around the leader
+    let around = |r: i32| {
+        let cardinals = [(0, -r), (r, 0), (0, r), (-r, 0)];
+        let diagonals = [(r, -r), (r, r), (-r, r), (-r, -r)];
+        cardinals.into_iter().chain(diagonals)
+            .map(move |(dx, dy)| Position::new(leader.x + dx, leader.y + dy))
+    };
+    match formation {
+        Formation::Ring => around(2).chain(around(1)).collect(),
+        Formation::Cluster => around(1).chain(around(2)).collect(),
+    }
+}
+
+/// Whether a group member can stand on a tile
+fn is_free(pos: Position, map: &Map, world: &World) -> bool {
+    map.is_walkable(pos.x, pos.y)
+        && !world.query::<(&Position, &BlocksMovement)>().iter().any(|(_, (p, _))| *p == pos)
+}
+
+/// Spawn a group with its leader at a position. Members that don't fit
+/// around the leader are left out.
+pub fn spawn_group(
+    world: &mut World,
+    group: &GroupDef,
+    leader_pos: Position,
+    map: &Map,
+    scaling: &FloorScaling,
+) -> Vec<Entity> {
+    let Some(leader_def) = enemy_def(&group.leader) else {
+        log::warn!("Group {} has unknown leader {}", group.name, group.leader);
+        return Vec::new();
+    };
+
+    let leader = spawn_enemy_scaled(world, leader_def, leader_pos, scaling);
+    let _ = world.insert_one(leader, PackLeader);
+    let mut spawned = vec![leader];
+
+    let mut tiles = formation_tiles(group.formation, leader_pos).into_iter();
+    for name in &group.members {
+        let Some(def) = enemy_def(name) else {
+            log::warn!("Group {} has unknown member {}", group.name, name);
+            continue;
+        };
+        let Some(pos) = tiles.by_ref().find(|pos| is_free(*pos, map, world)) else { break };
+        let member = spawn_enemy_scaled(world, def, pos, scaling);
+        let _ = world.insert_one(member, PackMember { leader, rally_strength: group.rally_strength });
+        spawned.push(member);
+    }
+
+    spawned
+}
+
+/// Maybe spawn one group suited to the floor at one of the given positions
+pub fn spawn_groups_for_floor(
+    world: &mut World,
+    groups: &GroupDefs,
+    valid_positions: &[Position],
+    map: &Map,
+    scaling: &FloorScaling,
+    rng: &mut impl Rng,
+) -> Vec<Entity> {
+    let candidates = groups.for_floor(map.biome, scaling.floor);
+    let Some(group) = candidates.choose(rng) else { return Vec::new() };
+    if !rng.gen_bool(GROUP_CHANCE) {
+        return Vec::new();
+    }
+
+    // The leader needs room for its whole pack in formation
+    let mut positions = valid_positions.to_vec();
+    positions.shuffle(rng);
+    let spot = positions.into_iter().find(|pos| {
+        is_free(*pos, map, world)
+            && formation_tiles(group.formation, *pos).into_iter()
+                .filter(|p| is_free(*p, map, world))
+                .count() >= group.members.len()
+    });
+
+    match spot {
+        Some(pos) => {
+            log::info!("Spawned {} on floor {}", group.name, scaling.floor);
+            spawn_group(world, group, pos, map, scaling)
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Walking path from a tile to a random tile within reach that suits a
+/// route end. Paths shorter than a few steps aren't worth walking.
+pub fn patrol_path(
+    map: &Map,
+    from: Position,
+    reach: usize,
+    suits: impl Fn(Position) -> bool,
+    rng: &mut impl Rng,
+) -> Option<Vec<Position>> {
+    // Breadth-first over walkable tiles, remembering how each was reached
+    let mut came_from: HashMap<Position, Position> = HashMap::new();
+    let mut steps: HashMap<Position, usize> = HashMap::from([(from, 0)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(pos) = queue.pop_front() {
+        let dist = steps[&pos];
+        if dist >= reach {
+            continue;
+        }
+        for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+            let next = Position::new(pos.x + dx, pos.y + dy);
+            if map.is_walkable(next.x, next.y) && !steps.contains_key(&next) {
+                steps.insert(next, dist + 1);
+                came_from.insert(next, pos);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let ends: Vec<Position> = steps.iter()
+        .filter(|(pos, dist)| **dist >= 4 && suits(**pos))
+        .map(|(pos, _)| *pos)
+        .collect();
+    let mut end = *ends.choose(rng)?;
+
+    let mut path = vec![end];
+    while let Some(&prev) = came_from.get(&end) {
+        path.push(prev);
+        end = prev;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Give lone enemies patrol routes, guard posts or a bed for the night.
+/// Bosses and packs keep to their own devices.
+pub fn assign_patrols(world: &mut World, map: &Map, rng: &mut impl Rng) {
+    let loners: Vec<(Entity, Position)> = world
+        .query::<(&Position, &Enemy)>()
+        .without::<&BossComponent>()
+        .without::<&PackLeader>()
+        .without::<&PackMember>()
+        .iter()
+        .map(|(entity, (pos, _))| (entity, *pos))
+        .collect();
+
+    let is_corridor = |pos: Position| map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type == TileType::Corridor);
+    let (mut patrols, mut guards, mut sleepers) = (0, 0, 0);
+    for (entity, pos) in loners {
+        let roll: f64 = rng.gen();
+        let route = if roll < PATROL_CHANCE {
+            patrol_path(map, pos, PATROL_REACH, is_corridor, rng).map(|path| Patrol::new(path, PATROL_LINGER))
+        } else if roll < PATROL_CHANCE + GUARD_CHANCE {
+            patrol_path(map, pos, PATROL_REACH, |p| !is_corridor(p), rng).map(|path| Patrol::new(path, GUARD_LINGER))
+        } else {
+            None
+        };
+
+        match route {
+            Some(patrol) => {
+                if patrol.linger == GUARD_LINGER { guards += 1 } else { patrols += 1 }
+                let _ = world.insert_one(entity, patrol);
+            }
+            None if rng.gen_bool(SLEEPER_CHANCE) => {
+                sleepers += 1;
+                let _ = world.insert_one(entity, Sleeper);
+            }
+            None => {}
+        }
+    }
+    log::info!("Assigned {} patrols, {} guards and {} sleepers", patrols, guards, sleepers);
+}
+
+/// Spawn a merchant caravan under attack somewhere mid-floor: the merchant,
+/// a spilled cargo chest, and ambushers ringed around them. Returns where the
+/// caravan stands.
+pub fn spawn_ambushed_caravan(
+    world: &mut World,
+    map: &Map,
+    scaling: &FloorScaling,
+    rng: &mut StdRng,
+    item_id_counter: &mut u64,
+) -> Option<Position> {
+    // Well away from the start, but short of the stairs
+    let spots: Vec<Position> = map.get_npc_spawn_positions(12)
+        .into_iter()
+        .filter(|pos| map.exit_pos.is_none_or(|exit| pos.chebyshev_distance(&exit) >= 8))
+        .filter(|pos| is_free(*pos, map, world))
+        .collect();
+    let &merchant_pos = spots.choose(rng)?;
+    spawn_npc(world, NpcType::Merchant, merchant_pos, rng, scaling.floor, map.biome, item_id_counter);
+
+    let mut tiles = formation_tiles(Formation::Ring, merchant_pos).into_iter();
+    if let Some(pos) = formation_tiles(Formation::Cluster, merchant_pos).into_iter().find(|p| is_free(*p, map, world)) {
+        spawn_chest(world, pos, ChestRarity::Rare, ChestKind::Plain);
+    }
+    let raiders = enemies_for_biome(map.biome);
+    for _ in 0..CARAVAN_AMBUSHERS {
+        let (Some(pos), Some(def)) = (tiles.by_ref().find(|p| is_free(*p, map, world)), raiders.choose(rng)) else { break };
+        spawn_enemy_scaled(world, def, pos, scaling);
+    }
+    log::info!("Spawned an ambushed caravan at {:?} on floor {}", merchant_pos, scaling.floor);
+    Some(merchant_pos)
+}
+
+/// Give the floor's elites New Game Plus modifiers: anything of the Elite
+/// archetype or standing in an elite zone, bosses aside
+pub fn empower_elites(world: &mut World, map: &Map, cycle: u32, rng: &mut impl Rng) {
+    if cycle == 0 {
+        return;
+    }
+    let elites: Vec<Entity> = world
+        .query::<(&Position, &Enemy)>()
+        .without::<&BossComponent>()
+        .iter()
+        .filter(|(_, (pos, enemy))| enemy.archetype == EnemyArchetype::Elite || map.is_elite_zone(**pos))
+        .map(|(entity, _)| entity)
+        .collect();
+
+    for entity in elites {
+        let mods = EliteModifiers::roll(cycle, rng);
+        if mods.has(EliteModifier::Juggernaut) {
+            if let Ok(mut health) = world.get::<&mut Health>(entity) {
+                *health = Health::new(health.max * 3 / 2);
+            }
+        }
+        if mods.has(EliteModifier::Swift) {
+            if let Ok(mut stats) = world.get::<&mut Stats>(entity) {
+                stats.dexterity = stats.dexterity * 3 / 2;
+            }
+        }
+        if let Ok(mut name) = world.get::<&mut Name>(entity) {
+            name.0 = mods.title(&name.0);
+        }
+        let _ = world.insert_one(entity, mods);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formation_tiles() {
+        let leader = Position::new(10, 10);
+        let ring = formation_tiles(Formation::Ring, leader);
+        let cluster = formation_tiles(Formation::Cluster, leader);
+
+        // Rings stand off, clusters huddle; neither stands on the leader
+        assert_eq!(ring[0].chebyshev_distance(&leader), 2);
+        assert_eq!(cluster[0].chebyshev_distance(&leader), 1);
+        assert!(!ring.contains(&leader) && !cluster.contains(&leader));
+
+        // The first few picks land on different sides
+        assert_eq!(ring[0], Position::new(10, 8));
+        assert_eq!(ring[2], Position::new(10, 12));
+    }
+
+    #[test]
+    fn test_patrol_route() {
+        use rand::SeedableRng;
+
+        let map = Map::test_map();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let start = map.start_pos;
+        let path = patrol_path(&map, start, PATROL_REACH, |_| true, &mut rng).unwrap();
+        assert_eq!(path[0], start);
+        assert!(path.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+
+        // Out to the far post, lingering there, and back to the start
+        let mut patrol = Patrol::new(path.clone(), 2);
+        let mut pos = start;
+        let mut seen_far = 0;
+        for _ in 0..(path.len() * 2 + 4) {
+            patrol.arrive(pos);
+            pos = patrol.target().unwrap();
+            if pos == *path.last().unwrap() {
+                seen_far += 1;
+            }
+        }
+        assert_eq!(seen_far, 3);
+        assert_eq!(pos, start);
+    }
+}
@@ -0,0 +1,45 @@
+//! The Hollow Warden
+//!
+//! Players who linger too long on one floor are hunted. After enough turns
+//! the Warden rises at the far end of the floor and tracks the player down
+//! wherever they go, through doors and over hazards. It is hard to kill but
+//! carries loot from deeper in the dungeon.
+
+use hecs::{Entity, World};
+use serde::{Deserialize, Serialize};
+use crate::ecs::{EnemyArchetype, Position, Stats};
+use crate::progression::FloorScaling;
+use super::enemies::{spawn_enemy_scaled, EnemyDef};
+
+/// Turns on a floor before the Warden comes
+pub const STALKER_TURNS: u32 = 1200;
+/// Turns before the Warden comes that the player is warned
+pub const STALKER_WARNING_TURNS: u32 = 150;
+/// Floors deeper than the current one the Warden's loot is rolled for
+pub const STALKER_LOOT_DEPTH: u32 = 3;
+
+/// Hunts the player across the whole floor
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Stalker;
+
+pub const HOLLOW_WARDEN: EnemyDef = EnemyDef {
+    name: "The Hollow Warden",
+    glyph: 'W',
+    fg: (170, 190, 230),
+    archetype: EnemyArchetype::Elite,
+    stats: Stats { strength: 16, dexterity: 10, intelligence: 8, vitality: 18 },
+    hp: 150,
+    xp_value: 250,
+    hazard_immune: true,
+    raises_dead: false,
+};
+
+/// Spawn the Warden, already on the hunt
+pub fn spawn_stalker(world: &mut World, pos: Position, scaling: &FloorScaling) -> Entity {
+    let warden = spawn_enemy_scaled(world, &HOLLOW_WARDEN, pos, scaling);
+    let _ = world.insert_one(warden, Stalker);
+    if let Ok(mut ai) = world.get::<&mut crate::ecs::AI>(warden) {
+        ai.state = crate::ecs::AIState::Chase;
+    }
+    warden
+}
@@ -0,0 +1,185 @@
+//! Errors
+//!
+//! What can go wrong outside the dungeon itself: reading and writing saves
+//! and the profile, loading data files, opening the sound device or the log
+//! file. None of it is worth crashing over. Each failure is logged under a
+//! target for the part of the game it came from, and the ones the player
+//! should hear about wait in a popup until they dismiss it.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+/// The part of the game an error came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Area {
+    Save,
+    Profile,
+    Data,
+    Audio,
+    Log,
+    Preview,
+    Screenshot,
+}
+
+impl Area {
+    /// What the player knows it as
+    pub fn noun(self) -> &'static str {
+        match self {
+            Area::Save => "save",
+            Area::Profile => "profile",
+            Area::Data => "data file",
+            Area::Audio => "sound",
+            Area::Log => "log",
+            Area::Preview => "floor preview",
+            Area::Screenshot => "screenshot",
+        }
+    }
+
+    /// The log target its errors go under
+    pub fn target(self) -> &'static str {
+        match self {
+            Area::Save => "hollowdeep::save",
+            Area::Profile => "hollowdeep::profile",
+            Area::Data => "hollowdeep::data",
+            Area::Audio => "hollowdeep::audio",
+            Area::Log => "hollowdeep::log",
+            Area::Preview => "hollowdeep::preview",
+            Area::Screenshot => "hollowdeep::screenshot",
+        }
+    }
+}
+
+/// Something that went wrong, worded for the player
+#[derive(Debug, Error)]
+pub enum Error {
+    /// A file couldn't be read, written or removed
+    #[error("Failed to {verb} {}: {}", area.noun(), io_reason(source))]
+    Io {
+        area: Area,
+        verb: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    /// A file was read but doesn't hold what it should
+    #[error("Failed to read {} {}: {message}", area.noun(), file_name(path))]
+    Parse { area: Area, path: PathBuf, message: String },
+    /// A save from a version this build can't load
+    #[error("Save is from version {found}, this build loads version {expected}")]
+    SaveVersion { expected: u32, found: u32 },
+    /// A save that can't be written out or played from
+    #[error("Invalid save: {0}")]
+    InvalidSave(String),
+    /// The sound device couldn't be opened, or a sound couldn't be loaded
+    #[error("No sound: {0}")]
+    Audio(String),
+}
+
+impl Error {
+    pub fn io(area: Area, verb: &'static str, path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Error::Io { area, verb, path: path.into(), source }
+    }
+
+    pub fn parse(area: Area, path: impl Into<PathBuf>, error: impl std::fmt::Display) -> Self {
+        Error::Parse { area, path: path.into(), message: error.to_string() }
+    }
+
+    pub fn area(&self) -> Area {
+        match self {
+            Error::Io { area, .. } | Error::Parse { area, .. } => *area,
+            Error::SaveVersion { .. } | Error::InvalidSave(_) => Area::Save,
+            Error::Audio(_) => Area::Audio,
+        }
+    }
+
+    /// Write the error to the log under its area's target, with the file it
+    /// concerns
+    pub fn log(&self) {
+        let target = self.area().target();
+        match self {
+            Error::Io { path, source, .. } => {
+                log::error!(target: target, "{} [path={} kind={:?}]", self, path.display(), source.kind());
+            }
+            Error::Parse { path, .. } => log::error!(target: target, "{} [path={}]", self, path.display()),
+            _ => log::error!(target: target, "{}", self),
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or(path.as_os_str()).to_string_lossy().into_owned()
+}
+
+/// How an IO error reads to the player
+fn io_reason(error: &io::Error) -> String {
+    match error.kind() {
+        io::ErrorKind::StorageFull => "disk full".to_string(),
+        io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+        io::ErrorKind::NotFound => "file not found".to_string(),
+        io::ErrorKind::ReadOnlyFilesystem => "read-only file system".to_string(),
+        _ => error.to_string(),
+    }
+}
+
+/// Errors waiting for the player to see and dismiss, oldest first
+#[derive(Debug, Default)]
+pub struct ErrorPopups(Vec<Error>);
+
+impl ErrorPopups {
+    /// Queue an error, unless the same one is already waiting: a full disk
+    /// fails every save the same way
+    pub fn push(&mut self, error: Error) {
+        let message = error.to_string();
+        if self.0.iter().all(|waiting| waiting.to_string() != message) {
+            self.0.push(error);
+        }
+    }
+
+    /// The error on show
+    pub fn current(&self) -> Option<&Error> {
+        self.0.first()
+    }
+
+    /// Dismiss the error on show, bringing up the next
+    pub fn dismiss(&mut self) {
+        if !self.0.is_empty() {
+            self.0.remove(0);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_read_plainly_and_queue_once() {
+        let full = || Error::io(Area::Save, "write", "saves/slot_0.json", io::Error::from(io::ErrorKind::StorageFull));
+        assert_eq!(full().to_string(), "Failed to write save: disk full");
+        assert_eq!(full().area().target(), "hollowdeep::save");
+
+        let bad = Error::parse(Area::Data, "assets/data/items.ron", "expected `(`");
+        assert_eq!(bad.to_string(), "Failed to read data file items.ron: expected `(`");
+
+        let mut popups = ErrorPopups::default();
+        popups.push(full());
+        popups.push(bad);
+        popups.push(full());
+        assert_eq!(popups.len(), 2);
+        assert_eq!(popups.current().unwrap().to_string(), "Failed to write save: disk full");
+        popups.dismiss();
+        assert_eq!(popups.current().unwrap().area(), Area::Data);
+        popups.dismiss();
+        assert!(popups.is_empty());
+    }
+}
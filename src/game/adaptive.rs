@@ -0,0 +1,177 @@
+//! Adaptive difficulty
+//!
+//! An opt-in layer that watches how the run is going and leans on the
+//! spawner and the loot generator to match. Close calls, deaths on the same
+//! floor in earlier runs and slow clears ease it off: fewer enemies, and
+//! loot rolled as if from deeper down. Quick, clean clears press harder with
+//! more enemies. Every shift is announced, and the run summary lists them
+//! all with their reasons.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// Dropping below this share of max HP is a close call
+const CLOSE_CALL_HEALTH: f32 = 0.25;
+/// Climbing back above this share of max HP ends the danger
+const RECOVERED_HEALTH: f32 = 0.5;
+/// A floor cleared in this many turns or fewer was quick
+const QUICK_CLEAR_TURNS: u32 = 300;
+/// A floor that took this many turns or more was slow going
+const SLOW_CLEAR_TURNS: u32 = 1200;
+/// Floors the pressure is judged over
+const WINDOW: usize = 3;
+/// Furthest the pressure goes either way
+const MAX_PRESSURE: i32 = 2;
+/// Enemies added (or taken away) per step of pressure
+const ENEMIES_PER_STEP: f32 = 0.15;
+/// Floors deeper loot is rolled from per step of easing off
+const LOOT_FLOORS_PER_STEP: u32 = 2;
+
+/// A change in how hard the dungeon pushes, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PressureShift {
+    /// Floor whose clear caused the shift
+    pub floor: u32,
+    /// Pressure after the shift
+    pub pressure: i32,
+    pub reason: String,
+}
+
+impl PressureShift {
+    pub fn describe(&self) -> &'static str {
+        describe(self.pressure)
+    }
+}
+
+/// How hard the dungeon pushes, judged from recent floors (saved with the run)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdaptiveDifficulty {
+    /// From -2 (easing right off) to 2 (pressing hard)
+    pressure: i32,
+    /// Close calls on the current floor
+    close_calls: u32,
+    /// Whether the player is still low from the last close call
+    in_danger: bool,
+    /// How recent floors went, newest last
+    recent: VecDeque<i32>,
+    shifts: Vec<PressureShift>,
+}
+
+impl AdaptiveDifficulty {
+    pub fn pressure(&self) -> i32 {
+        self.pressure
+    }
+
+    /// Every change in pressure this run, oldest first
+    pub fn shifts(&self) -> &[PressureShift] {
+        &self.shifts
+    }
+
+    pub fn describe(&self) -> &'static str {
+        describe(self.pressure)
+    }
+
+    /// Watch the player's health after a turn, counting each dip to a
+    /// sliver of health once
+    pub fn observe_health(&mut self, current: i32, max: i32) {
+        let share = current as f32 / max.max(1) as f32;
+        if share < CLOSE_CALL_HEALTH && !self.in_danger {
+            self.close_calls += 1;
+            self.in_danger = true;
+        } else if share > RECOVERED_HEALTH {
+            self.in_danger = false;
+        }
+    }
+
+    /// Judge a cleared floor, returning the shift if the pressure changed
+    pub fn floor_cleared(&mut self, floor: u32, turns: u32, deaths: u32) -> Option<&PressureShift> {
+        let mut score = 0;
+        let mut reasons = Vec::new();
+        match self.close_calls {
+            0 => {}
+            1 => reasons.push("a close call".to_string()),
+            n => reasons.push(format!("{} close calls", n)),
+        }
+        score -= self.close_calls.min(2) as i32;
+        if deaths > 0 {
+            score -= 1;
+            reasons.push(format!("{} earlier death{} here", deaths, if deaths == 1 { "" } else { "s" }));
+        }
+        if turns >= SLOW_CLEAR_TURNS {
+            score -= 1;
+            reasons.push(format!("a slow clear ({} turns)", turns));
+        } else if turns <= QUICK_CLEAR_TURNS && self.close_calls == 0 {
+            score += 1;
+            reasons.push(format!("a quick clear ({} turns)", turns));
+        }
+        if self.close_calls == 0 && deaths == 0 {
+            score += 1;
+            reasons.push("no close calls".to_string());
+        }
+        self.close_calls = 0;
+
+        self.recent.push_back(score);
+        if self.recent.len() > WINDOW {
+            self.recent.pop_front();
+        }
+        let average = self.recent.iter().sum::<i32>() as f32 / self.recent.len() as f32;
+        let pressure = (average.round() as i32).clamp(-MAX_PRESSURE, MAX_PRESSURE);
+        if pressure == self.pressure {
+            return None;
+        }
+        self.pressure = pressure;
+        self.shifts.push(PressureShift { floor, pressure, reason: reasons.join(", ") });
+        self.shifts.last()
+    }
+
+    /// Multiplier on how many enemies a floor spawns
+    pub fn enemy_count_mult(&self) -> f32 {
+        1.0 + self.pressure as f32 * ENEMIES_PER_STEP
+    }
+
+    /// The floor loot is rolled for, deeper while easing off
+    pub fn loot_floor(&self, floor: u32) -> u32 {
+        floor + (-self.pressure).max(0) as u32 * LOOT_FLOORS_PER_STEP
+    }
+}
+
+/// What a level of pressure means, for messages and summaries
+fn describe(pressure: i32) -> &'static str {
+    match pressure {
+        i32::MIN..=-2 => "easing right off",
+        -1 => "easing off",
+        0 => "holding steady",
+        1 => "pressing",
+        _ => "pressing hard",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pressure_follows_recent_floors() {
+        let mut adaptive = AdaptiveDifficulty::default();
+
+        // Quick, clean clears press harder
+        assert!(adaptive.floor_cleared(1, 200, 0).is_some());
+        assert_eq!(adaptive.pressure(), 2);
+        assert!(adaptive.enemy_count_mult() > 1.0);
+        assert_eq!(adaptive.loot_floor(4), 4);
+
+        // A dip to a sliver of health counts once until the player recovers
+        adaptive.observe_health(10, 100);
+        adaptive.observe_health(5, 100);
+        adaptive.observe_health(80, 100);
+        adaptive.observe_health(20, 100);
+        let shift = adaptive.floor_cleared(2, 1500, 1).cloned().unwrap();
+        assert_eq!(shift.floor, 2);
+        assert_eq!(shift.reason, "2 close calls, 1 earlier death here, a slow clear (1500 turns)");
+        assert!(adaptive.pressure() < 0);
+        assert!(adaptive.enemy_count_mult() < 1.0);
+        assert!(adaptive.loot_floor(4) > 4);
+        assert_eq!(adaptive.shifts().len(), 2);
+    }
+}
@@ -0,0 +1,202 @@
+//! Balance report
+//!
+//! A dev check on enemy scaling. Standard player builds square up against
+//! every enemy template on each floor its biomes cover, and the blows each
+//! side needs to bring the other down (turns to kill, turns to die) go in a
+//! table. A fight far harder or easier than the floor's usual one for that
+//! build is flagged, so a data change that turns an enemy into a wall or a
+//! pushover shows up. Run with `--balance-report`.
+
+use crate::combat::{expected_damage, EquipmentBonuses};
+use crate::data::{BalanceConfig, EnemyTemplates};
+use crate::ecs::{EnemyArchetype, Stats};
+use crate::items::{ArmorType, EquipSlot, WeaponType};
+use crate::progression::{Difficulty, FloorScaling};
+use crate::world::generation::biome_for_floor;
+use super::threat::{blows_to_kill, Threat, DEADLY_RATIO};
+
+/// Deepest floor the report covers
+const REPORT_FLOORS: u32 = 20;
+/// Max HP a new character starts with
+const PLAYER_BASE_HP: i32 = 100;
+/// Max HP each point of vitality adds
+const HP_PER_VITALITY: i32 = 5;
+/// Armor pieces a player is assumed to wear
+const WORN_SLOTS: [EquipSlot; 3] = [EquipSlot::Body, EquipSlot::Head, EquipSlot::Feet];
+
+/// A typical way to spend stat points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StandardBuild {
+    /// Every point into strength
+    Duelist,
+    /// Every point into dexterity
+    Skirmisher,
+    /// Every point into vitality
+    Bulwark,
+}
+
+impl StandardBuild {
+    pub const ALL: [StandardBuild; 3] = [StandardBuild::Duelist, StandardBuild::Skirmisher, StandardBuild::Bulwark];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StandardBuild::Duelist => "Duelist",
+            StandardBuild::Skirmisher => "Skirmisher",
+            StandardBuild::Bulwark => "Bulwark",
+        }
+    }
+
+    /// Stats by a floor, having levelled once a floor with a point each time
+    pub fn stats(&self, floor: u32) -> Stats {
+        let points = floor.saturating_sub(1) as i32;
+        let mut stats = Stats::player_base();
+        match self {
+            StandardBuild::Duelist => stats.strength += points,
+            StandardBuild::Skirmisher => stats.dexterity += points,
+            StandardBuild::Bulwark => stats.vitality += points,
+        }
+        stats
+    }
+
+    pub fn max_hp(&self, floor: u32) -> i32 {
+        let base = Stats::player_base().vitality;
+        PLAYER_BASE_HP + (self.stats(floor).vitality - base) * HP_PER_VITALITY
+    }
+
+    /// Common gear found by a floor: a sword and leather armor, scaled
+    /// the way loot scales with depth
+    pub fn gear(floor: u32) -> EquipmentBonuses {
+        let depth = floor.max(1) as i32 - 1;
+        EquipmentBonuses {
+            weapon_damage: WeaponType::Sword.base_damage() + depth / 2,
+            armor: WORN_SLOTS.iter().map(|slot| ArmorType::Leather.base_armor(*slot) + depth / 3).sum(),
+            ..Default::default()
+        }
+    }
+}
+
+/// How one build fares against one enemy on one floor
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceRow {
+    pub enemy: String,
+    pub archetype: EnemyArchetype,
+    pub floor: u32,
+    pub build: StandardBuild,
+    /// Player blows needed to kill the enemy
+    pub turns_to_kill: f32,
+    /// Enemy blows needed to kill the player
+    pub turns_to_die: f32,
+    /// How much harder than the floor's median fight for this build it is
+    pub versus_median: f32,
+}
+
+impl BalanceRow {
+    pub fn threat(&self) -> Threat {
+        Threat::rate(self.turns_to_kill, self.turns_to_die)
+    }
+
+    /// Share of the player's blows to the enemy's; higher is harder
+    fn ratio(&self) -> f32 {
+        self.turns_to_kill / self.turns_to_die.max(0.1)
+    }
+
+    /// A regular enemy this many times harder or easier than its
+    /// neighbours. Elites, tanks and swarms are meant to stand out.
+    pub fn is_outlier(&self) -> bool {
+        let regular = !matches!(
+            self.archetype,
+            EnemyArchetype::Elite | EnemyArchetype::Boss | EnemyArchetype::Tank | EnemyArchetype::Swarm,
+        );
+        regular && (self.versus_median >= DEADLY_RATIO || self.versus_median <= 1.0 / DEADLY_RATIO)
+    }
+}
+
+/// Square every build up against every template on the floors it can appear
+pub fn balance_rows(templates: &EnemyTemplates, balance: &BalanceConfig, difficulty: Difficulty) -> Vec<BalanceRow> {
+    let mut rows = Vec::new();
+    for floor in 1..=REPORT_FLOORS {
+        let biome = biome_for_floor(floor);
+        let scaling = FloorScaling::with_balance(floor, difficulty, balance);
+        let gear = StandardBuild::gear(floor);
+        for template in templates.templates.iter().filter(|t| t.biomes.contains(&biome)) {
+            let enemy = Stats {
+                strength: scaling.scale_stat(template.stats.strength),
+                dexterity: scaling.scale_stat(template.stats.dexterity),
+                intelligence: scaling.scale_stat(template.stats.intelligence),
+                vitality: scaling.scale_stat(template.stats.vitality),
+            };
+            let enemy_hp = scaling.scale_enemy_hp(template.hp);
+            let bare = EquipmentBonuses::default();
+            for build in StandardBuild::ALL {
+                let player = build.stats(floor);
+                let dealt = expected_damage(&player, &enemy, &gear, &bare);
+                let taken = expected_damage(&enemy, &player, &bare, &gear);
+                rows.push(BalanceRow {
+                    enemy: template.name.clone(),
+                    archetype: template.archetype,
+                    floor,
+                    build,
+                    turns_to_kill: blows_to_kill(enemy_hp, dealt),
+                    turns_to_die: blows_to_kill(build.max_hp(floor), taken),
+                    versus_median: 1.0,
+                });
+            }
+        }
+    }
+
+    // Measure each fight against the median for its floor and build
+    let medians: Vec<f32> = rows.iter().map(|row| {
+        let mut peers: Vec<f32> = rows.iter()
+            .filter(|r| r.floor == row.floor && r.build == row.build)
+            .map(BalanceRow::ratio)
+            .collect();
+        peers.sort_by(f32::total_cmp);
+        peers[peers.len() / 2]
+    }).collect();
+    for (row, median) in rows.iter_mut().zip(medians) {
+        row.versus_median = row.ratio() / median.max(f32::EPSILON);
+    }
+    rows
+}
+
+/// The rows as a plain-text table, outliers marked with `!`
+pub fn balance_report(rows: &[BalanceRow]) -> String {
+    let mut out = format!(
+        "{:<18} {:>5}  {:<10} {:>6} {:>6} {:>7}  {}\n",
+        "Enemy", "Floor", "Build", "TTK", "TTD", "vs med", "Threat",
+    );
+    for row in rows {
+        out.push_str(&format!(
+            "{:<18} {:>5}  {:<10} {:>6.1} {:>6.1} {:>6.1}x  {}{}\n",
+            row.enemy,
+            row.floor,
+            row.build.name(),
+            row.turns_to_kill,
+            row.turns_to_die,
+            row.versus_median,
+            row.threat().label(),
+            if row.is_outlier() { " !" } else { "" },
+        ));
+    }
+    let outliers = rows.iter().filter(|r| r.is_outlier()).count();
+    out.push_str(&format!("\n{} fights, {} outliers\n", rows.len(), outliers));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::enemies::default_enemy_templates;
+
+    #[test]
+    fn test_balance_rows() {
+        let rows = balance_rows(&default_enemy_templates(), &BalanceConfig::default(), Difficulty::Normal);
+        assert!(!rows.is_empty());
+        assert!(rows.iter().all(|r| r.turns_to_kill > 0.0 && r.turns_to_die > 0.0));
+
+        // A bulwark outlasts a duelist against the same foe
+        let against = |build| rows.iter().find(|r| r.enemy == "Skeleton" && r.floor == 5 && r.build == build).unwrap();
+        assert!(against(StandardBuild::Bulwark).turns_to_die > against(StandardBuild::Duelist).turns_to_die);
+        assert!(balance_report(&rows).contains("Skeleton"));
+    }
+}
@@ -0,0 +1,102 @@
+//! Boss rush
+//!
+//! Unlocked by a first victory. Every biome boss is fought back to back,
+//! with a short interlude before each one: a merchant, a shrine, some gold
+//! and stat points, then straight down to the next arena. Runs are scored
+//! on bosses felled and time taken.
+
+use serde::{Deserialize, Serialize};
+use crate::entities::BossType;
+
+/// Bosses fought in a boss rush, in order
+pub const BOSS_RUSH_ORDER: [BossType; 5] = BossType::ALL;
+/// Stat points granted at each interlude
+pub const BOSS_RUSH_STAT_POINTS: u32 = 5;
+/// Gold granted at each interlude, times the number of the boss ahead
+pub const BOSS_RUSH_GOLD: u32 = 250;
+
+/// Where a boss rush is up to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BossRush {
+    /// Index of the next or current boss in `BOSS_RUSH_ORDER`
+    pub stage: usize,
+    /// Resting between bosses rather than fighting one
+    pub interlude: bool,
+    /// Seconds spent playing the rush
+    pub elapsed: f32,
+}
+
+impl Default for BossRush {
+    fn default() -> Self {
+        // Every rush opens with a chance to gear up
+        Self { stage: 0, interlude: true, elapsed: 0.0 }
+    }
+}
+
+impl BossRush {
+    /// The boss being fought, or None during an interlude
+    pub fn boss(&self) -> Option<BossType> {
+        if self.interlude { None } else { BOSS_RUSH_ORDER.get(self.stage).copied() }
+    }
+
+    /// The boss being fought or prepared for
+    pub fn next_boss(&self) -> BossType {
+        BOSS_RUSH_ORDER[self.stage.min(BOSS_RUSH_ORDER.len() - 1)]
+    }
+
+    /// Floor the current stage is set on (interludes share their boss's floor)
+    pub fn floor(&self) -> u32 {
+        self.next_boss().floor()
+    }
+
+    /// Whether this is the last boss's arena
+    pub fn is_final(&self) -> bool {
+        !self.interlude && self.stage + 1 >= BOSS_RUSH_ORDER.len()
+    }
+
+    /// Bosses felled so far
+    pub fn bosses_defeated(&self) -> u32 {
+        self.stage as u32
+    }
+
+    /// Move from an interlude to its boss, or from a boss to the next interlude
+    pub fn advance(&mut self) {
+        if !self.interlude {
+            self.stage += 1;
+        }
+        self.interlude = !self.interlude;
+    }
+
+    /// Whole seconds spent on the rush
+    pub fn seconds(&self) -> u32 {
+        self.elapsed as u32
+    }
+}
+
+/// Minutes and seconds, for rush timers
+pub fn format_rush_time(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boss_rush_stages() {
+        let mut rush = BossRush::default();
+        assert_eq!(rush.boss(), None);
+        assert_eq!(rush.floor(), 5);
+
+        // Interlude, boss, interlude, boss... ending on the Harbinger
+        let mut fought = Vec::new();
+        while !rush.is_final() {
+            rush.advance();
+            fought.extend(rush.boss());
+        }
+        assert_eq!(fought, BOSS_RUSH_ORDER.to_vec());
+        assert_eq!(rush.floor(), 25);
+        assert_eq!(rush.bosses_defeated(), 4);
+        assert_eq!(format_rush_time(754), "12:34");
+    }
+}
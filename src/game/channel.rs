@@ -0,0 +1,117 @@
+//! Channeled actions
+//!
+//! Some jobs take several turns of concentration: binding wounds, working a
+//! lock. Like a rest, the turns play out one at a time so the progress can be
+//! watched. Getting hurt breaks concentration and the work so far is lost.
+
+use crate::ecs::Position;
+
+/// Real time between channel turns, so the progress is visible
+pub const CHANNEL_TURN_SECONDS: f32 = 0.1;
+/// Turns spent binding wounds
+pub const BANDAGE_TURNS: u32 = 4;
+/// Stamina spent to start bandaging
+pub const BANDAGE_STAMINA_COST: i32 = 10;
+/// Percent of max HP restored by a finished bandage
+const BANDAGE_HEAL_PERCENT: i32 = 20;
+
+/// What the player is working at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelKind {
+    /// Binding wounds: heals and stops bleeding
+    Bandage,
+    /// Picking the locked door at a position
+    PickLock(Position),
+}
+
+impl ChannelKind {
+    /// Label for the progress bar
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChannelKind::Bandage => "Bandage",
+            ChannelKind::PickLock(_) => "Lockpick",
+        }
+    }
+
+    /// Message when the work starts
+    pub fn start_message(&self) -> &'static str {
+        match self {
+            ChannelKind::Bandage => "You start binding your wounds...",
+            ChannelKind::PickLock(_) => "You kneel and start working at the lock...",
+        }
+    }
+
+    /// Message when the work is broken off
+    pub fn interrupted_message(&self) -> &'static str {
+        match self {
+            ChannelKind::Bandage => "Pain jolts through you - the bandage falls loose!",
+            ChannelKind::PickLock(_) => "You're struck mid-work and lose your place in the lock!",
+        }
+    }
+}
+
+/// HP restored by a finished bandage
+pub fn bandage_heal(max_hp: i32) -> i32 {
+    (max_hp * BANDAGE_HEAL_PERCENT / 100).max(5)
+}
+
+/// Turns it takes to pick a lock; nimble fingers work faster
+pub fn lockpick_turns(dexterity: i32) -> u32 {
+    (8 - (dexterity - 10) / 3).clamp(3, 12) as u32
+}
+
+/// Multi-turn action in progress
+#[derive(Debug, Clone)]
+pub struct Channel {
+    pub kind: ChannelKind,
+    /// Turns worked so far
+    pub turns: u32,
+    /// Turns needed to finish
+    pub turns_needed: u32,
+    /// HP after the last turn, to spot damage
+    pub last_hp: i32,
+    /// Real time accumulated toward the next turn
+    pub timer: f32,
+}
+
+impl Channel {
+    pub fn new(kind: ChannelKind, turns_needed: u32, hp: i32) -> Self {
+        Self {
+            kind,
+            turns: 0,
+            turns_needed: turns_needed.max(1),
+            last_hp: hp,
+            timer: 0.0,
+        }
+    }
+
+    /// Fraction of the work done
+    pub fn progress(&self) -> f32 {
+        (self.turns as f32 / self.turns_needed as f32).clamp(0.0, 1.0)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.turns >= self.turns_needed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_progress() {
+        let mut channel = Channel::new(ChannelKind::Bandage, BANDAGE_TURNS, 20);
+        assert_eq!(channel.progress(), 0.0);
+        channel.turns = 2;
+        assert_eq!(channel.progress(), 0.5);
+        channel.turns = BANDAGE_TURNS;
+        assert!(channel.is_done());
+
+        // Dexterous hands are quicker, within bounds
+        assert!(lockpick_turns(19) < lockpick_turns(10));
+        assert_eq!(lockpick_turns(100), 3);
+        assert_eq!(lockpick_turns(-50), 12);
+        assert_eq!(bandage_heal(10), 5);
+    }
+}
@@ -0,0 +1,145 @@
+//! Collapsing floors
+//!
+//! Some bosses bring their floor down with them. The stairs on the dais fall
+//! in, a fissure opens back at the entrance, and after a few turns of
+//! rumbling the ground starts giving way into pits, spreading out from the
+//! dais a tile a turn. The player has a set number of turns to get down the
+//! fissure; if the floor goes first, they fall through to the next one
+//! battered and injured.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::ecs::Position;
+use crate::world::{Map, TileType};
+
+/// Turns to spare beyond the walk from the dais to the fissure
+pub const COLLAPSE_SLACK_TURNS: u32 = 12;
+/// Damage from falling through with the floor
+pub const COLLAPSE_FALL_DAMAGE: i32 = 20;
+/// Seconds the screen shakes after each turn of the collapse
+pub const SHAKE_SECONDS: f32 = 0.4;
+/// Turns the floor only rumbles before it starts to give way
+const GRACE_TURNS: u32 = 3;
+/// Chance each turn that a tile the collapse has reached gives way
+const CRUMBLE_CHANCE: f64 = 0.35;
+/// Turns left at which the player is warned again
+const WARNING_TURNS: [u32; 2] = [10, 5];
+
+/// A floor coming down around the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Collapse {
+    /// Where it started: the dais the boss fell on
+    pub origin: Position,
+    /// Turns until the whole floor goes
+    pub turns_left: u32,
+    /// Turns since it started
+    pub elapsed: u32,
+}
+
+impl Collapse {
+    /// Start bringing the floor down from `origin`, with time enough to walk
+    /// to `exit` and some to spare
+    pub fn new(origin: Position, exit: Position) -> Self {
+        let walk = origin.chebyshev_distance(&exit).max(0) as u32;
+        Self { origin, turns_left: walk + COLLAPSE_SLACK_TURNS, elapsed: 0 }
+    }
+
+    /// How far from the dais the ground is giving way
+    pub fn reach(&self) -> i32 {
+        self.elapsed.saturating_sub(GRACE_TURNS) as i32
+    }
+
+    /// Whether the whole floor has gone
+    pub fn expired(&self) -> bool {
+        self.turns_left == 0
+    }
+
+    /// Whether this many turns left is worth a fresh warning
+    pub fn warning_due(&self) -> bool {
+        WARNING_TURNS.contains(&self.turns_left)
+    }
+
+    /// Run one turn of the collapse: ground within reach gives way into pits,
+    /// sparing the stairs and anything `spared` says to. Returns the tiles
+    /// that fell.
+    pub fn crumble(&mut self, map: &mut Map, rng: &mut impl Rng, spared: impl Fn(Position) -> bool) -> Vec<Position> {
+        self.elapsed += 1;
+        self.turns_left = self.turns_left.saturating_sub(1);
+
+        let reach = self.reach();
+        if self.elapsed <= GRACE_TURNS {
+            return Vec::new();
+        }
+        let mut fallen = Vec::new();
+        for y in self.origin.y - reach..=self.origin.y + reach {
+            for x in self.origin.x - reach..=self.origin.x + reach {
+                let pos = Position::new(x, y);
+                let ground = map.get_tile(x, y)
+                    .is_some_and(|t| t.is_walkable() && t.tile_type != TileType::StairsDown);
+                if ground && !spared(pos) && rng.gen_bool(CRUMBLE_CHANCE) {
+                    map.set_tile(x, y, TileType::Pit);
+                    fallen.push(pos);
+                }
+            }
+        }
+        fallen
+    }
+}
+
+/// How far to jolt the map while the screen shakes, from the seconds left
+pub fn shake_offset(remaining: f32) -> (i32, i32) {
+    if remaining <= 0.0 {
+        return (0, 0);
+    }
+    // Flick between a few offsets, settling as the shake dies away
+    let step = (remaining * 30.0) as i32;
+    let size = if remaining > SHAKE_SECONDS / 2.0 { 1 } else { step % 2 };
+    match step % 4 {
+        0 => (size, 0),
+        1 => (-size, 0),
+        2 => (0, size),
+        _ => (-size, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::Biome;
+
+    #[test]
+    fn test_collapse_spreads_from_the_dais() {
+        let mut map = Map::new(40, 10, 15, Biome::HollowCathedral);
+        for x in 1..39 {
+            map.set_tile(x, 5, TileType::Floor);
+        }
+        let dais = Position::new(2, 5);
+        let exit = Position::new(37, 5);
+        map.set_tile(exit.x, exit.y, TileType::StairsDown);
+        let player = Position::new(20, 5);
+
+        let mut collapse = Collapse::new(dais, exit);
+        assert_eq!(collapse.turns_left, 35 + COLLAPSE_SLACK_TURNS);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(4);
+
+        // Nothing gives way while the floor only rumbles
+        for _ in 0..GRACE_TURNS {
+            assert!(collapse.crumble(&mut map, &mut rng, |_| false).is_empty());
+        }
+        while !collapse.expired() {
+            let reach = collapse.reach() + 1;
+            for pos in collapse.crumble(&mut map, &mut rng, |pos| pos == player) {
+                assert!(pos.chebyshev_distance(&dais) <= reach);
+            }
+        }
+
+        // The stairs and the spared tile stand, and much of the rest is gone
+        assert_eq!(map.get_tile(exit.x, exit.y).unwrap().tile_type, TileType::StairsDown);
+        assert_eq!(map.get_tile(player.x, player.y).unwrap().tile_type, TileType::Floor);
+        let pits = (1..39).filter(|x| map.get_tile(*x, 5).unwrap().tile_type == TileType::Pit).count();
+        assert!(pits > 30);
+        assert_eq!(shake_offset(0.0), (0, 0));
+    }
+}
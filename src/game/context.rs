@@ -0,0 +1,75 @@
+//! Context actions
+//!
+//! What the player can do right where they stand, for the action bar under
+//! the map: stairs and shrines underfoot, loot and bodies within reach,
+//! doors and locks next to them. Each action knows the key that does it.
+
+/// Something the player can do from where they stand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContextAction {
+    Descend,
+    /// Shrine or altar underfoot
+    Pray { altar: bool },
+    /// The one item within reach
+    PickUp(String),
+    /// Several items within reach
+    PickUpPile(usize),
+    SearchCorpse,
+    /// A chest next to the player, opened by walking into it
+    OpenChest,
+    /// Someone next to the player, talked to by walking into them
+    Talk(String),
+    CloseDoor,
+    PeekDoor,
+    PickLock,
+    BindWounds,
+    Disengage,
+}
+
+impl ContextAction {
+    /// Key that does it, as shown in the bar
+    pub fn key(&self) -> &'static str {
+        match self {
+            ContextAction::Descend => ">",
+            ContextAction::Pray { .. } => "E",
+            ContextAction::PickUp(_) | ContextAction::PickUpPile(_) | ContextAction::SearchCorpse => "G",
+            ContextAction::OpenChest | ContextAction::Talk(_) => "Bump",
+            ContextAction::CloseDoor => "X",
+            ContextAction::PeekDoor => "P",
+            ContextAction::PickLock => "L",
+            ContextAction::BindWounds => "B",
+            ContextAction::Disengage => "D",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ContextAction::Descend => "Descend".to_string(),
+            ContextAction::Pray { altar: false } => "Pray at shrine".to_string(),
+            ContextAction::Pray { altar: true } => "Worship at altar".to_string(),
+            ContextAction::PickUp(name) => format!("Pick up {}", name),
+            ContextAction::PickUpPile(count) => format!("Pick up {} items", count),
+            ContextAction::SearchCorpse => "Search corpse".to_string(),
+            ContextAction::OpenChest => "Open chest".to_string(),
+            ContextAction::Talk(name) => format!("Talk to {}", name),
+            ContextAction::CloseDoor => "Close door".to_string(),
+            ContextAction::PeekDoor => "Peek through door".to_string(),
+            ContextAction::PickLock => "Pick lock".to_string(),
+            ContextAction::BindWounds => "Bind wounds".to_string(),
+            ContextAction::Disengage => "Disengage".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_action_labels() {
+        let pick_up = ContextAction::PickUp("Rusty Sword".to_string());
+        assert_eq!((pick_up.key(), pick_up.label().as_str()), ("G", "Pick up Rusty Sword"));
+        assert_eq!(ContextAction::PickUpPile(3).label(), "Pick up 3 items");
+        assert_eq!(ContextAction::Pray { altar: true }.key(), "E");
+    }
+}
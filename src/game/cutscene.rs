@@ -0,0 +1,155 @@
+//! Cutscene playback
+//!
+//! Steps through a cutscene from `data::cutscenes`: timed steps run on the
+//! clock, dialogue waits for the player, and the whole scene can be skipped.
+//! The cutscene owns the camera while it plays.
+
+use crate::data::{CutsceneAnchor, CutsceneDef, CutsceneStep};
+use crate::ecs::Position;
+
+/// A cutscene being played
+#[derive(Debug, Clone)]
+pub struct Cutscene {
+    pub id: String,
+    steps: Vec<CutsceneStep>,
+    /// Index of the current step
+    step: usize,
+    /// Seconds spent on the current step
+    elapsed: f32,
+    /// Line of the current dialogue being shown
+    line: usize,
+    /// Where the current pan set out from
+    pan_from: Option<Position>,
+    /// Where the camera is looking
+    pub camera: Position,
+}
+
+impl Cutscene {
+    /// Start a cutscene with the camera where it already is
+    pub fn new(def: &CutsceneDef, camera: Position) -> Self {
+        Self {
+            id: def.id.clone(),
+            steps: def.steps.clone(),
+            step: 0,
+            elapsed: 0.0,
+            line: 0,
+            pan_from: None,
+            camera,
+        }
+    }
+
+    /// The step being played
+    pub fn current(&self) -> Option<&CutsceneStep> {
+        self.steps.get(self.step)
+    }
+
+    /// Speaker and line of the dialogue on screen
+    pub fn dialogue(&self) -> Option<(&str, &str)> {
+        match self.current()? {
+            CutsceneStep::Dialogue { speaker, lines } => lines.get(self.line).map(|l| (speaker.as_str(), l.as_str())),
+            _ => None,
+        }
+    }
+
+    /// Title and text of the banner on screen
+    pub fn banner(&self) -> Option<(&str, &str)> {
+        match self.current()? {
+            CutsceneStep::Banner { title, text, .. } => Some((title.as_str(), text.as_str())),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step >= self.steps.len()
+    }
+
+    /// Run the clock. Pans look up their anchor when they begin.
+    pub fn update(&mut self, delta: f32, locate: impl Fn(CutsceneAnchor) -> Option<Position>) {
+        let Some(step) = self.current().cloned() else { return };
+        self.elapsed += delta;
+        match step {
+            CutsceneStep::Pan { to, seconds } => {
+                let from = *self.pan_from.get_or_insert(self.camera);
+                let target = locate(to).unwrap_or(from);
+                let t = if seconds > 0.0 { (self.elapsed / seconds).min(1.0) } else { 1.0 };
+                let lerp = |a: i32, b: i32| a + ((b - a) as f32 * t).round() as i32;
+                self.camera = Position::new(lerp(from.x, target.x), lerp(from.y, target.y));
+                if t >= 1.0 {
+                    self.next_step();
+                }
+            }
+            CutsceneStep::Banner { seconds, .. } | CutsceneStep::Wait { seconds } => {
+                if self.elapsed >= seconds {
+                    self.next_step();
+                }
+            }
+            // Dialogue waits for the player
+            CutsceneStep::Dialogue { .. } => {}
+        }
+    }
+
+    /// The player read on: show the next line, or cut the current step short
+    pub fn advance(&mut self, locate: impl Fn(CutsceneAnchor) -> Option<Position>) {
+        match self.current() {
+            Some(CutsceneStep::Dialogue { lines, .. }) if self.line + 1 < lines.len() => self.line += 1,
+            Some(CutsceneStep::Pan { to, .. }) => {
+                if let Some(target) = locate(*to) {
+                    self.camera = target;
+                }
+                self.next_step();
+            }
+            Some(_) => self.next_step(),
+            None => {}
+        }
+    }
+
+    /// Skip to the end
+    pub fn skip(&mut self) {
+        self.step = self.steps.len();
+    }
+
+    fn next_step(&mut self) {
+        self.step += 1;
+        self.elapsed = 0.0;
+        self.line = 0;
+        self.pan_from = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CutsceneTrigger;
+
+    #[test]
+    fn test_cutscene_playback() {
+        let def = CutsceneDef {
+            id: "test".to_string(),
+            trigger: Some(CutsceneTrigger::Ending),
+            steps: vec![
+                CutsceneStep::Pan { to: CutsceneAnchor::Exit, seconds: 1.0 },
+                CutsceneStep::Dialogue { speaker: "Someone".to_string(), lines: vec!["One".to_string(), "Two".to_string()] },
+                CutsceneStep::Wait { seconds: 1.0 },
+            ],
+        };
+        let exit = |_| Some(Position::new(10, 0));
+        let mut scene = Cutscene::new(&def, Position::new(0, 0));
+
+        // Halfway through the pan the camera is halfway there
+        scene.update(0.5, exit);
+        assert_eq!(scene.camera, Position::new(5, 0));
+        scene.update(0.5, exit);
+        assert_eq!(scene.camera, Position::new(10, 0));
+
+        // Dialogue doesn't move on by itself
+        scene.update(5.0, exit);
+        assert_eq!(scene.dialogue(), Some(("Someone", "One")));
+        scene.advance(exit);
+        assert_eq!(scene.dialogue(), Some(("Someone", "Two")));
+        scene.advance(exit);
+        assert!(scene.dialogue().is_none() && !scene.is_finished());
+
+        scene.skip();
+        assert!(scene.is_finished());
+    }
+}
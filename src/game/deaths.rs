@@ -0,0 +1,76 @@
+//! Deaths
+//!
+//! Nothing despawns an enemy where it falls. Whatever kills one queues its
+//! death, and the dead are reaped at set points in the turn: once the
+//! player's action is done, after statuses tick, and before the turn is
+//! cleaned up. Reaping is where loot drops, XP is granted, death blasts go
+//! off, corpses are laid down and the kill is recorded, whatever did the
+//! killing.
+
+use hecs::Entity;
+
+/// An enemy that has died and is waiting to be reaped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Death {
+    pub entity: Entity,
+    /// Blown apart: leaves nothing to raise or consume
+    pub gibbed: bool,
+    /// Slain by the player's own hand: its loot and XP are theirs
+    pub by_player: bool,
+}
+
+/// Deaths waiting to be reaped, one for each of the dead
+#[derive(Debug, Clone, Default)]
+pub struct DeathQueue(Vec<Death>);
+
+impl DeathQueue {
+    /// Queue a death. Dying twice before the reaping only makes it worse:
+    /// a gib or the player's blow carries over to the death already queued.
+    pub fn push(&mut self, death: Death) {
+        match self.0.iter_mut().find(|d| d.entity == death.entity) {
+            Some(queued) => {
+                queued.gibbed |= death.gibbed;
+                queued.by_player |= death.by_player;
+            }
+            None => self.0.push(death),
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.iter().any(|d| d.entity == entity)
+    }
+
+    /// Take every queued death, in the order they died
+    pub fn drain(&mut self) -> Vec<Death> {
+        std::mem::take(&mut self.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_death_is_queued_once() {
+        let mut world = hecs::World::new();
+        let (hound, zealot) = (world.spawn(()), world.spawn(()));
+        let mut queue = DeathQueue::default();
+
+        queue.push(Death { entity: hound, gibbed: false, by_player: false });
+        queue.push(Death { entity: zealot, gibbed: false, by_player: true });
+        // Finished off by a heavy blow after the bleeding already killed it
+        queue.push(Death { entity: hound, gibbed: true, by_player: true });
+
+        assert!(queue.contains(zealot));
+        let deaths = queue.drain();
+        assert_eq!(deaths, vec![
+            Death { entity: hound, gibbed: true, by_player: true },
+            Death { entity: zealot, gibbed: false, by_player: true },
+        ]);
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,302 @@
+//! Deities and worship
+//!
+//! A small pantheon of grim gods with altars scattered through the dungeon.
+//! The player dedicates themselves to a patron, earns favor through kills and
+//! offerings, and unlocks boons at favor thresholds. Gods that are abandoned
+//! or desecrated grow wrathful and send punishers after the player.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::items::{Item, Rarity};
+use crate::world::TileType;
+
+/// Favor needed for a patron's minor boon
+pub const FAVOR_MINOR_BOON: i32 = 25;
+/// Favor needed for a patron's major boon
+pub const FAVOR_MAJOR_BOON: i32 = 60;
+/// Favor spent when a patron saves the player from death
+pub const FAVOR_INTERVENTION: i32 = 100;
+/// Highest favor a god can hold
+pub const MAX_FAVOR: i32 = 150;
+/// Favor at or below which a god sends punishers
+pub const WRATH_THRESHOLD: i32 = -30;
+/// Favor lost with the old patron when dedicating to another god
+pub const ABANDON_PENALTY: i32 = 50;
+/// Favor lost when desecrating an altar
+pub const DESECRATE_PENALTY: i32 = 60;
+
+/// Gold looted from a desecrated altar
+pub fn desecrate_reward(floor: u32) -> u32 {
+    30 + floor * 15
+}
+
+/// The gods of the Hollowdeep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Deity {
+    /// Vhorath, the Red Maw - hungers for slaughter
+    RedMaw,
+    /// Ysolde, the Ashen Widow - covets offerings
+    AshenWidow,
+    /// Oth, the Drowned Eye - rewards those who go deeper
+    DrownedEye,
+}
+
+impl Deity {
+    pub const ALL: [Deity; 3] = [Deity::RedMaw, Deity::AshenWidow, Deity::DrownedEye];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Deity::RedMaw => "Vhorath",
+            Deity::AshenWidow => "Ysolde",
+            Deity::DrownedEye => "Oth",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Deity::RedMaw => "the Red Maw",
+            Deity::AshenWidow => "the Ashen Widow",
+            Deity::DrownedEye => "the Drowned Eye",
+        }
+    }
+
+    /// What the god values, shown at the altar
+    pub fn domain(&self) -> &'static str {
+        match self {
+            Deity::RedMaw => "Delights in every life you take.",
+            Deity::AshenWidow => "Covets gold and treasured things.",
+            Deity::DrownedEye => "Watches those who sink ever deeper.",
+        }
+    }
+
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            Deity::RedMaw => (200, 30, 30),
+            Deity::AshenWidow => (190, 180, 170),
+            Deity::DrownedEye => (60, 140, 200),
+        }
+    }
+
+    /// Altar tile dedicated to this god
+    pub fn altar_tile(&self) -> TileType {
+        match self {
+            Deity::RedMaw => TileType::AltarMaw,
+            Deity::AshenWidow => TileType::AltarWidow,
+            Deity::DrownedEye => TileType::AltarEye,
+        }
+    }
+
+    /// God an altar tile belongs to
+    pub fn from_altar(tile: TileType) -> Option<Deity> {
+        Deity::ALL.into_iter().find(|d| d.altar_tile() == tile)
+    }
+
+    /// Minor and major boons granted to devotees
+    pub fn boons(&self) -> [Boon; 2] {
+        match self {
+            Deity::RedMaw => [Boon::Bloodthirst, Boon::Carnage],
+            Deity::AshenWidow => [Boon::Tithe, Boon::EmberWard],
+            Deity::DrownedEye => [Boon::DeepSight, Boon::TidalRest],
+        }
+    }
+
+    /// Favor a patron grants for a kill dedicated to them
+    pub fn kill_favor(&self, is_boss: bool) -> i32 {
+        match (self, is_boss) {
+            (Deity::RedMaw, true) => 25,
+            (Deity::RedMaw, false) => 3,
+            (_, true) => 10,
+            (_, false) => 1,
+        }
+    }
+
+    /// Favor granted for an offering of gold
+    pub fn gold_favor(&self, gold: u32) -> i32 {
+        let per_favor = if *self == Deity::AshenWidow { 5 } else { 10 };
+        (gold / per_favor) as i32
+    }
+
+    /// Favor granted for sacrificing an item
+    pub fn item_favor(&self, item: &Item) -> i32 {
+        let base = match item.rarity {
+            Rarity::Common => 2,
+            Rarity::Uncommon => 4,
+            Rarity::Rare => 8,
+            Rarity::Epic => 15,
+            Rarity::Legendary => 25,
+            Rarity::Mythic => 40,
+        };
+        if *self == Deity::AshenWidow { base * 2 } else { base }
+    }
+
+    /// Favor a patron grants for descending a floor
+    pub fn descent_favor(&self) -> i32 {
+        if *self == Deity::DrownedEye { 8 } else { 0 }
+    }
+}
+
+/// Passive blessings granted by a patron
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boon {
+    /// Heal on every kill
+    Bloodthirst,
+    /// Kills restore stamina and mana
+    Carnage,
+    /// Extra gold whenever gold is collected
+    Tithe,
+    /// Wounds mend each turn while badly hurt
+    EmberWard,
+    /// The stairs are revealed on arrival
+    DeepSight,
+    /// Descending restores health and mana
+    TidalRest,
+}
+
+impl Boon {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Boon::Bloodthirst => "Bloodthirst",
+            Boon::Carnage => "Carnage",
+            Boon::Tithe => "Tithe",
+            Boon::EmberWard => "Ember Ward",
+            Boon::DeepSight => "Deep Sight",
+            Boon::TidalRest => "Tidal Rest",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Boon::Bloodthirst => "Kills heal you",
+            Boon::Carnage => "Kills restore stamina and mana",
+            Boon::Tithe => "+20% gold collected",
+            Boon::EmberWard => "Regenerate health while below half",
+            Boon::DeepSight => "Reveal the stairs on each new floor",
+            Boon::TidalRest => "Descending restores health and mana",
+        }
+    }
+}
+
+/// The player's standing with the pantheon (saved with the run)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Worship {
+    /// God the player is dedicated to
+    pub patron: Option<Deity>,
+    /// Favor with each god (missing = 0)
+    favor: HashMap<Deity, i32>,
+}
+
+impl Worship {
+    /// Favor with a god
+    pub fn favor(&self, deity: Deity) -> i32 {
+        self.favor.get(&deity).copied().unwrap_or(0)
+    }
+
+    /// Change favor with a god, returning boons newly unlocked by the change
+    pub fn add_favor(&mut self, deity: Deity, amount: i32) -> Vec<Boon> {
+        let before = self.boons_at(deity, self.favor(deity));
+        let favor = (self.favor(deity) + amount).clamp(-MAX_FAVOR, MAX_FAVOR);
+        self.favor.insert(deity, favor);
+        self.boons_at(deity, favor)
+            .into_iter()
+            .filter(|boon| !before.contains(boon))
+            .collect()
+    }
+
+    /// Become a god's devotee, returning the abandoned patron (if any)
+    pub fn dedicate(&mut self, deity: Deity) -> Option<Deity> {
+        let previous = self.patron.replace(deity).filter(|p| *p != deity);
+        if let Some(old) = previous {
+            self.add_favor(old, -ABANDON_PENALTY);
+        }
+        previous
+    }
+
+    /// Boons granted by the patron at a favor level
+    fn boons_at(&self, deity: Deity, favor: i32) -> Vec<Boon> {
+        if self.patron != Some(deity) {
+            return Vec::new();
+        }
+        let [minor, major] = deity.boons();
+        let mut boons = Vec::new();
+        if favor >= FAVOR_MINOR_BOON {
+            boons.push(minor);
+        }
+        if favor >= FAVOR_MAJOR_BOON {
+            boons.push(major);
+        }
+        boons
+    }
+
+    /// Boons currently granted by the patron
+    pub fn active_boons(&self) -> Vec<Boon> {
+        match self.patron {
+            Some(deity) => self.boons_at(deity, self.favor(deity)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether the patron currently grants a boon
+    pub fn has_boon(&self, boon: Boon) -> bool {
+        self.active_boons().contains(&boon)
+    }
+
+    /// Whether the patron would save the player from death right now
+    pub fn can_intervene(&self) -> bool {
+        self.patron.is_some_and(|p| self.favor(p) >= FAVOR_INTERVENTION)
+    }
+
+    /// Spend favor for the patron to save the player from death
+    pub fn intervene(&mut self) -> Option<Deity> {
+        let patron = self.patron.filter(|_| self.can_intervene())?;
+        self.add_favor(patron, -FAVOR_INTERVENTION);
+        Some(patron)
+    }
+
+    /// Wrathful gods; their punishment settles the debt and resets their favor
+    pub fn take_wrath(&mut self) -> Vec<Deity> {
+        let wrathful: Vec<Deity> = Deity::ALL.into_iter()
+            .filter(|d| self.favor(*d) <= WRATH_THRESHOLD)
+            .collect();
+        for deity in &wrathful {
+            self.favor.insert(*deity, 0);
+        }
+        wrathful
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boons_unlock_for_patron_only() {
+        let mut worship = Worship::default();
+        assert!(worship.add_favor(Deity::RedMaw, FAVOR_MINOR_BOON).is_empty());
+
+        worship.dedicate(Deity::RedMaw);
+        assert!(worship.has_boon(Boon::Bloodthirst));
+        assert!(!worship.has_boon(Boon::Carnage));
+
+        let unlocked = worship.add_favor(Deity::RedMaw, FAVOR_MAJOR_BOON);
+        assert_eq!(unlocked, vec![Boon::Carnage]);
+        assert!(!worship.can_intervene());
+
+        worship.add_favor(Deity::RedMaw, FAVOR_INTERVENTION);
+        assert!(worship.can_intervene());
+        assert_eq!(worship.intervene(), Some(Deity::RedMaw));
+        assert!(!worship.can_intervene());
+    }
+
+    #[test]
+    fn test_abandoning_patron_brings_wrath() {
+        let mut worship = Worship::default();
+        worship.dedicate(Deity::DrownedEye);
+        assert_eq!(worship.dedicate(Deity::AshenWidow), Some(Deity::DrownedEye));
+        assert_eq!(worship.favor(Deity::DrownedEye), -ABANDON_PENALTY);
+
+        assert_eq!(worship.take_wrath(), vec![Deity::DrownedEye]);
+        assert_eq!(worship.favor(Deity::DrownedEye), 0);
+        assert!(worship.take_wrath().is_empty());
+    }
+}
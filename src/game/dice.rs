@@ -0,0 +1,86 @@
+//! Dice with the gambler
+//!
+//! The gambler plays a plain game: each side throws two dice and the higher
+//! total takes the pot. Ties go to the house, and double sixes pay the
+//! player extra. Stakes and payouts come from the gold sink config.
+
+use rand::Rng;
+
+use crate::data::DiceConfig;
+
+/// One throw at the gambler's table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiceThrow {
+    pub player: [u32; 2],
+    pub house: [u32; 2],
+    pub stake: u32,
+    /// Gold paid back, stake included (0 on a loss)
+    pub winnings: u32,
+}
+
+impl DiceThrow {
+    /// Throw for a stake
+    pub fn roll(stake: u32, dice: &DiceConfig, rng: &mut impl Rng) -> Self {
+        let player = [rng.gen_range(1..=6), rng.gen_range(1..=6)];
+        let house = [rng.gen_range(1..=6), rng.gen_range(1..=6)];
+        Self::settle(player, house, stake, dice)
+    }
+
+    /// Settle a throw already made
+    pub fn settle(player: [u32; 2], house: [u32; 2], stake: u32, dice: &DiceConfig) -> Self {
+        let won = player.iter().sum::<u32>() > house.iter().sum::<u32>();
+        let multiple = if !won {
+            0
+        } else if player == [6, 6] {
+            dice.jackpot
+        } else {
+            dice.payout
+        };
+        Self { player, house, stake, winnings: stake * multiple }
+    }
+
+    pub fn won(&self) -> bool {
+        self.winnings > 0
+    }
+
+    pub fn jackpot(&self) -> bool {
+        self.won() && self.player == [6, 6]
+    }
+
+    pub fn description(&self) -> String {
+        let throws = format!(
+            "You throw {}+{}, the gambler {}+{}.",
+            self.player[0], self.player[1], self.house[0], self.house[1],
+        );
+        if self.jackpot() {
+            format!("{} Double sixes! You win {} gold.", throws, self.winnings)
+        } else if self.won() {
+            format!("{} You win {} gold.", throws, self.winnings)
+        } else if self.player.iter().sum::<u32>() == self.house.iter().sum::<u32>() {
+            format!("{} A tie goes to the house.", throws)
+        } else {
+            format!("{} The gambler sweeps up your {} gold.", throws, self.stake)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::data::GoldSinkConfig;
+
+    #[test]
+    fn test_the_house_keeps_an_edge() {
+        let dice = GoldSinkConfig::default().dice;
+        assert_eq!(DiceThrow::settle([5, 4], [3, 3], 50, &dice).winnings, 100);
+        assert_eq!(DiceThrow::settle([6, 6], [5, 6], 50, &dice).winnings, 200);
+        assert!(!DiceThrow::settle([4, 3], [5, 2], 50, &dice).won());
+        assert!(!DiceThrow::settle([6, 6], [6, 6], 50, &dice).won());
+
+        // Over many throws the table takes more than it pays
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let paid: u32 = (0..5000).map(|_| DiceThrow::roll(10, &dice, &mut rng).winnings).sum();
+        assert!(paid < 5000 * 10);
+    }
+}
@@ -0,0 +1,72 @@
+//! Examining the map
+//!
+//! What the player knows about a single cell: the tile, what's marked on
+//! it, and who and what is there if it's in sight. The same description
+//! fills the mouse-hover tooltip and the look-mode panel.
+
+/// Everything known about a cell
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CellDescription {
+    /// Tile name
+    pub tile: String,
+    /// Blood, scorch marks or bones on the floor
+    pub decal: Option<&'static str>,
+    /// Creatures standing there, with a word on their condition
+    pub creatures: Vec<String>,
+    /// Chests, corpses and the like
+    pub features: Vec<String>,
+    /// Items lying on the ground
+    pub items: Vec<String>,
+    /// Out of sight: only the tile as remembered
+    pub remembered: bool,
+}
+
+impl CellDescription {
+    /// Lines of the panel, tile first
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![if self.remembered {
+            format!("{} (remembered)", self.tile)
+        } else {
+            self.tile.clone()
+        }];
+        lines.extend(self.decal.map(str::to_string));
+        lines.extend(self.creatures.iter().cloned());
+        lines.extend(self.features.iter().cloned());
+        lines.extend(self.items.iter().map(|name| format!("- {}", name)));
+        lines
+    }
+}
+
+/// A word on how hurt something is
+pub fn condition(current: i32, max: i32) -> &'static str {
+    let fraction = current as f32 / max.max(1) as f32;
+    if fraction >= 1.0 {
+        "unhurt"
+    } else if fraction > 0.6 {
+        "wounded"
+    } else if fraction > 0.3 {
+        "badly wounded"
+    } else {
+        "near death"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_description_lines() {
+        let cell = CellDescription {
+            tile: "Stone floor".to_string(),
+            decal: Some("Spattered with blood"),
+            creatures: vec![format!("Ghoul ({})", condition(4, 20))],
+            items: vec!["Rusty Sword".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(cell.lines(), vec!["Stone floor", "Spattered with blood", "Ghoul (near death)", "- Rusty Sword"]);
+
+        let remembered = CellDescription { tile: "Wall".to_string(), remembered: true, ..Default::default() };
+        assert_eq!(remembered.lines(), vec!["Wall (remembered)"]);
+    }
+}
@@ -0,0 +1,36 @@
+//! Floating combat text
+//!
+//! Short-lived numbers that rise off an entity on the map, so the damage
+//! and healing of status effects can be read without checking the log.
+
+use crate::ecs::Position;
+
+/// Seconds a floating number stays on the map
+pub const FLOATING_TEXT_SECONDS: f32 = 1.2;
+/// Rows a floating number rises over its lifetime
+const FLOATING_TEXT_RISE: f32 = 2.0;
+
+/// A number drifting up from where something happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatingText {
+    pub pos: Position,
+    pub text: String,
+    pub color: (u8, u8, u8),
+    /// Seconds since it appeared
+    pub age: f32,
+}
+
+impl FloatingText {
+    pub fn new(pos: Position, text: impl Into<String>, color: (u8, u8, u8)) -> Self {
+        Self { pos, text: text.into(), color, age: 0.0 }
+    }
+
+    /// Rows above its origin it has risen to
+    pub fn rise(&self) -> i32 {
+        (self.age / FLOATING_TEXT_SECONDS * FLOATING_TEXT_RISE) as i32
+    }
+
+    pub fn expired(&self) -> bool {
+        self.age >= FLOATING_TEXT_SECONDS
+    }
+}
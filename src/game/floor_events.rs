@@ -0,0 +1,110 @@
+//! Floor events and the floor-intro banner
+//!
+//! The map side of floor events: caving in corridors without cutting the
+//! stairs off, and raising a cluster of corruption shrines. The caravan is
+//! spawned in `entities::spawner`, and darkness only changes how far the
+//! player sees. Each new floor is announced with a banner that fades after a
+//! few seconds.
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::ecs::Position;
+use crate::world::{DijkstraMap, Map, TileType};
+
+/// How far the player sees on an ordinary floor
+pub const SIGHT_RADIUS: i32 = 8;
+/// How far the player sees in total darkness
+pub const DARKNESS_SIGHT_RADIUS: i32 = 3;
+/// Seconds the floor-intro banner stays up
+pub const FLOOR_BANNER_SECONDS: f32 = 4.0;
+/// Corridor cave-ins attempted on a collapsed floor
+const COLLAPSE_ATTEMPTS: usize = 12;
+/// Most cave-ins a floor keeps
+const MAX_COLLAPSES: usize = 4;
+/// Corruption shrines in a cluster
+const SHRINE_CLUSTER_SIZE: usize = 3;
+
+/// Announces a new floor
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloorBanner {
+    /// Floor number and biome
+    pub title: String,
+    /// Event name and announcement, if something has happened here
+    pub event: Option<(String, String)>,
+    /// Seconds left on screen
+    pub remaining: f32,
+}
+
+impl FloorBanner {
+    pub fn new(title: String, event: Option<(String, String)>) -> Self {
+        Self { title, event, remaining: FLOOR_BANNER_SECONDS }
+    }
+}
+
+/// Cave in corridor tiles, keeping the stairs reachable from the start.
+/// Returns how many caved in.
+pub fn collapse_passages(map: &mut Map, rng: &mut impl Rng) -> usize {
+    let Some(exit) = map.exit_pos else { return 0 };
+    let mut corridors: Vec<Position> = map.get_walkable_positions()
+        .into_iter()
+        .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type == TileType::Corridor))
+        .filter(|pos| pos.chebyshev_distance(&map.start_pos) > 3 && pos.chebyshev_distance(&exit) > 3)
+        .collect();
+    corridors.shuffle(rng);
+
+    let mut collapsed = 0;
+    for pos in corridors.into_iter().take(COLLAPSE_ATTEMPTS) {
+        if collapsed >= MAX_COLLAPSES {
+            break;
+        }
+        map.set_tile(pos.x, pos.y, TileType::Wall);
+        if DijkstraMap::new(map, exit).distance(map.start_pos).is_none() {
+            map.set_tile(pos.x, pos.y, TileType::Corridor);
+            continue;
+        }
+
+        // Debris spills into the corridor on either side
+        for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+            let (x, y) = (pos.x + dx, pos.y + dy);
+            if map.get_tile(x, y).is_some_and(|t| t.tile_type == TileType::Corridor) {
+                map.set_tile(x, y, TileType::Rubble);
+            }
+        }
+        collapsed += 1;
+    }
+    collapsed
+}
+
+/// Raise corruption shrines together in an open spot away from the start.
+/// Returns where they stand.
+pub fn raise_shrine_cluster(map: &mut Map, rng: &mut impl Rng) -> Vec<Position> {
+    let Some(&center) = map.get_npc_spawn_positions(10).choose(rng) else { return Vec::new() };
+    let spots: Vec<Position> = (-2..=2)
+        .flat_map(|dy| (-2..=2).map(move |dx| Position::new(center.x + dx, center.y + dy)))
+        .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type == TileType::Floor))
+        .filter(|pos| !map.is_narrow_passage(*pos))
+        .collect();
+
+    let shrines: Vec<Position> = spots.choose_multiple(rng, SHRINE_CLUSTER_SIZE).copied().collect();
+    for pos in &shrines {
+        map.set_tile(pos.x, pos.y, TileType::ShrineCorruption);
+    }
+    shrines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_collapse_keeps_exit_reachable() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let mut map = crate::world::generation::generate_floor(&mut rng, 3, crate::world::Biome::SunkenCatacombs);
+        let exit = map.exit_pos.unwrap();
+
+        collapse_passages(&mut map, &mut rng);
+        assert!(DijkstraMap::new(&map, exit).distance(map.start_pos).is_some());
+    }
+}
@@ -0,0 +1,58 @@
+//! First-time hints
+//!
+//! A line in the message log the first time the player comes across
+//! something the game otherwise leaves them to work out: a corruption
+//! shrine, an elite zone, a trapped chest. Each hint shows once per profile.
+//! Separately, enemies rated deadly are called out when they come into
+//! view, every time, so a fight can be turned down before it starts.
+
+/// Something worth explaining the first time it turns up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    CorruptionShrine,
+    Altar,
+    ThreatColors,
+    EliteZone,
+    EliteAffixes,
+    LockedDoor,
+    Hazard,
+    Chest,
+    Corpse,
+    LowHealth,
+}
+
+impl Hint {
+    /// Key the profile remembers the hint by
+    pub fn id(&self) -> &'static str {
+        match self {
+            Hint::CorruptionShrine => "corruption_shrine",
+            Hint::Altar => "altar",
+            Hint::ThreatColors => "threat_colors",
+            Hint::EliteZone => "elite_zone",
+            Hint::EliteAffixes => "elite_affixes",
+            Hint::LockedDoor => "locked_door",
+            Hint::Hazard => "hazard",
+            Hint::Chest => "chest",
+            Hint::Corpse => "corpse",
+            Hint::LowHealth => "low_health",
+        }
+    }
+
+    pub fn text(&self) -> &'static str {
+        match self {
+            Hint::CorruptionShrine => "Hint: Corruption shrines trade permanent curses for power. Read each pact before you take it.",
+            Hint::Altar => "Hint: Altars belong to the gods. Worship at one (e) to take a patron, whose favor grows as you serve it.",
+            Hint::ThreatColors => "Hint: Enemies in the Nearby panel are colored by threat, from gray (trivial) to red (deadly). Look (;) at one for more.",
+            Hint::EliteZone => "Hint: You've entered an elite zone (✧). Its enemies hit harder and last longer, but give more XP.",
+            Hint::EliteAffixes => "Hint: Elites wear their affixes in their names. Vampiric ones heal as they hit; Venomous ones poison.",
+            Hint::LockedDoor => "Hint: Every locked door has a key somewhere on the floor. Without it, pick the lock (L) or keep walking into the door to break it.",
+            Hint::Hazard => "Hint: Lava, pits and corrupted blood hurt whoever enters them. Stepping in takes a second move to confirm.",
+            Hint::Chest => "Hint: Not every chest is what it seems. Some are trapped, some locked, and some bite. Walk into one to open it.",
+            Hint::Corpse => "Hint: The fallen sometimes carry more than they dropped. Stand by a corpse and press g to search it.",
+            Hint::LowHealth => "Hint: Badly hurt? Back off and rest (R), bind your wounds (B), or drink a potion from your belt (6-9).",
+        }
+    }
+}
+
+/// Health below this share of the maximum brings up the low health hint
+pub const LOW_HEALTH_HINT_PERCENT: i32 = 30;
@@ -0,0 +1,96 @@
+//! Interaction
+//!
+//! Everything the player can use goes through `Game::interact`: shrines and
+//! altars, the stairs, doors, levers, chests and NPCs alike. Entities carry
+//! an `Interactable`, and tiles offer one of their own, so new content only
+//! has to spawn an entity with one to be usable. The interface just follows
+//! up on the `Interaction` that comes back.
+
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use super::{Deity, PlayingState, ShrineType};
+use crate::entities::ChestApproach;
+use crate::world::TileType;
+
+/// Something in the world the player can use
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Interactable {
+    /// A shrine or an altar, used from where the player stands
+    Shrine(ShrineType),
+    /// The way down, used from where the player stands
+    StairsDown,
+    /// A shut door or a wall of flesh, forced by walking into it
+    Door,
+    /// A lever set in the wall, thrown by walking into it
+    Lever,
+    /// A chest, opened by walking into it
+    Chest,
+    /// Someone to talk to, by walking into them
+    Npc,
+}
+
+impl Interactable {
+    /// What a tile offers the player, if anything
+    pub fn of_tile(tile: TileType) -> Option<Self> {
+        let shrine = match tile {
+            TileType::ShrineSkill => ShrineType::Skill,
+            TileType::ShrineEnchant => ShrineType::Enchanting,
+            TileType::ShrineRest => ShrineType::Rest,
+            TileType::ShrineCorruption => ShrineType::Corruption,
+            TileType::ShrineGamble => ShrineType::Gambling,
+            TileType::ShrineSacrifice => ShrineType::Sacrifice,
+            TileType::ShrineTransmute => ShrineType::Transmutation,
+            TileType::StairsDown => return Some(Interactable::StairsDown),
+            TileType::Lever | TileType::LeverPulled => return Some(Interactable::Lever),
+            t if t.is_breachable() => return Some(Interactable::Door),
+            t => ShrineType::Altar(Deity::from_altar(t)?),
+        };
+        Some(Interactable::Shrine(shrine))
+    }
+
+    /// Whether it's used from where the player stands, rather than by
+    /// walking into it
+    pub fn underfoot(&self) -> bool {
+        matches!(self, Interactable::Shrine(_) | Interactable::StairsDown)
+    }
+}
+
+/// What came of an interaction, for the interface to follow up on
+#[derive(Debug, Clone, PartialEq)]
+pub enum Interaction {
+    /// Nothing there to use, or it turned the player away
+    Nothing,
+    /// Used, without taking the turn
+    Free,
+    /// Used, and the turn passes
+    Acted,
+    /// Opened a screen for the player to choose from
+    Opened(PlayingState),
+    /// Went down the stairs
+    Descended,
+    /// Reached for a chest
+    Chest(Entity, ChestApproach),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiles_offer_what_stands_on_them() {
+        let shrine = Interactable::of_tile(TileType::ShrineGamble).unwrap();
+        assert_eq!(shrine, Interactable::Shrine(ShrineType::Gambling));
+        assert!(shrine.underfoot());
+
+        let altar = Interactable::of_tile(Deity::DrownedEye.altar_tile());
+        assert_eq!(altar, Some(Interactable::Shrine(ShrineType::Altar(Deity::DrownedEye))));
+
+        for tile in [TileType::DoorClosed, TileType::DoorLocked, TileType::FleshWall] {
+            assert_eq!(Interactable::of_tile(tile), Some(Interactable::Door));
+        }
+        assert!(!Interactable::Lever.underfoot());
+        assert_eq!(Interactable::of_tile(TileType::DoorOpen), None);
+        assert_eq!(Interactable::of_tile(TileType::Floor), None);
+    }
+}
@@ -3,7 +3,49 @@
 mod state;
 mod turn;
 mod time;
+mod shrines;
+mod deities;
+mod rest;
+mod channel;
+mod boss_rush;
+mod floor_events;
+mod collapse;
+mod cutscene;
+mod context;
+mod examine;
+mod run_clock;
+mod hints;
+mod threat;
+mod floating;
+mod balance_report;
+mod reputation;
+mod theft;
+mod dice;
+mod adaptive;
+mod deaths;
+mod interact;
 
 pub use state::{Game, GameState, PlayingState, MessageCategory, ShrineType};
-pub use turn::TurnManager;
-pub use time::AmbientTime;
+pub use turn::{TurnManager, TurnRegen, PreparedAction, prepared_range, DISENGAGE_STAMINA_COST, leaves_reach, opportunity_attackers};
+pub use time::{AmbientTime, DAY_CYCLE_SECONDS, is_night};
+pub use rest::{Rest, RestEnd, REST_MAX_TURNS, TURNS_PER_RATION, FED_HEAL_BONUS, RATION_HEAL, REST_TURN_SECONDS, interruption_chance};
+pub use floor_events::{FloorBanner, SIGHT_RADIUS, DARKNESS_SIGHT_RADIUS, FLOOR_BANNER_SECONDS, collapse_passages, raise_shrine_cluster};
+pub use collapse::{Collapse, COLLAPSE_SLACK_TURNS, COLLAPSE_FALL_DAMAGE, SHAKE_SECONDS, shake_offset};
+pub use cutscene::Cutscene;
+pub use context::ContextAction;
+pub use examine::{CellDescription, condition};
+pub use hints::{Hint, LOW_HEALTH_HINT_PERCENT};
+pub use balance_report::{balance_report, balance_rows, BalanceRow, StandardBuild};
+pub use floating::{FloatingText, FLOATING_TEXT_SECONDS};
+pub use threat::{Threat, DEADLY_RATIO, blows_to_kill, with_affixes};
+pub use run_clock::{RunClock, FloorSplit, format_run_time, format_split_delta};
+pub use boss_rush::{BossRush, BOSS_RUSH_ORDER, BOSS_RUSH_STAT_POINTS, BOSS_RUSH_GOLD, format_rush_time};
+pub use channel::{Channel, ChannelKind, CHANNEL_TURN_SECONDS, BANDAGE_TURNS, BANDAGE_STAMINA_COST, bandage_heal, lockpick_turns};
+pub use shrines::{GambleOutcome, SacrificeStat, roll_gamble, sacrifice_boon, can_transmute, transmute_item};
+pub use reputation::{NpcFaction, Standing, Reputation, ROBBERY_REPUTATION, KILL_REPUTATION, ESCORT_REPUTATION, HUNTER_SQUAD_CHANCE};
+pub use dice::DiceThrow;
+pub use adaptive::{AdaptiveDifficulty, PressureShift};
+pub use deaths::{Death, DeathQueue};
+pub use interact::{Interactable, Interaction};
+pub use theft::{Pickpocketed, Haul, PICKPOCKET_REPUTATION, steal_chance, enemy_perception, npc_haul};
+pub use deities::{Deity, Boon, Worship, FAVOR_MINOR_BOON, FAVOR_MAJOR_BOON, FAVOR_INTERVENTION, desecrate_reward};
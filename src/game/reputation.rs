@@ -0,0 +1,287 @@
+//! Faction reputation
+//!
+//! The Merchants' Guild and the Penitent Order keep track of how the player
+//! treats their people. Robbing or killing them sours the faction: prices
+//! climb, stock thins, and once they've had enough their people refuse to
+//! deal with the player at all and send hunters down after them. Seeing
+//! their captives safely out of the dungeon wins them back, with discounts
+//! and stock kept for friends of the faction.
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::ecs::{EnemyArchetype, Stats};
+use crate::entities::NpcType;
+use crate::entities::enemies::EnemyDef;
+use crate::items::{Item, Rarity};
+use crate::items::item::templates;
+use crate::items::loot::{generate_armor_with_min_rarity, generate_weapon_with_min_rarity};
+
+/// Reputation lost for robbing one of a faction's people
+pub const ROBBERY_REPUTATION: i32 = 30;
+/// Reputation lost for killing one of a faction's people
+pub const KILL_REPUTATION: i32 = 30;
+/// Reputation gained for escorting one of a faction's people to safety
+pub const ESCORT_REPUTATION: i32 = 25;
+/// Chance each floor that a faction hunting the player sends a squad
+pub const HUNTER_SQUAD_CHANCE: f64 = 0.5;
+/// Highest (and lowest) reputation a faction can hold
+const MAX_REPUTATION: i32 = 100;
+
+/// The factions whose people walk the dungeon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NpcFaction {
+    /// Merchants, peddlers, smiths and jewelers
+    MerchantsGuild,
+    /// Healers and the storytellers who keep the Order's records
+    PenitentOrder,
+}
+
+impl NpcFaction {
+    pub const ALL: [NpcFaction; 2] = [NpcFaction::MerchantsGuild, NpcFaction::PenitentOrder];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NpcFaction::MerchantsGuild => "Merchants' Guild",
+            NpcFaction::PenitentOrder => "Penitent Order",
+        }
+    }
+
+    /// The faction an NPC answers to, if any
+    pub fn of(npc_type: NpcType) -> Option<Self> {
+        match npc_type {
+            NpcType::Merchant | NpcType::Peddler | NpcType::Blacksmith | NpcType::Jeweler => Some(NpcFaction::MerchantsGuild),
+            NpcType::Healer | NpcType::Storyteller => Some(NpcFaction::PenitentOrder),
+            NpcType::Collector | NpcType::Gambler => None,
+        }
+    }
+
+    /// Who the faction sends after the player, leader first
+    pub fn hunters(&self) -> [&'static EnemyDef; 2] {
+        match self {
+            NpcFaction::MerchantsGuild => [&GUILD_ENFORCER, &GUILD_CROSSBOWMAN],
+            NpcFaction::PenitentOrder => [&PENITENT_INQUISITOR, &PENITENT_FLAGELLANT],
+        }
+    }
+
+    /// Stock the faction's traders keep for its friends
+    pub fn exclusive_stock(&self, floor: u32, rng: &mut impl Rng, item_id_counter: &mut u64) -> Vec<Item> {
+        match self {
+            NpcFaction::MerchantsGuild => vec![
+                generate_weapon_with_min_rarity(floor, Rarity::Epic, rng),
+                generate_armor_with_min_rarity(floor, Rarity::Epic, rng),
+            ],
+            NpcFaction::PenitentOrder => {
+                *item_id_counter += 2;
+                vec![
+                    templates::bonesetters_salve(*item_id_counter - 1),
+                    templates::scroll_of_return(*item_id_counter),
+                ]
+            }
+        }
+    }
+}
+
+/// How a faction regards the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Standing {
+    /// Refuses to deal with the player and sends hunters after them
+    Hunted,
+    /// Charges more, pays less and keeps the good stock back
+    Distrusted,
+    Neutral,
+    /// Deals at a discount
+    Trusted,
+    /// Deals at a steep discount and brings out exclusive stock
+    Honored,
+}
+
+impl Standing {
+    pub fn from_reputation(reputation: i32) -> Self {
+        match reputation {
+            i32::MIN..=-50 => Standing::Hunted,
+            -49..=-11 => Standing::Distrusted,
+            -10..=19 => Standing::Neutral,
+            20..=49 => Standing::Trusted,
+            _ => Standing::Honored,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Standing::Hunted => "Hunted",
+            Standing::Distrusted => "Distrusted",
+            Standing::Neutral => "Neutral",
+            Standing::Trusted => "Trusted",
+            Standing::Honored => "Honored",
+        }
+    }
+
+    /// Whether the faction's people will deal with the player at all
+    pub fn will_trade(&self) -> bool {
+        *self != Standing::Hunted
+    }
+
+    /// What the faction charges for something priced at `base`
+    pub fn buy_price(&self, base: u32) -> u32 {
+        let multiplier = match self {
+            Standing::Hunted | Standing::Distrusted => 1.3,
+            Standing::Neutral => 1.0,
+            Standing::Trusted => 0.9,
+            Standing::Honored => 0.75,
+        };
+        ((base as f32 * multiplier) as u32).max(1)
+    }
+
+    /// What the faction pays for something it would normally pay `base` for
+    pub fn sell_price(&self, base: u32) -> u32 {
+        let multiplier = match self {
+            Standing::Hunted | Standing::Distrusted => 0.75,
+            Standing::Neutral => 1.0,
+            Standing::Trusted => 1.1,
+            Standing::Honored => 1.25,
+        };
+        ((base as f32 * multiplier) as u32).max(1)
+    }
+
+    /// How much of a faction healer's usual care the player gets
+    pub fn healing(&self, base: i32) -> i32 {
+        match self {
+            Standing::Hunted => 0,
+            Standing::Distrusted => base / 2,
+            Standing::Neutral => base,
+            Standing::Trusted => base * 3 / 2,
+            Standing::Honored => base * 2,
+        }
+    }
+
+    /// Thin out a shop's stock to what the faction will show the player
+    pub fn withhold_stock<T>(&self, stock: &mut Vec<T>, rng: &mut impl Rng) {
+        if *self > Standing::Distrusted {
+            return;
+        }
+        stock.shuffle(rng);
+        stock.truncate(stock.len() * 2 / 3);
+    }
+}
+
+/// The player's reputation with each faction (saved with the run)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reputation {
+    guild: i32,
+    order: i32,
+}
+
+impl Reputation {
+    pub fn reputation(&self, faction: NpcFaction) -> i32 {
+        match faction {
+            NpcFaction::MerchantsGuild => self.guild,
+            NpcFaction::PenitentOrder => self.order,
+        }
+    }
+
+    pub fn standing(&self, faction: NpcFaction) -> Standing {
+        Standing::from_reputation(self.reputation(faction))
+    }
+
+    /// Change reputation with a faction, returning the new standing if it
+    /// changed
+    pub fn adjust(&mut self, faction: NpcFaction, amount: i32) -> Option<Standing> {
+        let before = self.standing(faction);
+        let reputation = match faction {
+            NpcFaction::MerchantsGuild => &mut self.guild,
+            NpcFaction::PenitentOrder => &mut self.order,
+        };
+        *reputation = (*reputation + amount).clamp(-MAX_REPUTATION, MAX_REPUTATION);
+        let after = self.standing(faction);
+        (after != before).then_some(after)
+    }
+
+    /// Factions hunting the player
+    pub fn hunting(&self) -> Vec<NpcFaction> {
+        NpcFaction::ALL.into_iter().filter(|f| self.standing(*f) == Standing::Hunted).collect()
+    }
+}
+
+pub const GUILD_ENFORCER: EnemyDef = EnemyDef {
+    name: "Guild Enforcer",
+    glyph: 'E',
+    fg: (230, 190, 60),
+    archetype: EnemyArchetype::Tank,
+    stats: Stats { strength: 15, dexterity: 10, intelligence: 8, vitality: 16 },
+    hp: 90,
+    xp_value: 60,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const GUILD_CROSSBOWMAN: EnemyDef = EnemyDef {
+    name: "Guild Crossbowman",
+    glyph: 'c',
+    fg: (230, 190, 60),
+    archetype: EnemyArchetype::Ranged,
+    stats: Stats { strength: 10, dexterity: 15, intelligence: 8, vitality: 10 },
+    hp: 50,
+    xp_value: 40,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const PENITENT_INQUISITOR: EnemyDef = EnemyDef {
+    name: "Penitent Inquisitor",
+    glyph: 'I',
+    fg: (220, 220, 240),
+    archetype: EnemyArchetype::Caster,
+    stats: Stats { strength: 8, dexterity: 10, intelligence: 16, vitality: 12 },
+    hp: 70,
+    xp_value: 60,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+pub const PENITENT_FLAGELLANT: EnemyDef = EnemyDef {
+    name: "Penitent Flagellant",
+    glyph: 'f',
+    fg: (220, 220, 240),
+    archetype: EnemyArchetype::Melee,
+    stats: Stats { strength: 14, dexterity: 12, intelligence: 6, vitality: 12 },
+    hp: 55,
+    xp_value: 40,
+    hazard_immune: false,
+    raises_dead: false,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_reputation_sets_prices_and_hunters() {
+        let mut reputation = Reputation::default();
+        let guild = NpcFaction::MerchantsGuild;
+        assert_eq!(reputation.standing(guild), Standing::Neutral);
+        assert_eq!(NpcFaction::of(NpcType::Peddler), Some(guild));
+
+        // Robbing a peddler and then killing them turns the guild hostile
+        assert_eq!(reputation.adjust(guild, -ROBBERY_REPUTATION), Some(Standing::Distrusted));
+        assert!(Standing::Distrusted.buy_price(100) > 100);
+        assert_eq!(reputation.adjust(guild, -KILL_REPUTATION), Some(Standing::Hunted));
+        assert!(!reputation.standing(guild).will_trade());
+        assert_eq!(reputation.hunting(), vec![guild]);
+
+        // Seeing their people to safety wins them back, and then some
+        for _ in 0..5 {
+            reputation.adjust(guild, ESCORT_REPUTATION);
+        }
+        assert_eq!(reputation.standing(guild), Standing::Honored);
+        assert!(Standing::Honored.buy_price(100) < 100);
+        assert!(Standing::Honored.sell_price(100) > 100);
+        assert_eq!(reputation.standing(NpcFaction::PenitentOrder), Standing::Neutral);
+
+        let mut stock = vec![1, 2, 3, 4, 5, 6];
+        Standing::Distrusted.withhold_stock(&mut stock, &mut rand::rngs::StdRng::seed_from_u64(3));
+        assert_eq!(stock.len(), 4);
+    }
+}
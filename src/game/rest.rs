@@ -0,0 +1,111 @@
+//! Resting
+//!
+//! Resting passes turns until the player has recovered or something cuts it
+//! short. Rations speed recovery up; every turn spent resting risks drawing a
+//! wandering monster, more so on deep floors and in noisy armor.
+
+/// Longest a single rest can last
+pub const REST_MAX_TURNS: u32 = 200;
+/// Turns of rest a single ration feeds
+pub const TURNS_PER_RATION: u32 = 20;
+/// Extra HP recovered per turn while fed
+pub const FED_HEAL_BONUS: i32 = 2;
+/// HP restored by eating a ration outside of a rest
+pub const RATION_HEAL: i32 = 10;
+/// Real time between rest turns, so the progress is visible
+pub const REST_TURN_SECONDS: f32 = 0.05;
+
+/// Per-turn chance of a wandering monster on floor 1 in medium armor
+const BASE_INTERRUPT_CHANCE: f64 = 0.004;
+/// Added per floor of depth
+const INTERRUPT_CHANCE_PER_FLOOR: f64 = 0.001;
+/// Added per point of armor noise
+const INTERRUPT_CHANCE_PER_NOISE: f64 = 0.003;
+
+/// Chance each rest turn that a wandering monster finds the player
+pub fn interruption_chance(floor: u32, noise: i32) -> f64 {
+    (BASE_INTERRUPT_CHANCE
+        + floor as f64 * INTERRUPT_CHANCE_PER_FLOOR
+        + noise as f64 * INTERRUPT_CHANCE_PER_NOISE)
+        .clamp(0.001, 0.05)
+}
+
+/// Why a rest came to an end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestEnd {
+    /// HP, mana and stamina are full
+    Recovered,
+    /// A wandering monster showed up
+    Interrupted,
+    /// An enemy came into view
+    EnemySighted,
+    /// The player took damage
+    Hurt,
+    /// Rested as long as a single rest allows
+    TooLong,
+    /// The player stopped resting
+    Cancelled,
+}
+
+impl RestEnd {
+    pub fn message(&self) -> &'static str {
+        match self {
+            RestEnd::Recovered => "You feel fully rested.",
+            RestEnd::Interrupted => "Something stirs in the dark - your rest is interrupted!",
+            RestEnd::EnemySighted => "An enemy comes into view. You scramble to your feet.",
+            RestEnd::Hurt => "Pain jolts you out of your rest!",
+            RestEnd::TooLong => "You can't seem to rest any longer.",
+            RestEnd::Cancelled => "You stop resting.",
+        }
+    }
+}
+
+/// An ongoing rest
+#[derive(Debug, Clone, Default)]
+pub struct Rest {
+    /// Turns rested so far
+    pub turns: u32,
+    /// Turns left on the ration currently being eaten
+    pub fed_turns: u32,
+    /// Rations eaten during this rest
+    pub rations_eaten: u32,
+    /// HP when the rest began, for the progress bar and to spot damage
+    pub start_hp: i32,
+    /// HP after the last rest turn
+    pub last_hp: i32,
+    /// Real time accumulated toward the next rest turn
+    pub timer: f32,
+}
+
+impl Rest {
+    pub fn new(hp: i32) -> Self {
+        Self {
+            start_hp: hp,
+            last_hp: hp,
+            ..Self::default()
+        }
+    }
+
+    /// Fraction of the missing HP recovered so far
+    pub fn progress(&self, hp: i32, max_hp: i32) -> f32 {
+        let missing = max_hp - self.start_hp;
+        if missing <= 0 {
+            return 1.0;
+        }
+        ((hp - self.start_hp) as f32 / missing as f32).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interruption_scales_with_depth_and_noise() {
+        let shallow = interruption_chance(1, 0);
+        assert!(interruption_chance(10, 0) > shallow);
+        assert!(interruption_chance(1, 2) > shallow);
+        assert!(interruption_chance(1, -1) < shallow);
+        assert!(interruption_chance(100, 10) <= 0.05);
+    }
+}
@@ -0,0 +1,120 @@
+//! Run clock
+//!
+//! Turns taken and time played over a run, and the split each floor was
+//! cleared in. The clock runs while the run is being played, screens over
+//! the map included, and stops while the game is paused. It is saved with
+//! the run, so a loaded game picks up where it left off.
+
+use serde::{Deserialize, Serialize};
+
+/// How a floor was cleared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FloorSplit {
+    pub floor: u32,
+    /// Run time when the floor was left, in seconds
+    pub at: u32,
+    /// Seconds spent on the floor
+    pub seconds: u32,
+    /// Turns taken on the floor
+    pub turns: u32,
+    /// Personal best for leaving this floor before this run, when speedrunning
+    pub best: Option<u32>,
+}
+
+impl FloorSplit {
+    /// Seconds ahead of (negative) or behind the personal best
+    pub fn delta(&self) -> Option<i64> {
+        self.best.map(|best| self.at as i64 - best as i64)
+    }
+}
+
+/// Turns and time over a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunClock {
+    /// Turns taken this run
+    pub turns: u32,
+    /// Seconds played this run
+    pub elapsed: f32,
+    /// Run time and turn count on entering the current floor
+    floor_start: (f32, u32),
+    /// Floors cleared so far, in order
+    pub splits: Vec<FloorSplit>,
+}
+
+impl RunClock {
+    pub fn tick(&mut self, delta_secs: f32) {
+        self.elapsed += delta_secs;
+    }
+
+    pub fn count_turn(&mut self) {
+        self.turns += 1;
+    }
+
+    /// Whole seconds played this run
+    pub fn seconds(&self) -> u32 {
+        self.elapsed as u32
+    }
+
+    /// Whole seconds spent on the current floor
+    pub fn floor_seconds(&self) -> u32 {
+        (self.elapsed - self.floor_start.0) as u32
+    }
+
+    /// Close the current floor's split and start timing the next one
+    pub fn split(&mut self, floor: u32, best: Option<u32>) -> FloorSplit {
+        let split = FloorSplit {
+            floor,
+            at: self.seconds(),
+            seconds: self.floor_seconds(),
+            turns: self.turns - self.floor_start.1,
+            best,
+        };
+        self.splits.push(split);
+        self.floor_start = (self.elapsed, self.turns);
+        split
+    }
+}
+
+/// Minutes and seconds, with hours once a run goes past one
+pub fn format_run_time(seconds: u32) -> String {
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// A split's difference from the personal best, like "-0:12" or "+1:05"
+pub fn format_split_delta(delta: i64) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{}{}", sign, format_run_time(delta.unsigned_abs() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_clock_splits() {
+        let mut clock = RunClock::default();
+        clock.tick(65.5);
+        clock.count_turn();
+        clock.count_turn();
+        let first = clock.split(1, Some(70));
+        assert_eq!((first.at, first.seconds, first.turns), (65, 65, 2));
+        assert_eq!(first.delta(), Some(-5));
+
+        // The next floor's split starts from where the last one ended
+        clock.tick(30.0);
+        clock.count_turn();
+        assert_eq!(clock.floor_seconds(), 30);
+        let second = clock.split(2, None);
+        assert_eq!((second.at, second.seconds, second.turns), (95, 30, 1));
+        assert_eq!(clock.splits.len(), 2);
+
+        assert_eq!(format_run_time(754), "12:34");
+        assert_eq!(format_run_time(3723), "1:02:03");
+        assert_eq!(format_split_delta(-12), "-0:12");
+        assert_eq!(format_split_delta(65), "+1:05");
+    }
+}
@@ -0,0 +1,142 @@
+//! Shrine rules
+//!
+//! Outcome tables for the gambling, sacrifice and transmutation shrines.
+
+use rand::Rng;
+
+use crate::items::{Item, EquipSlot, Rarity};
+
+/// Corruption level at which an item can no longer be transmuted
+pub const MAX_TRANSMUTE_CORRUPTION: u8 = 10;
+
+/// Result of an offering at a gambling shrine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GambleOutcome {
+    /// The shrine keeps the gold
+    Nothing,
+    /// The player is weakened for a while
+    Curse,
+    /// Gold is paid back (amount won)
+    Gold(u32),
+    /// A random consumable
+    Consumable,
+    /// A random piece of equipment from deeper floors
+    Equipment,
+    /// A large gold payout (amount won)
+    Jackpot(u32),
+}
+
+impl GambleOutcome {
+    /// Message shown when this outcome is rolled
+    pub fn description(&self) -> String {
+        match self {
+            GambleOutcome::Nothing => "The shrine swallows your gold. Nothing happens.".to_string(),
+            GambleOutcome::Curse => "The shrine laughs. A chill saps your strength!".to_string(),
+            GambleOutcome::Gold(amount) => format!("Coins spill from the shrine! You win {} gold.", amount),
+            GambleOutcome::Consumable => "A small vial materializes before you.".to_string(),
+            GambleOutcome::Equipment => "Something glints in the shrine's maw...".to_string(),
+            GambleOutcome::Jackpot(amount) => format!("JACKPOT! The shrine erupts with {} gold!", amount),
+        }
+    }
+}
+
+/// Roll the outcome of a gamble that cost `cost` gold
+pub fn roll_gamble(cost: u32, rng: &mut impl Rng) -> GambleOutcome {
+    match rng.gen_range(0..100) {
+        0..=34 => GambleOutcome::Nothing,
+        35..=49 => GambleOutcome::Curse,
+        50..=69 => GambleOutcome::Gold(cost * 2),
+        70..=84 => GambleOutcome::Consumable,
+        85..=96 => GambleOutcome::Equipment,
+        _ => GambleOutcome::Jackpot(cost * 5),
+    }
+}
+
+/// Stat permanently raised by a sacrifice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SacrificeStat {
+    Strength,
+    Dexterity,
+    Intelligence,
+    Vitality,
+}
+
+impl SacrificeStat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SacrificeStat::Strength => "Strength",
+            SacrificeStat::Dexterity => "Dexterity",
+            SacrificeStat::Intelligence => "Intelligence",
+            SacrificeStat::Vitality => "Vitality",
+        }
+    }
+}
+
+/// Stat and amount granted for sacrificing an item from a slot
+pub fn sacrifice_boon(item: &Item, slot: EquipSlot) -> (SacrificeStat, i32) {
+    let stat = match slot {
+        EquipSlot::MainHand => SacrificeStat::Strength,
+        EquipSlot::OffHand | EquipSlot::Ring1 | EquipSlot::Ring2 => SacrificeStat::Dexterity,
+        EquipSlot::Head | EquipSlot::Amulet => SacrificeStat::Intelligence,
+        EquipSlot::Body | EquipSlot::Hands | EquipSlot::Feet => SacrificeStat::Vitality,
+    };
+
+    let base = match item.rarity {
+        Rarity::Common | Rarity::Uncommon => 1,
+        Rarity::Rare => 2,
+        Rarity::Epic => 3,
+        Rarity::Legendary => 4,
+        Rarity::Mythic => 5,
+    };
+
+    // Heavily enchanted items make for a richer offering
+    (stat, base + item.enchantment_level as i32 / 3)
+}
+
+/// Whether an item can still be transmuted
+pub fn can_transmute(item: &Item) -> bool {
+    item.corruption_level < MAX_TRANSMUTE_CORRUPTION
+}
+
+/// Reroll an item's affixes, corrupting it in the process
+pub fn transmute_item(item: &mut Item, rng: &mut impl Rng) -> bool {
+    if !can_transmute(item) {
+        return false;
+    }
+    crate::items::reroll_affixes(item, rng);
+    item.corrupt();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::items::item::templates;
+
+    #[test]
+    fn test_transmute_corrupts() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut item = templates::iron_sword(1);
+        item.rarity = Rarity::Rare;
+
+        assert!(transmute_item(&mut item, &mut rng));
+        assert_eq!(item.corruption_level, 1);
+        assert_eq!(item.affixes.len(), 2);
+
+        item.corruption_level = MAX_TRANSMUTE_CORRUPTION;
+        assert!(!transmute_item(&mut item, &mut rng));
+    }
+
+    #[test]
+    fn test_sacrifice_boon_scales_with_rarity() {
+        let mut item = templates::iron_sword(1);
+        let (stat, common) = sacrifice_boon(&item, EquipSlot::MainHand);
+        assert_eq!(stat, SacrificeStat::Strength);
+
+        item.rarity = Rarity::Legendary;
+        let (_, legendary) = sacrifice_boon(&item, EquipSlot::MainHand);
+        assert!(legendary > common);
+    }
+}
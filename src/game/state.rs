@@ -4,15 +4,69 @@
 
 use std::time::{Duration, Instant};
 use hecs::{World, Entity};
+use serde::{Deserialize, Serialize};
 use rand::SeedableRng;
 use rand::rngs::StdRng;
 
 use crate::world::Map;
+use crate::world::generation::{floor_name, FINAL_FLOOR};
 use crate::progression::Difficulty;
-use crate::ecs::{Position, Health, Mana, Stamina, Stats, Experience};
-use crate::save::{PlayerProfile, load_profile, save_profile};
+use crate::ecs::{Position, Health, Mana, Stamina, Stats, Experience, Flow, Schedule, Stage};
+use crate::save::{PlayerProfile, load_profile, save_profile, RunStats, ScoreBreakdown};
 use crate::data::DataManager;
+use crate::error::{Error, ErrorPopups};
 use crate::audio::{AudioManager, SoundId};
+use crate::presence::{PresenceManager, PresenceActivity};
+use crate::combat::{EXHAUSTION_DAMAGE_PENALTY, EXHAUSTION_DODGE_PENALTY};
+
+/// Range at which bosses can drag the player in
+const BOSS_YANK_RANGE: i32 = 6;
+/// Blows needed to break down a closed door
+const DOOR_HP: i32 = 3;
+/// Blows needed to break down a locked door
+const LOCKED_DOOR_HP: i32 = 6;
+/// Damage per turn spent standing in lava
+const LAVA_DAMAGE: i32 = 8;
+/// Damage from falling through a pit to the floor below
+const PIT_FALL_DAMAGE: i32 = 15;
+/// Damage a chest trap deals on the first floor; it grows by one per floor
+const CHEST_TRAP_DAMAGE: i32 = 6;
+/// Turns of Abyss exposure each turn in corrupted blood is worth
+const BLOOD_POOL_EXPOSURE: u32 = 10;
+/// Stamina a sprinting turn costs before DEX and armor weight
+const SPRINT_BASE_COST: i32 = 6;
+/// Stamina (percent of max) needed to shake off exhaustion
+const EXHAUSTION_RECOVERY_PERCENT: i32 = 30;
+/// Max HP (percent) the darkness takes for each trip to death's door
+const DEATHS_DOOR_MAX_HP_COST: i32 = 10;
+/// Turns of weakness and slowness after surviving at death's door
+const DEATHS_DOOR_DEBUFF_TURNS: f32 = 20.0;
+/// Blows needed to tear through living flesh
+const FLESH_HP: i32 = 2;
+/// Chance each turn that a stretch of torn flesh nobody is standing in
+/// knits shut again
+const FLESH_REGROW_CHANCE: f64 = 0.02;
+/// Most heart nodes the Gardener keeps beating at once
+const GARDENER_MAX_HEARTS: usize = 3;
+/// Turns a creature caught in spilled coals or burning oil keeps burning
+const FIRE_BURN_TURNS: f32 = 3.0;
+/// Burn damage per turn from spilled coals or burning oil
+const FIRE_BURN_INTENSITY: i32 = 3;
+/// Damage from a dart trap
+const DART_DAMAGE: i32 = 6;
+/// Turns a dart trap's poison lasts
+const DART_POISON_TURNS: f32 = 4.0;
+/// Poison damage per turn from a dart trap
+const DART_POISON_INTENSITY: i32 = 2;
+/// Door damage the Hollow Warden deals with each blow
+const STALKER_BASH_POWER: i32 = 3;
+/// Share of the chance to spot a trap that a secret door beside the player
+/// is noticed each turn without searching
+const SECRET_NOTICE_FACTOR: f64 = 0.25;
+/// How far a deliberate search reaches
+const SEARCH_RADIUS: i32 = 2;
+/// Added to the chance to spot a secret door when searching for one
+const SEARCH_BONUS: f64 = 0.4;
 
 /// The main game struct that holds all game data
 pub struct Game {
@@ -24,10 +78,18 @@ pub struct Game {
     map: Option<Map>,
     /// Random number generator (seeded for reproducibility)
     rng: StdRng,
+    /// Seed the run's generator started from
+    seed: u64,
     /// Current floor number
     floor: u32,
     /// Current difficulty setting
     difficulty: Difficulty,
+    /// Curse sigils the run was started under
+    sigils: crate::progression::CurseSigils,
+    /// Adaptive difficulty, when the run opted into it
+    adaptive: Option<super::AdaptiveDifficulty>,
+    /// Whether the run carried on into the depths below the final floor
+    endless: bool,
     /// Message log
     messages: Vec<GameMessage>,
     /// Accumulated time for ambient effects
@@ -40,16 +102,92 @@ pub struct Game {
     used_shrines: std::collections::HashSet<(u32, i32, i32)>,
     /// Persistent player profile
     profile: PlayerProfile,
-    /// Accumulated mana regeneration (fractional)
-    mana_regen_accum: f32,
-    /// Accumulated stamina regeneration (fractional)
-    stamina_regen_accum: f32,
+    /// Mana and stamina regeneration carried between turns
+    regen: super::TurnRegen,
     /// When the current run started (for playtime tracking)
     run_start_time: Option<Instant>,
     /// External game data (items, enemies, skills, synergies)
     data: DataManager,
     /// Audio manager for sound effects
     audio: AudioManager,
+    /// Statistics for the current run (used for scoring)
+    run_stats: RunStats,
+    /// Score of the last finished run and its leaderboard rank (if it placed)
+    last_score: Option<(ScoreBreakdown, Option<usize>)>,
+    /// Discord Rich Presence publisher
+    presence: PresenceManager,
+    /// Unix time (seconds) the current run started, for presence elapsed time
+    run_started_unix: Option<u64>,
+    /// Standing with the gods for the current run
+    worship: super::Worship,
+    /// Standing with the Merchants' Guild and the Penitent Order for the current run
+    reputation: super::Reputation,
+    /// Paid shrine services this run, which push shrine prices up
+    shrine_services: u32,
+    /// Whether the player moves two tiles per turn
+    sprinting: bool,
+    /// Fleeing enemies the player gets a free strike against
+    fleeing_strikes: Vec<Entity>,
+    /// Action readied for the coming enemy turn, with its reach
+    prepared: Option<(super::PreparedAction, i32)>,
+    /// Enemy that walked into the prepared action, for the caller to resolve
+    overwatch_strike: Option<(Entity, super::PreparedAction)>,
+    /// Blows each battered door on the current floor has taken
+    door_damage: std::collections::HashMap<Position, i32>,
+    /// Player HP when the current turn began, for what the turn cost them
+    turn_start_hp: Option<i32>,
+    /// How long each stage of the last turn took, for the perf overlay
+    turn_times: crate::ecs::StageTimes,
+    /// Enemies slain since the dead were last reaped
+    deaths: super::DeathQueue,
+    /// The rest in progress, if the player is resting
+    rest: Option<super::Rest>,
+    /// Multi-turn action in progress
+    channel: Option<super::Channel>,
+    /// Moves made while overloaded, to pace the extra enemy turns
+    overload_steps: u32,
+    /// Synergy tags worn at the last check, to announce tier changes
+    worn_synergy_tags: Option<Vec<crate::items::SynergyTag>>,
+    /// This run's potion and scroll appearances
+    discoveries: crate::items::Discoveries,
+    /// Whether death's door has already saved the player on this floor
+    deaths_door_used: bool,
+    /// Turns taken on this floor, which bring the Warden when they mount up
+    floor_turns: u32,
+    /// Turns and time over the whole run, with floor splits
+    run_clock: super::RunClock,
+    /// Tutorial step being shown, while on the tutorial floor
+    tutorial: Option<usize>,
+    /// Enemies on this floor already checked for the danger warning
+    sized_up: std::collections::HashSet<Entity>,
+    /// Melee blows landed since the auras were last resolved (for thorns)
+    hits: Vec<crate::combat::Hit>,
+    /// Damage and healing numbers rising off the map
+    floating: Vec<super::FloatingText>,
+    /// Progress through a boss rush, when this run is one
+    boss_rush: Option<super::BossRush>,
+    /// New Game Plus cycle, 0 on a first run
+    ng_plus: u32,
+    /// How the story ended, once the run is won
+    epilogue: Option<crate::data::Epilogue>,
+    /// What has befallen the current floor, if anything
+    floor_event: Option<crate::data::FloorEventKind>,
+    /// Announcement of the floor just entered
+    floor_banner: Option<super::FloorBanner>,
+    /// The floor coming down after its boss fell, if it is
+    collapse: Option<super::Collapse>,
+    /// Seconds left of the screen shaking
+    shake: f32,
+    /// Turn on this floor the traveling peddler turns up, if they're coming
+    peddler_due: Option<u32>,
+    /// Cutscenes waiting to play, the first one playing
+    cutscenes: std::collections::VecDeque<super::Cutscene>,
+    /// State to move to once the cutscenes are over
+    after_cutscenes: Option<GameState>,
+    /// Errors waiting in a popup for the player to dismiss
+    errors: ErrorPopups,
+    /// Nothing is written to disk: no profile, no autosaves
+    headless: bool,
 }
 
 /// All possible game states
@@ -72,6 +210,10 @@ pub enum GameState {
     LoadSlots { selected: u8 },
     /// Viewing achievements and stats
     Achievements,
+    /// Viewing the local high score table
+    Leaderboard,
+    /// Reading the latest lines of the log, scrolled back some lines
+    Log { scroll: usize },
     /// Player died
     GameOver {
         floor_reached: u32,
@@ -98,16 +240,22 @@ pub enum PlayingState {
     Shrine { shrine_type: ShrineType },
     /// Shopping at a merchant
     Shop { npc_entity: Entity },
+    /// Throwing dice with the gambler
+    Dice { npc_entity: Entity },
     /// Viewing character sheet
     Character,
     /// Viewing full map
     MapView,
     /// Help screen
     Help,
+    /// Choosing what to take from a pile of items
+    Pickup,
+    /// Potion and scroll appearances identified this run
+    Discoveries,
 }
 
 /// Types of shrines the player can interact with
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ShrineType {
     /// Learn new skills
     Skill,
@@ -117,6 +265,14 @@ pub enum ShrineType {
     Rest,
     /// Corruption shrine (risk/reward)
     Corruption,
+    /// Pay gold for a random outcome
+    Gambling,
+    /// Destroy an equipped item for a permanent stat
+    Sacrifice,
+    /// Reroll an item's affixes at the cost of corruption
+    Transmutation,
+    /// Worship at a god's altar
+    Altar(super::Deity),
 }
 
 /// A message to display in the game log
@@ -140,27 +296,91 @@ pub enum MessageCategory {
 impl Game {
     /// Create a new game instance
     pub fn new() -> Self {
-        let profile = load_profile();
-        let data = DataManager::new();
-        let audio = AudioManager::new();
+        let (profile, profile_error) = load_profile();
+        let (data, data_errors) = DataManager::load_from_assets();
+        let (audio, audio_error) = AudioManager::open();
+        let mut game = Self::with(profile, data, audio);
+        for error in profile_error.into_iter().chain(data_errors).chain(audio_error) {
+            game.report_error(error);
+        }
+        game.update_presence();
+        game
+    }
+
+    /// A game that keeps to itself: a fresh profile that's never written,
+    /// the default data, no sound and no autosaves. For benchmarks and
+    /// tools that play the game without a player.
+    pub fn headless() -> Self {
+        let mut game = Self::with(PlayerProfile::new(), DataManager::default(), AudioManager::silent());
+        game.headless = true;
+        game
+    }
+
+    fn with(profile: PlayerProfile, data: DataManager, audio: AudioManager) -> Self {
+        let presence = PresenceManager::new(
+            profile.settings.discord_presence,
+            &profile.settings.discord_client_id,
+        );
         Self {
             state: GameState::MainMenu,
             world: World::new(),
             map: None,
             rng: StdRng::from_entropy(),
+            seed: 0,
             floor: 0,
             difficulty: Difficulty::Normal,
+            sigils: crate::progression::CurseSigils::default(),
+            adaptive: None,
+            endless: false,
             messages: Vec::new(),
             ambient_time: 0.0,
             player_entity: None,
             item_id_counter: 1000, // Start at 1000 to reserve low IDs
             used_shrines: std::collections::HashSet::new(),
             profile,
-            mana_regen_accum: 0.0,
-            stamina_regen_accum: 0.0,
+            regen: super::TurnRegen::default(),
             run_start_time: None,
             data,
             audio,
+            run_stats: RunStats::default(),
+            last_score: None,
+            presence,
+            run_started_unix: None,
+            worship: super::Worship::default(),
+            reputation: super::Reputation::default(),
+            shrine_services: 0,
+            sprinting: false,
+            fleeing_strikes: Vec::new(),
+            prepared: None,
+            overwatch_strike: None,
+            door_damage: std::collections::HashMap::new(),
+            turn_start_hp: None,
+            turn_times: crate::ecs::StageTimes::default(),
+            deaths: super::DeathQueue::default(),
+            rest: None,
+            channel: None,
+            overload_steps: 0,
+            worn_synergy_tags: None,
+            discoveries: crate::items::Discoveries::default(),
+            deaths_door_used: false,
+            floor_turns: 0,
+            run_clock: super::RunClock::default(),
+            tutorial: None,
+            sized_up: std::collections::HashSet::new(),
+            hits: Vec::new(),
+            floating: Vec::new(),
+            boss_rush: None,
+            ng_plus: 0,
+            epilogue: None,
+            floor_event: None,
+            floor_banner: None,
+            collapse: None,
+            shake: 0.0,
+            peddler_due: None,
+            cutscenes: std::collections::VecDeque::new(),
+            after_cutscenes: None,
+            errors: ErrorPopups::default(),
+            headless: false,
         }
     }
 
@@ -186,6 +406,11 @@ impl Game {
         id
     }
 
+    /// The next item ID, without taking it (for saving)
+    pub fn item_id_counter(&self) -> u64 {
+        self.item_id_counter
+    }
+
     /// Get the player entity
     pub fn player(&self) -> Option<Entity> {
         self.player_entity
@@ -205,6 +430,7 @@ impl Game {
                 *p = pos;
             }
         }
+        self.tutorial_event(crate::data::TutorialTrigger::Moved);
     }
 
     /// Get player health
@@ -235,6 +461,12 @@ impl Game {
         })
     }
 
+    /// The player's stats with gear, set bonuses, mutations, buffs and
+    /// exhaustion applied
+    pub fn player_derived_stats(&self) -> Option<crate::combat::DerivedStats> {
+        crate::combat::DerivedStats::compute(&self.world, self.player_entity?)
+    }
+
     /// Get player experience
     pub fn player_experience(&self) -> Option<Experience> {
         self.player_entity.and_then(|e| {
@@ -253,42 +485,110 @@ impl Game {
         false
     }
 
-    /// Heal the player (considers equipment HP bonuses)
-    pub fn heal_player(&mut self, amount: i32) {
-        use crate::ecs::EquipmentComponent;
+    /// This run's potion and scroll appearances
+    pub fn discoveries(&self) -> &crate::items::Discoveries {
+        &self.discoveries
+    }
 
-        if let Some(entity) = self.player_entity {
-            // Get equipment HP bonus
-            let eq_hp = self.world.get::<&EquipmentComponent>(entity)
-                .map(|eq| eq.equipment.hp_bonus())
-                .unwrap_or(0);
+    /// Identify a potion or scroll kind, announcing what it looked like
+    pub fn discover_item(&mut self, name: &str) {
+        let Some(appearance) = self.discoveries.appearance(name).map(str::to_string) else { return };
+        self.discoveries.learn(name);
+        self.add_message(format!("The {} was a {}!", appearance, name), MessageCategory::Item);
+    }
 
-            if let Ok(mut health) = self.world.get::<&mut Health>(entity) {
-                // Heal up to effective max (base + equipment bonus)
-                let effective_max = health.max + eq_hp;
-                let actual_heal = amount.min(effective_max - health.current);
-                health.current += actual_heal;
+    /// Move the player to a random open tile on the floor.
+    /// Returns false if there was nowhere to go.
+    pub fn teleport_player_randomly(&mut self) -> bool {
+        use rand::seq::SliceRandom;
+
+        let Some(map) = &self.map else { return false };
+        let spots: Vec<Position> = map.get_walkable_positions()
+            .into_iter()
+            .filter(|p| map.get_tile(p.x, p.y).is_some_and(|t| !t.tile_type.is_hazard()))
+            .filter(|p| !self.is_blocked_by_entity(*p))
+            .collect();
+        let Some(&dest) = spots.choose(&mut self.rng) else { return false };
+        self.set_player_position(dest);
+        self.refresh_fov();
+        true
+    }
+
+    /// Carry the player back to where they entered the floor, or the
+    /// nearest free tile if something is standing there
+    pub fn return_to_entrance(&mut self) -> bool {
+        let Some(map) = &self.map else { return false };
+        let start = map.start_pos;
+        let dest = map.get_walkable_positions()
+            .into_iter()
+            .filter(|p| map.get_tile(p.x, p.y).is_some_and(|t| !t.tile_type.is_hazard()))
+            .filter(|p| !self.is_blocked_by_entity(*p))
+            .min_by_key(|p| p.chebyshev_distance(&start));
+        let Some(dest) = dest else { return false };
+        self.set_player_position(dest);
+        self.refresh_fov();
+        true
+    }
+
+    /// Ride the teleporter pad the player is standing on to its partner.
+    /// Returns false if there's no pad here or something blocks the far end.
+    pub fn take_teleporter(&mut self) -> bool {
+        let Some(pos) = self.player_position() else { return false };
+        let Some(dest) = self.map.as_ref().and_then(|m| m.teleporter_exit(pos)) else { return false };
+        if self.is_blocked_by_entity(dest) {
+            self.add_message("The pad flickers. Something stands on the other side.", MessageCategory::Warning);
+            return false;
+        }
+        self.set_player_position(dest);
+        self.refresh_fov();
+        self.play_sound(SoundId::SkillMovement);
+        self.float_text(dest, "*", (140, 110, 255));
+        self.add_message("The pad hums and the world folds. You step out elsewhere.", MessageCategory::System);
+        true
+    }
+
+    /// Mark every tile on the floor as explored
+    pub fn reveal_map(&mut self) {
+        if let Some(map) = self.map.as_mut() {
+            for tile in &mut map.tiles {
+                tile.explored = true;
             }
         }
     }
 
-    /// Restore player mana (considers equipment MP bonuses)
-    pub fn restore_mana(&mut self, amount: i32) {
-        use crate::ecs::EquipmentComponent;
+    /// Heal the player up to their effective max HP, returning the HP healed
+    pub fn heal_player(&mut self, amount: i32) -> i32 {
+        let amount = self.sigils.healing(amount);
+        let Some(max_hp) = self.player_derived_stats().map(|d| d.max_hp) else { return 0 };
+        let Some(entity) = self.player_entity else { return 0 };
+        self.world.get::<&mut Health>(entity)
+            .map(|mut health| {
+                let actual_heal = amount.min(max_hp - health.current).max(0);
+                health.current += actual_heal;
+                actual_heal
+            })
+            .unwrap_or(0)
+    }
 
-        if let Some(entity) = self.player_entity {
-            // Get equipment MP bonus
-            let eq_mp = self.world.get::<&EquipmentComponent>(entity)
-                .map(|eq| eq.equipment.mp_bonus())
-                .unwrap_or(0);
-
-            if let Ok(mut mana) = self.world.get::<&mut Mana>(entity) {
-                // Restore up to effective max (base + equipment bonus)
-                let effective_max = mana.max + eq_mp;
-                let actual_restore = amount.min(effective_max - mana.current);
+    /// Restore player mana up to their effective max MP, returning the MP restored
+    pub fn restore_mana(&mut self, amount: i32) -> i32 {
+        let Some(max_mp) = self.player_derived_stats().map(|d| d.max_mp) else { return 0 };
+        let Some(entity) = self.player_entity else { return 0 };
+        self.world.get::<&mut Mana>(entity)
+            .map(|mut mana| {
+                let actual_restore = amount.min(max_mp - mana.current).max(0);
                 mana.current += actual_restore;
-            }
-        }
+                actual_restore
+            })
+            .unwrap_or(0)
+    }
+
+    /// Weight class of the player's worn armor
+    pub fn armor_weight(&self) -> crate::items::WeightClass {
+        self.player_entity
+            .and_then(|e| self.world.get::<&crate::ecs::EquipmentComponent>(e).ok())
+            .map(|eq| eq.equipment.weight_class())
+            .unwrap_or(crate::items::WeightClass::Light)
     }
 
     /// Restore player stamina
@@ -329,6 +629,16 @@ impl Game {
         None
     }
 
+    /// Get the enemy standing at a position, if any
+    pub fn enemy_at(&self, pos: Position) -> Option<hecs::Entity> {
+        use crate::ecs::Enemy;
+
+        self.world.query::<(&Position, &Enemy)>()
+            .iter()
+            .find(|(_, (p, _))| p.x == pos.x && p.y == pos.y)
+            .map(|(entity, _)| entity)
+    }
+
     /// Get the current game state
     pub fn state(&self) -> &GameState {
         &self.state
@@ -338,6 +648,63 @@ impl Game {
     pub fn set_state(&mut self, state: GameState) {
         log::debug!("State transition: {:?} -> {:?}", self.state, state);
         self.state = state;
+        self.update_presence();
+    }
+
+    /// Publish the current activity to rich presence (no-op if unchanged)
+    pub fn update_presence(&mut self) {
+        use crate::entities::BossComponent;
+        use crate::ecs::{AI, AIState};
+        use crate::progression::xp::level_title;
+
+        let details;
+        let state;
+        match &self.state {
+            GameState::Playing(_) | GameState::Paused | GameState::SaveSlots { .. } => {
+                let level = self.player_experience().map(|x| x.level).unwrap_or(1);
+                details = format!("Lv {} {} - {}", level, level_title(level), floor_name(self.floor));
+
+                // A boss that has noticed the player means a boss fight is underway
+                let engaged_boss = self.world
+                    .query::<(&BossComponent, &AI)>()
+                    .iter()
+                    .find(|(_, (_, ai))| ai.state != AIState::Idle)
+                    .map(|(_, (boss, _))| boss.boss_type.name());
+                state = match engaged_boss {
+                    Some(name) => format!("Fighting {}", name),
+                    None => format!("{} ({})", self.biome().name(), self.difficulty.name()),
+                };
+            }
+            GameState::GameOver { floor_reached, .. } => {
+                details = format!("Fell on {}", floor_name(*floor_reached).to_lowercase());
+                state = match &self.last_score {
+                    Some((score, _)) => format!("Score {}", score.total),
+                    None => "Claimed by the darkness".to_string(),
+                };
+            }
+            GameState::Victory => {
+                details = "Conquered the Hollowdeep".to_string();
+                state = match &self.last_score {
+                    Some((score, _)) => format!("Score {}", score.total),
+                    None => "Victorious".to_string(),
+                };
+            }
+            _ => {
+                details = "In the main menu".to_string();
+                state = "Preparing to descend".to_string();
+            }
+        }
+
+        let started_at = match self.state {
+            GameState::Playing(_) | GameState::Paused | GameState::SaveSlots { .. } => self.run_started_unix,
+            _ => None,
+        };
+        self.presence.update(PresenceActivity { details, state, started_at });
+    }
+
+    /// Get mutable access to the presence manager
+    pub fn presence(&mut self) -> &mut PresenceManager {
+        &mut self.presence
     }
 
     /// Get the ECS world
@@ -370,6 +737,36 @@ impl Game {
         self.difficulty
     }
 
+    /// Curse sigils the run was started under
+    pub fn sigils(&self) -> &crate::progression::CurseSigils {
+        &self.sigils
+    }
+
+    /// Whether the run carried on into the depths below the final floor
+    pub fn endless(&self) -> bool {
+        self.endless
+    }
+
+    /// Adaptive difficulty, when the run opted into it
+    pub fn adaptive(&self) -> Option<&super::AdaptiveDifficulty> {
+        self.adaptive.as_ref()
+    }
+
+    /// The floor loot is rolled for, which adaptive difficulty may push
+    /// deeper
+    pub fn loot_floor(&self) -> u32 {
+        self.adaptive.as_ref().map_or(self.floor, |a| a.loot_floor(self.floor))
+    }
+
+    /// Let adaptive difficulty judge the floor just cleared
+    fn judge_floor(&mut self) {
+        let deaths = self.profile.deaths_on_floor(self.floor);
+        let Some(adaptive) = self.adaptive.as_mut() else { return };
+        let Some(shift) = adaptive.floor_cleared(self.floor, self.floor_turns, deaths) else { return };
+        let message = format!("⚖ Adaptive difficulty is now {}: {}.", shift.describe(), shift.reason);
+        self.add_message(message, MessageCategory::System);
+    }
+
     /// Get the current biome based on floor
     pub fn biome(&self) -> crate::world::Biome {
         crate::world::generation::biome_for_floor(self.floor)
@@ -398,14 +795,41 @@ impl Game {
     pub fn update(&mut self, delta: Duration) {
         let delta_secs = delta.as_secs_f32();
 
+        if matches!(self.state, GameState::Playing(_)) {
+            self.check_synergy_changes();
+            // The boss rush clock runs through shopping and menus alike
+            if let Some(rush) = &mut self.boss_rush {
+                rush.elapsed += delta_secs;
+            }
+            self.run_clock.tick(delta_secs);
+            self.update_cutscene(delta_secs);
+            for text in &mut self.floating {
+                text.age += delta_secs;
+            }
+            self.shake = (self.shake - delta_secs).max(0.0);
+            self.floating.retain(|text| !text.expired());
+            // The floor banner waits for any cutscene to finish
+            if let Some(banner) = self.floor_banner.as_mut().filter(|_| self.cutscenes.is_empty()) {
+                banner.remaining -= delta_secs;
+                if banner.remaining <= 0.0 {
+                    self.floor_banner = None;
+                }
+            }
+        }
+
         match &self.state {
             GameState::Playing(PlayingState::Exploring) => {
                 // Update ambient time for effects
+                let was_night = self.is_night();
                 self.ambient_time += delta_secs;
+                match (was_night, self.is_night()) {
+                    (false, true) => self.add_message("Night falls. The dungeon's sleepers settle in.", MessageCategory::Lore),
+                    (true, false) => self.add_message("Dawn. The dungeon stirs awake.", MessageCategory::Lore),
+                    _ => {}
+                }
 
-                // Passive mana regeneration while exploring
-                // Base: 1 MP every 3 seconds + INT/10 bonus
-                self.regenerate_resources(delta_secs);
+                self.advance_rest(delta_secs);
+                self.advance_channel(delta_secs);
             }
             GameState::Playing(PlayingState::Combat) => {
                 // Combat is turn-based, no time updates
@@ -415,83 +839,81 @@ impl Game {
         }
     }
 
-    /// Regenerate mana and stamina over time
-    fn regenerate_resources(&mut self, delta_secs: f32) {
-        use crate::ecs::{Stats, EquipmentComponent};
+    /// Announce synergies that activated, changed tier or broke since the
+    /// last check. The first check after a run starts or loads only records.
+    fn check_synergy_changes(&mut self) {
+        use crate::ecs::EquipmentComponent;
 
-        let player = match self.player_entity {
-            Some(p) => p,
-            None => return,
+        let Some(player) = self.player_entity else { return };
+        let Some(tags) = self.world.get::<&EquipmentComponent>(player)
+            .ok()
+            .map(|eq| eq.equipment.synergy_tags())
+        else {
+            return;
         };
 
-        // Get INT for mana regen scaling
-        let int_bonus = self.world
-            .get::<&Stats>(player)
-            .map(|s| s.intelligence as f32 / 10.0)
-            .unwrap_or(0.0);
-
-        // Mana regen: base 0.33 MP/sec (1 every 3 sec) + INT scaling
-        let mana_per_sec = 0.33 + int_bonus * 0.1;
-
-        // Stamina regen: base 0.5/sec
-        let stamina_per_sec = 0.5;
-
-        // Accumulate regen (fractional amounts)
-        self.mana_regen_accum += mana_per_sec * delta_secs;
-        self.stamina_regen_accum += stamina_per_sec * delta_secs;
-
-        // Apply whole mana regen
-        if self.mana_regen_accum >= 1.0 {
-            let regen = self.mana_regen_accum as i32;
-            self.mana_regen_accum -= regen as f32;
-
-            // Use restore_mana which considers equipment bonuses
-            // (we can't call self.restore_mana here due to borrow, so inline it)
-            let eq_mp = self.world.get::<&EquipmentComponent>(player)
-                .map(|eq| eq.equipment.mp_bonus())
-                .unwrap_or(0);
-            if let Ok(mut mana) = self.world.get::<&mut Mana>(player) {
-                let effective_max = mana.max + eq_mp;
-                let actual_regen = regen.min(effective_max - mana.current);
-                mana.current += actual_regen;
+        if let Some(before) = self.worn_synergy_tags.replace(tags.clone()) {
+            if before == tags {
+                return;
             }
-        }
-
-        // Apply whole stamina regen
-        if self.stamina_regen_accum >= 1.0 {
-            let regen = self.stamina_regen_accum as i32;
-            self.stamina_regen_accum -= regen as f32;
-
-            if let Ok(mut stamina) = self.world.get::<&mut Stamina>(player) {
-                stamina.restore(regen);
+            for change in crate::items::synergy_changes(&before, &tags) {
+                self.add_message(change.message(), MessageCategory::Item);
             }
         }
     }
 
     /// Start a new run with the given settings
-    pub fn start_new_run(&mut self, seed: Option<u64>, difficulty: Difficulty) {
+    pub fn start_new_run(&mut self, seed: Option<u64>, difficulty: Difficulty, sigils: crate::progression::CurseSigils) {
+        self.start_run(seed, difficulty, sigils, 0);
+    }
+
+    /// Start a run on a New Game Plus cycle (0 for a first run)
+    fn start_run(&mut self, seed: Option<u64>, difficulty: Difficulty, sigils: crate::progression::CurseSigils, ng_plus: u32) {
         // Record run start in profile and start playtime tracking
         self.profile.record_run_start();
         self.run_start_time = Some(Instant::now());
-        if let Err(e) = save_profile(&self.profile) {
-            log::warn!("Failed to save profile: {}", e);
-        }
+        self.store_profile();
 
-        // Reset game state
+        // Reset game state; a new profile starts on the tutorial floor
         self.world = World::new();
-        self.floor = 1;
+        self.tutorial = (ng_plus == 0 && !self.profile.tutorial_completed).then_some(0);
+        self.floor = if self.tutorial.is_some() { 0 } else { 1 };
         self.difficulty = difficulty;
+        self.sigils = sigils;
+        self.adaptive = self.profile.settings.adaptive_difficulty.then(super::AdaptiveDifficulty::default);
+        self.endless = false;
         self.messages.clear();
         self.ambient_time = 0.0;
         self.player_entity = None;
         self.item_id_counter = 1000;
         self.used_shrines.clear();
-
-        // Seed RNG
-        self.rng = match seed {
-            Some(s) => StdRng::seed_from_u64(s),
-            None => StdRng::from_entropy(),
-        };
+        self.run_stats = RunStats::default();
+        self.last_score = None;
+        self.run_started_unix = Some(crate::save::leaderboard::unix_timestamp());
+        self.worship = super::Worship::default();
+        self.reputation = super::Reputation::default();
+        self.shrine_services = 0;
+        self.sprinting = false;
+        self.fleeing_strikes.clear();
+        self.prepared = None;
+        self.overwatch_strike = None;
+        self.rest = None;
+        self.channel = None;
+        self.overload_steps = 0;
+        self.worn_synergy_tags = None;
+        self.deaths_door_used = false;
+        self.floor_turns = 0;
+        self.run_clock = super::RunClock::default();
+        self.boss_rush = None;
+        self.ng_plus = ng_plus;
+        self.epilogue = None;
+        self.cutscenes.clear();
+        self.after_cutscenes = None;
+
+        // Seed RNG, keeping the seed for crash reports
+        self.seed = seed.unwrap_or_else(rand::random);
+        self.rng = StdRng::seed_from_u64(self.seed);
+        self.discoveries = crate::items::Discoveries::new(&mut self.rng);
 
         // Generate first floor
         self.generate_floor();
@@ -501,6 +923,14 @@ impl Game {
             let start = map.start_pos;
             let player = crate::entities::spawn_player(&mut self.world, start);
             self.player_entity = Some(player);
+
+            // The starting kit is what it says on the label
+            let packed: Vec<String> = self.world.get::<&crate::ecs::InventoryComponent>(player)
+                .map(|inv| inv.inventory.items().iter().map(|i| i.name.clone()).collect())
+                .unwrap_or_default();
+            for name in packed {
+                self.discoveries.learn(&name);
+            }
         }
 
         // Transition to playing
@@ -509,6 +939,7 @@ impl Game {
             MessageCategory::System
         );
         self.set_state(GameState::Playing(PlayingState::Exploring));
+        self.autosave();
     }
 
     /// Generate the current floor's map
@@ -516,17 +947,91 @@ impl Game {
         use crate::world::generation::generate_floor;
         use crate::entities::{spawn_enemies_for_floor_with_zones, BossType, spawn_boss, spawn_npcs_for_floor, spawn_chests_for_floor};
 
+        // Nothing from the last floor comes along but the player and followers
+        let left_behind: Vec<Entity> = self.world
+            .query::<&Position>()
+            .without::<&crate::entities::Follower>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .filter(|entity| Some(*entity) != self.player_entity)
+            .collect();
+        for entity in left_behind {
+            let _ = self.world.despawn(entity);
+        }
+
         let biome = crate::world::generation::biome_for_floor(self.floor);
-        self.map = Some(generate_floor(&mut self.rng, self.floor, biome));
+        let mut tutorial_spawns = Vec::new();
+        self.map = Some(if self.floor == 0 {
+            let (map, spawns) = crate::world::generation::templates::tutorial_floor();
+            tutorial_spawns = spawns;
+            map
+        } else {
+            generate_floor(&mut self.rng, self.floor, biome)
+        });
+        // Pilgrims keep a shrine to rest at before each boss
+        let pilgrims_rest = self.profile.has_run_unlock(crate::progression::RunUnlock::PilgrimsRest);
+        if pilgrims_rest && self.floor % 5 == 4 && self.boss_rush.is_none() {
+            if let Some(map) = self.map.as_mut() {
+                map.ensure_shrine(crate::world::TileType::ShrineRest, &mut self.rng);
+            }
+        }
+        if self.sigils.has(crate::progression::CurseSigil::Forsaken) {
+            if let Some(map) = self.map.as_mut() {
+                map.remove_shrines();
+            }
+        }
+        self.door_damage.clear();
+        self.sized_up.clear();
+        self.hits.clear();
+        self.floating.clear();
+        self.rest = None;
+        self.channel = None;
+        self.deaths_door_used = false;
+        self.floor_turns = 0;
+        self.collapse = None;
+        self.shake = 0.0;
 
         // Check if this is a boss floor
         let is_boss_floor = BossType::is_boss_floor(self.floor);
+        let rush_stage = self.boss_rush.map(|rush| rush.boss());
+
+        // Now and then a peddler passes through partway into the floor
+        use rand::Rng;
+        self.peddler_due = None;
+        if self.floor > 0 && !is_boss_floor && self.boss_rush.is_none() && self.rng.gen_bool(crate::entities::PEDDLER_CHANCE) {
+            let (earliest, latest) = crate::entities::PEDDLER_ARRIVAL;
+            self.peddler_due = Some(self.rng.gen_range(earliest..=latest));
+        }
+
+        // Now and then something has befallen the floor
+        self.floor_event = None;
+        if self.floor == 0 {
+            self.furnish_tutorial(tutorial_spawns);
+            return;
+        }
+        if !is_boss_floor && rush_stage.is_none() {
+            self.roll_floor_event(biome);
+        }
+
+        // Boss rush interludes are quiet: a merchant, a shrine and the stairs
+        if rush_stage == Some(None) {
+            self.furnish_interlude();
+            self.announce_floor();
+            log::info!("Generated boss rush interlude on floor {} ({:?})", self.floor, biome);
+            return;
+        }
 
         // Spawn enemies with difficulty scaling (fewer on boss floors)
+        let scaling = self.floor_scaling();
         if let Some(map) = &self.map {
             let spawn_positions = map.get_spawn_positions(5); // Min 5 tiles from player
 
-            if is_boss_floor {
+            if rush_stage.is_some() {
+                // Boss rush arenas hold the boss and nothing else
+                if let (Some(boss_type), Some(exit_pos)) = (BossType::for_floor(self.floor), map.exit_pos) {
+                    spawn_boss(&mut self.world, boss_type, exit_pos);
+                }
+            } else if is_boss_floor {
                 // Boss floor: spawn the boss near the exit
                 if let Some(boss_type) = BossType::for_floor(self.floor) {
                     // Spawn boss at exit position (player must defeat to proceed)
@@ -535,19 +1040,19 @@ impl Game {
                         log::info!("Spawned boss {} on floor {}", boss_type.name(), self.floor);
                     }
                 }
-                // Spawn fewer regular enemies on boss floors
-                let reduced_positions: Vec<_> = spawn_positions.iter()
-                    .take(spawn_positions.len() / 2)
-                    .copied()
-                    .collect();
+                // The boss's adds wait in the arena's alcoves
+                let reduced_positions: Vec<_> = if map.alcoves.is_empty() {
+                    spawn_positions.iter().take(spawn_positions.len() / 2).copied().collect()
+                } else {
+                    map.alcoves.clone()
+                };
                 let enemies = spawn_enemies_for_floor_with_zones(
                     &mut self.world,
                     biome,
-                    self.floor,
                     &reduced_positions,
                     map,
                     &mut self.rng,
-                    self.difficulty,
+                    &scaling,
                 );
                 log::info!("Spawned {} enemies on boss floor {}", enemies.len(), self.floor);
 
@@ -570,15 +1075,37 @@ impl Game {
                 let enemies = spawn_enemies_for_floor_with_zones(
                     &mut self.world,
                     biome,
-                    self.floor,
                     &spawn_positions,
                     map,
                     &mut self.rng,
-                    self.difficulty,
+                    &scaling,
                 );
                 log::info!("Spawned {} enemies on floor {} ({:?} difficulty, {} elite zones)",
                     enemies.len(), self.floor, self.difficulty, map.elite_rooms.len());
 
+                // Sometimes a pack roams the floor around its leader
+                crate::entities::spawn_groups_for_floor(
+                    &mut self.world,
+                    &self.data.groups,
+                    &spawn_positions,
+                    map,
+                    &scaling,
+                    &mut self.rng,
+                );
+
+                // Idle enemies walk beats, guard posts or sleep the night away
+                crate::entities::assign_patrols(&mut self.world, map, &mut self.rng);
+                crate::entities::empower_elites(&mut self.world, map, self.ng_plus, &mut self.rng);
+
+                // The garden's hearts take root where the generator left them room
+                for heart in &map.hearts {
+                    crate::entities::spawn_heart_node(&mut self.world, *heart, &scaling);
+                }
+
+                for (pos, prop) in &map.props {
+                    crate::entities::spawn_prop(&mut self.world, *pos, *prop);
+                }
+
                 // Spawn NPCs on non-boss floors (use NPC-specific positions to avoid corridors)
                 let npc_positions = map.get_npc_spawn_positions(8); // Further from start, not in narrow passages
                 let _npcs = spawn_npcs_for_floor(
@@ -588,6 +1115,7 @@ impl Game {
                     &npc_positions,
                     &mut self.rng,
                     &mut self.item_id_counter,
+                    self.profile.has_run_unlock(crate::progression::RunUnlock::Jeweler),
                 );
 
                 // Spawn chests on normal floors
@@ -600,30 +1128,356 @@ impl Game {
                     &mut self.rng,
                 );
                 log::info!("Spawned {} chests on floor {}", chests.len(), self.floor);
+
+                // Hidden rooms and puzzle vaults keep their hoard in the middle
+                for room in map.secret_rooms.iter().chain(&map.vaults) {
+                    crate::entities::spawn_secret_chest(&mut self.world, self.floor, *room, &mut self.rng);
+                }
+
+                // Occasionally a prisoner is locked in a cage somewhere on the floor
+                let prisoner_positions = map.get_npc_spawn_positions(10);
+                crate::entities::spawn_prisoner_for_floor(
+                    &mut self.world,
+                    self.floor,
+                    &prisoner_positions,
+                    &self.profile.rescued_npcs,
+                    &mut self.rng,
+                );
+
+                if self.floor_event == Some(crate::data::FloorEventKind::AmbushedCaravan) {
+                    let caravan = crate::entities::spawn_ambushed_caravan(
+                        &mut self.world,
+                        map,
+                        &scaling,
+                        &mut self.rng,
+                        &mut self.item_id_counter,
+                    );
+                    if caravan.is_none() {
+                        self.floor_event = None;
+                    }
+                }
+            }
+
+            // One key lies somewhere on the floor for every locked door
+            let locked_doors = (0..map.height)
+                .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+                .filter(|&(x, y)| map.get_tile(x, y).is_some_and(|t| t.tile_type == crate::world::TileType::DoorLocked))
+                .count();
+            let key_positions = map.get_spawn_positions(3);
+            for _ in 0..locked_doors {
+                use rand::Rng;
+
+                if key_positions.is_empty() {
+                    break;
+                }
+                let pos = key_positions[self.rng.gen_range(0..key_positions.len())];
+                self.item_id_counter += 1;
+                let key = crate::items::item::templates::iron_key(self.item_id_counter);
+                self.world.spawn((
+                    pos,
+                    crate::ecs::Renderable::new(key.glyph, key.rarity.color()).with_order(10),
+                    crate::ecs::GroundItem { item: key },
+                ));
+            }
+
+            // NPCs rescued in earlier runs wait near the entrance
+            if self.floor == 1 {
+                use rand::Rng;
+                let mut camp_positions: Vec<_> = map.get_npc_spawn_positions(2)
+                    .into_iter()
+                    .filter(|pos| pos.chebyshev_distance(&map.start_pos) <= 8)
+                    .collect();
+                for npc_type in self.profile.rescued_npcs.clone() {
+                    if camp_positions.is_empty() {
+                        break;
+                    }
+                    let pos = camp_positions.remove(self.rng.gen_range(0..camp_positions.len()));
+                    crate::entities::spawn_npc(
+                        &mut self.world,
+                        npc_type,
+                        pos,
+                        &mut self.rng,
+                        self.floor,
+                        biome,
+                        &mut self.item_id_counter,
+                    );
+                }
             }
         }
 
+        // The factions deal with the player as they've earned
+        if !is_boss_floor && rush_stage.is_none() {
+            let npcs: Vec<Entity> = self.world.query::<&crate::entities::NpcMarker>().iter().map(|(e, _)| e).collect();
+            self.stock_by_standing(&npcs);
+        }
+
+        crate::entities::attach_resistances(&mut self.world, &self.data.enemies);
+        self.announce_floor();
         log::info!("Generated floor {} ({:?})", self.floor, biome);
     }
 
+    // ========================================================================
+    // Errors
+    // ========================================================================
+
+    /// Log an error and show it to the player, without stopping the game
+    pub fn report_error(&mut self, error: Error) {
+        error.log();
+        self.errors.push(error);
+    }
+
+    /// The error on show, if one is waiting
+    pub fn error_popup(&self) -> Option<&Error> {
+        self.errors.current()
+    }
+
+    /// Dismiss the error on show
+    pub fn dismiss_error(&mut self) {
+        self.errors.dismiss();
+    }
+
+    /// Save the run to the autosave slot, offered after a crash
+    fn autosave(&mut self) {
+        if self.headless {
+            return;
+        }
+        if let Err(e) = crate::save::save_game(self, crate::save::AUTOSAVE_SLOT) {
+            self.report_error(e);
+        }
+    }
+
+    /// Where the run stands, for a crash report
+    pub fn crash_context(&self) -> crate::crash::CrashContext {
+        let first = self.messages.len().saturating_sub(crate::crash::RECENT_EVENTS);
+        crate::crash::CrashContext {
+            seed: self.seed,
+            floor: self.floor,
+            turn: self.run_clock.turns,
+            events: self.messages[first..].iter().map(|m| m.text.clone()).collect(),
+        }
+    }
+
+    /// The seed the run's generator started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Write the profile out, reporting it if that fails
+    fn store_profile(&mut self) {
+        if self.headless {
+            return;
+        }
+        if let Err(e) = save_profile(&self.profile) {
+            self.report_error(e);
+        }
+    }
+
+    // ========================================================================
+    // Cutscenes
+    // ========================================================================
+
+    /// Queue the cutscene a moment plays, if there is one. Returns whether
+    /// one was queued.
+    pub fn play_cutscene(&mut self, trigger: crate::data::CutsceneTrigger) -> bool {
+        let Some(def) = self.data.cutscene_defs().for_trigger(trigger).cloned() else { return false };
+        self.queue_cutscene(&def);
+        true
+    }
+
+    /// Queue a cutscene by id, for scenes without a trigger of their own
+    pub fn play_cutscene_by_id(&mut self, id: &str) -> bool {
+        let Some(def) = self.data.cutscene_defs().get(id).cloned() else {
+            log::warn!("No cutscene named {}", id);
+            return false;
+        };
+        self.queue_cutscene(&def);
+        true
+    }
+
+    fn queue_cutscene(&mut self, def: &crate::data::CutsceneDef) {
+        let camera = self.player_position().unwrap_or(Position::new(0, 0));
+        self.cutscenes.push_back(super::Cutscene::new(def, camera));
+    }
+
+    /// The cutscene playing, if any
+    pub fn cutscene(&self) -> Option<&super::Cutscene> {
+        self.cutscenes.front()
+    }
+
+    /// Show the next line of dialogue, or cut the current step short
+    pub fn advance_cutscene(&mut self) {
+        let Some(mut scene) = self.cutscenes.pop_front() else { return };
+        scene.advance(|anchor| self.locate_anchor(anchor));
+        self.cutscenes.push_front(scene);
+        self.finish_cutscene();
+    }
+
+    /// Skip the cutscene playing
+    pub fn skip_cutscene(&mut self) {
+        if let Some(scene) = self.cutscenes.front_mut() {
+            scene.skip();
+        }
+        self.finish_cutscene();
+    }
+
+    fn update_cutscene(&mut self, delta: f32) {
+        let Some(mut scene) = self.cutscenes.pop_front() else { return };
+        scene.update(delta, |anchor| self.locate_anchor(anchor));
+        self.cutscenes.push_front(scene);
+        self.finish_cutscene();
+    }
+
+    /// Drop a finished cutscene, moving on once the last one is done
+    fn finish_cutscene(&mut self) {
+        if !self.cutscenes.front().is_some_and(|s| s.is_finished()) {
+            return;
+        }
+        self.cutscenes.pop_front();
+        let player = self.player_position();
+        if let Some(next) = self.cutscenes.front_mut() {
+            next.camera = player.unwrap_or(next.camera);
+        } else if let Some(state) = self.after_cutscenes.take() {
+            self.set_state(state);
+        }
+    }
+
+    /// Where a cutscene anchor is on this floor
+    fn locate_anchor(&self, anchor: crate::data::CutsceneAnchor) -> Option<Position> {
+        use crate::data::CutsceneAnchor;
+
+        let map = self.map.as_ref()?;
+        match anchor {
+            CutsceneAnchor::Player => self.player_position(),
+            CutsceneAnchor::Start => Some(map.start_pos),
+            CutsceneAnchor::Exit => map.exit_pos,
+            CutsceneAnchor::Boss => self.world
+                .query::<(&Position, &crate::entities::BossComponent)>()
+                .iter()
+                .map(|(_, (pos, _))| *pos)
+                .next()
+                .or(map.exit_pos),
+        }
+    }
+
+    /// Roll the current floor's event and make any changes it needs to the
+    /// map. The caravan is spawned with the rest of the floor's entities.
+    fn roll_floor_event(&mut self, biome: crate::world::Biome) {
+        use crate::data::FloorEventKind;
+
+        let Some(event) = self.data.floor_event_defs().roll(biome, self.floor, &mut self.rng) else { return };
+        let kind = event.kind;
+        let Some(map) = self.map.as_mut() else { return };
+        let took_hold = match kind {
+            FloorEventKind::CollapsedPassages => super::collapse_passages(map, &mut self.rng) > 0,
+            FloorEventKind::CorruptedShrines => !super::raise_shrine_cluster(map, &mut self.rng).is_empty(),
+            FloorEventKind::AmbushedCaravan | FloorEventKind::TotalDarkness => true,
+        };
+        if took_hold {
+            log::info!("Floor {} event: {:?}", self.floor, kind);
+            self.floor_event = Some(kind);
+        }
+    }
+
+    /// Put up the floor-intro banner, and log what has happened here
+    fn announce_floor(&mut self) {
+        let place = match crate::world::generation::mashup_biome(self.floor) {
+            Some(mashup) => format!("{} & {}", self.biome().name(), mashup.name()),
+            None => self.biome().name().to_string(),
+        };
+        let title = format!("{} - {}", floor_name(self.floor), place);
+        let event = self.floor_event
+            .and_then(|kind| self.data.floor_event_defs().get(kind))
+            .map(|e| (e.name.clone(), e.announcement.clone()));
+        if let Some((_, announcement)) = &event {
+            self.add_message(announcement.clone(), MessageCategory::Warning);
+        }
+        if crate::entities::heart_count(&self.world) > 0 {
+            self.add_message("Somewhere in the walls, something is beating.", MessageCategory::Warning);
+        }
+        self.floor_banner = Some(super::FloorBanner::new(title, event));
+    }
+
+    /// What has befallen the current floor, if anything
+    pub fn floor_event(&self) -> Option<crate::data::FloorEventKind> {
+        self.floor_event
+    }
+
+    /// The floor-intro banner, while it is up
+    pub fn floor_banner(&self) -> Option<&super::FloorBanner> {
+        self.floor_banner.as_ref()
+    }
+
+    /// How far the player can see on this floor
+    pub fn sight_radius(&self) -> i32 {
+        if self.floor_event == Some(crate::data::FloorEventKind::TotalDarkness) {
+            super::DARKNESS_SIGHT_RADIUS
+        } else {
+            super::SIGHT_RADIUS
+        }
+    }
+
     /// Proceed to the next floor
     pub fn descend(&mut self) {
+        // Escorts reaching the stairs are rescued; the rest are lost
+        self.resolve_escorts();
+
+        if self.boss_rush.is_some() {
+            self.advance_boss_rush();
+            return;
+        }
+        if self.floor == 0 {
+            self.finish_tutorial();
+        }
+        self.split_floor();
+        if self.floor > 0 {
+            self.judge_floor();
+        }
+        // The way down from the final floor leads out of the Hollowdeep,
+        // unless the run has chosen to go on into the depths
+        if self.floor >= FINAL_FLOOR && !self.endless {
+            self.player_won();
+            return;
+        }
+        self.enter_next_floor();
+    }
+
+    /// Carry a won run on below the final floor, into depths that grow
+    /// deadlier without end
+    pub fn continue_endless(&mut self) {
+        self.endless = true;
+        self.run_start_time = Some(Instant::now());
+        self.set_state(GameState::Playing(PlayingState::Exploring));
+        self.add_message("You turn from the light and climb down into the dark below the Abyss.".to_string(), MessageCategory::Lore);
+        self.enter_next_floor();
+    }
+
+    /// Go down to the next floor and set it up
+    fn enter_next_floor(&mut self) {
         use crate::entities::BossType;
 
+        let previous_biome = self.biome();
         self.floor += 1;
 
         // Track floor descent in profile
         self.profile.record_floor_descent(self.floor);
-        if let Err(e) = save_profile(&self.profile) {
-            log::warn!("Failed to save profile: {}", e);
-        }
+        self.store_profile();
 
         self.generate_floor();
+        self.move_followers_to_start();
+        if let Some(start) = self.map.as_ref().map(|m| m.start_pos) {
+            self.set_player_position(start);
+        }
+        self.apply_descent_boons();
 
         self.add_message(
-            format!("You descend to floor {}...", self.floor),
+            format!("You descend to {}...", floor_name(self.floor).to_lowercase()),
             MessageCategory::System
         );
+        self.send_hunters();
+
+        if self.biome() != previous_biome {
+            self.play_cutscene(crate::data::CutsceneTrigger::EnterBiome(self.biome()));
+        }
 
         // Boss floor warning
         if let Some(boss_type) = BossType::for_floor(self.floor) {
@@ -635,141 +1489,3975 @@ impl Game {
                 boss_type.phase_description(1).to_string(),
                 MessageCategory::Lore
             );
+            self.play_cutscene(crate::data::CutsceneTrigger::BossIntro(boss_type));
         }
+
+        self.autosave();
+        self.update_presence();
     }
 
-    /// Tick status effects on the player (called on player actions/movement)
-    pub fn tick_player_status_effects(&mut self) {
-        use crate::ecs::{StatusEffects, Health, Name};
-        use crate::combat::apply_status_damage;
+    /// Close the current floor's split. In speedrun mode it's held up
+    /// against the personal best and kept if it beats it.
+    fn split_floor(&mut self) {
+        // The clock starts over once the tutorial is behind the player
+        if self.floor == 0 {
+            self.run_clock = super::RunClock::default();
+            return;
+        }
+        if !self.speedrunning() {
+            self.run_clock.split(self.floor, None);
+            return;
+        }
+        let best = self.profile.best_splits.get(&self.floor).copied();
+        let split = self.run_clock.split(self.floor, best);
+        if self.profile.record_split(split.floor, split.at) && best.is_some() {
+            self.add_message(
+                format!("New best split on floor {}: {}", split.floor, super::format_run_time(split.at)),
+                MessageCategory::System,
+            );
+        }
+    }
 
-        if let Some(player) = self.player_entity {
-            // Get player name
-            let player_name = self.world
-                .get::<&Name>(player)
-                .map(|n| n.0.clone())
-                .unwrap_or_else(|_| "You".to_string());
+    /// Whether splits are being timed against personal bests: speedrun mode
+    /// on a first descent (boss rushes and New Game Plus keep no splits)
+    pub fn speedrunning(&self) -> bool {
+        self.profile.settings.speedrun_mode && self.boss_rush.is_none() && self.ng_plus == 0
+    }
 
-            // Tick status effects
-            let tick_result = {
-                if let Ok(mut effects) = self.world.get::<&mut StatusEffects>(player) {
-                    effects.tick(&player_name)
-                } else {
-                    return;
+    // ========================================================================
+    // Tutorial
+    // ========================================================================
+
+    /// Fill the tutorial floor: an enemy to fight and an item to pick up,
+    /// where its layout marks them
+    fn furnish_tutorial(&mut self, spawns: Vec<(char, Position)>) {
+        for (mark, pos) in spawns {
+            match mark {
+                'r' => {
+                    if let Some(def) = crate::entities::enemy_def("Rat Swarm") {
+                        crate::entities::spawn_enemy(&mut self.world, def, pos);
+                    }
                 }
-            };
-
-            // Apply damage/healing
-            if tick_result.damage_dealt != 0 {
-                if let Ok(mut health) = self.world.get::<&mut Health>(player) {
-                    apply_status_damage(&mut health, &tick_result);
+                '!' => {
+                    self.item_id_counter += 1;
+                    let potion = crate::items::item::templates::health_potion(self.item_id_counter);
+                    self.world.spawn((
+                        pos,
+                        crate::ecs::Renderable::new(potion.glyph, potion.rarity.color()).with_order(10),
+                        crate::ecs::GroundItem { item: potion },
+                    ));
                 }
+                _ => {}
             }
+        }
+        self.floor_banner = Some(super::FloorBanner::new("The Threshold".to_string(), None));
+        self.add_message("A short way in, the dungeon is quiet enough to learn in.", MessageCategory::System);
+    }
 
-            // Add messages
-            for msg in tick_result.messages {
-                self.add_message(msg, MessageCategory::Combat);
-            }
+    /// Move the tutorial on if the player just did what its step asks
+    pub fn tutorial_event(&mut self, trigger: crate::data::TutorialTrigger) {
+        let Some(step) = self.tutorial else { return };
+        match self.data.tutorial_defs().advance(step, trigger) {
+            Some(next) => self.tutorial = Some(next),
+            None => self.finish_tutorial(),
         }
     }
 
-    /// Tick status effects on all enemies (called during AI tick)
-    pub fn tick_enemy_status_effects(&mut self) {
-        use crate::ecs::{StatusEffects, Health, Name, Enemy};
+    /// The tutorial step being shown, with its number
+    pub fn tutorial_step(&self) -> Option<(usize, &crate::data::TutorialStep)> {
+        let step = self.tutorial?;
+        self.data.tutorial_defs().steps.get(step).map(|s| (step, s))
+    }
 
-        // Collect entities to tick (avoid borrow issues)
-        let entities: Vec<_> = self.world
-            .query::<(&Enemy, &Name)>()
-            .iter()
-            .map(|(e, (_, name))| (e, name.0.clone()))
-            .collect();
+    /// Leave the tutorial floor for floor 1
+    pub fn skip_tutorial(&mut self) {
+        if self.floor == 0 {
+            self.add_message("You press on without the lessons.", MessageCategory::System);
+            self.descend();
+        }
+    }
 
-        let mut dead_entities = Vec::new();
-        let mut messages_to_add = Vec::new();
+    /// Put the tutorial away for good
+    fn finish_tutorial(&mut self) {
+        self.tutorial = None;
+        if !self.profile.tutorial_completed {
+            self.profile.tutorial_completed = true;
+            self.store_profile();
+        }
+    }
 
-        for (entity, name) in entities {
-            // Tick status effects
-            let tick_result = {
-                if let Ok(mut effects) = self.world.get::<&mut StatusEffects>(entity) {
-                    effects.tick(&name)
-                } else {
-                    continue;
-                }
-            };
+    // ========================================================================
+    // Hints
+    // ========================================================================
 
-            // Apply damage/healing first (before moving messages)
-            let damage = tick_result.damage_dealt;
-            if damage != 0 {
-                if let Ok(mut health) = self.world.get::<&mut Health>(entity) {
-                    let _ = if damage > 0 {
-                        health.take_damage(damage)
-                    } else {
-                        health.heal(-damage)
-                    };
+    /// Show a first-time hint, unless this profile has seen it already
+    pub fn show_hint(&mut self, hint: super::Hint) {
+        if !self.profile.settings.show_hints || !self.profile.see_hint(hint.id()) {
+            return;
+        }
+        self.add_message(hint.text(), MessageCategory::System);
+        self.store_profile();
+    }
 
-                    // Check if enemy died from DoT
-                    if health.is_dead() {
-                        dead_entities.push((entity, name.clone()));
-                    }
-                }
+    /// Look over what the player can see for anything that needs a hint
+    fn check_hints(&mut self) {
+        use super::Hint;
+        use crate::world::TileType;
+
+        if !self.profile.settings.show_hints {
+            return;
+        }
+        let (Some(player_pos), Some(map)) = (self.player_position(), self.map.as_ref()) else { return };
+        let radius = self.sight_radius();
+        let visible = |pos: &Position| map.get_tile(pos.x, pos.y).is_some_and(|t| t.visible);
+
+        let mut hints = Vec::new();
+        for y in player_pos.y - radius..=player_pos.y + radius {
+            for x in player_pos.x - radius..=player_pos.x + radius {
+                let Some(tile) = map.get_tile(x, y).filter(|t| t.visible) else { continue };
+                hints.extend(match tile.tile_type {
+                    TileType::ShrineCorruption => Some(Hint::CorruptionShrine),
+                    TileType::DoorLocked => Some(Hint::LockedDoor),
+                    t if t.is_altar() => Some(Hint::Altar),
+                    t if t.is_hazard() => Some(Hint::Hazard),
+                    _ => None,
+                });
+            }
+        }
+        if map.is_elite_zone(player_pos) {
+            hints.push(Hint::EliteZone);
+        }
+        if self.world.query::<(&Position, &crate::ecs::Chest)>().iter().any(|(_, (pos, chest))| !chest.opened && visible(pos)) {
+            hints.push(Hint::Chest);
+        }
+        if self.world.query::<(&Position, &crate::entities::Corpse)>().iter().any(|(_, (pos, corpse))| !corpse.searched && visible(pos)) {
+            hints.push(Hint::Corpse);
+        }
+        if self.world.query::<(&Position, &crate::progression::EliteModifiers)>().iter().any(|(_, (pos, _))| visible(pos)) {
+            hints.push(Hint::EliteAffixes);
+        }
+        if self.player_hp_and_max().is_some_and(|(hp, max)| hp * 100 < max * super::LOW_HEALTH_HINT_PERCENT) {
+            hints.push(Hint::LowHealth);
+        }
+
+        for hint in hints {
+            self.show_hint(hint);
+        }
+    }
+
+    /// Warn about deadly enemies coming into view, once for each
+    fn size_up_enemies(&mut self) {
+        let Some(map) = self.map.as_ref() else { return };
+        let in_view: Vec<Entity> = self.world.query::<(&Position, &crate::ecs::Enemy)>()
+            .iter()
+            .filter(|(enemy, (pos, _))| !self.sized_up.contains(enemy) && map.get_tile(pos.x, pos.y).is_some_and(|t| t.visible))
+            .map(|(enemy, _)| enemy)
+            .collect();
+
+        if !in_view.is_empty() {
+            self.show_hint(super::Hint::ThreatColors);
+        }
+        for enemy in in_view {
+            self.sized_up.insert(enemy);
+            if self.threat_of(enemy) == Some(super::Threat::Deadly) {
+                let name = self.world.get::<&crate::ecs::Name>(enemy)
+                    .map(|n| n.0.clone())
+                    .unwrap_or_else(|_| "creature".to_string());
+                self.add_message(
+                    format!("The {} is far beyond you. Think hard before you fight it.", name),
+                    MessageCategory::Warning,
+                );
+            }
+        }
+    }
+
+    /// How dangerous an enemy is to the player, or None for anything that
+    /// isn't an enemy
+    pub fn threat_of(&self, enemy: Entity) -> Option<super::Threat> {
+        use crate::combat::{expected_damage, DerivedStats};
+
+        self.world.get::<&crate::ecs::Enemy>(enemy).ok()?;
+        let player = self.player_derived_stats()?;
+        let foe = DerivedStats::compute(&self.world, enemy)?;
+
+        let mut dealt = expected_damage(&player.sources.base, &foe.sources.base, &player.attack_bonuses(), &foe.defense_bonuses());
+        let mut taken = expected_damage(&foe.sources.base, &player.sources.base, &foe.attack_bonuses(), &player.defense_bonuses());
+        if let Ok(mods) = self.world.get::<&crate::progression::EliteModifiers>(enemy) {
+            (dealt, taken) = super::with_affixes(dealt, taken, &mods);
+        }
+        Some(super::Threat::rate(
+            super::blows_to_kill(foe.max_hp, dealt),
+            super::blows_to_kill(player.max_hp, taken),
+        ))
+    }
+
+    // ========================================================================
+    // Boss rush
+    // ========================================================================
+
+    // ========================================================================
+    // New Game Plus
+    // ========================================================================
+
+    /// Go around again after a victory, keeping the item in one equipment
+    /// slot and every learned skill. Enemies scale up with each cycle.
+    pub fn start_new_game_plus(&mut self, keep: Option<crate::items::EquipSlot>) {
+        use crate::ecs::{EquipmentComponent, InventoryComponent, SkillsComponent};
+
+        let player = self.player_entity;
+        let kept_item = player.zip(keep).and_then(|(p, slot)| {
+            self.world.get::<&EquipmentComponent>(p).ok()
+                .and_then(|eq| eq.equipment.get(slot).cloned())
+        });
+        let kept_skills = player
+            .and_then(|p| self.world.get::<&SkillsComponent>(p).ok().map(|s| s.skills.clone()));
+        let cycle = self.ng_plus + 1;
+
+        self.start_run(None, self.difficulty, self.sigils.clone(), cycle);
+        self.profile.record_ng_plus(cycle);
+        self.store_profile();
+
+        let Some(player) = self.player_entity else { return };
+        if let Some(mut skills) = kept_skills {
+            skills.cooldowns = [0; 5];
+            if let Ok(mut component) = self.world.get::<&mut SkillsComponent>(player) {
+                component.skills = skills;
+            }
+        }
+        if let (Some(item), Some(slot)) = (kept_item, keep) {
+            let name = item.name.clone();
+            let displaced = self.world.get::<&mut EquipmentComponent>(player)
+                .map(|mut eq| eq.equipment.equip_to(slot, item))
+                .unwrap_or_default();
+            if let Ok(mut inv) = self.world.get::<&mut InventoryComponent>(player) {
+                for old in displaced {
+                    inv.inventory.add_item(old);
+                }
+            }
+            self.add_message(format!("You carry your {} back into the dark.", name), MessageCategory::Item);
+        }
+        self.add_message(
+            format!("New Game+{}: the Hollowdeep stirs, stronger than before.", cycle),
+            MessageCategory::Warning,
+        );
+    }
+
+    /// Start a boss rush: every boss back to back, with interludes between
+    pub fn start_boss_rush(&mut self, difficulty: Difficulty) {
+        self.start_new_run(None, difficulty, crate::progression::CurseSigils::default());
+        self.boss_rush = Some(super::BossRush::default());
+        self.enter_boss_rush_stage();
+    }
+
+    /// The boss rush in progress, if this run is one
+    pub fn boss_rush(&self) -> Option<&super::BossRush> {
+        self.boss_rush.as_ref()
+    }
+
+    /// Take the stairs out of a boss rush stage
+    fn advance_boss_rush(&mut self) {
+        let Some(rush) = &mut self.boss_rush else { return };
+        if rush.is_final() {
+            self.player_won();
+            return;
+        }
+        rush.advance();
+        self.enter_boss_rush_stage();
+    }
+
+    /// Build the floor for the boss rush's current stage
+    fn enter_boss_rush_stage(&mut self) {
+        let Some(rush) = self.boss_rush else { return };
+        self.floor = rush.floor();
+        self.generate_floor();
+        self.move_followers_to_start();
+        if let Some(start) = self.map.as_ref().map(|m| m.start_pos) {
+            self.set_player_position(start);
+        }
+
+        let boss = rush.next_boss();
+        match rush.boss() {
+            Some(_) => {
+                self.add_message(format!("⚠ {} awaits!", boss.name()), MessageCategory::Warning);
+                self.add_message(boss.phase_description(1).to_string(), MessageCategory::Lore);
+                self.play_cutscene(crate::data::CutsceneTrigger::BossIntro(boss));
+            }
+            None => {
+                let gold = super::BOSS_RUSH_GOLD * (rush.stage as u32 + 1);
+                if let Some(player) = self.player_entity {
+                    if let Ok(mut points) = self.world.get::<&mut crate::ecs::StatPoints>(player) {
+                        points.0 += super::BOSS_RUSH_STAT_POINTS;
+                    }
+                    if let Ok(mut inv) = self.world.get::<&mut crate::ecs::InventoryComponent>(player) {
+                        inv.inventory.add_gold(gold);
+                    }
+                }
+                self.add_message(
+                    format!(
+                        "A moment's respite before {}. (+{} gold, +{} stat points)",
+                        boss.name(), gold, super::BOSS_RUSH_STAT_POINTS,
+                    ),
+                    MessageCategory::System,
+                );
+            }
+        }
+        self.update_presence();
+    }
+
+    /// Stock a boss rush interlude: a merchant and a shrine by the entrance,
+    /// and the stairs close at hand
+    fn furnish_interlude(&mut self) {
+        use rand::seq::SliceRandom;
+        use crate::world::TileType;
+
+        let Some(map) = &mut self.map else { return };
+        let start = map.start_pos;
+        let mut nearby: Vec<Position> = map.get_npc_spawn_positions(2)
+            .into_iter()
+            .filter(|pos| pos.chebyshev_distance(&start) <= 6)
+            .collect();
+        nearby.shuffle(&mut self.rng);
+
+        // Bring the stairs to the entrance
+        if let Some(stairs) = nearby.pop() {
+            if let Some(old) = map.exit_pos {
+                map.set_tile(old.x, old.y, TileType::Floor);
+            }
+            map.set_tile(stairs.x, stairs.y, TileType::StairsDown);
+            map.exit_pos = Some(stairs);
+        }
+        if let Some(shrine) = nearby.pop() {
+            let kind = [TileType::ShrineRest, TileType::ShrineEnchant, TileType::ShrineSkill]
+                .choose(&mut self.rng)
+                .copied()
+                .unwrap_or(TileType::ShrineRest);
+            map.set_tile(shrine.x, shrine.y, kind);
+        }
+
+        let biome = map.biome;
+        if let Some(pos) = nearby.pop() {
+            crate::entities::spawn_npc(
+                &mut self.world,
+                crate::entities::NpcType::Merchant,
+                pos,
+                &mut self.rng,
+                self.floor,
+                biome,
+                &mut self.item_id_counter,
+            );
+        }
+    }
+
+    /// Tick status effects on the player and every enemy, logging what
+    /// they did and floating the numbers over the map
+    fn tick_status_effects(&mut self) {
+        use crate::ecs::{Enemy, Name};
+
+        let events = crate::combat::run_status_effects(&mut self.world);
+        let mut dead = Vec::new();
+        for event in events {
+            let name = self.world.get::<&Name>(event.entity)
+                .map(|n| n.0.clone())
+                .unwrap_or_else(|_| "Something".to_string());
+            let name = if Some(event.entity) == self.player_entity { "You".to_string() } else { name };
+            self.add_message(event.message(&name), MessageCategory::Combat);
+
+            if let Ok(pos) = self.world.get::<&Position>(event.entity).map(|p| *p) {
+                self.float_text(pos, event.floating_text(), event.effect_type.color());
+            }
+
+            let killed = self.world.get::<&Enemy>(event.entity).is_ok()
+                && self.world.get::<&Health>(event.entity).is_ok_and(|h| h.is_dead());
+            if killed && !dead.iter().any(|(entity, _)| *entity == event.entity) {
+                dead.push((event.entity, name));
+            }
+        }
+
+        for (entity, name) in dead {
+            self.add_message(
+                format!("{} succumbed to their wounds!", name),
+                MessageCategory::Combat,
+            );
+            self.queue_death(entity, false, false);
+        }
+    }
+
+    /// Float a number up from a spot on the map, if damage numbers are on
+    pub fn float_text(&mut self, pos: Position, text: impl Into<String>, color: (u8, u8, u8)) {
+        if self.profile.settings.show_damage_numbers {
+            self.floating.push(super::FloatingText::new(pos, text, color));
+        }
+    }
+
+    /// Numbers floating over the map
+    pub fn floating_texts(&self) -> &[super::FloatingText] {
+        &self.floating
+    }
+
+    /// Run the rest of the turn after the player acts: statuses tick,
+    /// enemies act, hazards strike, the dead are reaped and events catch up,
+    /// stage by stage
+    pub fn run_ai_tick(&mut self) {
+        self.turn_start_hp = self.player_health().map(|h| h.current);
+        let mut times = crate::ecs::StageTimes::default();
+        turn_schedule().run_timed(self, &mut times);
+        self.turn_times = times;
+    }
+
+    /// How long each stage of the last turn took
+    pub fn turn_times(&self) -> &crate::ecs::StageTimes {
+        &self.turn_times
+    }
+
+    /// Skill cooldowns tick down and mana and stamina come back
+    fn recover(&mut self) -> Flow {
+        use crate::ecs::{SkillsComponent, Stats};
+
+        let Some(player) = self.player_entity else { return Flow::Continue };
+        if let Ok(mut skills) = self.world.get::<&mut SkillsComponent>(player) {
+            skills.skills.tick_cooldowns();
+        }
+
+        let intelligence = self.world.get::<&Stats>(player)
+            .map(|s| s.intelligence)
+            .unwrap_or(0);
+        let (mana, stamina) = self.regen.tick(intelligence, self.armor_weight().stamina_regen_multiplier());
+        if mana > 0 {
+            self.restore_mana(mana);
+        }
+        if stamina > 0 {
+            self.restore_stamina(stamina);
+        }
+        Flow::Continue
+    }
+
+    /// The player looks over the chests and walls next to them
+    fn search_surroundings(&mut self) -> Flow {
+        self.search_adjacent_chests();
+        if let Some(stats) = self.player_stats() {
+            let chance = crate::entities::spot_chance(stats.intelligence, stats.dexterity) * SECRET_NOTICE_FACTOR;
+            self.look_for_secrets(1, chance);
+        }
+        Flow::Continue
+    }
+
+    /// DoT damage and regeneration, with anything summoned since last turn
+    /// resisting what its kind resists
+    fn tick_statuses(&mut self) -> Flow {
+        crate::entities::attach_resistances(&mut self.world, &self.data.enemies);
+        self.tick_status_effects();
+        Flow::Continue
+    }
+
+    /// Enemies think and act, battering at doors in their way
+    fn enemies_act(&mut self) -> Flow {
+        use crate::ecs::{run_enemy_ai, execute_ai_actions};
+
+        let Some(player_pos) = self.player_position() else { return Flow::Stop };
+        let Some(map) = &self.map else { return Flow::Stop };
+
+        // A prepared action covers the visible tiles within its reach
+        let prepared = self.prepared.take();
+        let watched: Option<std::collections::HashSet<Position>> = prepared.map(|(_, range)| {
+            (-range..=range)
+                .flat_map(|dy| (-range..=range).map(move |dx| Position::new(player_pos.x + dx, player_pos.y + dy)))
+                .filter(|pos| *pos != player_pos)
+                .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| t.visible && t.tile_type.is_walkable()))
+                .collect()
+        });
+
+        // Run AI to get actions (pass rng for slow effect chance)
+        let mut actions = run_enemy_ai(
+            &mut self.world,
+            map,
+            player_pos,
+            self.data.behavior_trees(),
+            super::is_night(self.ambient_time),
+            &mut self.rng,
+        );
+        actions.extend(crate::ecs::run_stalker_ai(&self.world, map, player_pos));
+
+        // Execute the actions (need to pass rng for combat calculations)
+        let outcome = execute_ai_actions(&mut self.world, actions, self.player_entity, watched.as_ref(), &mut self.rng);
+        self.hits.extend(outcome.hits);
+        if outcome.blocks > 0 {
+            self.play_sound(SoundId::Block);
+        }
+
+        // Add combat messages
+        for msg in outcome.messages {
+            self.add_message(msg, MessageCategory::Combat);
+        }
+        self.fleeing_strikes.extend(outcome.provoked);
+        if let Some((action, _)) = prepared {
+            match outcome.overwatch_triggered {
+                Some(enemy) => self.overwatch_strike = Some((enemy, action)),
+                None => self.add_message("Nothing comes within reach. You lower your guard.", MessageCategory::System),
+            }
+        }
+
+        // Enemies batter at doors standing between them and the player
+        for (enemy, door) in outcome.door_bashes {
+            let name = self.world.get::<&crate::ecs::Name>(enemy)
+                .map(|n| n.0.clone())
+                .unwrap_or_else(|_| "enemy".to_string());
+            let seen = self.map.as_ref()
+                .and_then(|m| m.get_tile(door.x, door.y))
+                .is_some_and(|t| t.visible);
+            let flesh = self.map.as_ref()
+                .and_then(|m| m.get_tile(door.x, door.y))
+                .is_some_and(|t| t.tile_type == crate::world::TileType::FleshWall);
+            // The Warden tears doors from their hinges
+            let power = if self.world.get::<&crate::entities::Stalker>(enemy).is_ok() { STALKER_BASH_POWER } else { 1 };
+            if self.bash_door(door, power) {
+                if seen && flesh {
+                    self.add_message(format!("The {} tears its way through the flesh!", name), MessageCategory::Warning);
+                } else if seen {
+                    self.add_message(format!("The {} smashes through the door!", name), MessageCategory::Warning);
+                } else if !flesh {
+                    self.add_message("You hear a door splinter somewhere nearby.", MessageCategory::Warning);
+                }
+            } else if seen && !flesh {
+                self.add_message(format!("The {} pounds on the door.", name), MessageCategory::Combat);
+            }
+        }
+        Flow::Continue
+    }
+
+    /// Bosses use their abilities, and the dead rise and hearts beat
+    fn bosses_act(&mut self) -> Flow {
+        if let Some(player_pos) = self.player_position() {
+            self.boss_yanks(player_pos);
+        }
+        self.boss_grows_hearts();
+        self.boss_fight_turn();
+        self.necromancers_raise_dead();
+        crate::entities::hearts_beat(&mut self.world);
+        Flow::Continue
+    }
+
+    /// Followers act after the enemies, and peddlers wander on
+    fn followers_act(&mut self) -> Flow {
+        if let (Some(map), Some(player_pos)) = (&self.map, self.player_position()) {
+            let actions = crate::ecs::run_follower_ai(&self.world, map, player_pos);
+            let outcome = crate::ecs::execute_ai_actions(&mut self.world, actions, self.player_entity, None, &mut self.rng);
+            for msg in outcome.messages {
+                self.add_message(msg, MessageCategory::Combat);
+            }
+            self.credit_follower_kills(&outcome.hits);
+            self.hits.extend(outcome.hits);
+        }
+        self.resolve_auras();
+        self.update_followers();
+        self.move_peddlers();
+        Flow::Continue
+    }
+
+    /// Levers and plates do their work, and the ground underfoot bites
+    fn hazards_strike(&mut self) -> Flow {
+        self.work_mechanisms();
+        self.apply_hazard_tile();
+        Flow::Continue
+    }
+
+    /// The player dies of whatever the turn did to them, unless something
+    /// cheats death for them
+    fn reap_player(&mut self) -> Flow {
+        if let Some(health) = self.player_health() {
+            if health.is_dead() && !self.cheat_death() {
+                self.player_died("overwhelmed by the darkness");
+                return Flow::Stop;
+            }
+        }
+        let current_hp = self.player_health().map(|h| h.current);
+        let max_hp = self.player_derived_stats().map(|d| d.max_hp);
+        if let (Some(adaptive), Some(current), Some(max_hp)) = (self.adaptive.as_mut(), current_hp, max_hp) {
+            adaptive.observe_health(current, max_hp);
+        }
+        Flow::Continue
+    }
+
+    /// A collapsing floor gives way a little more, maybe taking the player
+    /// down with it
+    fn collapse_floor(&mut self) -> Flow {
+        if self.crumble_floor() { Flow::Continue } else { Flow::Stop }
+    }
+
+    /// Bodies rot and cut flesh grows back
+    fn settle_remains(&mut self) -> Flow {
+        for pos in crate::entities::decay_corpses(&mut self.world) {
+            self.leave_decal(pos, crate::world::Decal::Bones);
+        }
+        self.regrow_flesh();
+        Flow::Continue
+    }
+
+    /// What the turn cost the player in blood, lasting harm, mutation and
+    /// breath
+    fn toll_on_player(&mut self) -> Flow {
+        use rand::Rng;
+
+        // Wounds bleed onto the floor
+        if let (Some(before), Some(health), Some(pos)) = (self.turn_start_hp, self.player_health(), self.player_position()) {
+            if health.current < before {
+                self.leave_decal(pos, crate::world::Decal::Blood);
+            }
+        }
+
+        // Coming close to death can leave lasting harm
+        if let (Some(before), Some((hp, max))) = (self.turn_start_hp, self.player_hp_and_max()) {
+            if crate::progression::is_critical_blow(before, hp, max)
+                && self.rng.gen_bool(crate::progression::injuries::INJURY_CHANCE)
+            {
+                self.grant_injury();
+            }
+        }
+        // Lingering in The Abyss twists the body
+        if self.biome() == crate::world::Biome::TheAbyss {
+            let mutation_due = self.player_entity
+                .and_then(|p| self.world.get::<&mut crate::progression::Mutations>(p).ok())
+                .is_some_and(|mut m| m.expose());
+            if mutation_due {
+                self.add_message("The Abyss seeps into your flesh...", MessageCategory::Warning);
+                self.grant_mutation();
+            }
+        }
+
+        self.update_exhaustion();
+
+        // The Ashen Widow mends her devotees' wounds
+        if self.worship.has_boon(super::Boon::EmberWard) {
+            if let Some((hp, max)) = self.player_hp_and_max() {
+                if hp * 2 < max {
+                    self.heal_player(1);
+                }
+            }
+        }
+        Flow::Continue
+    }
+
+    /// The floor clock runs on, and presence and hints catch up
+    fn catch_up_events(&mut self) -> Flow {
+        self.advance_floor_clock();
+        // Bosses noticing the player change the presence state
+        self.update_presence();
+        self.check_hints();
+        self.size_up_enemies();
+        Flow::Continue
+    }
+
+    // ========================================================================
+    // Sprint & exhaustion
+    // ========================================================================
+
+    /// Whether sprinting is toggled on
+    pub fn is_sprinting(&self) -> bool {
+        self.sprinting
+    }
+
+    /// Whether the player is exhausted
+    pub fn is_exhausted(&self) -> bool {
+        self.player_entity
+            .is_some_and(|p| self.world.get::<&crate::ecs::Exhausted>(p).is_ok())
+    }
+
+    /// Stamina spent per sprinting turn (DEX lightens it, heavy armor adds to it)
+    pub fn sprint_cost(&self) -> i32 {
+        let dex = self.player_stats().map(|s| s.dexterity).unwrap_or(10);
+        (SPRINT_BASE_COST - (dex - 10) / 4 + self.armor_weight().sprint_cost_modifier()).max(2)
+    }
+
+    /// Toggle sprinting on or off
+    pub fn toggle_sprint(&mut self) {
+        if self.sprinting {
+            self.sprinting = false;
+            self.add_message("You slow to a walk.", MessageCategory::System);
+        } else if self.is_exhausted() {
+            self.add_message("You're too exhausted to sprint.", MessageCategory::Warning);
+        } else {
+            self.sprinting = true;
+            self.add_message(
+                format!("You break into a sprint. ({} SP per turn)", self.sprint_cost()),
+                MessageCategory::System,
+            );
+        }
+    }
+
+    /// Pay for a sprinting step; running dry drains the last of the stamina and stops the sprint
+    pub fn spend_sprint_stamina(&mut self) -> bool {
+        let cost = self.sprint_cost();
+        let paid = self.player_entity
+            .and_then(|p| self.world.get::<&mut Stamina>(p).ok())
+            .is_some_and(|mut stamina| {
+                let paid = stamina.spend(cost);
+                if !paid {
+                    stamina.current = 0;
+                }
+                paid
+            });
+        if !paid {
+            self.sprinting = false;
+            self.add_message("You're out of breath and stop sprinting.", MessageCategory::Warning);
+        }
+        paid
+    }
+
+    /// Exhaust the player at zero stamina, and let them recover once rested
+    fn update_exhaustion(&mut self) {
+        use crate::ecs::Exhausted;
+
+        let (Some(player), Some(stamina)) = (self.player_entity, self.player_stamina()) else {
+            return;
+        };
+        let exhausted = self.is_exhausted();
+        if !exhausted && stamina.current <= 0 {
+            let _ = self.world.insert_one(player, Exhausted);
+            self.sprinting = false;
+            self.add_message(
+                format!("You are exhausted! (-{}% damage, -{}% dodge)", EXHAUSTION_DAMAGE_PENALTY, EXHAUSTION_DODGE_PENALTY as i32),
+                MessageCategory::Warning,
+            );
+        } else if exhausted && stamina.current * 100 >= stamina.max * EXHAUSTION_RECOVERY_PERCENT {
+            let _ = self.world.remove_one::<Exhausted>(player);
+            self.add_message("You catch your breath.", MessageCategory::System);
+        }
+    }
+
+    // ========================================================================
+    // Opportunity attacks
+    // ========================================================================
+
+    /// Let every enemy the player steps away from take a free swing.
+    /// Hasted players slip away untouched.
+    pub fn provoke_opportunity_attacks(&mut self, from: Position, to: Position) {
+        use crate::ecs::{execute_ai_actions, AIAction, Enemy, Name, StatusEffects, StatusEffectType};
+
+        let hasted = self.player_entity
+            .and_then(|p| self.world.get::<&StatusEffects>(p).ok())
+            .is_some_and(|effects| effects.has_effect(StatusEffectType::Haste));
+        if hasted {
+            return;
+        }
+
+        // Stunned enemies can't capitalize on the opening
+        let threats: Vec<(Entity, Position)> = self.world
+            .query::<(&Position, &Enemy, Option<&StatusEffects>)>()
+            .iter()
+            .filter(|(_, (_, _, effects))| !effects.is_some_and(|e| e.has_effect(StatusEffectType::Stun)))
+            .map(|(entity, (pos, _, _))| (entity, *pos))
+            .collect();
+
+        let attackers = super::opportunity_attackers(from, to, &threats);
+        if attackers.is_empty() {
+            return;
+        }
+
+        for &attacker in &attackers {
+            let name = self.world.get::<&Name>(attacker).map(|n| n.0.clone()).unwrap_or_else(|_| "enemy".to_string());
+            self.add_message(format!("The {} strikes as you pull away!", name), MessageCategory::Warning);
+        }
+
+        let actions = attackers.into_iter()
+            .map(|attacker| AIAction::Attack { attacker, target_pos: from })
+            .collect();
+        let outcome = execute_ai_actions(&mut self.world, actions, self.player_entity, None, &mut self.rng);
+        if outcome.blocks > 0 {
+            self.play_sound(SoundId::Block);
+        }
+        for msg in outcome.messages {
+            self.add_message(msg, MessageCategory::Combat);
+        }
+    }
+
+    /// Pay the stamina to step away without provoking opportunity attacks
+    pub fn spend_disengage_stamina(&mut self) -> bool {
+        let paid = self.player_entity
+            .and_then(|p| self.world.get::<&mut Stamina>(p).ok())
+            .is_some_and(|mut stamina| stamina.spend(super::DISENGAGE_STAMINA_COST));
+        if !paid {
+            self.add_message(
+                format!("Not enough stamina to disengage! (need {})", super::DISENGAGE_STAMINA_COST),
+                MessageCategory::Warning,
+            );
+        }
+        paid
+    }
+
+    /// Take the fleeing enemies the player may strike at
+    pub fn take_fleeing_strikes(&mut self) -> Vec<Entity> {
+        std::mem::take(&mut self.fleeing_strikes)
+    }
+
+    /// Ready an attack or skill and pass the turn watching for enemies.
+    /// The first enemy to step within `range` springs it.
+    pub fn prepare_action(&mut self, action: super::PreparedAction, range: i32) {
+        self.prepared = Some((action, range));
+        self.run_ai_tick();
+    }
+
+    /// Take the enemy that sprang the player's prepared action, if any
+    pub fn take_overwatch_strike(&mut self) -> Option<(Entity, super::PreparedAction)> {
+        self.overwatch_strike.take()
+    }
+
+    // ========================================================================
+    // Resting
+    // ========================================================================
+
+    /// Wait a single turn, catching your breath
+    pub fn wait_turn(&mut self) {
+        self.recover_for_turn(1);
+        self.run_ai_tick();
+    }
+
+    /// Whether the player is resting
+    pub fn is_resting(&self) -> bool {
+        self.rest.is_some()
+    }
+
+    /// The rest in progress and the fraction of missing HP recovered so far
+    pub fn rest_progress(&self) -> Option<(&super::Rest, f32)> {
+        let rest = self.rest.as_ref()?;
+        let (hp, max_hp) = self.player_hp_and_max()?;
+        Some((rest, rest.progress(hp, max_hp)))
+    }
+
+    /// Settle down to rest until recovered or interrupted
+    pub fn start_rest(&mut self) {
+        if self.enemy_in_sight() {
+            self.add_message("You can't rest with enemies nearby!", MessageCategory::Warning);
+            return;
+        }
+        if self.fully_recovered() {
+            self.add_message("You don't need to rest.", MessageCategory::System);
+            return;
+        }
+        let Some((hp, _)) = self.player_hp_and_max() else { return };
+
+        self.rest = Some(super::Rest::new(hp));
+        self.add_message("You settle down to rest...", MessageCategory::System);
+    }
+
+    /// Stop resting, saying why
+    pub fn stop_rest(&mut self, reason: super::RestEnd) {
+        use super::RestEnd;
+
+        let Some(rest) = self.rest.take() else { return };
+        let category = match reason {
+            RestEnd::Interrupted | RestEnd::EnemySighted | RestEnd::Hurt => MessageCategory::Warning,
+            _ => MessageCategory::System,
+        };
+        self.add_message(reason.message(), category);
+
+        let meals = match rest.rations_eaten {
+            0 => String::new(),
+            1 => " and ate a ration".to_string(),
+            n => format!(" and ate {} rations", n),
+        };
+        self.add_message(format!("You rested for {} turns{}.", rest.turns, meals), MessageCategory::System);
+    }
+
+    /// Play out the rest one turn at a time so the progress can be watched
+    fn advance_rest(&mut self, delta_secs: f32) {
+        let Some(rest) = self.rest.as_mut() else { return };
+        rest.timer += delta_secs;
+        if rest.timer < super::REST_TURN_SECONDS {
+            return;
+        }
+        rest.timer = 0.0;
+        self.rest_turn();
+    }
+
+    /// Spend one turn resting
+    fn rest_turn(&mut self) {
+        use rand::Rng;
+        use super::RestEnd;
+
+        let Some(mut rest) = self.rest.take() else { return };
+
+        // Reach for a ration whenever the last one is finished
+        if rest.fed_turns == 0 {
+            let ate = self.player_entity
+                .and_then(|p| self.world.get::<&mut crate::ecs::InventoryComponent>(p).ok())
+                .and_then(|mut inv| inv.inventory.consume_ration())
+                .is_some();
+            if ate {
+                rest.fed_turns = super::TURNS_PER_RATION;
+                rest.rations_eaten += 1;
+            }
+        }
+        let fed = rest.fed_turns > 0;
+        rest.fed_turns = rest.fed_turns.saturating_sub(1);
+        rest.turns += 1;
+
+        self.recover_for_turn(if fed { 1 + super::FED_HEAL_BONUS } else { 1 });
+        rest.last_hp = self.player_hp_and_max().map(|(hp, _)| hp).unwrap_or(rest.last_hp);
+
+        // Every turn spent in one place gives the dungeon a chance to find you
+        let chance = super::interruption_chance(self.floor, self.armor_weight().noise());
+        let wanderer = self.rng.gen_bool(chance);
+        if wanderer {
+            self.spawn_wanderer();
+        }
+
+        self.rest = Some(rest);
+        self.run_ai_tick();
+        self.refresh_fov();
+
+        let Some(rest) = self.rest.as_ref() else { return };
+        let Some((hp, _)) = self.player_hp_and_max() else { return };
+        if hp <= 0 {
+            self.rest = None;
+            return;
+        }
+
+        let end = if wanderer {
+            Some(RestEnd::Interrupted)
+        } else if hp < rest.last_hp {
+            Some(RestEnd::Hurt)
+        } else if self.enemy_in_sight() {
+            Some(RestEnd::EnemySighted)
+        } else if self.fully_recovered() {
+            Some(RestEnd::Recovered)
+        } else if rest.turns >= super::REST_MAX_TURNS {
+            Some(RestEnd::TooLong)
+        } else {
+            None
+        };
+        if let Some(end) = end {
+            self.stop_rest(end);
+        }
+    }
+
+    /// Recover HP, stamina and mana for a turn spent catching your breath
+    fn recover_for_turn(&mut self, hp: i32) {
+        self.heal_player(hp);
+        // Stamina comes back faster when resting (less in heavy armor)
+        let stamina = (5.0 * self.armor_weight().stamina_regen_multiplier()).round() as i32;
+        self.restore_stamina(stamina);
+        self.restore_mana(2);
+    }
+
+    /// Current and effective max HP of the player
+    fn player_hp_and_max(&self) -> Option<(i32, i32)> {
+        let max_hp = self.player_derived_stats()?.max_hp;
+        self.player_health().map(|h| (h.current, max_hp))
+    }
+
+    /// Whether HP, mana and stamina are all full
+    fn fully_recovered(&self) -> bool {
+        let Some(derived) = self.player_derived_stats() else { return true };
+
+        self.player_hp_and_max().is_none_or(|(hp, max)| hp >= max)
+            && self.player_mana().is_none_or(|m| m.current >= derived.max_mp)
+            && self.player_stamina().is_none_or(|s| s.current >= s.max)
+    }
+
+    /// Whether any enemy stands on a tile the player can see
+    fn enemy_in_sight(&self) -> bool {
+        let Some(map) = &self.map else { return false };
+        self.world
+            .query::<(&Position, &crate::ecs::Enemy)>()
+            .iter()
+            .any(|(_, (pos, _))| map.get_tile(pos.x, pos.y).is_some_and(|t| t.visible))
+    }
+
+    /// Spawn a wandering monster out of sight that has caught the player's scent
+    /// Enemy scaling for the current floor, eased after repeated deaths here
+    fn floor_scaling(&self) -> crate::progression::FloorScaling {
+        let balance = self.data.balance();
+        let deaths = self.profile.deaths_on_floor(self.floor);
+        let ease = balance.rubber_band.ease(self.difficulty, deaths);
+        crate::progression::FloorScaling::with_balance(self.floor, self.difficulty, balance)
+            .eased(ease)
+            .new_game_plus(self.ng_plus)
+            .with_elites(self.sigils.elites_per_zone())
+            .with_enemy_count(self.adaptive.as_ref().map_or(1.0, |a| a.enemy_count_mult()))
+    }
+
+    fn spawn_wanderer(&mut self) {
+        use rand::seq::SliceRandom;
+        use crate::entities::{enemies_for_floor, spawn_enemy_scaled};
+        use crate::ecs::{AI, AIState};
+
+        // Nothing wanders onto the tutorial floor
+        if self.floor == 0 {
+            return;
+        }
+        let (Some(player_pos), Some(map)) = (self.player_position(), &self.map) else {
+            return;
+        };
+
+        let positions: Vec<Position> = map.get_walkable_positions()
+            .into_iter()
+            .filter(|pos| (6..=12).contains(&pos.chebyshev_distance(&player_pos)))
+            .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| !t.visible))
+            .filter(|pos| !self.is_blocked_by_entity(*pos))
+            .collect();
+        let (Some(&pos), Some(&def)) = (
+            positions.choose(&mut self.rng),
+            enemies_for_floor(map.biome, self.floor).choose(&mut self.rng),
+        ) else {
+            return;
+        };
+
+        let scaling = self.floor_scaling();
+        let enemy = spawn_enemy_scaled(&mut self.world, def, pos, &scaling);
+        let _ = self.world.insert_one(enemy, AI { state: AIState::Chase, target: Some(player_pos), home: pos });
+    }
+
+    /// Count a turn on this floor. Linger too long and the Hollow Warden
+    /// comes for the player.
+    fn advance_floor_clock(&mut self) {
+        use crate::entities::{BossType, STALKER_TURNS, STALKER_WARNING_TURNS};
+
+        self.floor_turns += 1;
+        self.run_clock.count_turn();
+        if BossType::is_boss_floor(self.floor) || self.floor == 0 {
+            return;
+        }
+        if self.peddler_due == Some(self.floor_turns) {
+            self.peddler_arrives();
+        }
+        if self.floor_turns == STALKER_TURNS - STALKER_WARNING_TURNS {
+            self.add_message("A distant bell tolls. Something knows you have lingered here too long.", MessageCategory::Warning);
+        } else if self.floor_turns == STALKER_TURNS {
+            self.spawn_stalker();
+        }
+    }
+
+    /// Bring the peddler onto the floor somewhere out of sight, not too far off
+    fn peddler_arrives(&mut self) {
+        use rand::seq::SliceRandom;
+
+        let (Some(player_pos), Some(map)) = (self.player_position(), &self.map) else {
+            return;
+        };
+        let positions: Vec<Position> = map.get_npc_spawn_positions(0)
+            .into_iter()
+            .filter(|pos| (8..=20).contains(&pos.chebyshev_distance(&player_pos)))
+            .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| !t.visible))
+            .filter(|pos| !self.is_blocked_by_entity(*pos) && crate::entities::get_npc_at(&self.world, *pos).is_none())
+            .collect();
+        let Some(&pos) = positions.choose(&mut self.rng) else { return };
+
+        let biome = self.biome();
+        let peddler = crate::entities::spawn_peddler(&mut self.world, pos, &mut self.rng, self.floor, biome, &mut self.item_id_counter);
+        self.stock_by_standing(&[peddler]);
+        self.add_message(
+            "Bells jingle somewhere nearby: a traveling peddler is passing through, but won't stay long.",
+            MessageCategory::Lore,
+        );
+        log::info!("Peddler arrived on floor {} after {} turns", self.floor, self.floor_turns);
+    }
+
+    /// Walk the peddler about, and send them on their way when it's time
+    fn move_peddlers(&mut self) {
+        use crate::entities::{Peddler, PEDDLER_WARNING_TURNS};
+
+        let (Some(player_pos), Some(map)) = (self.player_position(), &self.map) else {
+            return;
+        };
+        let leaving = crate::entities::wander_peddlers(&mut self.world, map, player_pos, &mut self.rng);
+        let packing = self.world.query::<&Peddler>().iter().any(|(_, p)| p.turns_left == PEDDLER_WARNING_TURNS);
+        if packing {
+            self.add_message("The peddler starts packing up their wares.", MessageCategory::System);
+        }
+        for peddler in leaving {
+            let _ = self.world.despawn(peddler);
+            self.add_message("The peddler shoulders their pack and moves on.", MessageCategory::System);
+        }
+    }
+
+    /// Turn on a peddler for their pack. They fight back, and it taints the
+    /// player whatever comes of it.
+    pub fn rob_peddler(&mut self, peddler: Entity) {
+        let scaling = self.floor_scaling();
+        if crate::entities::rob_peddler(&mut self.world, peddler, &scaling).is_none() {
+            return;
+        }
+        self.add_message("You turn on the peddler! They drop their pack and draw a knife.", MessageCategory::Warning);
+        self.shift_reputation(super::NpcFaction::MerchantsGuild, -super::ROBBERY_REPUTATION);
+
+        let mutation_due = self.player_entity
+            .and_then(|p| self.world.get::<&mut crate::progression::Mutations>(p).ok())
+            .is_some_and(|mut m| m.expose_by(crate::entities::ROBBERY_CORRUPTION));
+        self.add_message("Preying on the innocent leaves a stain on your soul...", MessageCategory::Warning);
+        if mutation_due {
+            self.add_message("The corruption seeps into your flesh!", MessageCategory::Warning);
+            self.grant_mutation();
+        }
+    }
+
+    /// Raise the Warden as far from the player as the floor allows
+    fn spawn_stalker(&mut self) {
+        let (Some(player_pos), Some(map)) = (self.player_position(), &self.map) else {
+            return;
+        };
+
+        let scent = crate::world::DijkstraMap::new(map, player_pos);
+        let spot = map.get_walkable_positions()
+            .into_iter()
+            .filter(|pos| !self.is_blocked_by_entity(*pos))
+            .filter_map(|pos| scent.distance(pos).map(|d| (d, pos)))
+            .max_by_key(|(d, _)| *d)
+            .map(|(_, pos)| pos);
+        let Some(pos) = spot else { return };
+
+        let scaling = self.floor_scaling();
+        crate::entities::spawn_stalker(&mut self.world, pos, &scaling);
+        self.add_message("The Hollow Warden has risen. It is coming for you.", MessageCategory::Warning);
+        log::info!("Hollow Warden spawned on floor {} after {} turns", self.floor, self.floor_turns);
+    }
+
+    // ========================================================================
+    // Encumbrance
+    // ========================================================================
+
+    /// Pack weight and carry capacity, or None when the difficulty ignores weight
+    pub fn encumbrance(&self) -> Option<(u32, u32)> {
+        if !self.profile.settings.encumbrance_enabled(self.difficulty) {
+            return None;
+        }
+        let player = self.player_entity?;
+        let weight = self.world.get::<&crate::ecs::InventoryComponent>(player)
+            .map(|inv| inv.inventory.total_weight())
+            .unwrap_or(0);
+        let strength = self.player_stats().map(|s| s.strength).unwrap_or(10);
+        Some((weight, crate::items::carry_capacity(strength)))
+    }
+
+    /// Whether the pack is heavier than the player can carry
+    pub fn is_overloaded(&self) -> bool {
+        self.encumbrance().is_some_and(|(weight, capacity)| weight > capacity)
+    }
+
+    /// An overloaded player lumbers along, giving enemies extra turns:
+    /// every other move when a little over, every move past half again the capacity
+    pub fn lumber_under_load(&mut self) {
+        let Some((weight, capacity)) = self.encumbrance().filter(|(w, c)| w > c) else {
+            self.overload_steps = 0;
+            return;
+        };
+        self.overload_steps += 1;
+        let every = if weight * 2 > capacity * 3 { 1 } else { 2 };
+        if self.overload_steps.is_multiple_of(every) {
+            self.run_ai_tick();
+        }
+    }
+
+    // ========================================================================
+    // Forced movement
+    // ========================================================================
+
+    /// Shove an entity across the map and resolve what it hits.
+    /// Walls and bodies deal slam damage; hazards swallow ordinary enemies whole.
+    pub fn shove(&mut self, entity: Entity, direction: (i32, i32), distance: i32) -> Option<crate::combat::ForcedMove> {
+        use crate::combat::{force_move, Collision, HAZARD_BOSS_DAMAGE, HAZARD_PLAYER_DAMAGE};
+        use crate::ecs::Name;
+        use crate::world::TileType;
+
+        let map = self.map.as_ref()?;
+        let result = force_move(&mut self.world, map, entity, direction, distance);
+
+        let is_player = Some(entity) == self.player_entity;
+        let name = if is_player {
+            "You".to_string()
+        } else {
+            self.world.get::<&Name>(entity)
+                .map(|n| format!("The {}", n.0))
+                .unwrap_or_else(|_| "It".to_string())
+        };
+
+        match result.collision {
+            Some(Collision::Hazard(hazard)) => {
+                let is_boss = self.world.get::<&crate::entities::BossComponent>(entity).is_ok();
+                let (damage, message) = if is_player {
+                    (HAZARD_PLAYER_DAMAGE, "You stumble at the edge and are badly hurt!".to_string())
+                } else if is_boss {
+                    (HAZARD_BOSS_DAMAGE, format!("{} teeters at the edge and is badly hurt!", name))
+                } else if hazard == TileType::Lava {
+                    (i32::MAX, format!("{} is hurled into the lava!", name))
+                } else if hazard == TileType::DeepWater {
+                    (i32::MAX, format!("{} is swept under the water!", name))
+                } else {
+                    (i32::MAX, format!("{} plunges into the pit!", name))
+                };
+                self.damage_entity(entity, damage);
+                self.add_message(message, MessageCategory::Combat);
+            }
+            Some(Collision::Wall) | Some(Collision::Entity(_)) => {
+                let damage = result.slam_damage();
+                if damage > 0 {
+                    self.damage_entity(entity, damage);
+                    let into = match result.collision {
+                        Some(Collision::Entity(other)) => {
+                            // Both bodies take the impact
+                            self.damage_entity(other, damage);
+                            "another creature"
+                        }
+                        _ => "the wall",
+                    };
+                    let verb = if is_player { "slam" } else { "slams" };
+                    self.add_message(format!("{} {} into {}! ({} damage)", name, verb, into, damage), MessageCategory::Combat);
+                }
+            }
+            None => {}
+        }
+
+        if is_player {
+            let radius = self.sight_radius();
+            if let Some(map) = self.map.as_mut() {
+                crate::world::compute_fov(map, result.to, radius);
+            }
+        }
+
+        Some(result)
+    }
+
+    // ========================================================================
+    // Props
+    // ========================================================================
+
+    /// Whether the player is strong enough to shift a prop, saying so if not
+    fn can_shift(&mut self, prop: crate::entities::Prop) -> bool {
+        let strength = self.player_stats().map(|s| s.strength).unwrap_or(10);
+        if strength >= prop.min_strength() {
+            return true;
+        }
+        self.add_message(
+            format!("The {} won't budge. (needs {} STR)", prop.name().to_lowercase(), prop.min_strength()),
+            MessageCategory::System,
+        );
+        false
+    }
+
+    /// Push a prop one tile away from the player. Returns true if it moved
+    /// out of the way, leaving its old spot free to step into.
+    pub fn push_prop(&mut self, entity: Entity, direction: (i32, i32)) -> bool {
+        use crate::entities::{shove_prop, PropMove, Pushable};
+        use crate::world::TileType;
+
+        let Some(prop) = self.world.get::<&Pushable>(entity).ok().map(|p| p.0) else { return false };
+        if !self.can_shift(prop) {
+            return false;
+        }
+        let Some(map) = self.map.as_ref() else { return false };
+        let name = prop.name().to_lowercase();
+
+        match shove_prop(&mut self.world, map, entity, direction) {
+            PropMove::Moved(_) => {
+                self.add_message(format!("You push the {}.", name), MessageCategory::System);
+                true
+            }
+            PropMove::Toppled(spill) => {
+                self.add_message(format!("The {} topples over, scattering burning coals!", name), MessageCategory::Combat);
+                self.spill_coals(spill);
+                true
+            }
+            PropMove::Lost(hazard) => {
+                let into = if hazard == TileType::DeepWater { "water" } else { hazard.hazard_name() };
+                self.add_message(format!("The {} topples into the {} and is gone.", name, into), MessageCategory::System);
+                true
+            }
+            PropMove::Stuck => {
+                self.add_message(format!("The {} won't go any further that way.", name), MessageCategory::System);
+                false
+            }
+        }
+    }
+
+    /// Drag the prop beside the player after them as they step back the
+    /// other way. Returns true if the player moved.
+    pub fn pull_prop(&mut self, direction: (i32, i32)) -> bool {
+        let Some(player_pos) = self.player_position() else { return false };
+        let prop_pos = Position::new(player_pos.x + direction.0, player_pos.y + direction.1);
+        let Some((entity, prop)) = crate::entities::get_prop_at(&self.world, prop_pos) else {
+            self.add_message("There's nothing there to pull.", MessageCategory::System);
+            return false;
+        };
+        if !self.can_shift(prop) {
+            return false;
+        }
+
+        let back = Position::new(player_pos.x - direction.0, player_pos.y - direction.1);
+        let clear = self.map.as_ref()
+            .and_then(|m| m.get_tile(back.x, back.y))
+            .is_some_and(|t| t.is_walkable() && !t.tile_type.is_hazard())
+            && self.get_blocking_entity_at(back).is_none()
+            && crate::entities::get_chest_at(&self.world, back).is_none();
+        if !clear {
+            self.add_message("There's no room to back away.", MessageCategory::System);
+            return false;
+        }
+
+        self.set_player_position(back);
+        if let Ok(mut pos) = self.world.get::<&mut Position>(entity) {
+            *pos = player_pos;
+        }
+        let radius = self.sight_radius();
+        if let Some(map) = self.map.as_mut() {
+            crate::world::compute_fov(map, back, radius);
+        }
+        self.add_message(format!("You drag the {} after you.", prop.name().to_lowercase()), MessageCategory::System);
+        true
+    }
+
+    /// Hurl the light prop beside the player onward in `direction`.
+    /// Returns true if anything was thrown.
+    pub fn throw_prop(&mut self, direction: (i32, i32)) -> bool {
+        use crate::combat::Collision;
+        use crate::ecs::Name;
+        use crate::entities::{hurl_prop, THROW_DAMAGE};
+
+        let Some(player_pos) = self.player_position() else { return false };
+        let prop_pos = Position::new(player_pos.x + direction.0, player_pos.y + direction.1);
+        let Some((entity, prop)) = crate::entities::get_prop_at(&self.world, prop_pos) else {
+            self.add_message("There's nothing there to throw.", MessageCategory::System);
+            return false;
+        };
+        let name = prop.name().to_lowercase();
+        if !prop.throwable() {
+            self.add_message(format!("The {} is far too heavy to throw.", name), MessageCategory::System);
+            return false;
+        }
+        let Some(map) = self.map.as_ref() else { return false };
+
+        let flight = hurl_prop(&mut self.world, map, entity, direction);
+        match flight.collision {
+            Some(Collision::Entity(target)) => {
+                self.damage_entity(target, THROW_DAMAGE);
+                let target_name = self.world.get::<&Name>(target)
+                    .map(|n| n.0.to_lowercase())
+                    .unwrap_or_else(|_| "creature".to_string());
+                self.add_message(
+                    format!("The {} smashes into the {}! ({} damage)", name, target_name, THROW_DAMAGE),
+                    MessageCategory::Combat,
+                );
+            }
+            _ => self.add_message(format!("You hurl the {}. It breaks apart.", name), MessageCategory::System),
+        }
+        self.leave_decal(flight.to, crate::world::Decal::Bones);
+        true
+    }
+
+    /// Burning coals land on a tile, setting any oil touching it alight and
+    /// burning whoever stands in the fire
+    fn spill_coals(&mut self, at: Position) {
+        use crate::ecs::StatusEffectType;
+        use crate::world::Decal;
+
+        let Some(map) = self.map.as_mut() else { return };
+        let mut fire = crate::entities::ignite(map, at);
+        if !fire.is_empty() {
+            self.add_message("The spilled oil goes up in a sheet of flame!", MessageCategory::Warning);
+        }
+        fire.push(at);
+        for pos in &fire {
+            self.leave_decal(*pos, Decal::Scorch);
+        }
+
+        let caught: Vec<Entity> = self.world.query::<(&Position, &Health)>()
+            .iter()
+            .filter(|(_, (pos, health))| fire.contains(pos) && !health.is_dead())
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in caught {
+            let burning = crate::combat::afflict(&mut self.world, entity, StatusEffectType::Burn, FIRE_BURN_TURNS, FIRE_BURN_INTENSITY);
+            if burning && Some(entity) == self.player_entity {
+                self.add_message("You are caught in the flames!", MessageCategory::Warning);
+            }
+        }
+    }
+
+    // ========================================================================
+    // Channeled actions
+    // ========================================================================
+
+    /// The multi-turn action in progress, if any
+    pub fn channel(&self) -> Option<&super::Channel> {
+        self.channel.as_ref()
+    }
+
+    pub fn is_channeling(&self) -> bool {
+        self.channel.is_some()
+    }
+
+    /// Start binding wounds
+    pub fn start_bandage(&mut self) {
+        use crate::ecs::{StatusEffects, StatusEffectType};
+
+        let Some((hp, max_hp)) = self.player_hp_and_max() else { return };
+        let bleeding = self.player_entity
+            .and_then(|p| self.world.get::<&StatusEffects>(p).ok())
+            .is_some_and(|e| e.has_effect(StatusEffectType::Bleed));
+        if hp >= max_hp && !bleeding {
+            self.add_message("You have no wounds to bind.", MessageCategory::System);
+            return;
+        }
+
+        let paid = self.player_entity
+            .and_then(|p| self.world.get::<&mut Stamina>(p).ok())
+            .is_some_and(|mut stamina| stamina.spend(super::BANDAGE_STAMINA_COST));
+        if !paid {
+            self.add_message(
+                format!("Not enough stamina to bandage! (need {})", super::BANDAGE_STAMINA_COST),
+                MessageCategory::Warning,
+            );
+            return;
+        }
+        self.begin_channel(super::ChannelKind::Bandage, super::BANDAGE_TURNS);
+    }
+
+    /// Start picking the lock of an adjacent locked door or chest
+    pub fn start_lockpick(&mut self) {
+        use crate::world::TileType;
+
+        let Some(player_pos) = self.player_position() else { return };
+        let door = self.adjacent_tiles(player_pos, |t| t == TileType::DoorLocked).first().copied();
+        let chest = || {
+            (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| Position::new(player_pos.x + dx, player_pos.y + dy)))
+                .find(|pos| self.locked_chest_at(*pos).is_some())
+        };
+        let Some(lock) = door.or_else(chest) else {
+            self.add_message("There's nothing locked next to you.", MessageCategory::System);
+            return;
+        };
+        let dexterity = self.player_stats().map(|s| s.dexterity).unwrap_or(10);
+        self.begin_channel(super::ChannelKind::PickLock(lock), super::lockpick_turns(dexterity));
+    }
+
+    fn begin_channel(&mut self, kind: super::ChannelKind, turns: u32) {
+        let Some((hp, _)) = self.player_hp_and_max() else { return };
+        self.channel = Some(super::Channel::new(kind, turns, hp));
+        self.add_message(kind.start_message(), MessageCategory::System);
+    }
+
+    /// Break off the action in progress; the work done so far is lost
+    pub fn cancel_channel(&mut self) {
+        let Some(channel) = self.channel.take() else { return };
+        self.add_message(
+            format!("You give up. ({}/{} turns of work lost)", channel.turns, channel.turns_needed),
+            MessageCategory::System,
+        );
+    }
+
+    /// Play out the action one turn at a time so the progress can be watched
+    fn advance_channel(&mut self, delta_secs: f32) {
+        let Some(channel) = self.channel.as_mut() else { return };
+        channel.timer += delta_secs;
+        if channel.timer < super::CHANNEL_TURN_SECONDS {
+            return;
+        }
+        channel.timer = 0.0;
+        self.channel_turn();
+    }
+
+    /// Spend one turn on the action in progress
+    fn channel_turn(&mut self) {
+        use crate::world::TileType;
+        use super::ChannelKind;
+
+        if let Some(channel) = self.channel.as_mut() {
+            channel.turns += 1;
+        }
+        self.run_ai_tick();
+        self.refresh_fov();
+
+        // Death or a new floor may have ended it already
+        let Some(mut channel) = self.channel.take() else { return };
+        let Some((hp, _)) = self.player_hp_and_max() else { return };
+        if hp <= 0 {
+            return;
+        }
+        if hp < channel.last_hp {
+            self.add_message(channel.kind.interrupted_message(), MessageCategory::Warning);
+            return;
+        }
+        channel.last_hp = hp;
+
+        // Something may have smashed the door in the meantime
+        if let ChannelKind::PickLock(lock) = channel.kind {
+            let still_locked = self.locked_chest_at(lock).is_some() || self.map.as_ref()
+                .and_then(|m| m.get_tile(lock.x, lock.y))
+                .is_some_and(|t| t.tile_type == TileType::DoorLocked);
+            if !still_locked {
+                self.add_message("The lock you were working at is gone.", MessageCategory::System);
+                return;
+            }
+        }
+
+        if channel.is_done() {
+            self.finish_channel(channel.kind);
+        } else {
+            self.channel = Some(channel);
+        }
+    }
+
+    /// Apply the result of a finished action
+    fn finish_channel(&mut self, kind: super::ChannelKind) {
+        use crate::ecs::{StatusEffects, StatusEffectType};
+        use crate::world::TileType;
+        use super::ChannelKind;
+
+        match kind {
+            ChannelKind::Bandage => {
+                if let Some(player) = self.player_entity {
+                    if let Ok(mut effects) = self.world.get::<&mut StatusEffects>(player) {
+                        effects.remove_effect(StatusEffectType::Bleed);
+                    }
+                }
+                let max_hp = self.player_hp_and_max().map(|(_, max)| max).unwrap_or(0);
+                let heal = super::bandage_heal(max_hp);
+                self.heal_player(heal);
+                self.add_message(format!("You tie off the bandage. (+{} HP)", heal), MessageCategory::System);
+            }
+            ChannelKind::PickLock(lock) => {
+                if let Some(chest) = self.locked_chest_at(lock) {
+                    if let Ok(mut chest) = self.world.get::<&mut crate::ecs::Chest>(chest) {
+                        chest.secured = false;
+                    }
+                    self.add_message("The chest's lock clicks open.", MessageCategory::System);
+                    return;
+                }
+                self.set_tile(lock, TileType::DoorOpen);
+                self.refresh_fov();
+                self.play_sound(SoundId::DoorOpen);
+                self.add_message("The lock clicks open.", MessageCategory::System);
+            }
+        }
+    }
+
+    // ========================================================================
+    // Doors
+    // ========================================================================
+
+    /// Try to get through a shut door the player walked into.
+    /// Closed doors swing open; locked ones take a key or a beating.
+    pub fn open_door(&mut self, pos: Position) {
+        use crate::ecs::InventoryComponent;
+        use crate::world::TileType;
+
+        let tile = self.map.as_ref().and_then(|m| m.get_tile(pos.x, pos.y)).map(|t| t.tile_type);
+        match tile {
+            Some(TileType::DoorClosed) => {
+                self.set_tile(pos, TileType::DoorOpen);
+                self.refresh_fov();
+                self.play_sound(SoundId::DoorOpen);
+                self.add_message("You open the door.", MessageCategory::System);
+            }
+            Some(TileType::DoorLocked) => {
+                let key = self.player_entity
+                    .and_then(|p| self.world.get::<&mut InventoryComponent>(p).ok())
+                    .and_then(|mut inv| inv.inventory.consume_key());
+                if let Some(key) = key {
+                    self.set_tile(pos, TileType::DoorOpen);
+                    self.refresh_fov();
+                    self.play_sound(SoundId::DoorOpen);
+                    self.add_message(format!("You unlock the door with the {}.", key.name), MessageCategory::System);
+                    return;
+                }
+
+                // No key: put a shoulder into it
+                if self.bash_door(pos, self.player_tear_power()) {
+                    self.add_message("The locked door bursts apart!", MessageCategory::System);
+                } else {
+                    self.add_message("The door is locked. You throw your shoulder into it.", MessageCategory::System);
+                }
+            }
+            Some(TileType::FleshWall) => {
+                if self.bash_door(pos, self.player_tear_power()) {
+                    self.add_message("You tear a way through the living flesh.", MessageCategory::System);
+                } else {
+                    self.add_message("You claw at the living flesh. It shudders and bleeds.", MessageCategory::System);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Damage the player deals battering a door or flesh (strength makes it go faster)
+    fn player_tear_power(&self) -> i32 {
+        let strength = self.player_entity
+            .and_then(|p| self.world.get::<&crate::ecs::Stats>(p).ok().map(|s| s.strength))
+            .unwrap_or(10);
+        1 + (strength - 10).max(0) / 4
+    }
+
+    /// Torn flesh nobody is standing in slowly knits shut again
+    fn regrow_flesh(&mut self) {
+        use rand::Rng;
+        use crate::ecs::BlocksMovement;
+        use crate::world::TileType;
+
+        if self.biome() != crate::world::Biome::FleshGardens {
+            return;
+        }
+        let Some(map) = self.map.as_ref() else { return };
+        let occupied: std::collections::HashSet<Position> = self.world.query::<(&Position, &BlocksMovement)>()
+            .iter()
+            .map(|(_, (pos, _))| *pos)
+            .collect();
+        let torn: Vec<(Position, bool)> = (0..map.tiles.len())
+            .filter(|&i| map.tiles[i].tile_type == TileType::TornFlesh)
+            .map(|i| {
+                let (x, y) = map.idx_to_xy(i);
+                (Position::new(x, y), map.tiles[i].visible)
+            })
+            .filter(|(pos, _)| !occupied.contains(pos))
+            .collect();
+
+        let mut seen = false;
+        let mut healed = false;
+        for (pos, visible) in torn {
+            if self.rng.gen_bool(FLESH_REGROW_CHANCE) {
+                self.set_tile(pos, TileType::FleshWall);
+                healed = true;
+                seen |= visible;
+            }
+        }
+        if healed {
+            self.refresh_fov();
+        }
+        if seen {
+            self.add_message("The torn flesh knits itself shut.", MessageCategory::System);
+        }
+    }
+
+    /// Close every open door next to the player, returning how many were shut
+    pub fn close_adjacent_doors(&mut self) -> usize {
+        use crate::world::TileType;
+
+        let Some(player_pos) = self.player_position() else { return 0 };
+        let doors: Vec<Position> = self.adjacent_tiles(player_pos, |t| t == TileType::DoorOpen)
+            .into_iter()
+            // Can't shut a door on someone standing in it
+            .filter(|pos| !self.world.query::<&Position>().iter().any(|(_, p)| p == pos))
+            .collect();
+
+        for &door in &doors {
+            self.set_tile(door, TileType::DoorClosed);
+        }
+        if !doors.is_empty() {
+            self.play_sound(SoundId::DoorOpen);
+            self.refresh_fov();
+        }
+        doors.len()
+    }
+
+    /// Look through the cracks of adjacent shut doors, returning how many were peeked through.
+    /// The glimpse lasts until the player next moves.
+    pub fn peek_through_doors(&mut self) -> usize {
+        let Some(player_pos) = self.player_position() else { return 0 };
+        let doors: Vec<(Position, crate::world::TileType)> = self.adjacent_tiles(player_pos, |t| t.is_shut_door())
+            .into_iter()
+            .filter_map(|pos| self.map.as_ref()?.get_tile(pos.x, pos.y).map(|t| (pos, t.tile_type)))
+            .collect();
+
+        let radius = self.sight_radius();
+        if let Some(map) = self.map.as_mut() {
+            for &(door, _) in &doors {
+                map.set_tile(door.x, door.y, crate::world::TileType::DoorOpen);
+            }
+            crate::world::compute_fov(map, player_pos, radius);
+            for &(door, tile) in &doors {
+                map.set_tile(door.x, door.y, tile);
+            }
+        }
+        doors.len()
+    }
+
+    /// Doors being bashed and the damage they've taken (for saving)
+    pub fn door_damage(&self) -> Vec<((i32, i32), i32)> {
+        self.door_damage.iter().map(|(pos, damage)| ((pos.x, pos.y), *damage)).collect()
+    }
+
+    /// Batter a shut door or living flesh, returning true once it gives way
+    fn bash_door(&mut self, pos: Position, power: i32) -> bool {
+        use crate::world::TileType;
+
+        let hp = match self.map.as_ref().and_then(|m| m.get_tile(pos.x, pos.y)).map(|t| t.tile_type) {
+            Some(TileType::DoorClosed) => DOOR_HP,
+            Some(TileType::DoorLocked) => LOCKED_DOOR_HP,
+            Some(TileType::FleshWall) => FLESH_HP,
+            _ => return false,
+        };
+        let damage = self.door_damage.entry(pos).or_insert(0);
+        *damage += power;
+        if *damage < hp {
+            return false;
+        }
+
+        self.door_damage.remove(&pos);
+        let torn = if hp == FLESH_HP { TileType::TornFlesh } else { TileType::Rubble };
+        self.set_tile(pos, torn);
+        self.play_sound(SoundId::DoorOpen);
+        self.refresh_fov();
+        true
+    }
+
+    /// Positions next to `center` whose tile matches a predicate
+    fn adjacent_tiles(&self, center: Position, matches: impl Fn(crate::world::TileType) -> bool) -> Vec<Position> {
+        let Some(map) = self.map.as_ref() else { return Vec::new() };
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| Position::new(center.x + dx, center.y + dy)))
+            .filter(|pos| *pos != center)
+            .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| matches(t.tile_type)))
+            .collect()
+    }
+
+    /// Change a tile on the current floor
+    fn set_tile(&mut self, pos: Position, tile: crate::world::TileType) {
+        if let Some(map) = self.map.as_mut() {
+            map.set_tile(pos.x, pos.y, tile);
+        }
+    }
+
+    /// Recompute what the player can see
+    fn refresh_fov(&mut self) {
+        let radius = self.sight_radius();
+        if let (Some(pos), Some(map)) = (self.player_position(), self.map.as_mut()) {
+            crate::world::compute_fov(map, pos, radius);
+        }
+    }
+
+    // ========================================================================
+    // Secrets
+    // ========================================================================
+
+    /// Spend a turn searching the walls nearby for secret doors
+    pub fn search(&mut self) {
+        let Some(stats) = self.player_stats() else { return };
+        let chance = (crate::entities::spot_chance(stats.intelligence, stats.dexterity) + SEARCH_BONUS).min(0.95);
+        if self.look_for_secrets(SEARCH_RADIUS, chance) == 0 {
+            self.add_message("You search the walls around you, but find nothing.", MessageCategory::System);
+        }
+        self.run_ai_tick();
+    }
+
+    /// Roll to notice each secret door within `radius` of the player,
+    /// revealing the ones found. Returns how many turned up.
+    fn look_for_secrets(&mut self, radius: i32, chance: f64) -> usize {
+        use rand::Rng;
+        use crate::world::TileType;
+
+        let (Some(player_pos), Some(map)) = (self.player_position(), self.map.as_ref()) else { return 0 };
+        let hidden: Vec<Position> = (-radius..=radius)
+            .flat_map(|dy| (-radius..=radius).map(move |dx| Position::new(player_pos.x + dx, player_pos.y + dy)))
+            .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type == TileType::SecretDoor))
+            .collect();
+
+        let mut found = 0;
+        for pos in hidden {
+            if !self.rng.gen_bool(chance) {
+                continue;
+            }
+            self.set_tile(pos, TileType::DoorClosed);
+            self.float_text(pos, "?", (255, 220, 120));
+            found += 1;
+        }
+        if found > 0 {
+            self.play_sound(SoundId::SecretFound);
+            self.add_message("A draft whistles through a seam in the stone. A hidden door!", MessageCategory::Lore);
+        }
+        found
+    }
+
+    // ========================================================================
+    // Chests
+    // ========================================================================
+
+    /// A locked chest still shut at a position
+    fn locked_chest_at(&self, pos: Position) -> Option<Entity> {
+        use crate::ecs::{Chest, ChestKind};
+
+        crate::entities::get_chest_at(&self.world, pos)
+            .filter(|&e| self.world.get::<&Chest>(e).is_ok_and(|c| c.kind == ChestKind::Locked && c.secured))
+    }
+
+    /// Look over the chests next to the player for traps and teeth.
+    /// Each chest gets one look.
+    fn search_adjacent_chests(&mut self) {
+        use rand::Rng;
+        use crate::ecs::{Chest, ChestKind};
+
+        let Some(player_pos) = self.player_position() else { return };
+        let Some(stats) = self.player_stats() else { return };
+        let chance = crate::entities::spot_chance(stats.intelligence, stats.dexterity);
+
+        let unsearched: Vec<(Entity, ChestKind)> = self.world.query::<(&Position, &Chest)>()
+            .iter()
+            .filter(|(_, (pos, chest))| !chest.opened && !chest.searched && pos.chebyshev_distance(&player_pos) <= 1)
+            .map(|(e, (_, chest))| (e, chest.kind))
+            .collect();
+        for (entity, kind) in unsearched {
+            let spotted = matches!(kind, ChestKind::Trapped(_) | ChestKind::Mimic) && self.rng.gen_bool(chance);
+            if let Ok(mut chest) = self.world.get::<&mut Chest>(entity) {
+                chest.searched = true;
+                chest.revealed = spotted;
+            }
+            if !spotted {
+                continue;
+            }
+            crate::entities::refresh_chest_glyph(&mut self.world, entity);
+            let message = match kind {
+                ChestKind::Trapped(trap) => format!("You spot a {} rigged to the chest's lid.", trap.name()),
+                _ => "That chest is breathing. It's a mimic!".to_string(),
+            };
+            self.add_message(message, MessageCategory::Warning);
+        }
+    }
+
+    /// Reach for a chest, dealing with whatever guards it first
+    pub fn approach_chest(&mut self, entity: Entity) -> crate::entities::ChestApproach {
+        use rand::Rng;
+        use crate::ecs::{Chest, ChestKind, InventoryComponent};
+        use crate::entities::ChestApproach;
+
+        let Ok((kind, secured, revealed, rarity)) = self.world.get::<&Chest>(entity)
+            .map(|c| (c.kind, c.secured, c.revealed, c.rarity)) else {
+            return ChestApproach::Shut;
+        };
+        if !secured {
+            return ChestApproach::Open;
+        }
+
+        match kind {
+            ChestKind::Plain => ChestApproach::Open,
+            ChestKind::Locked => {
+                let key = self.player_entity
+                    .and_then(|p| self.world.get::<&mut InventoryComponent>(p).ok())
+                    .and_then(|mut inv| inv.inventory.consume_key());
+                let Some(key) = key else {
+                    self.add_message("The chest is locked. Find a key, or [L] to pick the lock.", MessageCategory::System);
+                    return ChestApproach::Shut;
+                };
+                if let Ok(mut chest) = self.world.get::<&mut Chest>(entity) {
+                    chest.secured = false;
+                }
+                self.add_message(format!("You unlock the chest with the {}.", key.name), MessageCategory::System);
+                ChestApproach::Open
+            }
+            ChestKind::Trapped(trap) => {
+                if let Ok(mut chest) = self.world.get::<&mut Chest>(entity) {
+                    chest.secured = false;
+                }
+                let dexterity = self.player_stats().map(|s| s.dexterity).unwrap_or(10);
+                if revealed && self.rng.gen_bool(crate::entities::disarm_chance(dexterity)) {
+                    self.add_message(format!("You carefully disarm the {}.", trap.name()), MessageCategory::System);
+                    return ChestApproach::Open;
+                }
+                if revealed {
+                    self.add_message("Your hand slips!", MessageCategory::Warning);
+                }
+                if self.spring_chest_trap(trap) {
+                    ChestApproach::Open
+                } else {
+                    ChestApproach::Shut
+                }
+            }
+            ChestKind::Mimic => {
+                let Ok(pos) = self.world.get::<&Position>(entity).map(|p| *p) else {
+                    return ChestApproach::Shut;
+                };
+                let _ = self.world.despawn(entity);
+                let scaling = self.floor_scaling();
+                crate::entities::spawn_mimic(&mut self.world, pos, rarity, &scaling);
+                self.add_message("The lid yawns open on rows of teeth - it's a mimic!", MessageCategory::Warning);
+                ChestApproach::Ambush
+            }
+            ChestKind::Puzzle(riddle) => ChestApproach::Riddle(riddle),
+        }
+    }
+
+    /// Answer a puzzle chest's riddle. The right answer unseals it; a wrong
+    /// one seals it for good. Returns whether the answer was right.
+    pub fn answer_riddle(&mut self, entity: Entity, answer: usize) -> bool {
+        use crate::ecs::{Chest, ChestKind};
+        use crate::entities::RIDDLES;
+
+        let riddle = self.world.get::<&Chest>(entity).ok().and_then(|c| match c.kind {
+            ChestKind::Puzzle(i) => RIDDLES.get(i).copied(),
+            _ => None,
+        });
+        let Some(riddle) = riddle else { return false };
+
+        if answer == riddle.correct {
+            if let Ok(mut chest) = self.world.get::<&mut Chest>(entity) {
+                chest.secured = false;
+            }
+            self.add_message("\"Correct.\" The lock turns of its own accord.", MessageCategory::System);
+            true
+        } else {
+            crate::entities::mark_chest_opened(&mut self.world, entity);
+            self.add_message("\"Wrong.\" The lid fuses shut with a hiss.", MessageCategory::Warning);
+            false
+        }
+    }
+
+    /// Set off a chest trap on the player. Returns false if it killed them.
+    fn spring_chest_trap(&mut self, trap: crate::ecs::ChestTrap) -> bool {
+        use crate::ecs::{ChestTrap, StatusEffects, StatusEffectType};
+
+        let Some(player) = self.player_entity else { return false };
+        let damage = CHEST_TRAP_DAMAGE + self.floor as i32;
+        self.damage_entity(player, damage);
+        let (effect, duration, intensity, message) = match trap {
+            ChestTrap::PoisonNeedle => (StatusEffectType::Poison, 5.0, 2, "A needle jabs into your hand!"),
+            ChestTrap::FireBurst => (StatusEffectType::Burn, 3.0, 3, "The lid erupts in flame!"),
+        };
+        if let Ok(mut effects) = self.world.get::<&mut StatusEffects>(player) {
+            effects.add_effect(effect, duration, intensity);
+        }
+        if trap == ChestTrap::FireBurst {
+            if let Some(pos) = self.player_position() {
+                self.leave_decal(pos, crate::world::Decal::Scorch);
+            }
+        }
+        self.add_message(format!("{} (-{} HP)", message, damage), MessageCategory::Combat);
+
+        if self.player_health().is_some_and(|h| h.is_dead()) && !self.cheat_death() {
+            self.player_died("caught by a trapped chest");
+            return false;
+        }
+        true
+    }
+
+    // ========================================================================
+    // Hazards
+    // ========================================================================
+
+    /// Burn or corrupt the player for standing in a hazard
+    fn apply_hazard_tile(&mut self) {
+        use crate::ecs::{StatusEffects, StatusEffectType};
+        use crate::world::TileType;
+
+        let (Some(player), Some(pos)) = (self.player_entity, self.player_position()) else {
+            return;
+        };
+        let tile = self.map.as_ref()
+            .and_then(|m| m.get_tile(pos.x, pos.y))
+            .map(|t| t.tile_type);
+
+        match tile {
+            Some(TileType::Lava) => {
+                self.damage_entity(player, LAVA_DAMAGE);
+                if let Ok(mut effects) = self.world.get::<&mut StatusEffects>(player) {
+                    effects.add_effect(StatusEffectType::Burn, 3.0, 2);
+                }
+                self.add_message(format!("The lava sears you! (-{} HP)", LAVA_DAMAGE), MessageCategory::Combat);
+            }
+            Some(TileType::BloodPool) => {
+                let mutation_due = self.world.get::<&mut crate::progression::Mutations>(player)
+                    .is_ok_and(|mut m| m.expose_by(BLOOD_POOL_EXPOSURE));
+                self.add_message("Corrupted blood clings to your skin...", MessageCategory::Warning);
+                if mutation_due {
+                    self.add_message("The corruption seeps into your flesh!", MessageCategory::Warning);
+                    self.grant_mutation();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Drop through a pit to the floor below, landing hard
+    pub fn fall_into_pit(&mut self) {
+        self.play_sound(SoundId::Descend);
+        self.add_message("You plunge into the pit!", MessageCategory::Warning);
+        self.descend();
+        self.play_sound(SoundId::NewFloor);
+
+        if let Some(player) = self.player_entity {
+            self.damage_entity(player, PIT_FALL_DAMAGE);
+        }
+        self.add_message(
+            format!("You crash onto the floor below. (-{} HP)", PIT_FALL_DAMAGE),
+            MessageCategory::Combat,
+        );
+
+        if self.player_health().is_some_and(|h| h.is_dead()) && !self.cheat_death() {
+            self.player_died("fell into a pit");
+        }
+    }
+
+    // ========================================================================
+    // Mechanisms
+    // ========================================================================
+
+    /// Raise or lower portcullises to match their levers and plates, and
+    /// spring the traps under anything that just stepped onto a plate
+    fn work_mechanisms(&mut self) {
+        use crate::ecs::BlocksMovement;
+
+        let weighed: std::collections::HashSet<Position> = self.world
+            .query::<(&Position, &BlocksMovement)>()
+            .iter()
+            .map(|(_, (pos, _))| *pos)
+            .chain(self.player_position())
+            .collect();
+        let Some(map) = self.map.as_mut() else { return };
+        for (trap, at) in crate::world::work_mechanisms(map, &weighed) {
+            self.spring_trap(trap, at);
+        }
+    }
+
+    /// Throw the lever at `pos`. Returns false if there's no lever there.
+    pub fn pull_lever(&mut self, pos: Position) -> bool {
+        let Some(map) = self.map.as_mut() else { return false };
+        let Some(on) = crate::world::throw_lever(map, pos) else { return false };
+        self.play_sound(SoundId::DoorOpen);
+        if on {
+            self.add_message("You throw the lever. Chains rattle as a portcullis grinds up.", MessageCategory::System);
+        } else {
+            self.add_message("You push the lever back. A portcullis rattles down.", MessageCategory::System);
+        }
+        self.work_mechanisms();
+        true
+    }
+
+    /// A trap plate goes down under whatever stepped onto it
+    fn spring_trap(&mut self, trap: crate::world::Trap, at: Position) {
+        use crate::ecs::{Enemy, Name, StatusEffectType};
+        use crate::world::Trap;
+
+        let seen = self.map.as_ref()
+            .and_then(|m| m.get_tile(at.x, at.y))
+            .is_some_and(|t| t.visible);
+        match trap {
+            Trap::Flames => {
+                if seen {
+                    self.add_message("Click. Flames roar up from the floor!", MessageCategory::Warning);
+                }
+                self.spill_coals(at);
+            }
+            Trap::Darts => {
+                let victim = self.world.query::<(&Position, &Health)>()
+                    .iter()
+                    .find(|(_, (pos, _))| **pos == at)
+                    .map(|(entity, _)| entity);
+                let Some(victim) = victim else { return };
+                self.damage_entity(victim, DART_DAMAGE);
+                crate::combat::afflict(&mut self.world, victim, StatusEffectType::Poison, DART_POISON_TURNS, DART_POISON_INTENSITY);
+
+                if Some(victim) == self.player_entity {
+                    self.add_message(
+                        format!("Click. Darts hiss out of the walls! (-{} HP)", DART_DAMAGE),
+                        MessageCategory::Combat,
+                    );
+                    return;
+                }
+                let name = self.world.get::<&Name>(victim)
+                    .map(|n| n.0.to_lowercase())
+                    .unwrap_or_else(|_| "creature".to_string());
+                if seen {
+                    self.add_message(format!("Darts riddle the {}!", name), MessageCategory::Combat);
+                }
+                let killed = self.world.get::<&Enemy>(victim).is_ok()
+                    && self.world.get::<&Health>(victim).is_ok_and(|h| h.is_dead());
+                if killed {
+                    if seen {
+                        self.add_message(format!("The {} falls to the darts.", name), MessageCategory::Combat);
+                    }
+                    self.queue_death(victim, false, false);
+                }
+            }
+        }
+    }
+
+    // ========================================================================
+    // Collapse
+    // ========================================================================
+
+    /// Bring the floor down if its boss is one that takes it with them. The
+    /// stairs on the dais fall in and a fissure opens back at the entrance.
+    fn begin_collapse(&mut self) {
+        use crate::entities::BossType;
+        use crate::world::TileType;
+
+        if self.collapse.is_some() || self.boss_rush.is_some() {
+            return;
+        }
+        let Some(boss_type) = BossType::for_floor(self.floor).filter(|b| b.collapses_floor()) else { return };
+        let Some(map) = self.map.as_mut() else { return };
+        let (Some(dais), fissure) = (map.exit_pos, map.start_pos) else { return };
+        if dais == fissure {
+            return;
+        }
+
+        map.set_tile(dais.x, dais.y, TileType::Rubble);
+        map.set_tile(fissure.x, fissure.y, TileType::StairsDown);
+        map.exit_pos = Some(fissure);
+        let collapse = super::Collapse::new(dais, fissure);
+        self.collapse = Some(collapse);
+        self.shake = super::SHAKE_SECONDS * 2.0;
+        self.play_sound(SoundId::Rumble);
+
+        self.add_message(boss_type.collapse_message(), MessageCategory::Warning);
+        self.add_message(
+            format!("The stairs crumble away! A fissure has split open back at the entrance - reach it within {} turns!", collapse.turns_left),
+            MessageCategory::Warning,
+        );
+    }
+
+    /// The floor coming down, if it is
+    pub fn collapse(&self) -> Option<&super::Collapse> {
+        self.collapse.as_ref()
+    }
+
+    /// Seconds left of the screen shaking
+    pub fn screen_shake(&self) -> f32 {
+        self.shake
+    }
+
+    /// Run a turn of the collapse. Anything standing where the ground gives
+    /// way goes down with it. Returns false if the player fell through.
+    fn crumble_floor(&mut self) -> bool {
+        use crate::ecs::{Enemy, Name};
+
+        let Some(mut collapse) = self.collapse else { return true };
+        let Some(player_pos) = self.player_position() else { return true };
+        let occupied: Vec<(Entity, Position, bool)> = self.world.query::<(&Position, &crate::ecs::BlocksMovement)>()
+            .iter()
+            .map(|(entity, (pos, _))| (entity, *pos, self.world.get::<&Enemy>(entity).is_ok()))
+            .collect();
+        let Some(map) = self.map.as_mut() else { return true };
+        let fallen = collapse.crumble(map, &mut self.rng, |pos| {
+            occupied.iter().any(|(_, at, enemy)| *at == pos && !enemy)
+        });
+        self.collapse = Some(collapse);
+        self.shake = super::SHAKE_SECONDS;
+        self.play_sound(SoundId::Rumble);
+
+        for (entity, pos, enemy) in occupied {
+            if !enemy || !fallen.contains(&pos) {
+                continue;
+            }
+            let seen = self.map.as_ref().and_then(|m| m.get_tile(pos.x, pos.y)).is_some_and(|t| t.visible);
+            if seen {
+                let name = self.world.get::<&Name>(entity).map(|n| n.0.to_lowercase()).unwrap_or_else(|_| "creature".to_string());
+                self.add_message(format!("The {} plunges into the depths!", name), MessageCategory::Combat);
+            }
+            let _ = self.world.despawn(entity);
+        }
+
+        if collapse.expired() {
+            self.add_message("With a roar, the whole floor gives way!", MessageCategory::Warning);
+            self.fall_with_floor();
+            return false;
+        }
+        if fallen.contains(&player_pos) {
+            self.add_message("The ground crumbles beneath your feet!", MessageCategory::Warning);
+            self.fall_with_floor();
+            return false;
+        }
+        if collapse.warning_due() {
+            self.add_message(
+                format!("The floor groans. {} turns until it gives way!", collapse.turns_left),
+                MessageCategory::Warning,
+            );
+        }
+        true
+    }
+
+    /// Fall through with the collapsing floor to the one below, hurt and
+    /// injured
+    fn fall_with_floor(&mut self) {
+        self.play_sound(SoundId::Descend);
+        self.descend();
+        self.play_sound(SoundId::NewFloor);
+
+        if let Some(player) = self.player_entity {
+            self.damage_entity(player, super::COLLAPSE_FALL_DAMAGE);
+        }
+        self.add_message(
+            format!("You crash onto the floor below amid a hail of rubble. (-{} HP)", super::COLLAPSE_FALL_DAMAGE),
+            MessageCategory::Combat,
+        );
+        self.grant_injury();
+
+        if self.player_health().is_some_and(|h| h.is_dead()) && !self.cheat_death() {
+            self.player_died("buried by a collapsing floor");
+        }
+    }
+
+    /// Knock health off an entity, never below zero
+    fn damage_entity(&mut self, entity: Entity, damage: i32) {
+        if let Ok(mut health) = self.world.get::<&mut Health>(entity) {
+            health.current = health.current.saturating_sub(damage).max(0);
+        }
+    }
+
+    /// Bosses that drag the player in use their special when it comes off cooldown
+    fn boss_yanks(&mut self, player_pos: Position) {
+        use crate::entities::BossComponent;
+        use crate::combat::direction_toward;
+
+        let Some(player) = self.player_entity else { return };
+
+        let mut yanks = Vec::new();
+        for (_, (boss, pos)) in self.world.query_mut::<(&mut BossComponent, &Position)>() {
+            let distance = pos.chebyshev_distance(&player_pos);
+            if !boss.boss_type.yanks_player() || !(2..=BOSS_YANK_RANGE).contains(&distance) {
+                continue;
+            }
+            boss.special_cooldown = boss.special_cooldown.saturating_sub(1);
+            if boss.special_cooldown == 0 {
+                boss.special_cooldown = boss.boss_type.special_cooldown();
+                yanks.push((boss.boss_type, *pos, distance));
+            }
+        }
+
+        for (boss_type, boss_pos, distance) in yanks {
+            self.add_message(boss_type.yank_message(), MessageCategory::Warning);
+            self.shove(player, direction_toward(player_pos, boss_pos), distance - 1);
+        }
+    }
+
+    /// Bosses that sow heart nodes grow a fresh one beside them when their
+    /// special comes off cooldown
+    fn boss_grows_hearts(&mut self) {
+        use crate::ecs::{AI, AIState, BlocksMovement};
+        use crate::entities::BossComponent;
+
+        let mut sowing = Vec::new();
+        for (_, (boss, ai, pos)) in self.world.query_mut::<(&mut BossComponent, &AI, &Position)>() {
+            let engaged = !boss.defeated && matches!(ai.state, AIState::Chase | AIState::Attack);
+            if !boss.boss_type.grows_hearts() || !engaged {
+                continue;
+            }
+            boss.special_cooldown = boss.special_cooldown.saturating_sub(1);
+            if boss.special_cooldown == 0 {
+                boss.special_cooldown = boss.boss_type.special_cooldown();
+                sowing.push((boss.boss_type, *pos));
+            }
+        }
+
+        let scaling = self.floor_scaling();
+        for (boss_type, boss_pos) in sowing {
+            if crate::entities::heart_count(&self.world) >= GARDENER_MAX_HEARTS {
+                continue;
+            }
+            let Some(map) = self.map.as_ref() else { return };
+            let spot = (-2..=2)
+                .flat_map(|dy| (-2..=2).map(move |dx| Position::new(boss_pos.x + dx, boss_pos.y + dy)))
+                .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type.is_walkable()))
+                .find(|pos| !self.world.query::<(&Position, &BlocksMovement)>().iter().any(|(_, (p, _))| p == pos));
+            let Some(spot) = spot else { continue };
+            crate::entities::spawn_heart_node(&mut self.world, spot, &scaling);
+            self.add_message(format!("{} presses a heart into the floor. It begins to beat.", boss_type.name()), MessageCategory::Warning);
+        }
+    }
+
+    /// The boss hunting the player, if a boss fight is on
+    pub fn active_boss(&self) -> Option<crate::entities::BossFight> {
+        use crate::ecs::{AI, AIState};
+        use crate::entities::{BossComponent, BossFight};
+
+        self.world.query::<(&BossComponent, &Health, &AI)>()
+            .iter()
+            .find(|(_, (boss, health, ai))| {
+                !boss.defeated && !health.is_dead() && matches!(ai.state, AIState::Chase | AIState::Attack)
+            })
+            .map(|(_, (boss, health, _))| BossFight {
+                boss_type: boss.boss_type,
+                phase: boss.phase,
+                health: *health,
+                enrage_in: boss.enrage_in(),
+            })
+    }
+
+    /// Bosses fighting the player count down to enraging, and hit harder
+    /// once they do
+    fn boss_fight_turn(&mut self) {
+        use crate::ecs::{AI, AIState};
+        use crate::entities::{bosses::ENRAGE_STRENGTH_PERCENT, BossComponent};
+
+        let mut enraged = Vec::new();
+        for (_, (boss, ai, stats)) in self.world.query_mut::<(&mut BossComponent, &AI, &mut Stats)>() {
+            let engaged = !boss.defeated && matches!(ai.state, AIState::Chase | AIState::Attack);
+            if engaged && boss.tick_engaged() {
+                stats.strength += stats.strength * ENRAGE_STRENGTH_PERCENT / 100;
+                enraged.push(boss.boss_type);
+            }
+        }
+        for boss_type in enraged {
+            self.add_message(format!("⚠ {} is ENRAGED!", boss_type.name()), MessageCategory::Warning);
+            self.add_message(boss_type.enrage_message(), MessageCategory::Lore);
+        }
+    }
+
+    // ========================================================================
+    // Examine
+    // ========================================================================
+
+    /// What the player knows about a cell, or None if they've never seen it
+    pub fn describe_cell(&self, pos: Position) -> Option<super::CellDescription> {
+        use crate::ecs::{Chest, ChestKind, GroundItem, Name, Player};
+        use crate::entities::Corpse;
+
+        let map = self.map.as_ref()?;
+        let tile = map.get_tile(pos.x, pos.y).filter(|t| t.explored)?;
+        let mut cell = super::CellDescription {
+            tile: tile.tile_type.name().to_string(),
+            decal: map.decal_at(pos).map(|d| d.name()),
+            remembered: !tile.visible,
+            ..Default::default()
+        };
+        if !tile.visible {
+            return Some(cell);
+        }
+
+        for (entity, (at, name, health)) in self.world.query::<(&Position, &Name, &Health)>().iter() {
+            if *at != pos {
+                continue;
+            }
+            if self.world.get::<&Player>(entity).is_ok() {
+                cell.creatures.push("You".to_string());
+            } else if let Some(threat) = self.threat_of(entity) {
+                cell.creatures.push(format!(
+                    "{} ({}, {} threat)",
+                    name.0,
+                    super::condition(health.current, health.max),
+                    threat.label(),
+                ));
+            } else {
+                cell.creatures.push(format!("{} ({})", name.0, super::condition(health.current, health.max)));
+            }
+            if let Ok(resistances) = self.world.get::<&crate::combat::Resistances>(entity) {
+                cell.creatures.extend(resistances.describe().into_iter().map(|line| format!("  {}", line)));
+            }
+        }
+        for (_, (at, chest)) in self.world.query::<(&Position, &Chest)>().iter() {
+            if *at != pos {
+                continue;
+            }
+            let what = match chest.kind {
+                _ if chest.opened => "Opened chest",
+                ChestKind::Trapped(_) if chest.revealed => "Trapped chest",
+                ChestKind::Mimic if chest.revealed => "Chest (it has teeth)",
+                ChestKind::Locked if chest.secured => "Locked chest",
+                ChestKind::Puzzle(_) if chest.secured => "Puzzle chest",
+                _ => "Chest",
+            };
+            cell.features.push(what.to_string());
+        }
+        for (_, (at, corpse)) in self.world.query::<(&Position, &Corpse)>().iter() {
+            if *at != pos {
+                continue;
+            }
+            let body = if corpse.gibbed { "Remains" } else { "Corpse" };
+            let searched = if corpse.searched { ", searched" } else { "" };
+            cell.features.push(format!("{} of a {}{}", body, corpse.name, searched));
+        }
+        if let Some((_, prop)) = crate::entities::get_prop_at(&self.world, pos) {
+            cell.features.push(prop.name().to_string());
+        }
+        for (_, (at, ground)) in self.world.query::<(&Position, &GroundItem)>().iter() {
+            if *at == pos {
+                cell.items.push(self.discoveries.disguise(&ground.item).name.clone());
+            }
+        }
+        Some(cell)
+    }
+
+    // ========================================================================
+    // Context actions
+    // ========================================================================
+
+    /// What the player can do from where they stand, for the action bar
+    pub fn context_actions(&self) -> Vec<super::ContextAction> {
+        use super::ContextAction;
+        use crate::ecs::{Chest, ChestKind, Enemy, GroundItem, StatusEffects, StatusEffectType};
+        use crate::entities::{Corpse, NpcComponent};
+        use crate::world::TileType;
+
+        let mut actions = Vec::new();
+        let (Some(map), Some(player_pos)) = (self.map.as_ref(), self.player_position()) else { return actions };
+        let near = |pos: &Position| pos.chebyshev_distance(&player_pos) <= 1;
+
+        let underfoot = map.get_tile(player_pos.x, player_pos.y).map(|t| t.tile_type);
+        if map.exit_pos == Some(player_pos) {
+            actions.push(ContextAction::Descend);
+        }
+        match underfoot {
+            Some(t) if t.is_altar() => actions.push(ContextAction::Pray { altar: true }),
+            Some(t) if t.is_shrine() && !self.is_shrine_used(player_pos) => actions.push(ContextAction::Pray { altar: false }),
+            _ => {}
+        }
+
+        let items: Vec<String> = self.world.query::<(&Position, &GroundItem)>()
+            .iter()
+            .filter(|(_, (pos, _))| near(pos))
+            .map(|(_, (_, ground))| self.discoveries.disguise(&ground.item).name.clone())
+            .collect();
+        match items.as_slice() {
+            [] => {
+                let corpse = self.world.query::<(&Position, &Corpse)>().iter().any(|(_, (pos, c))| near(pos) && !c.searched);
+                if corpse {
+                    actions.push(ContextAction::SearchCorpse);
+                }
+            }
+            [name] => actions.push(ContextAction::PickUp(name.clone())),
+            _ => actions.push(ContextAction::PickUpPile(items.len())),
+        }
+
+        // Locked chests are listed under picking the lock instead; anything
+        // else looks the same from outside, traps and teeth included
+        let chest = self.world.query::<(&Position, &Chest)>()
+            .iter()
+            .any(|(_, (pos, c))| near(pos) && !c.opened && !(c.kind == ChestKind::Locked && c.secured));
+        if chest {
+            actions.push(ContextAction::OpenChest);
+        }
+        if let Some((_, (_, npc))) = self.world.query::<(&Position, &NpcComponent)>().iter().find(|(_, (pos, _))| near(pos)) {
+            actions.push(ContextAction::Talk(npc.npc_type.name().to_string()));
+        }
+
+        if !self.adjacent_tiles(player_pos, |t| t == TileType::DoorOpen).is_empty() {
+            actions.push(ContextAction::CloseDoor);
+        }
+        if !self.adjacent_tiles(player_pos, |t| t.is_shut_door()).is_empty() {
+            actions.push(ContextAction::PeekDoor);
+        }
+        let locked_chest = (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| Position::new(player_pos.x + dx, player_pos.y + dy)))
+            .any(|pos| self.locked_chest_at(pos).is_some());
+        if locked_chest || !self.adjacent_tiles(player_pos, |t| t == TileType::DoorLocked).is_empty() {
+            actions.push(ContextAction::PickLock);
+        }
+
+        let bleeding = self.player_entity
+            .and_then(|p| self.world.get::<&StatusEffects>(p).ok())
+            .is_some_and(|e| e.has_effect(StatusEffectType::Bleed));
+        if bleeding {
+            actions.push(ContextAction::BindWounds);
+        }
+        let engaged = self.world.query::<(&Position, &Enemy, &Health)>()
+            .iter()
+            .any(|(_, (pos, _, hp))| near(pos) && !hp.is_dead());
+        if engaged {
+            actions.push(ContextAction::Disengage);
+        }
+        actions
+    }
+
+    // ========================================================================
+    // Decals
+    // ========================================================================
+
+    /// Mark the floor at a position. Blood spatters onto a few tiles around.
+    pub fn leave_decal(&mut self, pos: Position, decal: crate::world::Decal) {
+        let spread = if decal == crate::world::Decal::Blood { 2 } else { 0 };
+        if let Some(map) = self.map.as_mut() {
+            crate::world::decals::splatter(map, pos, decal, spread, &mut self.rng);
+        }
+    }
+
+    // ========================================================================
+    // Interaction
+    // ========================================================================
+
+    /// What the player could use at a position: an entity's interactable
+    /// first, then whatever the tile offers
+    pub fn interactable_at(&self, pos: Position) -> Option<(Option<Entity>, super::Interactable)> {
+        use crate::ecs::Chest;
+
+        let entity = self.world.query::<(&Position, &super::Interactable)>()
+            .iter()
+            .filter(|(entity, (p, _))| **p == pos && Some(*entity) != self.player_entity)
+            .find(|(entity, _)| !self.world.get::<&Chest>(*entity).is_ok_and(|c| c.opened))
+            .map(|(entity, (_, interactable))| (Some(entity), *interactable));
+        entity.or_else(|| {
+            let tile = self.map.as_ref()?.get_tile(pos.x, pos.y)?.tile_type;
+            super::Interactable::of_tile(tile).map(|interactable| (None, interactable))
+        })
+    }
+
+    /// Use whatever is at a position
+    pub fn interact(&mut self, pos: Position) -> super::Interaction {
+        use super::{Interactable, Interaction};
+
+        let Some((entity, interactable)) = self.interactable_at(pos) else {
+            self.add_message("Nothing to interact with here.".to_string(), MessageCategory::System);
+            return Interaction::Nothing;
+        };
+        match (interactable, entity) {
+            (Interactable::Shrine(shrine_type), _) => self.approach_shrine(pos, shrine_type),
+            (Interactable::StairsDown, _) => {
+                self.play_sound(SoundId::Descend);
+                self.add_message("You descend deeper into the darkness...".to_string(), MessageCategory::System);
+                self.descend();
+                self.play_sound(SoundId::NewFloor);
+                Interaction::Descended
+            }
+            (Interactable::Door, _) => {
+                self.open_door(pos);
+                Interaction::Acted
+            }
+            (Interactable::Lever, _) => {
+                if self.pull_lever(pos) { Interaction::Acted } else { Interaction::Nothing }
+            }
+            (Interactable::Chest, Some(chest)) => Interaction::Chest(chest, self.reach_for_chest(chest)),
+            (Interactable::Npc, Some(npc)) => self.talk_to(npc),
+            (Interactable::Chest | Interactable::Npc, None) => Interaction::Nothing,
+        }
+    }
+
+    /// Step up to a shrine or altar: rest at it, or open what it offers
+    fn approach_shrine(&mut self, pos: Position, shrine_type: ShrineType) -> super::Interaction {
+        use super::Interaction;
+
+        // Corruption, gambling and the gods can be called on again and again
+        let reusable = matches!(shrine_type, ShrineType::Corruption | ShrineType::Gambling | ShrineType::Altar(_));
+        if !reusable && self.is_shrine_used(pos) {
+            self.add_message("This shrine's power has already been used.".to_string(), MessageCategory::Warning);
+            return Interaction::Nothing;
+        }
+
+        let (greeting, category) = match shrine_type {
+            ShrineType::Rest => {
+                self.rest_at_shrine(pos);
+                return Interaction::Free;
+            }
+            ShrineType::Skill => ("You approach the Skill Shrine. It pulses with arcane energy.".to_string(), MessageCategory::System),
+            ShrineType::Enchanting => ("You approach the Enchanting Shrine. Select equipment to enchant.".to_string(), MessageCategory::System),
+            ShrineType::Corruption => ("You approach the Corruption Shrine. Dark power calls to you...".to_string(), MessageCategory::Combat),
+            ShrineType::Gambling => ("You approach the Gambling Shrine. Coins glitter in its maw...".to_string(), MessageCategory::System),
+            ShrineType::Sacrifice => ("You approach the Sacrifice Shrine. It hungers for something you hold dear.".to_string(), MessageCategory::System),
+            ShrineType::Transmutation => ("You approach the Transmutation Shrine. Its vapors twist all they touch.".to_string(), MessageCategory::System),
+            ShrineType::Altar(deity) => (format!("You stand before the altar of {} {}.", deity.name(), deity.title()), MessageCategory::Lore),
+        };
+        self.play_sound(SoundId::ShrineApproach);
+        self.add_message(greeting, category);
+        self.set_state(GameState::Playing(PlayingState::Shrine { shrine_type }));
+        Interaction::Opened(PlayingState::Shrine { shrine_type })
+    }
+
+    /// Rest at a shrine: wounds heal, and mana, stamina, skill charges and
+    /// wands all come back
+    fn rest_at_shrine(&mut self, pos: Position) {
+        use crate::ecs::{InventoryComponent, SkillsComponent};
+
+        self.play_sound(SoundId::ShrineUse);
+        self.add_message("You rest at the shrine. Your wounds heal and your abilities are restored.".to_string(), MessageCategory::System);
+        if let Some(player) = self.player_entity {
+            // Heal and restore mana to their effective max
+            let (max_hp, max_mp) = self.player_derived_stats()
+                .map(|d| (d.max_hp, d.max_mp))
+                .unwrap_or((0, 0));
+            self.heal_player(max_hp);
+            if let Ok(mut mp) = self.world.get::<&mut Mana>(player) {
+                mp.current = max_mp;
+            }
+            if let Ok(mut sp) = self.world.get::<&mut Stamina>(player) {
+                sp.current = sp.max;
+            }
+            if let Ok(mut skills) = self.world.get::<&mut SkillsComponent>(player) {
+                skills.skills.restore_charges();
+            }
+            if let Ok(mut inv) = self.world.get::<&mut InventoryComponent>(player) {
+                inv.inventory.recharge_wands();
+            }
+        }
+        self.mark_shrine_used(pos);
+    }
+
+    /// Talk to an NPC: open their shop or their dice, take their healing,
+    /// or just hear them out
+    fn talk_to(&mut self, npc: Entity) -> super::Interaction {
+        use super::Interaction;
+        use crate::entities::{NpcComponent, NpcType};
+
+        let Ok(npc_type) = self.world.get::<&NpcComponent>(npc).map(|n| n.npc_type) else {
+            return Interaction::Nothing;
+        };
+        if self.npc_refuses(npc_type) {
+            return Interaction::Nothing;
+        }
+        let greeting = format!("{}: \"{}\"", npc_type.name(), npc_type.greeting());
+        match npc_type {
+            NpcType::Merchant | NpcType::Peddler | NpcType::Jeweler => {
+                self.add_message(greeting, MessageCategory::System);
+                let state = PlayingState::Shop { npc_entity: npc };
+                self.set_state(GameState::Playing(state.clone()));
+                Interaction::Opened(state)
+            }
+            NpcType::Gambler => {
+                self.add_message(greeting, MessageCategory::System);
+                let state = PlayingState::Dice { npc_entity: npc };
+                self.set_state(GameState::Playing(state.clone()));
+                Interaction::Opened(state)
+            }
+            NpcType::Healer => {
+                // Heal the player, as well as the Order thinks they deserve
+                let healing = self.standing_with(npc_type).healing(50);
+                self.heal_player(healing);
+                self.add_message(format!("{} (Healed {} HP)", greeting, healing), MessageCategory::System);
+                if self.treat_injuries() > 0 {
+                    self.add_message(
+                        "The healer sets your bones and binds your head. Your injuries are treated.",
+                        MessageCategory::System,
+                    );
+                }
+                Interaction::Free
+            }
+            _ => {
+                self.add_message(greeting, MessageCategory::Lore);
+                Interaction::Free
+            }
+        }
+    }
+
+    /// Reach for a chest, opening it if nothing stands in the way
+    pub fn reach_for_chest(&mut self, chest: Entity) -> crate::entities::ChestApproach {
+        let approach = self.approach_chest(chest);
+        if approach == crate::entities::ChestApproach::Open {
+            self.open_chest(chest);
+        }
+        approach
+    }
+
+    /// Open a chest, scaling the loot by what it took to get into
+    pub fn open_chest(&mut self, chest: Entity) {
+        use crate::ecs::{Chest, InventoryComponent, GroundItem, Renderable};
+        use crate::entities::{mark_chest_opened, generate_chest_loot};
+
+        let (Ok(chest_pos), Ok((rarity, multiplier))) = (
+            self.world.get::<&Position>(chest).map(|p| *p),
+            self.world.get::<&Chest>(chest).map(|c| (c.rarity, c.kind.loot_multiplier())),
+        ) else {
+            return;
+        };
+
+        self.play_sound(SoundId::ChestOpen);
+        let Some(player) = self.player_entity else { return };
+
+        // Generate loot based on chest rarity
+        let floor = self.loot_floor();
+        let drops = self.data.economy().gold_drop;
+        let (items, gold) = generate_chest_loot(rarity, floor, multiplier, drops, &mut self.rng);
+
+        if gold > 0 {
+            self.play_sound(SoundId::GoldPickup);
+            if let Ok(mut inv) = self.world.get::<&mut InventoryComponent>(player) {
+                inv.inventory.add_gold(gold);
+            }
+            self.add_message(format!("Found {} gold in {:?} chest!", gold, rarity), MessageCategory::Item);
+            self.record_gold_collected(gold);
+        }
+
+        // Spill the items on the ground where the chest stands
+        for item in items {
+            let item_name = self.discoveries().disguise(&item).name.clone();
+            let item_rarity = item.rarity;
+            self.world.spawn((
+                chest_pos,
+                GroundItem { item: item.clone() },
+                Renderable::new(item.glyph, item_rarity.color()).with_order(80),
+            ));
+            self.announce_loot(format!("Found: {} [{}]", item_name, item_rarity.name()), &item);
+        }
+
+        mark_chest_opened(&mut self.world, chest);
+        self.add_message(format!("Opened a {:?} chest!", rarity), MessageCategory::System);
+    }
+
+    // ========================================================================
+    // Deaths
+    // ========================================================================
+
+    /// Queue an enemy's death to be resolved when the dead are next reaped
+    pub fn queue_death(&mut self, entity: Entity, gibbed: bool, by_player: bool) {
+        self.deaths.push(super::Death { entity, gibbed, by_player });
+    }
+
+    /// Resolve every death queued since the last reaping, and those of any
+    /// enemies found dead without one: spoils, XP, death blasts, corpses and
+    /// kill records. Stops the turn if a death took the player with it.
+    pub fn reap_dead(&mut self) -> Flow {
+        use crate::ecs::Enemy;
+
+        let unqueued: Vec<Entity> = self.world.query::<(&Enemy, &Health)>()
+            .iter()
+            .filter(|(_, (_, health))| health.is_dead())
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in unqueued {
+            self.queue_death(entity, false, false);
+        }
+
+        for death in self.deaths.drain() {
+            if !self.world.contains(death.entity) {
+                continue;
+            }
+            let is_boss = self.world.get::<&crate::entities::BossComponent>(death.entity).is_ok();
+            if death.by_player {
+                self.drop_spoils(death.entity);
+            }
+            let xp = self.world.get::<&crate::ecs::XpReward>(death.entity)
+                .map(|xp| xp.0)
+                .unwrap_or(15);
+            self.leave_corpse(death.entity, death.gibbed);
+            let _ = self.world.despawn(death.entity);
+            self.record_enemy_kill(is_boss);
+            if death.by_player {
+                self.grant_xp(xp);
+            }
+        }
+
+        if matches!(self.state, GameState::Playing(_)) { Flow::Continue } else { Flow::Stop }
+    }
+
+    /// Drop what an enemy the player slew was carrying, and the gold it had
+    fn drop_spoils(&mut self, entity: Entity) {
+        use crate::ecs::{GroundItem, InventoryComponent, Name, Renderable};
+        use crate::entities::{BossComponent, MimicHoard, PeddlerHoard, Stalker};
+        use crate::items::{generate_enemy_loot, generate_boss_loot};
+
+        let Ok(pos) = self.world.get::<&Position>(entity).map(|p| *p) else { return };
+        let name = self.world.get::<&Name>(entity)
+            .map(|n| n.0.clone())
+            .unwrap_or_else(|_| "something".to_string());
+        let is_boss = self.world.get::<&BossComponent>(entity).is_ok();
+        let is_stalker = self.world.get::<&Stalker>(entity).is_ok();
+
+        // A mimic coughs up the chest it was pretending to be
+        let floor = self.loot_floor();
+        let drops = self.data.economy().gold_drop;
+        let hoard = self.world.get::<&MimicHoard>(entity)
+            .map(|h| h.rarity)
+            .ok()
+            .map(|rarity| {
+                let multiplier = crate::ecs::ChestKind::Mimic.loot_multiplier();
+                crate::entities::generate_chest_loot(rarity, floor, multiplier, drops, &mut self.rng)
+            });
+
+        // A robbed peddler's pack and purse spill out
+        let pack = self.world.get::<&PeddlerHoard>(entity)
+            .ok()
+            .map(|h| (h.items.clone(), h.gold));
+
+        // Bosses get better loot, and the Warden's comes from deeper down
+        let loot = if let Some((items, _)) = &hoard {
+            self.add_message(format!("The {} spits out its hoard!", name), MessageCategory::Item);
+            items.clone()
+        } else if let Some((items, _)) = &pack {
+            self.add_message("The peddler's pack spills open across the floor.".to_string(), MessageCategory::Item);
+            self.shift_reputation(super::NpcFaction::MerchantsGuild, -super::KILL_REPUTATION);
+            items.clone()
+        } else if is_stalker {
+            self.add_message("★ The Warden falls, and its hoard spills out! ★".to_string(), MessageCategory::Item);
+            generate_boss_loot(floor + crate::entities::STALKER_LOOT_DEPTH, &mut self.rng)
+        } else if is_boss {
+            self.add_message("★ The boss drops powerful loot! ★".to_string(), MessageCategory::Item);
+            generate_boss_loot(floor, &mut self.rng)
+        } else {
+            generate_enemy_loot(floor, &mut self.rng)
+        };
+
+        for item in loot {
+            self.announce_loot(format!("The {} dropped: {} [{}]", name, item.name, item.rarity.name()), &item);
+            self.world.spawn((
+                pos,
+                Renderable::new(item.glyph, item.rarity.color()).with_order(10),
+                GroundItem { item },
+            ));
+        }
+
+        // Bosses drop more gold, straight into the player's purse
+        let gold = if let Some((_, gold)) = hoard.or(pack) {
+            gold
+        } else if is_boss || is_stalker {
+            self.data.economy().boss_gold_drop.roll(floor, &mut self.rng)
+        } else {
+            self.data.economy().gold_drop.roll(floor, &mut self.rng)
+        };
+        if gold > 0 {
+            let added = self.player_entity
+                .and_then(|player| self.world.get::<&mut InventoryComponent>(player).ok())
+                .map(|mut inv| inv.inventory.add_gold(gold))
+                .is_some();
+            if added {
+                self.add_message(format!("You found {} gold!", gold), MessageCategory::Item);
+                self.record_gold_collected(gold);
+            }
+        }
+    }
+
+    /// Grant the player XP, with a stat point for each level gained
+    fn grant_xp(&mut self, xp: u32) {
+        use crate::ecs::StatPoints;
+
+        self.add_message(format!("+{} XP", xp), MessageCategory::System);
+        let Some(player) = self.player_entity else { return };
+        let leveled_up = self.world.get::<&mut Experience>(player)
+            .ok()
+            .and_then(|mut experience| experience.add_xp(xp).then_some(experience.level));
+        if let Some(level) = leveled_up {
+            self.play_sound(SoundId::LevelUp);
+            if let Ok(mut points) = self.world.get::<&mut StatPoints>(player) {
+                points.0 += 1;
+            }
+            self.add_message(format!("LEVEL UP! You are now level {}! (+1 stat point)", level), MessageCategory::System);
+        }
+    }
+
+    // ========================================================================
+    // Corpses
+    // ========================================================================
+
+    /// Leave the body of a slain enemy where it fell; call before despawning
+    /// it. Bosses, the Warden and the risen leave nothing behind.
+    pub fn leave_corpse(&mut self, entity: Entity, gibbed: bool) {
+        use crate::entities::{BossComponent, Risen, Stalker};
+
+        let leaves_nothing = self.world.get::<&BossComponent>(entity).is_ok()
+            || self.world.get::<&Stalker>(entity).is_ok()
+            || self.world.get::<&Risen>(entity).is_ok();
+        let Ok(pos) = self.world.get::<&Position>(entity).map(|p| *p) else { return };
+        if leaves_nothing {
+            return;
+        }
+        let name = self.world.get::<&crate::ecs::Name>(entity)
+            .map(|n| n.0.clone())
+            .unwrap_or_else(|_| "something".to_string());
+        // Under the Volatile sigil the dead go up in a blast
+        let blast = self.sigils.death_blast(self.floor)
+            .filter(|_| self.world.get::<&crate::ecs::Enemy>(entity).is_ok());
+        if let Some(damage) = blast {
+            self.add_message(format!("The {} explodes!", name), MessageCategory::Combat);
+            self.shake = self.shake.max(super::SHAKE_SECONDS);
+            if self.player_position().is_some_and(|p| p.chebyshev_distance(&pos) <= 1) {
+                self.add_message(format!("You're caught in the blast! (-{} HP)", damage), MessageCategory::Combat);
+                if self.damage_player(damage) && !self.cheat_death() {
+                    self.player_died("caught in a dying enemy's blast");
+                }
+            }
+        } else if gibbed {
+            self.add_message(format!("The {} is blown apart!", name), MessageCategory::Combat);
+        }
+        let gibbed = gibbed || blast.is_some();
+        if gibbed {
+            if let Some(map) = self.map.as_mut() {
+                crate::world::decals::splatter(map, pos, crate::world::Decal::Blood, 8, &mut self.rng);
+            }
+        }
+        crate::entities::spawn_corpse(&mut self.world, pos, &name, gibbed);
+    }
+
+    /// Search the unsearched corpses next to the player, dropping what turns
+    /// up on the ground. Returns whether there was anything to search.
+    pub fn search_nearby_corpses(&mut self) -> bool {
+        use crate::ecs::{GroundItem, InventoryComponent, Renderable};
+        use crate::entities::Corpse;
+
+        let Some(player_pos) = self.player_position() else { return false };
+        let corpses: Vec<(Entity, Position, String)> = self.world.query::<(&Position, &Corpse)>()
+            .iter()
+            .filter(|(_, (pos, corpse))| !corpse.searched && pos.chebyshev_distance(&player_pos) <= 1)
+            .map(|(e, (pos, corpse))| (e, *pos, corpse.name.clone()))
+            .collect();
+
+        for (entity, pos, name) in &corpses {
+            if let Ok(mut corpse) = self.world.get::<&mut Corpse>(*entity) {
+                corpse.searched = true;
+            }
+            let (item, gold) = crate::entities::search_corpse_loot(self.floor, self.data.economy().gold_drop, &mut self.rng);
+            if gold > 0 {
+                if let Some(player) = self.player_entity {
+                    if let Ok(mut inv) = self.world.get::<&mut InventoryComponent>(player) {
+                        inv.inventory.add_gold(gold);
+                    }
+                }
+                self.record_gold_collected(gold);
+            }
+            match (&item, gold) {
+                (None, 0) => self.add_message(format!("You search the {} corpse. Nothing of use.", name), MessageCategory::Item),
+                (None, _) => self.add_message(format!("You search the {} corpse and find {} gold.", name, gold), MessageCategory::Item),
+                (Some(_), _) => self.add_message(format!("You search the {} corpse and find something.", name), MessageCategory::Item),
+            }
+            if let Some(item) = item {
+                self.world.spawn((
+                    *pos,
+                    Renderable::new(item.glyph, item.rarity.color()).with_order(80),
+                    GroundItem { item },
+                ));
+            }
+        }
+        !corpses.is_empty()
+    }
+
+    /// Engaged necromancers raise the nearest corpse when their power returns
+    fn necromancers_raise_dead(&mut self) {
+        use crate::ecs::{AI, AIState, Name};
+        use crate::entities::{Necromancer, nearest_corpse, raise_corpse, RAISE_COOLDOWN, RAISE_RANGE};
+
+        let mut ready = Vec::new();
+        for (entity, (necromancer, ai, pos)) in self.world.query_mut::<(&mut Necromancer, &AI, &Position)>() {
+            if ai.state != AIState::Chase {
+                continue;
+            }
+            necromancer.cooldown = necromancer.cooldown.saturating_sub(1);
+            if necromancer.cooldown == 0 {
+                ready.push((entity, *pos));
+            }
+        }
+
+        let scaling = self.floor_scaling();
+        for (entity, pos) in ready {
+            let Some(corpse) = nearest_corpse(&self.world, pos, RAISE_RANGE) else { continue };
+            let Some(risen) = raise_corpse(&mut self.world, corpse, &scaling) else { continue };
+            if let Ok(mut necromancer) = self.world.get::<&mut Necromancer>(entity) {
+                necromancer.cooldown = RAISE_COOLDOWN;
+            }
+            let seen = self.map.as_ref()
+                .and_then(|m| m.get_tile(pos.x, pos.y))
+                .is_some_and(|t| t.visible);
+            if seen {
+                let name = self.world.get::<&Name>(risen).map(|n| n.0.clone()).unwrap_or_default();
+                self.add_message(format!("The Necromancer chants, and the {} claws its way up!", name), MessageCategory::Warning);
+            }
+        }
+    }
+
+    // ========================================================================
+    // Auras
+    // ========================================================================
+
+    /// Note a melee blow that landed, for thorns to answer
+    pub fn record_hit(&mut self, attacker: Entity, target: Entity, damage: i32) {
+        self.hits.push(crate::combat::Hit { attacker, target, damage });
+    }
+
+    /// Grant an entity an aura for a number of turns
+    pub fn grant_aura(&mut self, entity: Entity, aura: crate::combat::Aura, turns: u32) {
+        use crate::combat::Auras;
+
+        if let Ok(mut auras) = self.world.get::<&mut Auras>(entity) {
+            auras.grant(aura, turns);
+            return;
+        }
+        let mut auras = Auras::default();
+        auras.grant(aura, turns);
+        let _ = self.world.insert_one(entity, auras);
+    }
+
+    /// Run the aura system for the turn: regeneration, fear and thorns
+    /// against the blows landed since last time
+    fn resolve_auras(&mut self) {
+        use crate::ecs::{Enemy, Name};
+
+        let hits = std::mem::take(&mut self.hits);
+        let outcome = crate::combat::run_auras(&mut self.world, &hits);
+        let name_of = |world: &World, entity| world.get::<&Name>(entity)
+            .map(|n| n.0.clone())
+            .unwrap_or_else(|_| "creature".to_string());
+
+        for (attacker, holder, damage) in outcome.thorns {
+            if Some(holder) == self.player_entity {
+                let name = name_of(&self.world, attacker);
+                self.add_message(format!("Your thorns cut the {} for {} damage.", name, damage), MessageCategory::Combat);
+            } else if Some(attacker) == self.player_entity {
+                let name = name_of(&self.world, holder);
+                self.add_message(format!("The {}'s thorns cut you for {} damage.", name, damage), MessageCategory::Combat);
+            }
+
+            let slain = self.world.get::<&Enemy>(attacker).is_ok()
+                && self.world.get::<&Health>(attacker).is_ok_and(|h| h.is_dead());
+            if slain {
+                let name = name_of(&self.world, attacker);
+                self.add_message(format!("The {} is torn apart by thorns!", name), MessageCategory::Combat);
+                self.queue_death(attacker, false, false);
+            }
+        }
+
+        let seen = |world: &World, map: Option<&Map>, entity| world.get::<&Position>(entity)
+            .is_ok_and(|pos| map.and_then(|m| m.get_tile(pos.x, pos.y)).is_some_and(|t| t.visible));
+        for enemy in outcome.shaken {
+            if seen(&self.world, self.map.as_ref(), enemy) {
+                let name = name_of(&self.world, enemy);
+                self.add_message(format!("The {} falters, its nerve shaken.", name), MessageCategory::Combat);
+            }
+        }
+    }
+
+    // ========================================================================
+    // Mutations
+    // ========================================================================
+
+    /// Mutate the player's body, returning the new mutation (None if fully mutated)
+    pub fn grant_mutation(&mut self) -> Option<crate::progression::Mutation> {
+        use crate::progression::Mutations;
+        use crate::ecs::{EquipmentComponent, InventoryComponent, GroundItem, Renderable};
+
+        let player = self.player_entity?;
+        let mutation = self.world.get::<&Mutations>(player).ok()
+            .and_then(|m| m.roll(&mut self.rng));
+        let Some(mutation) = mutation else {
+            self.add_message("Your body can change no further.", MessageCategory::System);
+            return None;
+        };
+
+        if let Ok(mut mutations) = self.world.get::<&mut Mutations>(player) {
+            mutations.mutations.push(mutation);
+        }
+
+        // Attribute changes follow the usual per-point HP/MP rules
+        let (str_d, dex_d, int_d, vit_d) = mutation.stat_changes();
+        if let Ok(mut stats) = self.world.get::<&mut Stats>(player) {
+            stats.strength += str_d;
+            stats.dexterity += dex_d;
+            stats.intelligence += int_d;
+            stats.vitality += vit_d;
+        }
+        if vit_d != 0 {
+            if let Ok(mut health) = self.world.get::<&mut Health>(player) {
+                health.max += vit_d * 5;
+                health.current = health.current.min(health.max).max(1);
+            }
+        }
+        if int_d != 0 {
+            if let Ok(mut mana) = self.world.get::<&mut Mana>(player) {
+                mana.max += int_d * 3;
+                mana.current = mana.current.min(mana.max);
+            }
+        }
+
+        self.play_sound(SoundId::PlayerHurt);
+        self.add_message(format!("MUTATION: {}", mutation.onset()), MessageCategory::Warning);
+        self.add_message(
+            format!("{}: {} / {}", mutation.name(), mutation.benefit(), mutation.drawback()),
+            MessageCategory::System,
+        );
+
+        // The mutated body sheds whatever no longer fits
+        let shed = mutation.blocked_slot().and_then(|slot| {
+            self.world.get::<&mut EquipmentComponent>(player).ok()
+                .and_then(|mut eq| eq.equipment.unequip(slot))
+        });
+        if let Some(item) = shed {
+            self.add_message(format!("Your {} no longer fits!", item.name), MessageCategory::Item);
+            let stored = self.world.get::<&mut InventoryComponent>(player)
+                .is_ok_and(|mut inv| inv.inventory.can_fit(&item) && inv.inventory.add_item(item.clone()));
+            if !stored {
+                if let Some(pos) = self.player_position() {
+                    self.world.spawn((
+                        pos,
+                        Renderable::new(item.glyph, item.rarity.color()).with_order(10),
+                        GroundItem { item },
+                    ));
+                }
+            }
+        }
+
+        Some(mutation)
+    }
+
+    // ========================================================================
+    // Injuries
+    // ========================================================================
+
+    /// Injure the player, returning the injury (None if already suffering all of them)
+    pub fn grant_injury(&mut self) -> Option<crate::progression::Injury> {
+        use crate::progression::Injuries;
+
+        let player = self.player_entity?;
+        let injury = self.world.get::<&Injuries>(player).ok()
+            .and_then(|i| i.roll(&mut self.rng))?;
+        if let Ok(mut injuries) = self.world.get::<&mut Injuries>(player) {
+            injuries.injuries.push(injury);
+        }
+        self.apply_injury_stats(injury, 1);
+
+        self.add_message(format!("INJURY: {}", injury.onset()), MessageCategory::Warning);
+        self.add_message(
+            format!("{}: {} until treated.", injury.name(), injury.effect()),
+            MessageCategory::System,
+        );
+        Some(injury)
+    }
+
+    /// Heal every injury the player carries, returning how many were treated
+    pub fn treat_injuries(&mut self) -> usize {
+        let Some(player) = self.player_entity else { return 0 };
+        let treated = self.world.get::<&mut crate::progression::Injuries>(player)
+            .map(|mut i| std::mem::take(&mut i.injuries))
+            .unwrap_or_default();
+        for injury in &treated {
+            self.apply_injury_stats(*injury, -1);
+        }
+        treated.len()
+    }
+
+    /// Apply (sign 1) or undo (sign -1) an injury's attribute changes
+    fn apply_injury_stats(&mut self, injury: crate::progression::Injury, sign: i32) {
+        let Some(player) = self.player_entity else { return };
+        let (str_d, dex_d, int_d, vit_d) = injury.stat_changes();
+        if let Ok(mut stats) = self.world.get::<&mut Stats>(player) {
+            stats.strength += str_d * sign;
+            stats.dexterity += dex_d * sign;
+            stats.intelligence += int_d * sign;
+            stats.vitality += vit_d * sign;
+        }
+    }
+
+    /// Chance a skill fizzles from the player's injuries
+    pub fn skill_fizzle_chance(&self) -> f64 {
+        self.player_entity
+            .and_then(|p| self.world.get::<&crate::progression::Injuries>(p).ok())
+            .map(|i| i.fizzle_chance())
+            .unwrap_or(0.0)
+    }
+
+    /// Mutation preventing the player from using an equipment slot
+    pub fn mutation_blocking(&self, slot: crate::items::EquipSlot) -> Option<crate::progression::Mutation> {
+        self.player_entity
+            .and_then(|p| self.world.get::<&crate::progression::Mutations>(p).ok())
+            .and_then(|m| m.blocking(slot))
+    }
+
+    // ========================================================================
+    // Worship
+    // ========================================================================
+
+    /// Standing with the gods
+    pub fn worship(&self) -> &super::Worship {
+        &self.worship
+    }
+
+    /// Dedicate the player to a god, angering the previous patron
+    pub fn dedicate_to(&mut self, deity: super::Deity) {
+        if self.worship.patron == Some(deity) {
+            self.add_message(format!("You already serve {}.", deity.name()), MessageCategory::System);
+            return;
+        }
+
+        let abandoned = self.worship.dedicate(deity);
+        self.play_sound(SoundId::ShrineUse);
+        self.add_message(
+            format!("You kneel before {} {}. Your deeds are now theirs.", deity.name(), deity.title()),
+            MessageCategory::Lore,
+        );
+        if let Some(old) = abandoned {
+            self.add_message(format!("{} will not forget your betrayal.", old.name()), MessageCategory::Warning);
+        }
+        // Favor already earned may grant boons immediately
+        for boon in self.worship.active_boons() {
+            self.add_message(
+                format!("{} grants you {}: {}.", deity.name(), boon.name(), boon.description()),
+                MessageCategory::Lore,
+            );
+        }
+        self.punish_wrathful_gods();
+    }
+
+    /// Gold given in one offering at an altar
+    pub fn offering_cost(&self) -> u32 {
+        self.data.gold_sinks().offering.at(self.floor)
+    }
+
+    /// Offer gold at an altar, returning false if the player can't afford it
+    pub fn offer_gold(&mut self, deity: super::Deity) -> bool {
+        let cost = self.offering_cost();
+        if !self.spend_gold(cost) {
+            return false;
+        }
+
+        self.play_sound(SoundId::GoldPickup);
+        self.add_message(format!("You offer {} gold to {}.", cost, deity.name()), MessageCategory::System);
+        self.gain_favor(deity, deity.gold_favor(cost));
+        true
+    }
+
+    /// Sacrifice an inventory item at an altar
+    pub fn offer_item(&mut self, deity: super::Deity, index: usize) -> bool {
+        use crate::ecs::InventoryComponent;
+
+        let item = self.player_entity
+            .and_then(|p| self.world.get::<&mut InventoryComponent>(p).ok())
+            .and_then(|mut inv| inv.inventory.remove_at(index));
+        let Some(item) = item else { return false };
+
+        self.play_sound(SoundId::ShrineUse);
+        self.add_message(
+            format!("{} is consumed by {}'s altar.", item.display_name(), deity.name()),
+            MessageCategory::Item,
+        );
+        self.gain_favor(deity, deity.item_favor(&item));
+        true
+    }
+
+    /// Smash an altar for its gold, enraging its god
+    pub fn desecrate_altar(&mut self, deity: super::Deity, pos: Position) {
+        use crate::ecs::InventoryComponent;
+        use crate::world::TileType;
+
+        let reward = super::desecrate_reward(self.floor);
+        if let Some(mut inv) = self.player_entity
+            .and_then(|p| self.world.get::<&mut InventoryComponent>(p).ok())
+        {
+            inv.inventory.add_gold(reward);
+        }
+        self.record_gold_collected(reward);
+        if let Some(map) = &mut self.map {
+            map.set_tile(pos.x, pos.y, TileType::Rubble);
+        }
+
+        self.add_message(
+            format!("You smash the altar of {} and pry {} gold from the rubble.", deity.name(), reward),
+            MessageCategory::Warning,
+        );
+        self.worship.add_favor(deity, -super::deities::DESECRATE_PENALTY);
+        self.punish_wrathful_gods();
+    }
+
+    /// Change favor with a god and announce any boons it unlocks
+    fn gain_favor(&mut self, deity: super::Deity, amount: i32) {
+        let could_intervene = self.worship.can_intervene();
+        for boon in self.worship.add_favor(deity, amount) {
+            self.add_message(
+                format!("{} grants you {}: {}.", deity.name(), boon.name(), boon.description()),
+                MessageCategory::Lore,
+            );
+        }
+        if !could_intervene && self.worship.can_intervene() {
+            self.add_message(
+                format!("{} will save you from death, once.", deity.name()),
+                MessageCategory::Lore,
+            );
+        }
+    }
+
+    /// Credit a kill to the patron
+    fn dedicate_kill(&mut self, is_boss: bool) {
+        use super::Boon;
+
+        let Some(patron) = self.worship.patron else { return };
+        self.gain_favor(patron, patron.kill_favor(is_boss));
+
+        if self.worship.has_boon(Boon::Bloodthirst) {
+            self.heal_player(3 + self.floor as i32 / 2);
+        }
+        if self.worship.has_boon(Boon::Carnage) {
+            self.restore_stamina(10);
+            self.restore_mana(5);
+        }
+    }
+
+    /// Apply the patron's blessings on arriving at a new floor
+    fn apply_descent_boons(&mut self) {
+        use super::Boon;
+
+        let Some(patron) = self.worship.patron else { return };
+        self.gain_favor(patron, patron.descent_favor());
+
+        if self.worship.has_boon(Boon::DeepSight) {
+            if let Some(map) = &mut self.map {
+                if let Some(exit) = map.exit_pos {
+                    for y in exit.y - 2..=exit.y + 2 {
+                        for x in exit.x - 2..=exit.x + 2 {
+                            map.mark_explored(x, y);
+                        }
+                    }
+                    self.add_message(format!("{} shows you the way down.", patron.name()), MessageCategory::Lore);
+                }
+            }
+        }
+        if self.worship.has_boon(Boon::TidalRest) {
+            let (max_hp, max_mp) = self.player_derived_stats()
+                .map(|d| (d.max_hp, d.max_mp))
+                .unwrap_or((0, 0));
+            self.heal_player(max_hp / 4);
+            self.restore_mana(max_mp);
+        }
+    }
+
+    /// Seconds of exploration this run, which drive the day and night cycle
+    pub fn ambient_time(&self) -> f32 {
+        self.ambient_time
+    }
+
+    /// Turns taken on the current floor
+    pub fn floor_turns(&self) -> u32 {
+        self.floor_turns
+    }
+
+    /// Turns and time over the run
+    pub fn run_clock(&self) -> &super::RunClock {
+        &self.run_clock
+    }
+
+    /// New Game Plus cycle, 0 on a first run
+    pub fn ng_plus(&self) -> u32 {
+        self.ng_plus
+    }
+
+    /// Whether it is night, when sleepers sleep
+    pub fn is_night(&self) -> bool {
+        super::is_night(self.ambient_time)
+    }
+
+    /// Whether death's door has already been spent on this floor
+    pub fn deaths_door_used(&self) -> bool {
+        self.deaths_door_used
+    }
+
+    /// Give the dying player one last chance: the patron god first, then death's door
+    fn cheat_death(&mut self) -> bool {
+        self.try_divine_intervention() || self.try_deaths_door()
+    }
+
+    /// Survive a killing blow at 1 HP, once per floor, at the cost of max HP
+    /// and a spell of weakness
+    fn try_deaths_door(&mut self) -> bool {
+        use crate::ecs::{StatusEffect, StatusEffectType, StatusEffects};
+
+        if self.deaths_door_used || !self.difficulty.has_deaths_door() {
+            return false;
+        }
+        let Some(player) = self.player_entity else { return false };
+        self.deaths_door_used = true;
+
+        let lost = self.world.get::<&mut Health>(player)
+            .map(|mut health| {
+                let lost = (health.max * DEATHS_DOOR_MAX_HP_COST / 100).max(1);
+                health.max = (health.max - lost).max(1);
+                health.current = 1;
+                lost
+            })
+            .unwrap_or(0);
+        if let Ok(mut effects) = self.world.get::<&mut StatusEffects>(player) {
+            effects.effects.push(StatusEffect {
+                effect_type: StatusEffectType::Weakness,
+                duration: DEATHS_DOOR_DEBUFF_TURNS,
+                intensity: 30,
+            });
+            effects.effects.push(StatusEffect {
+                effect_type: StatusEffectType::Slow,
+                duration: DEATHS_DOOR_DEBUFF_TURNS,
+                intensity: 30,
+            });
+        }
+
+        self.play_sound(SoundId::PlayerHurt);
+        self.add_message("DEATH'S DOOR: You refuse to fall, clinging to life by a thread!", MessageCategory::Warning);
+        self.add_message(
+            format!("The darkness takes its due. (-{} max HP, weakened and slowed)", lost),
+            MessageCategory::System,
+        );
+        true
+    }
+
+    /// Let the patron save the player from death, spending favor
+    fn try_divine_intervention(&mut self) -> bool {
+        let Some(patron) = self.worship.intervene() else { return false };
+        if let Some(player) = self.player_entity {
+            if let Ok(mut health) = self.world.get::<&mut Health>(player) {
+                health.current = (health.max / 2).max(1);
+            }
+        }
+        self.play_sound(SoundId::LevelUp);
+        self.add_message(
+            format!("{} {} refuses to let you die!", patron.name(), patron.title()),
+            MessageCategory::Lore,
+        );
+        true
+    }
+
+    /// Send punishers from every wrathful god after the player
+    fn punish_wrathful_gods(&mut self) {
+        for deity in self.worship.take_wrath() {
+            self.spawn_punishers(deity);
+        }
+    }
+
+    /// Open ground a few steps from the player for a squad to close in from,
+    /// in no particular order
+    fn squad_positions(&mut self) -> Vec<Position> {
+        use rand::seq::SliceRandom;
+
+        let (Some(player_pos), Some(map)) = (self.player_position(), &self.map) else {
+            return Vec::new();
+        };
+
+        let mut positions: Vec<Position> = Vec::new();
+        for y in player_pos.y - 6..=player_pos.y + 6 {
+            for x in player_pos.x - 6..=player_pos.x + 6 {
+                let pos = Position::new(x, y);
+                if map.is_walkable(x, y)
+                    && pos.chebyshev_distance(&player_pos) >= 3
+                    && !self.is_blocked_by_entity(pos)
+                {
+                    positions.push(pos);
+                }
+            }
+        }
+        positions.shuffle(&mut self.rng);
+        positions
+    }
+
+    /// Spawn a squad of elite enemies around the player on behalf of an angry god
+    fn spawn_punishers(&mut self, deity: super::Deity) {
+        use rand::seq::SliceRandom;
+        use crate::entities::{enemies_for_floor, spawn_enemy_scaled};
+        use crate::ecs::{AI, AIState, Renderable};
+
+        let positions = self.squad_positions();
+        let (Some(player_pos), Some(map)) = (self.player_position(), &self.map) else {
+            return;
+        };
+
+        let pool = enemies_for_floor(map.biome, self.floor);
+        let scaling = self.floor_scaling().elite();
+        let count = (2 + self.floor as usize / 5).min(5);
+        for pos in positions.into_iter().take(count) {
+            let Some(def) = pool.choose(&mut self.rng) else { break };
+            let enemy = spawn_enemy_scaled(&mut self.world, def, pos, &scaling);
+            let _ = self.world.insert(enemy, (
+                Renderable::new(def.glyph, deity.color()).with_order(50),
+                AI { state: AIState::Chase, target: Some(player_pos), home: pos },
+            ));
+        }
+
+        self.play_sound(SoundId::PlayerHurt);
+        self.add_message(
+            format!("The wrath of {} {} is upon you! Punishers close in!", deity.name(), deity.title()),
+            MessageCategory::Warning,
+        );
+    }
+
+    // ========================================================================
+    // Faction Reputation
+    // ========================================================================
+
+    pub fn reputation(&self) -> &super::Reputation {
+        &self.reputation
+    }
+
+    /// How an NPC's faction regards the player (NPCs of no faction deal
+    /// with anyone)
+    pub fn standing_with(&self, npc_type: crate::entities::NpcType) -> super::Standing {
+        super::NpcFaction::of(npc_type)
+            .map(|faction| self.reputation.standing(faction))
+            .unwrap_or(super::Standing::Neutral)
+    }
+
+    /// Whether an NPC turns the player away. Says so if they do.
+    pub fn npc_refuses(&mut self, npc_type: crate::entities::NpcType) -> bool {
+        if self.standing_with(npc_type).will_trade() {
+            return false;
+        }
+        self.add_message(
+            format!("The {} backs away from you: \"I'll have no dealings with the likes of you.\"", npc_type.name()),
+            MessageCategory::Warning,
+        );
+        true
+    }
+
+    /// Win or lose reputation with a faction. A faction pushed too far sends
+    /// hunters at once.
+    pub fn shift_reputation(&mut self, faction: super::NpcFaction, amount: i32) {
+        use super::Standing;
+
+        let Some(standing) = self.reputation.adjust(faction, amount) else { return };
+        let (msg, category) = match standing {
+            Standing::Hunted => (format!("The {} has put a price on your head!", faction.name()), MessageCategory::Warning),
+            Standing::Distrusted if amount < 0 => (format!("Word spreads. The {} no longer trusts you.", faction.name()), MessageCategory::Warning),
+            Standing::Honored => (format!("The {} honors you as a friend. Their traders will bring out their best.", faction.name()), MessageCategory::Lore),
+            _ => (format!("You are now {} with the {}.", standing.name(), faction.name()), MessageCategory::System),
+        };
+        self.add_message(msg, category);
+        if standing == Standing::Hunted {
+            self.spawn_hunters(faction);
+        }
+    }
+
+    /// Price, thin or add to the stock of freshly spawned NPCs by how their
+    /// factions regard the player and what the profile has unlocked
+    fn stock_by_standing(&mut self, npcs: &[Entity]) {
+        use crate::entities::{NpcComponent, ShopItem};
+        use crate::entities::npcs::add_shop_slot;
+        use super::{NpcFaction, Standing};
+
+        let deep_pockets = self.profile.has_run_unlock(crate::progression::RunUnlock::DeepPockets);
+        let honored: Vec<NpcFaction> = NpcFaction::ALL.into_iter()
+            .filter(|f| self.reputation.standing(*f) == Standing::Honored)
+            .collect();
+        for &npc in npcs {
+            let Ok(mut npc) = self.world.get::<&mut NpcComponent>(npc) else { continue };
+            if npc.shop_items.is_empty() {
+                continue;
             }
+            if deep_pockets {
+                add_shop_slot(&mut npc, &mut self.rng, self.floor, &mut self.item_id_counter);
+            }
+            let standing = self.standing_with(npc.npc_type);
+            standing.withhold_stock(&mut npc.shop_items, &mut self.rng);
+            // Friends of either faction find its goods at the guild's stalls
+            for faction in &honored {
+                for item in faction.exclusive_stock(self.floor, &mut self.rng, &mut self.item_id_counter) {
+                    npc.shop_items.push(ShopItem::new(item));
+                }
+            }
+        }
+    }
+
+    /// Roll for every faction hunting the player to send a squad down after
+    /// them on a new floor
+    fn send_hunters(&mut self) {
+        use rand::Rng;
 
-            // Collect messages after damage processing
-            for msg in tick_result.messages {
-                messages_to_add.push(msg);
+        if crate::entities::BossType::is_boss_floor(self.floor) || self.boss_rush.is_some() {
+            return;
+        }
+        for faction in self.reputation.hunting() {
+            if self.rng.gen_bool(super::HUNTER_SQUAD_CHANCE) {
+                self.spawn_hunters(faction);
             }
         }
+    }
+
+    /// Spawn a faction's hunters around the player, already on their trail
+    fn spawn_hunters(&mut self, faction: super::NpcFaction) {
+        use crate::entities::spawn_enemy_scaled;
+        use crate::ecs::{AI, AIState};
+
+        let positions = self.squad_positions();
+        let Some(player_pos) = self.player_position() else { return };
+        let scaling = self.floor_scaling();
+        let [leader, follower] = faction.hunters();
+        let count = (3 + self.floor as usize / 8).min(5);
+        for (i, pos) in positions.into_iter().take(count).enumerate() {
+            let def = if i == 0 { leader } else { follower };
+            let hunter = spawn_enemy_scaled(&mut self.world, def, pos, &scaling);
+            let _ = self.world.insert_one(hunter, AI { state: AIState::Chase, target: Some(player_pos), home: pos });
+        }
 
-        // Add all messages after releasing borrows
-        for msg in messages_to_add {
-            self.add_message(msg, MessageCategory::Combat);
+        self.add_message(
+            format!("Hunters sent by the {} close in around you!", faction.name()),
+            MessageCategory::Warning,
+        );
+    }
+
+    // ========================================================================
+    // Pickpocketing
+    // ========================================================================
+
+    /// Try to pick the pockets of whoever stands in `direction`: an NPC, or
+    /// an enemy asleep where it lies. Returns true if it took a turn.
+    pub fn steal(&mut self, direction: (i32, i32)) -> bool {
+        use crate::entities::BossComponent;
+        use crate::ecs::{AI, AIState, Enemy};
+
+        let Some(player_pos) = self.player_position() else { return false };
+        let pos = Position::new(player_pos.x + direction.0, player_pos.y + direction.1);
+        let dexterity = self.player_stats().map(|s| s.dexterity).unwrap_or(10);
+
+        if let Some(npc) = crate::entities::get_npc_at(&self.world, pos) {
+            return self.pickpocket_npc(npc, dexterity);
+        }
+        let mark = self.world.query::<(&Position, &Enemy, &AI, &Stats)>()
+            .without::<&BossComponent>()
+            .iter()
+            .find(|(_, (at, ..))| **at == pos)
+            .map(|(entity, (_, _, ai, stats))| (entity, ai.state == AIState::Sleeping, super::enemy_perception(stats)));
+        match mark {
+            Some((enemy, true, perception)) => self.pickpocket_sleeper(enemy, perception, dexterity),
+            Some(_) => {
+                self.add_message("It's wide awake. You'd never get close enough.", MessageCategory::System);
+                false
+            }
+            None => {
+                self.add_message("There's nobody there to steal from.", MessageCategory::System);
+                false
+            }
+        }
+    }
+
+    fn pickpocket_npc(&mut self, npc: Entity, dexterity: i32) -> bool {
+        use rand::Rng;
+        use crate::entities::NpcComponent;
+        use super::{Haul, Pickpocketed};
+
+        let Some(npc_type) = self.world.get::<&NpcComponent>(npc).ok().map(|n| n.npc_type) else { return false };
+        if self.world.get::<&Pickpocketed>(npc).is_ok() {
+            self.add_message(format!("The {} keeps a hand on their purse around you now.", npc_type.name()), MessageCategory::System);
+            return false;
+        }
+        let _ = self.world.insert_one(npc, Pickpocketed);
+
+        if !self.rng.gen_bool(super::steal_chance(dexterity, npc_type.perception(), false)) {
+            self.add_message(format!("The {} catches your hand in their purse!", npc_type.name()), MessageCategory::Warning);
+            if let Some(faction) = super::NpcFaction::of(npc_type) {
+                self.shift_reputation(faction, -super::PICKPOCKET_REPUTATION);
+            }
+            return true;
+        }
+
+        let haul = match self.world.get::<&mut NpcComponent>(npc) {
+            Ok(mut stock) => match super::npc_haul(&stock, &mut self.rng) {
+                Haul::Gold(gold) => {
+                    let gold = gold.min(stock.gold);
+                    stock.gold -= gold;
+                    (gold, None)
+                }
+                Haul::Stall(index) => (0, Some(stock.shop_items.remove(index).item)),
+            },
+            Err(_) => return false,
+        };
+        self.pocket_haul(haul, &format!("the {}", npc_type.name()));
+        true
+    }
+
+    fn pickpocket_sleeper(&mut self, enemy: Entity, perception: i32, dexterity: i32) -> bool {
+        use rand::Rng;
+        use crate::ecs::{AI, AIState, Name};
+        use super::Pickpocketed;
+
+        let name = self.world.get::<&Name>(enemy).map(|n| n.0.clone()).unwrap_or_else(|_| "sleeper".to_string());
+        if self.world.get::<&Pickpocketed>(enemy).is_ok() {
+            self.add_message(format!("You've already been through the {}'s pockets.", name), MessageCategory::System);
+            return false;
+        }
+        let _ = self.world.insert_one(enemy, Pickpocketed);
+
+        if !self.rng.gen_bool(super::steal_chance(dexterity, perception, true)) {
+            let player_pos = self.player_position();
+            if let Ok(mut ai) = self.world.get::<&mut AI>(enemy) {
+                ai.state = AIState::Chase;
+                ai.target = player_pos;
+            }
+            self.add_message(format!("The {} wakes with your hand in its pouch!", name), MessageCategory::Warning);
+            return true;
+        }
+
+        let gold = self.data.economy().gold_drop.roll(self.floor, &mut self.rng);
+        let item = if self.rng.gen_bool(super::theft::SLEEPER_ITEM_CHANCE) {
+            crate::items::generate_enemy_loot(self.loot_floor(), &mut self.rng).into_iter().next()
+        } else {
+            None
+        };
+        self.pocket_haul((gold, item), &format!("the sleeping {}", name));
+        true
+    }
+
+    /// Take what was lifted; anything with no room in the pack is dropped at
+    /// the player's feet
+    fn pocket_haul(&mut self, (gold, item): (u32, Option<crate::items::Item>), from: &str) {
+        use crate::ecs::{GroundItem, InventoryComponent, Renderable};
+
+        let Some(player) = self.player_entity else { return };
+        let mut dropped = None;
+        if let Ok(mut inv) = self.world.get::<&mut InventoryComponent>(player) {
+            inv.inventory.add_gold(gold);
+            if let Some(item) = item.clone() {
+                if !inv.inventory.add_item(item.clone()) {
+                    dropped = Some(item);
+                }
+            }
+        }
+        if gold > 0 {
+            self.play_sound(SoundId::GoldPickup);
+            self.add_message(format!("You lift {} gold from {}.", gold, from), MessageCategory::Item);
+            self.record_gold_collected(gold);
+        }
+        if let Some(item) = item {
+            let name = self.discoveries.disguise(&item).name.clone();
+            self.add_message(format!("You palm {} from {}.", name, from), MessageCategory::Item);
+        }
+        if let (Some(item), Some(pos)) = (dropped, self.player_position()) {
+            self.add_message("Your pack is full; you let it slip to the floor.", MessageCategory::Warning);
+            self.world.spawn((
+                pos,
+                Renderable::new(item.glyph, item.rarity.color()).with_order(10),
+                GroundItem { item },
+            ));
+        }
+    }
+
+    // ========================================================================
+    // Gold Sinks
+    // ========================================================================
+
+    /// Run an item transaction against the player's inventory and
+    /// equipment: all of it goes through, or none of it
+    pub fn transact(&mut self, transaction: crate::items::Transaction) -> Result<crate::items::Receipt, crate::items::TransactionError> {
+        use crate::ecs::{EquipmentComponent, InventoryComponent};
+
+        let player = self.player_entity.ok_or(crate::items::TransactionError::Missing)?;
+        let (inv, eq) = self.world
+            .query_one_mut::<(&mut InventoryComponent, &mut EquipmentComponent)>(player)
+            .map_err(|_| crate::items::TransactionError::Missing)?;
+        let receipt = transaction.apply(&mut inv.inventory, &mut eq.equipment)?;
+        Ok(receipt)
+    }
+
+    /// Take gold from the player, returning false if they can't afford it
+    fn spend_gold(&mut self, amount: u32) -> bool {
+        use crate::ecs::InventoryComponent;
+
+        self.player_entity
+            .and_then(|p| self.world.get::<&mut InventoryComponent>(p).ok())
+            .is_some_and(|mut inv| inv.inventory.spend_gold(amount))
+    }
+
+    /// Paid shrine services this run
+    pub fn shrine_services(&self) -> u32 {
+        self.shrine_services
+    }
+
+    /// How far shrine prices have climbed this run
+    pub fn shrine_markup(&self) -> crate::data::ShrineMarkup {
+        self.data.gold_sinks().shrine_markup(self.shrine_services)
+    }
+
+    /// Gold for one offering at a gambling shrine
+    pub fn shrine_gamble_cost(&self) -> u32 {
+        self.shrine_markup().price(self.data.gold_sinks().shrine_gamble.at(self.floor))
+    }
+
+    /// Pay a shrine for its service, raising what shrines charge from now on.
+    /// Returns false if the player can't afford it.
+    pub fn pay_shrine(&mut self, cost: u32) -> bool {
+        if !self.spend_gold(cost) {
+            return false;
+        }
+        self.shrine_services += 1;
+        true
+    }
+
+    /// Gold the gambler takes as stakes on this floor
+    pub fn dice_stakes(&self) -> Vec<u32> {
+        self.data.gold_sinks().dice_stakes(self.floor)
+    }
+
+    /// Throw dice with the gambler. Returns None if the player can't cover
+    /// the stake.
+    pub fn play_dice(&mut self, stake: u32) -> Option<super::DiceThrow> {
+        use crate::ecs::InventoryComponent;
+
+        if !self.spend_gold(stake) {
+            self.play_sound(SoundId::Error);
+            return None;
+        }
+        let throw = super::DiceThrow::roll(stake, &self.data.gold_sinks().dice, &mut self.rng);
+        if throw.won() {
+            if let Some(mut inv) = self.player_entity
+                .and_then(|p| self.world.get::<&mut InventoryComponent>(p).ok())
+            {
+                inv.inventory.add_gold(throw.winnings);
+            }
+            self.play_sound(SoundId::GoldPickup);
+            self.record_gold_collected(throw.winnings);
         }
+        self.add_message(throw.description(), MessageCategory::Item);
+        Some(throw)
+    }
+
+    /// Whether there's a shut toll gate at `pos`
+    pub fn is_toll_gate(&self, pos: Position) -> bool {
+        self.map.as_ref()
+            .and_then(|m| m.get_tile(pos.x, pos.y))
+            .is_some_and(|t| t.tile_type == crate::world::TileType::TollGate)
+    }
+
+    /// Gold to open a toll gate on this floor
+    pub fn toll(&self) -> u32 {
+        self.data.gold_sinks().toll.at(self.floor)
+    }
 
-        // Remove dead entities and add death messages
-        for (entity, name) in dead_entities {
+    /// Pay the toll and open the gate at `pos`, returning false if the
+    /// player can't afford it
+    pub fn pay_toll(&mut self, pos: Position) -> bool {
+        let toll = self.toll();
+        if !self.spend_gold(toll) {
+            self.play_sound(SoundId::Error);
             self.add_message(
-                format!("{} succumbed to their wounds!", name),
-                MessageCategory::Combat,
+                format!("The toll gate wants {} gold. You can't pay; the long way round it is.", toll),
+                MessageCategory::Warning,
             );
-            let _ = self.world.despawn(entity);
+            return false;
         }
+        if let Some(map) = self.map.as_mut() {
+            map.set_tile(pos.x, pos.y, crate::world::TileType::TollGateOpen);
+        }
+        self.play_sound(SoundId::DoorOpen);
+        self.add_message(format!("You drop {} gold in the toll box. The gate swings open.", toll), MessageCategory::System);
+        true
     }
 
-    /// Run AI for all enemies (called after player action)
-    pub fn run_ai_tick(&mut self) {
-        use crate::ecs::{run_enemy_ai, execute_ai_actions};
+    // ========================================================================
+    // Prisoners & Followers
+    // ========================================================================
+
+    /// Free a caged prisoner, returning what the player got out of it
+    pub fn free_prisoner(&mut self, entity: Entity) -> Option<crate::entities::RescueOutcome> {
+        use crate::entities::{Prisoner, PrisonerKind, RescueOutcome, make_follower, generate_chest_loot};
+        use crate::ecs::{ChestRarity, GroundItem, InventoryComponent, Renderable};
 
-        // First, tick status effects on all enemies (DoT damage applies per turn)
-        self.tick_enemy_status_effects();
+        let (kind, pos) = {
+            let prisoner = self.world.get::<&Prisoner>(entity).ok()?;
+            let pos = self.world.get::<&Position>(entity).ok()?;
+            (prisoner.kind, *pos)
+        };
+
+        self.play_sound(SoundId::ChestOpen);
+        self.add_message(
+            format!("You break open the cage. {}: \"{}\"", kind.name(), kind.plea()),
+            MessageCategory::Lore,
+        );
+
+        match kind {
+            PrisonerKind::Scavenger => {
+                // The scavenger's stash is as good as a rare chest
+                let (items, gold) = generate_chest_loot(ChestRarity::Rare, self.loot_floor(), 1.0, self.data.economy().gold_drop, &mut self.rng);
+                if let Some(player) = self.player_entity {
+                    if let Ok(mut inv) = self.world.get::<&mut InventoryComponent>(player) {
+                        inv.inventory.add_gold(gold);
+                    }
+                }
+                if gold > 0 {
+                    self.play_sound(SoundId::GoldPickup);
+                    self.add_message(format!("The scavenger hands you {} gold.", gold), MessageCategory::Item);
+                    self.record_gold_collected(gold);
+                }
+                for item in items {
+                    self.add_message(
+                        format!("Found: {} [{}]", item.name, item.rarity.name()),
+                        MessageCategory::Item,
+                    );
+                    self.world.spawn((
+                        pos,
+                        Renderable::new(item.glyph, item.rarity.color()).with_order(80),
+                        GroundItem { item },
+                    ));
+                }
+                self.add_message("The scavenger scurries off into the dark.", MessageCategory::System);
+                let _ = self.world.despawn(entity);
+                Some(RescueOutcome::Stash(gold))
+            }
+            PrisonerKind::Sellsword => {
+                make_follower(&mut self.world, entity, kind, self.floor);
+                self.add_message(
+                    format!("The {} joins you.", kind.name()),
+                    MessageCategory::System,
+                );
+                Some(RescueOutcome::Follower)
+            }
+            PrisonerKind::Survivor(_) => {
+                make_follower(&mut self.world, entity, kind, self.floor);
+                self.add_message(
+                    format!("Escort the {} to the stairs to rescue them.", kind.name()),
+                    MessageCategory::System,
+                );
+                Some(RescueOutcome::Follower)
+            }
+        }
+    }
 
-        // Also tick player status effects (DoT applies on their turn too)
-        self.tick_player_status_effects();
+    /// Resolve follower deaths, kills and departures after the AI has acted
+    fn update_followers(&mut self) {
+        use crate::entities::Follower;
+        use crate::ecs::{Health, Name};
+
+        let mut departed = Vec::new();
+        for (entity, (follower, health, name)) in self.world.query_mut::<(&mut Follower, &Health, &Name)>() {
+            if health.is_dead() {
+                departed.push((entity, format!("{} has fallen!", name.0), MessageCategory::Warning));
+                continue;
+            }
+            if let Some(turns) = follower.turns_left.as_mut() {
+                *turns = turns.saturating_sub(1);
+                if *turns == 0 {
+                    departed.push((entity, format!("{} bids you farewell and slips into the dark.", name.0), MessageCategory::System));
+                }
+            }
+        }
+        for (entity, msg, category) in departed {
+            self.add_message(msg, category);
+            let _ = self.world.despawn(entity);
+        }
+    }
+
+    /// Queue the deaths of enemies the followers' blows just finished off.
+    /// Enemies dying any other way are left for the reaping to find.
+    fn credit_follower_kills(&mut self, hits: &[crate::combat::Hit]) {
+        use crate::ecs::{Enemy, Health, Name};
+        use crate::entities::BossComponent;
+
+        // Bosses are never engaged by followers
+        for hit in hits {
+            let slain = self.world.get::<&Enemy>(hit.target).is_ok()
+                && self.world.get::<&BossComponent>(hit.target).is_err()
+                && self.world.get::<&Health>(hit.target).is_ok_and(|health| health.is_dead())
+                && !self.deaths.contains(hit.target);
+            if !slain {
+                continue;
+            }
+            let name = self.world.get::<&Name>(hit.target).map(|n| n.0.clone()).unwrap_or_else(|_| "enemy".to_string());
+            self.add_message(format!("The {} is slain by your companion!", name), MessageCategory::Combat);
+            self.queue_death(hit.target, false, false);
+        }
+    }
+
+    /// Rescue escorts standing near the player; escorts left behind are lost
+    fn resolve_escorts(&mut self) {
+        use crate::entities::Follower;
+        use crate::ecs::Name;
 
         let player_pos = match self.player_position() {
             Some(pos) => pos,
             None => return,
         };
 
+        let escorts: Vec<(Entity, String, crate::entities::NpcType, bool)> = self.world
+            .query::<(&Follower, &Position, &Name)>()
+            .iter()
+            .filter_map(|(entity, (follower, pos, name))| {
+                follower.escort_unlock.map(|npc| (entity, name.0.clone(), npc, pos.chebyshev_distance(&player_pos) <= 2))
+            })
+            .collect();
+
+        for (entity, name, npc_type, made_it) in escorts {
+            if made_it {
+                self.run_stats.escorts_rescued += 1;
+                self.add_message(
+                    format!("The {} slips away toward the surface. They will wait at the entrance in future descents.", name),
+                    MessageCategory::Lore,
+                );
+                if let Some(faction) = super::NpcFaction::of(npc_type) {
+                    self.shift_reputation(faction, super::ESCORT_REPUTATION);
+                }
+                if self.profile.rescue_npc(npc_type) {
+                    self.store_profile();
+                }
+            } else {
+                self.run_stats.escorts_lost += 1;
+                self.add_message(format!("You left the {} behind.", name), MessageCategory::Warning);
+            }
+            let _ = self.world.despawn(entity);
+        }
+    }
+
+    /// Bring the remaining followers along to the new floor's entrance
+    fn move_followers_to_start(&mut self) {
+        use crate::entities::Follower;
+
         let map = match &self.map {
             Some(m) => m,
             None => return,
         };
+        let start = map.start_pos;
 
-        // Run AI to get actions (pass rng for slow effect chance)
-        let actions = run_enemy_ai(&mut self.world, map, player_pos, &mut self.rng);
-
-        // Execute the actions (need to pass rng for combat calculations)
-        let messages = execute_ai_actions(&mut self.world, actions, self.player_entity, &mut self.rng);
-
-        // Add combat messages
-        for msg in messages {
-            self.add_message(msg, MessageCategory::Combat);
-        }
+        let mut free_tiles: Vec<Position> = (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| Position::new(start.x + dx, start.y + dy)))
+            .filter(|pos| *pos != start && map.is_walkable(pos.x, pos.y))
+            .collect();
 
-        // Check if player died (from combat or DoT)
-        if let Some(health) = self.player_health() {
-            if health.is_dead() {
-                self.player_died("overwhelmed by the darkness");
+        for (_, (pos, _)) in self.world.query_mut::<(&mut Position, &Follower)>() {
+            match free_tiles.pop() {
+                Some(tile) => *pos = tile,
+                None => break,
             }
         }
     }
@@ -787,13 +5475,14 @@ impl Game {
         if self.floor == 1 {
             self.profile.unlock_achievement("die_on_floor_1");
         }
-        if let Err(e) = save_profile(&self.profile) {
-            log::warn!("Failed to save profile: {}", e);
-        }
+
+        let cause = cause.into();
+        self.record_run_score(false, &cause);
+        self.store_profile();
 
         self.set_state(GameState::GameOver {
             floor_reached: self.floor,
-            cause_of_death: cause.into(),
+            cause_of_death: cause,
         });
     }
 
@@ -805,13 +5494,131 @@ impl Game {
             self.profile.add_playtime(elapsed);
         }
 
-        // Update profile stats
-        self.profile.record_victory();
-        if let Err(e) = save_profile(&self.profile) {
-            log::warn!("Failed to save profile: {}", e);
+        // Update profile stats (a boss rush clear is its own kind of win)
+        match self.boss_rush {
+            Some(rush) => {
+                if self.profile.record_boss_rush(rush.seconds(), self.difficulty) {
+                    self.add_message(
+                        format!("A new best boss rush time: {}!", super::format_rush_time(rush.seconds())),
+                        MessageCategory::System,
+                    );
+                }
+                self.record_run_score(true, &format!("Boss rush cleared in {}", super::format_rush_time(rush.seconds())));
+            }
+            None => {
+                if self.speedrunning() && self.profile.record_run_time(self.run_clock.seconds()) {
+                    self.add_message(
+                        format!("A new best run time: {}!", super::format_run_time(self.run_clock.seconds())),
+                        MessageCategory::System,
+                    );
+                }
+                self.profile.record_victory();
+                self.record_run_score(true, "Victory");
+                self.epilogue = self.resolve_ending();
+            }
+        }
+        self.store_profile();
+
+        // A descent ends on its closing scene; a boss rush goes straight to the results
+        if self.boss_rush.is_none() && self.play_cutscene(crate::data::CutsceneTrigger::Ending) {
+            self.after_cutscenes = Some(GameState::Victory);
+        } else {
+            self.set_state(GameState::Victory);
+        }
+    }
+
+    /// How far corruption has taken the player, 0 to 100. A body that can
+    /// hold no more mutations is fully corrupted.
+    pub fn corruption(&self) -> u32 {
+        use crate::progression::{Mutations, MAX_MUTATIONS};
+
+        let mutations = self.player_entity
+            .and_then(|p| self.world.get::<&Mutations>(p).ok().map(|m| m.mutations.len()))
+            .unwrap_or(0);
+        (mutations.min(MAX_MUTATIONS) * 100 / MAX_MUTATIONS) as u32
+    }
+
+    /// Pick the epilogue this run has earned from its corruption, patron,
+    /// conducts and escorts
+    fn resolve_ending(&self) -> Option<crate::data::Epilogue> {
+        let patron = self.worship.patron;
+        let outcome = crate::data::RunOutcome {
+            corruption: self.corruption(),
+            patron,
+            patron_favor: patron.map(|d| self.worship.favor(d)).unwrap_or(0),
+            conducts: crate::save::calculate_score(&self.run_stats, self.floor, self.difficulty, &self.sigils, true).conducts,
+            escorts_rescued: self.run_stats.escorts_rescued,
+            escorts_lost: self.run_stats.escorts_lost,
+        };
+        let epilogue = self.data.epilogue_defs().resolve(&outcome);
+        log::info!("Run ended with the {:?} epilogue", epilogue.as_ref().map(|e| &e.id));
+        epilogue
+    }
+
+    /// The epilogue of a won run
+    pub fn epilogue(&self) -> Option<&crate::data::Epilogue> {
+        self.epilogue.as_ref()
+    }
+
+    /// Score the finished run and record it on the leaderboard
+    fn record_run_score(&mut self, won: bool, cause: &str) {
+        use crate::save::{calculate_score, LeaderboardEntry, leaderboard::unix_timestamp};
+
+        let score = match self.boss_rush {
+            Some(rush) => {
+                let bosses = if won { super::BOSS_RUSH_ORDER.len() as u32 } else { rush.bosses_defeated() };
+                crate::save::calculate_boss_rush_score(bosses, rush.seconds(), self.difficulty, won)
+            }
+            // A run that went on into the depths had already won
+            None => calculate_score(&self.run_stats, self.floor, self.difficulty, &self.sigils, won || self.endless),
+        };
+        let entry = LeaderboardEntry {
+            score: score.total,
+            floor: self.floor,
+            difficulty: self.difficulty,
+            kills: self.run_stats.kills,
+            gold: self.run_stats.gold_collected,
+            won: won || self.endless,
+            cause: cause.to_string(),
+            timestamp: unix_timestamp(),
+            boss_rush: self.boss_rush.is_some(),
+            sigils: self.sigils.clone(),
+        };
+
+        #[cfg(feature = "online-leaderboard")]
+        if self.profile.settings.submit_scores_online {
+            let url = self.profile.settings.leaderboard_url.clone();
+            let name = self.profile.settings.player_name.clone();
+            let entry = entry.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = crate::save::leaderboard::submit_online(&url, &name, &entry) {
+                    log::warn!("Failed to submit score online: {}", e);
+                }
+            });
         }
 
-        self.set_state(GameState::Victory);
+        // The run was scored when it left the Abyss; below it, what counts
+        // is how deep it went
+        let rank = if self.endless {
+            if self.profile.leaderboard.submit_depth(entry) {
+                self.add_message(format!("A new deepest descent: {}!", floor_name(self.floor)), MessageCategory::System);
+            }
+            None
+        } else {
+            self.profile.leaderboard.submit(entry)
+        };
+        log::info!("Run scored {} (rank {:?})", score.total, rank);
+        self.last_score = Some((score, rank));
+    }
+
+    /// Get the score of the last finished run and its leaderboard rank
+    pub fn last_score(&self) -> Option<&(ScoreBreakdown, Option<usize>)> {
+        self.last_score.as_ref()
+    }
+
+    /// Get the statistics for the current run
+    pub fn run_stats(&self) -> &RunStats {
+        &self.run_stats
     }
 
     /// Request to quit the game
@@ -827,24 +5634,62 @@ impl Game {
     /// Mark a shrine at the given position as used
     pub fn mark_shrine_used(&mut self, pos: Position) {
         self.used_shrines.insert((self.floor, pos.x, pos.y));
+        self.run_stats.shrines_used += 1;
+        self.tutorial_event(crate::data::TutorialTrigger::ShrineUsed);
+    }
+
+    /// Every shrine used this run, by floor and position (for saving)
+    pub fn used_shrines(&self) -> Vec<(u32, i32, i32)> {
+        self.used_shrines.iter().copied().collect()
     }
 
     /// Restore game state from save data
-    pub fn restore_from_save(&mut self, save: crate::save::SaveData) -> Result<(), String> {
+    pub fn restore_from_save(&mut self, save: crate::save::SaveData) -> Result<(), Error> {
         use crate::ecs::{
             Renderable, Name, FactionComponent, Faction, AI, AIState,
             BlocksMovement, XpReward, Enemy, EnemyArchetype,
             InventoryComponent, EquipmentComponent, SkillsComponent, StatPoints, GroundItem,
         };
-        use crate::items::{Equipment, Inventory};
+        use crate::items::{Equipment, EquipSlot, Inventory};
         use crate::world::{Map, Tile};
 
         // Reset world
         self.world = World::new();
+        self.sized_up.clear();
+        self.hits.clear();
+        self.floating.clear();
         self.floor = save.game.floor;
+        self.seed = save.game.rng_seed;
         self.difficulty = save.game.difficulty;
+        self.sigils = save.game.sigils.clone();
+        self.adaptive = save.game.adaptive.clone();
+        self.endless = save.game.endless;
+        // Older saves didn't keep the counter; never hand out an ID twice
+        self.item_id_counter = save.game.item_id_counter.max(self.item_id_counter);
+        self.used_shrines = save.game.used_shrines.into_iter().collect();
+        self.door_damage = save.game.door_damage.into_iter()
+            .map(|((x, y), damage)| (Position::new(x, y), damage))
+            .collect();
         self.messages.clear();
-        self.ambient_time = 0.0;
+        self.ambient_time = save.game.ambient_time;
+        self.floor_turns = save.game.floor_turns;
+        self.run_clock = save.game.run_clock;
+        self.tutorial = save.game.tutorial;
+        self.boss_rush = save.game.boss_rush;
+        self.ng_plus = save.game.ng_plus;
+        self.floor_event = save.game.floor_event;
+        self.collapse = save.game.collapse;
+        self.floor_banner = None;
+        self.cutscenes.clear();
+        self.after_cutscenes = None;
+        self.run_stats = save.game.run_stats;
+        self.last_score = None;
+        self.run_started_unix = Some(crate::save::leaderboard::unix_timestamp());
+        self.worship = save.game.worship;
+        self.reputation = save.game.reputation;
+        self.shrine_services = save.game.shrine_services;
+        self.discoveries = save.game.discoveries;
+        self.deaths_door_used = save.game.deaths_door_used;
 
         // Restore map
         let mut map = Map::new(
@@ -868,6 +5713,13 @@ impl Game {
         for (x, y) in save.map.elite_rooms {
             map.elite_rooms.push(Position::new(x, y));
         }
+        for ((x, y), decal) in save.map.decals {
+            map.decals.insert(Position::new(x, y), decal);
+        }
+        for ((ax, ay), (bx, by)) in save.map.teleporters {
+            map.teleporters.push((Position::new(ax, ay), Position::new(bx, by)));
+        }
+        map.mechanisms = save.map.mechanisms;
         self.map = Some(map);
 
         // Restore player
@@ -893,14 +5745,22 @@ impl Game {
         // Restore inventory with gold and items
         let mut inventory = Inventory::new();
         inventory.add_gold(save.player.gold);
+        inventory.expand(save.player.pack_rows.saturating_sub(inventory.grid_size().1));
+        *inventory.belt_mut() = save.player.belt;
+        // Whatever no longer fits the pack is left at the player's feet
+        let mut spilled = Vec::new();
         for item in save.player.inventory {
-            let _ = inventory.add_item(item);
+            if inventory.can_fit(&item) {
+                inventory.add_item(item);
+            } else {
+                spilled.push(item);
+            }
         }
 
         // Restore equipment
         let mut equipment = Equipment::new();
-        if let Some(item) = save.player.equipment.main_hand { equipment.equip(item); }
-        if let Some(item) = save.player.equipment.off_hand { equipment.equip(item); }
+        if let Some(item) = save.player.equipment.main_hand { equipment.equip_to(EquipSlot::MainHand, item); }
+        if let Some(item) = save.player.equipment.off_hand { equipment.equip_to(EquipSlot::OffHand, item); }
         if let Some(item) = save.player.equipment.head { equipment.equip(item); }
         if let Some(item) = save.player.equipment.body { equipment.equip(item); }
         if let Some(item) = save.player.equipment.hands { equipment.equip(item); }
@@ -926,9 +5786,17 @@ impl Game {
             SkillsComponent { skills: save.player.skills },
             StatPoints(save.player.stat_points),
         ));
+        let _ = self.world.insert_one(player, save.player.mutations);
+        let _ = self.world.insert_one(player, save.player.injuries);
         self.player_entity = Some(player);
+        self.worn_synergy_tags = None;
 
-        // Restore enemies
+        // Restore enemies, loot, props, chests and corpses
+        crate::save::save_game::level_registry()
+            .restore(&mut self.world, &save.entities)
+            .map_err(|e| Error::InvalidSave(format!("corrupt entities: {}", e)))?;
+
+        // Older saves kept them apart
         for enemy_data in save.enemies {
             let pos = Position::new(enemy_data.position.0, enemy_data.position.1);
             let stats = Stats::new(
@@ -940,7 +5808,7 @@ impl Game {
             let mut health = Health::new(enemy_data.health.1);
             health.current = enemy_data.health.0;
 
-            self.world.spawn((
+            let enemy = self.world.spawn((
                 Name::new(&enemy_data.name),
                 pos,
                 Renderable::new(enemy_data.glyph, enemy_data.color).with_order(50),
@@ -952,6 +5820,27 @@ impl Game {
                 BlocksMovement,
                 XpReward(enemy_data.xp_reward),
             ));
+            if !enemy_data.modifiers.0.is_empty() {
+                let _ = self.world.insert_one(enemy, enemy_data.modifiers);
+            }
+            if crate::entities::enemy_def(&enemy_data.name).is_some_and(|def| def.raises_dead) {
+                let _ = self.world.insert_one(enemy, crate::entities::Necromancer::default());
+            }
+            if enemy_data.name == crate::entities::HEART_NODE.name {
+                crate::entities::root_heart(&mut self.world, enemy);
+            }
+        }
+        crate::entities::attach_resistances(&mut self.world, &self.data.enemies);
+
+        if !spilled.is_empty() {
+            log::warn!("{} saved items no longer fit the pack; dropped them at the player's feet", spilled.len());
+        }
+        for item in spilled {
+            self.world.spawn((
+                player_pos,
+                Renderable::new(item.glyph, item.rarity.color()).with_order(10),
+                GroundItem { item },
+            ));
         }
 
         // Restore items on ground
@@ -964,6 +5853,25 @@ impl Game {
             ));
         }
 
+        for ((x, y), prop) in save.props {
+            crate::entities::spawn_prop(&mut self.world, Position::new(x, y), prop);
+        }
+        for ((x, y), chest) in save.chests {
+            crate::entities::place_chest(&mut self.world, Position::new(x, y), chest);
+        }
+        for ((x, y), corpse) in save.corpses {
+            crate::entities::place_corpse(&mut self.world, Position::new(x, y), corpse);
+        }
+        // Chests saved before they were interactable
+        let bare: Vec<Entity> = self.world.query::<&crate::ecs::Chest>()
+            .without::<&super::Interactable>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect();
+        for chest in bare {
+            let _ = self.world.insert_one(chest, super::Interactable::Chest);
+        }
+
         // Set game state
         self.add_message("Game loaded successfully.", MessageCategory::System);
         self.set_state(GameState::Playing(PlayingState::Exploring));
@@ -985,28 +5893,87 @@ impl Game {
         &mut self.profile
     }
 
+    /// Change a setting and persist it straight away
+    pub fn update_settings(&mut self, change: impl FnOnce(&mut crate::save::ProfileSettings)) {
+        change(&mut self.profile.settings);
+        self.store_profile();
+    }
+
+    /// Log a drop, unless the loot filter hides it
+    pub fn announce_loot(&mut self, text: impl Into<String>, item: &crate::items::Item) {
+        use crate::save::LootFilterMode;
+
+        match self.profile.settings.loot_filter_for(item) {
+            LootFilterMode::Off => self.add_message(text, MessageCategory::Item),
+            LootFilterMode::Flag => self.add_message(format!("{} (junk)", text.into()), MessageCategory::System),
+            LootFilterMode::Hide => {}
+        }
+    }
+
     /// Record an enemy kill in the profile
     pub fn record_enemy_kill(&mut self, is_boss: bool) {
+        self.run_stats.kills += 1;
+        if is_boss {
+            self.run_stats.bosses_killed += 1;
+        }
         self.profile.record_enemy_kill(is_boss);
+        self.dedicate_kill(is_boss);
+        if is_boss {
+            self.begin_collapse();
+        }
+        self.tutorial_event(crate::data::TutorialTrigger::EnemyKilled);
         // Save periodically (every 10 kills to reduce I/O)
         if self.profile.stats.enemies_killed % 10 == 0 {
-            if let Err(e) = save_profile(&self.profile) {
-                log::warn!("Failed to save profile: {}", e);
-            }
+            self.store_profile();
         }
     }
 
     /// Record gold collected in the profile
     pub fn record_gold_collected(&mut self, amount: u32) {
+        self.run_stats.gold_collected += amount;
         self.profile.record_gold(amount);
+
+        // The Ashen Widow takes a tithe on her devotees' behalf
+        let tithe = amount / 5;
+        if tithe > 0 && self.worship.has_boon(super::Boon::Tithe) {
+            if let Some(player) = self.player_entity {
+                if let Ok(mut inv) = self.world.get::<&mut crate::ecs::InventoryComponent>(player) {
+                    inv.inventory.add_gold(tithe);
+                }
+            }
+            self.run_stats.gold_collected += tithe;
+            self.profile.record_gold(tithe);
+        }
     }
 
     /// Record an item found in the profile
     pub fn record_item_found(&mut self, item_id: &str) {
+        self.run_stats.items_found += 1;
         self.profile.record_item_found(item_id);
+        self.tutorial_event(crate::data::TutorialTrigger::PickedUp);
     }
 }
 
+/// Everything that happens in a turn after the player acts, stage by stage
+fn turn_schedule() -> Schedule<Game> {
+    Schedule::new()
+        .with(Stage::PlayerAction, "reap the slain", Game::reap_dead)
+        .with(Stage::PlayerAction, "recover", Game::recover)
+        .with(Stage::PlayerAction, "search surroundings", Game::search_surroundings)
+        .with(Stage::StatusTick, "tick statuses", Game::tick_statuses)
+        .with(Stage::StatusTick, "reap the afflicted", Game::reap_dead)
+        .with(Stage::Ai, "enemies act", Game::enemies_act)
+        .with(Stage::Ai, "bosses act", Game::bosses_act)
+        .with(Stage::Ai, "followers act", Game::followers_act)
+        .with(Stage::Hazards, "hazards strike", Game::hazards_strike)
+        .with(Stage::Cleanup, "reap the dead", Game::reap_dead)
+        .with(Stage::Cleanup, "reap player", Game::reap_player)
+        .with(Stage::Cleanup, "collapse floor", Game::collapse_floor)
+        .with(Stage::Cleanup, "settle remains", Game::settle_remains)
+        .with(Stage::Cleanup, "toll on player", Game::toll_on_player)
+        .with(Stage::Events, "catch up events", Game::catch_up_events)
+}
+
 impl Default for Game {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,89 @@
+//! Pickpocketing
+//!
+//! A light-fingered player can lift gold or goods from NPCs, and from
+//! enemies sleeping where they lie, pitting their dexterity against the
+//! mark's perception. Sleepers notice far less. Get caught by an NPC and
+//! their faction hears of it; get caught by an enemy and it wakes up
+//! swinging. Nobody can be tried twice.
+
+use rand::Rng;
+
+use crate::ecs::Stats;
+use crate::entities::NpcComponent;
+
+/// Reputation lost when an NPC catches the player at their purse
+pub const PICKPOCKET_REPUTATION: i32 = 15;
+/// Chance a sleeping enemy has something worth taking besides gold
+pub const SLEEPER_ITEM_CHANCE: f64 = 0.3;
+/// How much easier it is to rob someone asleep
+const SLEEPING_BONUS: f64 = 0.25;
+/// Chance a shopkeeper's stall is lifted from rather than their purse
+const STALL_CHANCE: f64 = 0.5;
+
+/// Has already had someone's hand in their pockets
+#[derive(Debug, Clone, Copy)]
+pub struct Pickpocketed;
+
+/// Chance to lift something unnoticed
+pub fn steal_chance(dexterity: i32, perception: i32, asleep: bool) -> f64 {
+    let bonus = if asleep { SLEEPING_BONUS } else { 0.0 };
+    (0.35 + (dexterity - perception) as f64 * 0.04 + bonus).clamp(0.05, 0.9)
+}
+
+/// How sharp an enemy's eyes are
+pub fn enemy_perception(stats: &Stats) -> i32 {
+    (stats.intelligence + stats.dexterity) / 2
+}
+
+/// What a light-fingered player takes from an NPC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Haul {
+    /// Gold from their purse
+    Gold(u32),
+    /// Something off their stall, by index
+    Stall(usize),
+}
+
+/// Pick what to take from an NPC: something off their stall if they keep
+/// one, otherwise a cut of their purse
+pub fn npc_haul(npc: &NpcComponent, rng: &mut impl Rng) -> Haul {
+    if !npc.shop_items.is_empty() && rng.gen_bool(STALL_CHANCE) {
+        return Haul::Stall(rng.gen_range(0..npc.shop_items.len()));
+    }
+    let purse = npc.gold.max(4);
+    Haul::Gold(rng.gen_range(purse / 10..=purse / 4).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::entities::npcs::{NpcType, ShopItem};
+    use crate::items::item::templates;
+
+    #[test]
+    fn test_light_fingers_and_sleepy_marks() {
+        // Nimble hands beat dull eyes, and sleepers notice least of all
+        assert!(steal_chance(16, 10, false) > steal_chance(10, 10, false));
+        assert!(steal_chance(10, 10, true) > steal_chance(10, 10, false));
+        assert!(steal_chance(30, 0, true) <= 0.9);
+        assert!(steal_chance(0, 30, false) >= 0.05);
+
+        let mut npc = NpcComponent {
+            npc_type: NpcType::Merchant,
+            shop_items: vec![ShopItem::new(templates::health_potion(1))],
+            gold: 400,
+            interacted: false,
+            dialogue_state: 0,
+        };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        for _ in 0..20 {
+            match npc_haul(&npc, &mut rng) {
+                Haul::Gold(gold) => assert!((40..=100).contains(&gold)),
+                Haul::Stall(index) => assert_eq!(index, 0),
+            }
+        }
+        npc.shop_items.clear();
+        assert!(matches!(npc_haul(&npc, &mut rng), Haul::Gold(_)));
+    }
+}
@@ -0,0 +1,105 @@
+//! Threat ratings
+//!
+//! How a fight with an enemy is likely to go, judged by the blows each side
+//! needs to bring the other down from full health. Levels, stats, gear and
+//! floor scaling all feed into those blows, and an elite's affixes are
+//! weighed on top. The rating shows in the Nearby panel and when examining
+//! an enemy, and a deadly one gets a warning when it first comes into view.
+
+use crate::progression::{EliteModifier, EliteModifiers};
+use crate::progression::new_game_plus::{VAMPIRIC_DRAIN_PERCENT, VENOM_POISON};
+
+/// The player needing this many times the enemy's blows makes it deadly
+/// (and the other way around, trivial)
+pub const DEADLY_RATIO: f32 = 3.0;
+/// Past this many times the enemy's blows, a fight is hard
+const HARD_RATIO: f32 = 1.5;
+
+/// How dangerous an enemy is to the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Threat {
+    Trivial,
+    Easy,
+    Even,
+    Hard,
+    Deadly,
+}
+
+impl Threat {
+    /// Rate a fight from the blows the player needs to kill the enemy and
+    /// the blows the enemy needs to kill the player
+    pub fn rate(player_blows: f32, enemy_blows: f32) -> Self {
+        let ratio = player_blows / enemy_blows.max(0.1);
+        if ratio >= DEADLY_RATIO {
+            Threat::Deadly
+        } else if ratio >= HARD_RATIO {
+            Threat::Hard
+        } else if ratio > 1.0 / HARD_RATIO {
+            Threat::Even
+        } else if ratio > 1.0 / DEADLY_RATIO {
+            Threat::Easy
+        } else {
+            Threat::Trivial
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Threat::Trivial => "trivial",
+            Threat::Easy => "easy",
+            Threat::Even => "even",
+            Threat::Hard => "hard",
+            Threat::Deadly => "deadly",
+        }
+    }
+
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            Threat::Trivial => (120, 120, 120),
+            Threat::Easy => (120, 200, 120),
+            Threat::Even => (220, 220, 220),
+            Threat::Hard => (230, 180, 60),
+            Threat::Deadly => (230, 60, 60),
+        }
+    }
+}
+
+/// Average blows needed to wear down `hp` at `damage` per blow
+pub fn blows_to_kill(hp: i32, damage: f32) -> f32 {
+    hp.max(1) as f32 / damage.max(0.1)
+}
+
+/// Damage per blow each side trades once an elite's affixes are counted:
+/// a vampiric elite drinks back part of what it deals, and a venomous one's
+/// poison adds to every hit
+pub fn with_affixes(player_damage: f32, enemy_damage: f32, mods: &EliteModifiers) -> (f32, f32) {
+    let mut player_damage = player_damage;
+    let mut enemy_damage = enemy_damage;
+    if mods.has(EliteModifier::Venomous) {
+        enemy_damage += VENOM_POISON.1 as f32;
+    }
+    if mods.has(EliteModifier::Vampiric) {
+        let drained = enemy_damage * VAMPIRIC_DRAIN_PERCENT as f32 / 100.0;
+        player_damage = (player_damage - drained).max(player_damage * 0.1);
+    }
+    (player_damage, enemy_damage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threat_rating() {
+        assert_eq!(Threat::rate(blows_to_kill(60, 5.0), blows_to_kill(60, 5.0)), Threat::Even);
+        assert_eq!(Threat::rate(blows_to_kill(10, 5.0), blows_to_kill(60, 5.0)), Threat::Trivial);
+        // A brute that kills in two blows and takes twenty
+        assert_eq!(Threat::rate(blows_to_kill(200, 10.0), blows_to_kill(60, 30.0)), Threat::Deadly);
+
+        // Affixes only ever make an elite worse to face
+        let mods = EliteModifiers(vec![EliteModifier::Vampiric, EliteModifier::Venomous]);
+        let (player, enemy) = with_affixes(10.0, 8.0, &mods);
+        assert!(player < 10.0 && enemy > 8.0);
+        assert_eq!(with_affixes(10.0, 8.0, &EliteModifiers::default()), (10.0, 8.0));
+    }
+}
@@ -1,9 +1,21 @@
 //! Ambient time system
 //!
-//! Handles time-based effects that tick during exploration.
+//! Handles time-based effects that tick during exploration, and the day and
+//! night cycle that runs on exploration time.
 
 use std::time::Duration;
 
+/// Seconds of exploration in one day and night
+pub const DAY_CYCLE_SECONDS: f32 = 600.0;
+/// Share of each cycle that is night
+const NIGHT_SHARE: f32 = 0.4;
+
+/// Whether it is night after some seconds of exploration. Runs start at
+/// dawn; night takes the end of each cycle.
+pub fn is_night(elapsed: f32) -> bool {
+    elapsed.rem_euclid(DAY_CYCLE_SECONDS) >= DAY_CYCLE_SECONDS * (1.0 - NIGHT_SHARE)
+}
+
 /// Manages ambient time effects
 pub struct AmbientTime {
     /// Total elapsed time in seconds
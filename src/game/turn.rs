@@ -1,8 +1,77 @@
 //! Turn management for combat
 //!
-//! Handles turn order, action points, and combat flow.
+//! Handles turn order, action points, and combat flow, including the
+//! opportunity attacks provoked by stepping out of an enemy's reach, and the
+//! regeneration that comes back at the end of every turn.
 
 use hecs::Entity;
+use crate::ecs::Position;
+
+/// Stamina spent to disengage without provoking opportunity attacks
+pub const DISENGAGE_STAMINA_COST: i32 = 5;
+
+/// Mana regained per turn before INT
+const MANA_PER_TURN: f32 = 0.33;
+/// Extra mana per turn for each point of INT
+const MANA_PER_TURN_PER_INT: f32 = 0.01;
+/// Stamina regained per turn in medium armor
+const STAMINA_PER_TURN: f32 = 0.5;
+
+/// An attack or skill readied to fire at the first enemy that comes into range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreparedAction {
+    /// A melee attack against anything stepping next to the player
+    Attack,
+    /// The skill in a hotbar slot
+    Skill(usize),
+}
+
+/// How far a prepared skill reaches, or None if it can't be prepared
+/// (self-targeted and movement skills have nothing to fire at)
+pub fn prepared_range(target: crate::progression::TargetType) -> Option<i32> {
+    use crate::progression::TargetType;
+    match target {
+        TargetType::SingleEnemy => Some(3),
+        TargetType::AllAdjacent => Some(1),
+        TargetType::AllInRange(range) => Some(range),
+        TargetType::Self_ | TargetType::Ground { .. } => None,
+    }
+}
+
+/// Fractional mana and stamina regeneration carried from turn to turn
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TurnRegen {
+    mana: f32,
+    stamina: f32,
+}
+
+impl TurnRegen {
+    /// Bank one turn of regeneration and take out the whole points earned,
+    /// as (mana, stamina)
+    pub fn tick(&mut self, intelligence: i32, stamina_multiplier: f32) -> (i32, i32) {
+        self.mana += MANA_PER_TURN + intelligence as f32 * MANA_PER_TURN_PER_INT;
+        self.stamina += STAMINA_PER_TURN * stamina_multiplier;
+
+        let mana = self.mana as i32;
+        let stamina = self.stamina as i32;
+        self.mana -= mana as f32;
+        self.stamina -= stamina as f32;
+        (mana, stamina)
+    }
+}
+
+/// Whether moving from `from` to `to` leaves the melee reach of a combatant at `threat`
+pub fn leaves_reach(from: Position, to: Position, threat: Position) -> bool {
+    from.chebyshev_distance(&threat) <= 1 && to.chebyshev_distance(&threat) > 1
+}
+
+/// Combatants that get a free attack when something moves from `from` to `to`
+pub fn opportunity_attackers(from: Position, to: Position, threats: &[(Entity, Position)]) -> Vec<Entity> {
+    threats.iter()
+        .filter(|(_, pos)| leaves_reach(from, to, *pos))
+        .map(|(entity, _)| *entity)
+        .collect()
+}
 
 /// Manages turn order during combat
 pub struct TurnManager {
@@ -85,3 +154,50 @@ impl Default for TurnManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepared_range() {
+        use crate::progression::TargetType;
+        assert_eq!(prepared_range(TargetType::AllAdjacent), Some(1));
+        assert_eq!(prepared_range(TargetType::AllInRange(4)), Some(4));
+        assert_eq!(prepared_range(TargetType::Self_), None);
+    }
+
+    #[test]
+    fn test_turn_regen_accumulates() {
+        let mut regen = TurnRegen::default();
+        let (mut mana, mut stamina) = (0, 0);
+        for _ in 0..10 {
+            let (m, s) = regen.tick(10, 1.0);
+            mana += m;
+            stamina += s;
+        }
+        // 0.43 MP and 0.5 SP a turn
+        assert_eq!(mana, 4);
+        assert_eq!(stamina, 5);
+
+        // Heavy armor slows stamina but not mana
+        let mut heavy = TurnRegen::default();
+        let stamina: i32 = (0..10).map(|_| heavy.tick(10, 0.5).1).sum();
+        assert!(stamina < 5);
+    }
+
+    #[test]
+    fn test_only_leaving_reach_provokes() {
+        let mut world = hecs::World::new();
+        let beside = world.spawn(());
+        let ahead = world.spawn(());
+        let threats = vec![(beside, Position::new(4, 5)), (ahead, Position::new(6, 5))];
+
+        // Stepping east away from one enemy and toward the other
+        let provoked = opportunity_attackers(Position::new(5, 5), Position::new(6, 4), &threats);
+        assert_eq!(provoked, vec![beside]);
+
+        // Sidestepping while staying in reach provokes nothing
+        assert!(opportunity_attackers(Position::new(5, 5), Position::new(5, 4), &threats).is_empty());
+    }
+}
@@ -289,6 +289,7 @@ impl GraphicalApp {
 
         // Render entities
         renderer::render_entities(game, &self.camera, map_area);
+        renderer::render_enemy_overlays(game, &self.camera, map_area);
 
         // Render UI panels
         renderer::render_status_panel(game, sidebar_area);
@@ -369,6 +370,7 @@ impl GraphicalApp {
                 crate::world::TileType::ShrineCorruption => "Corruption Shrine",
                 crate::world::TileType::DoorClosed => "Door (Closed)",
                 crate::world::TileType::DoorOpen => "Door (Open)",
+                crate::world::TileType::DoorLocked => "Door (Locked)",
                 _ => return,
             };
             renderer::render_tooltip(tile_name, mx, my);
@@ -36,9 +36,10 @@ pub fn tile_color(tile_type: TileType, biome: Biome) -> Color {
         TileType::Wall => colors::rgb(config.wall_color.0, config.wall_color.1, config.wall_color.2),
         TileType::Corridor => colors::rgb(config.corridor_color.0, config.corridor_color.1, config.corridor_color.2),
         TileType::StairsDown | TileType::StairsUp => colors::STAIRS,
-        TileType::DoorClosed | TileType::DoorOpen => colors::DOOR,
+        TileType::DoorClosed | TileType::DoorOpen | TileType::DoorLocked => colors::DOOR,
         TileType::Lava => colors::LAVA,
         TileType::Pit => colors::PIT,
+        TileType::BloodPool => colors::BLOOD,
         TileType::Torch => colors::TORCH,
         TileType::Brazier => colors::BRAZIER,
         TileType::BloodStain => colors::BLOOD,
@@ -61,9 +62,11 @@ pub fn tile_glyph(tile_type: TileType) -> char {
         TileType::StairsDown => '>',
         TileType::StairsUp => '<',
         TileType::DoorClosed => '+',
+        TileType::DoorLocked => '+',
         TileType::DoorOpen => '/',
         TileType::Lava => '~',
         TileType::Pit => ' ',
+        TileType::BloodPool => '~',
         TileType::Torch | TileType::Brazier => '†',
         TileType::BloodStain => '·',
         TileType::Bones => '%',
@@ -213,6 +216,51 @@ pub fn render_entities(game: &Game, camera: &Camera, view_area: Rect) {
     }
 }
 
+/// Render intent icons above visible enemies and their worst status effect
+/// beside them, at the density picked in the settings
+pub fn render_enemy_overlays(game: &Game, camera: &Camera, view_area: Rect) {
+    use crate::ecs::{AI, Enemy, StatusEffects};
+    use crate::render::intent::{enemy_intent, most_pressing, status_glyph};
+    use crate::save::EnemyOverlays;
+
+    let density = game.profile().settings.enemy_overlays;
+    if density == EnemyOverlays::Off {
+        return;
+    }
+    let map = game.map();
+    let player_pos = game.player_position();
+
+    for (_, (pos, enemy, ai, effects, necromancer)) in game.world()
+        .query::<(&Position, &Enemy, &AI, Option<&StatusEffects>, Option<&crate::entities::Necromancer>)>()
+        .iter()
+    {
+        if !map.is_visible(*pos) {
+            continue;
+        }
+        let screen_x = view_area.x + pos.x as f32 * TILE_SIZE - camera.x;
+        let screen_y = view_area.y + pos.y as f32 * TILE_SIZE - camera.y;
+        if screen_x + TILE_SIZE < view_area.x || screen_x > view_area.x + view_area.w
+            || screen_y + TILE_SIZE < view_area.y || screen_y > view_area.y + view_area.h
+        {
+            continue;
+        }
+
+        let spell_ready = necromancer.is_some_and(|n| n.cooldown == 0);
+        let distance = player_pos.map_or(i32::MAX, |p| pos.chebyshev_distance(&p));
+        if let Some(intent) = enemy_intent(ai.state, enemy.archetype, spell_ready, distance) {
+            let (r, g, b) = intent.color();
+            draw_text(&intent.glyph(false).to_string(), screen_x + 8.0, screen_y - 2.0, 16.0, colors::rgb(r, g, b));
+        }
+        if density == EnemyOverlays::Full {
+            let shown = effects.and_then(|e| most_pressing(e.effects.iter().map(|s| s.effect_type)));
+            if let Some(effect) = shown {
+                let (glyph, (r, g, b)) = status_glyph(effect, false);
+                draw_text(&glyph.to_string(), screen_x + TILE_SIZE - 6.0, screen_y + 10.0, 14.0, colors::rgb(r, g, b));
+            }
+        }
+    }
+}
+
 /// Render the player status panel
 pub fn render_status_panel(game: &Game, area: Rect) {
     // Panel background
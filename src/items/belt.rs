@@ -0,0 +1,81 @@
+//! Consumable belt
+//!
+//! Four quick-use slots for consumables. Slots remember an item by name rather
+//! than by id, so a slot keeps pointing at "Health Potion" after the stack it
+//! was assigned from runs out and a new one is picked up.
+
+use serde::{Deserialize, Serialize};
+
+use super::item::Item;
+
+/// Number of belt slots
+pub const BELT_SLOTS: usize = 4;
+
+/// Consumables assigned to the belt
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Belt {
+    slots: [Option<String>; BELT_SLOTS],
+}
+
+impl Belt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name of the item assigned to a slot
+    pub fn get(&self, slot: usize) -> Option<&str> {
+        self.slots.get(slot)?.as_deref()
+    }
+
+    /// Assign an item to a slot, taking it out of any other slot it was in.
+    /// Assigning an item to the slot it's already in clears the slot instead.
+    /// Returns false if the item can't go on the belt.
+    pub fn toggle(&mut self, slot: usize, item: &Item) -> bool {
+        if slot >= BELT_SLOTS || !item.is_consumable() {
+            return false;
+        }
+        if self.get(slot) == Some(item.name.as_str()) {
+            self.slots[slot] = None;
+            return true;
+        }
+        for s in self.slots.iter_mut() {
+            if s.as_deref() == Some(item.name.as_str()) {
+                *s = None;
+            }
+        }
+        self.slots[slot] = Some(item.name.clone());
+        true
+    }
+
+    /// Slot an item is assigned to, if any
+    pub fn slot_of(&self, name: &str) -> Option<usize> {
+        self.slots.iter().position(|s| s.as_deref() == Some(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::item::templates;
+
+    #[test]
+    fn test_belt_toggle() {
+        let mut belt = Belt::new();
+        let potion = templates::health_potion(1);
+
+        assert!(belt.toggle(0, &potion));
+        assert_eq!(belt.get(0), Some("Health Potion"));
+
+        // Moving to another slot frees the first
+        assert!(belt.toggle(2, &potion));
+        assert_eq!(belt.get(0), None);
+        assert_eq!(belt.slot_of("Health Potion"), Some(2));
+
+        // Same slot again clears it
+        assert!(belt.toggle(2, &potion));
+        assert_eq!(belt.slot_of("Health Potion"), None);
+
+        // Only consumables go on the belt
+        assert!(!belt.toggle(1, &templates::iron_key(2)));
+    }
+}
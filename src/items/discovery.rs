@@ -0,0 +1,143 @@
+//! Unidentified consumables
+//!
+//! Potions and scrolls don't show what they are until the player drinks or
+//! reads one, or pays a merchant to identify it. Each run deals out a fresh
+//! set of appearances ("Murky Crimson Vial"), so knowledge from a previous run
+//! doesn't carry over. Kinds are keyed by item name, like the belt.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::item::Item;
+
+/// Potions that start unidentified
+pub const POTION_KINDS: &[&str] = &["Health Potion", "Mana Potion", "Mutagenic Vial"];
+
+/// Scrolls that start unidentified
+pub const SCROLL_KINDS: &[&str] = &[
+    "Scroll of Teleportation",
+    "Scroll of Mapping",
+    "Scroll of Recharging",
+    "Scroll of Return",
+];
+
+const POTION_APPEARANCES: &[&str] = &[
+    "Murky Crimson Vial",
+    "Bubbling Azure Flask",
+    "Cloudy Grey Phial",
+    "Smoking Black Bottle",
+    "Glittering Gold Vial",
+    "Viscous Green Draught",
+    "Fizzing Violet Tonic",
+    "Milky White Phial",
+];
+
+const SCROLL_APPEARANCES: &[&str] = &[
+    "Scroll Inscribed VASH KORRIN",
+    "Scroll Inscribed THUL ODANE",
+    "Scroll Inscribed MORR ESKETH",
+    "Scroll Inscribed IN ABYSSUM",
+    "Scroll Inscribed SEK NUL TARA",
+    "Scroll Inscribed OSSAR VEY",
+];
+
+/// Per-run table of consumable appearances and which ones the player knows
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Discoveries {
+    /// Item name -> appearance for this run
+    appearances: HashMap<String, String>,
+    /// Item names the player has identified
+    known: HashSet<String>,
+}
+
+impl Discoveries {
+    /// Deal out appearances for a new run
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let mut appearances = HashMap::new();
+        for (kinds, pool) in [(POTION_KINDS, POTION_APPEARANCES), (SCROLL_KINDS, SCROLL_APPEARANCES)] {
+            let mut pool = pool.to_vec();
+            pool.shuffle(rng);
+            for (kind, appearance) in kinds.iter().zip(pool) {
+                appearances.insert(kind.to_string(), appearance.to_string());
+            }
+        }
+        Self { appearances, known: HashSet::new() }
+    }
+
+    /// Appearance of an item kind the player hasn't identified yet
+    pub fn appearance(&self, name: &str) -> Option<&str> {
+        if self.known.contains(name) {
+            return None;
+        }
+        self.appearances.get(name).map(|a| a.as_str())
+    }
+
+    pub fn is_known(&self, name: &str) -> bool {
+        self.appearance(name).is_none()
+    }
+
+    /// Mark an item kind as identified. Returns true if it wasn't known before.
+    pub fn learn(&mut self, name: &str) -> bool {
+        self.appearances.contains_key(name) && self.known.insert(name.to_string())
+    }
+
+    /// The item as the player sees it: unidentified kinds get their appearance,
+    /// a generic glyph and no description
+    pub fn disguise<'a>(&self, item: &'a Item) -> Cow<'a, Item> {
+        let Some(appearance) = self.appearance(&item.name) else {
+            return Cow::Borrowed(item);
+        };
+        let mut shown = item.clone();
+        shown.name = appearance.to_string();
+        shown.glyph = if SCROLL_KINDS.contains(&item.name.as_str()) { '?' } else { '!' };
+        shown.description = "You don't know what this does yet.".to_string();
+        Cow::Owned(shown)
+    }
+
+    /// Every appearance dealt this run with the kind behind it, if identified.
+    /// Potions first, then scrolls.
+    pub fn entries(&self) -> Vec<(&str, Option<&str>)> {
+        POTION_KINDS.iter()
+            .chain(SCROLL_KINDS)
+            .filter_map(|kind| {
+                let appearance = self.appearances.get(*kind)?;
+                let name = self.known.contains(*kind).then_some(*kind);
+                Some((appearance.as_str(), name))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::item::templates;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_discoveries() {
+        let mut discoveries = Discoveries::new(&mut StdRng::seed_from_u64(7));
+        let potion = templates::health_potion(1);
+
+        let appearance = discoveries.appearance("Health Potion").unwrap().to_string();
+        assert_eq!(discoveries.disguise(&potion).name, appearance);
+        assert_eq!(discoveries.entries().len(), POTION_KINDS.len() + SCROLL_KINDS.len());
+
+        // Same seed, same appearances
+        let again = Discoveries::new(&mut StdRng::seed_from_u64(7));
+        assert_eq!(again.appearance("Health Potion"), Some(appearance.as_str()));
+
+        assert!(discoveries.learn("Health Potion"));
+        assert!(!discoveries.learn("Health Potion"));
+        assert_eq!(discoveries.disguise(&potion).name, "Health Potion");
+
+        // Gear and unlisted consumables are always known
+        assert!(!discoveries.learn("Travel Ration"));
+        assert!(discoveries.is_known("Travel Ration"));
+    }
+}
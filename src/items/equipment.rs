@@ -4,9 +4,14 @@
 
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use super::item::{Item, EquipSlot, AffixType};
+use super::item::{Item, EquipSlot, AffixType, WeaponType, WeightClass};
 use super::synergies::{SynergyTag, SynergyBonuses, ActiveSynergy, calculate_synergies};
 
+/// Share of the off-hand weapon's damage dealt by its follow-up strike (percent)
+pub const DUAL_WIELD_DAMAGE_PERCENT: i32 = 60;
+/// Dexterity lost on the off-hand strike
+pub const DUAL_WIELD_DEX_PENALTY: i32 = 3;
+
 /// Player equipment slots
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Equipment {
@@ -21,13 +26,68 @@ impl Equipment {
         }
     }
 
-    /// Equip an item, returning the previously equipped item if any
-    pub fn equip(&mut self, item: Item) -> Option<Item> {
-        if let Some(slot) = item.equip_slot {
-            self.slots.insert(slot, item)
-        } else {
-            None // Item not equippable
+    /// Equip an item in its own slot, returning every item it displaced
+    pub fn equip(&mut self, item: Item) -> Vec<Item> {
+        match item.equip_slot {
+            Some(slot) => self.equip_to(slot, item),
+            None => vec![item], // Item not equippable
+        }
+    }
+
+    /// Equip an item in a specific slot, returning every item it displaced.
+    /// Two-handed weapons clear the off hand, and anything put in the off hand
+    /// clears a two-handed weapon. An item that doesn't fit is handed back.
+    pub fn equip_to(&mut self, slot: EquipSlot, item: Item) -> Vec<Item> {
+        if !item.fits_slot(slot) {
+            return vec![item];
+        }
+        let mut displaced = Vec::new();
+        if item.is_two_handed() {
+            displaced.extend(self.slots.remove(&EquipSlot::OffHand));
         }
+        if slot == EquipSlot::OffHand && self.is_two_handed() {
+            displaced.extend(self.slots.remove(&EquipSlot::MainHand));
+        }
+        displaced.extend(self.slots.insert(slot, item));
+        displaced
+    }
+
+    /// Whether a two-handed weapon fills both hands
+    pub fn is_two_handed(&self) -> bool {
+        self.get(EquipSlot::MainHand).is_some_and(|w| w.is_two_handed())
+    }
+
+    /// Weapon held in the off hand, if any
+    pub fn offhand_weapon(&self) -> Option<&Item> {
+        self.get(EquipSlot::OffHand).filter(|i| i.weapon_type.is_some())
+    }
+
+    /// Whether a weapon is held in each hand
+    pub fn is_dual_wielding(&self) -> bool {
+        self.get(EquipSlot::MainHand).is_some() && self.offhand_weapon().is_some()
+    }
+
+    /// Damage of the off-hand follow-up strike (0 if not dual wielding)
+    pub fn offhand_damage(&self) -> i32 {
+        if !self.is_dual_wielding() {
+            return 0;
+        }
+        self.offhand_weapon()
+            .map(|w| (w.total_damage() * DUAL_WIELD_DAMAGE_PERCENT / 100).max(1))
+            .unwrap_or(0)
+    }
+
+    /// Crit bonus of the off-hand weapon
+    pub fn offhand_crit_bonus(&self) -> f32 {
+        self.offhand_weapon()
+            .and_then(|w| w.weapon_type)
+            .map(|wt| wt.crit_bonus())
+            .unwrap_or(0.0)
+    }
+
+    /// Chance (0-100) to block an attack with the equipped shield
+    pub fn block_chance(&self) -> i32 {
+        self.get(EquipSlot::OffHand).map(|i| i.block_chance()).unwrap_or(0)
     }
 
     /// Unequip an item from a slot
@@ -69,6 +129,15 @@ impl Equipment {
             .sum()
     }
 
+    /// Overall weight class of the worn armor
+    pub fn weight_class(&self) -> WeightClass {
+        let load = self.slots.values()
+            .filter_map(|item| item.armor_type)
+            .map(|armor| armor.weight_class().load())
+            .sum();
+        WeightClass::from_load(load)
+    }
+
     /// Calculate total stat bonus
     pub fn stat_bonus(&self, stat: AffixType) -> i32 {
         self.slots.values()
@@ -83,6 +152,11 @@ impl Equipment {
             .unwrap_or(2) // Unarmed = 2 damage
     }
 
+    /// Type of the main hand weapon (None if unarmed)
+    pub fn weapon_type(&self) -> Option<WeaponType> {
+        self.get(EquipSlot::MainHand).and_then(|w| w.weapon_type)
+    }
+
     /// Get weapon crit bonus
     pub fn weapon_crit_bonus(&self) -> f32 {
         self.get(EquipSlot::MainHand)
@@ -188,3 +262,45 @@ impl EquipSlot {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::item::templates;
+
+    #[test]
+    fn test_two_handed_clears_both_hands() {
+        let mut equipment = Equipment::new();
+        equipment.equip(templates::iron_sword(1));
+        assert!(equipment.equip_to(EquipSlot::OffHand, templates::rusty_dagger(2)).is_empty());
+        assert!(equipment.is_dual_wielding());
+
+        let displaced = equipment.equip(templates::battle_axe(3));
+        assert_eq!(displaced.len(), 2);
+        assert!(equipment.is_two_handed());
+
+        // A shield pushes the two-hander out again
+        let displaced = equipment.equip(templates::wooden_shield(4));
+        assert_eq!(displaced[0].base_name, "Battle Axe");
+        assert!(equipment.get(EquipSlot::MainHand).is_none());
+        assert!(equipment.block_chance() > 0);
+    }
+
+    #[test]
+    fn test_weight_class_from_worn_armor() {
+        let mut equipment = Equipment::new();
+        assert_eq!(equipment.weight_class(), WeightClass::Light);
+
+        equipment.equip(templates::leather_armor(1));
+        equipment.equip(templates::chain_boots(2));
+        assert_eq!(equipment.weight_class(), WeightClass::Light);
+
+        equipment.equip(templates::chain_helm(3));
+        equipment.equip(templates::iron_shield(4));
+        assert_eq!(equipment.weight_class(), WeightClass::Medium);
+
+        equipment.equip(templates::knight_plate(5));
+        equipment.equip(templates::knight_helm(6));
+        assert_eq!(equipment.weight_class(), WeightClass::Heavy);
+    }
+}
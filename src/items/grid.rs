@@ -26,6 +26,8 @@ pub enum SortMode {
 /// Grid dimensions
 pub const GRID_WIDTH: usize = 8;
 pub const GRID_HEIGHT: usize = 6;
+/// Most rows bag upgrades can grow the grid to
+pub const MAX_GRID_HEIGHT: usize = 10;
 
 /// Position in the inventory grid
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -83,10 +85,11 @@ impl PlacedItem {
 }
 
 /// Grid-based inventory
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InventoryGrid {
-    /// Grid cells - each cell contains Option<ItemId> pointing to the item that occupies it
-    cells: [[Option<ItemId>; GRID_WIDTH]; GRID_HEIGHT],
+    /// Grid cells - each cell contains Option<ItemId> pointing to the item that occupies it.
+    /// Starts at GRID_HEIGHT rows; bag upgrades add more.
+    cells: Vec<[Option<ItemId>; GRID_WIDTH]>,
     /// Items stored by ID
     items: HashMap<ItemId, PlacedItem>,
 }
@@ -95,14 +98,29 @@ impl InventoryGrid {
     /// Create a new empty inventory grid
     pub fn new() -> Self {
         Self {
-            cells: [[None; GRID_WIDTH]; GRID_HEIGHT],
+            cells: vec![[None; GRID_WIDTH]; GRID_HEIGHT],
             items: HashMap::new(),
         }
     }
 
+    /// Number of rows in the grid
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Add rows to the grid, up to MAX_GRID_HEIGHT. Returns false if already at the limit.
+    pub fn expand(&mut self, rows: usize) -> bool {
+        let new_height = (self.cells.len() + rows).min(MAX_GRID_HEIGHT);
+        if new_height == self.cells.len() {
+            return false;
+        }
+        self.cells.resize(new_height, [None; GRID_WIDTH]);
+        true
+    }
+
     /// Check if a position is valid within the grid
     pub fn is_valid_position(&self, x: u8, y: u8) -> bool {
-        (x as usize) < GRID_WIDTH && (y as usize) < GRID_HEIGHT
+        (x as usize) < GRID_WIDTH && (y as usize) < self.cells.len()
     }
 
     /// Check if an item can be placed at a position
@@ -142,7 +160,7 @@ impl InventoryGrid {
         let (w, h) = item.grid_size;
 
         // Try without rotation first
-        for y in 0..GRID_HEIGHT as u8 {
+        for y in 0..self.cells.len() as u8 {
             for x in 0..GRID_WIDTH as u8 {
                 if self.can_place_at(x, y, w, h) {
                     return Some((GridPosition::new(x, y), false));
@@ -152,7 +170,7 @@ impl InventoryGrid {
 
         // Try with rotation if item isn't square
         if w != h {
-            for y in 0..GRID_HEIGHT as u8 {
+            for y in 0..self.cells.len() as u8 {
                 for x in 0..GRID_WIDTH as u8 {
                     if self.can_place_at(x, y, h, w) {
                         return Some((GridPosition::new(x, y), true));
@@ -325,7 +343,7 @@ impl InventoryGrid {
     }
 
     /// Get the cell contents (for rendering)
-    pub fn cells(&self) -> &[[Option<ItemId>; GRID_WIDTH]; GRID_HEIGHT] {
+    pub fn cells(&self) -> &[[Option<ItemId>; GRID_WIDTH]] {
         &self.cells
     }
 
@@ -465,7 +483,9 @@ impl InventoryGrid {
         }
 
         // Clear all cells
-        self.cells = [[None; GRID_WIDTH]; GRID_HEIGHT];
+        for row in &mut self.cells {
+            *row = [None; GRID_WIDTH];
+        }
 
         // Re-add all items
         for item in items {
@@ -491,6 +511,12 @@ impl InventoryGrid {
     }
 }
 
+impl Default for InventoryGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -537,7 +563,14 @@ mod tests {
 
         // Should be full now
         let extra = make_test_item(999, 1, 1);
-        assert!(!grid.add_item(extra));
+        assert!(!grid.add_item(extra.clone()));
+
+        // A bag upgrade makes room, but only up to the limit
+        assert!(grid.expand(1));
+        assert!(grid.add_item(extra));
+        assert!(grid.expand(MAX_GRID_HEIGHT));
+        assert_eq!(grid.height(), MAX_GRID_HEIGHT);
+        assert!(!grid.expand(1));
     }
 
     #[test]
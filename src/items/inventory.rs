@@ -3,8 +3,14 @@
 //! Manages player's item collection using a grid-based system (RE4 style).
 
 use serde::{Deserialize, Serialize};
-use super::item::{Item, ItemId, ItemCategory};
-use super::grid::{InventoryGrid, PlacedItem, GRID_WIDTH, GRID_HEIGHT, SortMode};
+use super::item::{Item, ItemId, ItemCategory, ConsumableEffect};
+use super::grid::{InventoryGrid, PlacedItem, GRID_WIDTH, SortMode};
+use super::belt::Belt;
+
+/// Weight the pack holds before the bearer is overloaded
+pub fn carry_capacity(strength: i32) -> u32 {
+    (20 + strength.max(0) * 2) as u32
+}
 
 /// Player inventory using a grid-based system
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -13,6 +19,9 @@ pub struct Inventory {
     grid: InventoryGrid,
     /// Gold currency
     gold: u32,
+    /// Consumables on the quick-use belt
+    #[serde(default)]
+    belt: Belt,
 }
 
 impl Inventory {
@@ -21,6 +30,7 @@ impl Inventory {
         Self {
             grid: InventoryGrid::new(),
             gold: 0,
+            belt: Belt::new(),
         }
     }
 
@@ -30,13 +40,13 @@ impl Inventory {
     }
 
     /// Get grid dimensions
-    pub fn grid_size() -> (usize, usize) {
-        (GRID_WIDTH, GRID_HEIGHT)
+    pub fn grid_size(&self) -> (usize, usize) {
+        (GRID_WIDTH, self.grid.height())
     }
 
     /// Get total cell capacity
     pub fn capacity(&self) -> usize {
-        GRID_WIDTH * GRID_HEIGHT
+        GRID_WIDTH * self.grid.height()
     }
 
     /// Check if inventory has any space
@@ -49,6 +59,44 @@ impl Inventory {
         self.grid.has_space()
     }
 
+    /// The quick-use belt
+    pub fn belt(&self) -> &Belt {
+        &self.belt
+    }
+
+    /// The quick-use belt, for assigning slots
+    pub fn belt_mut(&mut self) -> &mut Belt {
+        &mut self.belt
+    }
+
+    /// How many of an item the pack holds, across all its stacks
+    pub fn count_named(&self, name: &str) -> u32 {
+        self.grid.items().into_iter()
+            .filter(|i| i.name == name)
+            .map(|i| i.stack_count.max(1))
+            .sum()
+    }
+
+    /// Index (in `items()` order) of the first stack of an item
+    pub fn index_of_named(&self, name: &str) -> Option<usize> {
+        self.grid.items().iter().position(|i| i.name == name)
+    }
+
+    /// Sew extra rows onto the pack. Returns false if it can't grow any further.
+    pub fn expand(&mut self, rows: usize) -> bool {
+        self.grid.expand(rows)
+    }
+
+    /// Total weight of everything in the pack
+    pub fn total_weight(&self) -> u32 {
+        self.grid.items().iter().map(|i| i.weight()).sum()
+    }
+
+    /// Number of empty grid cells
+    pub fn free_cells(&self) -> usize {
+        self.cells().iter().flatten().filter(|c| c.is_none()).count()
+    }
+
     /// Check if a specific item can fit
     pub fn can_fit(&self, item: &Item) -> bool {
         self.grid.find_space_for(item).is_some()
@@ -173,6 +221,51 @@ impl Inventory {
         }
     }
 
+    /// Use up one key, returning it if there was one to use
+    pub fn consume_key(&mut self) -> Option<Item> {
+        self.consume_first(|i| i.category == ItemCategory::Key)
+    }
+
+    /// Eat one ration, returning it if there was one to eat
+    pub fn consume_ration(&mut self) -> Option<Item> {
+        self.consume_first(|i| i.consumable_effect == Some(ConsumableEffect::Ration))
+    }
+
+    /// Take one item from the first stack matching `pred`
+    fn consume_first(&mut self, pred: impl Fn(&Item) -> bool) -> Option<Item> {
+        let id = self.grid.items().into_iter()
+            .find(|i| pred(i))?
+            .id;
+        let item = self.grid.get_by_id_mut(id)?;
+
+        if item.stack_count > 1 {
+            item.stack_count -= 1;
+            let mut consumed = item.clone();
+            consumed.stack_count = 1;
+            Some(consumed)
+        } else {
+            self.grid.remove(id)
+        }
+    }
+
+    /// Fill every wand in the pack back up. Returns how many gained charges.
+    pub fn recharge_wands(&mut self) -> usize {
+        let ids: Vec<ItemId> = self.items_of_category(ItemCategory::Wand)
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        ids.into_iter()
+            .filter(|id| self.grid.get_by_id_mut(*id).is_some_and(|w| w.recharge()))
+            .count()
+    }
+
+    /// First wand that still has a charge
+    pub fn first_charged_wand(&self) -> Option<&Item> {
+        self.items_of_category(ItemCategory::Wand)
+            .into_iter()
+            .find(|w| w.charges > 0)
+    }
+
     /// Get current gold
     pub fn gold(&self) -> u32 {
         self.gold
@@ -229,7 +322,7 @@ impl Inventory {
     }
 
     /// Get the grid cells for rendering
-    pub fn cells(&self) -> &[[Option<ItemId>; GRID_WIDTH]; GRID_HEIGHT] {
+    pub fn cells(&self) -> &[[Option<ItemId>; GRID_WIDTH]] {
         self.grid.cells()
     }
 }
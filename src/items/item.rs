@@ -4,6 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 use super::synergies::SynergyTag;
+use super::wand::WandKind;
 
 /// Unique item ID for tracking
 pub type ItemId = u64;
@@ -81,6 +82,7 @@ pub enum ItemCategory {
     Armor,
     Accessory,
     Consumable,
+    Wand,
     Key,
     Lore,
 }
@@ -93,9 +95,10 @@ impl ItemCategory {
             ItemCategory::Weapon => 0,
             ItemCategory::Armor => 1,
             ItemCategory::Accessory => 2,
-            ItemCategory::Key => 3,
-            ItemCategory::Consumable => 4,
-            ItemCategory::Lore => 5,
+            ItemCategory::Wand => 3,
+            ItemCategory::Key => 4,
+            ItemCategory::Consumable => 5,
+            ItemCategory::Lore => 6,
         }
     }
 
@@ -128,6 +131,8 @@ pub enum WeaponType {
     Mace,       // Crushing: good damage, high armor penetration
     Staff,      // Arcane: magic damage scaling (INT based)
     Bow,        // Ranged: can attack from distance, good crit
+    Spear,      // Piercing: thrusts through to the foe behind
+    Whip,       // Lashing: strikes at range 2, entangles
 }
 
 impl WeaponType {
@@ -139,6 +144,8 @@ impl WeaponType {
             WeaponType::Mace => 10,
             WeaponType::Staff => 6,
             WeaponType::Bow => 7,
+            WeaponType::Spear => 9,
+            WeaponType::Whip => 5,
         }
     }
 
@@ -151,6 +158,8 @@ impl WeaponType {
             WeaponType::Mace => 12,     // Moderate
             WeaponType::Staff => 8,     // Light
             WeaponType::Bow => 8,       // Moderate
+            WeaponType::Spear => 12,    // Long thrusts
+            WeaponType::Whip => 7,      // Light
         }
     }
 
@@ -170,6 +179,7 @@ impl WeaponType {
             WeaponType::Mace => 40,     // Crushing weapons bypass armor
             WeaponType::Axe => 20,      // Heavy cleaving
             WeaponType::Dagger => 15,   // Finds gaps in armor
+            WeaponType::Spear => 10,    // Driven point
             _ => 0,
         }
     }
@@ -179,9 +189,28 @@ impl WeaponType {
             WeaponType::Dagger => 15.0, // +15% crit chance
             WeaponType::Sword => 5.0,
             WeaponType::Bow => 10.0,    // Ranged precision
+            WeaponType::Whip => 5.0,
             _ => 0.0,
         }
     }
+
+    /// How far away a melee attack can land
+    pub fn reach(&self) -> i32 {
+        match self {
+            WeaponType::Whip => 2,
+            _ => 1,
+        }
+    }
+
+    /// Whether attacks also strike the tile behind the target
+    pub fn pierces(&self) -> bool {
+        matches!(self, WeaponType::Spear)
+    }
+
+    /// Whether attacks on unaware enemies are backstabs
+    pub fn can_backstab(&self) -> bool {
+        matches!(self, WeaponType::Dagger)
+    }
 }
 
 /// Armor subtypes
@@ -194,6 +223,15 @@ pub enum ArmorType {
 }
 
 impl ArmorType {
+    /// How heavy this armor is to move and fight in
+    pub fn weight_class(&self) -> WeightClass {
+        match self {
+            ArmorType::Cloth | ArmorType::Leather => WeightClass::Light,
+            ArmorType::Chain => WeightClass::Medium,
+            ArmorType::Plate => WeightClass::Heavy,
+        }
+    }
+
     pub fn base_armor(&self, slot: EquipSlot) -> i32 {
         let base = match self {
             ArmorType::Cloth => 1,
@@ -211,6 +249,94 @@ impl ArmorType {
     }
 }
 
+/// Armor weight classes: heavier armor protects more but slows you down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum WeightClass {
+    Light,
+    Medium,
+    Heavy,
+}
+
+impl WeightClass {
+    /// Total load at which worn armor counts as medium
+    pub const MEDIUM_LOAD: i32 = 6;
+    /// Total load at which worn armor counts as heavy
+    pub const HEAVY_LOAD: i32 = 12;
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeightClass::Light => "Light",
+            WeightClass::Medium => "Medium",
+            WeightClass::Heavy => "Heavy",
+        }
+    }
+
+    /// Load a single piece of this class adds
+    pub fn load(&self) -> i32 {
+        match self {
+            WeightClass::Light => 1,
+            WeightClass::Medium => 2,
+            WeightClass::Heavy => 4,
+        }
+    }
+
+    /// Overall class for a total armor load
+    pub fn from_load(load: i32) -> Self {
+        if load >= Self::HEAVY_LOAD {
+            WeightClass::Heavy
+        } else if load >= Self::MEDIUM_LOAD {
+            WeightClass::Medium
+        } else {
+            WeightClass::Light
+        }
+    }
+
+    /// Dodge chance modifier (percentage points)
+    pub fn dodge_modifier(&self) -> f32 {
+        match self {
+            WeightClass::Light => 5.0,
+            WeightClass::Medium => 0.0,
+            WeightClass::Heavy => -5.0,
+        }
+    }
+
+    /// Extra range at which clanking armor draws enemies
+    pub fn noise(&self) -> i32 {
+        match self {
+            WeightClass::Light => -1,
+            WeightClass::Medium => 0,
+            WeightClass::Heavy => 2,
+        }
+    }
+
+    /// Stamina regeneration multiplier
+    pub fn stamina_regen_multiplier(&self) -> f32 {
+        match self {
+            WeightClass::Light => 1.25,
+            WeightClass::Medium => 1.0,
+            WeightClass::Heavy => 0.6,
+        }
+    }
+
+    /// Extra stamina spent per sprinting turn
+    pub fn sprint_cost_modifier(&self) -> i32 {
+        match self {
+            WeightClass::Light => 0,
+            WeightClass::Medium => 1,
+            WeightClass::Heavy => 3,
+        }
+    }
+
+    /// Change to the range of movement skills
+    pub fn movement_range_modifier(&self) -> i32 {
+        match self {
+            WeightClass::Light => 1,
+            WeightClass::Medium => 0,
+            WeightClass::Heavy => -1,
+        }
+    }
+}
+
 /// Consumable effects
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConsumableEffect {
@@ -222,7 +348,26 @@ pub enum ConsumableEffect {
     BuffIntelligence(i32, u32),
     CurePoison,
     Teleport,
+    /// Carries the user back to the floor's entrance
+    Return,
     RevealMap,
+    /// Grants a random body mutation
+    Mutate,
+    /// Food that speeds up recovery while resting
+    Ration,
+    /// Adds rows to the inventory grid
+    ExpandPack(u8),
+    /// Refills every wand in the pack
+    RechargeWands,
+    /// Sets bones and heals every injury
+    TreatInjuries,
+}
+
+/// What an unidentified item is hiding until a merchant identifies it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Concealed {
+    pub name: String,
+    pub affixes: Vec<Affix>,
 }
 
 /// Item affixes (magical properties)
@@ -284,6 +429,13 @@ pub enum AffixType {
     AscendedPower,
     /// Health regeneration per turn
     Regeneration,
+
+    // ===== WAND-ONLY AFFIXES =====
+
+    /// Bonus bolt damage
+    WandPower,
+    /// Bonus maximum charges
+    WandCapacity,
 }
 
 impl AffixType {
@@ -295,7 +447,8 @@ impl AffixType {
             AffixType::BonusArmor | AffixType::BonusHP |
             // Mythic prefixes
             AffixType::AllStats | AffixType::DamageReduction |
-            AffixType::Thorns | AffixType::Regeneration
+            AffixType::Thorns | AffixType::Regeneration |
+            AffixType::WandPower
         )
     }
 
@@ -342,6 +495,9 @@ impl AffixType {
             AffixType::ResourceConservation => "of Efficiency",
             AffixType::AscendedPower => "Ascended",
             AffixType::Regeneration => "Regenerating",
+            // Wand affixes
+            AffixType::WandPower => "Potent",
+            AffixType::WandCapacity => "of Plenty",
         }
     }
 
@@ -379,6 +535,8 @@ impl AffixType {
             AffixType::ResourceConservation => "% chance to not consume MP/SP",
             AffixType::AscendedPower => "Damage scales with floor depth",
             AffixType::Regeneration => "Regenerate HP each turn",
+            AffixType::WandPower => "Increases wand bolt damage",
+            AffixType::WandCapacity => "Increases wand charges",
         }
     }
 }
@@ -508,6 +666,18 @@ pub struct Item {
     /// Whether this item is newly picked up (not yet viewed)
     #[serde(default)]
     pub is_new: bool,
+    /// Favorited by the player - can't be destroyed, sold or sacrificed
+    #[serde(default)]
+    pub locked: bool,
+    /// Real name and affixes, hidden until identified. Concealed affixes have no effect.
+    #[serde(default)]
+    pub unidentified: Option<Concealed>,
+    /// Bolt fired when zapped (if wand)
+    #[serde(default)]
+    pub wand: Option<WandKind>,
+    /// Charges left (wands)
+    #[serde(default)]
+    pub charges: u8,
 
     // ===== ENDGAME ENHANCEMENT FIELDS =====
 
@@ -554,6 +724,10 @@ impl Item {
             value: 1,
             max_enchantments: 5,
             is_new: true,
+            locked: false,
+            unidentified: None,
+            wand: None,
+            charges: 0,
             // Endgame enhancement defaults
             enchantment_level: 0,
             awakening_level: 0,
@@ -567,6 +741,38 @@ impl Item {
         self.is_new = false;
     }
 
+    /// Whether the item's name and affixes are known
+    pub fn is_identified(&self) -> bool {
+        self.unidentified.is_none()
+    }
+
+    /// Hide the item's name and affixes until it is identified
+    pub fn conceal(&mut self) {
+        if self.unidentified.is_some() {
+            return;
+        }
+        let name = std::mem::replace(&mut self.name, format!("Unidentified {}", self.base_name));
+        let affixes = std::mem::take(&mut self.affixes);
+        self.unidentified = Some(Concealed { name, affixes });
+    }
+
+    /// Reveal a concealed item. Affixes gained while unidentified are kept.
+    /// Returns false if there was nothing to reveal.
+    pub fn identify(&mut self) -> bool {
+        let Some(concealed) = self.unidentified.take() else {
+            return false;
+        };
+        let gained = std::mem::replace(&mut self.affixes, concealed.affixes);
+        self.affixes.extend(gained);
+        self.name = concealed.name;
+        true
+    }
+
+    /// Marker shown before the name of a locked item
+    pub fn lock_marker(&self) -> &'static str {
+        if self.locked { "★ " } else { "" }
+    }
+
     /// Get enchantment multiplier (1.0 + 0.1 per level)
     pub fn enchantment_multiplier(&self) -> f32 {
         1.0 + (self.enchantment_level as f32 * 0.10)
@@ -784,11 +990,96 @@ impl Item {
             .sum()
     }
 
+    /// Most charges the wand holds, counting affixes
+    pub fn max_charges(&self) -> u8 {
+        let Some(kind) = self.wand else { return 0 };
+        (kind.base_charges() as i32 + self.stat_bonus(AffixType::WandCapacity)).clamp(1, u8::MAX as i32) as u8
+    }
+
+    /// Bolt damage, counting affixes
+    pub fn zap_damage(&self) -> i32 {
+        self.wand.map_or(0, |kind| kind.base_damage() + self.stat_bonus(AffixType::WandPower))
+    }
+
+    /// Spend a charge. Returns false if the wand is empty.
+    pub fn use_charge(&mut self) -> bool {
+        if self.wand.is_none() || self.charges == 0 {
+            return false;
+        }
+        self.charges -= 1;
+        true
+    }
+
+    /// Refill a wand. Returns false if it was already full.
+    pub fn recharge(&mut self) -> bool {
+        let max = self.max_charges();
+        if self.charges >= max {
+            return false;
+        }
+        self.charges = max;
+        true
+    }
+
+    /// "3/5 charges" for wands
+    pub fn charge_label(&self) -> Option<String> {
+        self.wand.map(|_| format!("{}/{} charges", self.charges, self.max_charges()))
+    }
+
     /// Check if item is equippable
     pub fn is_equippable(&self) -> bool {
         self.equip_slot.is_some()
     }
 
+    /// Whether a weapon needs both hands (occupies the off hand too)
+    pub fn is_two_handed(&self) -> bool {
+        self.synergy_tags.contains(&SynergyTag::TwoHanded)
+            || matches!(self.weapon_type, Some(WeaponType::Bow) | Some(WeaponType::Staff))
+    }
+
+    /// Whether the item is a shield (off-hand armor)
+    pub fn is_shield(&self) -> bool {
+        self.equip_slot == Some(EquipSlot::OffHand) && self.armor_type.is_some()
+    }
+
+    /// Chance (0-100) for a shield to block an attack outright
+    pub fn block_chance(&self) -> i32 {
+        if !self.is_shield() {
+            return 0;
+        }
+        match self.armor_type {
+            Some(ArmorType::Cloth) => 5,
+            Some(ArmorType::Leather) => 10,
+            Some(ArmorType::Chain) => 15,
+            Some(ArmorType::Plate) => 20,
+            None => 0,
+        }
+    }
+
+    /// Whether the item can go in a slot (one-handed weapons fit the off hand)
+    pub fn fits_slot(&self, slot: EquipSlot) -> bool {
+        self.equip_slot == Some(slot)
+            || (slot == EquipSlot::OffHand
+                && self.equip_slot == Some(EquipSlot::MainHand)
+                && self.weapon_type.is_some()
+                && !self.is_two_handed())
+    }
+
+    /// Carrying weight of the whole stack, for the optional encumbrance rules
+    pub fn weight(&self) -> u32 {
+        let cells = self.grid_size.0 as u32 * self.grid_size.1 as u32;
+        let each = match self.category {
+            ItemCategory::Weapon => cells * 2,
+            ItemCategory::Armor => cells * match self.armor_type.map(|a| a.weight_class()) {
+                Some(WeightClass::Heavy) => 4,
+                Some(WeightClass::Medium) => 2,
+                _ => 1,
+            },
+            ItemCategory::Key => 0,
+            ItemCategory::Accessory | ItemCategory::Consumable | ItemCategory::Wand | ItemCategory::Lore => 1,
+        };
+        each * self.stack_count.max(1)
+    }
+
     /// Check if item is consumable
     pub fn is_consumable(&self) -> bool {
         self.consumable_effect.is_some()
@@ -799,6 +1090,15 @@ impl Item {
         self.max_stack > 1
     }
 
+    /// Synergy tags as a comma-separated list for tooltips
+    pub fn synergy_summary(&self) -> Option<String> {
+        let tags = self.all_synergy_tags();
+        if tags.is_empty() {
+            return None;
+        }
+        Some(tags.iter().map(|t| t.name()).collect::<Vec<_>>().join(", "))
+    }
+
     /// Get all synergy tags (base + from affixes)
     pub fn all_synergy_tags(&self) -> Vec<SynergyTag> {
         let mut tags = self.synergy_tags.clone();
@@ -900,6 +1200,31 @@ pub mod templates {
         item
     }
 
+    pub fn iron_spear(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Iron Spear", ItemCategory::Weapon);
+        item.equip_slot = Some(EquipSlot::MainHand);
+        item.weapon_type = Some(WeaponType::Spear);
+        item.base_damage = WeaponType::Spear.base_damage();
+        item.glyph = '↑';
+        item.grid_size = (1, 3);
+        item.value = 60;
+        item.description = "A long spear that runs one foe into the next.".to_string();
+        item.synergy_tags = vec![SynergyTag::TwoHanded];
+        item
+    }
+
+    pub fn leather_whip(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Leather Whip", ItemCategory::Weapon);
+        item.equip_slot = Some(EquipSlot::MainHand);
+        item.weapon_type = Some(WeaponType::Whip);
+        item.base_damage = WeaponType::Whip.base_damage();
+        item.glyph = '§';
+        item.grid_size = (1, 2);
+        item.value = 40;
+        item.description = "A braided lash that bites from two paces away.".to_string();
+        item
+    }
+
     pub fn leather_armor(id: ItemId) -> Item {
         let mut item = Item::new(id, "Leather Armor", ItemCategory::Armor);
         item.equip_slot = Some(EquipSlot::Body);
@@ -950,6 +1275,124 @@ pub mod templates {
         item
     }
 
+    pub fn mutagenic_vial(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Mutagenic Vial", ItemCategory::Consumable);
+        item.consumable_effect = Some(ConsumableEffect::Mutate);
+        item.glyph = '⚱';
+        item.grid_size = (1, 1);
+        item.max_stack = 5;
+        item.value = 60;
+        item.description = "Black ichor from the Abyss. Your body will never be the same.".to_string();
+        item.rarity = Rarity::Rare;
+        item
+    }
+
+    pub fn travel_ration(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Travel Ration", ItemCategory::Consumable);
+        item.consumable_effect = Some(ConsumableEffect::Ration);
+        item.glyph = '%';
+        item.grid_size = (1, 1);
+        item.max_stack = 10;
+        item.value = 10;
+        item.description = "Hard bread and dried meat. Eaten while resting to recover faster.".to_string();
+        item.rarity = Rarity::Common;
+        item
+    }
+
+    pub fn scroll_of_teleportation(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Scroll of Teleportation", ItemCategory::Consumable);
+        item.consumable_effect = Some(ConsumableEffect::Teleport);
+        item.glyph = '📜';
+        item.grid_size = (1, 1);
+        item.max_stack = 5;
+        item.value = 40;
+        item.description = "Whisks you away to a random spot on this floor.".to_string();
+        item.rarity = Rarity::Uncommon;
+        item
+    }
+
+    pub fn scroll_of_return(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Scroll of Return", ItemCategory::Consumable);
+        item.consumable_effect = Some(ConsumableEffect::Return);
+        item.glyph = '📜';
+        item.grid_size = (1, 1);
+        item.max_stack = 5;
+        item.value = 45;
+        item.description = "Pulls you back to where you entered this floor. There's no going the other way.".to_string();
+        item.rarity = Rarity::Uncommon;
+        item
+    }
+
+    pub fn scroll_of_mapping(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Scroll of Mapping", ItemCategory::Consumable);
+        item.consumable_effect = Some(ConsumableEffect::RevealMap);
+        item.glyph = '📜';
+        item.grid_size = (1, 1);
+        item.max_stack = 5;
+        item.value = 50;
+        item.description = "Etches the layout of this floor into your memory.".to_string();
+        item.rarity = Rarity::Uncommon;
+        item
+    }
+
+    pub fn scroll_of_recharging(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Scroll of Recharging", ItemCategory::Consumable);
+        item.consumable_effect = Some(ConsumableEffect::RechargeWands);
+        item.glyph = '📜';
+        item.grid_size = (1, 1);
+        item.max_stack = 5;
+        item.value = 80;
+        item.description = "Crackling runes that refill every wand you carry.".to_string();
+        item.rarity = Rarity::Uncommon;
+        item
+    }
+
+    pub fn bonesetters_salve(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Bonesetter's Salve", ItemCategory::Consumable);
+        item.consumable_effect = Some(ConsumableEffect::TreatInjuries);
+        item.glyph = '!';
+        item.grid_size = (1, 1);
+        item.max_stack = 3;
+        item.value = 150;
+        item.description = "A pungent poultice that knits bone and clears the head.".to_string();
+        item.rarity = Rarity::Rare;
+        item
+    }
+
+    pub fn wand(id: ItemId, kind: WandKind) -> Item {
+        let mut item = Item::new(id, kind.name(), ItemCategory::Wand);
+        item.wand = Some(kind);
+        item.charges = kind.base_charges();
+        item.glyph = '/';
+        item.grid_size = (1, 2);
+        item.value = 120;
+        item.description = kind.description().to_string();
+        item
+    }
+
+    pub fn sturdy_satchel(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Sturdy Satchel", ItemCategory::Consumable);
+        item.consumable_effect = Some(ConsumableEffect::ExpandPack(1));
+        item.glyph = '⊞';
+        item.grid_size = (2, 1);
+        item.max_stack = 1;
+        item.value = 150;
+        item.description = "Straps and stitching enough to add another row to your pack.".to_string();
+        item.rarity = Rarity::Uncommon;
+        item
+    }
+
+    pub fn iron_key(id: ItemId) -> Item {
+        let mut item = Item::new(id, "Iron Key", ItemCategory::Key);
+        item.glyph = '⚷';
+        item.grid_size = (1, 1);
+        item.max_stack = 5;
+        item.value = 5;
+        item.description = "Opens one locked door. Snaps off in the lock.".to_string();
+        item.rarity = Rarity::Common;
+        item
+    }
+
     // Synergy-themed items
     pub fn flame_sword(id: ItemId) -> Item {
         let mut item = Item::new(id, "Flame Sword", ItemCategory::Weapon);
@@ -1184,4 +1627,19 @@ pub mod templates {
         item.synergy_tags = vec![SynergyTag::Knight];
         item
     }
+    /// One of every template, for tables keyed by base name
+    pub fn catalogue() -> Vec<Item> {
+        let mut items = vec![
+            iron_sword(0), rusty_dagger(0), battle_axe(0), iron_spear(0), leather_whip(0),
+            leather_armor(0), chain_helm(0), health_potion(0), mana_potion(0), mutagenic_vial(0),
+            travel_ration(0), scroll_of_teleportation(0), scroll_of_return(0), scroll_of_mapping(0),
+            scroll_of_recharging(0), bonesetters_salve(0), sturdy_satchel(0), iron_key(0),
+            flame_sword(0), frost_dagger(0), cultist_robe(0), cultist_dagger(0), knight_helm(0),
+            knight_plate(0), shadow_cloak(0), venom_blade(0), corrupted_gauntlets(0),
+            leather_boots(0), chain_boots(0), leather_gloves(0), wooden_shield(0), iron_shield(0),
+            bone_ring(0), copper_amulet(0), silver_ring(0),
+        ];
+        items.extend(WandKind::ALL.map(|kind| wand(0, kind)));
+        items
+    }
 }
@@ -4,6 +4,7 @@
 
 use rand::Rng;
 use super::item::{Item, ItemId, Rarity, Affix, AffixType, templates};
+use super::wand::WandKind;
 
 /// Counter for generating unique item IDs
 static mut NEXT_ITEM_ID: ItemId = 1;
@@ -126,7 +127,7 @@ pub fn rarity_stat_bonus(rarity: Rarity, rng: &mut impl Rng) -> i32 {
 
 /// Generate a random affix with value scaled by rarity
 pub fn roll_affix_with_rarity(rng: &mut impl Rng, for_weapon: bool, rarity: Rarity) -> Affix {
-    let possible_affixes = if for_weapon {
+    let mut possible_affixes = if for_weapon {
         vec![
             (AffixType::BonusDamage, 2, 8),
             (AffixType::BonusCritChance, 3, 10),
@@ -154,9 +155,32 @@ pub fn roll_affix_with_rarity(rng: &mut impl Rng, for_weapon: bool, rarity: Rari
             (AffixType::BonusVitality, 1, 5),
         ]
     };
+    // Mythic armor can carry a passive aura
+    if !for_weapon && rarity.can_have_mythic_affixes() {
+        possible_affixes.extend([(AffixType::Thorns, 5, 10), (AffixType::Regeneration, 1, 1)]);
+    }
+
+    let (affix_type, min_val, max_val) = possible_affixes[rng.gen_range(0..possible_affixes.len())];
+    let value = roll_affix_value(rng, min_val, max_val, rarity);
+
+    Affix { affix_type, value }
+}
+
+/// Generate a random wand affix with value scaled by rarity
+pub fn roll_wand_affix(rng: &mut impl Rng, rarity: Rarity) -> Affix {
+    let possible_affixes = [
+        (AffixType::WandPower, 2, 6),
+        (AffixType::WandCapacity, 1, 2),
+    ];
 
     let (affix_type, min_val, max_val) = possible_affixes[rng.gen_range(0..possible_affixes.len())];
+    let value = roll_affix_value(rng, min_val, max_val, rarity);
+
+    Affix { affix_type, value }
+}
 
+/// Roll an affix value, with higher rarities rolling higher
+fn roll_affix_value(rng: &mut impl Rng, min_val: i32, max_val: i32, rarity: Rarity) -> i32 {
     // Scale the roll range based on rarity - higher rarity rolls MUCH higher values
     let (scaled_min, scaled_max) = match rarity {
         Rarity::Common => (min_val, (min_val + max_val) / 2),           // Low range (50% of max)
@@ -167,9 +191,22 @@ pub fn roll_affix_with_rarity(rng: &mut impl Rng, for_weapon: bool, rarity: Rari
         Rarity::Mythic => (max_val * 2, max_val * 4),                    // 200-400% of max
     };
 
-    let value = rng.gen_range(scaled_min..=scaled_max);
+    rng.gen_range(scaled_min..=scaled_max)
+}
 
-    Affix { affix_type, value }
+/// Reroll all affixes on an item, keeping at least as many as its rarity grants
+pub fn reroll_affixes(item: &mut Item, rng: &mut impl Rng) {
+    let count = item.affixes.len().max(affixes_for_rarity(item.rarity));
+    let for_weapon = item.weapon_type.is_some();
+    let is_wand = item.wand.is_some();
+    item.affixes = (0..count)
+        .map(|_| if is_wand {
+            roll_wand_affix(rng, item.rarity)
+        } else {
+            roll_affix_with_rarity(rng, for_weapon, item.rarity)
+        })
+        .collect();
+    item.generate_name();
 }
 
 /// Generate a random affix (legacy, defaults to Uncommon scaling)
@@ -187,6 +224,8 @@ pub fn generate_weapon(floor: u32, rng: &mut impl Rng) -> Item {
         0 => templates::iron_sword(id),
         1 => templates::rusty_dagger(id),
         2 => templates::battle_axe(id),
+        3 => templates::iron_spear(id),
+        4 => templates::leather_whip(id),
         _ => templates::iron_sword(id), // Default to sword
     };
 
@@ -274,11 +313,48 @@ pub fn generate_armor(floor: u32, rng: &mut impl Rng) -> Item {
 pub fn generate_consumable(rng: &mut impl Rng) -> Item {
     let id = next_item_id();
 
-    if rng.gen_bool(0.7) {
-        templates::health_potion(id)
-    } else {
-        templates::mana_potion(id)
+    match rng.gen_range(0..26) {
+        0 => templates::mutagenic_vial(id),
+        1..=11 => templates::health_potion(id),
+        12..=16 => templates::mana_potion(id),
+        17..=19 => templates::travel_ration(id),
+        20..=21 => templates::scroll_of_teleportation(id),
+        22 => templates::scroll_of_mapping(id),
+        23 => templates::scroll_of_recharging(id),
+        24 => templates::scroll_of_return(id),
+        _ => templates::bonesetters_salve(id),
+    }
+}
+
+/// Generate a random wand, fully charged
+pub fn generate_wand(floor: u32, rng: &mut impl Rng) -> Item {
+    let kind = match rng.gen_range(0..4) {
+        0 => WandKind::Firebolt,
+        1 => WandKind::Frost,
+        2 => WandKind::Lightning,
+        _ => WandKind::Force,
+    };
+    let mut item = templates::wand(next_item_id(), kind);
+
+    // Wands top out at two affixes so they stay a side arm
+    let rarity = roll_rarity(floor, rng);
+    item.rarity = rarity;
+    for _ in 0..affixes_for_rarity(rarity).min(2) {
+        item.affixes.push(roll_wand_affix(rng, rarity));
     }
+    item.generate_name();
+    item.recharge();
+
+    item.value = match rarity {
+        Rarity::Common => item.value,
+        Rarity::Uncommon => item.value * 2,
+        Rarity::Rare => item.value * 3,
+        Rarity::Epic => item.value * 5,
+        Rarity::Legendary => item.value * 8,
+        Rarity::Mythic => item.value * 12,
+    };
+
+    item
 }
 
 /// Generate random loot for an enemy kill
@@ -318,6 +394,8 @@ pub fn generate_floor_loot(floor: u32, count: usize, rng: &mut impl Rng) -> Vec<
 
         if roll < 40 {
             loot.push(generate_consumable(rng));
+        } else if roll < 45 {
+            loot.push(generate_wand(floor, rng));
         } else if roll < 70 {
             loot.push(generate_weapon(floor, rng));
         } else {
@@ -328,13 +406,6 @@ pub fn generate_floor_loot(floor: u32, count: usize, rng: &mut impl Rng) -> Vec<
     loot
 }
 
-/// Generate gold drop amount based on floor
-pub fn generate_gold_drop(floor: u32, rng: &mut impl Rng) -> u32 {
-    let base = 5 + floor * 3;
-    let variance = rng.gen_range(0..=base / 2);
-    base + variance
-}
-
 /// Get minimum rarity based on floor level
 /// Floor 1-4: Common drops
 /// Floor 5-9: Minimum Uncommon
@@ -374,6 +445,8 @@ pub fn generate_weapon_with_min_rarity(floor: u32, min_rarity: Rarity, rng: &mut
         0 => templates::iron_sword(id),
         1 => templates::rusty_dagger(id),
         2 => templates::battle_axe(id),
+        3 => templates::iron_spear(id),
+        4 => templates::leather_whip(id),
         _ => templates::iron_sword(id),
     };
 
@@ -448,6 +521,40 @@ pub fn generate_armor_with_min_rarity(floor: u32, min_rarity: Rarity, rng: &mut
     item
 }
 
+/// Generate a ring or amulet with minimum rarity
+pub fn generate_jewelry_with_min_rarity(floor: u32, min_rarity: Rarity, rng: &mut impl Rng) -> Item {
+    let id = next_item_id();
+
+    let mut item = match rng.gen_range(0..3) {
+        0 => templates::bone_ring(id),
+        1 => templates::silver_ring(id),
+        _ => templates::copper_amulet(id),
+    };
+
+    let rarity = roll_rarity_with_minimum(floor, min_rarity, rng);
+    item.rarity = rarity;
+
+    item.base_armor += rarity_stat_bonus(rarity, rng);
+
+    let num_affixes = affixes_for_rarity(rarity);
+    for _ in 0..num_affixes {
+        item.affixes.push(roll_affix_with_rarity(rng, false, rarity));
+    }
+
+    item.generate_name();
+
+    item.value = match rarity {
+        Rarity::Common => item.value,
+        Rarity::Uncommon => item.value * 2,
+        Rarity::Rare => item.value * 4,
+        Rarity::Epic => item.value * 8,
+        Rarity::Legendary => item.value * 20,
+        Rarity::Mythic => item.value * 50,
+    };
+
+    item
+}
+
 /// Generate boss loot - guaranteed drops with minimum rarity based on floor
 /// Bosses always drop:
 /// - 1 weapon or armor piece at minimum rarity+1 for the floor
@@ -488,10 +595,3 @@ pub fn generate_boss_loot(floor: u32, rng: &mut impl Rng) -> Vec<Item> {
 
     loot
 }
-
-/// Generate gold drop for boss (more generous)
-pub fn generate_boss_gold_drop(floor: u32, rng: &mut impl Rng) -> u32 {
-    let base = 50 + floor * 20;
-    let variance = rng.gen_range(0..=base / 2);
-    base + variance
-}
@@ -6,10 +6,18 @@ pub mod equipment;
 pub mod synergies;
 pub mod loot;
 pub mod grid;
+pub mod belt;
+pub mod discovery;
+pub mod wand;
+pub mod transaction;
 
-pub use item::{Item, ItemId, ItemCategory, Rarity, EquipSlot, WeaponType, ArmorType, ConsumableEffect, Affix, AffixType, GemType, Gem};
-pub use inventory::Inventory;
-pub use equipment::Equipment;
-pub use loot::{generate_enemy_loot, generate_floor_loot, generate_gold_drop, generate_weapon, generate_armor, generate_consumable, generate_boss_loot, generate_boss_gold_drop};
-pub use synergies::{SynergyTag, SynergyBonus, Synergy, SynergyTier, SynergyBonuses, ActiveSynergy, calculate_synergies};
-pub use grid::{InventoryGrid, GridPosition, PlacedItem, GRID_WIDTH, GRID_HEIGHT, SortMode};
+pub use item::{Item, ItemId, ItemCategory, Rarity, EquipSlot, WeaponType, ArmorType, WeightClass, ConsumableEffect, Affix, AffixType, GemType, Gem, Concealed};
+pub use inventory::{Inventory, carry_capacity};
+pub use equipment::{Equipment, DUAL_WIELD_DAMAGE_PERCENT, DUAL_WIELD_DEX_PENALTY};
+pub use loot::{generate_enemy_loot, generate_floor_loot, generate_weapon, generate_armor, generate_consumable, generate_wand, generate_boss_loot, reroll_affixes};
+pub use synergies::{SynergyTag, SynergyBonus, Synergy, SynergyTier, SynergyBonuses, ActiveSynergy, SynergyProgress, SynergyChange, calculate_synergies, synergy_progress, synergy_changes};
+pub use belt::{Belt, BELT_SLOTS};
+pub use discovery::Discoveries;
+pub use wand::{WandKind, WAND_RANGE};
+pub use transaction::{Transaction, TransactionError, Receipt};
+pub use grid::{InventoryGrid, GridPosition, PlacedItem, GRID_WIDTH, GRID_HEIGHT, MAX_GRID_HEIGHT, SortMode};
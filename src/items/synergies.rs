@@ -34,6 +34,30 @@ pub enum SynergyTag {
     TwoHanded,
 }
 
+impl SynergyTag {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SynergyTag::Fire => "Fire",
+            SynergyTag::Ice => "Ice",
+            SynergyTag::Lightning => "Lightning",
+            SynergyTag::Poison => "Poison",
+            SynergyTag::Cultist => "Cultist",
+            SynergyTag::Knight => "Knight",
+            SynergyTag::Shadow => "Shadow",
+            SynergyTag::Holy => "Holy",
+            SynergyTag::Corruption => "Corruption",
+            SynergyTag::Berserker => "Berserker",
+            SynergyTag::Arcane => "Arcane",
+            SynergyTag::Assassin => "Assassin",
+            SynergyTag::Guardian => "Guardian",
+            SynergyTag::Vampire => "Vampire",
+            SynergyTag::Beast => "Beast",
+            SynergyTag::DualWield => "Dual Wield",
+            SynergyTag::TwoHanded => "Two-Handed",
+        }
+    }
+}
+
 /// Bonus type provided by a synergy
 #[derive(Debug, Clone)]
 pub enum SynergyBonus {
@@ -61,6 +85,25 @@ pub enum SynergyBonus {
     Corruption { power: i32, penalty: i32 },
 }
 
+impl SynergyBonus {
+    /// Short player-facing description
+    pub fn describe(&self) -> String {
+        match self {
+            SynergyBonus::BonusDamage(v) => format!("+{} damage", v),
+            SynergyBonus::DamagePercent(v) => format!("+{:.0}% damage", v * 100.0),
+            SynergyBonus::BonusArmor(v) => format!("+{} armor", v),
+            SynergyBonus::BonusHP(v) => format!("+{} HP", v),
+            SynergyBonus::BonusMP(v) => format!("+{} MP", v),
+            SynergyBonus::CritChance(v) => format!("+{:.0}% crit", v * 100.0),
+            SynergyBonus::Lifesteal(v) => format!("{:.0}% lifesteal", v * 100.0),
+            SynergyBonus::FireDamageOnHit(v) => format!("+{} fire on hit", v),
+            SynergyBonus::PoisonDamageOnHit(v) => format!("+{} poison on hit", v),
+            SynergyBonus::LightningDamageOnHit(v) => format!("+{} lightning on hit", v),
+            SynergyBonus::Corruption { power, penalty } => format!("+{} power, -{} HP", power, penalty),
+        }
+    }
+}
+
 /// Definition of a synergy
 #[derive(Debug, Clone)]
 pub struct Synergy {
@@ -90,6 +133,11 @@ impl Synergy {
             .filter(|t| count >= t.required)
             .last()
     }
+
+    /// Number of tiers reached with `count` items (0 = inactive)
+    pub fn tier_level(&self, count: u8) -> usize {
+        self.tiers.iter().filter(|t| count >= t.required).count()
+    }
 }
 
 /// All available synergies
@@ -271,6 +319,73 @@ pub fn calculate_synergies(tags: &[SynergyTag]) -> Vec<ActiveSynergy> {
     active
 }
 
+/// Progress toward a synergy from the tags worn
+#[derive(Debug, Clone)]
+pub struct SynergyProgress {
+    pub synergy: Synergy,
+    pub item_count: u8,
+    /// Tiers reached (0 = not active yet)
+    pub tier_level: usize,
+}
+
+impl SynergyProgress {
+    /// Items needed for the next tier, or for the top tier once it's reached
+    pub fn goal(&self) -> u8 {
+        self.synergy.tiers.iter()
+            .map(|t| t.required)
+            .find(|&r| r > self.item_count)
+            .or_else(|| self.synergy.tiers.last().map(|t| t.required))
+            .unwrap_or(0)
+    }
+}
+
+/// Every synergy with at least one matching tag worn, active ones first
+pub fn synergy_progress(tags: &[SynergyTag]) -> Vec<SynergyProgress> {
+    let mut progress: Vec<SynergyProgress> = all_synergies().into_iter()
+        .filter_map(|synergy| {
+            let item_count = tags.iter().filter(|t| **t == synergy.tag).count() as u8;
+            (item_count > 0).then(|| SynergyProgress {
+                tier_level: synergy.tier_level(item_count),
+                synergy,
+                item_count,
+            })
+        })
+        .collect();
+    progress.sort_by_key(|p| std::cmp::Reverse((p.tier_level, p.item_count)));
+    progress
+}
+
+/// A synergy moving between tiers after an equipment change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SynergyChange {
+    pub name: &'static str,
+    pub from_tier: usize,
+    pub to_tier: usize,
+}
+
+impl SynergyChange {
+    pub fn message(&self) -> String {
+        match (self.from_tier, self.to_tier) {
+            (0, to) => format!("Synergy activated: {} (tier {})", self.name, to),
+            (_, 0) => format!("Synergy broken: {}", self.name),
+            (from, to) if to > from => format!("Synergy strengthened: {} (tier {})", self.name, to),
+            (_, to) => format!("Synergy weakened: {} (tier {})", self.name, to),
+        }
+    }
+}
+
+/// Synergies whose tier differs between two sets of worn tags
+pub fn synergy_changes(before: &[SynergyTag], after: &[SynergyTag]) -> Vec<SynergyChange> {
+    let count = |tags: &[SynergyTag], tag: SynergyTag| tags.iter().filter(|t| **t == tag).count() as u8;
+    all_synergies().into_iter()
+        .filter_map(|synergy| {
+            let from_tier = synergy.tier_level(count(before, synergy.tag));
+            let to_tier = synergy.tier_level(count(after, synergy.tag));
+            (from_tier != to_tier).then_some(SynergyChange { name: synergy.name, from_tier, to_tier })
+        })
+        .collect()
+}
+
 /// Aggregate bonuses from all active synergies
 #[derive(Debug, Default)]
 pub struct SynergyBonuses {
@@ -342,3 +457,27 @@ impl SynergyBonuses {
         self.corruption_power != 0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synergy_changes_and_progress() {
+        let one = [SynergyTag::Fire];
+        let two = [SynergyTag::Fire, SynergyTag::Fire];
+
+        let up = synergy_changes(&one, &two);
+        assert_eq!(up.len(), 1);
+        assert_eq!((up[0].from_tier, up[0].to_tier), (0, 1));
+        assert!(up[0].message().contains("activated"));
+
+        let down = synergy_changes(&two, &one);
+        assert!(down[0].message().contains("broken"));
+        assert!(synergy_changes(&two, &two).is_empty());
+
+        let progress = synergy_progress(&one);
+        assert_eq!(progress.len(), 1);
+        assert_eq!((progress[0].item_count, progress[0].goal(), progress[0].tier_level), (1, 2, 0));
+    }
+}
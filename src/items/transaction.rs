@@ -0,0 +1,218 @@
+//! Item transactions
+//!
+//! Buying, selling and changing gear each take several steps: gold changes
+//! hands, items leave the pack, whatever gets displaced has to fit back in.
+//! A transaction lists those steps and applies them to a staged copy of the
+//! inventory and equipment, checking each as it goes. Only once every step
+//! has gone through does the real inventory change, so a purchase never
+//! takes the gold without the item fitting, and gear never falls out of the
+//! world when the pack is full.
+
+use std::fmt;
+
+use super::{EquipSlot, Equipment, Inventory, Item, ItemId};
+
+/// Why a transaction didn't go through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionError {
+    NotEnoughGold,
+    InventoryFull,
+    /// An item the transaction names isn't where it should be
+    Missing,
+    /// An item it would give up is locked
+    Locked,
+    /// An item can't be worn in the slot it was meant for
+    CantEquip,
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            TransactionError::NotEnoughGold => "Not enough gold!",
+            TransactionError::InventoryFull => "Inventory full!",
+            TransactionError::Missing => "That item is gone.",
+            TransactionError::Locked => "That item is locked.",
+            TransactionError::CantEquip => "That can't be worn there.",
+        };
+        f.write_str(message)
+    }
+}
+
+/// One step of a transaction
+#[derive(Debug, Clone)]
+enum Step {
+    Pay(u32),
+    Earn(u32),
+    Receive(Box<Item>),
+    HandOver(ItemId),
+    Equip(Option<EquipSlot>, ItemId),
+    Unequip(EquipSlot),
+    Strip(EquipSlot),
+}
+
+/// Items that moved in a transaction that went through
+#[derive(Debug, Clone, Default)]
+pub struct Receipt {
+    /// Items that left the player altogether
+    pub handed_over: Vec<Item>,
+    /// Items put on
+    pub equipped: Vec<Item>,
+    /// Items taken off and packed away, including any displaced
+    pub unequipped: Vec<Item>,
+}
+
+/// Steps with items and gold that happen together or not at all
+#[derive(Debug, Clone, Default)]
+pub struct Transaction {
+    steps: Vec<Step>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pay gold out of the purse
+    pub fn pay(mut self, gold: u32) -> Self {
+        self.steps.push(Step::Pay(gold));
+        self
+    }
+
+    /// Put gold in the purse
+    pub fn earn(mut self, gold: u32) -> Self {
+        self.steps.push(Step::Earn(gold));
+        self
+    }
+
+    /// Put an item in the pack
+    pub fn receive(mut self, item: Item) -> Self {
+        self.steps.push(Step::Receive(Box::new(item)));
+        self
+    }
+
+    /// Take an unlocked item out of the pack and give it up
+    pub fn hand_over(mut self, id: ItemId) -> Self {
+        self.steps.push(Step::HandOver(id));
+        self
+    }
+
+    /// Put on an item from the pack in its own slot, packing away whatever
+    /// it displaces
+    pub fn equip(mut self, id: ItemId) -> Self {
+        self.steps.push(Step::Equip(None, id));
+        self
+    }
+
+    /// Put on an item from the pack in a given slot (a one-handed weapon in
+    /// the off hand), packing away whatever it displaces
+    pub fn equip_to(mut self, slot: EquipSlot, id: ItemId) -> Self {
+        self.steps.push(Step::Equip(Some(slot), id));
+        self
+    }
+
+    /// Take off whatever is worn in a slot and pack it away
+    pub fn unequip(mut self, slot: EquipSlot) -> Self {
+        self.steps.push(Step::Unequip(slot));
+        self
+    }
+
+    /// Take off an unlocked item worn in a slot and give it up
+    pub fn strip(mut self, slot: EquipSlot) -> Self {
+        self.steps.push(Step::Strip(slot));
+        self
+    }
+
+    /// Apply every step, or leave the inventory and equipment as they were
+    /// if any of them fails
+    pub fn apply(self, inventory: &mut Inventory, equipment: &mut Equipment) -> Result<Receipt, TransactionError> {
+        let mut staged = (inventory.clone(), equipment.clone());
+        let mut receipt = Receipt::default();
+        for step in self.steps {
+            apply_step(step, &mut staged.0, &mut staged.1, &mut receipt)?;
+        }
+        (*inventory, *equipment) = staged;
+        Ok(receipt)
+    }
+}
+
+fn apply_step(step: Step, inventory: &mut Inventory, equipment: &mut Equipment, receipt: &mut Receipt) -> Result<(), TransactionError> {
+    match step {
+        Step::Pay(gold) => {
+            if !inventory.spend_gold(gold) {
+                return Err(TransactionError::NotEnoughGold);
+            }
+        }
+        Step::Earn(gold) => inventory.add_gold(gold),
+        Step::Receive(item) => pack(inventory, *item)?,
+        Step::HandOver(id) => {
+            if inventory.get_by_id(id).ok_or(TransactionError::Missing)?.locked {
+                return Err(TransactionError::Locked);
+            }
+            receipt.handed_over.extend(inventory.remove_by_id(id));
+        }
+        Step::Equip(slot, id) => {
+            let item = inventory.remove_by_id(id).ok_or(TransactionError::Missing)?;
+            let Some(slot) = slot.or(item.equip_slot).filter(|slot| item.fits_slot(*slot)) else {
+                return Err(TransactionError::CantEquip);
+            };
+            receipt.equipped.push(item.clone());
+            for displaced in equipment.equip_to(slot, item) {
+                pack(inventory, displaced.clone())?;
+                receipt.unequipped.push(displaced);
+            }
+        }
+        Step::Unequip(slot) => {
+            let item = equipment.unequip(slot).ok_or(TransactionError::Missing)?;
+            pack(inventory, item.clone())?;
+            receipt.unequipped.push(item);
+        }
+        Step::Strip(slot) => {
+            if equipment.get(slot).ok_or(TransactionError::Missing)?.locked {
+                return Err(TransactionError::Locked);
+            }
+            receipt.handed_over.extend(equipment.unequip(slot));
+        }
+    }
+    Ok(())
+}
+
+fn pack(inventory: &mut Inventory, item: Item) -> Result<(), TransactionError> {
+    if inventory.add_item(item) { Ok(()) } else { Err(TransactionError::InventoryFull) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::item::templates;
+
+    #[test]
+    fn test_failed_transactions_leave_everything_as_it_was() {
+        let mut inventory = Inventory::new();
+        let mut equipment = Equipment::new();
+        inventory.add_gold(100);
+
+        // A purchase that goes through
+        let receipt = Transaction::new()
+            .pay(60)
+            .receive(templates::health_potion(1))
+            .apply(&mut inventory, &mut equipment)
+            .unwrap();
+        assert!(receipt.handed_over.is_empty());
+        assert_eq!((inventory.gold(), inventory.count()), (40, 1));
+
+        // One the purse can't cover takes nothing
+        let failed = Transaction::new()
+            .receive(templates::health_potion(2))
+            .pay(60)
+            .apply(&mut inventory, &mut equipment);
+        assert_eq!(failed.unwrap_err(), TransactionError::NotEnoughGold);
+        assert_eq!((inventory.gold(), inventory.count()), (40, 1));
+
+        // A locked item won't be sold, and the sale's gold never arrives
+        inventory.grid_mut().get_by_id_mut(1).unwrap().locked = true;
+        let failed = Transaction::new().earn(25).hand_over(1).apply(&mut inventory, &mut equipment);
+        assert_eq!(failed.unwrap_err(), TransactionError::Locked);
+        assert_eq!((inventory.gold(), inventory.count()), (40, 1));
+        assert_eq!(TransactionError::Locked.to_string(), "That item is locked.");
+    }
+}
@@ -0,0 +1,100 @@
+//! Wands
+//!
+//! Charged implements that fire a bolt in a straight line. Bolt damage is flat
+//! rather than INT-scaled so any build can carry one for the odd ranged burst.
+//! Charges come back from Scrolls of Recharging and rest shrines.
+
+use serde::{Deserialize, Serialize};
+
+/// Tiles a bolt travels before fizzling out
+pub const WAND_RANGE: i32 = 6;
+
+/// Kinds of wand and the bolt they fire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WandKind {
+    /// Damage plus a burn
+    Firebolt,
+    /// Less damage, slows the target
+    Frost,
+    /// High damage, may stun
+    Lightning,
+    /// Light damage, hurls the target back
+    Force,
+}
+
+impl WandKind {
+    pub const ALL: [WandKind; 4] = [WandKind::Firebolt, WandKind::Frost, WandKind::Lightning, WandKind::Force];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WandKind::Firebolt => "Wand of Firebolt",
+            WandKind::Frost => "Wand of Frost",
+            WandKind::Lightning => "Wand of Lightning",
+            WandKind::Force => "Wand of Force",
+        }
+    }
+
+    /// Damage before affixes
+    pub fn base_damage(&self) -> i32 {
+        match self {
+            WandKind::Firebolt => 10,
+            WandKind::Frost => 7,
+            WandKind::Lightning => 14,
+            WandKind::Force => 5,
+        }
+    }
+
+    /// Charges before affixes
+    pub fn base_charges(&self) -> u8 {
+        match self {
+            WandKind::Firebolt => 5,
+            WandKind::Frost => 5,
+            WandKind::Lightning => 3,
+            WandKind::Force => 6,
+        }
+    }
+
+    /// What the bolt looks like in messages
+    pub fn bolt(&self) -> &'static str {
+        match self {
+            WandKind::Firebolt => "firebolt",
+            WandKind::Frost => "frost ray",
+            WandKind::Lightning => "lightning bolt",
+            WandKind::Force => "force blast",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            WandKind::Firebolt => "Hurls a bolt of fire that sets the target burning.",
+            WandKind::Frost => "Fires a ray of frost that slows the target.",
+            WandKind::Lightning => "Calls down a lightning bolt that may stun the target.",
+            WandKind::Force => "Unleashes a blast that hurls the target backwards.",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::item::{templates, Affix, AffixType};
+
+    #[test]
+    fn test_wand_charges() {
+        let mut wand = templates::wand(1, WandKind::Lightning);
+        assert_eq!(wand.charges, WandKind::Lightning.base_charges());
+        assert!(!wand.recharge());
+
+        wand.affixes.push(Affix { affix_type: AffixType::WandCapacity, value: 2 });
+        wand.affixes.push(Affix { affix_type: AffixType::WandPower, value: 4 });
+        assert_eq!(wand.max_charges(), WandKind::Lightning.base_charges() + 2);
+        assert_eq!(wand.zap_damage(), WandKind::Lightning.base_damage() + 4);
+
+        assert!(wand.use_charge());
+        assert!(wand.recharge());
+        assert_eq!(wand.charges, wand.max_charges());
+
+        wand.charges = 0;
+        assert!(!wand.use_charge());
+    }
+}
@@ -13,11 +13,18 @@ pub mod progression;
 pub mod ui;
 pub mod render;
 pub mod audio;
+pub mod presence;
 pub mod save;
 pub mod mods;
 pub mod data;
+pub mod cli;
+pub mod crash;
+pub mod error;
+pub mod logging;
+pub mod perf;
 
 // Re-export commonly used types
 pub use game::{Game, GameState};
+pub use error::Error;
 pub use ecs::components::*;
 pub use world::map::Map;
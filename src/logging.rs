@@ -0,0 +1,164 @@
+//! Logging
+//!
+//! The log is written to `logs/hollowdeep.log` in the platform data
+//! directory, beside the saves and the profile. Each launch moves the logs
+//! already there back a generation (`hollowdeep.1.log` is the last run's),
+//! keeping a few. The latest lines are also kept in memory for the in-game
+//! log viewer, so a bug report can be written without hunting for the file.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use log::LevelFilter;
+
+use crate::error::{Area, Error};
+
+/// Logs from earlier runs kept beside the current one
+const KEEP_LOGS: usize = 3;
+/// Lines kept in memory for the log viewer
+const RECENT_LINES: usize = 500;
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static LOG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Where the logs are kept
+pub fn log_directory() -> PathBuf {
+    use directories::ProjectDirs;
+
+    if let Some(proj_dirs) = ProjectDirs::from("com", "hollowdeep", "Hollowdeep") {
+        let mut path = proj_dirs.data_local_dir().to_path_buf();
+        path.push("logs");
+        path
+    } else {
+        PathBuf::from("./logs")
+    }
+}
+
+/// The log a number of runs back (0 for the current run)
+pub fn log_path(dir: &Path, generation: usize) -> PathBuf {
+    match generation {
+        0 => dir.join("hollowdeep.log"),
+        n => dir.join(format!("hollowdeep.{}.log", n)),
+    }
+}
+
+/// The file this run logs to, if it could be opened
+pub fn current_log_path() -> Option<&'static Path> {
+    LOG_PATH.get().map(PathBuf::as_path)
+}
+
+/// Move each log back a generation, dropping any past the oldest kept
+pub fn rotate(dir: &Path, keep: usize) -> io::Result<()> {
+    let oldest = log_path(dir, keep);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+    for generation in (0..keep).rev() {
+        let from = log_path(dir, generation);
+        if from.exists() {
+            fs::rename(&from, log_path(dir, generation + 1))?;
+        }
+    }
+    Ok(())
+}
+
+/// Start logging. `level` overrides `RUST_LOG`, which defaults to info.
+/// If the log file can't be opened the log is only kept in memory, and the
+/// error comes back to be shown once the game is up.
+pub fn init(level: Option<LevelFilter>) -> Result<(), Error> {
+    let dir = log_directory();
+    let path = log_path(&dir, 0);
+    let opened = fs::create_dir_all(&dir)
+        .and_then(|()| rotate(&dir, KEEP_LOGS))
+        .and_then(|()| File::create(&path))
+        .map_err(|e| Error::io(Area::Log, "open", &path, e));
+
+    let (file, result) = match opened {
+        Ok(file) => {
+            let _ = LOG_PATH.set(path);
+            (Some(file), Ok(()))
+        }
+        Err(e) => (None, Err(e)),
+    };
+
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"));
+    if let Some(level) = level {
+        builder.filter_level(level);
+    }
+    builder
+        .write_style(env_logger::WriteStyle::Never)
+        .target(env_logger::Target::Pipe(Box::new(LogWriter { file, partial: String::new() })))
+        .init();
+
+    result
+}
+
+/// Every line still kept in memory, oldest first
+pub fn recent_lines() -> Vec<String> {
+    RECENT.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+}
+
+fn remember(line: String) {
+    let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    if recent.len() == RECENT_LINES {
+        recent.pop_front();
+    }
+    recent.push_back(line);
+}
+
+/// Writes the log to its file, and keeps each finished line in memory
+struct LogWriter {
+    file: Option<File>,
+    /// The start of a line still being written
+    partial: String,
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(file) = &mut self.file {
+            file.write_all(buf)?;
+        }
+        self.partial.push_str(&String::from_utf8_lossy(buf));
+        while let Some(end) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=end).collect();
+            remember(line.trim_end().to_string());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logs_rotate_and_lines_are_kept() {
+        let dir = std::env::temp_dir().join(format!("hollowdeep-logs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for run in 0..4 {
+            rotate(&dir, 2).unwrap();
+            fs::write(log_path(&dir, 0), format!("run {}", run)).unwrap();
+        }
+        let read = |generation| fs::read_to_string(log_path(&dir, generation)).unwrap();
+        assert_eq!((read(0), read(1), read(2)), ("run 3".into(), "run 2".into(), "run 1".into()));
+        assert!(!log_path(&dir, 3).exists());
+        fs::remove_dir_all(&dir).unwrap();
+
+        // Lines are kept once they're finished, however they're written
+        let mut writer = LogWriter { file: None, partial: String::new() };
+        writer.write_all(b"[WARN  hollowdeep::data] first\n[ERROR hollo").unwrap();
+        writer.write_all(b"wdeep::save] second\n").unwrap();
+        let recent = recent_lines();
+        assert!(recent.ends_with(&["[WARN  hollowdeep::data] first".to_string(), "[ERROR hollowdeep::save] second".to_string()]));
+    }
+}
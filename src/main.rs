@@ -5,10 +5,11 @@
 
 use std::io;
 use std::time::{Duration, Instant};
-use std::fs::OpenOptions;
 
 use anyhow::Result;
+use clap::Parser;
 use crossterm::{
+    cursor,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -18,30 +19,55 @@ use ratatui::{
     Terminal,
 };
 
+use hollowdeep::cli::{Cli, RenderModeChoice};
 use hollowdeep::game::{Game, GameState};
+use hollowdeep::progression::CurseSigils;
+use hollowdeep::perf::{self, CountingAllocator, FrameSample};
+use hollowdeep::render::preview::{self, PreviewFormat, PreviewSpec};
 use hollowdeep::ui::App;
 
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
 /// Target frames per second for the game loop
 const TARGET_FPS: u64 = 60;
 const FRAME_TIME: Duration = Duration::from_millis(1000 / TARGET_FPS);
 
 fn main() -> Result<()> {
-    // Initialize logging to file (to avoid interfering with TUI)
-    let log_file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open("hollowdeep.log")
-        .unwrap_or_else(|_| OpenOptions::new().write(true).open("/dev/null").unwrap());
-
-    env_logger::Builder::from_env(
-        env_logger::Env::default().default_filter_or("info")
-    )
-    .target(env_logger::Target::Pipe(Box::new(log_file)))
-    .init();
+    let cli = Cli::parse();
+
+    // Dev tool: print how standard builds fare against every enemy, then quit
+    if cli.balance_report {
+        print_balance_report();
+        return Ok(());
+    }
+    // Dev tool: generate N floors per biome and check their invariants
+    if let Some(count) = cli.gen_check {
+        let failures = hollowdeep::world::generation::check::check_generation(count);
+        for failure in &failures {
+            println!("{}", failure.describe());
+        }
+        println!("{} failing floors out of {}", failures.len(), count * 4);
+        std::process::exit(if failures.is_empty() { 0 } else { 1 });
+    }
+    if cli.validate_data {
+        std::process::exit(validate_data());
+    }
+    if let Some(spec) = cli.preview() {
+        std::process::exit(print_floor_preview(spec));
+    }
+
+    // Log to the data directory rather than the terminal, which the TUI owns
+    let log_error = hollowdeep::logging::init(cli.log_level).err();
 
     log::info!("Starting Hollowdeep v{}", env!("CARGO_PKG_VERSION"));
 
+    if cli.headless {
+        std::process::exit(run_headless(&cli));
+    }
+
+    install_panic_hook();
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -49,21 +75,29 @@ fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create game and UI
-    let mut app = App::new();
+    // Create game and UI; in wizard mode the look cursor inspects entities
+    // and F3 shows where frame time goes
     let mut game = Game::new();
+    let render_mode = cli.render_mode.map_or(game.profile().settings.render_mode, RenderModeChoice::mode);
+    let mut app = App::new()
+        .with_render_mode(render_mode)
+        .with_seed(cli.seed)
+        .with_wizard(cli.wizard)
+        .with_crash_report(hollowdeep::crash::take_pending());
+    if let Some(e) = log_error {
+        game.report_error(e);
+    }
+    if let Some(slot) = cli.load {
+        app.load_slot(&mut game, slot);
+    } else if let Some(difficulty) = cli.new_run {
+        game.start_new_run(cli.seed, difficulty, CurseSigils::default());
+        app.follow_player(&game);
+    }
 
     // Run the game loop
     let result = run_game_loop(&mut terminal, &mut app, &mut game);
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    restore_terminal()?;
 
     // Report any errors
     if let Err(ref e) = result {
@@ -75,6 +109,113 @@ fn main() -> Result<()> {
     result
 }
 
+/// Put the terminal back the way it was found
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, cursor::Show)
+}
+
+/// On a panic, put the terminal back before anything is printed, and write
+/// a crash report for the next launch to bring up
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        log::error!("{}", info);
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        match hollowdeep::crash::write_report(&info.to_string(), &backtrace.to_string()) {
+            Ok(path) => eprintln!("Hollowdeep crashed. A report was written to {}", path.display()),
+            Err(e) => eprintln!("Hollowdeep crashed, and the crash report couldn't be written: {}", e),
+        }
+        default_hook(info);
+    }));
+}
+
+/// Load the data files and print what's wrong with them. Returns the exit
+/// code: 1 if anything was.
+fn validate_data() -> i32 {
+    use hollowdeep::data::DataManager;
+
+    let (_, problems) = DataManager::load_from_assets();
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    println!("{} problems in the data files", problems.len());
+    i32::from(!problems.is_empty())
+}
+
+/// Draw a floor as the generator lays it out from a seed: to stdout as
+/// text, coloured if stdout is a terminal, or to a file. Returns the exit code.
+fn print_floor_preview(spec: PreviewSpec) -> i32 {
+    use std::io::IsTerminal;
+
+    let seed = spec.seed.unwrap_or_else(rand::random);
+    let map = preview::generate(spec.floor, seed);
+    let format = spec.format.unwrap_or(match (&spec.out, io::stdout().is_terminal()) {
+        (Some(path), _) if path.extension().is_some_and(|ext| ext == "png") => PreviewFormat::Png,
+        (None, true) => PreviewFormat::Ansi,
+        _ => PreviewFormat::Text,
+    });
+    let written = match (format, &spec.out) {
+        (PreviewFormat::Png, None) => {
+            eprintln!("A PNG preview needs out=PATH");
+            return 2;
+        }
+        (PreviewFormat::Png, Some(path)) => preview::to_png(&map).save(path).map_err(|e| e.to_string()),
+        (format, Some(path)) => std::fs::write(path, preview::to_text(&map, format == PreviewFormat::Ansi)).map_err(|e| e.to_string()),
+        (format, None) => {
+            println!("Floor {} ({}), seed {}", spec.floor, map.biome.name(), seed);
+            print!("{}", preview::to_text(&map, format == PreviewFormat::Ansi));
+            return 0;
+        }
+    };
+    match written {
+        Ok(()) => {
+            println!("Floor {} ({}), seed {}: wrote {}", spec.floor, map.biome.name(), seed, spec.out.unwrap_or_default().display());
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Set up the run the command line asks for without a terminal, and print
+/// where it stands. Returns the exit code: 1 if the run couldn't be set up.
+fn run_headless(cli: &Cli) -> i32 {
+    let mut game = Game::headless();
+    if let Some(slot) = cli.load {
+        if let Err(e) = hollowdeep::save::load_game(slot).and_then(|save| game.restore_from_save(save)) {
+            eprintln!("{}", e);
+            return 1;
+        }
+    } else {
+        game.start_new_run(cli.seed, cli.new_run.unwrap_or_default(), CurseSigils::default());
+        game.skip_tutorial();
+    }
+
+    println!("Seed: {}", game.seed());
+    println!("Floor: {} ({})", game.floor(), game.biome().name());
+    println!("Difficulty: {}", game.difficulty().name());
+    if let (Some(health), Some(pos)) = (game.player_health(), game.player_position()) {
+        println!("Player: {}/{} HP at ({}, {})", health.current, health.max, pos.x, pos.y);
+    }
+    println!("Entities: {}", game.world().len());
+    0
+}
+
+/// Print the enemy scaling table for the loaded data files
+fn print_balance_report() {
+    use hollowdeep::data::DataManager;
+    use hollowdeep::game::{balance_report, balance_rows};
+    use hollowdeep::progression::Difficulty;
+
+    let data = DataManager::new();
+    let rows = balance_rows(data.enemy_templates(), data.balance(), Difficulty::Normal);
+    print!("{}", balance_report(&rows));
+}
+
 /// Main game loop
 fn run_game_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
@@ -87,28 +228,46 @@ fn run_game_loop(
         let frame_start = Instant::now();
         let delta = frame_start.duration_since(last_frame);
         last_frame = frame_start;
+        let (allocations, allocated_bytes) = perf::allocations();
 
         // Handle input
         if event::poll(Duration::from_millis(0))? {
-            if let Event::Key(key) = event::read()? {
+            match event::read()? {
                 // Only handle key press events, not releases
-                if key.kind == KeyEventKind::Press {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
                     match app.handle_input(key, game) {
                         Ok(should_quit) if should_quit => break,
                         Ok(_) => {}
                         Err(e) => log::warn!("Input handling error: {}", e),
                     }
                 }
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                Event::Resize(width, _) => app.handle_resize(width),
+                _ => {}
             }
         }
 
-        // Update game state
+        // Update game state, noting it in case the frame crashes
+        let update_start = Instant::now();
         game.update(delta);
+        hollowdeep::crash::set_context(game.crash_context());
 
         // Render
-        terminal.draw(|frame| {
+        let render_start = Instant::now();
+        let drawn = terminal.draw(|frame| {
             app.render(frame, game);
         })?;
+        app.capture_screenshot(drawn.buffer, game);
+
+        // Note where the frame's time went, for the perf overlay
+        let (allocations_after, bytes_after) = perf::allocations();
+        app.record_frame(FrameSample {
+            input: update_start - frame_start,
+            update: render_start - update_start,
+            render: render_start.elapsed(),
+            allocations: allocations_after - allocations,
+            allocated_bytes: bytes_after - allocated_bytes,
+        });
 
         // Check if game wants to quit
         if matches!(game.state(), GameState::Quit) {
@@ -0,0 +1,128 @@
+//! Performance counters
+//!
+//! What the wizard mode perf overlay shows: how long each frame spends on
+//! input, updating and rendering, and how much it allocates. Counting
+//! allocations takes the binary registering `CountingAllocator` as its
+//! global allocator; without it they read as zero.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Frames the averages are taken over
+const WINDOW: usize = 120;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting what passes through it
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Allocations made since the program started, and their bytes
+pub fn allocations() -> (u64, u64) {
+    (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed))
+}
+
+/// Where one frame's time went
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameSample {
+    pub input: Duration,
+    pub update: Duration,
+    pub render: Duration,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+impl FrameSample {
+    /// Time spent working on the frame, not waiting for the next
+    pub fn busy(&self) -> Duration {
+        self.input + self.update + self.render
+    }
+}
+
+/// The last couple of seconds of frames
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    samples: VecDeque<FrameSample>,
+}
+
+impl FrameStats {
+    pub fn push(&mut self, sample: FrameSample) {
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// The average frame over the window
+    pub fn average(&self) -> FrameSample {
+        let n = self.samples.len().max(1) as u32;
+        let sum = self.samples.iter().fold(FrameSample::default(), |sum, s| FrameSample {
+            input: sum.input + s.input,
+            update: sum.update + s.update,
+            render: sum.render + s.render,
+            allocations: sum.allocations + s.allocations,
+            allocated_bytes: sum.allocated_bytes + s.allocated_bytes,
+        });
+        FrameSample {
+            input: sum.input / n,
+            update: sum.update / n,
+            render: sum.render / n,
+            allocations: sum.allocations / n as u64,
+            allocated_bytes: sum.allocated_bytes / n as u64,
+        }
+    }
+
+    /// The longest frame in the window: the stutter, if there was one
+    pub fn worst(&self) -> Duration {
+        self.samples.iter().map(FrameSample::busy).max().unwrap_or_default()
+    }
+}
+
+/// A duration in milliseconds, to two places
+pub fn millis(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_stats_average_and_catch_the_stutter() {
+        let ms = Duration::from_millis;
+        let mut stats = FrameStats::default();
+        for _ in 0..WINDOW {
+            stats.push(FrameSample { input: ms(1), update: ms(2), render: ms(3), allocations: 10, allocated_bytes: 400 });
+        }
+        let average = stats.average();
+        assert_eq!((average.busy(), average.allocations), (ms(6), 10));
+        assert_eq!(stats.worst(), ms(6));
+
+        // A stutter on a large floor shows as the worst frame, and the
+        // oldest frame drops out of the window
+        stats.push(FrameSample { input: ms(40), update: ms(2), render: ms(3), allocations: 130, allocated_bytes: 400 });
+        assert_eq!(stats.worst(), ms(45));
+        assert_eq!(stats.average().allocations, 11);
+        assert_eq!(millis(ms(45)), "45.00 ms");
+    }
+}
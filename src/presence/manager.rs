@@ -0,0 +1,158 @@
+//! Presence manager
+//!
+//! Owns the connection to Discord and pushes activity updates from a
+//! background thread so a slow or missing Discord client never stalls the game.
+
+/// A snapshot of what the player is doing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresenceActivity {
+    /// Top line (e.g. "Lv 5 Journeyman - Floor 3")
+    pub details: String,
+    /// Bottom line (e.g. "Sunken Catacombs" or "Fighting The Bone Tyrant")
+    pub state: String,
+    /// Unix time (seconds) the run started, shown as elapsed time
+    pub started_at: Option<u64>,
+}
+
+/// Messages sent to the presence worker thread
+#[cfg(feature = "discord-presence")]
+enum PresenceCommand {
+    Set(PresenceActivity),
+    Clear,
+}
+
+/// Publishes activity updates to Discord Rich Presence
+pub struct PresenceManager {
+    /// Whether presence updates are enabled (profile setting)
+    enabled: bool,
+    /// Discord application ID (empty = presence unavailable)
+    #[cfg_attr(not(feature = "discord-presence"), allow(dead_code))]
+    client_id: String,
+    /// Last activity sent, to avoid redundant updates
+    last: Option<PresenceActivity>,
+    /// Channel to the worker thread that owns the Discord connection
+    #[cfg(feature = "discord-presence")]
+    sender: Option<std::sync::mpsc::Sender<PresenceCommand>>,
+}
+
+impl PresenceManager {
+    /// Create a new presence manager
+    ///
+    /// `client_id` is the Discord application ID; the `HOLLOWDEEP_DISCORD_CLIENT_ID`
+    /// environment variable takes precedence when set.
+    pub fn new(enabled: bool, client_id: &str) -> Self {
+        let client_id = std::env::var("HOLLOWDEEP_DISCORD_CLIENT_ID")
+            .unwrap_or_else(|_| client_id.to_string());
+
+        let mut presence = Self {
+            enabled: false,
+            client_id,
+            last: None,
+            #[cfg(feature = "discord-presence")]
+            sender: None,
+        };
+        presence.set_enabled(enabled);
+        presence
+    }
+
+    /// Whether presence updates are enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable presence updates
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled == enabled {
+            return;
+        }
+        if !enabled {
+            self.clear();
+        }
+        self.enabled = enabled;
+
+        #[cfg(feature = "discord-presence")]
+        if enabled && self.sender.is_none() {
+            if self.client_id.is_empty() {
+                log::info!("Discord presence enabled but no client ID is configured");
+            } else {
+                self.sender = Some(spawn_worker(self.client_id.clone()));
+            }
+        }
+    }
+
+    /// Publish an activity (ignored if unchanged or disabled)
+    pub fn update(&mut self, activity: PresenceActivity) {
+        if !self.enabled || self.last.as_ref() == Some(&activity) {
+            return;
+        }
+        log::debug!("Presence: {} | {}", activity.details, activity.state);
+
+        #[cfg(feature = "discord-presence")]
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(PresenceCommand::Set(activity.clone()));
+        }
+
+        self.last = Some(activity);
+    }
+
+    /// Clear the published activity
+    pub fn clear(&mut self) {
+        #[cfg(feature = "discord-presence")]
+        if let (Some(_), Some(sender)) = (&self.last, &self.sender) {
+            let _ = sender.send(PresenceCommand::Clear);
+        }
+
+        self.last = None;
+    }
+}
+
+/// Spawn the worker thread that talks to Discord over IPC
+#[cfg(feature = "discord-presence")]
+fn spawn_worker(client_id: String) -> std::sync::mpsc::Sender<PresenceCommand> {
+    use discord_rich_presence::{activity, DiscordIpc, DiscordIpcClient};
+
+    let (sender, receiver) = std::sync::mpsc::channel::<PresenceCommand>();
+
+    std::thread::spawn(move || {
+        let mut client = DiscordIpcClient::new(&client_id);
+        let mut connected = false;
+
+        for command in receiver {
+            // Connect lazily so the game starts fine without Discord running
+            if !connected {
+                match client.connect() {
+                    Ok(()) => {
+                        log::info!("Connected to Discord Rich Presence");
+                        connected = true;
+                    }
+                    Err(e) => {
+                        log::debug!("Discord not available: {}", e);
+                        continue;
+                    }
+                }
+            }
+
+            let result = match command {
+                PresenceCommand::Set(a) => {
+                    let mut payload = activity::Activity::new()
+                        .details(a.details.as_str())
+                        .state(a.state.as_str());
+                    if let Some(start) = a.started_at {
+                        payload = payload.timestamps(activity::Timestamps::new().start(start as i64 * 1000));
+                    }
+                    client.set_activity(payload)
+                }
+                PresenceCommand::Clear => client.clear_activity(),
+            };
+
+            if let Err(e) = result {
+                log::debug!("Failed to update Discord presence: {}", e);
+                connected = false;
+            }
+        }
+
+        let _ = client.close();
+    });
+
+    sender
+}
@@ -0,0 +1,9 @@
+//! Rich presence integration
+//!
+//! Publishes the current activity (level, floor, biome, elapsed time) to
+//! Discord Rich Presence. The Discord client is only compiled in with the
+//! `discord-presence` feature; otherwise the manager is a no-op.
+
+pub mod manager;
+
+pub use manager::{PresenceManager, PresenceActivity};
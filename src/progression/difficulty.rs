@@ -1,8 +1,18 @@
 //! Difficulty settings and floor-based scaling
 //!
-//! Provides both global difficulty settings and per-floor scaling.
+//! Provides both global difficulty settings and per-floor scaling. The
+//! built-in multipliers here are the defaults for the RON balance config.
 
 use serde::{Deserialize, Serialize};
+use crate::data::balance::{BalanceConfig, DifficultyCurve};
+use crate::world::generation::{endless_depth, FINAL_FLOOR};
+
+/// Enemy strength multiplies by this for each depth below the final floor
+const DEPTH_GROWTH: f32 = 1.12;
+/// Furthest the depth multiplier climbs
+const MAX_DEPTH_MULT: f32 = 1000.0;
+/// Ceiling on any scaled HP, stat or XP, keeping them well inside an i32
+const MAX_SCALED: f32 = 1_000_000.0;
 
 /// Game difficulty levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -45,6 +55,21 @@ impl Difficulty {
         }
     }
 
+    /// Score multiplier for the leaderboard
+    pub fn score_mult(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+            Difficulty::Nightmare => 2.0,
+        }
+    }
+
+    /// Whether the player gets one reprieve from death per floor
+    pub fn has_deaths_door(&self) -> bool {
+        !matches!(self, Difficulty::Nightmare)
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             Difficulty::Easy => "Easy",
@@ -68,83 +93,145 @@ pub struct FloorScaling {
     pub difficulty: Difficulty,
     /// Elite bonus multiplier (for elite zones)
     pub elite_mult: f32,
+    /// XP multiplier for elite zones
+    elite_xp_mult: f32,
+    /// Elite multipliers from the balance config
+    elite_power: f32,
+    elite_xp: f32,
+    /// Strength added per floor
+    floor_growth: f32,
+    /// Multipliers for the difficulty
+    curve: DifficultyCurve,
+    /// Rubber-band multiplier on enemy HP and power (1.0 = none)
+    ease: f32,
+    /// New Game Plus multiplier on enemy HP and power (1.0 = first run)
+    ng_plus: f32,
+    /// Elites guaranteed in each elite zone
+    elites_per_zone: usize,
+    /// Adaptive difficulty's multiplier on enemy count (1.0 = none)
+    enemy_count_mult: f32,
 }
 
 impl FloorScaling {
+    /// Scaling with the built-in balance curves
     pub fn new(floor: u32, difficulty: Difficulty) -> Self {
-        Self { floor, difficulty, elite_mult: 1.0 }
+        Self::with_balance(floor, difficulty, &BalanceConfig::default())
     }
 
-    /// Create scaling for elite zone enemies (50% stronger, 100% more XP)
-    pub fn elite_scaled(floor: u32, difficulty: Difficulty) -> Self {
-        Self { floor, difficulty, elite_mult: 1.5 }
+    /// Scaling with balance curves loaded from data
+    pub fn with_balance(floor: u32, difficulty: Difficulty, balance: &BalanceConfig) -> Self {
+        Self {
+            floor,
+            difficulty,
+            elite_mult: 1.0,
+            elite_xp_mult: 1.0,
+            elite_power: balance.elite_power,
+            elite_xp: balance.elite_xp,
+            floor_growth: balance.floor_growth,
+            curve: *balance.curve(difficulty),
+            ease: 1.0,
+            ng_plus: 1.0,
+            elites_per_zone: 1,
+            enemy_count_mult: 1.0,
+        }
+    }
+
+    /// The same scaling for elite zone enemies (stronger, more XP)
+    pub fn elite(&self) -> Self {
+        Self { elite_mult: self.elite_power, elite_xp_mult: self.elite_xp, ..self.clone() }
+    }
+
+    /// Ease enemy HP and power by a rubber-band multiplier
+    pub fn eased(self, ease: f32) -> Self {
+        Self { ease, ..self }
+    }
+
+    /// Strengthen enemies for a New Game Plus cycle
+    pub fn new_game_plus(self, cycle: u32) -> Self {
+        Self { ng_plus: super::ng_plus_multiplier(cycle), ..self }
     }
 
-    /// Get the floor scaling factor (1.0 at floor 1, increases per floor)
-    /// Each floor adds ~5% to enemy strength
+    /// Guarantee more than one elite in each elite zone
+    pub fn with_elites(self, elites_per_zone: usize) -> Self {
+        Self { elites_per_zone, ..self }
+    }
+
+    /// Elites guaranteed in each elite zone
+    pub fn elites_per_zone(&self) -> usize {
+        self.elites_per_zone
+    }
+
+    /// Spawn more or fewer enemies for adaptive difficulty
+    pub fn with_enemy_count(self, enemy_count_mult: f32) -> Self {
+        Self { enemy_count_mult, ..self }
+    }
+
+    /// Apply the adaptive difficulty multiplier to a rolled enemy count
+    pub fn adjust_enemy_count(&self, count: usize) -> usize {
+        ((count as f32 * self.enemy_count_mult).round() as usize).max(1)
+    }
+
+    /// Get the floor scaling factor (1.0 at floor 1, increases per floor,
+    /// and compounds per depth below the final floor)
     fn floor_factor(&self) -> f32 {
-        1.0 + (self.floor.saturating_sub(1) as f32 * 0.05)
+        let linear = 1.0 + (self.floor.min(FINAL_FLOOR).saturating_sub(1) as f32 * self.floor_growth);
+        linear * depth_multiplier(self.floor)
     }
 
     /// Calculate scaled enemy HP
-    /// Base formula: base_hp * floor_factor * difficulty_mult * elite_mult
+    /// Base formula: base_hp * floor_factor * difficulty_mult * elite_mult * ease * ng_plus
     pub fn scale_enemy_hp(&self, base_hp: i32) -> i32 {
-        let scaled = base_hp as f32 * self.floor_factor() * self.difficulty.enemy_health_mult() * self.elite_mult;
-        scaled.round() as i32
+        let scaled = base_hp as f32 * self.floor_factor() * self.curve.enemy_health * self.elite_mult * self.ease * self.ng_plus;
+        scaled.round().clamp(1.0, MAX_SCALED) as i32
     }
 
     /// Calculate scaled enemy damage (via stats)
     /// Returns a stat multiplier to apply to STR/INT
     pub fn stat_multiplier(&self) -> f32 {
-        self.floor_factor() * self.difficulty.enemy_damage_mult() * self.elite_mult
+        self.floor_factor() * self.curve.enemy_damage * self.elite_mult * self.ease * self.ng_plus
     }
 
-    /// Calculate scaled XP reward (elite zones give double XP)
+    /// Calculate scaled XP reward (elite zones give more XP)
     pub fn scale_xp(&self, base_xp: u32) -> u32 {
-        let elite_xp_mult = if self.elite_mult > 1.0 { 2.0 } else { 1.0 };
-        let scaled = base_xp as f32 * self.floor_factor() * self.difficulty.xp_mult() * elite_xp_mult;
-        scaled.round() as u32
+        let scaled = base_xp as f32 * self.floor_factor() * self.curve.xp * self.elite_xp_mult;
+        scaled.round().min(MAX_SCALED) as u32
     }
 
     /// Scale a stat value (STR, DEX, etc)
     pub fn scale_stat(&self, base_stat: i32) -> i32 {
         let scaled = base_stat as f32 * self.stat_multiplier();
-        scaled.round() as i32
+        scaled.round().clamp(-MAX_SCALED, MAX_SCALED) as i32
     }
 
     /// Get enemy count multiplier for this floor
     /// Returns (min_add, max_add) to add to base enemy count
     pub fn enemy_count_bonus(&self) -> (usize, usize) {
-        let base = (self.floor / 5) as usize;
-        let diff_bonus = match self.difficulty {
-            Difficulty::Easy => 0,
-            Difficulty::Normal => 0,
-            Difficulty::Hard => 1,
-            Difficulty::Nightmare => 2,
-        };
-        (base, base + diff_bonus)
+        // The depths grow stronger, not more crowded
+        let base = (self.floor.min(FINAL_FLOOR) / 5) as usize;
+        (base, base + self.curve.extra_enemies)
     }
 
     /// Check if this floor should have an elite enemy guaranteed
     pub fn has_guaranteed_elite(&self) -> bool {
-        // Elite on every 5th floor, or always on Nightmare
-        self.floor % 5 == 0 || self.difficulty == Difficulty::Nightmare
+        // Elite on every 5th floor, or always when the difficulty says so
+        self.floor % 5 == 0 || self.curve.elite_every_floor
     }
 
     /// Get item drop quality bonus (affects rarity chances)
     /// Higher value = better drops
     pub fn loot_quality_bonus(&self) -> f32 {
         let floor_bonus = (self.floor as f32 - 1.0) * 0.02; // +2% per floor
-        let diff_bonus = match self.difficulty {
-            Difficulty::Easy => -0.1,
-            Difficulty::Normal => 0.0,
-            Difficulty::Hard => 0.1,
-            Difficulty::Nightmare => 0.2,
-        };
-        floor_bonus + diff_bonus
+        floor_bonus + self.curve.loot_bonus
     }
 }
 
+/// How much stronger enemies are on a floor below the final one (1.0 above
+/// it), compounding per depth up to a ceiling
+pub fn depth_multiplier(floor: u32) -> f32 {
+    let depth = endless_depth(floor).unwrap_or(0).min(i32::MAX as u32);
+    DEPTH_GROWTH.powi(depth as i32).min(MAX_DEPTH_MULT)
+}
+
 /// Convenience function for simple floor scaling
 pub fn floor_hp_scale(base_hp: i32, floor: u32) -> i32 {
     FloorScaling::new(floor, Difficulty::Normal).scale_enemy_hp(base_hp)
@@ -159,3 +246,26 @@ pub fn floor_xp_scale(base_xp: u32, floor: u32) -> u32 {
 pub fn floor_stat_scale(base_stat: i32, floor: u32) -> i32 {
     FloorScaling::new(floor, Difficulty::Normal).scale_stat(base_stat)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depths_compound_without_overflowing() {
+        assert_eq!(depth_multiplier(FINAL_FLOOR), 1.0);
+        assert!(depth_multiplier(FINAL_FLOOR + 2) > depth_multiplier(FINAL_FLOOR + 1));
+
+        let abyss = FloorScaling::new(FINAL_FLOOR, Difficulty::Nightmare);
+        let depth = FloorScaling::new(FINAL_FLOOR + 10, Difficulty::Nightmare);
+        assert!(depth.scale_enemy_hp(100) > abyss.scale_enemy_hp(100) * 3);
+        assert_eq!(depth.enemy_count_bonus(), abyss.enemy_count_bonus());
+
+        // However deep the run goes, the numbers stay sane
+        let bottom = FloorScaling::new(u32::MAX, Difficulty::Nightmare).elite().new_game_plus(u32::MAX);
+        assert_eq!(depth_multiplier(u32::MAX), MAX_DEPTH_MULT);
+        assert_eq!(bottom.scale_enemy_hp(i32::MAX), MAX_SCALED as i32);
+        assert_eq!(bottom.scale_stat(50), MAX_SCALED as i32);
+        assert_eq!(bottom.scale_xp(u32::MAX), MAX_SCALED as u32);
+    }
+}
@@ -0,0 +1,129 @@
+//! Critical injuries
+//!
+//! Nearly dying leaves a mark. Dropping into the last sliver of health or
+//! taking a crushing blow can leave the player with an injury that lingers
+//! from floor to floor until a healer or a bonesetter's salve treats it.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// HP (percent of max) below which the player is at death's door
+const NEAR_DEATH_PERCENT: i32 = 10;
+/// Damage in one turn (percent of max HP) that counts as a crushing blow
+const MASSIVE_HIT_PERCENT: i32 = 35;
+/// Chance a critical blow leaves an injury
+pub const INJURY_CHANCE: f64 = 0.5;
+/// Chance a concussed player's skill fizzles
+const CONCUSSION_FIZZLE_CHANCE: f64 = 0.25;
+
+/// A lasting injury
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Injury {
+    /// Weaker blows
+    BrokenArm,
+    /// Skills sometimes fizzle
+    Concussion,
+    /// Every movement hurts
+    CrackedRibs,
+}
+
+impl Injury {
+    pub const ALL: [Injury; 3] = [Injury::BrokenArm, Injury::Concussion, Injury::CrackedRibs];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Injury::BrokenArm => "Broken Arm",
+            Injury::Concussion => "Concussion",
+            Injury::CrackedRibs => "Cracked Ribs",
+        }
+    }
+
+    /// What the injury does
+    pub fn effect(&self) -> &'static str {
+        match self {
+            Injury::BrokenArm => "-2 STR",
+            Injury::Concussion => "Skills may fizzle",
+            Injury::CrackedRibs => "-2 DEX",
+        }
+    }
+
+    /// Message shown when the injury is suffered
+    pub fn onset(&self) -> &'static str {
+        match self {
+            Injury::BrokenArm => "Something snaps in your arm!",
+            Injury::Concussion => "Your skull rings and the world tilts.",
+            Injury::CrackedRibs => "You feel your ribs crack.",
+        }
+    }
+
+    /// Attribute changes while injured (STR, DEX, INT, VIT)
+    pub fn stat_changes(&self) -> (i32, i32, i32, i32) {
+        match self {
+            Injury::BrokenArm => (-2, 0, 0, 0),
+            Injury::CrackedRibs => (0, -2, 0, 0),
+            Injury::Concussion => (0, 0, 0, 0),
+        }
+    }
+}
+
+/// Whether going from `hp_before` to `hp_after` in one turn was a critical
+/// blow: dropping to death's door, or losing a huge chunk at once
+pub fn is_critical_blow(hp_before: i32, hp_after: i32, max_hp: i32) -> bool {
+    if hp_after <= 0 || hp_after >= hp_before || max_hp <= 0 {
+        return false;
+    }
+    let near_death = hp_after * 100 < max_hp * NEAR_DEATH_PERCENT
+        && hp_before * 100 >= max_hp * NEAR_DEATH_PERCENT;
+    let massive = (hp_before - hp_after) * 100 >= max_hp * MASSIVE_HIT_PERCENT;
+    near_death || massive
+}
+
+/// Injuries carried by the player
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Injuries {
+    pub injuries: Vec<Injury>,
+}
+
+impl Injuries {
+    pub fn has(&self, injury: Injury) -> bool {
+        self.injuries.contains(&injury)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.injuries.is_empty()
+    }
+
+    /// Chance a skill fizzles when used
+    pub fn fizzle_chance(&self) -> f64 {
+        if self.has(Injury::Concussion) { CONCUSSION_FIZZLE_CHANCE } else { 0.0 }
+    }
+
+    /// Roll an injury the player doesn't have yet
+    pub fn roll(&self, rng: &mut impl Rng) -> Option<Injury> {
+        let candidates: Vec<Injury> = Injury::ALL.into_iter()
+            .filter(|i| !self.has(*i))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(candidates[rng.gen_range(0..candidates.len())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_critical_blows() {
+        // Dropping below 10%
+        assert!(is_critical_blow(20, 9, 100));
+        // Already at death's door and nicked again
+        assert!(!is_critical_blow(9, 8, 100));
+        // One huge hit
+        assert!(is_critical_blow(100, 60, 100));
+        assert!(!is_critical_blow(100, 70, 100));
+        // Dying isn't an injury, it's the end
+        assert!(!is_critical_blow(20, 0, 100));
+    }
+}
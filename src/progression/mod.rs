@@ -4,7 +4,16 @@ pub mod xp;
 pub mod skills;
 pub mod unlocks;
 pub mod difficulty;
+pub mod mutations;
+pub mod injuries;
+pub mod new_game_plus;
+pub mod sigils;
 
 pub use difficulty::{Difficulty, FloorScaling, floor_hp_scale, floor_xp_scale, floor_stat_scale};
-pub use skills::{Skill, SkillId, SkillCost, TargetType, SkillEffect, EquippedSkills, SkillRarity};
+pub use skills::{Skill, SkillId, SkillCost, TargetType, SkillEffect, EquippedSkills, SkillRarity, MAX_SKILL_RANK};
 pub use skills::{skill_power_strike, skill_first_aid, starting_skills, learnable_skills, generate_shrine_skills};
+pub use mutations::{Mutation, Mutations, MAX_MUTATIONS};
+pub use injuries::{Injury, Injuries, is_critical_blow};
+pub use new_game_plus::{EliteModifier, EliteModifiers, ng_plus_multiplier};
+pub use sigils::{CurseSigil, CurseSigils};
+pub use unlocks::RunUnlock;
@@ -0,0 +1,226 @@
+//! Body mutations
+//!
+//! Lingering in The Abyss or drinking mutagenic vials twists the player's
+//! body. Every mutation is a trade: a new strength paired with a drawback,
+//! and some leave no room for armor in an equipment slot.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::items::EquipSlot;
+
+/// Most mutations a body can hold
+pub const MAX_MUTATIONS: usize = 4;
+/// Turns spent in The Abyss before the next mutation takes hold
+pub const ABYSS_EXPOSURE_TURNS: u32 = 120;
+
+/// A mutation of the player's body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mutation {
+    /// An extra arm: a chance to strike twice, but it splits your armor
+    WrithingLimb,
+    /// Eyes that see through walls, and draw horrors to you
+    WatchingEyes,
+    /// Chitin grows over your torso: heavy armor, stiff joints
+    ChitinPlates,
+    /// Hands harden into claws
+    VoidClaws,
+    /// Light, hollow bones: faster but frailer
+    HollowBones,
+    /// Abyssal ichor in your veins sharpens the mind and saps the body
+    AbyssalBlood,
+}
+
+impl Mutation {
+    pub const ALL: [Mutation; 6] = [
+        Mutation::WrithingLimb,
+        Mutation::WatchingEyes,
+        Mutation::ChitinPlates,
+        Mutation::VoidClaws,
+        Mutation::HollowBones,
+        Mutation::AbyssalBlood,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mutation::WrithingLimb => "Writhing Limb",
+            Mutation::WatchingEyes => "Watching Eyes",
+            Mutation::ChitinPlates => "Chitin Plates",
+            Mutation::VoidClaws => "Void Claws",
+            Mutation::HollowBones => "Hollow Bones",
+            Mutation::AbyssalBlood => "Abyssal Blood",
+        }
+    }
+
+    /// What the mutation gives
+    pub fn benefit(&self) -> &'static str {
+        match self {
+            Mutation::WrithingLimb => "25% chance to strike twice",
+            Mutation::WatchingEyes => "See enemies through walls",
+            Mutation::ChitinPlates => "+8 armor",
+            Mutation::VoidClaws => "+4 melee damage",
+            Mutation::HollowBones => "+3 DEX",
+            Mutation::AbyssalBlood => "+3 INT",
+        }
+    }
+
+    /// What the mutation costs
+    pub fn drawback(&self) -> &'static str {
+        match self {
+            Mutation::WrithingLimb => "-4 armor",
+            Mutation::WatchingEyes => "Enemies notice you from further away; no helmets",
+            Mutation::ChitinPlates => "-2 DEX; no body armor",
+            Mutation::VoidClaws => "No gloves",
+            Mutation::HollowBones => "-2 VIT",
+            Mutation::AbyssalBlood => "-2 STR",
+        }
+    }
+
+    /// Message shown when the mutation takes hold
+    pub fn onset(&self) -> &'static str {
+        match self {
+            Mutation::WrithingLimb => "A third arm tears its way out of your ribs!",
+            Mutation::WatchingEyes => "Eyes open all over your scalp. You see... too much.",
+            Mutation::ChitinPlates => "Your skin hardens into cracked black chitin.",
+            Mutation::VoidClaws => "Your fingers fuse into long, lightless claws.",
+            Mutation::HollowBones => "Your bones grow light and hollow.",
+            Mutation::AbyssalBlood => "Your blood runs black and whispers to you.",
+        }
+    }
+
+    /// Equipment slot the mutated body can no longer use
+    pub fn blocked_slot(&self) -> Option<EquipSlot> {
+        match self {
+            Mutation::WatchingEyes => Some(EquipSlot::Head),
+            Mutation::ChitinPlates => Some(EquipSlot::Body),
+            Mutation::VoidClaws => Some(EquipSlot::Hands),
+            _ => None,
+        }
+    }
+
+    /// Permanent attribute changes (STR, DEX, INT, VIT)
+    pub fn stat_changes(&self) -> (i32, i32, i32, i32) {
+        match self {
+            Mutation::ChitinPlates => (0, -2, 0, 0),
+            Mutation::HollowBones => (0, 3, 0, -2),
+            Mutation::AbyssalBlood => (-2, 0, 3, 0),
+            _ => (0, 0, 0, 0),
+        }
+    }
+}
+
+/// Mutations carried by the player
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Mutations {
+    pub mutations: Vec<Mutation>,
+    /// Turns spent in The Abyss since the last mutation
+    pub abyss_exposure: u32,
+}
+
+impl Mutations {
+    pub fn has(&self, mutation: Mutation) -> bool {
+        self.mutations.contains(&mutation)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.mutations.len() >= MAX_MUTATIONS
+    }
+
+    /// Armor gained or lost from mutations
+    pub fn armor_modifier(&self) -> i32 {
+        let mut armor = 0;
+        if self.has(Mutation::ChitinPlates) {
+            armor += 8;
+        }
+        if self.has(Mutation::WrithingLimb) {
+            armor -= 4;
+        }
+        armor
+    }
+
+    /// Extra melee damage from mutations
+    pub fn damage_bonus(&self) -> i32 {
+        if self.has(Mutation::VoidClaws) { 4 } else { 0 }
+    }
+
+    /// Chance for a melee attack to be followed by a second strike
+    pub fn extra_attack_chance(&self) -> f64 {
+        if self.has(Mutation::WrithingLimb) { 0.25 } else { 0.0 }
+    }
+
+    /// Range at which enemies are seen through walls (0 = not at all)
+    pub fn xray_range(&self) -> i32 {
+        if self.has(Mutation::WatchingEyes) { 10 } else { 0 }
+    }
+
+    /// Extra range at which enemies notice the player
+    pub fn detection_bonus(&self) -> i32 {
+        if self.has(Mutation::WatchingEyes) { 4 } else { 0 }
+    }
+
+    /// Mutation that prevents using an equipment slot
+    pub fn blocking(&self, slot: EquipSlot) -> Option<Mutation> {
+        self.mutations.iter().copied().find(|m| m.blocked_slot() == Some(slot))
+    }
+
+    /// Count a turn in The Abyss, returning true when a new mutation is due
+    pub fn expose(&mut self) -> bool {
+        self.expose_by(1)
+    }
+
+    /// Count several turns' worth of exposure at once (wading through corrupted blood)
+    pub fn expose_by(&mut self, turns: u32) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.abyss_exposure += turns;
+        if self.abyss_exposure >= ABYSS_EXPOSURE_TURNS {
+            self.abyss_exposure = 0;
+            return true;
+        }
+        false
+    }
+
+    /// Roll a mutation the player doesn't have yet
+    pub fn roll(&self, rng: &mut impl Rng) -> Option<Mutation> {
+        if self.is_full() {
+            return None;
+        }
+        let candidates: Vec<Mutation> = Mutation::ALL.into_iter()
+            .filter(|m| !self.has(*m))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(candidates[rng.gen_range(0..candidates.len())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_mutations_never_repeat() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut mutations = Mutations::default();
+        while let Some(m) = mutations.roll(&mut rng) {
+            assert!(!mutations.has(m));
+            mutations.mutations.push(m);
+        }
+        assert_eq!(mutations.mutations.len(), MAX_MUTATIONS);
+        assert!(!mutations.expose());
+    }
+
+    #[test]
+    fn test_exposure_threshold() {
+        let mut mutations = Mutations::default();
+        for _ in 1..ABYSS_EXPOSURE_TURNS {
+            assert!(!mutations.expose());
+        }
+        assert!(mutations.expose());
+        assert_eq!(mutations.abyss_exposure, 0);
+    }
+}
@@ -0,0 +1,99 @@
+//! New Game Plus
+//!
+//! A victorious player can go around again, keeping one piece of gear and
+//! every skill they learned. Each cycle makes every enemy stronger, and
+//! elites gain modifiers that make them more than just bigger.
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// Enemy HP and power added per NG+ cycle
+const ENEMY_SCALING_PER_CYCLE: f32 = 0.25;
+/// Most modifiers a single elite can carry
+const MAX_ELITE_MODIFIERS: usize = 2;
+/// Share of damage dealt a vampiric elite drinks back
+pub const VAMPIRIC_DRAIN_PERCENT: i32 = 50;
+/// Poison a venomous elite's hits leave behind (seconds, intensity)
+pub const VENOM_POISON: (f32, i32) = (5.0, 2);
+
+/// Enemy HP and power multiplier for an NG+ cycle (1.0 on a first run)
+pub fn ng_plus_multiplier(cycle: u32) -> f32 {
+    1.0 + cycle as f32 * ENEMY_SCALING_PER_CYCLE
+}
+
+/// Something extra an elite brings in NG+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EliteModifier {
+    /// Heals for half the damage it deals
+    Vampiric,
+    /// Hits poison
+    Venomous,
+    /// Half again as much health
+    Juggernaut,
+    /// Half again as much dexterity
+    Swift,
+}
+
+impl EliteModifier {
+    pub const ALL: [EliteModifier; 4] = [
+        EliteModifier::Vampiric,
+        EliteModifier::Venomous,
+        EliteModifier::Juggernaut,
+        EliteModifier::Swift,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EliteModifier::Vampiric => "Vampiric",
+            EliteModifier::Venomous => "Venomous",
+            EliteModifier::Juggernaut => "Juggernaut",
+            EliteModifier::Swift => "Swift",
+        }
+    }
+}
+
+/// Modifiers an elite carries
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EliteModifiers(pub Vec<EliteModifier>);
+
+impl EliteModifiers {
+    /// Roll modifiers for an elite: one per NG+ cycle, up to two, never twice the same
+    pub fn roll(cycle: u32, rng: &mut impl Rng) -> Self {
+        let count = (cycle as usize).min(MAX_ELITE_MODIFIERS);
+        Self(EliteModifier::ALL.choose_multiple(rng, count).copied().collect())
+    }
+
+    pub fn has(&self, modifier: EliteModifier) -> bool {
+        self.0.contains(&modifier)
+    }
+
+    /// The elite's name with its modifiers in front
+    pub fn title(&self, name: &str) -> String {
+        self.0.iter()
+            .map(|m| m.name())
+            .chain(std::iter::once(name))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_elite_modifiers() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        assert!(EliteModifiers::roll(0, &mut rng).0.is_empty());
+        assert_eq!(ng_plus_multiplier(0), 1.0);
+        assert!(ng_plus_multiplier(2) > ng_plus_multiplier(1));
+
+        // Deep cycles cap out at two distinct modifiers
+        let mods = EliteModifiers::roll(5, &mut rng);
+        assert_eq!(mods.0.len(), 2);
+        assert_ne!(mods.0[0], mods.0[1]);
+        assert!(mods.title("Gargoyle").ends_with(" Gargoyle"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Curse sigils
+//!
+//! Run modifiers taken on the new-run screen alongside the difficulty. Each
+//! makes the descent harder in its own way and multiplies the run's score;
+//! they stack, and the run summary lists the ones a run was started under.
+
+use serde::{Deserialize, Serialize};
+
+/// Blast damage from an enemy dying under the Volatile sigil, before depth
+const BLAST_BASE: i32 = 4;
+
+/// A curse a run can be started under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CurseSigil {
+    /// Enemies explode when they die, hurting anyone next to them
+    Volatile,
+    /// No shrines are raised on any floor
+    Forsaken,
+    /// All healing the player receives is halved
+    Anemic,
+    /// Elite zones hold twice as many elites
+    Gauntlet,
+}
+
+impl CurseSigil {
+    pub const ALL: [CurseSigil; 4] = [CurseSigil::Volatile, CurseSigil::Forsaken, CurseSigil::Anemic, CurseSigil::Gauntlet];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CurseSigil::Volatile => "Volatile",
+            CurseSigil::Forsaken => "Forsaken",
+            CurseSigil::Anemic => "Anemic",
+            CurseSigil::Gauntlet => "Gauntlet",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            CurseSigil::Volatile => "Enemies explode on death",
+            CurseSigil::Forsaken => "No shrines",
+            CurseSigil::Anemic => "Half healing",
+            CurseSigil::Gauntlet => "Double elites",
+        }
+    }
+
+    /// What taking the sigil multiplies the run's score by
+    pub fn score_mult(&self) -> f32 {
+        match self {
+            CurseSigil::Volatile => 1.25,
+            CurseSigil::Forsaken => 1.3,
+            CurseSigil::Anemic => 1.4,
+            CurseSigil::Gauntlet => 1.35,
+        }
+    }
+}
+
+/// The curse sigils a run was started under (saved with the run)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurseSigils(Vec<CurseSigil>);
+
+impl CurseSigils {
+    pub fn has(&self, sigil: CurseSigil) -> bool {
+        self.0.contains(&sigil)
+    }
+
+    /// Take a sigil, or put it back
+    pub fn toggle(&mut self, sigil: CurseSigil) {
+        if self.has(sigil) {
+            self.0.retain(|s| *s != sigil);
+        } else {
+            self.0.push(sigil);
+            self.0.sort_by_key(|s| CurseSigil::ALL.iter().position(|a| a == s));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = CurseSigil> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// The sigils' score multipliers, compounded
+    pub fn score_mult(&self) -> f32 {
+        self.0.iter().map(|s| s.score_mult()).product()
+    }
+
+    /// Healing the player actually receives
+    pub fn healing(&self, amount: i32) -> i32 {
+        if self.has(CurseSigil::Anemic) {
+            (amount + 1) / 2
+        } else {
+            amount
+        }
+    }
+
+    /// Elites guaranteed in each elite zone
+    pub fn elites_per_zone(&self) -> usize {
+        if self.has(CurseSigil::Gauntlet) { 2 } else { 1 }
+    }
+
+    /// Damage an enemy's death blast deals on a floor, if enemies explode
+    pub fn death_blast(&self, floor: u32) -> Option<i32> {
+        self.has(CurseSigil::Volatile).then(|| BLAST_BASE + floor as i32)
+    }
+
+    /// The sigils' names, for summaries
+    pub fn names(&self) -> String {
+        self.0.iter().map(|s| s.name()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sigils_stack_their_curses_and_score() {
+        let mut sigils = CurseSigils::default();
+        assert_eq!(sigils.score_mult(), 1.0);
+        assert_eq!(sigils.healing(9), 9);
+        assert_eq!(sigils.death_blast(5), None);
+
+        sigils.toggle(CurseSigil::Gauntlet);
+        sigils.toggle(CurseSigil::Anemic);
+        assert_eq!(sigils.names(), "Anemic, Gauntlet");
+        assert!((sigils.score_mult() - 1.4 * 1.35).abs() < 1e-4);
+        assert_eq!(sigils.healing(9), 5);
+        assert_eq!(sigils.healing(1), 1);
+        assert_eq!(sigils.elites_per_zone(), 2);
+
+        sigils.toggle(CurseSigil::Anemic);
+        assert!(!sigils.has(CurseSigil::Anemic));
+        assert_eq!(sigils.healing(9), 9);
+    }
+}
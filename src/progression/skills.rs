@@ -8,6 +8,16 @@ use rand::Rng;
 /// Unique skill ID
 pub type SkillId = u32;
 
+/// Highest rank a skill can be upgraded to
+pub const MAX_SKILL_RANK: u8 = 3;
+
+/// Chance that a shrine offer is an upgrade to a skill the player knows
+const UPGRADE_OFFER_CHANCE: f64 = 0.35;
+
+fn first_rank() -> u8 {
+    1
+}
+
 /// Skill rarity determines power level and availability
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SkillRarity {
@@ -81,10 +91,31 @@ pub enum SkillEffect {
     BuffSelf { buff: BuffType, duration: u32 },
     /// Move/teleport
     Movement { range: i32 },
+    /// Knock targets away from the player
+    Knockback { distance: i32 },
+    /// Drag targets next to the player
+    Pull,
+    /// Freeze lava around the player into solid floor
+    FreezeLava { radius: i32 },
+    /// Devour a nearby corpse for health and mana
+    ConsumeCorpse { heal: i32 },
+    /// Detonate a nearby corpse, hurting enemies around it
+    ExplodeCorpse { base: i32, radius: i32 },
     /// Combined effects
     Multi(Vec<SkillEffect>),
 }
 
+impl SkillEffect {
+    /// Occult effects that need a corpse nearby to work
+    pub fn needs_corpse(&self) -> bool {
+        match self {
+            SkillEffect::ConsumeCorpse { .. } | SkillEffect::ExplodeCorpse { .. } => true,
+            SkillEffect::Multi(effects) => effects.iter().any(SkillEffect::needs_corpse),
+            _ => false,
+        }
+    }
+}
+
 /// Stat that the skill scales with
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ScalingStat {
@@ -116,6 +147,8 @@ pub enum BuffType {
     Regeneration(i32),
     Haste,
     Shield(i32),
+    /// A passive aura for the buff's duration
+    Aura(crate::combat::Aura),
 }
 
 /// A skill definition
@@ -126,6 +159,9 @@ pub struct Skill {
     pub description: String,
     pub icon: char,
     pub rarity: SkillRarity,
+    /// 1 when first learned, raised by upgrades at skill shrines
+    #[serde(default = "first_rank")]
+    pub rank: u8,
     pub cost: SkillCost,
     pub cooldown_turns: u8,
     pub target: TargetType,
@@ -148,6 +184,69 @@ impl Skill {
             _ => 0,
         }
     }
+
+    /// Roman numeral for ranks above the first ("" at rank 1)
+    pub fn rank_label(&self) -> &'static str {
+        match self.rank {
+            0 | 1 => "",
+            2 => "II",
+            3 => "III",
+            _ => "+",
+        }
+    }
+
+    /// Name with the rank appended, e.g. "Power Strike II"
+    pub fn display_name(&self) -> String {
+        match self.rank_label() {
+            "" => self.name.clone(),
+            label => format!("{} {}", self.name, label),
+        }
+    }
+
+    pub fn is_max_rank(&self) -> bool {
+        self.rank >= MAX_SKILL_RANK
+    }
+
+    /// The skill one rank higher: stronger effects, a shorter cooldown and an
+    /// extra charge for charge skills. None if it's already at max rank.
+    pub fn upgraded(&self) -> Option<Skill> {
+        if self.is_max_rank() {
+            return None;
+        }
+        let mut skill = self.clone();
+        skill.rank += 1;
+        skill.effect = upgrade_effect(&skill.effect);
+        if skill.cooldown_turns > 1 {
+            skill.cooldown_turns -= 1;
+        }
+        if let SkillCost::Charge(n) = skill.cost {
+            skill.cost = SkillCost::Charge(n + 1);
+        }
+        Some(skill)
+    }
+}
+
+/// One rank's worth of improvement to a skill effect
+fn upgrade_effect(effect: &SkillEffect) -> SkillEffect {
+    // Roughly +35% per rank, always at least +1
+    let boost = |n: i32| n + (n * 35 / 100).max(1);
+    match effect {
+        SkillEffect::Damage { base, scaling_stat } => SkillEffect::Damage { base: boost(*base), scaling_stat: *scaling_stat },
+        SkillEffect::Heal { base, scaling_stat } => SkillEffect::Heal { base: boost(*base), scaling_stat: *scaling_stat },
+        SkillEffect::ApplyStatus { status, duration, chance } => SkillEffect::ApplyStatus {
+            status: *status,
+            duration: duration + 1,
+            chance: (chance + 0.15).min(1.0),
+        },
+        SkillEffect::BuffSelf { buff, duration } => SkillEffect::BuffSelf { buff: *buff, duration: duration + 1 },
+        SkillEffect::Movement { range } => SkillEffect::Movement { range: range + 1 },
+        SkillEffect::Knockback { distance } => SkillEffect::Knockback { distance: distance + 1 },
+        SkillEffect::Pull => SkillEffect::Pull,
+        SkillEffect::FreezeLava { radius } => SkillEffect::FreezeLava { radius: radius + 1 },
+        SkillEffect::ConsumeCorpse { heal } => SkillEffect::ConsumeCorpse { heal: boost(*heal) },
+        SkillEffect::ExplodeCorpse { base, radius } => SkillEffect::ExplodeCorpse { base: boost(*base), radius: *radius },
+        SkillEffect::Multi(effects) => SkillEffect::Multi(effects.iter().map(upgrade_effect).collect()),
+    }
 }
 
 /// Player's equipped skills (up to 5 slots)
@@ -181,6 +280,30 @@ impl EquippedSkills {
         self.learned.iter().any(|s| s.id == skill_id)
     }
 
+    /// Current rank of a learned skill
+    pub fn rank_of(&self, skill_id: SkillId) -> Option<u8> {
+        self.learned.iter().find(|s| s.id == skill_id).map(|s| s.rank)
+    }
+
+    /// Replace a known skill with its upgraded version, in the learned list
+    /// and in any slot it's equipped to. A slotted charge skill gets its
+    /// charges refilled. Returns false if the skill isn't known.
+    pub fn upgrade(&mut self, upgraded: Skill) -> bool {
+        let Some(known) = self.learned.iter_mut().find(|s| s.id == upgraded.id) else {
+            return false;
+        };
+        *known = upgraded.clone();
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            if slot.as_ref().is_some_and(|s| s.id == upgraded.id) {
+                if let SkillCost::Charge(n) = upgraded.cost {
+                    self.charges[i] = n;
+                }
+                *slot = Some(upgraded.clone());
+            }
+        }
+        true
+    }
+
     /// Get all learned skills that are not currently equipped
     pub fn unequipped_skills(&self) -> Vec<&Skill> {
         self.learned.iter()
@@ -312,6 +435,7 @@ pub fn skill_power_strike() -> Skill {
         description: "A powerful attack dealing 150% weapon damage.".to_string(),
         icon: '⚔',
         rarity: SkillRarity::Common,
+        rank: 1,
         cost: SkillCost::Stamina(15),
         cooldown_turns: 2,
         target: TargetType::SingleEnemy,
@@ -330,6 +454,7 @@ pub fn skill_first_aid() -> Skill {
         description: "Heal yourself for 20 + VIT HP.".to_string(),
         icon: '❤',
         rarity: SkillRarity::Common,
+        rank: 1,
         cost: SkillCost::Mana(20),
         cooldown_turns: 4,
         target: TargetType::Self_,
@@ -351,6 +476,7 @@ pub fn skill_quick_strike() -> Skill {
         description: "A fast attack with DEX scaling.".to_string(),
         icon: '⚡',
         rarity: SkillRarity::Common,
+        rank: 1,
         cost: SkillCost::Stamina(8),
         cooldown_turns: 1,
         target: TargetType::SingleEnemy,
@@ -368,6 +494,7 @@ pub fn skill_bandage() -> Skill {
         description: "Heal 15 HP. No cooldown but costs more.".to_string(),
         icon: '🩹',
         rarity: SkillRarity::Common,
+        rank: 1,
         cost: SkillCost::Mana(25),
         cooldown_turns: 0,
         target: TargetType::Self_,
@@ -385,6 +512,7 @@ pub fn skill_bash() -> Skill {
         description: "Heavy blow. 30% chance to stun for 2 turns.".to_string(),
         icon: '💥',
         rarity: SkillRarity::Common,
+        rank: 1,
         cost: SkillCost::Stamina(12),
         cooldown_turns: 3,
         target: TargetType::SingleEnemy,
@@ -413,6 +541,7 @@ pub fn skill_envenom() -> Skill {
         description: "Strike with a poisoned blade. 60% chance to poison.".to_string(),
         icon: '☠',
         rarity: SkillRarity::Uncommon,
+        rank: 1,
         cost: SkillCost::Stamina(10),
         cooldown_turns: 3,
         target: TargetType::SingleEnemy,
@@ -437,6 +566,7 @@ pub fn skill_iron_skin() -> Skill {
         description: "Gain +5 armor for 5 turns.".to_string(),
         icon: '🛡',
         rarity: SkillRarity::Uncommon,
+        rank: 1,
         cost: SkillCost::Mana(15),
         cooldown_turns: 6,
         target: TargetType::Self_,
@@ -447,6 +577,42 @@ pub fn skill_iron_skin() -> Skill {
     }
 }
 
+pub fn skill_shield_bash() -> Skill {
+    Skill {
+        id: 23,
+        name: "Shield Bash".to_string(),
+        description: "Knock an enemy back 2 tiles. Walls hurt, hazards kill.".to_string(),
+        icon: '⛨',
+        rarity: SkillRarity::Uncommon,
+        rank: 1,
+        cost: SkillCost::Stamina(14),
+        cooldown_turns: 3,
+        target: TargetType::SingleEnemy,
+        effect: SkillEffect::Multi(vec![
+            SkillEffect::Damage {
+                base: 3,
+                scaling_stat: ScalingStat::Strength,
+            },
+            SkillEffect::Knockback { distance: 2 },
+        ]),
+    }
+}
+
+pub fn skill_grappling_hook() -> Skill {
+    Skill {
+        id: 24,
+        name: "Grappling Hook".to_string(),
+        description: "Hook the nearest enemy and drag it to you.".to_string(),
+        icon: '⚓',
+        rarity: SkillRarity::Uncommon,
+        rank: 1,
+        cost: SkillCost::Stamina(10),
+        cooldown_turns: 4,
+        target: TargetType::SingleEnemy,
+        effect: SkillEffect::Pull,
+    }
+}
+
 pub fn skill_burning_strike() -> Skill {
     Skill {
         id: 20,
@@ -454,6 +620,7 @@ pub fn skill_burning_strike() -> Skill {
         description: "Fire-infused attack. 50% chance to burn.".to_string(),
         icon: '🔥',
         rarity: SkillRarity::Uncommon,
+        rank: 1,
         cost: SkillCost::Mana(12),
         cooldown_turns: 2,
         target: TargetType::SingleEnemy,
@@ -475,16 +642,23 @@ pub fn skill_battle_cry() -> Skill {
     Skill {
         id: 21,
         name: "Battle Cry".to_string(),
-        description: "Boost STR by 3 for 4 turns.".to_string(),
+        description: "Boost STR by 3 and unnerve enemies within 4 for 4 turns.".to_string(),
         icon: '📢',
         rarity: SkillRarity::Uncommon,
+        rank: 1,
         cost: SkillCost::Stamina(15),
         cooldown_turns: 5,
         target: TargetType::Self_,
-        effect: SkillEffect::BuffSelf {
-            buff: BuffType::Strength(3),
-            duration: 4,
-        },
+        effect: SkillEffect::Multi(vec![
+            SkillEffect::BuffSelf {
+                buff: BuffType::Strength(3),
+                duration: 4,
+            },
+            SkillEffect::BuffSelf {
+                buff: BuffType::Aura(crate::combat::Aura::Fear { radius: 4, morale: 30 }),
+                duration: 4,
+            },
+        ]),
     }
 }
 
@@ -495,6 +669,7 @@ pub fn skill_recuperate() -> Skill {
         description: "Heal over time. +3 HP/turn for 5 turns.".to_string(),
         icon: '💚',
         rarity: SkillRarity::Uncommon,
+        rank: 1,
         cost: SkillCost::Mana(18),
         cooldown_turns: 6,
         target: TargetType::Self_,
@@ -509,6 +684,21 @@ pub fn skill_recuperate() -> Skill {
 // Rare Skills
 // =============================================================================
 
+pub fn skill_devour_the_dead() -> Skill {
+    Skill {
+        id: 25,
+        name: "Devour the Dead".to_string(),
+        description: "Occultist rite. Consume a nearby corpse to restore health and mana.".to_string(),
+        icon: '☠',
+        rarity: SkillRarity::Uncommon,
+        rank: 1,
+        cost: SkillCost::Cooldown,
+        cooldown_turns: 6,
+        target: TargetType::Self_,
+        effect: SkillEffect::ConsumeCorpse { heal: 15 },
+    }
+}
+
 pub fn skill_whirlwind() -> Skill {
     Skill {
         id: 5,
@@ -516,6 +706,7 @@ pub fn skill_whirlwind() -> Skill {
         description: "Attack all adjacent enemies.".to_string(),
         icon: '🌀',
         rarity: SkillRarity::Rare,
+        rank: 1,
         cost: SkillCost::Stamina(25),
         cooldown_turns: 4,
         target: TargetType::AllAdjacent,
@@ -533,6 +724,7 @@ pub fn skill_shadow_step() -> Skill {
         description: "Teleport up to 4 tiles away.".to_string(),
         icon: '👤',
         rarity: SkillRarity::Rare,
+        rank: 1,
         cost: SkillCost::Stamina(20),
         cooldown_turns: 5,
         target: TargetType::Self_,
@@ -544,9 +736,10 @@ pub fn skill_frost_nova() -> Skill {
     Skill {
         id: 31,
         name: "Frost Nova".to_string(),
-        description: "Freeze all adjacent enemies. 70% slow for 3 turns.".to_string(),
+        description: "Freeze adjacent enemies (70% slow) and nearby lava.".to_string(),
         icon: '❄',
         rarity: SkillRarity::Rare,
+        rank: 1,
         cost: SkillCost::Mana(22),
         cooldown_turns: 5,
         target: TargetType::AllAdjacent,
@@ -560,6 +753,7 @@ pub fn skill_frost_nova() -> Skill {
                 duration: 3,
                 chance: 0.7,
             },
+            SkillEffect::FreezeLava { radius: 2 },
         ]),
     }
 }
@@ -571,6 +765,7 @@ pub fn skill_life_drain() -> Skill {
         description: "Steal life from an enemy. Deals damage and heals you.".to_string(),
         icon: '🩸',
         rarity: SkillRarity::Rare,
+        rank: 1,
         cost: SkillCost::Mana(20),
         cooldown_turns: 4,
         target: TargetType::SingleEnemy,
@@ -594,6 +789,7 @@ pub fn skill_executioner() -> Skill {
         description: "Massive damage to a single target.".to_string(),
         icon: '⚰',
         rarity: SkillRarity::Rare,
+        rank: 1,
         cost: SkillCost::Stamina(30),
         cooldown_turns: 5,
         target: TargetType::SingleEnemy,
@@ -604,6 +800,21 @@ pub fn skill_executioner() -> Skill {
     }
 }
 
+pub fn skill_corpse_explosion() -> Skill {
+    Skill {
+        id: 34,
+        name: "Corpse Explosion".to_string(),
+        description: "Occultist rite. Detonate a nearby corpse, blasting every enemy around it.".to_string(),
+        icon: '✸',
+        rarity: SkillRarity::Rare,
+        rank: 1,
+        cost: SkillCost::Mana(15),
+        cooldown_turns: 3,
+        target: TargetType::Self_,
+        effect: SkillEffect::ExplodeCorpse { base: 14, radius: 2 },
+    }
+}
+
 // =============================================================================
 // Epic Skills
 // =============================================================================
@@ -615,6 +826,7 @@ pub fn skill_berserker_rage() -> Skill {
         description: "Go berserk! +5 STR, +3 DEX for 6 turns.".to_string(),
         icon: '😡',
         rarity: SkillRarity::Epic,
+        rank: 1,
         cost: SkillCost::Charge(2),
         cooldown_turns: 0,
         target: TargetType::Self_,
@@ -638,6 +850,7 @@ pub fn skill_chain_lightning() -> Skill {
         description: "Lightning bounces to all enemies in range 3.".to_string(),
         icon: '⚡',
         rarity: SkillRarity::Epic,
+        rank: 1,
         cost: SkillCost::Mana(35),
         cooldown_turns: 6,
         target: TargetType::AllInRange(3),
@@ -655,6 +868,7 @@ pub fn skill_shield_wall() -> Skill {
         description: "Absorb 30 damage before taking HP loss.".to_string(),
         icon: '🏰',
         rarity: SkillRarity::Epic,
+        rank: 1,
         cost: SkillCost::Mana(25),
         cooldown_turns: 8,
         target: TargetType::Self_,
@@ -672,6 +886,7 @@ pub fn skill_assassinate() -> Skill {
         description: "Critical strike with 100% bleed chance.".to_string(),
         icon: '🗡',
         rarity: SkillRarity::Epic,
+        rank: 1,
         cost: SkillCost::Stamina(35),
         cooldown_turns: 6,
         target: TargetType::SingleEnemy,
@@ -700,6 +915,7 @@ pub fn skill_meteor_strike() -> Skill {
         description: "Call down a meteor! Massive AoE damage.".to_string(),
         icon: '☄',
         rarity: SkillRarity::Legendary,
+        rank: 1,
         cost: SkillCost::Charge(1),
         cooldown_turns: 0,
         target: TargetType::Ground { range: 5, radius: 2 },
@@ -717,6 +933,7 @@ pub fn skill_divine_intervention() -> Skill {
         description: "Full heal and clear all debuffs.".to_string(),
         icon: '✨',
         rarity: SkillRarity::Legendary,
+        rank: 1,
         cost: SkillCost::Charge(1),
         cooldown_turns: 0,
         target: TargetType::Self_,
@@ -734,6 +951,7 @@ pub fn skill_deaths_embrace() -> Skill {
         description: "Mark of death. Huge damage + poison + bleed.".to_string(),
         icon: '💀',
         rarity: SkillRarity::Legendary,
+        rank: 1,
         cost: SkillCost::Mana(50),
         cooldown_turns: 8,
         target: TargetType::SingleEnemy,
@@ -782,6 +1000,9 @@ pub fn all_skills_by_rarity(rarity: SkillRarity) -> Vec<Skill> {
             skill_burning_strike(),
             skill_battle_cry(),
             skill_recuperate(),
+            skill_shield_bash(),
+            skill_grappling_hook(),
+            skill_devour_the_dead(),
         ],
         SkillRarity::Rare => vec![
             skill_whirlwind(),
@@ -789,6 +1010,7 @@ pub fn all_skills_by_rarity(rarity: SkillRarity) -> Vec<Skill> {
             skill_frost_nova(),
             skill_life_drain(),
             skill_executioner(),
+            skill_corpse_explosion(),
         ],
         SkillRarity::Epic => vec![
             skill_berserker_rage(),
@@ -869,17 +1091,38 @@ pub fn roll_skill_rarity(floor: u32, rng: &mut impl Rng) -> SkillRarity {
     }
 }
 
-/// Generate random skills for a shrine based on floor
-pub fn generate_shrine_skills(floor: u32, count: usize, rng: &mut impl Rng) -> Vec<Skill> {
-    let mut skills = Vec::new();
+/// Generate random skills for a shrine based on floor. Skills the player
+/// already knows are offered as upgrades to their next rank, and some offers
+/// are upgrades to a known skill outright.
+pub fn generate_shrine_skills(floor: u32, count: usize, known: &EquippedSkills, rng: &mut impl Rng) -> Vec<Skill> {
+    let upgradable: Vec<&Skill> = known.learned.iter().filter(|s| !s.is_max_rank()).collect();
+    let mut skills: Vec<Skill> = Vec::new();
+
+    // A few spare attempts so duplicates and maxed skills don't leave gaps
+    for _ in 0..count * 4 {
+        if skills.len() >= count {
+            break;
+        }
 
-    for _ in 0..count {
-        let rarity = roll_skill_rarity(floor, rng);
-        let available = all_skills_by_rarity(rarity);
+        let offer = if !upgradable.is_empty() && rng.gen_bool(UPGRADE_OFFER_CHANCE) {
+            upgradable[rng.gen_range(0..upgradable.len())].upgraded()
+        } else {
+            let rarity = roll_skill_rarity(floor, rng);
+            let available = all_skills_by_rarity(rarity);
+            if available.is_empty() {
+                continue;
+            }
+            let skill = available[rng.gen_range(0..available.len())].clone();
+            match known.learned.iter().find(|s| s.id == skill.id) {
+                Some(learned) => learned.upgraded(),
+                None => Some(skill),
+            }
+        };
 
-        if !available.is_empty() {
-            let idx = rng.gen_range(0..available.len());
-            skills.push(available[idx].clone());
+        if let Some(skill) = offer {
+            if !skills.iter().any(|s| s.id == skill.id) {
+                skills.push(skill);
+            }
         }
     }
 
@@ -896,3 +1139,31 @@ pub fn learnable_skills() -> Vec<Skill> {
     all.extend(all_skills_by_rarity(SkillRarity::Legendary));
     all
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skill_upgrade() {
+        let mut skills = EquippedSkills::new();
+        skills.equip(0, skill_power_strike());
+
+        let rank2 = skill_power_strike().upgraded().unwrap();
+        assert_eq!(rank2.rank, 2);
+        assert_eq!(rank2.display_name(), "Power Strike II");
+        assert!(rank2.cooldown_turns < skill_power_strike().cooldown_turns);
+        assert!(matches!(rank2.effect, SkillEffect::Damage { base, .. } if base > 5));
+
+        assert!(skills.upgrade(rank2));
+        assert_eq!(skills.rank_of(1), Some(2));
+        assert_eq!(skills.slots[0].as_ref().map(|s| s.rank), Some(2));
+
+        let rank3 = skills.learned[0].upgraded().unwrap();
+        assert!(rank3.is_max_rank());
+        assert!(rank3.upgraded().is_none());
+
+        // Unknown skills can't be upgraded
+        assert!(!skills.upgrade(skill_whirlwind()));
+    }
+}
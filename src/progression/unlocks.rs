@@ -63,6 +63,47 @@ impl Unlocks {
     }
 }
 
+/// Unlocks earned by achievements that change how every later run's floors
+/// are generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunUnlock {
+    /// The floor before each boss (every fifth floor) always has a rest shrine
+    PilgrimsRest,
+    /// The Jeweler can turn up on a floor, selling rings and amulets
+    Jeweler,
+    /// Every shop sets out one more ware
+    DeepPockets,
+}
+
+impl RunUnlock {
+    pub const ALL: [RunUnlock; 3] = [RunUnlock::PilgrimsRest, RunUnlock::Jeweler, RunUnlock::DeepPockets];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RunUnlock::PilgrimsRest => "Pilgrim's Rest",
+            RunUnlock::Jeweler => "The Jeweler",
+            RunUnlock::DeepPockets => "Deep Pockets",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            RunUnlock::PilgrimsRest => "A rest shrine on every fifth floor",
+            RunUnlock::Jeweler => "The Jeweler may be met in the dungeon",
+            RunUnlock::DeepPockets => "Shops stock an extra ware",
+        }
+    }
+
+    /// The achievement that earns the unlock
+    pub fn achievement(&self) -> &'static str {
+        match self {
+            RunUnlock::PilgrimsRest => "reach_floor_10",
+            RunUnlock::Jeweler => "defeat_first_boss",
+            RunUnlock::DeepPockets => "collect_10000_gold",
+        }
+    }
+}
+
 /// Achievements that can trigger unlocks
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Achievement {
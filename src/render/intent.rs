@@ -0,0 +1,122 @@
+//! Enemy intent icons
+//!
+//! Small markers drawn next to visible enemies: what the enemy means to do
+//! above it, and the worst of its status effects beside it. Both renderers
+//! read the same glyphs and colours from here.
+
+use crate::ecs::{AIState, EnemyArchetype, StatusEffectType};
+
+/// How far a caster is from the player when it starts to cast
+pub const CASTING_RANGE: i32 = 6;
+
+/// What an enemy is about to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intent {
+    Alerted,
+    Sleeping,
+    Fleeing,
+    Casting,
+}
+
+impl Intent {
+    pub fn glyph(&self, ascii: bool) -> char {
+        match (self, ascii) {
+            (Intent::Alerted, _) => '!',
+            (Intent::Sleeping, _) => 'z',
+            (Intent::Fleeing, false) => '↓',
+            (Intent::Fleeing, true) => 'v',
+            (Intent::Casting, false) => '⚡',
+            (Intent::Casting, true) => '*',
+        }
+    }
+
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            Intent::Alerted => (255, 90, 60),
+            Intent::Sleeping => (130, 150, 200),
+            Intent::Fleeing => (220, 220, 120),
+            Intent::Casting => (190, 120, 255),
+        }
+    }
+}
+
+/// Intent of an enemy in an AI state, or None if it's just idling about.
+/// Casters and necromancers ready to raise are casting once the player is
+/// close enough.
+pub fn enemy_intent(state: AIState, archetype: EnemyArchetype, spell_ready: bool, distance: i32) -> Option<Intent> {
+    match state {
+        AIState::Sleeping => Some(Intent::Sleeping),
+        AIState::Flee => Some(Intent::Fleeing),
+        AIState::Chase | AIState::Attack => {
+            let caster = archetype == EnemyArchetype::Caster || spell_ready;
+            if caster && distance <= CASTING_RANGE {
+                Some(Intent::Casting)
+            } else {
+                Some(Intent::Alerted)
+            }
+        }
+        AIState::Idle | AIState::Patrol => None,
+    }
+}
+
+/// Single-cell marker and colour for a status effect
+pub fn status_glyph(effect: StatusEffectType, ascii: bool) -> (char, (u8, u8, u8)) {
+    let glyph = match (effect, ascii) {
+        (StatusEffectType::Poison, _) => 'p',
+        (StatusEffectType::Burn, _) => 'b',
+        (StatusEffectType::Bleed, _) => 'x',
+        (StatusEffectType::Slow, _) => 's',
+        (StatusEffectType::Stun, false) => '✶',
+        (StatusEffectType::Stun, true) => '#',
+        (StatusEffectType::Weakness, _) => 'w',
+        (StatusEffectType::Curse, false) => '☽',
+        (StatusEffectType::Curse, true) => 'c',
+        (StatusEffectType::Regeneration, _) => '+',
+        (StatusEffectType::Haste, _) => 'h',
+        (StatusEffectType::Shield, _) => 'o',
+        (StatusEffectType::Strength, _) => '^',
+    };
+    let color = match effect {
+        StatusEffectType::Poison => (80, 200, 80),
+        StatusEffectType::Burn => (255, 120, 40),
+        StatusEffectType::Bleed => (200, 30, 30),
+        StatusEffectType::Slow => (90, 140, 255),
+        StatusEffectType::Stun => (255, 230, 80),
+        StatusEffectType::Weakness => (200, 90, 200),
+        StatusEffectType::Curse => (140, 70, 140),
+        StatusEffectType::Regeneration => (90, 220, 120),
+        StatusEffectType::Haste => (255, 230, 80),
+        StatusEffectType::Shield => (90, 210, 230),
+        StatusEffectType::Strength => (230, 90, 70),
+    };
+    (glyph, color)
+}
+
+/// Which of several status effects to show when there's only room for one:
+/// disabling ones first, then damage over time, then the rest
+pub fn most_pressing(effects: impl IntoIterator<Item = StatusEffectType>) -> Option<StatusEffectType> {
+    effects.into_iter().min_by_key(|effect| match effect {
+        StatusEffectType::Stun => 0,
+        StatusEffectType::Slow | StatusEffectType::Weakness | StatusEffectType::Curse => 1,
+        StatusEffectType::Burn | StatusEffectType::Poison | StatusEffectType::Bleed => 2,
+        _ => 3,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enemy_intent() {
+        assert_eq!(enemy_intent(AIState::Patrol, EnemyArchetype::Melee, false, 2), None);
+        assert_eq!(enemy_intent(AIState::Chase, EnemyArchetype::Melee, false, 2), Some(Intent::Alerted));
+        assert_eq!(enemy_intent(AIState::Chase, EnemyArchetype::Caster, false, 3), Some(Intent::Casting));
+        assert_eq!(enemy_intent(AIState::Chase, EnemyArchetype::Caster, false, 10), Some(Intent::Alerted));
+        assert_eq!(enemy_intent(AIState::Chase, EnemyArchetype::Tank, true, 4), Some(Intent::Casting));
+        assert_eq!(enemy_intent(AIState::Sleeping, EnemyArchetype::Caster, true, 1), Some(Intent::Sleeping));
+
+        let shown = most_pressing([StatusEffectType::Poison, StatusEffectType::Stun, StatusEffectType::Haste]);
+        assert_eq!(shown, Some(StatusEffectType::Stun));
+    }
+}
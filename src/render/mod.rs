@@ -9,6 +9,9 @@ pub mod mode;
 pub mod kitty;
 pub mod sprites;
 pub mod tilemap;
+pub mod intent;
+pub mod preview;
+pub mod screenshot;
 
 pub use mode::{RenderMode, detect_render_mode};
 pub use kitty::KittyGraphics;
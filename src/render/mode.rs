@@ -4,8 +4,10 @@
 
 use std::env;
 
+use serde::{Deserialize, Serialize};
+
 /// Available rendering modes
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum RenderMode {
     /// Classic ASCII characters (@ # . etc.)
     /// Works everywhere, nostalgic feel
@@ -49,84 +51,162 @@ impl RenderMode {
     }
 }
 
-/// Detect the best rendering mode for the current terminal
-pub fn detect_render_mode() -> RenderMode {
-    // Check for Kitty graphics protocol support
-    if is_kitty_supported() {
-        log::info!("Detected Kitty graphics protocol support");
-        return RenderMode::Kitty;
+impl RenderMode {
+    /// Read a mode as given on the command line
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "ascii" => Some(RenderMode::Ascii),
+            "unicode" => Some(RenderMode::Unicode),
+            "nerd" | "nerdfont" | "nerd-font" => Some(RenderMode::NerdFont),
+            "kitty" => Some(RenderMode::Kitty),
+            _ => None,
+        }
     }
+}
 
-    // Default to Unicode for modern terminals
-    if is_unicode_supported() {
-        log::info!("Using Unicode rendering mode");
-        return RenderMode::Unicode;
-    }
+/// What the terminal was found to support, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    pub mode: RenderMode,
+    pub true_color: bool,
+    /// How detection got there, for the log
+    pub reasons: Vec<String>,
+}
 
-    // Fallback to ASCII
-    log::info!("Falling back to ASCII rendering mode");
-    RenderMode::Ascii
+/// Where detection looks: the environment and the terminfo database
+pub struct Probe<'a> {
+    pub var: &'a dyn Fn(&str) -> Option<String>,
+    pub has_terminfo: &'a dyn Fn(&str) -> bool,
 }
 
-/// Check if Kitty graphics protocol is likely supported
-fn is_kitty_supported() -> bool {
-    // Check TERM environment variable
-    if let Ok(term) = env::var("TERM") {
-        let term_lower = term.to_lowercase();
-        if term_lower.contains("kitty") || term_lower.contains("ghostty") {
-            return true;
-        }
+impl Probe<'_> {
+    /// The environment as this process sees it
+    pub fn system() -> Probe<'static> {
+        Probe { var: &|name| env::var(name).ok(), has_terminfo: &terminfo_exists }
     }
 
-    // Check TERM_PROGRAM for additional terminals
-    if let Ok(term_prog) = env::var("TERM_PROGRAM") {
-        let prog_lower = term_prog.to_lowercase();
-        if prog_lower.contains("kitty")
-            || prog_lower.contains("ghostty")
-            || prog_lower.contains("wezterm")
-            || prog_lower.contains("iterm")
-        {
-            return true;
-        }
+    fn get(&self, name: &str) -> Option<String> {
+        (self.var)(name).filter(|value| !value.is_empty())
     }
+}
 
-    // Check for KITTY_WINDOW_ID (set by Kitty terminal)
-    if env::var("KITTY_WINDOW_ID").is_ok() {
-        return true;
+/// Detect the best rendering mode for the current terminal, logging why
+pub fn detect_render_mode() -> RenderMode {
+    let detection = detect(&Probe::system());
+    for reason in &detection.reasons {
+        log::info!(target: "hollowdeep::render", "{}", reason);
     }
+    log::info!(target: "hollowdeep::render", "Detected render mode: {} (true color: {})", detection.mode.name(), detection.true_color);
+    detection.mode
+}
 
-    // Check for GHOSTTY_RESOURCES_DIR (set by Ghostty)
-    if env::var("GHOSTTY_RESOURCES_DIR").is_ok() {
-        return true;
-    }
+/// Work out what the terminal can draw. Kitty graphics are only used when
+/// the terminal is known to speak the protocol and nothing sits in between:
+/// tmux and screen don't pass the images through, and a session over SSH
+/// without a terminfo entry for its terminal is one to be careful with.
+pub fn detect(probe: &Probe) -> Detection {
+    let mut reasons = Vec::new();
+    let term_raw = probe.get("TERM").unwrap_or_default();
+    let term = term_raw.to_lowercase();
+    let program = probe.get("TERM_PROGRAM").unwrap_or_default().to_lowercase();
 
-    // Check WEZTERM_PANE (set by WezTerm)
-    if env::var("WEZTERM_PANE").is_ok() {
-        return true;
+    let multiplexer = if probe.get("TMUX").is_some() || term.starts_with("tmux") || program == "tmux" {
+        Some("tmux")
+    } else if probe.get("STY").is_some() || term.starts_with("screen") {
+        Some("screen")
+    } else {
+        None
+    };
+    let ssh = ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"].iter().any(|name| probe.get(name).is_some());
+    let terminfo = !term.is_empty() && (probe.has_terminfo)(&term_raw);
+    if term.is_empty() {
+        reasons.push("TERM is not set".to_string());
+    } else if !terminfo {
+        reasons.push(format!("No terminfo entry for TERM={}", term_raw));
+    }
+    if let Some(multiplexer) = multiplexer {
+        reasons.push(format!("Running inside {}", multiplexer));
+    }
+    if ssh {
+        reasons.push("Running over SSH".to_string());
     }
 
-    false
-}
+    // The variables each graphics-capable terminal leaves behind
+    let kitty_evidence = if term.contains("kitty") || term.contains("ghostty") {
+        Some(format!("TERM={}", term_raw))
+    } else if ["kitty", "ghostty", "wezterm", "iterm"].iter().any(|name| program.contains(name)) {
+        Some(format!("TERM_PROGRAM={}", program))
+    } else {
+        ["KITTY_WINDOW_ID", "GHOSTTY_RESOURCES_DIR", "WEZTERM_PANE"]
+            .into_iter()
+            .find(|name| probe.get(name).is_some())
+            .map(|name| format!("{} is set", name))
+    };
 
-/// Check if Unicode is well-supported
-fn is_unicode_supported() -> bool {
-    // Check LANG for UTF-8
-    if let Ok(lang) = env::var("LANG") {
-        if lang.to_uppercase().contains("UTF") {
-            return true;
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"].into_iter().find_map(|name| probe.get(name).map(|value| (name, value)));
+    let mode = if term == "dumb" {
+        reasons.push("TERM=dumb draws nothing but plain text".to_string());
+        RenderMode::Ascii
+    } else if term == "linux" || term.starts_with("vt") {
+        reasons.push(format!("TERM={} has only a console font's glyphs", term_raw));
+        RenderMode::Ascii
+    } else if let Some((name, value)) = locale.as_ref().filter(|(_, value)| !value.to_uppercase().contains("UTF")) {
+        reasons.push(format!("{}={} is not a UTF-8 locale", name, value));
+        RenderMode::Ascii
+    } else {
+        if locale.is_none() {
+            reasons.push("No locale set, assuming UTF-8".to_string());
         }
-    }
-
-    // Check LC_ALL
-    if let Ok(lc) = env::var("LC_ALL") {
-        if lc.to_uppercase().contains("UTF") {
-            return true;
+        match kitty_evidence {
+            Some(evidence) if multiplexer.is_some() => {
+                reasons.push(format!("Kitty graphics look supported ({}) but don't pass through the multiplexer", evidence));
+                RenderMode::Unicode
+            }
+            Some(evidence) if ssh && !terminfo => {
+                reasons.push(format!("Kitty graphics look supported ({}) but the remote end doesn't know the terminal", evidence));
+                RenderMode::Unicode
+            }
+            Some(evidence) => {
+                reasons.push(format!("Kitty graphics supported: {}", evidence));
+                RenderMode::Kitty
+            }
+            None => RenderMode::Unicode,
         }
-    }
+    };
+
+    let colorterm = probe.get("COLORTERM").unwrap_or_default().to_lowercase();
+    let true_color = if colorterm.contains("truecolor") || colorterm.contains("24bit") || term.contains("direct") {
+        true
+    } else {
+        // The console has 16 colours, and a multiplexer only passes true
+        // color on when told to, saying so through COLORTERM
+        term != "linux" && multiplexer.is_none()
+    };
 
-    // Modern terminals generally support Unicode
-    // Default to true unless on very old systems
-    true
+    Detection { mode, true_color, reasons }
+}
+
+/// Whether the terminfo database has an entry for a terminal, looking
+/// where ncurses does
+fn terminfo_exists(term: &str) -> bool {
+    use std::path::PathBuf;
+
+    let Some(first) = term.chars().next() else { return false };
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(dir.into());
+    }
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Ok(list) = env::var("TERMINFO_DIRS") {
+        dirs.extend(list.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from));
+    }
+    dirs.extend(["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo", "/usr/lib/terminfo"].map(PathBuf::from));
+    // Entries sit under their first letter, or its hex code on macOS
+    dirs.iter().any(|dir| {
+        dir.join(first.to_string()).join(term).exists() || dir.join(format!("{:x}", first as u32)).join(term).exists()
+    })
 }
 
 /// Terminal capabilities info
@@ -141,30 +221,49 @@ pub struct TerminalCapabilities {
 impl TerminalCapabilities {
     /// Detect all terminal capabilities
     pub fn detect() -> Self {
-        let render_mode = detect_render_mode();
+        let detection = detect(&Probe::system());
 
         let terminal_name = env::var("TERM_PROGRAM")
             .or_else(|_| env::var("TERM"))
             .unwrap_or_else(|_| "unknown".to_string());
 
         Self {
-            render_mode,
-            true_color: detect_true_color(),
+            render_mode: detection.mode,
+            true_color: detection.true_color,
             cell_size: None, // Could query via escape sequences
             terminal_name,
         }
     }
 }
 
-/// Check if true color (24-bit) is supported
-fn detect_true_color() -> bool {
-    if let Ok(colorterm) = env::var("COLORTERM") {
-        let ct = colorterm.to_lowercase();
-        if ct.contains("truecolor") || ct.contains("24bit") {
-            return true;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_with(vars: &[(&str, &str)], terminfo: bool) -> Detection {
+        let var = |name: &str| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string());
+        detect(&Probe { var: &var, has_terminfo: &|_| terminfo })
     }
 
-    // Most modern terminals support true color
-    true
+    #[test]
+    fn test_detection_falls_back_through_tmux_and_ssh() {
+        let kitty = [("TERM", "xterm-kitty"), ("LANG", "en_US.UTF-8"), ("KITTY_WINDOW_ID", "1")];
+        assert_eq!(detect_with(&kitty, true).mode, RenderMode::Kitty);
+
+        // Inside tmux the graphics don't get through, whatever leaked in
+        let tmux = [("TERM", "tmux-256color"), ("TMUX", "/tmp/tmux-1000/default,1,0"), ("LANG", "en_US.UTF-8"), ("KITTY_WINDOW_ID", "1")];
+        let detection = detect_with(&tmux, true);
+        assert_eq!((detection.mode, detection.true_color), (RenderMode::Unicode, false));
+        assert!(detection.reasons.iter().any(|r| r.contains("multiplexer")));
+
+        // Over SSH to a host that doesn't know xterm-kitty
+        let ssh = [("TERM", "xterm-kitty"), ("LANG", "en_US.UTF-8"), ("SSH_TTY", "/dev/pts/3")];
+        assert_eq!(detect_with(&ssh, false).mode, RenderMode::Unicode);
+        assert_eq!(detect_with(&ssh, true).mode, RenderMode::Kitty);
+
+        assert_eq!(detect_with(&[("TERM", "xterm-256color"), ("LC_ALL", "C")], true).mode, RenderMode::Ascii);
+        assert_eq!(detect_with(&[("TERM", "linux")], true).mode, RenderMode::Ascii);
+        assert_eq!(RenderMode::parse("Nerd"), Some(RenderMode::NerdFont));
+        assert_eq!(RenderMode::parse("sixel"), None);
+    }
 }
@@ -0,0 +1,183 @@
+//! Floor previews
+//!
+//! A whole floor drawn outside the game, for debugging the generator and
+//! sharing seeds: as plain ASCII, as ASCII coloured with terminal escapes,
+//! or as a PNG with a small block per tile. The glyphs and colours are the
+//! tile renderer's, so a preview looks like the floor does in play with
+//! everything explored.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use image::{Rgb, RgbImage};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use ratatui::style::Color;
+
+use crate::ecs::Position;
+use crate::error::{Area, Error};
+use crate::render::{RenderMode, TileRenderer};
+use crate::world::generation::{biome_for_floor, generate_floor};
+use crate::world::Map;
+
+/// Pixels along each side of a tile in a PNG preview
+const PNG_TILE: u32 = 4;
+
+/// What a preview is written as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewFormat {
+    Text,
+    /// Text coloured with 24-bit terminal escapes
+    Ansi,
+    Png,
+}
+
+/// Which floor to preview and how, from `key=value` words such as
+/// `floor=3 seed=42 format=png out=floor3.png`. A bare number is the floor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewSpec {
+    pub floor: u32,
+    pub seed: Option<u64>,
+    pub format: Option<PreviewFormat>,
+    pub out: Option<PathBuf>,
+}
+
+impl PreviewSpec {
+    pub fn parse<S: AsRef<str>>(words: &[S]) -> Result<Self, String> {
+        let mut spec = PreviewSpec { floor: 1, seed: None, format: None, out: None };
+        for word in words {
+            let word = word.as_ref();
+            let (key, value) = word.split_once('=').unwrap_or(("floor", word));
+            match key {
+                "floor" => spec.floor = value.parse().map_err(|_| format!("not a floor number: {}", value))?,
+                "seed" => spec.seed = Some(value.parse().map_err(|_| format!("not a seed: {}", value))?),
+                "format" => {
+                    spec.format = Some(match value {
+                        "text" => PreviewFormat::Text,
+                        "ansi" => PreviewFormat::Ansi,
+                        "png" => PreviewFormat::Png,
+                        _ => return Err(format!("format is one of text, ansi or png, not {}", value)),
+                    })
+                }
+                "out" => spec.out = Some(PathBuf::from(value)),
+                _ => return Err(format!("unknown preview setting: {}", key)),
+            }
+        }
+        Ok(spec)
+    }
+}
+
+/// The floor the generator lays out from a seed. A run reaching this floor
+/// on the same seed will have drawn other floors from it first, so this is
+/// the generator's output rather than that run's floor.
+pub fn generate(floor: u32, seed: u64) -> Map {
+    generate_floor(&mut StdRng::seed_from_u64(seed), floor, biome_for_floor(floor))
+}
+
+/// The floor as text, one line per row, with `@` where the player starts.
+/// Coloured text sets each tile's foreground and background.
+pub fn to_text(map: &Map, colored: bool) -> String {
+    let tiles = TileRenderer::new(RenderMode::Ascii);
+    let ambient = map.biome.config().ambient_color;
+    let mut text = String::with_capacity((map.width as usize + 1) * map.height as usize);
+    for y in 0..map.height {
+        for x in 0..map.width {
+            let Some(tile) = map.get_tile(x, y) else { continue };
+            let glyph = if Position::new(x, y) == map.start_pos { '@' } else { tiles.tile_char(tile.tile_type) };
+            if colored {
+                let (fr, fg, fb) = rgb(tiles.tile_fg_color_biome(tile.tile_type, true, ambient));
+                let (br, bg, bb) = rgb(tiles.tile_bg_color_biome(tile.tile_type, true, ambient));
+                let _ = write!(text, "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m{}", fr, fg, fb, br, bg, bb, glyph);
+            } else {
+                text.push(glyph);
+            }
+        }
+        if colored {
+            text.push_str("\x1b[0m");
+        } else {
+            text.truncate(text.trim_end().len());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// The floor as an image: each tile a block of its background colour, with
+/// its glyph's colour in the middle unless it's open floor
+pub fn to_png(map: &Map) -> RgbImage {
+    let tiles = TileRenderer::new(RenderMode::Ascii);
+    let ambient = map.biome.config().ambient_color;
+    let mut image = RgbImage::new(map.width as u32 * PNG_TILE, map.height as u32 * PNG_TILE);
+    for y in 0..map.height {
+        for x in 0..map.width {
+            let Some(tile) = map.get_tile(x, y) else { continue };
+            let start = Position::new(x, y) == map.start_pos;
+            let (br, bg, bb) = rgb(tiles.tile_bg_color_biome(tile.tile_type, true, ambient));
+            let (fr, fg, fb) = if start { (255, 255, 255) } else { rgb(tiles.tile_fg_color_biome(tile.tile_type, true, ambient)) };
+            let marked = start || !matches!(tiles.tile_char(tile.tile_type), '.' | ' ');
+            for py in 0..PNG_TILE {
+                for px in 0..PNG_TILE {
+                    let inner = (1..PNG_TILE - 1).contains(&px) && (1..PNG_TILE - 1).contains(&py);
+                    let color = if marked && inner { [fr, fg, fb] } else { [br, bg, bb] };
+                    image.put_pixel(x as u32 * PNG_TILE + px, y as u32 * PNG_TILE + py, Rgb(color));
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Write a floor as text and as a PNG beside each other in `dir`, named
+/// for its floor and seed. Returns the text file's path.
+pub fn export(map: &Map, seed: u64, dir: &Path) -> Result<PathBuf, Error> {
+    let stem = dir.join(format!("floor-{}-seed-{}", map.floor_number, seed));
+    let text_path = stem.with_extension("txt");
+    let png_path = stem.with_extension("png");
+    std::fs::create_dir_all(dir).map_err(|e| Error::io(Area::Preview, "write", dir, e))?;
+    std::fs::write(&text_path, to_text(map, false)).map_err(|e| Error::io(Area::Preview, "write", &text_path, e))?;
+    to_png(map).save(&png_path).map_err(|e| Error::parse(Area::Preview, &png_path, e))?;
+    Ok(text_path)
+}
+
+/// Where previews exported from the game go
+pub fn preview_directory() -> PathBuf {
+    use directories::ProjectDirs;
+
+    if let Some(proj_dirs) = ProjectDirs::from("com", "hollowdeep", "Hollowdeep") {
+        let mut path = proj_dirs.data_local_dir().to_path_buf();
+        path.push("previews");
+        path
+    } else {
+        PathBuf::from("./previews")
+    }
+}
+
+fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        _ => (128, 128, 128),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_draws_the_whole_floor() {
+        let spec = PreviewSpec::parse(&["floor=3", "seed=42", "format=png"]).unwrap();
+        assert_eq!((spec.floor, spec.seed, spec.format), (3, Some(42), Some(PreviewFormat::Png)));
+        assert_eq!(PreviewSpec::parse(&["7"]).unwrap().floor, 7);
+        assert!(PreviewSpec::parse(&["depth=3"]).is_err());
+
+        let map = generate(3, 42);
+        assert_eq!(to_text(&map, false), to_text(&generate(3, 42), false));
+        let text = to_text(&map, false);
+        assert_eq!(text.lines().count(), map.height as usize);
+        assert_eq!(text.matches('@').count(), 1);
+        assert!(to_text(&map, true).contains("\x1b[38;2;"));
+
+        let image = to_png(&map);
+        assert_eq!(image.dimensions(), (map.width as u32 * PNG_TILE, map.height as u32 * PNG_TILE));
+    }
+}
@@ -0,0 +1,391 @@
+//! Screenshots and run cards
+//!
+//! The screen as drawn is a buffer of styled cells, so a screenshot is that
+//! buffer written out: as text with terminal colour escapes, which `cat`
+//! plays back, and as a PNG drawn cell by cell with a small built-in font.
+//! A run card is a PNG of its own summing up a finished run, for sharing.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use image::{Rgb, RgbImage};
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use ratatui::text::Span;
+
+use crate::error::{Area, Error};
+
+/// Size of a screen cell in a screenshot PNG, about a terminal cell's shape
+const CELL_WIDTH: u32 = 6;
+const CELL_HEIGHT: u32 = 12;
+/// Rows of a cell above the glyph
+const GLYPH_TOP: u32 = 2;
+
+/// What unstyled text and background are drawn as
+const DEFAULT_FG: [u8; 3] = [200, 200, 200];
+const DEFAULT_BG: [u8; 3] = [0, 0, 0];
+
+/// 5×7 glyphs for printable ASCII from the space on, one byte per column
+/// with the top row in the lowest bit
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], [0x00, 0x00, 0x5F, 0x00, 0x00], [0x00, 0x07, 0x00, 0x07, 0x00],
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], [0x24, 0x2A, 0x7F, 0x2A, 0x12], [0x23, 0x13, 0x08, 0x64, 0x62],
+    [0x36, 0x49, 0x56, 0x20, 0x50], [0x00, 0x08, 0x07, 0x03, 0x00], [0x00, 0x1C, 0x22, 0x41, 0x00],
+    [0x00, 0x41, 0x22, 0x1C, 0x00], [0x2A, 0x1C, 0x7F, 0x1C, 0x2A], [0x08, 0x08, 0x3E, 0x08, 0x08],
+    [0x00, 0x50, 0x30, 0x00, 0x00], [0x08, 0x08, 0x08, 0x08, 0x08], [0x00, 0x60, 0x60, 0x00, 0x00],
+    [0x20, 0x10, 0x08, 0x04, 0x02], [0x3E, 0x51, 0x49, 0x45, 0x3E], [0x00, 0x42, 0x7F, 0x40, 0x00],
+    [0x42, 0x61, 0x51, 0x49, 0x46], [0x21, 0x41, 0x45, 0x4B, 0x31], [0x18, 0x14, 0x12, 0x7F, 0x10],
+    [0x27, 0x45, 0x45, 0x45, 0x39], [0x3C, 0x4A, 0x49, 0x49, 0x30], [0x01, 0x71, 0x09, 0x05, 0x03],
+    [0x36, 0x49, 0x49, 0x49, 0x36], [0x06, 0x49, 0x49, 0x29, 0x1E], [0x00, 0x36, 0x36, 0x00, 0x00],
+    [0x00, 0x56, 0x36, 0x00, 0x00], [0x08, 0x14, 0x22, 0x41, 0x00], [0x14, 0x14, 0x14, 0x14, 0x14],
+    [0x00, 0x41, 0x22, 0x14, 0x08], [0x02, 0x01, 0x51, 0x09, 0x06], [0x32, 0x49, 0x79, 0x41, 0x3E],
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], [0x7F, 0x49, 0x49, 0x49, 0x36], [0x3E, 0x41, 0x41, 0x41, 0x22],
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], [0x7F, 0x49, 0x49, 0x49, 0x41], [0x7F, 0x09, 0x09, 0x09, 0x01],
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], [0x7F, 0x08, 0x08, 0x08, 0x7F], [0x00, 0x41, 0x7F, 0x41, 0x00],
+    [0x20, 0x40, 0x41, 0x3F, 0x01], [0x7F, 0x08, 0x14, 0x22, 0x41], [0x7F, 0x40, 0x40, 0x40, 0x40],
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], [0x7F, 0x04, 0x08, 0x10, 0x7F], [0x3E, 0x41, 0x41, 0x41, 0x3E],
+    [0x7F, 0x09, 0x09, 0x09, 0x06], [0x3E, 0x41, 0x51, 0x21, 0x5E], [0x7F, 0x09, 0x19, 0x29, 0x46],
+    [0x46, 0x49, 0x49, 0x49, 0x31], [0x01, 0x01, 0x7F, 0x01, 0x01], [0x3F, 0x40, 0x40, 0x40, 0x3F],
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], [0x3F, 0x40, 0x38, 0x40, 0x3F], [0x63, 0x14, 0x08, 0x14, 0x63],
+    [0x07, 0x08, 0x70, 0x08, 0x07], [0x61, 0x51, 0x49, 0x45, 0x43], [0x00, 0x7F, 0x41, 0x41, 0x00],
+    [0x02, 0x04, 0x08, 0x10, 0x20], [0x00, 0x41, 0x41, 0x7F, 0x00], [0x04, 0x02, 0x01, 0x02, 0x04],
+    [0x40, 0x40, 0x40, 0x40, 0x40], [0x00, 0x01, 0x02, 0x04, 0x00], [0x20, 0x54, 0x54, 0x54, 0x78],
+    [0x7F, 0x48, 0x44, 0x44, 0x38], [0x38, 0x44, 0x44, 0x44, 0x20], [0x38, 0x44, 0x44, 0x48, 0x7F],
+    [0x38, 0x54, 0x54, 0x54, 0x18], [0x08, 0x7E, 0x09, 0x01, 0x02], [0x0C, 0x52, 0x52, 0x52, 0x3E],
+    [0x7F, 0x08, 0x04, 0x04, 0x78], [0x00, 0x44, 0x7D, 0x40, 0x00], [0x20, 0x40, 0x44, 0x3D, 0x00],
+    [0x7F, 0x10, 0x28, 0x44, 0x00], [0x00, 0x41, 0x7F, 0x40, 0x00], [0x7C, 0x04, 0x18, 0x04, 0x78],
+    [0x7C, 0x08, 0x04, 0x04, 0x78], [0x38, 0x44, 0x44, 0x44, 0x38], [0x7C, 0x14, 0x14, 0x14, 0x08],
+    [0x08, 0x14, 0x14, 0x18, 0x7C], [0x7C, 0x08, 0x04, 0x04, 0x08], [0x48, 0x54, 0x54, 0x54, 0x20],
+    [0x04, 0x3F, 0x44, 0x40, 0x20], [0x3C, 0x40, 0x40, 0x20, 0x7C], [0x1C, 0x20, 0x40, 0x20, 0x1C],
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], [0x44, 0x28, 0x10, 0x28, 0x44], [0x0C, 0x50, 0x50, 0x50, 0x3C],
+    [0x44, 0x64, 0x54, 0x4C, 0x44], [0x00, 0x08, 0x36, 0x41, 0x00], [0x00, 0x00, 0x7F, 0x00, 0x00],
+    [0x00, 0x41, 0x36, 0x08, 0x00], [0x08, 0x04, 0x08, 0x10, 0x08],
+];
+
+/// The screen as text that plays back in a terminal: each cell's colours
+/// and boldness set with escapes, every line ending with a reset
+pub fn to_ansi(buf: &Buffer) -> String {
+    let mut text = String::new();
+    for y in buf.area.top()..buf.area.bottom() {
+        let mut last_style = None;
+        let mut covered = 0usize;
+        for x in buf.area.left()..buf.area.right() {
+            let cell = &buf[(x, y)];
+            // Cells under a wide glyph or an image are drawn by it
+            if covered > 0 || cell.skip {
+                covered = covered.saturating_sub(1);
+                continue;
+            }
+            let style = (cell.fg, cell.bg, cell.modifier.contains(Modifier::BOLD));
+            if last_style != Some(style) {
+                text.push_str("\x1b[0");
+                if style.2 {
+                    text.push_str(";1");
+                }
+                if cell.fg != Color::Reset {
+                    let [r, g, b] = rgb(cell.fg, DEFAULT_FG);
+                    let _ = write!(text, ";38;2;{};{};{}", r, g, b);
+                }
+                if cell.bg != Color::Reset {
+                    let [r, g, b] = rgb(cell.bg, DEFAULT_BG);
+                    let _ = write!(text, ";48;2;{};{};{}", r, g, b);
+                }
+                text.push('m');
+                last_style = Some(style);
+            }
+            let symbol = if cell.symbol().is_empty() { " " } else { cell.symbol() };
+            text.push_str(symbol);
+            covered = Span::raw(symbol).width().saturating_sub(1);
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// The screen as an image, each cell its background with its glyph over it.
+/// ASCII, box drawing and block shading are drawn as such; any other glyph
+/// becomes a small block of its colour.
+pub fn to_png(buf: &Buffer) -> RgbImage {
+    let area = buf.area;
+    let mut image = RgbImage::new(area.width as u32 * CELL_WIDTH, area.height as u32 * CELL_HEIGHT);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            let cell = &buf[(x, y)];
+            let mut fg = rgb(cell.fg, DEFAULT_FG);
+            let mut bg = rgb(cell.bg, DEFAULT_BG);
+            if cell.modifier.contains(Modifier::REVERSED) {
+                std::mem::swap(&mut fg, &mut bg);
+            }
+            let left = (x - area.left()) as u32 * CELL_WIDTH;
+            let top = (y - area.top()) as u32 * CELL_HEIGHT;
+            fill(&mut image, left, top, CELL_WIDTH, CELL_HEIGHT, bg);
+            if let Some(c) = cell.symbol().chars().next() {
+                draw_glyph(&mut image, left, top, c, fg, 1);
+            }
+        }
+    }
+    image
+}
+
+/// Draw one character with its cell's top left at `left, top`, each pixel
+/// of the font `scale` pixels square
+fn draw_glyph(image: &mut RgbImage, left: u32, top: u32, c: char, color: [u8; 3], scale: u32) {
+    let (w, h) = (CELL_WIDTH * scale, CELL_HEIGHT * scale);
+    let (mid_x, mid_y) = (left + 2 * scale, top + h / 2);
+    match c {
+        ' ' => {}
+        ' '..='~' => {
+            let columns = FONT[c as usize - ' ' as usize];
+            for (col, bits) in columns.iter().enumerate() {
+                for row in 0..7 {
+                    if bits & (1 << row) != 0 {
+                        let px = left + col as u32 * scale;
+                        let py = top + (GLYPH_TOP + row) * scale;
+                        fill(image, px, py, scale, scale, color);
+                    }
+                }
+            }
+        }
+        '█' => fill(image, left, top, w, h, color),
+        '▀' => fill(image, left, top, w, h / 2, color),
+        '▄' => fill(image, left, top + h / 2, w, h - h / 2, color),
+        '░' | '▒' | '▓' => {
+            for py in 0..h {
+                for px in 0..w {
+                    let lit = match c {
+                        '░' => (px + py * 2) % 4 == 0,
+                        '▒' => (px + py) % 2 == 0,
+                        _ => (px + py) % 4 != 0,
+                    };
+                    if lit {
+                        image.put_pixel(left + px, top + py, Rgb(color));
+                    }
+                }
+            }
+        }
+        _ => match box_arms(c) {
+            Some([up, down, l, r]) => {
+                let thick = scale;
+                if up {
+                    fill(image, mid_x, top, thick, mid_y - top + thick, color);
+                }
+                if down {
+                    fill(image, mid_x, mid_y, thick, top + h - mid_y, color);
+                }
+                if l {
+                    fill(image, left, mid_y, mid_x - left + thick, thick, color);
+                }
+                if r {
+                    fill(image, mid_x, mid_y, left + w - mid_x, thick, color);
+                }
+            }
+            None => fill(image, left + scale, top + (GLYPH_TOP + 1) * scale, 3 * scale, 5 * scale, color),
+        },
+    }
+}
+
+/// Which way a box drawing character's lines run: up, down, left, right.
+/// Heavy, double and rounded lines are drawn as light ones.
+fn box_arms(c: char) -> Option<[bool; 4]> {
+    Some(match c {
+        '─' | '━' | '═' => [false, false, true, true],
+        '│' | '┃' | '║' => [true, true, false, false],
+        '┌' | '╭' | '╔' | '┏' => [false, true, false, true],
+        '┐' | '╮' | '╗' | '┓' => [false, true, true, false],
+        '└' | '╰' | '╚' | '┗' => [true, false, false, true],
+        '┘' | '╯' | '╝' | '┛' => [true, false, true, false],
+        '├' | '╠' | '┣' => [true, true, false, true],
+        '┤' | '╣' | '┫' => [true, true, true, false],
+        '┬' | '╦' | '┳' => [false, true, true, true],
+        '┴' | '╩' | '┻' => [true, false, true, true],
+        '┼' | '╬' | '╋' => [true, true, true, true],
+        _ => return None,
+    })
+}
+
+fn fill(image: &mut RgbImage, left: u32, top: u32, width: u32, height: u32, color: [u8; 3]) {
+    for py in top..(top + height).min(image.height()) {
+        for px in left..(left + width).min(image.width()) {
+            image.put_pixel(px, py, Rgb(color));
+        }
+    }
+}
+
+/// A colour as the terminal would show it, with `default` for the
+/// terminal's own. Named and indexed colours are the xterm palette.
+fn rgb(color: Color, default: [u8; 3]) -> [u8; 3] {
+    const NAMED: [[u8; 3]; 16] = [
+        [0, 0, 0], [205, 0, 0], [0, 205, 0], [205, 205, 0], [0, 0, 238], [205, 0, 205], [0, 205, 205], [229, 229, 229],
+        [127, 127, 127], [255, 0, 0], [0, 255, 0], [255, 255, 0], [92, 92, 255], [255, 0, 255], [0, 255, 255], [255, 255, 255],
+    ];
+    let named = |i: usize| NAMED[i];
+    match color {
+        Color::Reset => default,
+        Color::Rgb(r, g, b) => [r, g, b],
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => named(8),
+        Color::LightRed => named(9),
+        Color::LightGreen => named(10),
+        Color::LightYellow => named(11),
+        Color::LightBlue => named(12),
+        Color::LightMagenta => named(13),
+        Color::LightCyan => named(14),
+        Color::White => named(15),
+        Color::Indexed(i @ 0..=15) => named(i as usize),
+        Color::Indexed(i @ 16..=231) => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = i - 16;
+            [level(i / 36), level(i / 6 % 6), level(i % 6)]
+        }
+        Color::Indexed(i) => {
+            let v = 8 + (i - 232) * 10;
+            [v, v, v]
+        }
+    }
+}
+
+/// A finished run summed up for sharing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunCard {
+    pub victory: bool,
+    /// What killed the player, if something did
+    pub epitaph: Option<String>,
+    /// Name and value of each line of the summary, top to bottom
+    pub stats: Vec<(&'static str, String)>,
+}
+
+/// Pixels of font per font pixel on a run card
+const CARD_SCALE: u32 = 2;
+const CARD_COLUMNS: u32 = 40;
+const CARD_BACKGROUND: [u8; 3] = [18, 14, 16];
+
+impl RunCard {
+    /// The card as an image: a title and the outcome over the summary, in a
+    /// frame that's gold for a victory and blood red for a death
+    pub fn to_png(&self) -> RgbImage {
+        let (cell_w, cell_h) = (CELL_WIDTH * CARD_SCALE, CELL_HEIGHT * CARD_SCALE);
+        let rows = 6 + self.stats.len() as u32 + u32::from(self.epitaph.is_some()) * 2;
+        let (width, height) = ((CARD_COLUMNS + 4) * cell_w, (rows + 2) * cell_h);
+        let accent = if self.victory { [214, 170, 60] } else { [150, 24, 24] };
+        let mut image = RgbImage::new(width, height);
+        // Darker towards the bottom, like the deep
+        for y in 0..height {
+            let shade = 1.0 - 0.5 * y as f32 / height as f32;
+            let color = CARD_BACKGROUND.map(|v| (v as f32 * shade) as u8);
+            fill(&mut image, 0, y, width, 1, color);
+        }
+        for inset in [CARD_SCALE * 3, CARD_SCALE * 6] {
+            let (w, h) = (width - inset * 2, height - inset * 2);
+            fill(&mut image, inset, inset, w, CARD_SCALE, accent);
+            fill(&mut image, inset, inset + h - CARD_SCALE, w, CARD_SCALE, accent);
+            fill(&mut image, inset, inset, CARD_SCALE, h, accent);
+            fill(&mut image, inset + w - CARD_SCALE, inset, CARD_SCALE, h, accent);
+        }
+
+        let centred = |image: &mut RgbImage, row: u32, text: &str, color: [u8; 3], scale: u32| {
+            let text_width = text.chars().count() as u32 * CELL_WIDTH * scale;
+            let left = width.saturating_sub(text_width) / 2;
+            for (i, c) in text.chars().enumerate() {
+                draw_glyph(image, left + i as u32 * CELL_WIDTH * scale, row * cell_h, c, color, scale);
+            }
+        };
+        centred(&mut image, 1, "HOLLOWDEEP", [235, 225, 210], CARD_SCALE * 2);
+        centred(&mut image, 4, if self.victory { "VICTORIOUS" } else { "FALLEN" }, accent, CARD_SCALE);
+        let mut row = 6;
+        if let Some(epitaph) = &self.epitaph {
+            let epitaph: String = epitaph.chars().take(CARD_COLUMNS as usize).collect();
+            centred(&mut image, row, &epitaph, [150, 140, 135], CARD_SCALE);
+            row += 2;
+        }
+        for (name, value) in &self.stats {
+            let left = 2 * cell_w;
+            for (i, c) in name.chars().enumerate() {
+                draw_glyph(&mut image, left + i as u32 * cell_w, row * cell_h, c, [130, 120, 115], CARD_SCALE);
+            }
+            let value: String = value.chars().take(CARD_COLUMNS as usize - name.len() - 1).collect();
+            let right = left + CARD_COLUMNS * cell_w - value.chars().count() as u32 * cell_w;
+            for (i, c) in value.chars().enumerate() {
+                draw_glyph(&mut image, right + i as u32 * cell_w, row * cell_h, c, [235, 225, 210], CARD_SCALE);
+            }
+            row += 1;
+        }
+        image
+    }
+}
+
+/// Write the screen as `.ans` text and a PNG beside it in `dir`. Returns
+/// the text file's path.
+pub fn export_screen(buf: &Buffer, dir: &Path) -> Result<PathBuf, Error> {
+    let stem = dir.join(format!("screenshot-{}", timestamp()));
+    let text_path = stem.with_extension("ans");
+    let png_path = stem.with_extension("png");
+    std::fs::create_dir_all(dir).map_err(|e| Error::io(Area::Screenshot, "write", dir, e))?;
+    std::fs::write(&text_path, to_ansi(buf)).map_err(|e| Error::io(Area::Screenshot, "write", &text_path, e))?;
+    to_png(buf).save(&png_path).map_err(|e| Error::parse(Area::Screenshot, &png_path, e))?;
+    Ok(text_path)
+}
+
+/// Write a run card into `dir`, named for the run's seed. Returns its path.
+pub fn export_run_card(card: &RunCard, seed: u64, dir: &Path) -> Result<PathBuf, Error> {
+    let path = dir.join(format!("run-{}-{}.png", seed, timestamp()));
+    std::fs::create_dir_all(dir).map_err(|e| Error::io(Area::Screenshot, "write", dir, e))?;
+    card.to_png().save(&path).map_err(|e| Error::parse(Area::Screenshot, &path, e))?;
+    Ok(path)
+}
+
+fn timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Where screenshots and run cards go
+pub fn screenshot_directory() -> PathBuf {
+    use directories::ProjectDirs;
+
+    if let Some(proj_dirs) = ProjectDirs::from("com", "hollowdeep", "Hollowdeep") {
+        let mut path = proj_dirs.data_local_dir().to_path_buf();
+        path.push("screenshots");
+        path
+    } else {
+        PathBuf::from("./screenshots")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn test_screenshot_keeps_what_was_drawn() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buf.set_string(0, 0, "HP", Style::default().fg(Color::Red));
+        buf.set_string(0, 1, "┌─", Style::default().bg(Color::Rgb(10, 20, 30)));
+
+        let text = to_ansi(&buf);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.starts_with("\x1b[0;38;2;205;0;0mHP\x1b[0m    "));
+        assert!(text.contains("48;2;10;20;30m┌─"));
+
+        let image = to_png(&buf);
+        assert_eq!(image.dimensions(), (6 * CELL_WIDTH, 2 * CELL_HEIGHT));
+        // The H's left stroke is red on black; the box corner's arm runs
+        // along the middle of its cell
+        assert_eq!(image.get_pixel(0, GLYPH_TOP + 3).0, [205, 0, 0]);
+        assert_eq!(image.get_pixel(1, GLYPH_TOP + 1).0, DEFAULT_BG);
+        assert_eq!(image.get_pixel(CELL_WIDTH - 1, CELL_HEIGHT + CELL_HEIGHT / 2).0, DEFAULT_FG);
+
+        let card = RunCard { victory: false, epitaph: Some("Slain by a Ghoul".into()), stats: vec![("Floor", "5".into())] };
+        // Title, outcome, epitaph and one line of stats, with a margin
+        assert_eq!(card.to_png().height(), 11 * CELL_HEIGHT * CARD_SCALE);
+    }
+}
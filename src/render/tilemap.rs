@@ -82,6 +82,7 @@ impl TileRenderer {
         let tile_sprites = [
             (TileType::Floor, SpriteId::FLOOR),
             (TileType::Wall, SpriteId::WALL),
+            (TileType::SecretDoor, SpriteId::WALL),
             (TileType::Corridor, SpriteId::CORRIDOR),
             (TileType::Lava, SpriteId::LAVA),
             (TileType::Pit, SpriteId::PIT),
@@ -126,14 +127,29 @@ impl TileRenderer {
     fn ascii_char(tile_type: TileType) -> char {
         match tile_type {
             TileType::Floor => '.',
-            TileType::Wall => '#',
+            TileType::Wall | TileType::SecretDoor => '#',
             TileType::Corridor => '.',
             TileType::Lava => '~',
+            TileType::DeepWater => '~',
+            TileType::Bridge => '=',
+            TileType::FleshWall => '#',
+            TileType::TornFlesh => ',',
+            TileType::Oil => '~',
             TileType::Pit => ' ',
+            TileType::BloodPool => '~',
             TileType::DoorClosed => '+',
+            TileType::DoorLocked => '+',
             TileType::DoorOpen => '/',
             TileType::StairsDown => '>',
             TileType::StairsUp => '<',
+            TileType::Teleporter => 'O',
+            TileType::PressurePlate => '^',
+            TileType::Lever => '/',
+            TileType::LeverPulled => '\\',
+            TileType::Portcullis => '#',
+            TileType::PortcullisOpen => '.',
+            TileType::TollGate => '#',
+            TileType::TollGateOpen => '.',
             TileType::Rubble => ',',
             TileType::Bones => '%',
             TileType::BloodStain => '.',
@@ -148,6 +164,10 @@ impl TileRenderer {
             TileType::ShrineEnchant => 'E',
             TileType::ShrineRest => 'R',
             TileType::ShrineCorruption => 'C',
+            TileType::ShrineGamble => 'G',
+            TileType::ShrineSacrifice => 'X',
+            TileType::ShrineTransmute => 'T',
+            TileType::AltarMaw | TileType::AltarWidow | TileType::AltarEye => 'A',
         }
     }
 
@@ -155,14 +175,29 @@ impl TileRenderer {
     fn unicode_char(tile_type: TileType) -> char {
         match tile_type {
             TileType::Floor => '·',      // Middle dot
-            TileType::Wall => '█',       // Full block
+            TileType::Wall | TileType::SecretDoor => '█', // Full block
             TileType::Corridor => '∙',   // Bullet operator
             TileType::Lava => '≈',       // Wavy lava
+            TileType::DeepWater => '≈',  // Wavy water
+            TileType::Bridge => '═',     // Planks
+            TileType::FleshWall => '▓',  // Knotted flesh
+            TileType::TornFlesh => '⁏',  // Raw, closing wound
+            TileType::Oil => '≈',        // Dark, glistening slick
             TileType::Pit => ' ',
+            TileType::BloodPool => '≈',
             TileType::DoorClosed => '▮', // Black vertical rectangle
+            TileType::DoorLocked => '▮',
             TileType::DoorOpen => '▯',   // White vertical rectangle
             TileType::StairsDown => '▼', // Down triangle
             TileType::StairsUp => '▲',   // Up triangle
+            TileType::Teleporter => '◎',  // Bullseye
+            TileType::PressurePlate => '▫',  // Flagstone set in the floor
+            TileType::Lever => '⌿',
+            TileType::LeverPulled => '⍀',
+            TileType::Portcullis => '╫',     // Iron bars
+            TileType::PortcullisOpen => '┄',
+            TileType::TollGate => '╪',
+            TileType::TollGateOpen => '┄',
             TileType::Rubble => '░',     // Light shade
             TileType::Bones => '☠',      // Skull
             TileType::BloodStain => '•', // Bullet
@@ -177,6 +212,10 @@ impl TileRenderer {
             TileType::ShrineEnchant => '✦', // Black four pointed star
             TileType::ShrineRest => '☥',    // Ankh
             TileType::ShrineCorruption => '☠', // Skull (corruption)
+            TileType::ShrineGamble => '¢',     // Cent sign (gambling)
+            TileType::ShrineSacrifice => '†',  // Dagger (sacrifice)
+            TileType::ShrineTransmute => '⚗',  // Alembic (transmutation)
+            TileType::AltarMaw | TileType::AltarWidow | TileType::AltarEye => 'Ψ',
         }
     }
 
@@ -185,14 +224,29 @@ impl TileRenderer {
         // Nerd Font icons - these require the user to have a Nerd Font
         match tile_type {
             TileType::Floor => '·',
-            TileType::Wall => '█',
+            TileType::Wall | TileType::SecretDoor => '█',
             TileType::Corridor => '·',
             TileType::Lava => '󰈸',   // Fire icon
+            TileType::DeepWater => '≈',
+            TileType::Bridge => '═',
+            TileType::FleshWall => '▓',
+            TileType::TornFlesh => '⁏',
+            TileType::Oil => '≈',
             TileType::Pit => ' ',
+            TileType::BloodPool => '󰗈',
             TileType::DoorClosed => '󰠲', // Door closed
+            TileType::DoorLocked => '󰌾',
             TileType::DoorOpen => '󰠳',   // Door open
             TileType::StairsDown => '󰁅', // Arrow down
             TileType::StairsUp => '󰁝',   // Arrow up
+            TileType::Teleporter => '◎',
+            TileType::PressurePlate => '▫',
+            TileType::Lever => '⌿',
+            TileType::LeverPulled => '⍀',
+            TileType::Portcullis => '╫',
+            TileType::PortcullisOpen => '┄',
+            TileType::TollGate => '╪',
+            TileType::TollGateOpen => '┄',
             TileType::Rubble => '󰟀',     // Debris
             TileType::Bones => '󰚌',      // Skull
             TileType::BloodStain => '󰗈', // Drop
@@ -207,6 +261,10 @@ impl TileRenderer {
             TileType::ShrineEnchant => '󰂵', // Star
             TileType::ShrineRest => '󰒲',    // Sleep
             TileType::ShrineCorruption => '󰚌', // Skull (corruption)
+            TileType::ShrineGamble => '¢',
+            TileType::ShrineSacrifice => '†',
+            TileType::ShrineTransmute => '⚗',
+            TileType::AltarMaw | TileType::AltarWidow | TileType::AltarEye => 'Ψ',
         }
     }
 
@@ -215,14 +273,28 @@ impl TileRenderer {
         let (r, g, b) = if lit {
             match tile_type {
                 TileType::Floor => (80, 80, 80),
-                TileType::Wall => (130, 110, 90),
+                TileType::Wall | TileType::SecretDoor => (130, 110, 90),
                 TileType::Corridor => (70, 70, 70),
                 TileType::Lava => (255, 100, 0),
+                TileType::DeepWater => (60, 110, 200),
+                TileType::Bridge => (150, 110, 70),
+                TileType::FleshWall => (170, 70, 85),
+                TileType::TornFlesh => (140, 50, 60),
+                TileType::Oil => (95, 85, 45),
                 TileType::Pit => (20, 20, 20),
+                TileType::BloodPool => (170, 20, 40),
                 TileType::DoorClosed => (160, 120, 60),
+                TileType::DoorLocked => (200, 160, 70),
                 TileType::DoorOpen => (140, 100, 50),
                 TileType::StairsDown => (220, 220, 200),
                 TileType::StairsUp => (220, 220, 200),
+                TileType::Teleporter => (140, 110, 255),
+                TileType::PressurePlate => (150, 140, 120),
+                TileType::Lever | TileType::LeverPulled => (190, 160, 90),
+                TileType::Portcullis => (150, 150, 165),
+                TileType::PortcullisOpen => (90, 90, 100),
+                TileType::TollGate => (210, 170, 60),
+                TileType::TollGateOpen => (110, 95, 50),
                 TileType::Rubble => (100, 90, 80),
                 TileType::Bones => (220, 210, 190),
                 TileType::BloodStain => (180, 40, 40),
@@ -237,19 +309,39 @@ impl TileRenderer {
                 TileType::ShrineEnchant => (100, 200, 255),
                 TileType::ShrineRest => (100, 255, 100),
                 TileType::ShrineCorruption => (200, 50, 100),
+                TileType::ShrineGamble => (255, 215, 0),
+                TileType::ShrineSacrifice => (220, 40, 40),
+                TileType::ShrineTransmute => (120, 255, 200),
+                TileType::AltarMaw => (200, 30, 30),
+                TileType::AltarWidow => (190, 180, 170),
+                TileType::AltarEye => (60, 140, 200),
             }
         } else {
             // Dim colors for unexplored but seen tiles
             match tile_type {
                 TileType::Floor => (30, 30, 30),
-                TileType::Wall => (50, 45, 40),
+                TileType::Wall | TileType::SecretDoor => (50, 45, 40),
                 TileType::Corridor => (25, 25, 25),
                 TileType::Lava => (80, 40, 0),
+                TileType::DeepWater => (20, 35, 70),
+                TileType::Bridge => (50, 40, 25),
+                TileType::FleshWall => (60, 28, 32),
+                TileType::TornFlesh => (50, 20, 24),
+                TileType::Oil => (35, 32, 18),
                 TileType::Pit => (10, 10, 10),
+                TileType::BloodPool => (60, 10, 20),
                 TileType::DoorClosed => (60, 45, 25),
+                TileType::DoorLocked => (60, 45, 25),
                 TileType::DoorOpen => (50, 40, 20),
                 TileType::StairsDown => (80, 80, 70),
                 TileType::StairsUp => (80, 80, 70),
+                TileType::Teleporter => (50, 40, 90),
+                TileType::PressurePlate => (55, 50, 45),
+                TileType::Lever | TileType::LeverPulled => (70, 60, 35),
+                TileType::Portcullis => (55, 55, 62),
+                TileType::PortcullisOpen => (35, 35, 40),
+                TileType::TollGate => (75, 60, 25),
+                TileType::TollGateOpen => (40, 34, 18),
                 TileType::Rubble => (40, 35, 30),
                 TileType::Bones => (80, 75, 65),
                 TileType::BloodStain => (60, 20, 20),
@@ -264,6 +356,12 @@ impl TileRenderer {
                 TileType::ShrineEnchant => (40, 80, 100),
                 TileType::ShrineRest => (40, 100, 40),
                 TileType::ShrineCorruption => (80, 20, 40),
+                TileType::ShrineGamble => (100, 85, 0),
+                TileType::ShrineSacrifice => (90, 15, 15),
+                TileType::ShrineTransmute => (45, 100, 80),
+                TileType::AltarMaw => (80, 12, 12),
+                TileType::AltarWidow => (76, 72, 68),
+                TileType::AltarEye => (24, 56, 80),
             }
         };
 
@@ -290,14 +388,26 @@ impl TileRenderer {
         let (r, g, b) = if lit {
             match tile_type {
                 TileType::Floor => (20, 18, 15),
-                TileType::Wall => (40, 35, 30),
+                TileType::Wall | TileType::SecretDoor => (40, 35, 30),
                 TileType::Corridor => (15, 13, 10),
                 TileType::Lava => (80, 30, 0),
+                TileType::DeepWater => (10, 25, 60),
+                TileType::Bridge => (30, 22, 15),
+                TileType::FleshWall => (55, 20, 28),
+                TileType::TornFlesh => (35, 12, 16),
+                TileType::Oil => (25, 22, 12),
                 TileType::Pit => (5, 5, 5),
+                TileType::BloodPool => (45, 8, 15),
                 TileType::DoorClosed => (35, 28, 18),
+                TileType::DoorLocked => (35, 28, 18),
                 TileType::DoorOpen => (20, 18, 15),
                 TileType::StairsDown => (25, 23, 20),
                 TileType::StairsUp => (25, 23, 20),
+                TileType::Teleporter => (25, 15, 45),
+                TileType::PressurePlate => (30, 28, 24),
+                TileType::Lever | TileType::LeverPulled => (40, 35, 30),
+                TileType::Portcullis | TileType::PortcullisOpen => (20, 20, 25),
+                TileType::TollGate | TileType::TollGateOpen => (28, 24, 14),
                 TileType::Rubble => (25, 22, 18),
                 TileType::Bones => (22, 20, 17),
                 TileType::BloodStain => (45, 15, 15),
@@ -312,6 +422,12 @@ impl TileRenderer {
                 TileType::ShrineEnchant => (15, 30, 40),
                 TileType::ShrineRest => (15, 35, 15),
                 TileType::ShrineCorruption => (40, 10, 25),
+                TileType::ShrineGamble => (40, 35, 10),
+                TileType::ShrineSacrifice => (40, 10, 10),
+                TileType::ShrineTransmute => (10, 35, 30),
+                TileType::AltarMaw => (40, 8, 8),
+                TileType::AltarWidow => (30, 28, 26),
+                TileType::AltarEye => (8, 20, 35),
             }
         } else {
             // Very dark for unexplored
@@ -323,6 +439,12 @@ impl TileRenderer {
                 TileType::ShrineEnchant => (6, 12, 16),
                 TileType::ShrineRest => (6, 14, 6),
                 TileType::ShrineCorruption => (16, 4, 10),
+                TileType::ShrineGamble => (16, 14, 4),
+                TileType::ShrineSacrifice => (16, 4, 4),
+                TileType::ShrineTransmute => (4, 14, 12),
+                TileType::AltarMaw => (16, 3, 3),
+                TileType::AltarWidow => (12, 11, 10),
+                TileType::AltarEye => (3, 8, 14),
                 _ => (8, 7, 6),
             }
         };
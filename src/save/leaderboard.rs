@@ -0,0 +1,311 @@
+//! Run scoring and leaderboard
+//!
+//! Computes a score for each finished run, keeps a local top-N leaderboard
+//! in the player profile, and optionally submits scores to a community
+//! leaderboard (requires the `online-leaderboard` feature).
+
+use serde::{Deserialize, Serialize};
+
+use crate::progression::{CurseSigil, CurseSigils, Difficulty};
+
+/// Maximum number of entries kept on the local leaderboard
+pub const MAX_LEADERBOARD_ENTRIES: usize = 10;
+
+/// Points awarded per floor reached (before difficulty multiplier)
+const POINTS_PER_FLOOR: u32 = 1000;
+/// Points awarded per enemy killed
+const POINTS_PER_KILL: u32 = 10;
+/// Bonus points per boss defeated
+const POINTS_PER_BOSS: u32 = 500;
+/// Bonus for completing the run
+const VICTORY_BONUS: u32 = 5000;
+/// Bonus for never using a shrine
+const SHRINELESS_BONUS: u32 = 1000;
+/// Points per boss felled in a boss rush (before difficulty multiplier)
+const RUSH_POINTS_PER_BOSS: u32 = 2500;
+/// Clearing a boss rush faster than this earns a time bonus
+const RUSH_PAR_SECONDS: u32 = 1800;
+/// Time bonus per second under par
+const RUSH_POINTS_PER_SECOND: u32 = 5;
+
+/// Per-run statistics used for scoring
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    /// Enemies killed this run
+    pub kills: u32,
+    /// Bosses defeated this run
+    pub bosses_killed: u32,
+    /// Gold collected this run
+    pub gold_collected: u32,
+    /// Items picked up this run
+    pub items_found: u32,
+    /// Shrines used this run
+    pub shrines_used: u32,
+    /// Survivors escorted to the stairs this run
+    #[serde(default)]
+    pub escorts_rescued: u32,
+    /// Survivors left behind this run
+    #[serde(default)]
+    pub escorts_lost: u32,
+}
+
+/// Optional challenge conducts that award bonus points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Conduct {
+    /// Completed the run
+    Victorious,
+    /// Never used a shrine
+    Shrineless,
+}
+
+impl Conduct {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Conduct::Victorious => "Victorious",
+            Conduct::Shrineless => "Shrineless",
+        }
+    }
+
+    /// Bonus points for this conduct
+    pub fn bonus(&self) -> u32 {
+        match self {
+            Conduct::Victorious => VICTORY_BONUS,
+            Conduct::Shrineless => SHRINELESS_BONUS,
+        }
+    }
+}
+
+/// A run score split into its components
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    /// Floors reached × difficulty multiplier
+    pub floor_points: u32,
+    /// Points from kills (including boss bonus)
+    pub kill_points: u32,
+    /// Points from gold collected
+    pub gold_points: u32,
+    /// Points from conducts
+    pub conduct_points: u32,
+    /// Boss rush bonus for clearing under par time
+    #[serde(default)]
+    pub time_points: u32,
+    /// Conducts achieved this run
+    pub conducts: Vec<Conduct>,
+    /// Curse sigils the run was started under, which multiply the total
+    #[serde(default)]
+    pub sigils: CurseSigils,
+    /// Final score
+    pub total: u32,
+}
+
+/// Calculate the score for a finished run
+///
+/// Score = (floor × 1000 × difficulty multiplier + kills × 10 + bosses × 500
+///       + gold + conduct bonuses) × curse sigil multipliers
+pub fn calculate_score(stats: &RunStats, floor: u32, difficulty: Difficulty, sigils: &CurseSigils, won: bool) -> ScoreBreakdown {
+    let floor_points = (floor as f32 * POINTS_PER_FLOOR as f32 * difficulty.score_mult()).round() as u32;
+    let kill_points = stats.kills * POINTS_PER_KILL + stats.bosses_killed * POINTS_PER_BOSS;
+    let gold_points = stats.gold_collected;
+
+    let mut conducts = Vec::new();
+    if won {
+        conducts.push(Conduct::Victorious);
+    }
+    // Going without shrines is no feat when there were none to use
+    if stats.shrines_used == 0 && floor > 1 && !sigils.has(CurseSigil::Forsaken) {
+        conducts.push(Conduct::Shrineless);
+    }
+    let conduct_points = conducts.iter().map(|c| c.bonus()).sum();
+    let points = floor_points + kill_points + gold_points + conduct_points;
+
+    ScoreBreakdown {
+        floor_points,
+        kill_points,
+        gold_points,
+        conduct_points,
+        conducts,
+        time_points: 0,
+        sigils: sigils.clone(),
+        total: (points as f32 * sigils.score_mult()).round() as u32,
+    }
+}
+
+/// Calculate the score for a boss rush
+///
+/// Score = bosses × 2500 × difficulty multiplier + victory bonus
+///       + 5 per second under a 30 minute par for a full clear
+pub fn calculate_boss_rush_score(bosses: u32, seconds: u32, difficulty: Difficulty, won: bool) -> ScoreBreakdown {
+    let kill_points = (bosses as f32 * RUSH_POINTS_PER_BOSS as f32 * difficulty.score_mult()).round() as u32;
+    let conducts = if won { vec![Conduct::Victorious] } else { Vec::new() };
+    let conduct_points = conducts.iter().map(|c| c.bonus()).sum();
+    let time_points = if won { RUSH_PAR_SECONDS.saturating_sub(seconds) * RUSH_POINTS_PER_SECOND } else { 0 };
+
+    ScoreBreakdown {
+        kill_points,
+        conduct_points,
+        conducts,
+        time_points,
+        total: kill_points + conduct_points + time_points,
+        ..ScoreBreakdown::default()
+    }
+}
+
+/// A single leaderboard entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub score: u32,
+    pub floor: u32,
+    pub difficulty: Difficulty,
+    pub kills: u32,
+    pub gold: u32,
+    pub won: bool,
+    /// Cause of death (or "Victory")
+    pub cause: String,
+    /// Unix timestamp (seconds) when the run ended
+    pub timestamp: u64,
+    /// Scored as a boss rush rather than a descent
+    #[serde(default)]
+    pub boss_rush: bool,
+    /// Curse sigils the run was started under
+    #[serde(default)]
+    pub sigils: CurseSigils,
+}
+
+/// Local leaderboard stored in the player profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    /// Entries sorted by score, highest first
+    pub entries: Vec<LeaderboardEntry>,
+    /// The endless run that went deepest
+    #[serde(default)]
+    pub deepest: Option<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    /// Insert an entry, returning its 1-based rank if it made the board
+    pub fn submit(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        let index = self.entries
+            .iter()
+            .position(|e| entry.score > e.score)
+            .unwrap_or(self.entries.len());
+
+        if index >= MAX_LEADERBOARD_ENTRIES {
+            return None;
+        }
+
+        self.entries.insert(index, entry);
+        self.entries.truncate(MAX_LEADERBOARD_ENTRIES);
+        Some(index + 1)
+    }
+
+    /// Highest score on the board
+    pub fn best(&self) -> Option<&LeaderboardEntry> {
+        self.entries.first()
+    }
+
+    /// Keep an endless run if it went deeper than the deepest so far (or as
+    /// deep, for more points), returning whether it did
+    pub fn submit_depth(&mut self, entry: LeaderboardEntry) -> bool {
+        let deeper = self.deepest
+            .as_ref()
+            .is_none_or(|d| (entry.floor, entry.score) > (d.floor, d.score));
+        if deeper {
+            self.deepest = Some(entry);
+        }
+        deeper
+    }
+}
+
+/// Current unix time in seconds
+pub fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Submit an entry to a community leaderboard server
+///
+/// Posts the entry as JSON to `url`. Blocking; call from a background thread.
+#[cfg(feature = "online-leaderboard")]
+pub fn submit_online(url: &str, player_name: &str, entry: &LeaderboardEntry) -> Result<(), String> {
+    let body = serde_json::json!({
+        "name": player_name,
+        "version": env!("CARGO_PKG_VERSION"),
+        "entry": entry,
+    });
+
+    ureq::post(url)
+        .timeout(std::time::Duration::from_secs(10))
+        .send_json(body)
+        .map_err(|e| e.to_string())?;
+
+    log::info!("Submitted score {} to {}", entry.score, url);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u32) -> LeaderboardEntry {
+        LeaderboardEntry {
+            score,
+            floor: 1,
+            difficulty: Difficulty::Normal,
+            kills: 0,
+            gold: 0,
+            won: false,
+            cause: String::new(),
+            timestamp: 0,
+            boss_rush: false,
+            sigils: CurseSigils::default(),
+        }
+    }
+
+    #[test]
+    fn test_calculate_score() {
+        let stats = RunStats { kills: 10, bosses_killed: 1, gold_collected: 250, items_found: 3, shrines_used: 2, ..Default::default() };
+        let score = calculate_score(&stats, 5, Difficulty::Normal, &CurseSigils::default(), false);
+        assert_eq!(score.floor_points, 5000);
+        assert_eq!(score.kill_points, 600);
+        assert_eq!(score.gold_points, 250);
+        assert_eq!(score.total, 5850);
+
+        // Harder difficulty and conducts raise the score
+        let stats = RunStats { shrines_used: 0, ..stats };
+        let hard = calculate_score(&stats, 5, Difficulty::Hard, &CurseSigils::default(), true);
+        assert!(hard.conducts.contains(&Conduct::Victorious));
+        assert!(hard.conducts.contains(&Conduct::Shrineless));
+        assert!(hard.total > score.total);
+
+        // Curse sigils multiply the lot, but a shrineless floor earns
+        // nothing when the shrines were never there
+        let mut sigils = CurseSigils::default();
+        sigils.toggle(CurseSigil::Forsaken);
+        let cursed = calculate_score(&stats, 5, Difficulty::Hard, &sigils, true);
+        assert!(!cursed.conducts.contains(&Conduct::Shrineless));
+        assert_eq!(cursed.total, ((hard.total - SHRINELESS_BONUS) as f32 * 1.3).round() as u32);
+    }
+
+    #[test]
+    fn test_leaderboard_ordering() {
+        let mut board = Leaderboard::default();
+        assert_eq!(board.submit(entry(100)), Some(1));
+        assert_eq!(board.submit(entry(300)), Some(1));
+        assert_eq!(board.submit(entry(200)), Some(2));
+        assert_eq!(board.best().map(|e| e.score), Some(300));
+
+        for _ in 0..MAX_LEADERBOARD_ENTRIES {
+            board.submit(entry(500));
+        }
+        assert_eq!(board.entries.len(), MAX_LEADERBOARD_ENTRIES);
+        assert_eq!(board.submit(entry(1)), None);
+
+        // The deepest endless run is kept apart from the scores
+        assert!(board.submit_depth(LeaderboardEntry { floor: 30, ..entry(10) }));
+        assert!(!board.submit_depth(LeaderboardEntry { floor: 28, ..entry(9000) }));
+        assert!(board.submit_depth(LeaderboardEntry { floor: 30, ..entry(20) }));
+        assert_eq!(board.deepest.as_ref().map(|e| (e.floor, e.score)), Some((30, 20)));
+    }
+}
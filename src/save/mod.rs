@@ -4,14 +4,20 @@
 
 pub mod save_game;
 pub mod profile;
+pub mod leaderboard;
 
 pub use save_game::{
-    SaveData, SaveError, SaveSummary,
+    SaveData, SaveSummary,
     save_game, load_game, delete_save,
-    save_exists, list_saves, save_path,
+    save_exists, list_saves, save_path, AUTOSAVE_SLOT,
 };
 
 pub use profile::{
-    PlayerProfile, ProfileStats, ProfileSettings, Achievement,
+    PlayerProfile, ProfileStats, ProfileSettings, LootFilterMode, EnemyOverlays, Achievement,
     load_profile, save_profile, all_achievements,
 };
+
+pub use leaderboard::{
+    RunStats, Conduct, ScoreBreakdown, Leaderboard, LeaderboardEntry,
+    calculate_score, calculate_boss_rush_score,
+};
@@ -3,12 +3,21 @@
 //! Tracks unlocks, achievements, and statistics across runs.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
+use super::leaderboard::Leaderboard;
+use crate::entities::NpcType;
+use crate::error::{Area, Error};
+use crate::items::{Item, Rarity};
+use crate::progression::Difficulty;
+use crate::render::RenderMode;
+
 /// Current profile version for compatibility
 const PROFILE_VERSION: u32 = 1;
+/// Clearing a boss rush within this many seconds earns an achievement
+const BOSS_RUSH_SWIFT_SECONDS: u32 = 15 * 60;
 
 /// Persistent player profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +36,37 @@ pub struct PlayerProfile {
     pub victories: u32,
     /// Settings preferences
     pub settings: ProfileSettings,
+    /// Local high score table
+    #[serde(default)]
+    pub leaderboard: Leaderboard,
+    /// NPCs rescued from cages, who wait at the dungeon entrance
+    #[serde(default)]
+    pub rescued_npcs: Vec<NpcType>,
+    /// Floor the last death happened on
+    #[serde(default)]
+    pub death_floor: u32,
+    /// Deaths in a row on `death_floor`
+    #[serde(default)]
+    pub death_streak: u32,
+    /// Fastest boss rush clear, in seconds
+    #[serde(default)]
+    pub best_boss_rush: Option<u32>,
+    /// Deepest New Game Plus cycle reached
+    #[serde(default)]
+    pub ng_plus_depth: u32,
+    /// Fastest run time on leaving each floor in speedrun mode, in seconds
+    #[serde(default)]
+    pub best_splits: BTreeMap<u32, u32>,
+    /// Fastest victory in speedrun mode, in seconds
+    #[serde(default)]
+    pub best_run_time: Option<u32>,
+    /// Whether the tutorial floor has been played through or skipped.
+    /// Profiles from before the tutorial existed count as done.
+    #[serde(default = "default_true")]
+    pub tutorial_completed: bool,
+    /// First-time hints already shown
+    #[serde(default)]
+    pub seen_hints: HashSet<String>,
 }
 
 /// Profile statistics
@@ -61,6 +101,180 @@ pub struct ProfileSettings {
     pub auto_pickup_gold: bool,
     /// Confirm before using shrines
     pub confirm_shrine_use: bool,
+    /// Submit finished runs to the community leaderboard
+    /// (only used when built with the `online-leaderboard` feature)
+    #[serde(default)]
+    pub submit_scores_online: bool,
+    /// Community leaderboard endpoint
+    #[serde(default = "default_leaderboard_url")]
+    pub leaderboard_url: String,
+    /// Name shown on the community leaderboard
+    #[serde(default = "default_player_name")]
+    pub player_name: String,
+    /// Publish activity to Discord Rich Presence
+    /// (only used when built with the `discord-presence` feature)
+    #[serde(default = "default_true")]
+    pub discord_presence: bool,
+    /// Discord application ID used for Rich Presence
+    #[serde(default)]
+    pub discord_client_id: String,
+    /// Pick up consumables when walking over them
+    #[serde(default)]
+    pub auto_pickup_consumables: bool,
+    /// Pick up gear of at least this rarity when walking over it
+    #[serde(default)]
+    pub auto_pickup_rarity: Option<Rarity>,
+    /// What the loot filter does with drops below `loot_filter_rarity`
+    #[serde(default)]
+    pub loot_filter_mode: LootFilterMode,
+    /// Drops below this rarity are caught by the loot filter
+    #[serde(default = "default_loot_filter_rarity")]
+    pub loot_filter_rarity: Rarity,
+    /// Difficulties that play with pack weight and encumbrance
+    #[serde(default = "default_encumbrance")]
+    pub encumbrance: Vec<Difficulty>,
+    /// How much is drawn next to visible enemies
+    #[serde(default)]
+    pub enemy_overlays: EnemyOverlays,
+    /// Draw mini health bars above damaged enemies
+    #[serde(default = "default_true")]
+    pub enemy_health_bars: bool,
+    /// Zen mode: no sidebar or minimap, just the map and a one-line strip
+    #[serde(default)]
+    pub zen_mode: bool,
+    /// Speedrun mode: split times per floor, with personal bests kept
+    #[serde(default)]
+    pub speedrun_mode: bool,
+    /// Explain things the first time they turn up
+    #[serde(default = "default_true")]
+    pub show_hints: bool,
+    /// Start runs with adaptive difficulty, which eases off or presses
+    /// harder depending on how the last few floors went
+    #[serde(default)]
+    pub adaptive_difficulty: bool,
+    /// How the map is drawn, or `None` to go by what the terminal was
+    /// detected as
+    #[serde(default)]
+    pub render_mode: Option<RenderMode>,
+}
+
+/// Icons drawn next to visible enemies on the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EnemyOverlays {
+    /// No icons
+    Off,
+    /// What each enemy is about to do
+    Intent,
+    /// Intent and the most pressing status effect
+    #[default]
+    Full,
+}
+
+impl EnemyOverlays {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyOverlays::Off => "Off",
+            EnemyOverlays::Intent => "Intent",
+            EnemyOverlays::Full => "Intent + status",
+        }
+    }
+
+    /// The next density when cycling through them in the settings
+    pub fn next(&self) -> Self {
+        match self {
+            EnemyOverlays::Off => EnemyOverlays::Intent,
+            EnemyOverlays::Intent => EnemyOverlays::Full,
+            EnemyOverlays::Full => EnemyOverlays::Off,
+        }
+    }
+}
+
+/// How the loot filter treats low-rarity drops
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LootFilterMode {
+    /// Show every drop normally
+    #[default]
+    Off,
+    /// Dim the drop on the map and mark it as junk in the log
+    Flag,
+    /// Leave the drop off the map and out of the log
+    Hide,
+}
+
+impl LootFilterMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LootFilterMode::Off => "Off",
+            LootFilterMode::Flag => "Flag",
+            LootFilterMode::Hide => "Hide",
+        }
+    }
+
+    /// The next mode when cycling through them in the settings
+    pub fn next(&self) -> Self {
+        match self {
+            LootFilterMode::Off => LootFilterMode::Flag,
+            LootFilterMode::Flag => LootFilterMode::Hide,
+            LootFilterMode::Hide => LootFilterMode::Off,
+        }
+    }
+}
+
+impl ProfileSettings {
+    /// Whether walking over this item picks it up
+    pub fn auto_picks_up(&self, item: &Item) -> bool {
+        if item.is_consumable() {
+            self.auto_pickup_consumables
+        } else if item.is_equippable() {
+            self.auto_pickup_rarity
+                .is_some_and(|min| item.rarity.sort_value() >= min.sort_value())
+        } else {
+            false
+        }
+    }
+
+    /// Whether pack weight matters on this difficulty
+    pub fn encumbrance_enabled(&self, difficulty: Difficulty) -> bool {
+        self.encumbrance.contains(&difficulty)
+    }
+
+    /// Turn encumbrance on or off for a difficulty
+    pub fn toggle_encumbrance(&mut self, difficulty: Difficulty) {
+        if let Some(i) = self.encumbrance.iter().position(|d| *d == difficulty) {
+            self.encumbrance.remove(i);
+        } else {
+            self.encumbrance.push(difficulty);
+        }
+    }
+
+    /// How the loot filter treats this item (`Off` when it passes the filter)
+    pub fn loot_filter_for(&self, item: &Item) -> LootFilterMode {
+        if item.rarity.sort_value() < self.loot_filter_rarity.sort_value() {
+            self.loot_filter_mode
+        } else {
+            LootFilterMode::Off
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_leaderboard_url() -> String {
+    "https://hollowdeep.example.com/api/scores".to_string()
+}
+
+fn default_player_name() -> String {
+    "Hero".to_string()
+}
+
+fn default_loot_filter_rarity() -> Rarity {
+    Rarity::Uncommon
+}
+
+fn default_encumbrance() -> Vec<Difficulty> {
+    vec![Difficulty::Hard, Difficulty::Nightmare]
 }
 
 impl Default for ProfileSettings {
@@ -70,6 +284,23 @@ impl Default for ProfileSettings {
             show_damage_numbers: true,
             auto_pickup_gold: true,
             confirm_shrine_use: true,
+            submit_scores_online: false,
+            leaderboard_url: default_leaderboard_url(),
+            player_name: default_player_name(),
+            discord_presence: true,
+            discord_client_id: String::new(),
+            auto_pickup_consumables: false,
+            auto_pickup_rarity: None,
+            loot_filter_mode: LootFilterMode::Off,
+            loot_filter_rarity: default_loot_filter_rarity(),
+            encumbrance: default_encumbrance(),
+            enemy_overlays: EnemyOverlays::Full,
+            enemy_health_bars: true,
+            zen_mode: false,
+            speedrun_mode: false,
+            show_hints: true,
+            adaptive_difficulty: false,
+            render_mode: None,
         }
     }
 }
@@ -84,6 +315,16 @@ impl Default for PlayerProfile {
             highest_floor: 0,
             victories: 0,
             settings: ProfileSettings::default(),
+            leaderboard: Leaderboard::default(),
+            rescued_npcs: Vec::new(),
+            death_floor: 0,
+            death_streak: 0,
+            best_boss_rush: None,
+            ng_plus_depth: 0,
+            best_splits: BTreeMap::new(),
+            best_run_time: None,
+            tutorial_completed: false,
+            seen_hints: HashSet::new(),
         }
     }
 }
@@ -102,20 +343,84 @@ impl PlayerProfile {
     /// Record a death
     pub fn record_death(&mut self, floor: u32) {
         self.stats.total_deaths += 1;
+        if floor == self.death_floor {
+            self.death_streak += 1;
+        } else {
+            self.death_floor = floor;
+            self.death_streak = 1;
+        }
         if floor > self.highest_floor {
             self.highest_floor = floor;
         }
     }
 
+    /// Deaths in a row on a floor
+    pub fn deaths_on_floor(&self, floor: u32) -> u32 {
+        if floor == self.death_floor { self.death_streak } else { 0 }
+    }
+
     /// Record a victory
     pub fn record_victory(&mut self) {
         self.victories += 1;
         self.check_victory_achievements();
     }
 
+    /// Whether a victory has unlocked the boss rush
+    pub fn boss_rush_unlocked(&self) -> bool {
+        self.victories >= 1
+    }
+
+    /// Record a cleared boss rush, returning true for a new best time
+    pub fn record_boss_rush(&mut self, seconds: u32, difficulty: Difficulty) -> bool {
+        self.unlock_achievement("boss_rush_clear");
+        if seconds < BOSS_RUSH_SWIFT_SECONDS {
+            self.unlock_achievement("boss_rush_swift");
+        }
+        if difficulty == Difficulty::Nightmare {
+            self.unlock_achievement("boss_rush_nightmare");
+        }
+        let best = self.best_boss_rush.is_none_or(|b| seconds < b);
+        if best {
+            self.best_boss_rush = Some(seconds);
+        }
+        best
+    }
+
+    /// Record the run time a floor was left at, returning true for a new best
+    pub fn record_split(&mut self, floor: u32, seconds: u32) -> bool {
+        let best = self.best_splits.get(&floor).is_none_or(|&b| seconds < b);
+        if best {
+            self.best_splits.insert(floor, seconds);
+        }
+        best
+    }
+
+    /// Record a speedrun victory, returning true for a new best time
+    pub fn record_run_time(&mut self, seconds: u32) -> bool {
+        let best = self.best_run_time.is_none_or(|b| seconds < b);
+        if best {
+            self.best_run_time = Some(seconds);
+        }
+        best
+    }
+
+    /// Mark a hint as shown, returning true if it hadn't been before
+    pub fn see_hint(&mut self, id: &str) -> bool {
+        self.seen_hints.insert(id.to_string())
+    }
+
+    /// Record starting a New Game Plus cycle
+    pub fn record_ng_plus(&mut self, cycle: u32) {
+        self.ng_plus_depth = self.ng_plus_depth.max(cycle);
+    }
+
     /// Record floor descent
     pub fn record_floor_descent(&mut self, floor: u32) {
         self.stats.floors_descended += 1;
+        if floor > self.death_floor {
+            // Made it past the floor that kept killing
+            self.death_streak = 0;
+        }
         if floor > self.highest_floor {
             self.highest_floor = floor;
         }
@@ -161,6 +466,11 @@ impl PlayerProfile {
         self.achievements.contains(achievement_id)
     }
 
+    /// Check if an achievement has earned a run unlock
+    pub fn has_run_unlock(&self, unlock: crate::progression::RunUnlock) -> bool {
+        self.has_achievement(unlock.achievement())
+    }
+
     /// Unlock an achievement
     pub fn unlock_achievement(&mut self, achievement_id: &str) -> bool {
         if !self.achievements.contains(achievement_id) {
@@ -172,6 +482,16 @@ impl PlayerProfile {
         }
     }
 
+    /// Record a rescued NPC, returning true if newly rescued
+    pub fn rescue_npc(&mut self, npc_type: NpcType) -> bool {
+        if self.rescued_npcs.contains(&npc_type) {
+            return false;
+        }
+        self.rescued_npcs.push(npc_type);
+        log::info!("Rescued NPC: {}", npc_type.name());
+        true
+    }
+
     // Achievement checking helpers
     fn check_floor_achievements(&mut self, floor: u32) {
         if floor >= 5 {
@@ -183,7 +503,8 @@ impl PlayerProfile {
         if floor >= 15 {
             self.unlock_achievement("reach_floor_15");
         }
-        if floor >= 20 {
+        // Named for when the Abyss ended on floor 20; it still means the final floor
+        if floor >= 25 {
             self.unlock_achievement("reach_floor_20");
         }
     }
@@ -201,7 +522,7 @@ impl PlayerProfile {
         if self.stats.bosses_defeated >= 1 {
             self.unlock_achievement("defeat_first_boss");
         }
-        if self.stats.bosses_defeated >= 4 {
+        if self.stats.bosses_defeated >= 5 {
             self.unlock_achievement("defeat_all_bosses");
         }
     }
@@ -251,46 +572,41 @@ fn profile_path() -> PathBuf {
     }
 }
 
-/// Load the player profile (or create default)
-pub fn load_profile() -> PlayerProfile {
+/// Load the player profile, or start a new one. A profile that's there but
+/// can't be read is started over, and why comes back alongside.
+pub fn load_profile() -> (PlayerProfile, Option<Error>) {
     let path = profile_path();
 
     if path.exists() {
-        match fs::read_to_string(&path) {
-            Ok(data) => {
-                match serde_json::from_str(&data) {
-                    Ok(profile) => {
-                        log::info!("Profile loaded from {:?}", path);
-                        return profile;
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to parse profile: {}, creating new", e);
-                    }
-                }
-            }
-            Err(e) => {
-                log::warn!("Failed to read profile: {}, creating new", e);
+        let loaded = fs::read_to_string(&path)
+            .map_err(|e| Error::io(Area::Profile, "read", &path, e))
+            .and_then(|data| serde_json::from_str(&data).map_err(|e| Error::parse(Area::Profile, &path, e)));
+        match loaded {
+            Ok(profile) => {
+                log::info!("Profile loaded from {:?}", path);
+                return (profile, None);
             }
+            Err(e) => return (PlayerProfile::new(), Some(e)),
         }
     }
 
     log::info!("Creating new profile");
-    PlayerProfile::new()
+    (PlayerProfile::new(), None)
 }
 
 /// Save the player profile
-pub fn save_profile(profile: &PlayerProfile) -> Result<(), String> {
+pub fn save_profile(profile: &PlayerProfile) -> Result<(), Error> {
     let path = profile_path();
 
     // Ensure directory exists
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        fs::create_dir_all(parent).map_err(|e| Error::io(Area::Profile, "write", parent, e))?;
     }
 
     let json = serde_json::to_string_pretty(profile)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| Error::parse(Area::Profile, &path, e))?;
 
-    fs::write(&path, json).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| Error::io(Area::Profile, "write", &path, e))?;
 
     log::info!("Profile saved to {:?}", path);
     Ok(())
@@ -366,7 +682,7 @@ pub fn all_achievements() -> Vec<Achievement> {
         Achievement {
             id: "defeat_all_bosses",
             name: "Conqueror",
-            description: "Defeat all four bosses",
+            description: "Defeat all five bosses",
             hidden: false,
         },
         // Gold achievements
@@ -414,5 +730,24 @@ pub fn all_achievements() -> Vec<Achievement> {
             description: "Defeat a boss without taking damage",
             hidden: true,
         },
+        // Boss rush achievements
+        Achievement {
+            id: "boss_rush_clear",
+            name: "Gauntlet Runner",
+            description: "Clear the boss rush",
+            hidden: false,
+        },
+        Achievement {
+            id: "boss_rush_swift",
+            name: "Against the Clock",
+            description: "Clear the boss rush in under 15 minutes",
+            hidden: false,
+        },
+        Achievement {
+            id: "boss_rush_nightmare",
+            name: "Nightmare Procession",
+            description: "Clear the boss rush on Nightmare",
+            hidden: true,
+        },
     ]
 }
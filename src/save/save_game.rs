@@ -8,12 +8,16 @@ use std::path::PathBuf;
 
 use crate::ecs::{Position, Health, Mana, Stamina, Stats, Experience, StatPoints};
 use crate::ecs::{InventoryComponent, EquipmentComponent, SkillsComponent, GroundItem};
+use crate::ecs::{ComponentRegistry, WorldSnapshot};
+use crate::error::{Area, Error};
 use crate::items::Item;
-use crate::progression::{Difficulty, EquippedSkills};
-use crate::world::{Biome, TileType};
+use crate::progression::{CurseSigils, Difficulty, EquippedSkills};
+use crate::world::{Biome, Decal, Mechanism, TileType};
 
 /// Save file version for compatibility checking
 const SAVE_VERSION: u32 = 1;
+/// Slot the run is saved to on each new floor, past the ones players pick
+pub const AUTOSAVE_SLOT: u8 = 3;
 
 /// Complete save data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,8 +26,20 @@ pub struct SaveData {
     pub player: PlayerSaveData,
     pub game: GameSaveData,
     pub map: MapSaveData,
+    /// Enemies, loot, props, chests and corpses, component by component
+    #[serde(default)]
+    pub entities: WorldSnapshot,
+    /// Older saves kept enemies, loot, props, chests and corpses apart
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub enemies: Vec<EnemySaveData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items_on_ground: Vec<ItemOnGround>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub props: Vec<((i32, i32), crate::entities::Prop)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chests: Vec<((i32, i32), crate::ecs::Chest)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub corpses: Vec<((i32, i32), crate::entities::Corpse)>,
 }
 
 /// Player-specific save data
@@ -40,6 +56,19 @@ pub struct PlayerSaveData {
     pub inventory: Vec<Item>,
     pub equipment: EquipmentSaveData,
     pub skills: EquippedSkills,
+    #[serde(default)]
+    pub mutations: crate::progression::Mutations,
+    #[serde(default)]
+    pub injuries: crate::progression::Injuries,
+    /// Inventory grid rows, including bag upgrades
+    #[serde(default = "default_pack_rows")]
+    pub pack_rows: usize,
+    #[serde(default)]
+    pub belt: crate::items::Belt,
+}
+
+fn default_pack_rows() -> usize {
+    crate::items::GRID_HEIGHT
 }
 
 /// Stats save data
@@ -81,6 +110,57 @@ pub struct GameSaveData {
     pub item_id_counter: u64,
     pub used_shrines: Vec<(u32, i32, i32)>,
     pub rng_seed: u64,
+    #[serde(default)]
+    pub run_stats: super::RunStats,
+    #[serde(default)]
+    pub worship: crate::game::Worship,
+    /// Older saves load with every consumable identified
+    #[serde(default)]
+    pub discoveries: crate::items::Discoveries,
+    #[serde(default)]
+    pub deaths_door_used: bool,
+    /// Exploration time, so a save keeps its time of day
+    #[serde(default)]
+    pub ambient_time: f32,
+    /// Turns on the current floor, toward the Warden's arrival
+    #[serde(default)]
+    pub floor_turns: u32,
+    /// Turns and time over the run, with floor splits
+    #[serde(default)]
+    pub run_clock: crate::game::RunClock,
+    /// Tutorial step, on the tutorial floor
+    #[serde(default)]
+    pub tutorial: Option<usize>,
+    /// Boss rush progress, for boss rush runs
+    #[serde(default)]
+    pub boss_rush: Option<crate::game::BossRush>,
+    /// New Game Plus cycle (0 on a first run)
+    #[serde(default)]
+    pub ng_plus: u32,
+    /// What has befallen the current floor
+    #[serde(default)]
+    pub floor_event: Option<crate::data::FloorEventKind>,
+    /// The floor coming down around the player, if it is
+    #[serde(default)]
+    pub collapse: Option<crate::game::Collapse>,
+    /// Standing with the Merchants' Guild and the Penitent Order
+    #[serde(default)]
+    pub reputation: crate::game::Reputation,
+    /// Paid shrine services, which push shrine prices up
+    #[serde(default)]
+    pub shrine_services: u32,
+    /// Curse sigils the run was started under
+    #[serde(default)]
+    pub sigils: CurseSigils,
+    /// Adaptive difficulty's read on the run, if it was opted into
+    #[serde(default)]
+    pub adaptive: Option<crate::game::AdaptiveDifficulty>,
+    /// Whether the run carried on into the depths below the final floor
+    #[serde(default)]
+    pub endless: bool,
+    /// How far each door the player has been bashing is from giving way
+    #[serde(default)]
+    pub door_damage: Vec<((i32, i32), i32)>,
 }
 
 /// Map save data
@@ -94,6 +174,13 @@ pub struct MapSaveData {
     pub start_pos: (i32, i32),
     pub exit_pos: Option<(i32, i32)>,
     pub elite_rooms: Vec<(i32, i32)>,
+    #[serde(default)]
+    pub decals: Vec<((i32, i32), Decal)>,
+    #[serde(default)]
+    pub teleporters: Vec<((i32, i32), (i32, i32))>,
+    /// Levers and plates, with what they're wired to and how they're set
+    #[serde(default)]
+    pub mechanisms: Vec<Mechanism>,
 }
 
 /// Tile save data
@@ -114,6 +201,9 @@ pub struct EnemySaveData {
     pub xp_reward: u32,
     pub glyph: char,
     pub color: (u8, u8, u8),
+    /// NG+ elite modifiers
+    #[serde(default)]
+    pub modifiers: crate::progression::EliteModifiers,
 }
 
 /// Item on the ground
@@ -170,10 +260,10 @@ pub struct SaveSummary {
 }
 
 /// Load just the summary from a save file
-pub fn load_save_summary(slot: u8) -> Result<SaveSummary, SaveError> {
+pub fn load_save_summary(slot: u8) -> Result<SaveSummary, Error> {
     let path = save_path(slot);
-    let data = fs::read_to_string(&path).map_err(|e| SaveError::IoError(e.to_string()))?;
-    let save: SaveData = serde_json::from_str(&data).map_err(|e| SaveError::ParseError(e.to_string()))?;
+    let data = fs::read_to_string(&path).map_err(|e| Error::io(Area::Save, "read", &path, e))?;
+    let save: SaveData = serde_json::from_str(&data).map_err(|e| Error::parse(Area::Save, &path, e))?;
 
     Ok(SaveSummary {
         floor: save.game.floor,
@@ -182,56 +272,34 @@ pub fn load_save_summary(slot: u8) -> Result<SaveSummary, SaveError> {
     })
 }
 
-/// Save error types
-#[derive(Debug, Clone)]
-pub enum SaveError {
-    IoError(String),
-    ParseError(String),
-    VersionMismatch { expected: u32, found: u32 },
-    InvalidData(String),
-}
-
-impl std::fmt::Display for SaveError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SaveError::IoError(e) => write!(f, "IO error: {}", e),
-            SaveError::ParseError(e) => write!(f, "Parse error: {}", e),
-            SaveError::VersionMismatch { expected, found } => {
-                write!(f, "Save version mismatch: expected {}, found {}", expected, found)
-            }
-            SaveError::InvalidData(e) => write!(f, "Invalid save data: {}", e),
-        }
-    }
-}
-
 /// Save the game to a slot
-pub fn save_game(game: &crate::game::Game, slot: u8) -> Result<(), SaveError> {
+pub fn save_game(game: &crate::game::Game, slot: u8) -> Result<(), Error> {
     let save_data = extract_save_data(game)?;
 
     // Ensure directory exists
     let dir = save_directory();
-    fs::create_dir_all(&dir).map_err(|e| SaveError::IoError(e.to_string()))?;
+    fs::create_dir_all(&dir).map_err(|e| Error::io(Area::Save, "write", &dir, e))?;
 
     // Write save file
     let path = save_path(slot);
     let json = serde_json::to_string_pretty(&save_data)
-        .map_err(|e| SaveError::ParseError(e.to_string()))?;
-    fs::write(&path, json).map_err(|e| SaveError::IoError(e.to_string()))?;
+        .map_err(|e| Error::InvalidSave(e.to_string()))?;
+    fs::write(&path, json).map_err(|e| Error::io(Area::Save, "write", &path, e))?;
 
     log::info!("Game saved to slot {}", slot);
     Ok(())
 }
 
 /// Load a game from a slot
-pub fn load_game(slot: u8) -> Result<SaveData, SaveError> {
+pub fn load_game(slot: u8) -> Result<SaveData, Error> {
     let path = save_path(slot);
-    let data = fs::read_to_string(&path).map_err(|e| SaveError::IoError(e.to_string()))?;
+    let data = fs::read_to_string(&path).map_err(|e| Error::io(Area::Save, "read", &path, e))?;
     let save: SaveData = serde_json::from_str(&data)
-        .map_err(|e| SaveError::ParseError(e.to_string()))?;
+        .map_err(|e| Error::parse(Area::Save, &path, e))?;
 
     // Version check
     if save.version != SAVE_VERSION {
-        return Err(SaveError::VersionMismatch {
+        return Err(Error::SaveVersion {
             expected: SAVE_VERSION,
             found: save.version,
         });
@@ -242,41 +310,102 @@ pub fn load_game(slot: u8) -> Result<SaveData, SaveError> {
 }
 
 /// Delete a save slot
-pub fn delete_save(slot: u8) -> Result<(), SaveError> {
+pub fn delete_save(slot: u8) -> Result<(), Error> {
     let path = save_path(slot);
     if path.exists() {
-        fs::remove_file(&path).map_err(|e| SaveError::IoError(e.to_string()))?;
+        fs::remove_file(&path).map_err(|e| Error::io(Area::Save, "delete", &path, e))?;
         log::info!("Deleted save slot {}", slot);
     }
     Ok(())
 }
 
+/// Components saved with the floor's entities. Registering a component
+/// here is all it takes for it to survive a save and load.
+pub fn level_registry() -> ComponentRegistry {
+    use crate::ecs::{
+        Name, Renderable, BlocksMovement, Enemy, FactionComponent, AI, XpReward, StatusEffects,
+        HazardImmune, Patrol, Sleeper, PackLeader, PackMember, Chest,
+    };
+    use crate::entities::{
+        BossComponent, Corpse, Follower, HeartNode, MimicHoard, Necromancer, PeddlerHoard, Prisoner, Pushable, Risen, Stalker,
+    };
+
+    ComponentRegistry::new()
+        .register::<Position>("position")
+        .register::<Name>("name")
+        .register::<Renderable>("renderable")
+        .register::<BlocksMovement>("blocks_movement")
+        .register::<Enemy>("enemy")
+        .register::<Stats>("stats")
+        .register::<Health>("health")
+        .register::<FactionComponent>("faction")
+        .register::<AI>("ai")
+        .register::<XpReward>("xp_reward")
+        .register::<StatusEffects>("status_effects")
+        .register::<HazardImmune>("hazard_immune")
+        .register::<Patrol>("patrol")
+        .register::<Sleeper>("sleeper")
+        .register::<PackLeader>("pack_leader")
+        .register_linked::<PackMember>("pack_member")
+        .register::<crate::progression::EliteModifiers>("elite_modifiers")
+        .register::<BossComponent>("boss")
+        .register::<Necromancer>("necromancer")
+        .register::<Risen>("risen")
+        .register::<HeartNode>("heart_node")
+        .register::<Stalker>("stalker")
+        .register::<MimicHoard>("mimic_hoard")
+        .register::<PeddlerHoard>("peddler_hoard")
+        .register::<GroundItem>("ground_item")
+        .register::<Pushable>("prop")
+        .register::<Chest>("chest")
+        .register::<Corpse>("corpse")
+        .register::<crate::game::Interactable>("interactable")
+        .register::<Follower>("follower")
+        .register::<Prisoner>("prisoner")
+}
+
+/// Entities saved with the floor. The player is saved on their own, and
+/// NPCs are met afresh after a load.
+fn saved_with_level(entity: &hecs::EntityRef<'_>) -> bool {
+    use crate::ecs::{Chest, Enemy};
+    use crate::entities::{Corpse, Follower, Prisoner, Pushable};
+
+    entity.has::<Enemy>()
+        || entity.has::<Follower>()
+        || entity.has::<Prisoner>()
+        || entity.has::<GroundItem>()
+        || entity.has::<Pushable>()
+        || entity.has::<Chest>()
+        || entity.has::<Corpse>()
+}
+
 /// Extract save data from the current game state
-fn extract_save_data(game: &crate::game::Game) -> Result<SaveData, SaveError> {
-    use crate::ecs::{Name, Renderable, Enemy, XpReward};
+pub fn extract_save_data(game: &crate::game::Game) -> Result<SaveData, Error> {
     use crate::items::EquipSlot;
 
-    let player = game.player().ok_or(SaveError::InvalidData("No player entity".to_string()))?;
+    let player = game.player().ok_or(Error::InvalidSave("No player entity".to_string()))?;
     let world = game.world();
 
     // Extract player data
     let pos = world.get::<&Position>(player)
-        .map_err(|_| SaveError::InvalidData("Missing player position".to_string()))?;
+        .map_err(|_| Error::InvalidSave("Missing player position".to_string()))?;
     let health = world.get::<&Health>(player)
-        .map_err(|_| SaveError::InvalidData("Missing player health".to_string()))?;
+        .map_err(|_| Error::InvalidSave("Missing player health".to_string()))?;
     let mana = world.get::<&Mana>(player)
-        .map_err(|_| SaveError::InvalidData("Missing player mana".to_string()))?;
+        .map_err(|_| Error::InvalidSave("Missing player mana".to_string()))?;
     let stamina = world.get::<&Stamina>(player)
-        .map_err(|_| SaveError::InvalidData("Missing player stamina".to_string()))?;
+        .map_err(|_| Error::InvalidSave("Missing player stamina".to_string()))?;
     let stats = world.get::<&Stats>(player)
-        .map_err(|_| SaveError::InvalidData("Missing player stats".to_string()))?;
+        .map_err(|_| Error::InvalidSave("Missing player stats".to_string()))?;
     let exp = world.get::<&Experience>(player)
-        .map_err(|_| SaveError::InvalidData("Missing player experience".to_string()))?;
+        .map_err(|_| Error::InvalidSave("Missing player experience".to_string()))?;
     let stat_points = world.get::<&StatPoints>(player).map(|sp| sp.0).unwrap_or(0);
 
     // Get inventory (includes gold and items)
     let inv_comp = world.get::<&InventoryComponent>(player);
     let gold = inv_comp.as_ref().map(|inv| inv.inventory.gold()).unwrap_or(0);
+    let belt = inv_comp.as_ref().map(|inv| inv.inventory.belt().clone()).unwrap_or_default();
+    let pack_rows = inv_comp.as_ref().map(|inv| inv.inventory.grid_size().1).unwrap_or_else(|_| default_pack_rows());
     let inventory = inv_comp.map(|inv| inv.inventory.items_owned()).unwrap_or_default();
 
     // Equipment
@@ -302,6 +431,14 @@ fn extract_save_data(game: &crate::game::Game) -> Result<SaveData, SaveError> {
         .map(|sk| sk.skills.clone())
         .unwrap_or_default();
 
+    let mutations = world.get::<&crate::progression::Mutations>(player)
+        .map(|m| (*m).clone())
+        .unwrap_or_default();
+
+    let injuries = world.get::<&crate::progression::Injuries>(player)
+        .map(|i| (*i).clone())
+        .unwrap_or_default();
+
     let player_data = PlayerSaveData {
         position: (pos.x, pos.y),
         health: (health.current, health.max),
@@ -323,19 +460,41 @@ fn extract_save_data(game: &crate::game::Game) -> Result<SaveData, SaveError> {
         inventory,
         equipment,
         skills,
+        mutations,
+        injuries,
+        pack_rows,
+        belt,
     };
 
     // Game data
     let game_data = GameSaveData {
         floor: game.floor(),
         difficulty: game.difficulty(),
-        item_id_counter: 0, // Will need accessor
-        used_shrines: Vec::new(), // Will need accessor
-        rng_seed: 0, // Can't easily extract RNG state
+        item_id_counter: game.item_id_counter(),
+        used_shrines: game.used_shrines(),
+        rng_seed: game.seed(),
+        run_stats: *game.run_stats(),
+        worship: game.worship().clone(),
+        discoveries: game.discoveries().clone(),
+        deaths_door_used: game.deaths_door_used(),
+        ambient_time: game.ambient_time(),
+        floor_turns: game.floor_turns(),
+        run_clock: game.run_clock().clone(),
+        tutorial: game.tutorial_step().map(|(step, _)| step),
+        boss_rush: game.boss_rush().copied(),
+        ng_plus: game.ng_plus(),
+        floor_event: game.floor_event(),
+        collapse: game.collapse().copied(),
+        reputation: *game.reputation(),
+        shrine_services: game.shrine_services(),
+        sigils: game.sigils().clone(),
+        adaptive: game.adaptive().cloned(),
+        endless: game.endless(),
+        door_damage: game.door_damage(),
     };
 
     // Map data
-    let map = game.map().ok_or(SaveError::InvalidData("No map".to_string()))?;
+    let map = game.map().ok_or(Error::InvalidSave("No map".to_string()))?;
     let map_data = MapSaveData {
         width: map.width,
         height: map.height,
@@ -349,44 +508,25 @@ fn extract_save_data(game: &crate::game::Game) -> Result<SaveData, SaveError> {
         start_pos: (map.start_pos.x, map.start_pos.y),
         exit_pos: map.exit_pos.map(|p| (p.x, p.y)),
         elite_rooms: map.elite_rooms.iter().map(|p| (p.x, p.y)).collect(),
+        decals: map.decals.iter().map(|(p, d)| ((p.x, p.y), *d)).collect(),
+        teleporters: map.teleporters.iter().map(|(a, b)| ((a.x, a.y), (b.x, b.y))).collect(),
+        mechanisms: map.mechanisms.clone(),
     };
 
-    // Enemies
-    let mut enemies = Vec::new();
-    for (_, (epos, name, ehealth, estats, xp, renderable, _)) in world.query::<(
-        &Position, &Name, &Health, &Stats, &XpReward, &Renderable, &Enemy
-    )>().iter() {
-        enemies.push(EnemySaveData {
-            name: name.0.clone(),
-            position: (epos.x, epos.y),
-            health: (ehealth.current, ehealth.max),
-            stats: StatsSaveData {
-                strength: estats.strength,
-                dexterity: estats.dexterity,
-                intelligence: estats.intelligence,
-                vitality: estats.vitality,
-            },
-            xp_reward: xp.0,
-            glyph: renderable.glyph,
-            color: renderable.fg,
-        });
-    }
-
-    // Items on ground
-    let mut items_on_ground = Vec::new();
-    for (_, (ipos, ground_item)) in world.query::<(&Position, &GroundItem)>().iter() {
-        items_on_ground.push(ItemOnGround {
-            position: (ipos.x, ipos.y),
-            item: ground_item.item.clone(),
-        });
-    }
+    let entities = level_registry()
+        .snapshot(world, saved_with_level)
+        .map_err(|e| Error::InvalidSave(e.to_string()))?;
 
     Ok(SaveData {
         version: SAVE_VERSION,
         player: player_data,
         game: game_data,
         map: map_data,
-        enemies,
-        items_on_ground,
+        entities,
+        enemies: Vec::new(),
+        items_on_ground: Vec::new(),
+        props: Vec::new(),
+        chests: Vec::new(),
+        corpses: Vec::new(),
     })
 }
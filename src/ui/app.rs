@@ -3,21 +3,28 @@
 //! Coordinates rendering and input handling across all screens.
 
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 use rand::Rng;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Clear},
+    widgets::{Block, Borders, Paragraph, Clear, Widget},
 };
 
-use crate::game::{Game, GameState, PlayingState, MessageCategory, ShrineType};
+use crate::game::{Game, GameState, PlayingState, MessageCategory, ShrineType, Interaction};
 use crate::ecs::Position;
-use crate::render::{RenderMode, TileRenderer, detect_render_mode};
+use crate::items::Transaction;
+use crate::render::{RenderMode, TileRenderer, detect_render_mode, preview, screenshot};
 use crate::world::TileType;
+use crate::world::generation::floor_name;
 use crate::audio::SoundId;
+use crate::ui::layout::{CollapsedPanels, LayoutProfile, PlayingLayout};
+use crate::ui::palette::{Palette, PaletteAction};
+use crate::ui::panel_cache::PanelCache;
+use crate::ui::intern::intern;
+use crate::ui::widgets::{GridCursor, GridInventoryWidget, render_item_details};
 
 /// Truncate a string to fit within max_len characters, adding "…" if truncated
 fn truncate_name(name: &str, max_len: usize) -> String {
@@ -31,6 +38,60 @@ fn truncate_name(name: &str, max_len: usize) -> String {
     }
 }
 
+/// How the shopkeeper's faction regards the player, which sets their prices
+fn shop_standing(game: &Game, npc_entity: hecs::Entity) -> crate::game::Standing {
+    game.world()
+        .get::<&crate::entities::NpcComponent>(npc_entity)
+        .map(|npc| game.standing_with(npc.npc_type))
+        .unwrap_or(crate::game::Standing::Neutral)
+}
+
+/// What a merchant service works on
+#[derive(Debug, Clone)]
+enum ServiceTarget {
+    /// An item in the pack
+    Pack(crate::items::ItemId),
+    /// An equipped item
+    Worn(crate::items::EquipSlot),
+    /// An unidentified potion or scroll kind, by item name
+    Kind(String),
+}
+
+/// One row on a merchant's services tab
+#[derive(Debug, Clone)]
+struct ServiceOffer {
+    service: crate::entities::npcs::MerchantService,
+    target: Option<ServiceTarget>,
+    label: String,
+    price: u32,
+}
+
+/// What to do with the prop in the direction the player picks
+#[derive(Debug, Clone, Copy)]
+enum PropVerb {
+    Pull,
+    Throw,
+}
+
+impl PropVerb {
+    fn name(&self) -> &'static str {
+        match self {
+            PropVerb::Pull => "Pull",
+            PropVerb::Throw => "Throw",
+        }
+    }
+}
+
+/// Working space for drawing the map, kept between frames so each frame
+/// reuses the last one's allocations
+#[derive(Debug, Default)]
+struct MapScratch {
+    /// Items on each tile with any, and the rarest of them
+    piles: std::collections::HashMap<Position, (usize, crate::items::Rarity)>,
+    /// Health bars, intents and status icons around enemies
+    marks: Vec<(Position, char, Color)>,
+}
+
 /// Main UI application
 pub struct App {
     /// Current camera position for map rendering
@@ -40,12 +101,20 @@ pub struct App {
     message_verbosity: u8,
     /// Current render mode (ASCII, Unicode, Kitty)
     render_mode: RenderMode,
+    /// What the terminal was detected as, for when no mode is chosen
+    detected_render_mode: RenderMode,
     /// Tile renderer instance
     tile_renderer: TileRenderer,
     /// Current inventory cursor position
     inventory_cursor: usize,
     /// Inventory tab (0=items, 1=equipment)
     inventory_tab: u8,
+    /// Show the items tab as the item grid instead of a list
+    inventory_grid_mode: bool,
+    /// Cursor cell in the grid view
+    grid_cursor: GridCursor,
+    /// Item picked up in the grid view, waiting to be placed
+    grid_held: Option<crate::items::ItemId>,
     /// Current inventory sort mode
     inventory_sort_mode: crate::items::SortMode,
     /// Character sheet selected slot (0-7 for equipment slots, 8-12 for skill slots)
@@ -56,12 +125,26 @@ pub struct App {
     skill_selection_cursor: usize,
     /// Currently selected skill slot for swapping (0-4)
     skill_slot_to_swap: usize,
+    /// Derived stat whose working is shown, while the character sheet is in info mode
+    stat_info: Option<usize>,
     /// Shop selection cursor
     shop_selection: usize,
     /// Shop mode: 0=Buy, 1=Sell
     shop_mode: u8,
     /// Sell selection cursor (index in player inventory)
     sell_selection: usize,
+    /// Items marked for selling together
+    sell_marked: std::collections::HashSet<crate::items::ItemId>,
+    /// Items sold this visit, with what was paid for them, oldest first
+    buyback: Vec<(crate::items::Item, u32)>,
+    /// Buyback selection cursor
+    buyback_selection: usize,
+    /// Services tab selection cursor
+    service_selection: usize,
+    /// Gambler's table: which of the floor's stakes is picked
+    dice_stake: usize,
+    /// Gambler's table: the last throw
+    dice_last: Option<crate::game::DiceThrow>,
     /// Whether we're in equip selection mode (selecting item from inventory to equip)
     equip_selection_mode: bool,
     /// Cursor for equip selection (index into filtered inventory)
@@ -88,36 +171,110 @@ pub struct App {
     shrine_skill_swap_cursor: usize,
     /// Skill shrine: the skill pending to be learned (stored when entering swap mode)
     shrine_pending_skill: Option<crate::progression::Skill>,
+    /// Sacrifice/transmutation shrine: cursor for selecting equipment
+    shrine_item_cursor: usize,
+    /// Gambling shrine: result of the last offering
+    shrine_last_outcome: Option<String>,
     /// Help screen scroll position
     help_scroll: u16,
     /// Pending movement skill (e.g., Shadow Step) - stores the range when awaiting direction
     pending_movement_skill: Option<i32>,
+    /// Awaiting a direction to disengage in (step away without provoking free attacks)
+    pending_disengage: bool,
+    /// Wand awaiting a direction to zap in
+    pending_zap: Option<crate::items::ItemId>,
+    /// Awaiting the direction of a prop to pull or throw
+    pending_prop: Option<PropVerb>,
+    /// Awaiting a choice of attack or skill to prepare
+    pending_prepare: bool,
+    /// Awaiting the direction of someone to pickpocket
+    pending_steal: bool,
+    /// Hazard tile the player has been warned about (moving there again confirms)
+    hazard_confirm: Option<Position>,
+    /// Ground items offered in the pickup menu, and whether each is marked
+    pickup_choices: Vec<(hecs::Entity, bool)>,
+    /// Cursor in the pickup menu
+    pickup_cursor: usize,
     /// Whether we're showing the difficulty selection popup
     difficulty_selection_mode: bool,
     /// Currently highlighted difficulty option (0=Easy, 1=Normal, 2=Hard, 3=Nightmare)
     difficulty_selection_cursor: usize,
+    /// Whether the difficulty chosen starts a boss rush rather than a descent
+    boss_rush_selected: bool,
+    /// Curse sigils taken for the next run
+    chosen_sigils: crate::progression::CurseSigils,
+    /// Cursor over the equipped items on the victory screen, while choosing
+    /// what to carry into New Game Plus
+    ng_plus_cursor: Option<usize>,
+    /// Puzzle chest whose riddle is being asked
+    riddle_chest: Option<hecs::Entity>,
+    /// Cell under the look-mode cursor, while looking around
+    look_cursor: Option<Position>,
+    /// Map cell under the mouse pointer
+    hover_cell: Option<Position>,
+    /// The command palette, while it's open
+    palette: Option<Palette>,
+    /// Where the map was last drawn (inner area and camera corner), to turn
+    /// mouse positions into map cells
+    map_view: std::cell::Cell<Option<(Rect, i32, i32)>>,
+    /// Layout picked for the terminal's size
+    layout_profile: LayoutProfile,
+    /// Panels folded away by hand
+    collapsed: CollapsedPanels,
+    /// Wizard mode: the look cursor inspects entities' components
+    wizard: bool,
+    /// Recent frame times while the wizard mode perf overlay is up
+    perf: Option<crate::perf::FrameStats>,
+    /// Seed for the next run started from the menu, given on the command line
+    seed: Option<u64>,
+    /// Bumped on every key, so cached panels know the player did something
+    input_revision: u64,
+    /// The status sidebar and nearby panel as last drawn
+    sidebar_cache: std::cell::RefCell<PanelCache>,
+    nearby_cache: std::cell::RefCell<PanelCache>,
+    map_scratch: std::cell::RefCell<MapScratch>,
+    /// Report of the crash the last launch ended in, until the player has
+    /// taken up or turned down the autosave
+    crash_report: Option<std::path::PathBuf>,
+    /// F12 was pressed, so the next frame drawn is saved
+    screenshot_requested: bool,
+    /// Where the run card of the run just finished was saved
+    run_card: Option<std::path::PathBuf>,
 }
 
 impl App {
     pub fn new() -> Self {
         let render_mode = detect_render_mode();
-        log::info!("Using render mode: {:?}", render_mode);
+        let layout_profile = crossterm::terminal::size()
+            .map(|(width, _)| LayoutProfile::for_width(width))
+            .unwrap_or(LayoutProfile::Standard);
 
         Self {
             camera: Position::new(0, 0),
             message_verbosity: 1,
             render_mode,
+            detected_render_mode: render_mode,
             tile_renderer: TileRenderer::new(render_mode),
             inventory_cursor: 0,
             inventory_tab: 0,
+            inventory_grid_mode: false,
+            grid_cursor: GridCursor::default(),
+            grid_held: None,
             inventory_sort_mode: crate::items::SortMode::Category,
             character_slot: 0,
             skill_selection_mode: false,
             skill_selection_cursor: 0,
             skill_slot_to_swap: 0,
+            stat_info: None,
             shop_selection: 0,
             shop_mode: 0,
             sell_selection: 0,
+            sell_marked: std::collections::HashSet::new(),
+            buyback: Vec::new(),
+            buyback_selection: 0,
+            service_selection: 0,
+            dice_stake: 0,
+            dice_last: None,
             equip_selection_mode: false,
             equip_selection_cursor: 0,
             enchant_affix_cursor: 0,
@@ -131,10 +288,101 @@ impl App {
             shrine_skill_swap_mode: false,
             shrine_skill_swap_cursor: 0,
             shrine_pending_skill: None,
+            shrine_item_cursor: 0,
+            shrine_last_outcome: None,
             help_scroll: 0,
             pending_movement_skill: None,
+            pending_disengage: false,
+            pending_zap: None,
+            pending_prop: None,
+            pending_prepare: false,
+            pending_steal: false,
+            hazard_confirm: None,
+            pickup_choices: Vec::new(),
+            pickup_cursor: 0,
             difficulty_selection_mode: false,
             difficulty_selection_cursor: 1, // Default to Normal
+            chosen_sigils: crate::progression::CurseSigils::default(),
+            boss_rush_selected: false,
+            ng_plus_cursor: None,
+            riddle_chest: None,
+            look_cursor: None,
+            hover_cell: None,
+            palette: None,
+            map_view: std::cell::Cell::new(None),
+            layout_profile,
+            collapsed: CollapsedPanels { side: layout_profile == LayoutProfile::Compact, log: false },
+            wizard: false,
+            perf: None,
+            seed: None,
+            input_revision: 0,
+            sidebar_cache: Default::default(),
+            nearby_cache: Default::default(),
+            map_scratch: Default::default(),
+            crash_report: None,
+            screenshot_requested: false,
+            run_card: None,
+        }
+    }
+
+    /// Turn wizard mode on or off
+    pub fn with_wizard(mut self, wizard: bool) -> Self {
+        self.wizard = wizard;
+        self
+    }
+
+    /// Seed the first run started from the menu
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Bring the camera to the player, for a run started outside the menus
+    pub fn follow_player(&mut self, game: &Game) {
+        if let Some(pos) = game.player_position() {
+            self.camera = pos;
+        }
+    }
+
+    /// Note where a frame's time went, if the perf overlay is up
+    pub fn record_frame(&mut self, sample: crate::perf::FrameSample) {
+        if let Some(perf) = &mut self.perf {
+            perf.push(sample);
+        }
+    }
+
+    /// Save the screen just drawn if F12 asked for it
+    pub fn capture_screenshot(&mut self, screen: &ratatui::buffer::Buffer, game: &mut Game) {
+        if !std::mem::take(&mut self.screenshot_requested) {
+            return;
+        }
+        match screenshot::export_screen(screen, &screenshot::screenshot_directory()) {
+            Ok(path) => {
+                log::info!("Screenshot saved to {}", path.display());
+                game.add_message(format!("Screenshot saved to {}", path.display()), MessageCategory::System);
+            }
+            Err(e) => game.report_error(e),
+        }
+    }
+
+    /// Own up to a crash the last launch ended in, offering the autosave
+    pub fn with_crash_report(mut self, report: Option<std::path::PathBuf>) -> Self {
+        self.crash_report = report;
+        self
+    }
+
+    /// Draw with a chosen render mode, or `None` for the detected one
+    pub fn with_render_mode(mut self, mode: Option<RenderMode>) -> Self {
+        self.set_render_mode(mode);
+        self
+    }
+
+    fn set_render_mode(&mut self, mode: Option<RenderMode>) {
+        self.render_mode = mode.unwrap_or(self.detected_render_mode);
+        self.tile_renderer = TileRenderer::new(self.render_mode);
+        match mode {
+            Some(mode) => log::info!("Using render mode: {:?} (chosen)", mode),
+            None => log::info!("Using render mode: {:?} (detected)", self.render_mode),
         }
     }
 
@@ -157,13 +405,40 @@ impl App {
 
     /// Handle keyboard input, returns true if should quit
     pub fn handle_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        self.input_revision += 1;
         // Global quit shortcut
         if key.code == KeyCode::Char('q') && key.modifiers.contains(KeyModifiers::CONTROL) {
             return Ok(true);
         }
+        // Wizard mode: F3 puts the perf overlay up or takes it down
+        if self.wizard && key.code == KeyCode::F(3) {
+            self.perf = match self.perf {
+                Some(_) => None,
+                None => Some(crate::perf::FrameStats::default()),
+            };
+            return Ok(false);
+        }
+        // F12 saves the screen as it's drawn next, wherever the player is
+        if key.code == KeyCode::F(12) {
+            self.screenshot_requested = true;
+            return Ok(false);
+        }
+        // An error on show takes the next key to dismiss
+        if game.error_popup().is_some() {
+            if matches!(key.code, KeyCode::Enter | KeyCode::Esc | KeyCode::Char(' ')) {
+                game.dismiss_error();
+            }
+            return Ok(false);
+        }
+        if self.crash_report.is_some() && matches!(game.state(), GameState::MainMenu) {
+            return self.handle_crash_input(key, game);
+        }
 
         match game.state().clone() {
             GameState::MainMenu => self.handle_main_menu_input(key, game),
+            GameState::Playing(_) if game.cutscene().is_some() => self.handle_cutscene_input(key, game),
+            GameState::Playing(_) if self.riddle_chest.is_some() => self.handle_riddle_input(key, game),
+            GameState::Playing(PlayingState::Exploring) if self.palette.is_some() => self.handle_palette_input(key, game),
             GameState::Playing(playing_state) => {
                 self.handle_playing_input(key, game, playing_state)
             }
@@ -171,6 +446,8 @@ impl App {
             GameState::SaveSlots { selected } => self.handle_save_slots_input(key, game, selected),
             GameState::LoadSlots { selected } => self.handle_load_slots_input(key, game, selected),
             GameState::Achievements => self.handle_achievements_input(key, game),
+            GameState::Leaderboard => self.handle_leaderboard_input(key, game),
+            GameState::Log { scroll } => self.handle_log_input(key, game, scroll),
             GameState::GameOver { .. } => self.handle_game_over_input(key, game),
             GameState::Victory => self.handle_victory_input(key, game),
             GameState::NewRun { .. } => self.handle_new_run_input(key, game),
@@ -178,6 +455,46 @@ impl App {
         }
     }
 
+    /// Pick the layout for the terminal's new size. Moving into the compact
+    /// layout folds the side panels away; leaving it brings them back.
+    pub fn handle_resize(&mut self, width: u16) {
+        let profile = LayoutProfile::for_width(width);
+        if profile != self.layout_profile {
+            self.collapsed.side = profile == LayoutProfile::Compact;
+            self.layout_profile = profile;
+        }
+    }
+
+    /// Panels to fold away: the ones collapsed by hand, and both side panels
+    /// in zen mode
+    fn panels(&self, game: &Game) -> CollapsedPanels {
+        let zen = game.profile().settings.zen_mode;
+        CollapsedPanels { side: self.collapsed.side || zen, ..self.collapsed }
+    }
+
+    /// Track the map cell under the mouse pointer for the hover tooltip
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if !matches!(mouse.kind, MouseEventKind::Moved) {
+            return;
+        }
+        self.hover_cell = self.map_view.get().and_then(|(inner, cam_x, cam_y)| {
+            let inside = mouse.column >= inner.x && mouse.column < inner.x + inner.width
+                && mouse.row >= inner.y && mouse.row < inner.y + inner.height;
+            inside.then(|| Position::new(cam_x + (mouse.column - inner.x) as i32, cam_y + (mouse.row - inner.y) as i32))
+        });
+    }
+
+    /// Difficulty under the cursor in the new-run popup
+    fn selected_difficulty(&self) -> crate::progression::Difficulty {
+        match self.difficulty_selection_cursor {
+            0 => crate::progression::Difficulty::Easy,
+            1 => crate::progression::Difficulty::Normal,
+            2 => crate::progression::Difficulty::Hard,
+            3 => crate::progression::Difficulty::Nightmare,
+            _ => crate::progression::Difficulty::Normal,
+        }
+    }
+
     fn handle_main_menu_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
         // Check if we're in difficulty selection mode
         if self.difficulty_selection_mode {
@@ -194,18 +511,30 @@ impl App {
                         self.difficulty_selection_cursor += 1;
                     }
                 }
+                KeyCode::Char('w') => {
+                    game.play_sound(SoundId::MenuMove);
+                    let difficulty = self.selected_difficulty();
+                    game.update_settings(|s| s.toggle_encumbrance(difficulty));
+                }
+                KeyCode::Char(c @ '1'..='4') if !self.boss_rush_selected => {
+                    game.play_sound(SoundId::MenuMove);
+                    let sigil = crate::progression::CurseSigil::ALL[c as usize - '1' as usize];
+                    self.chosen_sigils.toggle(sigil);
+                }
+                KeyCode::Char('a') if !self.boss_rush_selected => {
+                    game.play_sound(SoundId::MenuMove);
+                    game.update_settings(|s| s.adaptive_difficulty = !s.adaptive_difficulty);
+                }
                 KeyCode::Enter | KeyCode::Char(' ') => {
                     game.play_sound(SoundId::MenuSelect);
                     // Start new game with selected difficulty
-                    let difficulty = match self.difficulty_selection_cursor {
-                        0 => crate::progression::Difficulty::Easy,
-                        1 => crate::progression::Difficulty::Normal,
-                        2 => crate::progression::Difficulty::Hard,
-                        3 => crate::progression::Difficulty::Nightmare,
-                        _ => crate::progression::Difficulty::Normal,
-                    };
+                    let difficulty = self.selected_difficulty();
                     self.difficulty_selection_mode = false;
-                    game.start_new_run(None, difficulty);
+                    if self.boss_rush_selected {
+                        game.start_boss_rush(difficulty);
+                    } else {
+                        game.start_new_run(self.seed.take(), difficulty, self.chosen_sigils.clone());
+                    }
                     // Sync camera to player position
                     if let Some(pos) = game.player_position() {
                         self.camera = pos;
@@ -227,6 +556,17 @@ impl App {
                 // Show difficulty selection popup
                 self.difficulty_selection_mode = true;
                 self.difficulty_selection_cursor = 1; // Default to Normal
+                self.boss_rush_selected = false;
+            }
+            KeyCode::Char('b') => {
+                if game.profile().boss_rush_unlocked() {
+                    game.play_sound(SoundId::MenuSelect);
+                    self.difficulty_selection_mode = true;
+                    self.difficulty_selection_cursor = 1;
+                    self.boss_rush_selected = true;
+                } else {
+                    game.play_sound(SoundId::Error);
+                }
             }
             KeyCode::Char('l') => {
                 // Open load game slot selection
@@ -236,6 +576,10 @@ impl App {
                 // View achievements and stats
                 game.set_state(GameState::Achievements);
             }
+            KeyCode::Char('h') => {
+                // View local high scores
+                game.set_state(GameState::Leaderboard);
+            }
             KeyCode::Char('q') | KeyCode::Esc => {
                 game.quit();
             }
@@ -250,19 +594,137 @@ impl App {
         game: &mut Game,
         state: PlayingState,
     ) -> Result<bool> {
-        match state {
+        let result = match state {
             PlayingState::Exploring => self.handle_exploring_input(key, game),
             PlayingState::Inventory => self.handle_inventory_input(key, game),
             PlayingState::Character => self.handle_character_input(key, game),
             PlayingState::MapView => self.handle_mapview_input(key, game),
             PlayingState::Help => self.handle_help_input(key, game),
+            PlayingState::Discoveries => self.handle_discoveries_input(key, game),
+            PlayingState::Pickup => self.handle_pickup_input(key, game),
             PlayingState::Shrine { shrine_type } => self.handle_shrine_input(key, game, shrine_type),
             PlayingState::Shop { npc_entity } => self.handle_shop_input(key, game, npc_entity),
+            PlayingState::Dice { .. } => self.handle_dice_input(key, game),
             _ => Ok(false),
+        };
+        self.strike_fleeing_enemies(game);
+        self.spring_prepared_action(game);
+        game.reap_dead();
+        // Forced movement during the turn may have dragged the player elsewhere
+        if let Some(pos) = game.player_position() {
+            self.camera = pos;
+        }
+        result
+    }
+
+    /// Fire the player's prepared action at the enemy that walked into it
+    fn spring_prepared_action(&mut self, game: &mut Game) {
+        use crate::game::PreparedAction;
+
+        let Some((enemy, action)) = game.take_overwatch_strike() else { return };
+        if !matches!(game.state(), GameState::Playing(_)) || !game.world().contains(enemy) {
+            return;
+        }
+        let name = game.world().get::<&crate::ecs::Name>(enemy)
+            .map(|n| n.0.clone())
+            .unwrap_or_else(|_| "enemy".to_string());
+        match action {
+            PreparedAction::Attack => {
+                game.add_message(format!("You strike the {} as it closes in!", name), MessageCategory::Combat);
+                self.attack_enemy(game, enemy, false);
+            }
+            PreparedAction::Skill(slot) => {
+                game.add_message(format!("The {} walks into your prepared skill!", name), MessageCategory::Combat);
+                self.cast_skill(game, slot, false);
+            }
+        }
+    }
+
+    /// Ready an attack or the skill in a slot to fire during the enemy turn
+    fn prepare(&mut self, game: &mut Game, action: crate::game::PreparedAction) {
+        use crate::game::PreparedAction;
+
+        let range = match action {
+            PreparedAction::Attack => 1,
+            PreparedAction::Skill(slot) => {
+                let Some(player) = game.player() else { return };
+                let mana = game.player_mana().map(|m| m.current).unwrap_or(0);
+                let stamina = game.player_stamina().map(|s| s.current).unwrap_or(0);
+                let skill = game.world().get::<&crate::ecs::SkillsComponent>(player).ok()
+                    .and_then(|sc| sc.skills.slots[slot].clone().map(|s| (s, sc.skills.can_use(slot, mana, stamina))));
+                let Some((skill, usable)) = skill else {
+                    game.add_message(format!("No skill in slot {}", slot + 1), MessageCategory::Warning);
+                    return;
+                };
+                let Some(range) = crate::game::prepared_range(skill.target) else {
+                    game.add_message(format!("{} can't be prepared.", skill.display_name()), MessageCategory::Warning);
+                    return;
+                };
+                if !usable {
+                    game.add_message("Cannot use skill (on cooldown or not enough resources)".to_string(), MessageCategory::Warning);
+                    return;
+                }
+                range
+            }
+        };
+
+        let what = match action {
+            PreparedAction::Attack => "your weapon".to_string(),
+            PreparedAction::Skill(slot) => game.player()
+                .and_then(|p| game.world().get::<&crate::ecs::SkillsComponent>(p).ok()
+                    .and_then(|sc| sc.skills.slots[slot].as_ref().map(|s| s.display_name())))
+                .unwrap_or_default(),
+        };
+        game.add_message(format!("You ready {} and watch for movement...", what), MessageCategory::Combat);
+        game.prepare_action(action, range);
+    }
+
+    /// Punish enemies that turned their back on the player this turn
+    fn strike_fleeing_enemies(&mut self, game: &mut Game) {
+        for enemy in game.take_fleeing_strikes() {
+            if !matches!(game.state(), GameState::Playing(_)) || !game.world().contains(enemy) {
+                continue;
+            }
+            let name = game.world().get::<&crate::ecs::Name>(enemy)
+                .map(|n| n.0.clone())
+                .unwrap_or_else(|_| "enemy".to_string());
+            game.add_message(format!("You strike the fleeing {}!", name), MessageCategory::Combat);
+            self.attack_enemy(game, enemy, false);
         }
     }
 
     fn handle_exploring_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        // Any key gets the player back on their feet
+        if game.is_resting() {
+            game.stop_rest(crate::game::RestEnd::Cancelled);
+            return Ok(false);
+        }
+        // Any key breaks off a channeled action
+        if game.is_channeling() {
+            game.cancel_channel();
+            return Ok(false);
+        }
+
+        // Look mode moves the cursor instead of the player
+        if let Some(cursor) = self.look_cursor {
+            let step: Option<(i32, i32)> = match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Some((0, -1)),
+                KeyCode::Down | KeyCode::Char('j') => Some((0, 1)),
+                KeyCode::Left | KeyCode::Char('h') => Some((-1, 0)),
+                KeyCode::Right | KeyCode::Char('l') => Some((1, 0)),
+                KeyCode::Char('y') => Some((-1, -1)),
+                KeyCode::Char('u') => Some((1, -1)),
+                KeyCode::Char('b') => Some((-1, 1)),
+                KeyCode::Char('n') => Some((1, 1)),
+                _ => None,
+            };
+            match step {
+                Some((dx, dy)) => self.look_cursor = Some(Position::new(cursor.x + dx, cursor.y + dy)),
+                None => self.look_cursor = None,
+            }
+            return Ok(false);
+        }
+
         // Check for pending movement skill (Shadow Step, etc.)
         if let Some(range) = self.pending_movement_skill {
             let direction: Option<(i32, i32)> = match key.code {
@@ -292,6 +754,144 @@ impl App {
             return Ok(false);
         }
 
+        // Check for pending disengage
+        if self.pending_disengage {
+            let direction: Option<(i32, i32)> = match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Some((0, -1)),
+                KeyCode::Down | KeyCode::Char('j') => Some((0, 1)),
+                KeyCode::Left | KeyCode::Char('h') => Some((-1, 0)),
+                KeyCode::Right | KeyCode::Char('l') => Some((1, 0)),
+                KeyCode::Char('y') => Some((-1, -1)),
+                KeyCode::Char('u') => Some((1, -1)),
+                KeyCode::Char('b') => Some((-1, 1)),
+                KeyCode::Char('n') => Some((1, 1)),
+                KeyCode::Esc => {
+                    self.pending_disengage = false;
+                    game.add_message("Disengage cancelled.".to_string(), MessageCategory::System);
+                    return Ok(false);
+                }
+                _ => None,
+            };
+
+            if let Some((dx, dy)) = direction {
+                let to = Position::new(self.camera.x + dx, self.camera.y + dy);
+                let open = game.map().is_some_and(|m| m.is_walkable(to.x, to.y))
+                    && game.get_blocking_entity_at(to).is_none();
+                if !open {
+                    game.add_message("There's no room to disengage that way.".to_string(), MessageCategory::Warning);
+                } else if game.spend_disengage_stamina() {
+                    game.add_message("You carefully back away.".to_string(), MessageCategory::Combat);
+                    self.try_move(game, dx, dy);
+                }
+                self.pending_disengage = false;
+            }
+            return Ok(false);
+        }
+
+        // Check for a pending prepare: attack or skill slot
+        if self.pending_prepare {
+            use crate::game::PreparedAction;
+            self.pending_prepare = false;
+            match key.code {
+                KeyCode::Char('w') | KeyCode::Char('a') => self.prepare(game, PreparedAction::Attack),
+                KeyCode::Char(c @ '1'..='5') => {
+                    let slot = c as usize - '1' as usize;
+                    self.prepare(game, PreparedAction::Skill(slot));
+                }
+                _ => game.add_message("Prepare cancelled.".to_string(), MessageCategory::System),
+            }
+            return Ok(false);
+        }
+
+        // Check for a wand awaiting a direction
+        if let Some(wand_id) = self.pending_zap {
+            let direction: Option<(i32, i32)> = match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Some((0, -1)),
+                KeyCode::Down | KeyCode::Char('j') => Some((0, 1)),
+                KeyCode::Left | KeyCode::Char('h') => Some((-1, 0)),
+                KeyCode::Right | KeyCode::Char('l') => Some((1, 0)),
+                KeyCode::Char('y') => Some((-1, -1)),
+                KeyCode::Char('u') => Some((1, -1)),
+                KeyCode::Char('b') => Some((-1, 1)),
+                KeyCode::Char('n') => Some((1, 1)),
+                KeyCode::Esc => {
+                    self.pending_zap = None;
+                    game.add_message("Zap cancelled.".to_string(), MessageCategory::System);
+                    return Ok(false);
+                }
+                _ => None,
+            };
+
+            if let Some((dx, dy)) = direction {
+                self.pending_zap = None;
+                self.zap_wand(game, wand_id, dx, dy);
+            }
+            return Ok(false);
+        }
+
+        // Check for a prop to pull or throw
+        if let Some(verb) = self.pending_prop {
+            let direction: Option<(i32, i32)> = match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Some((0, -1)),
+                KeyCode::Down | KeyCode::Char('j') => Some((0, 1)),
+                KeyCode::Left | KeyCode::Char('h') => Some((-1, 0)),
+                KeyCode::Right | KeyCode::Char('l') => Some((1, 0)),
+                KeyCode::Char('y') => Some((-1, -1)),
+                KeyCode::Char('u') => Some((1, -1)),
+                KeyCode::Char('b') => Some((-1, 1)),
+                KeyCode::Char('n') => Some((1, 1)),
+                KeyCode::Esc => {
+                    self.pending_prop = None;
+                    game.add_message(format!("{} cancelled.", verb.name()), MessageCategory::System);
+                    return Ok(false);
+                }
+                _ => None,
+            };
+
+            if let Some(direction) = direction {
+                self.pending_prop = None;
+                let acted = match verb {
+                    PropVerb::Pull => game.pull_prop(direction),
+                    PropVerb::Throw => game.throw_prop(direction),
+                };
+                if acted {
+                    if let Some(pos) = game.player_position() {
+                        self.camera = pos;
+                    }
+                    game.run_ai_tick();
+                }
+            }
+            return Ok(false);
+        }
+
+        // Check for someone to pickpocket
+        if self.pending_steal {
+            let direction: Option<(i32, i32)> = match key.code {
+                KeyCode::Up | KeyCode::Char('k') => Some((0, -1)),
+                KeyCode::Down | KeyCode::Char('j') => Some((0, 1)),
+                KeyCode::Left | KeyCode::Char('h') => Some((-1, 0)),
+                KeyCode::Right | KeyCode::Char('l') => Some((1, 0)),
+                KeyCode::Char('y') => Some((-1, -1)),
+                KeyCode::Char('u') => Some((1, -1)),
+                KeyCode::Char('b') => Some((-1, 1)),
+                KeyCode::Char('n') => Some((1, 1)),
+                KeyCode::Esc => {
+                    self.pending_steal = false;
+                    game.add_message("Steal cancelled.".to_string(), MessageCategory::System);
+                    return Ok(false);
+                }
+                _ => None,
+            };
+
+            if let Some(direction) = direction {
+                self.pending_steal = false;
+                if game.steal(direction) {
+                    game.run_ai_tick();
+                }
+            }
+            return Ok(false);
+        }
+
         match key.code {
             // Movement
             KeyCode::Up | KeyCode::Char('k') => self.try_move(game, 0, -1),
@@ -305,16 +905,25 @@ impl App {
             KeyCode::Char('b') => self.try_move(game, -1, 1),
             KeyCode::Char('n') => self.try_move(game, 1, 1),
 
-            // Wait/Rest - skip turn, small HP and stamina regen
+            // Wait - skip turn, small HP and stamina regen
             KeyCode::Char('.') | KeyCode::Char(' ') => {
-                // Small HP regen when resting (1 HP per rest)
-                game.heal_player(1);
-                // Stamina regenerates faster when resting (5 SP per rest)
-                game.restore_stamina(5);
-                // Mana also gets a small boost when resting (2 MP per rest)
-                game.restore_mana(2);
-                // Enemies still get their turn
-                game.run_ai_tick();
+                game.wait_turn();
+            }
+            // Rest until recovered or interrupted
+            KeyCode::Char('R') => {
+                game.start_rest();
+            }
+            // Bind wounds over a few turns
+            KeyCode::Char('B') => {
+                game.start_bandage();
+            }
+            // Pick an adjacent lock over a few turns
+            KeyCode::Char('L') => {
+                game.start_lockpick();
+            }
+            // Search nearby walls for secret doors
+            KeyCode::Char('S') => {
+                game.search();
             }
 
             // Interact with stairs
@@ -339,6 +948,7 @@ impl App {
                 game.set_state(GameState::Playing(PlayingState::Inventory));
             }
             KeyCode::Char('c') => {
+                self.stat_info = None;
                 game.set_state(GameState::Playing(PlayingState::Character));
             }
             KeyCode::Char('m') => {
@@ -347,6 +957,9 @@ impl App {
             KeyCode::Char('?') => {
                 game.set_state(GameState::Playing(PlayingState::Help));
             }
+            KeyCode::Char('D') => {
+                game.set_state(GameState::Playing(PlayingState::Discoveries));
+            }
             KeyCode::Esc => {
                 game.set_state(GameState::Paused);
             }
@@ -358,6 +971,88 @@ impl App {
             KeyCode::Char('g') => {
                 self.pickup_items(game);
             }
+            // Toggle sprinting
+            KeyCode::Char('s') => {
+                game.toggle_sprint();
+            }
+            // Close adjacent doors
+            KeyCode::Char('x') => {
+                let closed = game.close_adjacent_doors();
+                if closed > 0 {
+                    game.add_message("You shut the door.".to_string(), MessageCategory::System);
+                    game.run_ai_tick();
+                } else {
+                    game.add_message("There's no open door next to you.".to_string(), MessageCategory::System);
+                }
+            }
+            // Search every action by name
+            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.palette = Some(Palette { wizard: self.wizard, ..Palette::default() });
+            }
+            // Peek through adjacent shut doors
+            KeyCode::Char('p') => {
+                if game.peek_through_doors() > 0 {
+                    game.add_message("You peer through a crack in the door...".to_string(), MessageCategory::System);
+                    game.run_ai_tick();
+                } else {
+                    game.add_message("There's no closed door next to you.".to_string(), MessageCategory::System);
+                }
+            }
+            // Disengage - step away without provoking opportunity attacks
+            KeyCode::Char('d') => {
+                self.pending_disengage = true;
+                game.add_message(
+                    format!("Disengage: choose a direction ({} SP, Esc to cancel)", crate::game::DISENGAGE_STAMINA_COST),
+                    MessageCategory::System,
+                );
+            }
+            // Prepare an attack or skill to fire when an enemy comes into reach
+            KeyCode::Char('w') => {
+                self.pending_prepare = true;
+                game.add_message(
+                    "Prepare: [w] attack or [1-5] skill (any other key cancels)".to_string(),
+                    MessageCategory::System,
+                );
+            }
+            // Drag a prop after you, or hurl a light one
+            KeyCode::Char('P') => {
+                self.pending_prop = Some(PropVerb::Pull);
+                game.add_message("Pull: choose the prop's direction (Esc to cancel)".to_string(), MessageCategory::System);
+            }
+            KeyCode::Char('t') => {
+                self.pending_prop = Some(PropVerb::Throw);
+                game.add_message("Throw: choose the prop's direction (Esc to cancel)".to_string(), MessageCategory::System);
+            }
+            // Pick the pockets of an NPC or a sleeping enemy
+            KeyCode::Char('F') => {
+                self.pending_steal = true;
+                game.add_message("Steal: choose who to rob (Esc to cancel)".to_string(), MessageCategory::System);
+            }
+            // Zap the first wand that still has charges
+            KeyCode::Char('z') => {
+                let wand = game.player()
+                    .and_then(|p| game.world().get::<&crate::ecs::InventoryComponent>(p).ok()
+                        .and_then(|inv| inv.inventory.first_charged_wand().map(|w| (w.id, w.name.clone()))));
+                match wand {
+                    Some((id, name)) => self.aim_wand(game, id, &name),
+                    None => game.add_message("You have no charged wand to zap.".to_string(), MessageCategory::Warning),
+                }
+            }
+            // Fold the side panels or the message log away
+            KeyCode::Char('[') => {
+                self.collapsed.side = !self.collapsed.side;
+            }
+            KeyCode::Char(']') => {
+                self.collapsed.log = !self.collapsed.log;
+            }
+            KeyCode::Char('Z') => {
+                game.update_settings(|s| s.zen_mode = !s.zen_mode);
+            }
+            // Look around the map with a cursor
+            KeyCode::Char(';') => {
+                self.look_cursor = Some(self.camera);
+                game.add_message("Look: move the cursor to examine (any other key to stop)".to_string(), MessageCategory::System);
+            }
             // Interact with tile (shrines, etc.)
             KeyCode::Char('e') | KeyCode::Enter => {
                 self.interact_with_tile(game);
@@ -368,13 +1063,99 @@ impl App {
             KeyCode::Char('3') => self.use_skill(game, 2),
             KeyCode::Char('4') => self.use_skill(game, 3),
             KeyCode::Char('5') => self.use_skill(game, 4),
+            // Quick-use belt (6-9)
+            KeyCode::Char(c @ '6'..='9') => self.use_belt(game, c as usize - '6' as usize),
             _ => {}
         }
         Ok(false)
     }
 
+    fn handle_palette_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        let Some(palette) = self.palette.as_mut() else { return Ok(false) };
+        match key.code {
+            KeyCode::Esc => self.palette = None,
+            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => self.palette = None,
+            KeyCode::Up => palette.move_cursor(-1),
+            KeyCode::Down | KeyCode::Tab => palette.move_cursor(1),
+            KeyCode::Backspace => palette.backspace(),
+            KeyCode::Char(c) => palette.type_char(c),
+            KeyCode::Enter => {
+                let Some(command) = palette.selected() else { return Ok(false) };
+                self.palette = None;
+                return self.run_palette_action(command.action, game);
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Do what a palette command stands for
+    fn run_palette_action(&mut self, action: PaletteAction, game: &mut Game) -> Result<bool> {
+        match action {
+            PaletteAction::Key(code) => {
+                return self.handle_playing_input(KeyEvent::new(code, KeyModifiers::NONE), game, PlayingState::Exploring);
+            }
+            PaletteAction::SaveGame => game.set_state(GameState::SaveSlots { selected: 0 }),
+            PaletteAction::CycleEnemyIcons => game.update_settings(|s| s.enemy_overlays = s.enemy_overlays.next()),
+            PaletteAction::ToggleHealthBars => game.update_settings(|s| s.enemy_health_bars = !s.enemy_health_bars),
+            PaletteAction::ToggleSpeedrun => game.update_settings(|s| s.speedrun_mode = !s.speedrun_mode),
+            PaletteAction::ToggleHints => game.update_settings(|s| s.show_hints = !s.show_hints),
+            PaletteAction::ToggleAutoPickupConsumables => {
+                game.update_settings(|s| s.auto_pickup_consumables = !s.auto_pickup_consumables)
+            }
+            PaletteAction::CycleLootFilter => game.update_settings(|s| s.loot_filter_mode = s.loot_filter_mode.next()),
+            PaletteAction::ExportFloorPreview => {
+                let Some(map) = game.map() else { return Ok(false) };
+                match preview::export(map, game.seed(), &preview::preview_directory()) {
+                    Ok(path) => game.add_message(format!("Floor preview written to {}", path.display()), MessageCategory::System),
+                    Err(e) => game.report_error(e),
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Use the consumable on a belt slot without opening the inventory
+    fn use_belt(&mut self, game: &mut Game, slot: usize) {
+        use crate::ecs::InventoryComponent;
+
+        let player = match game.player() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let (name, found) = match game.world().get::<&InventoryComponent>(player) {
+            Ok(inv) => {
+                let name = inv.inventory.belt().get(slot).map(str::to_string);
+                let found = name.as_deref()
+                    .and_then(|n| inv.inventory.index_of_named(n))
+                    .and_then(|i| inv.inventory.get(i).cloned().map(|item| (i, item)));
+                (name, found)
+            }
+            Err(_) => return,
+        };
+
+        match (name, found) {
+            (None, _) => {
+                game.play_sound(SoundId::Error);
+                game.add_message(
+                    format!("Belt slot {} is empty - assign one from the inventory.", slot + 6),
+                    MessageCategory::Warning,
+                );
+            }
+            (Some(name), None) => {
+                let shown = game.discoveries().appearance(&name).unwrap_or(&name).to_string();
+                game.play_sound(SoundId::Error);
+                game.add_message(format!("You're out of {}!", shown), MessageCategory::Warning);
+            }
+            (Some(_), Some((index, item))) => {
+                Self::use_consumable(game, player, index, &item);
+            }
+        }
+    }
+
     fn pickup_items(&mut self, game: &mut Game) {
-        use crate::ecs::{GroundItem, InventoryComponent};
+        use crate::ecs::GroundItem;
 
         let player_pos = match game.player_position() {
             Some(pos) => pos,
@@ -396,193 +1177,133 @@ impl App {
             .collect();
 
         if items_in_range.is_empty() {
-            game.add_message("Nothing to pick up nearby.".to_string(), MessageCategory::System);
+            if !game.search_nearby_corpses() {
+                game.add_message("Nothing to pick up nearby.".to_string(), MessageCategory::System);
+            }
             return;
         }
 
-        // Get player entity
-        let player = match game.player() {
-            Some(p) => p,
-            None => return,
-        };
-
-        // Sort by distance (pick up items on same tile first)
-        let mut items_sorted = items_in_range;
-        items_sorted.sort_by_key(|(_, _, dist)| *dist);
-
-        // Try to add each item to inventory
-        for (entity, item, _) in items_sorted {
-            let item_name = item.name.clone();
-            let item_base_name = item.base_name.clone();
-            let item_rarity = item.rarity.name();
-            let added = {
-                if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                    inv.inventory.add_item(item)
-                } else {
-                    false
-                }
-            };
+        // A pile lets the player choose what to take
+        if items_in_range.len() > 1 {
+            let mut items_sorted = items_in_range;
+            items_sorted.sort_by_key(|(_, item, dist)| (*dist, std::cmp::Reverse(item.rarity.sort_value())));
+            self.pickup_choices = items_sorted.into_iter().map(|(e, _, _)| (e, false)).collect();
+            self.pickup_cursor = 0;
+            game.set_state(GameState::Playing(PlayingState::Pickup));
+            return;
+        }
 
-            if added {
-                game.play_sound(SoundId::ItemPickup);
-                game.add_message(
-                    format!("Picked up: {} [{}]", item_name, item_rarity),
-                    MessageCategory::Item
-                );
-                let _ = game.world_mut().despawn(entity);
-                game.record_item_found(&item_base_name);
-            } else {
-                game.play_sound(SoundId::InventoryFull);
-                game.add_message(
-                    format!("Inventory full! Cannot pick up {}", item_name),
-                    MessageCategory::Warning
-                );
-                break;
-            }
+        for (entity, item, _) in items_in_range {
+            self.take_or_warn(game, entity, item);
         }
 
         // Also try to open any nearby chests
         self.open_nearby_chests(game);
     }
 
-    fn open_nearby_chests(&mut self, game: &mut Game) {
-        use crate::ecs::{Chest, InventoryComponent, GroundItem, Renderable};
-        use crate::entities::{mark_chest_opened, generate_chest_loot};
+    /// Pick up a ground item, complaining if the pack is full
+    fn take_or_warn(&mut self, game: &mut Game, entity: hecs::Entity, item: crate::items::Item) -> bool {
+        let item_name = game.discoveries().disguise(&item).name.clone();
+        let taken = self.take_ground_item(game, entity, item);
+        if !taken {
+            game.play_sound(SoundId::InventoryFull);
+            game.add_message(
+                format!("Inventory full! Cannot pick up {}", item_name),
+                MessageCategory::Warning
+            );
+        }
+        taken
+    }
 
-        let player_pos = match game.player_position() {
-            Some(pos) => pos,
-            None => return,
-        };
+    /// Pick up whatever the auto-pickup rules allow on the player's tile
+    fn auto_pickup(&mut self, game: &mut Game) {
+        use crate::ecs::GroundItem;
 
-        // Find all chests within range (on tile or adjacent)
-        let chests_in_range: Vec<(hecs::Entity, crate::ecs::ChestRarity, Position)> = game.world()
-            .query::<(&Position, &Chest)>()
+        let settings = &game.profile().settings;
+        let items: Vec<(hecs::Entity, crate::items::Item)> = game.world()
+            .query::<(&Position, &GroundItem)>()
             .iter()
-            .filter_map(|(e, (pos, chest))| {
-                let dist = player_pos.chebyshev_distance(pos);
-                if dist <= 1 && !chest.opened {
-                    Some((e, chest.rarity, *pos))
-                } else {
-                    None
-                }
-            })
+            .filter(|(_, (pos, gi))| **pos == self.camera && settings.auto_picks_up(&gi.item))
+            .map(|(e, (_, gi))| (e, gi.item.clone()))
             .collect();
 
-        let player = match game.player() {
-            Some(p) => p,
-            None => return,
-        };
-
-        for (entity, rarity, chest_pos) in chests_in_range {
-            // Play chest open sound
-            game.play_sound(SoundId::ChestOpen);
-
-            // Generate loot based on chest rarity
-            let floor = game.floor();
-            let (items, gold) = {
-                let rng = game.rng();
-                generate_chest_loot(rarity, floor, rng)
-            };
-
-            // Add gold
-            if gold > 0 {
-                game.play_sound(SoundId::GoldPickup);
-                if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                    inv.inventory.add_gold(gold);
-                }
+        for (entity, item) in items {
+            let item_name = game.discoveries().disguise(&item).name.clone();
+            if !self.take_ground_item(game, entity, item) {
                 game.add_message(
-                    format!("Found {} gold in {:?} chest!", gold, rarity),
-                    MessageCategory::Item
+                    format!("No room to pick up {}.", item_name),
+                    MessageCategory::Warning
                 );
-                game.record_gold_collected(gold);
             }
+        }
+    }
 
-            // Spawn items on the ground near the chest
-            for item in items {
-                let item_name = item.name.clone();
-                let item_rarity = item.rarity;
-                game.world_mut().spawn((
-                    chest_pos,
-                    GroundItem { item: item.clone() },
-                    Renderable::new(item.glyph, item_rarity.color()).with_order(80),
-                ));
-                game.add_message(
-                    format!("Found: {} [{}]", item_name, item_rarity.name()),
-                    MessageCategory::Item
-                );
-            }
+    /// Move an item from the ground into the pack, returning false if it doesn't fit
+    fn take_ground_item(&mut self, game: &mut Game, entity: hecs::Entity, item: crate::items::Item) -> bool {
+        use crate::ecs::InventoryComponent;
 
-            // Mark chest as opened
-            mark_chest_opened(game.world_mut(), entity);
+        let Some(player) = game.player() else { return false };
+        let item_name = game.discoveries().disguise(&item).name.clone();
+        let item_base_name = item.base_name.clone();
+        let item_rarity = item.rarity.name();
+        let added = game.world_mut()
+            .get::<&mut InventoryComponent>(player)
+            .is_ok_and(|mut inv| inv.inventory.add_item(item));
 
+        if added {
+            game.play_sound(SoundId::ItemPickup);
             game.add_message(
-                format!("Opened a {:?} chest!", rarity),
-                MessageCategory::System
+                format!("Picked up: {} [{}]", item_name, item_rarity),
+                MessageCategory::Item
             );
+            let _ = game.world_mut().despawn(entity);
+            game.record_item_found(&item_base_name);
         }
+        added
     }
 
-    /// Open a specific chest (called when walking into it)
-    fn open_chest(&mut self, game: &mut Game, chest_entity: hecs::Entity, rarity: crate::ecs::ChestRarity, chest_pos: Position) {
-        use crate::ecs::{InventoryComponent, GroundItem, Renderable};
-        use crate::entities::{mark_chest_opened, generate_chest_loot};
-        use crate::game::MessageCategory;
-
-        // Play chest open sound
-        game.play_sound(SoundId::ChestOpen);
+    fn open_nearby_chests(&mut self, game: &mut Game) {
+        use crate::ecs::Chest;
 
-        let player = match game.player() {
-            Some(p) => p,
+        let player_pos = match game.player_position() {
+            Some(pos) => pos,
             None => return,
         };
 
-        // Generate loot based on chest rarity
-        let floor = game.floor();
-        let (items, gold) = {
-            let rng = game.rng();
-            generate_chest_loot(rarity, floor, rng)
-        };
+        // Find all chests within range (on tile or adjacent)
+        let chests_in_range: Vec<hecs::Entity> = game.world()
+            .query::<(&Position, &Chest)>()
+            .iter()
+            .filter(|(_, (pos, chest))| player_pos.chebyshev_distance(pos) <= 1 && !chest.opened)
+            .map(|(e, _)| e)
+            .collect();
 
-        // Add gold
-        if gold > 0 {
-            game.play_sound(SoundId::GoldPickup);
-            if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                inv.inventory.add_gold(gold);
+        for entity in chests_in_range {
+            self.interact_chest(game, entity);
+            // A riddle or a nasty surprise puts a stop to the rest
+            if self.riddle_chest.is_some() || !matches!(game.state(), GameState::Playing(_)) {
+                break;
             }
-            game.add_message(
-                format!("Found {} gold in {:?} chest!", gold, rarity),
-                MessageCategory::Item
-            );
-            game.record_gold_collected(gold);
         }
+    }
 
-        // Spawn items on the ground at the chest position
-        for item in items {
-            let item_name = item.name.clone();
-            let item_rarity = item.rarity;
-            game.world_mut().spawn((
-                chest_pos,
-                GroundItem { item: item.clone() },
-                Renderable::new(item.glyph, item_rarity.color()).with_order(80),
-            ));
-            game.add_message(
-                format!("Found: {} [{}]", item_name, item_rarity.name()),
-                MessageCategory::Item
-            );
+    /// Reach for a chest: open it if nothing stands in the way, or put up
+    /// its riddle
+    fn interact_chest(&mut self, game: &mut Game, chest_entity: hecs::Entity) {
+        if let crate::entities::ChestApproach::Riddle(_) = game.reach_for_chest(chest_entity) {
+            self.riddle_chest = Some(chest_entity);
         }
-
-        // Mark chest as opened
-        mark_chest_opened(game.world_mut(), chest_entity);
-
-        game.add_message(
-            format!("Opened a {:?} chest!", rarity),
-            MessageCategory::System
-        );
     }
 
     fn use_skill(&mut self, game: &mut Game, slot: usize) {
-        use crate::ecs::{SkillsComponent, Health, Mana, Stamina, Enemy, Stats, EquipmentComponent, StatusEffects, StatusEffect, StatusEffectType};
-        use crate::progression::skills::{SkillCost, TargetType, SkillEffect, ScalingStat, StatusType};
+        self.cast_skill(game, slot, true);
+    }
+
+    /// Use the skill in a slot. A prepared skill springing during the enemy
+    /// turn doesn't take a turn of its own.
+    fn cast_skill(&mut self, game: &mut Game, slot: usize, take_turn: bool) {
+        use crate::ecs::{SkillsComponent, Health, Mana, Stamina, Enemy, Stats, StatusEffects, StatusEffect, StatusEffectType};
+        use crate::progression::skills::{SkillCost, TargetType, SkillEffect, ScalingStat, StatusType, BuffType};
 
         let player = match game.player() {
             Some(p) => p,
@@ -631,11 +1352,22 @@ impl App {
             None => return,
         };
 
-        let skill_name = skill.name.clone();
+        let skill_name = skill.display_name();
         let skill_effect = skill.effect.clone();
         let skill_cost = skill.cost;
         let skill_target = skill.target;
 
+        // Occult rites need a body to work on
+        if skill_effect.needs_corpse() {
+            let near_corpse = game.player_position()
+                .and_then(|pos| crate::entities::nearest_corpse(game.world(), pos, crate::entities::CORPSE_SKILL_RANGE))
+                .is_some();
+            if !near_corpse {
+                game.add_message(format!("{} needs a corpse nearby.", skill_name), MessageCategory::Warning);
+                return;
+            }
+        }
+
         // Get player stats for damage scaling
         let player_stats = game.world()
             .get::<&Stats>(player)
@@ -661,6 +1393,17 @@ impl App {
         if let Ok(mut sc) = game.world_mut().get::<&mut SkillsComponent>(player) {
             sc.skills.use_skill(slot);
         }
+        game.tutorial_event(crate::data::TutorialTrigger::SkillUsed);
+
+        // A concussed head can lose the thread mid-cast
+        let fizzle_chance = game.skill_fizzle_chance();
+        if fizzle_chance > 0.0 && game.rng().gen_bool(fizzle_chance) {
+            game.add_message("Your head swims - the skill fizzles!", MessageCategory::Warning);
+            if take_turn {
+                game.run_ai_tick();
+            }
+            return;
+        }
 
         // Helper to convert skill StatusType to ECS StatusEffectType
         fn convert_status(status: StatusType) -> StatusEffectType {
@@ -669,7 +1412,7 @@ impl App {
                 StatusType::Burn => StatusEffectType::Burn,
                 StatusType::Bleed => StatusEffectType::Bleed,
                 StatusType::Slow => StatusEffectType::Slow,
-                StatusType::Stun => StatusEffectType::Slow, // Map stun to slow for now
+                StatusType::Stun => StatusEffectType::Stun,
                 StatusType::Weakness => StatusEffectType::Weakness,
             }
         }
@@ -733,17 +1476,7 @@ impl App {
                         _ => 0,
                     };
                     let heal_amount = base + bonus;
-                    // Get equipment HP bonus for effective max
-                    let eq_hp = game.world()
-                        .get::<&EquipmentComponent>(player)
-                        .map(|eq| eq.equipment.hp_bonus())
-                        .unwrap_or(0);
-                    if let Ok(mut hp) = game.world_mut().get::<&mut Health>(player) {
-                        let effective_max = hp.max + eq_hp;
-                        let actual = heal_amount.min(effective_max - hp.current);
-                        hp.current += actual;
-                        total_heal += actual;
-                    }
+                    total_heal += game.heal_player(heal_amount);
                 }
                 SkillEffect::Damage { base, scaling_stat } => {
                     let bonus = match scaling_stat {
@@ -775,6 +1508,15 @@ impl App {
                         // Check if status applies (based on chance)
                         let roll: f32 = rand::random();
                         if roll < chance {
+                            // Resistant targets shrug it off
+                            let immune = game.world().get::<&crate::combat::Resistances>(*target)
+                                .is_ok_and(|r| r.is_immune(effect_type));
+                            if immune {
+                                let name = game.world().get::<&crate::ecs::Name>(*target).map(|n| n.0.clone()).unwrap_or_default();
+                                game.add_message(format!("The {} is immune to {}.", name, effect_type.name().to_lowercase()), MessageCategory::Combat);
+                                continue;
+                            }
+
                             // Check if entity has StatusEffects component
                             let has_component = game.world().get::<&StatusEffects>(*target).is_ok();
 
@@ -798,13 +1540,27 @@ impl App {
                                     }],
                                 });
                             }
+                            if effect_type == StatusEffectType::Burn {
+                                if let Ok(pos) = game.world().get::<&Position>(*target).map(|p| *p) {
+                                    game.leave_decal(pos, crate::world::Decal::Scorch);
+                                }
+                            }
                             if !statuses_applied.contains(&status_name) {
                                 statuses_applied.push(status_name.clone());
                             }
                         }
                     }
                 }
-                SkillEffect::BuffSelf { buff: _, duration } => {
+                SkillEffect::BuffSelf { buff, duration } => {
+                    // Lasting effects become auras the aura system keeps up
+                    let aura = match buff {
+                        BuffType::Aura(aura) => Some(aura),
+                        BuffType::Regeneration(hp) => Some(crate::combat::Aura::Regeneration(hp)),
+                        _ => None,
+                    };
+                    if let Some(aura) = aura {
+                        game.grant_aura(player, aura, duration);
+                    }
                     game.add_message(format!("{} grants you a buff for {} turns!", skill_name, duration), MessageCategory::Combat);
                 }
                 SkillEffect::Movement { range } => {
@@ -813,39 +1569,84 @@ impl App {
                     game.add_message(format!("{} - choose direction to teleport (arrow keys)", skill_name), MessageCategory::System);
                     is_movement_skill = true;
                 }
+                SkillEffect::Knockback { distance } => {
+                    for target in &targets {
+                        let Some(pos) = game.world().get::<&Position>(*target).ok().map(|p| *p) else { continue };
+                        let direction = crate::combat::direction_toward(player_pos, pos);
+                        game.shove(*target, direction, distance);
+                    }
+                }
+                SkillEffect::Pull => {
+                    for target in &targets {
+                        let Some(pos) = game.world().get::<&Position>(*target).ok().map(|p| *p) else { continue };
+                        let direction = crate::combat::direction_toward(pos, player_pos);
+                        game.shove(*target, direction, pos.chebyshev_distance(&player_pos) - 1);
+                    }
+                }
+                SkillEffect::FreezeLava { radius } => {
+                    let mut frozen = 0;
+                    if let Some(map) = game.map_mut() {
+                        for y in (player_pos.y - radius)..=(player_pos.y + radius) {
+                            for x in (player_pos.x - radius)..=(player_pos.x + radius) {
+                                if map.get_tile(x, y).is_some_and(|t| t.tile_type == crate::world::TileType::Lava) {
+                                    map.set_tile(x, y, crate::world::TileType::Floor);
+                                    frozen += 1;
+                                }
+                            }
+                        }
+                    }
+                    if frozen > 0 {
+                        game.add_message(format!("{} tile(s) of lava freeze solid!", frozen), MessageCategory::Combat);
+                    }
+                }
+                SkillEffect::ConsumeCorpse { heal } => {
+                    let Some(corpse) = crate::entities::nearest_corpse(game.world(), player_pos, crate::entities::CORPSE_SKILL_RANGE) else { continue };
+                    let name = game.world().get::<&crate::entities::Corpse>(corpse).map(|c| c.name.clone()).unwrap_or_default();
+                    let _ = game.world_mut().despawn(corpse);
+                    let amount = heal + player_stats.intelligence / 2;
+                    total_heal += game.heal_player(amount);
+                    game.restore_mana(amount / 2);
+                    game.add_message(format!("You devour what's left of the {}.", name), MessageCategory::Combat);
+                }
+                SkillEffect::ExplodeCorpse { base, radius } => {
+                    let Some(corpse) = crate::entities::nearest_corpse(game.world(), player_pos, crate::entities::CORPSE_SKILL_RANGE) else { continue };
+                    let Ok(blast) = game.world().get::<&Position>(corpse).map(|p| *p) else { continue };
+                    let _ = game.world_mut().despawn(corpse);
+                    let damage = base + player_stats.intelligence / 2;
+                    let caught: Vec<hecs::Entity> = game.world()
+                        .query::<(&Position, &Enemy, &Health)>()
+                        .iter()
+                        .filter(|(_, (pos, _, _))| pos.chebyshev_distance(&blast) <= radius)
+                        .map(|(e, _)| e)
+                        .collect();
+                    for target in caught {
+                        if let Ok(mut hp) = game.world_mut().get::<&mut Health>(target) {
+                            hp.current -= damage;
+                            total_damage += damage;
+                            hit_count += 1;
+                            if hp.current <= 0 && !killed.contains(&target) {
+                                killed.push(target);
+                            }
+                        }
+                    }
+                    game.add_message("The corpse bursts in a spray of bone and bile!", MessageCategory::Combat);
+                }
                 SkillEffect::Multi(_) => {
                     // Nested Multi shouldn't happen, but ignore if it does
                 }
             }
         }
 
-        // Handle deaths
-        let mut total_xp = 0u32;
-        for dead in &killed {
-            // Get XP reward
-            let xp = game.world()
-                .get::<&crate::ecs::XpReward>(*dead)
-                .map(|x| x.0)
-                .unwrap_or(15);
-            total_xp += xp;
-
-            // Despawn the dead enemy
-            let _ = game.world_mut().despawn(*dead);
+        // Walls and hazards may have finished off what the blows didn't
+        for target in &targets {
+            let dead = game.world().get::<&Health>(*target).is_ok_and(|hp| hp.current <= 0);
+            if dead && !killed.contains(target) {
+                killed.push(*target);
+            }
         }
 
-        // Grant XP if any kills
-        if total_xp > 0 {
-            let level_up_info = if let Some(player_entity) = game.player() {
-                if let Ok(mut xp) = game.world_mut().get::<&mut crate::ecs::Experience>(player_entity) {
-                    let leveled = xp.add_xp(total_xp);
-                    if leveled { Some(xp.level) } else { None }
-                } else { None }
-            } else { None };
-
-            if let Some(new_level) = level_up_info {
-                game.add_message(format!("Level up! You are now level {}!", new_level), MessageCategory::System);
-            }
-            game.add_message(format!("+{} XP", total_xp), MessageCategory::System);
+        for dead in &killed {
+            game.queue_death(*dead, false, true);
         }
 
         // Build result message
@@ -878,126 +1679,109 @@ impl App {
             game.add_message(format!("{}: {}", skill_name, msg_parts.join(", ")), MessageCategory::Combat);
         }
 
-        // Tick cooldowns for self-targeted/non-attack skills
-        // (Attack skills tick in attack_enemy instead)
-        let is_self_target = matches!(skill_target, TargetType::Self_);
-        if is_self_target {
-            if let Ok(mut sc) = game.world_mut().get::<&mut SkillsComponent>(player) {
-                sc.skills.tick_cooldowns();
-            }
-        }
-
         // Enemies take their turn after skill use
-        game.run_ai_tick();
+        if take_turn {
+            game.run_ai_tick();
+        }
     }
 
-    fn interact_with_tile(&mut self, game: &mut Game) {
-        use crate::world::TileType;
-
-        let player_pos = match game.player_position() {
-            Some(pos) => pos,
-            None => return,
-        };
-
-        let tile_type = match game.map() {
-            Some(map) => map.get_tile(player_pos.x, player_pos.y).map(|t| t.tile_type),
-            None => return,
-        };
-
-        match tile_type {
-            Some(TileType::ShrineSkill) => {
-                if game.is_shrine_used(player_pos) {
-                    game.add_message("This shrine's power has already been used.".to_string(), MessageCategory::Warning);
-                } else {
-                    game.play_sound(SoundId::ShrineApproach);
-                    game.add_message("You approach the Skill Shrine. It pulses with arcane energy.".to_string(), MessageCategory::System);
-                    // Generate random skills based on floor
-                    let floor = game.floor();
-                    self.shrine_skills = crate::progression::generate_shrine_skills(floor, 3, game.rng());
-                    self.shrine_skill_cursor = 0;
-                    game.set_state(GameState::Playing(PlayingState::Shrine { shrine_type: ShrineType::Skill }));
-                }
-            }
-            Some(TileType::ShrineEnchant) => {
-                if game.is_shrine_used(player_pos) {
-                    game.add_message("This shrine's power has already been used.".to_string(), MessageCategory::Warning);
-                } else {
-                    game.play_sound(SoundId::ShrineApproach);
-                    game.add_message("You approach the Enchanting Shrine. Select equipment to enchant.".to_string(), MessageCategory::System);
-                    // Very rare chance (5%) for +1 max enchantment slot option
-                    self.enchant_upgrade_available = game.rng().gen_bool(0.05);
-                    self.enchant_affix_cursor = 0;
-                    self.enchant_swap_mode = false;
-                    self.enchant_swap_cursor = 0;
-                    self.enchant_selected_slot = None;  // Start in equipment selection mode
-                    self.enchant_equipment_cursor = 0;
-                    game.set_state(GameState::Playing(PlayingState::Shrine { shrine_type: ShrineType::Enchanting }));
-                }
-            }
-            Some(TileType::ShrineRest) => {
-                if game.is_shrine_used(player_pos) {
-                    game.add_message("This shrine's power has already been used.".to_string(), MessageCategory::Warning);
-                } else {
-                    // Rest shrine - heal fully and restore charges
-                    game.play_sound(SoundId::ShrineUse);
-                    game.add_message("You rest at the shrine. Your wounds heal and your abilities are restored.".to_string(), MessageCategory::System);
-                    if let Some(player) = game.player() {
-                        // Get equipment bonuses for effective max
-                        let (eq_hp, eq_mp) = game.world()
-                            .get::<&crate::ecs::EquipmentComponent>(player)
-                            .map(|eq| (eq.equipment.hp_bonus(), eq.equipment.mp_bonus()))
-                            .unwrap_or((0, 0));
-                        // Heal to full (including equipment bonus)
-                        if let Ok(mut hp) = game.world_mut().get::<&mut crate::ecs::Health>(player) {
-                            hp.current = hp.max + eq_hp;
-                        }
-                        // Restore mana (including equipment bonus)
-                        if let Ok(mut mp) = game.world_mut().get::<&mut crate::ecs::Mana>(player) {
-                            mp.current = mp.max + eq_mp;
-                        }
-                        // Restore stamina
-                        if let Ok(mut sp) = game.world_mut().get::<&mut crate::ecs::Stamina>(player) {
-                            sp.current = sp.max;
-                        }
-                        // Restore skill charges
-                        if let Ok(mut skills) = game.world_mut().get::<&mut crate::ecs::SkillsComponent>(player) {
-                            skills.skills.restore_charges();
-                        }
-                    }
-                    // Mark shrine as used
-                    game.mark_shrine_used(player_pos);
-                }
-            }
-            Some(TileType::ShrineCorruption) => {
-                // Corruption shrine can be used multiple times (risk/reward)
-                game.play_sound(SoundId::ShrineApproach);
-                game.add_message("You approach the Corruption Shrine. Dark power calls to you...".to_string(), MessageCategory::Combat);
-                game.set_state(GameState::Playing(PlayingState::Shrine { shrine_type: ShrineType::Corruption }));
-            }
-            Some(TileType::StairsDown) => {
-                game.play_sound(SoundId::Descend);
-                game.add_message("You descend deeper into the darkness...".to_string(), MessageCategory::System);
-                game.descend();
-                game.play_sound(SoundId::NewFloor);
+    fn interact_with_tile(&mut self, game: &mut Game) {
+        let Some(player_pos) = game.player_position() else { return };
+        let outcome = game.interact(player_pos);
+        self.follow_interaction(game, player_pos, outcome);
+    }
+
+    /// Follow up on an interaction: ready the screen it opened, let the turn
+    /// pass, or step into the space an opened chest leaves
+    fn follow_interaction(&mut self, game: &mut Game, pos: Position, outcome: Interaction) {
+        use crate::entities::ChestApproach;
+
+        match outcome {
+            Interaction::Nothing | Interaction::Free => {}
+            Interaction::Acted => game.run_ai_tick(),
+            Interaction::Opened(PlayingState::Shrine { shrine_type }) => self.ready_shrine(game, shrine_type),
+            Interaction::Opened(PlayingState::Dice { .. }) => {
+                self.dice_stake = 0;
+                self.dice_last = None;
+            }
+            Interaction::Opened(_) => {}
+            Interaction::Descended => {
                 if let Some(new_map) = game.map() {
                     self.camera = new_map.start_pos;
                 }
             }
-            _ => {
-                game.add_message("Nothing to interact with here.".to_string(), MessageCategory::System);
+            Interaction::Chest(chest, approach) => match approach {
+                ChestApproach::Open => {
+                    self.camera = pos;
+                    game.set_player_position(pos);
+                    let radius = game.sight_radius();
+                    if let Some(map) = game.map_mut() {
+                        crate::world::compute_fov(map, self.camera, radius);
+                    }
+                    game.run_ai_tick();
+                }
+                // The mimic gets the first bite
+                ChestApproach::Ambush => game.run_ai_tick(),
+                ChestApproach::Riddle(_) => self.riddle_chest = Some(chest),
+                ChestApproach::Shut => {}
+            },
+        }
+    }
+
+    /// Set up a shrine's screen as the player steps up to it
+    fn ready_shrine(&mut self, game: &mut Game, shrine_type: ShrineType) {
+        match shrine_type {
+            ShrineType::Skill => {
+                // Generate random skills based on floor
+                let floor = game.floor();
+                let known = game.player()
+                    .and_then(|p| game.world().get::<&crate::ecs::SkillsComponent>(p).ok().map(|sc| sc.skills.clone()))
+                    .unwrap_or_default();
+                self.shrine_skills = crate::progression::generate_shrine_skills(floor, 3, &known, game.rng());
+                self.shrine_skill_cursor = 0;
+            }
+            ShrineType::Enchanting => {
+                // Very rare chance (5%) for +1 max enchantment slot option
+                self.enchant_upgrade_available = game.rng().gen_bool(0.05);
+                self.enchant_affix_cursor = 0;
+                self.enchant_swap_mode = false;
+                self.enchant_swap_cursor = 0;
+                self.enchant_selected_slot = None;  // Start in equipment selection mode
+                self.enchant_equipment_cursor = 0;
             }
+            ShrineType::Gambling => self.shrine_last_outcome = None,
+            ShrineType::Sacrifice | ShrineType::Transmutation | ShrineType::Altar(_) => self.shrine_item_cursor = 0,
+            ShrineType::Rest | ShrineType::Corruption => {}
         }
     }
 
     fn try_move(&mut self, game: &mut Game, dx: i32, dy: i32) {
-        use crate::entities::{NpcMarker, NpcComponent, NpcType};
-        use crate::ecs::Chest;
-
         let new_x = self.camera.x + dx;
         let new_y = self.camera.y + dy;
 
         // Check walkability first (immutable borrow)
-        let can_walk = game.map().map(|m| m.is_walkable(new_x, new_y)).unwrap_or(false);
+        let hazard = game.map()
+            .and_then(|m| m.get_tile(new_x, new_y))
+            .map(|t| t.tile_type)
+            .filter(|t| t.is_hazard());
+        let can_walk = hazard.is_some() || game.map().map(|m| m.is_walkable(new_x, new_y)).unwrap_or(false);
+
+        // Walking into a door, a lever, a chest or someone to talk to uses
+        // it: doors are forced, levers thrown, chests opened
+        let target = Position::new(new_x, new_y);
+        if game.interactable_at(target).is_some_and(|(_, interactable)| !interactable.underfoot()) {
+            let outcome = game.interact(target);
+            self.follow_interaction(game, target, outcome);
+            return;
+        }
+
+        // Walking into a toll gate pays its fee, if the player has it
+        if game.is_toll_gate(Position::new(new_x, new_y)) {
+            if game.pay_toll(Position::new(new_x, new_y)) {
+                game.run_ai_tick();
+            }
+            return;
+        }
 
         if !can_walk {
             return;
@@ -1005,101 +1789,276 @@ impl App {
 
         let new_pos = Position::new(new_x, new_y);
 
-        // Check for NPC interaction (before enemy check)
-        let npc_at_pos = {
-            let mut found = None;
-            for (entity, (pos, _, npc)) in game.world().query::<(&Position, &NpcMarker, &NpcComponent)>().iter() {
-                if pos.x == new_pos.x && pos.y == new_pos.y {
-                    found = Some((entity, npc.npc_type));
-                    break;
-                }
-            }
-            found
-        };
-
-        if let Some((npc_entity, npc_type)) = npc_at_pos {
-            // Interact with NPC
-            match npc_type {
-                NpcType::Merchant => {
-                    // Open shop
-                    game.add_message(
-                        format!("{}: \"{}\"", npc_type.name(), npc_type.greeting()),
-                        crate::game::MessageCategory::System,
-                    );
-                    game.set_state(GameState::Playing(PlayingState::Shop { npc_entity }));
-                }
-                NpcType::Healer => {
-                    // Heal the player
-                    game.heal_player(50);
-                    game.add_message(
-                        format!("{}: \"{}\" (Healed 50 HP)", npc_type.name(), npc_type.greeting()),
-                        crate::game::MessageCategory::System,
-                    );
-                }
-                _ => {
-                    // Generic greeting
-                    game.add_message(
-                        format!("{}: \"{}\"", npc_type.name(), npc_type.greeting()),
-                        crate::game::MessageCategory::Lore,
-                    );
-                }
-            }
+        // Walk into a cage to free the prisoner inside
+        if let Some(prisoner) = crate::entities::get_prisoner_at(game.world(), new_pos) {
+            game.free_prisoner(prisoner);
+            game.run_ai_tick();
             return;
         }
 
-        // Check for chest interaction (walk into chest to open it)
-        let chest_at_pos = {
-            let mut found = None;
-            for (entity, (pos, chest)) in game.world().query::<(&Position, &Chest)>().iter() {
-                if pos.x == new_pos.x && pos.y == new_pos.y && !chest.opened {
-                    found = Some((entity, chest.rarity));
-                    break;
-                }
+        // Walk into a follower to swap places with them
+        if let Some(follower) = crate::entities::get_follower_at(game.world(), new_pos) {
+            if !self.pending_disengage {
+                game.provoke_opportunity_attacks(self.camera, new_pos);
+            }
+            if let Ok(mut pos) = game.world_mut().get::<&mut Position>(follower) {
+                *pos = self.camera;
             }
-            found
-        };
-
-        if let Some((chest_entity, rarity)) = chest_at_pos {
-            // Open the chest
-            self.open_chest(game, chest_entity, rarity, new_pos);
-            // Move onto the chest tile after opening
             self.camera = new_pos;
             game.set_player_position(new_pos);
+            let radius = game.sight_radius();
             if let Some(map) = game.map_mut() {
-                crate::world::compute_fov(map, self.camera, 8);
+                crate::world::compute_fov(map, self.camera, radius);
             }
             game.run_ai_tick();
             return;
         }
 
+        // Walk into a prop to push it, following it into the gap it leaves
+        if let Some((prop, _)) = crate::entities::get_prop_at(game.world(), new_pos) {
+            if game.push_prop(prop, (dx, dy)) {
+                self.camera = new_pos;
+                game.set_player_position(new_pos);
+                let radius = game.sight_radius();
+                if let Some(map) = game.map_mut() {
+                    crate::world::compute_fov(map, self.camera, radius);
+                }
+                game.run_ai_tick();
+            }
+            return;
+        }
+
+        let weapon = game.player()
+            .and_then(|p| game.world().get::<&crate::ecs::EquipmentComponent>(p).ok())
+            .and_then(|eq| eq.equipment.weapon_type());
+
+        // A whip lashes out at an enemy two tiles away instead of stepping closer
+        if weapon.is_some_and(|w| w.reach() >= 2) && game.get_blocking_entity_at(new_pos).is_none() {
+            let lash_pos = Position::new(new_x + dx, new_y + dy);
+            let visible = game.map()
+                .and_then(|m| m.get_tile(lash_pos.x, lash_pos.y))
+                .is_some_and(|t| t.visible);
+            if let Some(target_entity) = game.enemy_at(lash_pos).filter(|_| visible) {
+                game.add_message("Your whip cracks across the gap!".to_string(), MessageCategory::Combat);
+                self.attack_enemy(game, target_entity, false);
+                game.run_ai_tick();
+                return;
+            }
+        }
+
         // Check for blocking entity (enemy collision = attack!)
         if let Some(target_entity) = game.get_blocking_entity_at(new_pos) {
-            self.attack_enemy(game, target_entity);
+            self.attack_enemy(game, target_entity, false);
+            // A spear thrust carries through to the enemy behind
+            if weapon.is_some_and(|w| w.pierces()) {
+                if let Some(behind) = game.enemy_at(Position::new(new_x + dx, new_y + dy)) {
+                    game.add_message("Your thrust carries through to the foe behind!".to_string(), MessageCategory::Combat);
+                    self.attack_enemy(game, behind, false);
+                }
+            }
+            // The off-hand weapon follows up with a weaker second roll
+            let dual_wielding = game.player()
+                .and_then(|p| game.world().get::<&crate::ecs::EquipmentComponent>(p).ok())
+                .is_some_and(|eq| eq.equipment.is_dual_wielding());
+            if dual_wielding && game.world().contains(target_entity) {
+                self.attack_enemy(game, target_entity, true);
+            }
+            // A writhing limb sometimes lands a second blow
+            let extra_chance = game.player()
+                .and_then(|p| game.world().get::<&crate::progression::Mutations>(p).ok())
+                .map(|m| m.extra_attack_chance())
+                .unwrap_or(0.0);
+            if extra_chance > 0.0 && game.world().contains(target_entity) && game.rng().gen_bool(extra_chance) {
+                game.add_message("Your writhing limb lashes out again!".to_string(), MessageCategory::Combat);
+                self.attack_enemy(game, target_entity, false);
+            }
             // Run enemy AI after player action (even attacks count as actions)
             game.run_ai_tick();
             return;
         }
 
+        // Walking into a hazard takes a second, deliberate step
+        if let Some(hazard) = hazard {
+            if self.hazard_confirm != Some(new_pos) {
+                self.hazard_confirm = Some(new_pos);
+                game.add_message(
+                    format!("Really step into the {}? Move again to confirm.", hazard.hazard_name()),
+                    MessageCategory::Warning,
+                );
+                return;
+            }
+        }
+        self.hazard_confirm = None;
+
+        // Stepping out of an enemy's reach invites a free attack
+        if !self.pending_disengage {
+            game.provoke_opportunity_attacks(self.camera, new_pos);
+        }
+
         // Move player
         self.camera = new_pos;
         game.set_player_position(new_pos);
 
+        if hazard == Some(crate::world::TileType::Pit) {
+            game.fall_into_pit();
+            if let Some(pos) = game.player_position() {
+                self.camera = pos;
+            }
+            return;
+        }
+
+        // Sprinting carries the player a second tile along open ground,
+        // unless they've stopped on a teleporter pad
+        let on_pad = game.map().is_some_and(|m| m.teleporter_exit(new_pos).is_some());
+        if game.is_sprinting() && !self.pending_disengage && hazard.is_none() && !on_pad {
+            let sprint_pos = Position::new(new_x + dx, new_y + dy);
+            let open = game.map()
+                .and_then(|m| m.get_tile(sprint_pos.x, sprint_pos.y))
+                .is_some_and(|t| t.is_walkable() && !t.tile_type.is_hazard())
+                && game.get_blocking_entity_at(sprint_pos).is_none()
+                && game.interactable_at(sprint_pos).is_none_or(|(_, interactable)| interactable.underfoot());
+            if open && game.spend_sprint_stamina() {
+                game.provoke_opportunity_attacks(new_pos, sprint_pos);
+                self.camera = sprint_pos;
+                game.set_player_position(sprint_pos);
+            }
+        }
+
+        // A teleporter pad carries the player on to its partner
+        if game.take_teleporter() {
+            if let Some(pos) = game.player_position() {
+                self.camera = pos;
+            }
+        }
+
         // Update FOV (separate mutable borrow)
+        let radius = game.sight_radius();
         if let Some(map) = game.map_mut() {
-            crate::world::compute_fov(map, self.camera, 8);
+            crate::world::compute_fov(map, self.camera, radius);
         }
 
+        self.auto_pickup(game);
+
         // Run enemy AI after player action
         game.run_ai_tick();
+        game.lumber_under_load();
     }
 
     /// Execute a movement skill (teleport) in the given direction
+    /// Ready a wand and wait for a direction to zap it in
+    fn aim_wand(&mut self, game: &mut Game, wand_id: crate::items::ItemId, name: &str) {
+        self.pending_zap = Some(wand_id);
+        game.add_message(format!("Zap {} - choose a direction (Esc to cancel)", name), MessageCategory::System);
+    }
+
+    /// Fire a wand's bolt in a straight line, hitting the first enemy in its path
+    fn zap_wand(&mut self, game: &mut Game, wand_id: crate::items::ItemId, dx: i32, dy: i32) {
+        use crate::ecs::{Health, InventoryComponent, Name, StatusEffectType};
+        use crate::items::{WandKind, WAND_RANGE};
+
+        let Some(player) = game.player() else { return };
+        let Some(player_pos) = game.player_position() else { return };
+
+        // Spend the charge up front
+        let wand = game.world_mut()
+            .get::<&mut InventoryComponent>(player)
+            .ok()
+            .and_then(|mut inv| {
+                let wand = inv.inventory.grid_mut().get_by_id_mut(wand_id)?;
+                let zapped = wand.use_charge();
+                Some((wand.wand?, wand.zap_damage(), zapped))
+            });
+        let Some((kind, damage, zapped)) = wand else { return };
+        if !zapped {
+            game.play_sound(SoundId::Error);
+            game.add_message("The wand fizzles - it needs recharging.".to_string(), MessageCategory::Warning);
+            return;
+        }
+
+        // Trace the bolt until it finds an enemy or hits something solid
+        let mut hit = None;
+        for step in 1..=WAND_RANGE {
+            let pos = Position::new(player_pos.x + dx * step, player_pos.y + dy * step);
+            if game.map().is_none_or(|m| m.is_opaque(pos.x, pos.y)) {
+                break;
+            }
+            if let Some(enemy) = game.enemy_at(pos) {
+                hit = Some((enemy, pos));
+                break;
+            }
+        }
+
+        let Some((target, target_pos)) = hit else {
+            game.add_message(format!("The {} hits nothing.", kind.bolt()), MessageCategory::Combat);
+            game.run_ai_tick();
+            return;
+        };
+
+        let target_name = game.world()
+            .get::<&Name>(target)
+            .map(|n| n.0.clone())
+            .unwrap_or_else(|_| "something".to_string());
+
+        let died = game.world_mut()
+            .get::<&mut Health>(target)
+            .map(|mut hp| {
+                hp.current -= damage;
+                hp.current <= 0
+            })
+            .unwrap_or(false);
+        if kind == WandKind::Firebolt {
+            game.leave_decal(target_pos, crate::world::Decal::Scorch);
+        }
+
+        game.play_sound(SoundId::Hit);
+        if died {
+            game.play_sound(SoundId::EnemyDeath);
+            game.add_message(
+                format!("Your {} strikes the {} for {} damage! It dies!", kind.bolt(), target_name, damage),
+                MessageCategory::Combat,
+            );
+            game.queue_death(target, false, true);
+        } else {
+            game.add_message(
+                format!("Your {} strikes the {} for {} damage.", kind.bolt(), target_name, damage),
+                MessageCategory::Combat,
+            );
+            let status = match kind {
+                WandKind::Firebolt => Some((StatusEffectType::Burn, 3.0, 3, "catches fire")),
+                WandKind::Frost => Some((StatusEffectType::Slow, 3.0, 1, "is chilled to the bone")),
+                WandKind::Lightning if game.rng().gen_bool(0.35) => Some((StatusEffectType::Stun, 1.0, 1, "is stunned")),
+                _ => None,
+            };
+            if let Some((effect_type, duration, intensity, verb)) = status {
+                if crate::combat::afflict(game.world_mut(), target, effect_type, duration, intensity) {
+                    game.add_message(format!("The {} {}!", target_name, verb), MessageCategory::Combat);
+                } else {
+                    game.add_message(format!("The {} is immune to {}.", target_name, effect_type.name().to_lowercase()), MessageCategory::Combat);
+                }
+            }
+            if kind == WandKind::Force {
+                game.shove(target, (dx, dy), 2);
+                // Slamming into a wall or a hazard may finish it off
+                let dead = game.world().get::<&Health>(target).is_ok_and(|hp| hp.current <= 0);
+                if dead {
+                    game.add_message(format!("The {} dies!", target_name), MessageCategory::Combat);
+                    game.queue_death(target, false, true);
+                }
+            }
+        }
+
+        game.run_ai_tick();
+    }
+
     fn execute_movement_skill(&mut self, game: &mut Game, dx: i32, dy: i32, range: i32) {
         let player_pos = match game.player_position() {
             Some(pos) => pos,
             None => return,
         };
 
+        // Light armor carries you further, heavy armor holds you back
+        let range = (range + game.armor_weight().movement_range_modifier()).max(1);
+
         // Find the farthest valid tile in the direction, up to range
         let mut final_x = player_pos.x;
         let mut final_y = player_pos.y;
@@ -1146,8 +2105,9 @@ impl App {
         game.set_player_position(final_pos);
 
         // Update FOV
+        let radius = game.sight_radius();
         if let Some(map) = game.map_mut() {
-            crate::world::compute_fov(map, self.camera, 8);
+            crate::world::compute_fov(map, self.camera, radius);
         }
 
         game.add_message(format!("Shadow Step! Teleported {} tiles.", diagonal_dist as i32), MessageCategory::Combat);
@@ -1156,12 +2116,11 @@ impl App {
         game.run_ai_tick();
     }
 
-    fn attack_enemy(&mut self, game: &mut Game, target: hecs::Entity) {
-        use crate::ecs::{Name, Health, Stats, GroundItem, EquipmentComponent};
+    /// Strike an enemy in melee (`offhand` for a dual-wielded follow-up)
+    fn attack_enemy(&mut self, game: &mut Game, target: hecs::Entity, offhand: bool) {
+        use crate::ecs::{Name, Health, Stats, EquipmentComponent};
         use crate::game::MessageCategory;
-        use crate::combat::{calculate_attack_with_equipment, EquipmentBonuses};
-        use crate::items::{generate_enemy_loot, generate_gold_drop, generate_boss_loot, generate_boss_gold_drop};
-
+        use crate::combat::calculate_attack_with_equipment;
         // Get player and target stats
         let player_stats = game.player_stats().unwrap_or(Stats::player_base());
         let target_stats = game.world()
@@ -1169,21 +2128,38 @@ impl App {
             .map(|s| *s)
             .unwrap_or(Stats::new(5, 5, 5, 5));
 
-        // Get player equipment bonuses
-        let player_equipment = if let Some(player) = game.player() {
-            game.world()
-                .get::<&EquipmentComponent>(player)
-                .map(|eq| EquipmentBonuses {
-                    weapon_damage: eq.equipment.weapon_damage(),
-                    armor: eq.equipment.total_armor(),
-                    str_bonus: eq.equipment.strength_bonus(),
-                    dex_bonus: eq.equipment.dexterity_bonus(),
-                    crit_bonus: eq.equipment.weapon_crit_bonus(),
-                })
-                .unwrap_or_default()
-        } else {
-            EquipmentBonuses::default()
-        };
+        // Gear, set bonuses, mutations, buffs and exhaustion all count
+        let derived = game.player_derived_stats();
+        let mut player_equipment = derived
+            .and_then(|d| if offhand { d.offhand_bonuses() } else { Some(d.attack_bonuses()) })
+            .unwrap_or_default();
+        let target_defense = crate::combat::DerivedStats::compute(game.world(), target)
+            .map(|d| d.defense_bonuses())
+            .unwrap_or_default();
+
+        // Weapon swung this attack, and whether the Shadow set is active
+        let (weapon, shadow_synergy) = game.player()
+            .and_then(|p| game.world().get::<&EquipmentComponent>(p).ok())
+            .map(|eq| {
+                let weapon = if offhand {
+                    eq.equipment.offhand_weapon().and_then(|w| w.weapon_type)
+                } else {
+                    eq.equipment.weapon_type()
+                };
+                let shadow = eq.equipment.active_synergies().iter()
+                    .any(|s| s.synergy.tag == crate::items::SynergyTag::Shadow);
+                (weapon, shadow)
+            })
+            .unwrap_or((None, false));
+
+        // Daggers backstab enemies that haven't noticed the player
+        let unaware = game.world()
+            .get::<&crate::ecs::AI>(target)
+            .is_ok_and(|ai| matches!(ai.state, crate::ecs::AIState::Idle | crate::ecs::AIState::Patrol | crate::ecs::AIState::Sleeping));
+        let backstab = unaware && weapon.is_some_and(|w| w.can_backstab());
+        if backstab {
+            player_equipment.crit_bonus += crate::combat::BACKSTAB_CRIT_BONUS;
+        }
 
         // Get target info and position (need position before despawn for loot)
         let target_name = game.world()
@@ -1197,14 +2173,18 @@ impl App {
             .unwrap_or(self.camera);
 
         // Calculate attack with crits, dodges, equipment bonuses
-        let result = calculate_attack_with_equipment(
+        let mut result = calculate_attack_with_equipment(
             &player_stats,
             &target_stats,
             &player_equipment,
-            &EquipmentBonuses::default(), // Enemies don't have equipment (yet)
+            &target_defense,
             game.rng(),
         );
 
+        if offhand {
+            game.add_message("You follow up with your off-hand weapon.".to_string(), MessageCategory::Combat);
+        }
+
         // Handle dodge/miss
         if result.is_dodge {
             game.play_sound(SoundId::Dodge);
@@ -1223,6 +2203,12 @@ impl App {
             return;
         }
 
+        if backstab {
+            let multiplier = crate::combat::backstab_multiplier(shadow_synergy);
+            result.final_damage = (result.final_damage as f32 * multiplier).round() as i32;
+            game.add_message(format!("You backstab the unaware {}!", target_name), MessageCategory::Combat);
+        }
+
         // Apply damage
         let (target_died, current_health) = {
             if let Ok(mut health) = game.world_mut().get::<&mut Health>(target) {
@@ -1232,6 +2218,10 @@ impl App {
                 (false, None)
             }
         };
+        if let Some(player) = game.player() {
+            game.record_hit(player, target, result.final_damage);
+        }
+        game.leave_decal(target_pos, crate::world::Decal::Blood);
 
         // Check for boss phase transition (separate borrow)
         let phase_changed = if let Some(health) = current_health {
@@ -1273,98 +2263,10 @@ impl App {
             };
             game.add_message(msg, MessageCategory::Combat);
 
-            // Check if this was a boss
-            let is_boss = game.world()
-                .get::<&crate::entities::BossComponent>(target)
-                .is_ok();
-
-            // Generate and drop loot (bosses get better loot)
-            let floor = game.floor();
-            let loot = if is_boss {
-                game.add_message(
-                    "★ The boss drops powerful loot! ★".to_string(),
-                    MessageCategory::Item
-                );
-                generate_boss_loot(floor, game.rng())
-            } else {
-                generate_enemy_loot(floor, game.rng())
-            };
-
-            for item in loot {
-                // Include rarity in the drop message
-                let rarity_name = item.rarity.name();
-                game.add_message(
-                    format!("The {} dropped: {} [{}]", target_name, item.name, rarity_name),
-                    MessageCategory::Item
-                );
-                // Spawn item entity on ground
-                game.world_mut().spawn((
-                    target_pos,
-                    crate::ecs::Renderable::new(item.glyph, item.rarity.color()).with_order(10),
-                    GroundItem { item },
-                ));
-            }
-
-            // Drop gold (bosses drop more)
-            let gold = if is_boss {
-                generate_boss_gold_drop(floor, game.rng())
-            } else {
-                generate_gold_drop(floor, game.rng())
-            };
-            if gold > 0 {
-                // Add gold directly to player inventory
-                let added_gold = if let Some(player) = game.player() {
-                    if let Ok(mut inv) = game.world_mut().get::<&mut crate::ecs::InventoryComponent>(player) {
-                        inv.inventory.add_gold(gold);
-                        true
-                    } else { false }
-                } else { false };
-
-                if added_gold {
-                    game.add_message(format!("You found {} gold!", gold), MessageCategory::Item);
-                    game.record_gold_collected(gold);
-                }
-            }
-
-            // Get XP reward before despawning
-            let xp_reward = game.world()
-                .get::<&crate::ecs::XpReward>(target)
-                .map(|xp| xp.0)
-                .unwrap_or(15); // Default 15 XP if no XpReward component
-
-            // Remove the dead entity
-            let _ = game.world_mut().despawn(target);
-
-            // Record enemy kill in profile stats
-            game.record_enemy_kill(is_boss);
-
-            // Grant XP
-            game.add_message(format!("+{} XP", xp_reward), MessageCategory::System);
-
-            let leveled_up = if let Some(player) = game.player() {
-                if let Ok(mut xp) = game.world_mut().get::<&mut crate::ecs::Experience>(player) {
-                    let did_level = xp.add_xp(xp_reward);
-                    if did_level { Some(xp.level) } else { None }
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-
-            if let Some(new_level) = leveled_up {
-                game.play_sound(SoundId::LevelUp);
-                // Grant stat point on level up
-                if let Some(player) = game.player() {
-                    if let Ok(mut sp) = game.world_mut().get::<&mut crate::ecs::StatPoints>(player) {
-                        sp.0 += 1;
-                    }
-                }
-                game.add_message(
-                    format!("LEVEL UP! You are now level {}! (+1 stat point)", new_level),
-                    MessageCategory::System
-                );
-            }
+            // A heavy enough critical leaves nothing to raise
+            let max_hp = current_health.map_or(0, |h| h.max);
+            let gibbed = crate::entities::is_gib(result.is_crit, result.final_damage, max_hp);
+            game.queue_death(target, gibbed, true);
         } else {
             // Target didn't die - play hit/crit sound
             if result.is_crit {
@@ -1378,41 +2280,21 @@ impl App {
                 format!("You strike the {} for {} damage.", target_name, result.final_damage)
             };
             game.add_message(msg, MessageCategory::Combat);
+
+            // The weapon's on-hit procs
+            if let Some(weapon) = weapon {
+                self.apply_weapon_procs(game, target, &target_name, weapon);
+            }
         }
 
         // Apply lifesteal (vampiric) if player has it and did damage
-        // Each point of LifeSteal = 5% of damage converted to health
+        // (LifeSteal affixes and the Blood set)
         if result.final_damage > 0 {
-            let lifesteal_flat = if let Some(player) = game.player() {
-                game.world()
-                    .get::<&EquipmentComponent>(player)
-                    .map(|eq| eq.equipment.stat_bonus(crate::items::item::AffixType::LifeSteal))
-                    .unwrap_or(0)
-            } else {
-                0
-            };
+            let lifesteal_percent = derived.map(|d| d.lifesteal.round() as i32).unwrap_or(0);
 
-            if lifesteal_flat > 0 {
-                // Each point of LifeSteal = 5% of damage, so +3 LifeSteal = 15% of damage healed
-                let lifesteal_percent = lifesteal_flat * 5;
+            if lifesteal_percent > 0 {
                 let heal_amount = (result.final_damage * lifesteal_percent / 100).max(1);
-                let actual_heal = if let Some(player) = game.player() {
-                    // Get equipment HP bonus for effective max
-                    let eq_hp = game.world()
-                        .get::<&EquipmentComponent>(player)
-                        .map(|eq| eq.equipment.hp_bonus())
-                        .unwrap_or(0);
-                    if let Ok(mut hp) = game.world_mut().get::<&mut Health>(player) {
-                        let effective_max = hp.max + eq_hp;
-                        let actual = heal_amount.min(effective_max - hp.current);
-                        hp.current += actual;
-                        actual
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                };
+                let actual_heal = game.heal_player(heal_amount);
                 if actual_heal > 0 {
                     game.add_message(
                         format!("💉 Vampiric ({}%): +{} HP", lifesteal_percent, actual_heal),
@@ -1421,18 +2303,35 @@ impl App {
                 }
             }
         }
+    }
 
-        // Tick cooldowns after player action
-        if let Some(player) = game.player() {
-            if let Ok(mut skills) = game.world_mut().get::<&mut crate::ecs::SkillsComponent>(player) {
-                skills.skills.tick_cooldowns();
+    /// Roll a weapon's on-hit procs against a surviving target
+    fn apply_weapon_procs(&mut self, game: &mut Game, target: hecs::Entity, target_name: &str, weapon: crate::items::WeaponType) {
+        use crate::data::ProcEffect;
+        use crate::ecs::StatusEffectType;
+
+        let procs: Vec<(ProcEffect, i32, i32)> = {
+            let table = game.data().weapon_procs().clone();
+            crate::combat::roll_weapon_procs(&table, weapon, game.rng())
+                .into_iter()
+                .map(|p| (p.effect, p.intensity, p.duration))
+                .collect()
+        };
+
+        for (effect, intensity, duration) in procs {
+            let (effect_type, msg) = match effect {
+                ProcEffect::Stun => (StatusEffectType::Stun, format!("The {} is stunned!", target_name)),
+                ProcEffect::Bleed => (StatusEffectType::Bleed, format!("The {} starts bleeding!", target_name)),
+                ProcEffect::Slow => (StatusEffectType::Slow, format!("The {} is entangled!", target_name)),
+            };
+            if crate::combat::afflict(game.world_mut(), target, effect_type, duration as f32, intensity) {
+                game.add_message(msg, MessageCategory::Combat);
             }
         }
     }
 
     fn handle_inventory_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
-        use crate::ecs::{InventoryComponent, EquipmentComponent, Health, Mana};
-        use crate::items::ConsumableEffect;
+        use crate::ecs::InventoryComponent;
 
         let player = match game.player() {
             Some(p) => p,
@@ -1458,6 +2357,10 @@ impl App {
             equipment_count
         };
 
+        if self.inventory_tab == 0 && self.inventory_grid_mode && self.handle_grid_input(key, game, player) {
+            return Ok(false);
+        }
+
         match key.code {
             KeyCode::Esc | KeyCode::Char('i') => {
                 // Mark all items as seen when closing inventory
@@ -1481,6 +2384,38 @@ impl App {
             KeyCode::Tab => {
                 self.inventory_tab = (self.inventory_tab + 1) % 2;
                 self.inventory_cursor = 0;
+                self.grid_held = None;
+            }
+            // Put the selected consumable on a belt slot (again to take it off)
+            KeyCode::Char(c @ '6'..='9') if self.inventory_tab == 0 && inv_len > 0 => {
+                let slot = c as usize - '6' as usize;
+                let result = game.world_mut()
+                    .get::<&mut InventoryComponent>(player)
+                    .ok()
+                    .and_then(|mut inv| {
+                        let item = inv.inventory.get(self.inventory_cursor)?.clone();
+                        let placed = inv.inventory.belt_mut().toggle(slot, &item);
+                        let on_belt = inv.inventory.belt().get(slot) == Some(item.name.as_str());
+                        Some((item.name, placed, on_belt))
+                    });
+                match result {
+                    Some((name, true, true)) => {
+                        game.add_message(format!("{} goes on belt slot {}.", name, c), MessageCategory::System);
+                    }
+                    Some((name, true, false)) => {
+                        game.add_message(format!("{} taken off the belt.", name), MessageCategory::System);
+                    }
+                    Some((_, false, _)) => {
+                        game.play_sound(SoundId::Error);
+                        game.add_message("Only consumables go on the belt.", MessageCategory::Warning);
+                    }
+                    None => {}
+                }
+            }
+            // Toggle list / grid view
+            KeyCode::Char('v') if self.inventory_tab == 0 => {
+                self.inventory_grid_mode = !self.inventory_grid_mode;
+                self.grid_held = None;
             }
             // Use consumable
             KeyCode::Char('u') | KeyCode::Enter => {
@@ -1493,50 +2428,10 @@ impl App {
 
                     if let Some(item) = item_info {
                         if item.is_consumable() {
-                            // Apply effect
-                            let effect_msg = match item.consumable_effect {
-                                Some(ConsumableEffect::HealHP(amount)) => {
-                                    // Get equipment HP bonus for effective max
-                                    let eq_hp = game.world()
-                                        .get::<&EquipmentComponent>(player)
-                                        .map(|eq| eq.equipment.hp_bonus())
-                                        .unwrap_or(0);
-                                    if let Ok(mut hp) = game.world_mut().get::<&mut Health>(player) {
-                                        let effective_max = hp.max + eq_hp;
-                                        let actual_heal = amount.min(effective_max - hp.current);
-                                        hp.current += actual_heal;
-                                        Some(format!("Healed {} HP!", actual_heal))
-                                    } else { None }
-                                }
-                                Some(ConsumableEffect::RestoreMP(amount)) => {
-                                    // Get equipment MP bonus
-                                    let eq_mp = game.world()
-                                        .get::<&EquipmentComponent>(player)
-                                        .map(|eq| eq.equipment.mp_bonus())
-                                        .unwrap_or(0);
-
-                                    if let Ok(mut mp) = game.world_mut().get::<&mut Mana>(player) {
-                                        let effective_max = mp.max + eq_mp;
-                                        let actual_restore = amount.min(effective_max - mp.current);
-                                        mp.current += actual_restore;
-                                        Some(format!("Restored {} MP!", actual_restore))
-                                    } else { None }
-                                }
-                                _ => None,
-                            };
-
-                            // Consume the item
-                            if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                inv.inventory.consume_at(self.inventory_cursor);
-                            }
-
-                            if let Some(msg) = effect_msg {
-                                game.add_message(msg, MessageCategory::Item);
+                            if !Self::use_consumable(game, player, self.inventory_cursor, &item) {
+                                return Ok(false);
                             }
 
-                            // Using a consumable takes a turn - enemies act
-                            game.run_ai_tick();
-
                             // Adjust cursor if needed
                             let new_len = game.world()
                                 .get::<&InventoryComponent>(player)
@@ -1545,40 +2440,44 @@ impl App {
                             if self.inventory_cursor >= new_len && new_len > 0 {
                                 self.inventory_cursor = new_len - 1;
                             }
+                        } else if item.category == crate::items::ItemCategory::Wand {
+                            // Zapping needs the map, so close the pack and aim
+                            if item.charges == 0 {
+                                game.play_sound(SoundId::Error);
+                                game.add_message("The wand fizzles - it needs recharging.".to_string(), MessageCategory::Warning);
+                            } else {
+                                game.set_state(GameState::Playing(PlayingState::Exploring));
+                                self.aim_wand(game, item.id, &item.name);
+                            }
+                        } else if let Some(mutation) = item.equip_slot.and_then(|slot| game.mutation_blocking(slot)) {
+                            game.play_sound(SoundId::Error);
+                            game.add_message(
+                                format!("Your {} leave no room for {}.", mutation.name(), item.name),
+                                MessageCategory::Warning,
+                            );
                         } else if item.is_equippable() {
                             // Equip the item
                             let item_name = item.name.clone();
 
-                            // Remove from inventory and equip
-                            let removed = {
-                                if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                    inv.inventory.remove_at(self.inventory_cursor)
-                                } else { None }
-                            };
-
-                            if let Some(to_equip) = removed {
-                                let old_item = {
-                                    if let Ok(mut eq) = game.world_mut().get::<&mut EquipmentComponent>(player) {
-                                        eq.equipment.equip(to_equip)
-                                    } else { None }
-                                };
-
-                                // Put old item back in inventory
-                                if let Some(old) = old_item {
-                                    let old_name = old.name.clone();
-                                    if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                        inv.inventory.add_item(old);
-                                    }
+                            match game.transact(Transaction::new().equip(item.id)) {
+                                // Displaced gear goes back in the pack (a two-hander frees both hands)
+                                Ok(receipt) if !receipt.unequipped.is_empty() => {
+                                    let old_names: Vec<String> = receipt.unequipped.iter().map(|i| i.name.clone()).collect();
                                     game.add_message(
-                                        format!("Unequipped {} and equipped {}", old_name, item_name),
+                                        format!("Unequipped {} and equipped {}", old_names.join(" and "), item_name),
                                         MessageCategory::Item
                                     );
-                                } else {
+                                }
+                                Ok(_) => {
                                     game.add_message(
                                         format!("Equipped {}", item_name),
                                         MessageCategory::Item
                                     );
                                 }
+                                Err(e) => {
+                                    game.play_sound(SoundId::Error);
+                                    game.add_message(e.to_string(), MessageCategory::Warning);
+                                }
                             }
 
                             // Adjust cursor
@@ -1606,52 +2505,86 @@ impl App {
                     if self.inventory_cursor < equipment_items.len() {
                         let item_id = equipment_items[self.inventory_cursor];
 
-                        // Get item info and remove from inventory
-                        let removed = {
-                            if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                inv.inventory.remove_by_id(item_id)
-                            } else { None }
-                        };
-
-                        if let Some(to_equip) = removed {
-                            let item_name = to_equip.name.clone();
-                            let old_item = {
-                                if let Ok(mut eq) = game.world_mut().get::<&mut EquipmentComponent>(player) {
-                                    eq.equipment.equip(to_equip)
-                                } else { None }
-                            };
+                        // Mutated body parts can't wear some gear
+                        let blocked = game.world()
+                            .get::<&InventoryComponent>(player)
+                            .ok()
+                            .and_then(|inv| inv.inventory.get_by_id(item_id).and_then(|i| i.equip_slot))
+                            .and_then(|slot| game.mutation_blocking(slot));
+                        if let Some(mutation) = blocked {
+                            game.play_sound(SoundId::Error);
+                            game.add_message(
+                                format!("Your {} leave no room for that.", mutation.name()),
+                                MessageCategory::Warning,
+                            );
+                            return Ok(false);
+                        }
 
-                            // Put old item back in inventory
-                            if let Some(old) = old_item {
-                                let old_name = old.name.clone();
-                                if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                    inv.inventory.add_item(old);
-                                }
+                        let item_name = game.world()
+                            .get::<&InventoryComponent>(player)
+                            .ok()
+                            .and_then(|inv| inv.inventory.get_by_id(item_id).map(|i| i.name.clone()))
+                            .unwrap_or_default();
+                        match game.transact(Transaction::new().equip(item_id)) {
+                            // Displaced gear goes back in the pack (a two-hander frees both hands)
+                            Ok(receipt) if !receipt.unequipped.is_empty() => {
+                                let old_names: Vec<String> = receipt.unequipped.iter().map(|i| i.name.clone()).collect();
                                 game.add_message(
-                                    format!("Swapped {} for {}", old_name, item_name),
+                                    format!("Swapped {} for {}", old_names.join(" and "), item_name),
                                     MessageCategory::Item
                                 );
-                            } else {
+                            }
+                            Ok(_) => {
                                 game.add_message(
                                     format!("Equipped {}", item_name),
                                     MessageCategory::Item
                                 );
                             }
-
-                            // Adjust cursor
-                            let new_count = game.world()
-                                .get::<&InventoryComponent>(player)
-                                .map(|inv| inv.inventory.items().into_iter().filter(|i| i.category.is_equipment()).count())
-                                .unwrap_or(0);
-                            if self.inventory_cursor >= new_count && new_count > 0 {
-                                self.inventory_cursor = new_count - 1;
+                            Err(e) => {
+                                game.play_sound(SoundId::Error);
+                                game.add_message(e.to_string(), MessageCategory::Warning);
                             }
                         }
+
+                        // Adjust cursor
+                        let new_count = game.world()
+                            .get::<&InventoryComponent>(player)
+                            .map(|inv| inv.inventory.items().into_iter().filter(|i| i.category.is_equipment()).count())
+                            .unwrap_or(0);
+                        if self.inventory_cursor >= new_count && new_count > 0 {
+                            self.inventory_cursor = new_count - 1;
+                        }
+                    }
+                }
+            }
+            // Lock / unlock the selected item against destroying and selling
+            KeyCode::Char('f') => {
+                let toggled = self.selected_inventory_id(game, player).and_then(|id| {
+                    let mut inv = game.world_mut().get::<&mut InventoryComponent>(player).ok()?;
+                    let item = inv.inventory.grid_mut().get_by_id_mut(id)?;
+                    item.locked = !item.locked;
+                    Some((item.name.clone(), item.locked))
+                });
+                if let Some((name, locked)) = toggled {
+                    let name = game.discoveries().appearance(&name).unwrap_or(&name).to_string();
+                    if locked {
+                        game.add_message(format!("★ {} locked.", name), MessageCategory::System);
+                    } else {
+                        game.add_message(format!("{} unlocked.", name), MessageCategory::System);
                     }
                 }
             }
             // Destroy item (permanently delete)
             KeyCode::Char('d') => {
+                let locked = self.selected_inventory_id(game, player)
+                    .and_then(|id| game.world().get::<&InventoryComponent>(player).ok()
+                        .and_then(|inv| inv.inventory.get_by_id(id).map(|i| i.locked)))
+                    .unwrap_or(false);
+                if locked {
+                    game.play_sound(SoundId::Error);
+                    game.add_message("That item is locked. Press F to unlock it first.", MessageCategory::Warning);
+                    return Ok(false);
+                }
                 if self.inventory_tab == 0 && inv_len > 0 {
                     // Destroy from all items tab
                     let removed = {
@@ -1661,7 +2594,7 @@ impl App {
                     };
 
                     if let Some(item) = removed {
-                        let item_name = item.name.clone();
+                        let item_name = game.discoveries().disguise(&item).name.clone();
                         // Item is simply dropped (destroyed) - not spawned on ground
                         game.add_message(format!("Destroyed {}", item_name), MessageCategory::Item);
 
@@ -1693,7 +2626,7 @@ impl App {
                         };
 
                         if let Some(item) = removed {
-                            let item_name = item.name.clone();
+                            let item_name = game.discoveries().disguise(&item).name.clone();
                             // Item is simply dropped (destroyed) - not spawned on ground
                             game.add_message(format!("Destroyed {}", item_name), MessageCategory::Item);
 
@@ -1741,6 +2674,197 @@ impl App {
         Ok(false)
     }
 
+    /// Id of the item under the inventory cursor, on either tab
+    fn selected_inventory_id(&self, game: &Game, player: hecs::Entity) -> Option<crate::items::ItemId> {
+        let inv = game.world().get::<&crate::ecs::InventoryComponent>(player).ok()?;
+        let items = inv.inventory.items();
+        let item = if self.inventory_tab == 0 {
+            items.get(self.inventory_cursor).copied()
+        } else {
+            items.into_iter().filter(|i| i.category.is_equipment()).nth(self.inventory_cursor)
+        };
+        item.map(|i| i.id)
+    }
+
+    /// Use the consumable at `index` in the player's inventory. Returns false if
+    /// it had no effect and was kept.
+    fn use_consumable(game: &mut Game, player: hecs::Entity, index: usize, item: &crate::items::Item) -> bool {
+        use crate::ecs::InventoryComponent;
+        use crate::items::ConsumableEffect;
+
+        let pack_maxed = game.world()
+            .get::<&InventoryComponent>(player)
+            .map(|inv| inv.inventory.grid_size().1 >= crate::items::MAX_GRID_HEIGHT)
+            .unwrap_or(true);
+        if matches!(item.consumable_effect, Some(ConsumableEffect::ExpandPack(_))) && pack_maxed {
+            game.play_sound(SoundId::Error);
+            game.add_message("Your pack can't get any bigger.", MessageCategory::Warning);
+            return false;
+        }
+
+        // Apply effect
+        let effect_msg = match item.consumable_effect {
+            Some(ConsumableEffect::HealHP(amount)) => {
+                Some(format!("Healed {} HP!", game.heal_player(amount)))
+            }
+            Some(ConsumableEffect::RestoreMP(amount)) => {
+                Some(format!("Restored {} MP!", game.restore_mana(amount)))
+            }
+            Some(ConsumableEffect::Mutate) => {
+                Some("You drink the vial. Your flesh writhes...".to_string())
+            }
+            Some(ConsumableEffect::Ration) => {
+                game.heal_player(crate::game::RATION_HEAL);
+                Some("You wolf down the ration. It would go further while resting.".to_string())
+            }
+            Some(ConsumableEffect::ExpandPack(rows)) => {
+                if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
+                    inv.inventory.expand(rows as usize);
+                }
+                Some("You stitch the satchel onto your pack. More room!".to_string())
+            }
+            Some(ConsumableEffect::Teleport) => {
+                if game.teleport_player_randomly() {
+                    Some("The world lurches. You're somewhere else.".to_string())
+                } else {
+                    Some("The scroll crumbles, but nothing happens.".to_string())
+                }
+            }
+            Some(ConsumableEffect::Return) => {
+                if game.return_to_entrance() {
+                    Some("The scroll burns away and pulls you back to where you came in.".to_string())
+                } else {
+                    Some("The scroll crumbles, but nothing happens.".to_string())
+                }
+            }
+            Some(ConsumableEffect::RevealMap) => {
+                game.reveal_map();
+                Some("The floor's layout unfolds in your mind.".to_string())
+            }
+            Some(ConsumableEffect::RechargeWands) => {
+                let recharged = game.world_mut()
+                    .get::<&mut InventoryComponent>(player)
+                    .map(|mut inv| inv.inventory.recharge_wands())
+                    .unwrap_or(0);
+                if recharged > 0 {
+                    Some("Your wands hum with renewed power.".to_string())
+                } else {
+                    Some("The scroll glows briefly, but you have nothing to recharge.".to_string())
+                }
+            }
+            Some(ConsumableEffect::TreatInjuries) => {
+                if game.treat_injuries() > 0 {
+                    Some("The salve burns, then soothes. Your injuries mend.".to_string())
+                } else {
+                    Some("You smear on the salve, but nothing needed mending.".to_string())
+                }
+            }
+            _ => None,
+        };
+
+        // Consume the item
+        if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
+            inv.inventory.consume_at(index);
+        }
+
+        if let Some(msg) = effect_msg {
+            game.add_message(msg, MessageCategory::Item);
+        }
+        game.discover_item(&item.name);
+
+        // Mutate after the vial is gone, since shed gear lands in the inventory
+        if matches!(item.consumable_effect, Some(ConsumableEffect::Mutate)) {
+            game.grant_mutation();
+        }
+
+        // Using a consumable takes a turn - enemies act
+        game.run_ai_tick();
+
+        true
+    }
+
+    /// Grid view keys on the items tab. Returns true if the key was handled here;
+    /// use/destroy keys point `inventory_cursor` at the item under the grid cursor
+    /// and fall through to the list handling.
+    fn handle_grid_input(&mut self, key: KeyEvent, game: &mut Game, player: hecs::Entity) -> bool {
+        use crate::ecs::InventoryComponent;
+
+        let rows = game.world()
+            .get::<&InventoryComponent>(player)
+            .map(|inv| inv.inventory.grid_size().1)
+            .unwrap_or(0);
+        let (x, y) = (self.grid_cursor.x, self.grid_cursor.y);
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.grid_cursor.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.grid_cursor.move_down(rows),
+            KeyCode::Left | KeyCode::Char('h') => self.grid_cursor.move_left(),
+            KeyCode::Right | KeyCode::Char('l') => self.grid_cursor.move_right(),
+            // Put the held item back down without moving it
+            KeyCode::Esc if self.grid_held.is_some() => self.grid_held = None,
+            // Pick up the item under the cursor, or place the held one
+            KeyCode::Char(' ') => {
+                if let Some(id) = self.grid_held {
+                    let moved = game.world_mut()
+                        .get::<&mut InventoryComponent>(player)
+                        .map(|mut inv| inv.inventory.move_item(id, x, y))
+                        .unwrap_or(false);
+                    if moved {
+                        self.grid_held = None;
+                    } else {
+                        game.play_sound(SoundId::Error);
+                        game.add_message("It doesn't fit there.", MessageCategory::Warning);
+                    }
+                } else {
+                    self.grid_held = game.world()
+                        .get::<&InventoryComponent>(player)
+                        .ok()
+                        .and_then(|inv| inv.inventory.get_placed_at(x, y).map(|p| p.item.id));
+                }
+            }
+            // Rotate the held item, or the one under the cursor
+            KeyCode::Char('r') => {
+                let target = self.grid_held.or_else(|| game.world()
+                    .get::<&InventoryComponent>(player)
+                    .ok()
+                    .and_then(|inv| inv.inventory.get_placed_at(x, y).map(|p| p.item.id)));
+                if let Some(id) = target {
+                    let rotated = game.world_mut()
+                        .get::<&mut InventoryComponent>(player)
+                        .map(|mut inv| inv.inventory.rotate_item(id))
+                        .unwrap_or(false);
+                    if !rotated {
+                        game.play_sound(SoundId::Error);
+                        game.add_message("No room to turn it.", MessageCategory::Warning);
+                    }
+                }
+            }
+            KeyCode::Char('u') | KeyCode::Enter | KeyCode::Char('d') | KeyCode::Char('f') | KeyCode::Char('6'..='9') => {
+                self.grid_held = None;
+                let index = game.world()
+                    .get::<&InventoryComponent>(player)
+                    .ok()
+                    .and_then(|inv| {
+                        let id = inv.inventory.get_placed_at(x, y)?.item.id;
+                        inv.inventory.items().iter().position(|i| i.id == id)
+                    });
+                match index {
+                    Some(i) => {
+                        self.inventory_cursor = i;
+                        return false;
+                    }
+                    None => return true,
+                }
+            }
+            KeyCode::Char('s') => {
+                self.grid_held = None;
+                return false;
+            }
+            _ => return false,
+        }
+        true
+    }
+
     fn handle_character_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
         use crate::ecs::{EquipmentComponent, InventoryComponent, StatPoints, Stats, Health, Mana, SkillsComponent};
         use crate::items::EquipSlot;
@@ -1761,6 +2885,18 @@ impl App {
             EquipSlot::Ring2,
         ];
 
+        // Info mode: step through the derived stats, showing how each is worked out
+        if let Some(selected) = self.stat_info {
+            let count = game.player_derived_stats().map(|d| d.sources.breakdowns().len()).unwrap_or(1);
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('f') => self.stat_info = None,
+                KeyCode::Up | KeyCode::Char('k') => self.stat_info = Some((selected + count - 1) % count),
+                KeyCode::Down | KeyCode::Char('j') => self.stat_info = Some((selected + 1) % count),
+                _ => {}
+            }
+            return Ok(false);
+        }
+
         // Handle skill selection mode (selecting from learned skills to equip)
         if self.skill_selection_mode {
             let player = match game.player() {
@@ -1776,7 +2912,7 @@ impl App {
                         .filter(|(_, skill)| {
                             !sk.skills.slots.iter().any(|s| s.as_ref().map(|eq| eq.id == skill.id).unwrap_or(false))
                         })
-                        .map(|(i, skill)| (i, skill.name.clone(), skill.icon))
+                        .map(|(i, skill)| (i, skill.display_name(), skill.icon))
                         .collect()
                 })
                 .unwrap_or_default();
@@ -1834,7 +2970,7 @@ impl App {
                 .get::<&InventoryComponent>(player)
                 .map(|inv| {
                     inv.inventory.items().iter().enumerate()
-                        .filter(|(_, item)| item.equip_slot == Some(current_slot))
+                        .filter(|(_, item)| item.fits_slot(current_slot))
                         .map(|(i, item)| (i, item.name.clone()))
                         .collect()
                 })
@@ -1857,42 +2993,31 @@ impl App {
                     }
                 }
                 KeyCode::Enter | KeyCode::Right => {
+                    if let Some(mutation) = game.mutation_blocking(current_slot) {
+                        game.play_sound(SoundId::Error);
+                        game.add_message(
+                            format!("Your {} leave no room for that.", mutation.name()),
+                            MessageCategory::Warning,
+                        );
+                        return Ok(false);
+                    }
                     // Equip the selected item
                     if self.equip_selection_cursor < matching_items.len() {
                         let (inv_index, item_name) = matching_items[self.equip_selection_cursor].clone();
-
-                        // Remove from inventory
-                        let item = {
-                            if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                inv.inventory.remove_at(inv_index)
-                            } else {
-                                None
-                            }
-                        };
-
-                        if let Some(item) = item {
-                            // Unequip current item if any
-                            let old_item = {
-                                if let Ok(mut eq) = game.world_mut().get::<&mut EquipmentComponent>(player) {
-                                    eq.equipment.unequip(current_slot)
-                                } else {
-                                    None
-                                }
-                            };
-
-                            // Put old item back in inventory
-                            if let Some(old) = old_item {
-                                if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                    inv.inventory.add_item(old);
+                        let item_id = game.world()
+                            .get::<&InventoryComponent>(player)
+                            .ok()
+                            .and_then(|inv| inv.inventory.get(inv_index).map(|i| i.id));
+
+                        // Equip into the selected slot (one-handed weapons can go in the off hand)
+                        if let Some(item_id) = item_id {
+                            match game.transact(Transaction::new().equip_to(current_slot, item_id)) {
+                                Ok(_) => game.add_message(format!("Equipped {}", item_name), MessageCategory::Item),
+                                Err(e) => {
+                                    game.play_sound(SoundId::Error);
+                                    game.add_message(e.to_string(), MessageCategory::Warning);
                                 }
                             }
-
-                            // Equip new item
-                            if let Ok(mut eq) = game.world_mut().get::<&mut EquipmentComponent>(player) {
-                                eq.equipment.equip(item);
-                            }
-
-                            game.add_message(format!("Equipped {}", item_name), MessageCategory::Item);
                         }
 
                         self.equip_selection_mode = false;
@@ -1908,6 +3033,9 @@ impl App {
             KeyCode::Esc | KeyCode::Char('c') => {
                 game.set_state(GameState::Playing(PlayingState::Exploring));
             }
+            KeyCode::Char('f') => {
+                self.stat_info = Some(0);
+            }
             // Stat point allocation (1=STR, 2=DEX, 3=INT, 4=VIT)
             KeyCode::Char('1') | KeyCode::Char('2') | KeyCode::Char('3') | KeyCode::Char('4') => {
                 let player = match game.player() {
@@ -2023,7 +3151,7 @@ impl App {
                     let has_matching = game.world()
                         .get::<&InventoryComponent>(player)
                         .map(|inv| {
-                            inv.inventory.items().iter().any(|item| item.equip_slot == Some(current_slot))
+                            inv.inventory.items().iter().any(|item| item.fits_slot(current_slot))
                         })
                         .unwrap_or(false);
 
@@ -2071,34 +3199,24 @@ impl App {
                     // Unequip equipment
                     let slot = slots[self.character_slot];
 
-                    let unequipped = {
-                        if let Ok(mut eq) = game.world_mut().get::<&mut EquipmentComponent>(player) {
-                            eq.equipment.unequip(slot)
-                        } else {
-                            None
-                        }
-                    };
-
-                    if let Some(item) = unequipped {
-                        let item_name = item.name.clone();
-                        let added = {
-                            if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                inv.inventory.add_item(item)
-                            } else {
-                                false
+                    let worn = game.world()
+                        .get::<&EquipmentComponent>(player)
+                        .is_ok_and(|eq| !eq.equipment.is_empty(slot));
+                    if worn {
+                        match game.transact(Transaction::new().unequip(slot)) {
+                            Ok(receipt) => {
+                                let item_name = receipt.unequipped.first().map(|i| i.name.clone()).unwrap_or_default();
+                                game.add_message(
+                                    format!("Unequipped {}", item_name),
+                                    MessageCategory::Item,
+                                );
+                            }
+                            Err(_) => {
+                                game.add_message(
+                                    "Inventory full! Cannot unequip.".to_string(),
+                                    MessageCategory::Warning,
+                                );
                             }
-                        };
-
-                        if added {
-                            game.add_message(
-                                format!("Unequipped {}", item_name),
-                                MessageCategory::Item,
-                            );
-                        } else {
-                            game.add_message(
-                                "Inventory full! Cannot unequip.".to_string(),
-                                MessageCategory::Warning,
-                            );
                         }
                     }
                 } else {
@@ -2126,6 +3244,71 @@ impl App {
         Ok(false)
     }
 
+    fn handle_pickup_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        use crate::ecs::GroundItem;
+
+        // Anything picked up or carried off since the menu opened drops out of it
+        self.pickup_choices.retain(|(e, _)| game.world().get::<&GroundItem>(*e).is_ok());
+        let len = self.pickup_choices.len();
+        if len == 0 {
+            game.set_state(GameState::Playing(PlayingState::Exploring));
+            return Ok(false);
+        }
+        self.pickup_cursor = self.pickup_cursor.min(len - 1);
+
+        match key.code {
+            KeyCode::Esc => {
+                game.set_state(GameState::Playing(PlayingState::Exploring));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.pickup_cursor = self.pickup_cursor.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.pickup_cursor + 1 < len => {
+                self.pickup_cursor += 1;
+            }
+            // Mark or unmark the item under the cursor
+            KeyCode::Char(' ') => {
+                let marked = &mut self.pickup_choices[self.pickup_cursor].1;
+                *marked = !*marked;
+                if self.pickup_cursor + 1 < len {
+                    self.pickup_cursor += 1;
+                }
+            }
+            // Mark everything, or clear the marks if all are marked
+            KeyCode::Char('a') => {
+                let all = self.pickup_choices.iter().all(|(_, marked)| *marked);
+                for (_, marked) in &mut self.pickup_choices {
+                    *marked = !all;
+                }
+            }
+            // Take the marked items, or the one under the cursor if none are marked
+            KeyCode::Enter | KeyCode::Char('g') => {
+                let mut chosen: Vec<hecs::Entity> = self.pickup_choices.iter()
+                    .filter(|(_, marked)| *marked)
+                    .map(|(e, _)| *e)
+                    .collect();
+                if chosen.is_empty() {
+                    chosen.push(self.pickup_choices[self.pickup_cursor].0);
+                }
+
+                for entity in chosen {
+                    let item = game.world().get::<&GroundItem>(entity).map(|gi| gi.item.clone());
+                    if let Ok(item) = item {
+                        if !self.take_or_warn(game, entity, item) {
+                            break;
+                        }
+                    }
+                }
+
+                self.pickup_choices.clear();
+                game.set_state(GameState::Playing(PlayingState::Exploring));
+                self.open_nearby_chests(game);
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
     fn handle_mapview_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
         match key.code {
             KeyCode::Esc | KeyCode::Char('m') => {
@@ -2136,6 +3319,13 @@ impl App {
         Ok(false)
     }
 
+    fn handle_discoveries_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        if matches!(key.code, KeyCode::Esc | KeyCode::Char('D')) {
+            game.set_state(GameState::Playing(PlayingState::Exploring));
+        }
+        Ok(false)
+    }
+
     fn handle_help_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
         const HELP_LINES: u16 = 90; // Approximate number of lines in help
 
@@ -2227,15 +3417,248 @@ impl App {
         items
     }
 
+    /// Make an offering at a gambling shrine
+    fn gamble_at_shrine(&mut self, game: &mut Game) {
+        use crate::ecs::{InventoryComponent, GroundItem, Renderable, StatusEffects, StatusEffect, StatusEffectType};
+        use crate::game::{GambleOutcome, roll_gamble};
+        use crate::items::{generate_consumable, generate_weapon, generate_armor};
+
+        let (player, player_pos) = match (game.player(), game.player_position()) {
+            (Some(p), Some(pos)) => (p, pos),
+            _ => return,
+        };
+
+        let cost = game.shrine_gamble_cost();
+        if !game.pay_shrine(cost) {
+            game.play_sound(SoundId::Error);
+            self.shrine_last_outcome = Some(format!("You need {} gold to make an offering.", cost));
+            return;
+        }
+
+        // The first offering counts as using the shrine
+        if !game.is_shrine_used(player_pos) {
+            game.mark_shrine_used(player_pos);
+        }
+        game.play_sound(SoundId::ShrineUse);
+
+        let outcome = roll_gamble(cost, game.rng());
+        let mut description = outcome.description();
+        match outcome {
+            GambleOutcome::Nothing => {}
+            GambleOutcome::Curse => {
+                if let Ok(mut effects) = game.world_mut().get::<&mut StatusEffects>(player) {
+                    effects.effects.push(StatusEffect {
+                        effect_type: StatusEffectType::Weakness,
+                        duration: 30.0,
+                        intensity: 15,
+                    });
+                }
+            }
+            GambleOutcome::Gold(amount) | GambleOutcome::Jackpot(amount) => {
+                if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
+                    inv.inventory.add_gold(amount);
+                }
+                game.play_sound(SoundId::GoldPickup);
+                game.record_gold_collected(amount);
+            }
+            GambleOutcome::Consumable | GambleOutcome::Equipment => {
+                let floor = game.floor();
+                let item = if outcome == GambleOutcome::Consumable {
+                    generate_consumable(game.rng())
+                } else if game.rng().gen_bool(0.5) {
+                    generate_weapon(floor + 2, game.rng())
+                } else {
+                    generate_armor(floor + 2, game.rng())
+                };
+                description = format!("{} {} [{}]", description, item.name, item.rarity.name());
+
+                let base_name = item.base_name.clone();
+                let leftover = match game.world_mut().get::<&mut InventoryComponent>(player) {
+                    Ok(mut inv) => {
+                        if inv.inventory.add_item(item.clone()) { None } else { Some(item) }
+                    }
+                    Err(_) => Some(item),
+                };
+                if let Some(item) = leftover {
+                    // No room in the pack - leave it at the shrine
+                    game.world_mut().spawn((
+                        player_pos,
+                        Renderable::new(item.glyph, item.rarity.color()).with_order(80),
+                        GroundItem { item },
+                    ));
+                    description.push_str(" (dropped at your feet)");
+                } else {
+                    game.play_sound(SoundId::ItemPickup);
+                    game.record_item_found(&base_name);
+                }
+            }
+        }
+
+        let category = if outcome == GambleOutcome::Curse { MessageCategory::Warning } else { MessageCategory::Item };
+        game.add_message(description.clone(), category);
+        self.shrine_last_outcome = Some(description);
+    }
+
+    /// Destroy the selected equipped item for a permanent stat increase
+    fn sacrifice_at_shrine(&mut self, game: &mut Game) {
+        use crate::ecs::{Stats, Health, Mana};
+        use crate::game::{SacrificeStat, sacrifice_boon};
+
+        let items = self.get_equipped_items_for_enchant(game);
+        let Some((slot, _, _, _)) = items.get(self.shrine_item_cursor).cloned() else {
+            game.add_message("You have nothing worth sacrificing.".to_string(), MessageCategory::Warning);
+            return;
+        };
+        let (player, player_pos) = match (game.player(), game.player_position()) {
+            (Some(p), Some(pos)) => (p, pos),
+            _ => return,
+        };
+
+        let item = match game.transact(Transaction::new().strip(slot)) {
+            Ok(receipt) => receipt.handed_over.into_iter().next(),
+            Err(crate::items::TransactionError::Locked) => {
+                game.play_sound(SoundId::Error);
+                game.add_message("That item is locked. Unlock it before offering it.".to_string(), MessageCategory::Warning);
+                return;
+            }
+            Err(_) => None,
+        };
+        let Some(item) = item else { return };
+
+        let (stat, amount) = sacrifice_boon(&item, slot);
+        if let Ok(mut stats) = game.world_mut().get::<&mut Stats>(player) {
+            match stat {
+                SacrificeStat::Strength => stats.strength += amount,
+                SacrificeStat::Dexterity => stats.dexterity += amount,
+                SacrificeStat::Intelligence => stats.intelligence += amount,
+                SacrificeStat::Vitality => stats.vitality += amount,
+            }
+        }
+        // Same derived bonuses as spending stat points
+        match stat {
+            SacrificeStat::Vitality => {
+                if let Ok(mut hp) = game.world_mut().get::<&mut Health>(player) {
+                    hp.max += 5 * amount;
+                    hp.current += 5 * amount;
+                }
+            }
+            SacrificeStat::Intelligence => {
+                if let Ok(mut mp) = game.world_mut().get::<&mut Mana>(player) {
+                    mp.max += 3 * amount;
+                    mp.current += 3 * amount;
+                }
+            }
+            _ => {}
+        }
+
+        game.play_sound(SoundId::ShrineUse);
+        game.add_message(
+            format!("† The shrine consumes your {}. +{} {} permanently!", item.display_name(), amount, stat.name()),
+            MessageCategory::Item,
+        );
+        game.mark_shrine_used(player_pos);
+        self.shrine_item_cursor = 0;
+        game.set_state(GameState::Playing(PlayingState::Exploring));
+    }
+
+    /// Reroll the affixes of the selected equipped item, corrupting it
+    fn transmute_at_shrine(&mut self, game: &mut Game) {
+        use crate::ecs::EquipmentComponent;
+        use crate::game::{can_transmute, transmute_item};
+
+        let items = self.get_equipped_items_for_enchant(game);
+        let Some((slot, _, _, _)) = items.get(self.shrine_item_cursor).cloned() else {
+            game.add_message("You have nothing to transmute.".to_string(), MessageCategory::Warning);
+            return;
+        };
+        let (player, player_pos) = match (game.player(), game.player_position()) {
+            (Some(p), Some(pos)) => (p, pos),
+            _ => return,
+        };
+
+        // Work on a copy so the game's rng can be borrowed for the reroll
+        let item = game.world()
+            .get::<&EquipmentComponent>(player)
+            .ok()
+            .and_then(|eq| eq.equipment.get(slot).cloned());
+        let Some(mut item) = item else { return };
+
+        if !can_transmute(&item) {
+            game.play_sound(SoundId::Error);
+            game.add_message("That item is too corrupted to transmute further!".to_string(), MessageCategory::Warning);
+            return;
+        }
+
+        let old_name = item.display_name();
+        transmute_item(&mut item, game.rng());
+        let new_name = item.display_name();
+        if let Ok(mut eq) = game.world_mut().get::<&mut EquipmentComponent>(player) {
+            if let Some(equipped) = eq.equipment.get_mut(slot) {
+                *equipped = item;
+            }
+        }
+
+        game.play_sound(SoundId::ShrineUse);
+        game.add_message(
+            format!("⚗ {} twists into {}!", old_name, new_name),
+            MessageCategory::Item,
+        );
+        game.mark_shrine_used(player_pos);
+        self.shrine_item_cursor = 0;
+        game.set_state(GameState::Playing(PlayingState::Exploring));
+    }
+
     /// Check if an equip slot is a weapon slot
     fn is_weapon_slot(slot: crate::items::EquipSlot) -> bool {
         use crate::items::EquipSlot;
         matches!(slot, EquipSlot::MainHand | EquipSlot::OffHand)
     }
 
+    /// Handle worship at an altar
+    fn handle_altar_input(&mut self, key: KeyEvent, game: &mut Game, deity: crate::game::Deity) {
+        use crate::ecs::InventoryComponent;
+
+        let item_count = game.player()
+            .and_then(|p| game.world().get::<&InventoryComponent>(p).ok())
+            .map(|inv| inv.inventory.count())
+            .unwrap_or(0);
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.shrine_item_cursor = self.shrine_item_cursor.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.shrine_item_cursor + 1 < item_count => {
+                self.shrine_item_cursor += 1;
+            }
+            KeyCode::Enter | KeyCode::Char(' ') if game.offer_item(deity, self.shrine_item_cursor) => {
+                self.shrine_item_cursor = self.shrine_item_cursor.min(item_count.saturating_sub(2));
+            }
+            KeyCode::Char('1') => game.dedicate_to(deity),
+            KeyCode::Char('2') if !game.offer_gold(deity) => {
+                game.play_sound(SoundId::Error);
+                game.add_message("You cannot afford an offering.".to_string(), MessageCategory::Warning);
+            }
+            KeyCode::Char('3') => {
+                if let Some(pos) = game.player_position() {
+                    self.shrine_item_cursor = 0;
+                    game.set_state(GameState::Playing(PlayingState::Exploring));
+                    game.desecrate_altar(deity, pos);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn handle_shrine_input(&mut self, key: KeyEvent, game: &mut Game, shrine_type: ShrineType) -> Result<bool> {
         use crate::ecs::{SkillsComponent, StatusEffects, StatusEffect, StatusEffectType};
 
+        if let ShrineType::Altar(deity) = shrine_type {
+            if key.code != KeyCode::Esc {
+                self.handle_altar_input(key, game, deity);
+                return Ok(false);
+            }
+        }
+
         match key.code {
             KeyCode::Esc => {
                 // Check if we're in skill swap mode - cancel swap and go back to skill selection
@@ -2264,8 +3687,30 @@ impl App {
                 self.shrine_skill_swap_cursor = 0;
                 self.shrine_pending_skill = None;
                 self.shrine_skills.clear();
+                self.shrine_item_cursor = 0;
+                self.shrine_last_outcome = None;
                 game.set_state(GameState::Playing(PlayingState::Exploring));
             }
+            // Gambling shrine: make an offering
+            KeyCode::Enter | KeyCode::Char(' ') if shrine_type == ShrineType::Gambling => {
+                self.gamble_at_shrine(game);
+            }
+            // Sacrifice/transmutation shrines: choose an equipped item
+            KeyCode::Up | KeyCode::Char('k') if matches!(shrine_type, ShrineType::Sacrifice | ShrineType::Transmutation) => {
+                self.shrine_item_cursor = self.shrine_item_cursor.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if matches!(shrine_type, ShrineType::Sacrifice | ShrineType::Transmutation) => {
+                let count = self.get_equipped_items_for_enchant(game).len();
+                if self.shrine_item_cursor + 1 < count {
+                    self.shrine_item_cursor += 1;
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') if shrine_type == ShrineType::Sacrifice => {
+                self.sacrifice_at_shrine(game);
+            }
+            KeyCode::Enter | KeyCode::Char(' ') if shrine_type == ShrineType::Transmutation => {
+                self.transmute_at_shrine(game);
+            }
             // Corruption shrine pacts (1-3 to select)
             KeyCode::Char('1') | KeyCode::Char('2') | KeyCode::Char('3') if shrine_type == ShrineType::Corruption => {
                 let pact_idx = match key.code {
@@ -2379,6 +3824,26 @@ impl App {
                         self.shrine_skill_swap_mode = false;
                         game.set_state(GameState::Playing(PlayingState::Exploring));
                     }
+                } else if self.shrine_skills.get(self.shrine_skill_cursor).is_some_and(|s| s.rank > 1) {
+                    // Upgrade a skill the player already knows
+                    let skill = self.shrine_skills[self.shrine_skill_cursor].clone();
+                    let skill_name = skill.display_name();
+                    let upgraded = game.player()
+                        .and_then(|p| game.world_mut().get::<&mut SkillsComponent>(p).ok()
+                            .map(|mut sc| sc.skills.upgrade(skill)))
+                        .unwrap_or(false);
+                    if upgraded {
+                        game.play_sound(SoundId::ShrineUse);
+                        game.add_message(
+                            format!("Your skill grows stronger: {}!", skill_name),
+                            MessageCategory::System
+                        );
+                        if let Some(pos) = game.player_position() {
+                            game.mark_shrine_used(pos);
+                        }
+                        self.shrine_skills.clear();
+                        game.set_state(GameState::Playing(PlayingState::Exploring));
+                    }
                 } else if self.shrine_skill_cursor < self.shrine_skills.len() {
                     // Normal mode: try to learn the skill
                     let skill = self.shrine_skills[self.shrine_skill_cursor].clone();
@@ -2531,7 +3996,7 @@ impl App {
 
                 // Special case: +1 max enchantment slot (only if rare upgrade available)
                 if self.enchant_affix_cursor == 6 && self.enchant_upgrade_available {
-                    let cost = 200u32;
+                    let cost = game.shrine_markup().price(200);
                     let gold = game.player()
                         .and_then(|p| game.world().get::<&crate::ecs::InventoryComponent>(p).ok())
                         .map(|inv| inv.inventory.gold())
@@ -2556,11 +4021,7 @@ impl App {
 
                     match result {
                         Some(Ok((name, new_max, cost))) => {
-                            if let Some(player) = game.player() {
-                                if let Ok(mut inv) = game.world_mut().get::<&mut crate::ecs::InventoryComponent>(player) {
-                                    inv.inventory.spend_gold(cost);
-                                }
-                            }
+                            game.pay_shrine(cost);
                             game.add_message(format!("✦ {} can now hold {} enchantments!", name, new_max), MessageCategory::Item);
                             if let Some(pos) = game.player_position() {
                                 game.mark_shrine_used(pos);
@@ -2571,7 +4032,7 @@ impl App {
                             game.set_state(GameState::Playing(PlayingState::Exploring));
                         }
                         Some(Err("no_gold")) => {
-                            game.add_message("Not enough gold! (200g required)".to_string(), MessageCategory::Warning);
+                            game.add_message(format!("Not enough gold! ({}g required)", cost), MessageCategory::Warning);
                         }
                         _ => {}
                     }
@@ -2584,6 +4045,7 @@ impl App {
 
                 if is_endgame_option && !self.enchant_swap_mode {
                     let option_index = self.enchant_affix_cursor - base_option;
+                    let markup = game.shrine_markup();
                     let gold = game.player()
                         .and_then(|p| game.world().get::<&crate::ecs::InventoryComponent>(p).ok())
                         .map(|inv| inv.inventory.gold())
@@ -2595,7 +4057,7 @@ impl App {
                             let result = if let Some(player) = game.player() {
                                 if let Ok(mut equip) = game.world_mut().get::<&mut EquipmentComponent>(player) {
                                     if let Some(item) = equip.equipment.get_mut(target_slot) {
-                                        let cost = item.enchant_cost();
+                                        let cost = markup.price(item.enchant_cost());
                                         if gold < cost {
                                             Some(Err("no_gold"))
                                         } else if item.enchantment_level >= 15 {
@@ -2610,11 +4072,7 @@ impl App {
 
                             match result {
                                 Some(Ok((name, cost, level))) => {
-                                    if let Some(player) = game.player() {
-                                        if let Ok(mut inv) = game.world_mut().get::<&mut crate::ecs::InventoryComponent>(player) {
-                                            inv.inventory.spend_gold(cost);
-                                        }
-                                    }
+                                    game.pay_shrine(cost);
                                     game.add_message(format!("⚔ {} is now +{}! (+10% stats)", name, level), MessageCategory::Item);
                                 }
                                 Some(Err("no_gold")) => {
@@ -2631,7 +4089,7 @@ impl App {
                             let result = if let Some(player) = game.player() {
                                 if let Ok(mut equip) = game.world_mut().get::<&mut EquipmentComponent>(player) {
                                     if let Some(item) = equip.equipment.get_mut(target_slot) {
-                                        let cost = item.awakening_cost();
+                                        let cost = markup.price(item.awakening_cost());
                                         if gold < cost {
                                             Some(Err("no_gold"))
                                         } else {
@@ -2644,11 +4102,7 @@ impl App {
 
                             match result {
                                 Some(Ok((name, cost, level))) => {
-                                    if let Some(player) = game.player() {
-                                        if let Ok(mut inv) = game.world_mut().get::<&mut crate::ecs::InventoryComponent>(player) {
-                                            inv.inventory.spend_gold(cost);
-                                        }
-                                    }
+                                    game.pay_shrine(cost);
                                     game.add_message(format!("✦ {} awakened to tier {}! (+10% all stats)", name, level), MessageCategory::Item);
                                 }
                                 Some(Err("no_gold")) => {
@@ -2662,7 +4116,7 @@ impl App {
                             let result = if let Some(player) = game.player() {
                                 if let Ok(mut equip) = game.world_mut().get::<&mut EquipmentComponent>(player) {
                                     if let Some(item) = equip.equipment.get_mut(target_slot) {
-                                        let socket_cost = 300 + (item.sockets.len() as u32 * 200);
+                                        let socket_cost = markup.price(300 + (item.sockets.len() as u32 * 200));
                                         if gold < socket_cost {
                                             Some(Err("no_gold"))
                                         } else if !item.add_socket() {
@@ -2676,11 +4130,7 @@ impl App {
 
                             match result {
                                 Some(Ok((name, cost, sockets))) => {
-                                    if let Some(player) = game.player() {
-                                        if let Ok(mut inv) = game.world_mut().get::<&mut crate::ecs::InventoryComponent>(player) {
-                                            inv.inventory.spend_gold(cost);
-                                        }
-                                    }
+                                    game.pay_shrine(cost);
                                     game.add_message(format!("◇ Added socket to {}! ({} sockets total)", name, sockets), MessageCategory::Item);
                                 }
                                 Some(Err("no_gold")) => {
@@ -2697,7 +4147,7 @@ impl App {
                             let result = if let Some(player) = game.player() {
                                 if let Ok(mut equip) = game.world_mut().get::<&mut EquipmentComponent>(player) {
                                     if let Some(item) = equip.equipment.get_mut(target_slot) {
-                                        let cost = item.corruption_cost();
+                                        let cost = markup.price(item.corruption_cost());
                                         if gold < cost {
                                             Some(Err("no_gold"))
                                         } else if item.corruption_level >= 10 {
@@ -2712,11 +4162,7 @@ impl App {
 
                             match result {
                                 Some(Ok((name, cost, level))) => {
-                                    if let Some(player) = game.player() {
-                                        if let Ok(mut inv) = game.world_mut().get::<&mut crate::ecs::InventoryComponent>(player) {
-                                            inv.inventory.spend_gold(cost);
-                                        }
-                                    }
+                                    game.pay_shrine(cost);
                                     game.add_message(format!("☠ {} corrupted to {{C{}}}! (+15% dmg, -5% HP)", name, level), MessageCategory::Item);
                                 }
                                 Some(Err("no_gold")) => {
@@ -2736,6 +4182,7 @@ impl App {
                 let selected_enchant = enchantments.get(self.enchant_affix_cursor).cloned();
 
                 if let Some((affix_type, cost, value)) = selected_enchant {
+                    let cost = game.shrine_markup().price(cost);
                     let result = if let Some(player) = game.player() {
                         let gold = game.world().get::<&crate::ecs::InventoryComponent>(player)
                             .map(|inv| inv.inventory.gold())
@@ -2788,11 +4235,7 @@ impl App {
 
                     match result {
                         Some(Ok((mode, item_name, spent, old_value))) => {
-                            if let Some(player) = game.player() {
-                                if let Ok(mut inv) = game.world_mut().get::<&mut crate::ecs::InventoryComponent>(player) {
-                                    inv.inventory.spend_gold(spent);
-                                }
-                            }
+                            game.pay_shrine(spent);
                             let msg = match mode {
                                 "upgrade" => format!("✦ Upgraded {} on {}! ({} → {})", affix_type.name(), item_name, old_value, value),
                                 "swap" => format!("✦ Swapped enchantment on {}! (+{} {})", item_name, affix_type.name(), value),
@@ -2828,11 +4271,280 @@ impl App {
         Ok(false)
     }
 
-    fn handle_shop_input(&mut self, key: KeyEvent, game: &mut Game, npc_entity: hecs::Entity) -> Result<bool> {
+    /// Sell the given inventory items to a merchant. Locked items are skipped.
+    /// Sold items wait on the buyback tab until the player leaves the shop.
+    fn sell_items(&mut self, game: &mut Game, npc_entity: hecs::Entity, ids: &[crate::items::ItemId]) {
         use crate::entities::NpcComponent;
+        use crate::entities::npcs::{ShopItem, BUYBACK_SLOTS};
         use crate::ecs::InventoryComponent;
+
+        let player = match game.player() {
+            Some(p) => p,
+            None => return,
+        };
+        let standing = shop_standing(game, npc_entity);
+        let economy = game.data().economy().clone();
+
+        let for_sale: Vec<(crate::items::ItemId, u32)> = game.world()
+            .get::<&InventoryComponent>(player)
+            .map(|inv| ids.iter()
+                .filter_map(|&id| inv.inventory.get_by_id(id))
+                .filter(|item| !item.locked)
+                .map(|item| (item.id, standing.sell_price(economy.sell_price(item))))
+                .collect())
+            .unwrap_or_default();
+        if for_sale.is_empty() {
+            return;
+        }
+        let transaction = for_sale.iter()
+            .fold(Transaction::new(), |transaction, &(id, price)| transaction.hand_over(id).earn(price));
+        let Ok(receipt) = game.transact(transaction) else { return };
+        for (id, _) in &for_sale {
+            self.sell_marked.remove(id);
+        }
+        let sold: Vec<_> = receipt.handed_over.into_iter()
+            .zip(for_sale.iter().map(|&(_, price)| price))
+            .collect();
+
+        let total: u32 = sold.iter().map(|(_, price)| price).sum();
+        let message = match sold.as_slice() {
+            [(item, price)] => format!("Sold {} for {} gold.", game.discoveries().disguise(item).name, price),
+            _ => format!("Sold {} items for {} gold.", sold.len(), total),
+        };
+
+        self.buyback.extend(sold);
+        let overflow = self.buyback.len().saturating_sub(BUYBACK_SLOTS);
+        let mut final_gold = 0;
+        if let Ok(mut npc) = game.world_mut().get::<&mut NpcComponent>(npc_entity) {
+            npc.gold = npc.gold.saturating_sub(total);
+            for (item, price) in self.buyback.drain(..overflow) {
+                final_gold += price;
+                npc.shop_items.push(ShopItem::new(item));
+            }
+        }
+
+        // Keep the cursor on the list
+        let remaining = game.world()
+            .get::<&InventoryComponent>(player)
+            .map(|inv| inv.inventory.count())
+            .unwrap_or(0);
+        if self.sell_selection >= remaining {
+            self.sell_selection = remaining.saturating_sub(1);
+        }
+
+        game.add_message(message, MessageCategory::Item);
+        // Sales only count once they can no longer be bought back
+        if final_gold > 0 {
+            game.record_gold_collected(final_gold);
+        }
+    }
+
+    /// Buy back the selected item sold this visit, for what the merchant paid
+    fn buy_back(&mut self, game: &mut Game, npc_entity: hecs::Entity) {
+        use crate::entities::NpcComponent;
+
+        let Some((item, price)) = self.buyback.get(self.buyback_selection).cloned() else {
+            return;
+        };
+
+        match game.transact(Transaction::new().receive(item.clone()).pay(price)) {
+            Ok(_) => {
+                if let Ok(mut npc) = game.world_mut().get::<&mut NpcComponent>(npc_entity) {
+                    npc.gold += price;
+                }
+                self.buyback.remove(self.buyback_selection);
+                if self.buyback_selection >= self.buyback.len() {
+                    self.buyback_selection = self.buyback.len().saturating_sub(1);
+                }
+                game.add_message(format!("Bought back {} for {} gold.", game.discoveries().disguise(&item).name, price), MessageCategory::Item);
+            }
+            Err(e) => game.add_message(e.to_string(), MessageCategory::Warning),
+        }
+    }
+
+    /// Everything the merchant on this floor can do for the player right now
+    fn service_offers(game: &Game) -> Vec<ServiceOffer> {
+        use crate::entities::npcs::{merchant_services, identify_price, gamble_price, MerchantService};
+        use crate::ecs::{InventoryComponent, EquipmentComponent};
+        use crate::items::EquipSlot;
+
+        let Some(player) = game.player() else { return Vec::new() };
+        let mut offers = Vec::new();
+        for service in merchant_services(game.floor()) {
+            match service {
+                MerchantService::Identify => {
+                    if let Ok(eq) = game.world().get::<&EquipmentComponent>(player) {
+                        let slots = [
+                            EquipSlot::MainHand, EquipSlot::OffHand, EquipSlot::Head,
+                            EquipSlot::Body, EquipSlot::Hands, EquipSlot::Feet,
+                            EquipSlot::Amulet, EquipSlot::Ring1, EquipSlot::Ring2,
+                        ];
+                        for slot in slots {
+                            if let Some(item) = eq.equipment.get(slot).filter(|i| !i.is_identified()) {
+                                offers.push(ServiceOffer {
+                                    service,
+                                    target: Some(ServiceTarget::Worn(slot)),
+                                    label: format!("{} (worn)", item.name),
+                                    price: identify_price(item),
+                                });
+                            }
+                        }
+                    }
+                    if let Ok(inv) = game.world().get::<&InventoryComponent>(player) {
+                        for item in inv.inventory.items().into_iter().filter(|i| !i.is_identified()) {
+                            offers.push(ServiceOffer {
+                                service,
+                                target: Some(ServiceTarget::Pack(item.id)),
+                                label: item.name.clone(),
+                                price: identify_price(item),
+                            });
+                        }
+                        let mut kinds = std::collections::HashSet::new();
+                        for item in inv.inventory.items() {
+                            let Some(appearance) = game.discoveries().appearance(&item.name) else { continue };
+                            if kinds.insert(item.name.clone()) {
+                                offers.push(ServiceOffer {
+                                    service,
+                                    target: Some(ServiceTarget::Kind(item.name.clone())),
+                                    label: appearance.to_string(),
+                                    price: identify_price(item),
+                                });
+                            }
+                        }
+                    }
+                }
+                MerchantService::GambleWeapon | MerchantService::GambleArmor => {
+                    offers.push(ServiceOffer {
+                        service,
+                        target: None,
+                        label: service.name().to_string(),
+                        price: gamble_price(game.floor()),
+                    });
+                }
+            }
+        }
+        offers
+    }
+
+    /// Pay for a merchant service
+    fn perform_service(&mut self, game: &mut Game, npc_entity: hecs::Entity, offer: ServiceOffer) {
+        use crate::entities::NpcComponent;
+        use crate::entities::npcs::gamble_item;
+        use crate::ecs::{InventoryComponent, EquipmentComponent};
+
+        let Some(player) = game.player() else { return };
+        let gold = game.world()
+            .get::<&InventoryComponent>(player)
+            .map(|inv| inv.inventory.gold())
+            .unwrap_or(0);
+        if gold < offer.price {
+            game.play_sound(SoundId::Error);
+            game.add_message("Not enough gold!".to_string(), MessageCategory::Warning);
+            return;
+        }
+
+        let (message, transaction) = match offer.target {
+            Some(target) => {
+                // Identify
+                let revealed = match target {
+                    ServiceTarget::Pack(id) => game.world_mut()
+                        .get::<&mut InventoryComponent>(player)
+                        .ok()
+                        .and_then(|mut inv| {
+                            let item = inv.inventory.grid_mut().get_by_id_mut(id)?;
+                            item.identify().then(|| item.name.clone())
+                        }),
+                    ServiceTarget::Worn(slot) => game.world_mut()
+                        .get::<&mut EquipmentComponent>(player)
+                        .ok()
+                        .and_then(|mut eq| {
+                            let item = eq.equipment.get_mut(slot)?;
+                            item.identify().then(|| item.name.clone())
+                        }),
+                    ServiceTarget::Kind(name) => {
+                        if game.discoveries().is_known(&name) {
+                            return;
+                        }
+                        game.discover_item(&name);
+                        Some(name)
+                    }
+                };
+                let Some(name) = revealed else { return };
+                (format!("The merchant squints at it... it's {}!", name), Transaction::new())
+            }
+            None => {
+                // Gamble
+                let floor = game.floor();
+                let Some(item) = gamble_item(offer.service, floor, game.rng()) else { return };
+                (format!("You hand over the gold and receive an {}.", item.name), Transaction::new().receive(item))
+            }
+        };
+
+        if let Err(e) = game.transact(transaction.pay(offer.price)) {
+            game.play_sound(SoundId::Error);
+            game.add_message(e.to_string(), MessageCategory::Warning);
+            return;
+        }
+        if let Ok(mut npc) = game.world_mut().get::<&mut NpcComponent>(npc_entity) {
+            npc.gold += offer.price;
+        }
+        game.add_message(format!("{} ({} gold)", message, offer.price), MessageCategory::Item);
+
+        let remaining = Self::service_offers(game).len();
+        if self.service_selection >= remaining {
+            self.service_selection = remaining.saturating_sub(1);
+        }
+    }
+
+    /// Close the shop, putting whatever wasn't bought back on general sale
+    fn leave_shop(&mut self, game: &mut Game, npc_entity: hecs::Entity) {
+        use crate::entities::NpcComponent;
         use crate::entities::npcs::ShopItem;
 
+        self.shop_selection = 0;
+        self.sell_selection = 0;
+        self.buyback_selection = 0;
+        self.service_selection = 0;
+        self.shop_mode = 0;
+        self.sell_marked.clear();
+        let mut final_gold = 0;
+        if let Ok(mut npc) = game.world_mut().get::<&mut NpcComponent>(npc_entity) {
+            for (item, price) in self.buyback.drain(..) {
+                final_gold += price;
+                npc.shop_items.push(ShopItem::new(item));
+            }
+        }
+        if final_gold > 0 {
+            game.record_gold_collected(final_gold);
+        }
+        game.set_state(GameState::Playing(PlayingState::Exploring));
+    }
+
+    /// Pick a stake and throw dice with the gambler
+    fn handle_dice_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        let stakes = game.dice_stakes();
+        match key.code {
+            KeyCode::Esc => game.set_state(GameState::Playing(PlayingState::Exploring)),
+            KeyCode::Left | KeyCode::Char('h') => self.dice_stake = self.dice_stake.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.dice_stake = (self.dice_stake + 1).min(stakes.len().saturating_sub(1));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let Some(&stake) = stakes.get(self.dice_stake) else { return Ok(false) };
+                match game.play_dice(stake) {
+                    Some(throw) => self.dice_last = Some(throw),
+                    None => game.add_message(format!("You need {} gold to cover that stake.", stake), MessageCategory::Warning),
+                }
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn handle_shop_input(&mut self, key: KeyEvent, game: &mut Game, npc_entity: hecs::Entity) -> Result<bool> {
+        use crate::entities::NpcComponent;
+        use crate::ecs::InventoryComponent;
+
         // Get shop item count (for buy mode)
         let shop_item_count = game.world()
             .get::<&NpcComponent>(npc_entity)
@@ -2846,18 +4558,54 @@ impl App {
             .unwrap_or(0);
 
         match key.code {
-            KeyCode::Esc => {
-                self.shop_selection = 0;
-                self.sell_selection = 0;
-                self.shop_mode = 0;
-                game.set_state(GameState::Playing(PlayingState::Exploring));
+            KeyCode::Esc => self.leave_shop(game, npc_entity),
+            // Turn on a passing peddler for their pack
+            KeyCode::Char('X') if game.world().get::<&crate::entities::Peddler>(npc_entity).is_ok() => {
+                self.leave_shop(game, npc_entity);
+                game.rob_peddler(npc_entity);
+                game.run_ai_tick();
             }
             KeyCode::Tab => {
-                // Switch between Buy (0) and Sell (1)
-                self.shop_mode = if self.shop_mode == 0 { 1 } else { 0 };
+                // Cycle Buy (0) -> Sell (1) -> Buyback (2) -> Services (3)
+                self.shop_mode = (self.shop_mode + 1) % 4;
                 // Reset cursors when switching
                 self.shop_selection = 0;
                 self.sell_selection = 0;
+                self.buyback_selection = 0;
+                self.service_selection = 0;
+            }
+            // Mark / unmark the selected item for selling
+            KeyCode::Char(' ') if self.shop_mode == 1 => {
+                let selected = game.player()
+                    .and_then(|p| game.world().get::<&InventoryComponent>(p).ok())
+                    .and_then(|inv| inv.inventory.get(self.sell_selection).map(|i| (i.id, i.locked)));
+                match selected {
+                    Some((_, true)) => {
+                        game.play_sound(SoundId::Error);
+                        game.add_message("That item is locked.".to_string(), MessageCategory::Warning);
+                    }
+                    Some((id, false)) if !self.sell_marked.remove(&id) => {
+                        self.sell_marked.insert(id);
+                    }
+                    Some((_, false)) | None => {}
+                }
+            }
+            // Mark all unlocked gear below Rare
+            KeyCode::Char('r') if self.shop_mode == 1 => {
+                let junk: Vec<_> = game.player()
+                    .and_then(|p| game.world().get::<&InventoryComponent>(p).ok())
+                    .map(|inv| inv.inventory.items().into_iter()
+                        .filter(|i| i.category.is_equipment() && !i.locked)
+                        .filter(|i| i.rarity.sort_value() < crate::items::Rarity::Rare.sort_value())
+                        .map(|i| i.id)
+                        .collect())
+                    .unwrap_or_default();
+                let count = junk.len();
+                self.sell_marked.extend(junk);
+                game.add_message(
+                    format!("Marked {} piece(s) of gear below Rare. Enter to sell.", count),
+                    MessageCategory::System,
+                );
             }
             KeyCode::Up | KeyCode::Char('k') => {
                 if self.shop_mode == 0 {
@@ -2865,6 +4613,10 @@ impl App {
                     if self.shop_selection > 0 {
                         self.shop_selection -= 1;
                     }
+                } else if self.shop_mode == 2 {
+                    self.buyback_selection = self.buyback_selection.saturating_sub(1);
+                } else if self.shop_mode == 3 {
+                    self.service_selection = self.service_selection.saturating_sub(1);
                 } else {
                     // Sell mode
                     if self.sell_selection > 0 {
@@ -2878,6 +4630,14 @@ impl App {
                     if self.shop_selection + 1 < shop_item_count {
                         self.shop_selection += 1;
                     }
+                } else if self.shop_mode == 2 {
+                    if self.buyback_selection + 1 < self.buyback.len() {
+                        self.buyback_selection += 1;
+                    }
+                } else if self.shop_mode == 3 {
+                    if self.service_selection + 1 < Self::service_offers(game).len() {
+                        self.service_selection += 1;
+                    }
                 } else {
                     // Sell mode
                     if self.sell_selection + 1 < player_item_count {
@@ -2888,61 +4648,20 @@ impl App {
             KeyCode::Enter | KeyCode::Char(' ') => {
                 if self.shop_mode == 0 {
                     // BUY MODE
-                    let result = {
-                        let npc = game.world().get::<&NpcComponent>(npc_entity);
-                        let player = game.player();
-
-                        if let (Ok(npc), Some(player)) = (npc, player) {
-                            if let Some(shop_item) = npc.shop_items.get(self.shop_selection) {
-                                let price = shop_item.buy_price;
-                                let item_name = shop_item.item.name.clone();
-                                let item = shop_item.item.clone();
-
-                                // Check player gold
-                                let gold = game.world()
-                                    .get::<&InventoryComponent>(player)
-                                    .map(|inv| inv.inventory.gold())
-                                    .unwrap_or(0);
-
-                                if gold >= price {
-                                    Some((item, price, item_name, player, self.shop_selection))
-                                } else {
-                                    None // Not enough gold
-                                }
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
-                    };
-
-                    if let Some((item, price, item_name, player, bought_idx)) = result {
-                        // Deduct gold and add item
-                        let purchase_result = {
-                            if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                if inv.inventory.spend_gold(price) {
-                                    if inv.inventory.add_item(item) {
-                                        Some(true) // Success
-                                    } else {
-                                        // Refund gold if inventory full
-                                        inv.inventory.add_gold(price);
-                                        Some(false) // Inventory full
-                                    }
-                                } else {
-                                    None // Couldn't spend gold
-                                }
-                            } else {
-                                None
-                            }
-                        };
-
-                        match purchase_result {
-                            Some(true) => {
+                    let offer = game.world()
+                        .get::<&NpcComponent>(npc_entity)
+                        .ok()
+                        .and_then(|npc| npc.shop_items.get(self.shop_selection).map(|s| s.item.clone()));
+                    if let Some(item) = offer {
+                        let price = shop_standing(game, npc_entity)
+                            .buy_price(game.data().economy().buy_price(&item, game.floor()));
+                        let item_name = game.discoveries().disguise(&item).name.clone();
+                        match game.transact(Transaction::new().pay(price).receive(item)) {
+                            Ok(_) => {
                                 // Remove item from merchant inventory
                                 if let Ok(mut npc) = game.world_mut().get::<&mut NpcComponent>(npc_entity) {
-                                    if bought_idx < npc.shop_items.len() {
-                                        npc.shop_items.remove(bought_idx);
+                                    if self.shop_selection < npc.shop_items.len() {
+                                        npc.shop_items.remove(self.shop_selection);
                                     }
                                 }
                                 // Adjust cursor if needed
@@ -2954,73 +4673,32 @@ impl App {
                                     MessageCategory::Item
                                 );
                             }
-                            Some(false) => {
-                                game.add_message(
-                                    "Inventory full!".to_string(),
-                                    MessageCategory::Warning
-                                );
-                            }
-                            None => {}
+                            Err(e) => game.add_message(e.to_string(), MessageCategory::Warning),
                         }
-                    } else if shop_item_count > 0 {
-                        game.add_message(
-                            "Not enough gold!".to_string(),
-                            MessageCategory::Warning
-                        );
                     }
+                } else if self.shop_mode == 2 {
+                    self.buy_back(game, npc_entity);
+                } else if self.shop_mode == 3 {
+                    if let Some(offer) = Self::service_offers(game).into_iter().nth(self.service_selection) {
+                        self.perform_service(game, npc_entity, offer);
+                    }
+                } else if !self.sell_marked.is_empty() {
+                    let marked: Vec<_> = self.sell_marked.drain().collect();
+                    self.sell_items(game, npc_entity, &marked);
                 } else {
-                    // SELL MODE
-                    let sell_result = {
-                        let player = game.player();
-                        if let Some(player) = player {
-                            if let Ok(inv) = game.world().get::<&InventoryComponent>(player) {
-                                if let Some(&item) = inv.inventory.items().get(self.sell_selection) {
-                                    // Calculate sell price (40% of item value)
-                                    let sell_price = (item.value as f32 * 0.4).max(1.0) as u32;
-                                    Some((item.clone(), sell_price, item.name.clone(), player, self.sell_selection))
-                                } else {
-                                    None
-                                }
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
-                    };
-
-                    if let Some((item, sell_price, item_name, player, sell_idx)) = sell_result {
-                        // Remove item from player inventory and add gold
-                        let removed = {
-                            if let Ok(mut inv) = game.world_mut().get::<&mut InventoryComponent>(player) {
-                                if inv.inventory.remove_at(sell_idx).is_some() {
-                                    inv.inventory.add_gold(sell_price);
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                false
-                            }
-                        };
-
-                        if removed {
-                            // Give item to merchant
-                            if let Ok(mut npc) = game.world_mut().get::<&mut NpcComponent>(npc_entity) {
-                                npc.shop_items.push(ShopItem::new(item));
-                                npc.gold = npc.gold.saturating_sub(sell_price);
-                            }
-                            // Adjust cursor if needed
-                            let new_count = player_item_count.saturating_sub(1);
-                            if self.sell_selection > 0 && self.sell_selection >= new_count {
-                                self.sell_selection = self.sell_selection.saturating_sub(1);
-                            }
+                    let selected = game.player()
+                        .and_then(|p| game.world().get::<&InventoryComponent>(p).ok())
+                        .and_then(|inv| inv.inventory.get(self.sell_selection).map(|i| (i.id, i.locked)));
+                    match selected {
+                        Some((_, true)) => {
+                            game.play_sound(SoundId::Error);
                             game.add_message(
-                                format!("Sold {} for {} gold.", item_name, sell_price),
-                                MessageCategory::Item
+                                "That item is locked. Unlock it in the inventory to sell it.".to_string(),
+                                MessageCategory::Warning,
                             );
-                            game.record_gold_collected(sell_price);
                         }
+                        Some((id, false)) => self.sell_items(game, npc_entity, &[id]),
+                        None => {}
                     }
                 }
             }
@@ -3030,6 +4708,8 @@ impl App {
     }
 
     fn handle_pause_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        use crate::items::Rarity;
+
         match key.code {
             KeyCode::Esc | KeyCode::Char('p') => {
                 game.set_state(GameState::Playing(PlayingState::Exploring));
@@ -3041,6 +4721,75 @@ impl App {
             KeyCode::Char('q') => {
                 game.set_state(GameState::MainMenu);
             }
+            KeyCode::Char('l') => {
+                game.set_state(GameState::Log { scroll: 0 });
+            }
+            // Loot settings
+            KeyCode::Char('a') => {
+                game.update_settings(|s| s.auto_pickup_consumables = !s.auto_pickup_consumables);
+            }
+            KeyCode::Char('g') => {
+                game.update_settings(|s| {
+                    s.auto_pickup_rarity = match s.auto_pickup_rarity {
+                        None => Some(Rarity::Common),
+                        Some(Rarity::Common) => Some(Rarity::Uncommon),
+                        Some(Rarity::Uncommon) => Some(Rarity::Rare),
+                        Some(Rarity::Rare) => Some(Rarity::Epic),
+                        Some(Rarity::Epic) => Some(Rarity::Legendary),
+                        Some(_) => None,
+                    }
+                });
+            }
+            KeyCode::Char('f') => {
+                game.update_settings(|s| s.loot_filter_mode = s.loot_filter_mode.next());
+            }
+            KeyCode::Char('t') => {
+                game.update_settings(|s| {
+                    s.loot_filter_rarity = match s.loot_filter_rarity {
+                        Rarity::Uncommon => Rarity::Rare,
+                        Rarity::Rare => Rarity::Epic,
+                        _ => Rarity::Uncommon,
+                    }
+                });
+            }
+            // Display settings
+            KeyCode::Char('i') => {
+                game.update_settings(|s| s.enemy_overlays = s.enemy_overlays.next());
+            }
+            KeyCode::Char('h') => {
+                game.update_settings(|s| s.enemy_health_bars = !s.enemy_health_bars);
+            }
+            KeyCode::Char('d') => {
+                game.update_settings(|s| s.show_damage_numbers = !s.show_damage_numbers);
+            }
+            KeyCode::Char('z') => {
+                game.update_settings(|s| s.zen_mode = !s.zen_mode);
+            }
+            KeyCode::Char('r') => {
+                game.update_settings(|s| s.speedrun_mode = !s.speedrun_mode);
+            }
+            KeyCode::Char('n') => {
+                game.update_settings(|s| s.show_hints = !s.show_hints);
+            }
+            KeyCode::Char('m') => {
+                game.update_settings(|s| {
+                    s.render_mode = match s.render_mode {
+                        None => Some(RenderMode::Ascii),
+                        Some(RenderMode::Ascii) => Some(RenderMode::Unicode),
+                        Some(RenderMode::Unicode) => Some(RenderMode::NerdFont),
+                        Some(RenderMode::NerdFont) => Some(RenderMode::Kitty),
+                        Some(RenderMode::Kitty) => None,
+                    }
+                });
+                self.set_render_mode(game.profile().settings.render_mode);
+            }
+            KeyCode::Char('k') if game.tutorial_step().is_some() => {
+                game.skip_tutorial();
+                if let Some(map) = game.map() {
+                    self.camera = map.start_pos;
+                }
+                game.set_state(GameState::Playing(PlayingState::Exploring));
+            }
             _ => {}
         }
         Ok(false)
@@ -3066,7 +4815,7 @@ impl App {
                         game.set_state(GameState::Playing(PlayingState::Exploring));
                     }
                     Err(e) => {
-                        game.add_message(&format!("Failed to save: {}", e), crate::game::MessageCategory::System);
+                        game.report_error(e);
                         game.set_state(GameState::Paused);
                     }
                 }
@@ -3075,7 +4824,7 @@ impl App {
                 // Delete save in selected slot
                 if save_exists(selected) {
                     if let Err(e) = crate::save::delete_save(selected) {
-                        game.add_message(&format!("Failed to delete: {}", e), crate::game::MessageCategory::System);
+                        game.report_error(e);
                     }
                 }
             }
@@ -3087,45 +4836,63 @@ impl App {
         Ok(false)
     }
 
+    /// Load a save slot and pick the run up from it, or report why not
+    pub fn load_slot(&mut self, game: &mut Game, slot: u8) {
+        match crate::save::load_game(slot) {
+            Ok(save_data) => {
+                if let Err(e) = game.restore_from_save(save_data) {
+                    game.report_error(e);
+                    game.set_state(GameState::MainMenu);
+                } else {
+                    // Sync camera to player
+                    if let Some(pos) = game.player_position() {
+                        self.camera = pos;
+                    }
+                }
+            }
+            Err(e) => {
+                game.report_error(e);
+                game.set_state(GameState::MainMenu);
+            }
+        }
+    }
+
+    /// Take up the autosave after a crash, or leave it for now
+    fn handle_crash_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        use crate::save::{save_exists, AUTOSAVE_SLOT};
+
+        match key.code {
+            KeyCode::Enter if save_exists(AUTOSAVE_SLOT) => {
+                self.crash_report = None;
+                self.load_slot(game, AUTOSAVE_SLOT);
+            }
+            KeyCode::Esc | KeyCode::Enter => self.crash_report = None,
+            _ => {}
+        }
+        Ok(false)
+    }
+
     fn handle_load_slots_input(&mut self, key: KeyEvent, game: &mut Game, selected: u8) -> Result<bool> {
-        use crate::save::{load_game, save_exists};
+        use crate::save::save_exists;
 
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
                 let new_selected = if selected > 0 { selected - 1 } else { 2 };
                 game.set_state(GameState::LoadSlots { selected: new_selected });
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                let new_selected = if selected < 2 { selected + 1 } else { 0 };
-                game.set_state(GameState::LoadSlots { selected: new_selected });
-            }
-            KeyCode::Enter => {
-                // Load from selected slot (only if save exists)
-                if save_exists(selected) {
-                    match load_game(selected) {
-                        Ok(save_data) => {
-                            if let Err(e) = game.restore_from_save(save_data) {
-                                game.add_message(&format!("Failed to restore: {}", e), crate::game::MessageCategory::System);
-                                game.set_state(GameState::MainMenu);
-                            } else {
-                                // Sync camera to player
-                                if let Some(pos) = game.player_position() {
-                                    self.camera = pos;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            game.add_message(&format!("Failed to load: {}", e), crate::game::MessageCategory::System);
-                            game.set_state(GameState::MainMenu);
-                        }
-                    }
-                }
-            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let new_selected = if selected < 2 { selected + 1 } else { 0 };
+                game.set_state(GameState::LoadSlots { selected: new_selected });
+            }
+            // Load from selected slot (only if save exists)
+            KeyCode::Enter if save_exists(selected) => {
+                self.load_slot(game, selected);
+            }
             KeyCode::Char('d') => {
                 // Delete save in selected slot
                 if save_exists(selected) {
                     if let Err(e) = crate::save::delete_save(selected) {
-                        game.add_message(&format!("Failed to delete: {}", e), crate::game::MessageCategory::System);
+                        game.report_error(e);
                     }
                 }
             }
@@ -3147,31 +4914,141 @@ impl App {
         Ok(false)
     }
 
+    fn handle_leaderboard_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('h') => {
+                game.set_state(GameState::MainMenu);
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Scroll back through the log, a line or a page at a time
+    fn handle_log_input(&mut self, key: KeyEvent, game: &mut Game, scroll: usize) -> Result<bool> {
+        const PAGE: usize = 20;
+        let oldest = crate::logging::recent_lines().len().saturating_sub(1);
+        let scroll = match key.code {
+            KeyCode::Esc | KeyCode::Char('l') | KeyCode::Char('q') => {
+                game.set_state(GameState::Paused);
+                return Ok(false);
+            }
+            KeyCode::Up | KeyCode::Char('k') => scroll + 1,
+            KeyCode::Down | KeyCode::Char('j') => scroll.saturating_sub(1),
+            KeyCode::PageUp => scroll + PAGE,
+            KeyCode::PageDown => scroll.saturating_sub(PAGE),
+            KeyCode::Home => oldest,
+            KeyCode::End => 0,
+            _ => return Ok(false),
+        };
+        game.set_state(GameState::Log { scroll: scroll.min(oldest) });
+        Ok(false)
+    }
+
     fn handle_game_over_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
         match key.code {
             KeyCode::Enter | KeyCode::Esc => {
+                self.run_card = None;
                 game.set_state(GameState::MainMenu);
             }
+            KeyCode::Char('c') | KeyCode::Char('C') => self.save_run_card(game),
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Read on through a cutscene, or skip it
+    fn handle_cutscene_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        match key.code {
+            KeyCode::Esc => game.skip_cutscene(),
+            KeyCode::Enter | KeyCode::Char(' ') => game.advance_cutscene(),
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Answer a puzzle chest's riddle by number, or back away from it
+    fn handle_riddle_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        let Some(chest) = self.riddle_chest else { return Ok(false) };
+        match key.code {
+            KeyCode::Char(c @ '1'..='3') => {
+                self.riddle_chest = None;
+                let answer = c as usize - '1' as usize;
+                if game.answer_riddle(chest, answer) {
+                    game.open_chest(chest);
+                }
+                game.run_ai_tick();
+            }
+            KeyCode::Esc => self.riddle_chest = None,
             _ => {}
         }
         Ok(false)
     }
 
     fn handle_victory_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
+        if let Some(cursor) = self.ng_plus_cursor {
+            let kept = ng_plus_keepsakes(game);
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.ng_plus_cursor = Some(cursor.saturating_sub(1));
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.ng_plus_cursor = Some((cursor + 1).min(kept.len().saturating_sub(1)));
+                }
+                KeyCode::Enter => {
+                    self.ng_plus_cursor = None;
+                    game.start_new_game_plus(kept.get(cursor).map(|(slot, _)| *slot));
+                    // Sync camera to player position
+                    if let Some(pos) = game.player_position() {
+                        self.camera = pos;
+                    }
+                }
+                KeyCode::Esc => {
+                    self.ng_plus_cursor = None;
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+
         match key.code {
             KeyCode::Enter | KeyCode::Esc => {
+                self.run_card = None;
                 game.set_state(GameState::MainMenu);
             }
+            KeyCode::Char('c') | KeyCode::Char('C') => self.save_run_card(game),
+            KeyCode::Char('n') | KeyCode::Char('N') if game.boss_rush().is_none() => {
+                self.ng_plus_cursor = Some(0);
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') if game.boss_rush().is_none() => {
+                game.continue_endless();
+                // Sync camera to player position
+                if let Some(pos) = game.player_position() {
+                    self.camera = pos;
+                }
+            }
             _ => {}
         }
         Ok(false)
     }
 
+    /// Save a card summing up the run just finished, to share
+    fn save_run_card(&mut self, game: &mut Game) {
+        let card = run_card(game);
+        match screenshot::export_run_card(&card, game.seed(), &screenshot::screenshot_directory()) {
+            Ok(path) => {
+                log::info!("Run card saved to {}", path.display());
+                self.run_card = Some(path);
+            }
+            Err(e) => game.report_error(e),
+        }
+    }
+
     fn handle_new_run_input(&mut self, key: KeyEvent, game: &mut Game) -> Result<bool> {
         // For now, just start with defaults
         match key.code {
             KeyCode::Enter => {
-                game.start_new_run(None, crate::progression::Difficulty::Normal);
+                game.start_new_run(self.seed.take(), crate::progression::Difficulty::Normal, crate::progression::CurseSigils::default());
             }
             KeyCode::Esc => {
                 game.set_state(GameState::MainMenu);
@@ -3187,22 +5064,99 @@ impl App {
         frame.render_widget(Clear, frame.area());
 
         match game.state() {
-            GameState::MainMenu => self.render_main_menu(frame),
+            GameState::MainMenu => self.render_main_menu(frame, game),
             GameState::Playing(state) => self.render_playing(frame, game, state),
             GameState::Paused => self.render_pause(frame, game),
             GameState::SaveSlots { selected } => self.render_save_slots(frame, game, *selected),
             GameState::LoadSlots { selected } => self.render_load_slots(frame, *selected),
             GameState::Achievements => self.render_achievements(frame, game),
+            GameState::Leaderboard => self.render_leaderboard(frame, game),
+            GameState::Log { scroll } => self.render_log(frame, *scroll),
             GameState::GameOver { floor_reached, cause_of_death } => {
-                self.render_game_over(frame, *floor_reached, cause_of_death);
+                self.render_game_over(frame, game, *floor_reached, cause_of_death);
             }
-            GameState::Victory => self.render_victory(frame),
+            GameState::Victory => self.render_victory(frame, game),
             GameState::NewRun { .. } => self.render_new_run(frame),
             GameState::Quit => {}
         }
+        if let Some(perf) = &self.perf {
+            self.render_perf_overlay(frame, game, perf);
+        }
+        if let Some(error) = game.error_popup() {
+            self.render_error_popup(frame, error);
+        }
+    }
+
+    /// An error the player should know about, over whatever is on screen
+    fn render_error_popup(&self, frame: &mut Frame, error: &crate::Error) {
+        let area = frame.area();
+        let width = area.width.saturating_sub(4).min(60);
+        let text_width = width.saturating_sub(2).max(1) as usize;
+        let text_rows = (error.to_string().chars().count().div_ceil(text_width) as u16).max(1);
+        let height = (text_rows + 4).min(area.height);
+        let rect = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(Span::styled(error.to_string(), Style::default().fg(Color::White))),
+                Line::from(""),
+                Line::from(Span::styled("Enter to dismiss", Style::default().fg(Color::DarkGray))),
+            ])
+            .wrap(ratatui::widgets::Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Something went wrong ")
+                    .border_style(Style::default().fg(Color::Red)),
+            ),
+            rect,
+        );
+    }
+
+    /// Where the frames' time goes, in the top right corner
+    fn render_perf_overlay(&self, frame: &mut Frame, game: &Game, perf: &crate::perf::FrameStats) {
+        use crate::ecs::Stage;
+        use crate::perf::millis;
+
+        let average = perf.average();
+        let turn = game.turn_times();
+        let label = Style::default().fg(Color::DarkGray);
+        let row = |name: &'static str, value: String| {
+            Line::from(vec![Span::styled(format!("{:<9}", name), label), Span::raw(value)])
+        };
+        let lines = vec![
+            row("Frame", format!("{} (worst {})", millis(average.busy()), millis(perf.worst()))),
+            row("Input", millis(average.input)),
+            row("Update", millis(average.update)),
+            row("Render", millis(average.render)),
+            row("Turn", format!("{} (AI {})", millis(turn.total()), millis(turn.get(Stage::Ai)))),
+            row("Entities", game.world().len().to_string()),
+            row("Allocs", format!("{}/frame, {:.1} KB", average.allocations, average.allocated_bytes as f64 / 1024.0)),
+        ];
+
+        let area = frame.area();
+        let width = 38.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let rect = Rect { x: area.x + area.width - width, y: area.y, width, height };
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Perf ")
+                    .border_style(Style::default().fg(Color::Magenta)),
+            ),
+            rect,
+        );
     }
 
-    fn render_main_menu(&self, frame: &mut Frame) {
+    fn render_main_menu(&self, frame: &mut Frame, game: &Game) {
         let area = frame.area();
 
         let chunks = Layout::default()
@@ -3264,11 +5218,22 @@ impl App {
                 Style::default().fg(Color::White),
             )),
             Line::from(""),
+            if game.profile().boss_rush_unlocked() {
+                Line::from(Span::styled("[B] Boss Rush", Style::default().fg(Color::Rgb(220, 120, 60))))
+            } else {
+                Line::from(Span::styled("[B] Boss Rush (win a run to unlock)", Style::default().fg(Color::DarkGray)))
+            },
+            Line::from(""),
             Line::from(Span::styled(
                 "[A] Achievements",
                 Style::default().fg(Color::Yellow),
             )),
             Line::from(""),
+            Line::from(Span::styled(
+                "[H] High Scores",
+                Style::default().fg(Color::Yellow),
+            )),
+            Line::from(""),
             Line::from(Span::styled(
                 "[O] Options",
                 Style::default().fg(Color::DarkGray),
@@ -3292,19 +5257,61 @@ impl App {
 
         // Difficulty selection popup
         if self.difficulty_selection_mode {
-            self.render_difficulty_popup(frame);
+            self.render_difficulty_popup(frame, game);
+        }
+        if let Some(report) = &self.crash_report {
+            self.render_crash_popup(frame, report);
+        }
+    }
+
+    /// Owning up to the last launch's crash, with the autosave on offer
+    fn render_crash_popup(&self, frame: &mut Frame, report: &std::path::Path) {
+        use crate::save::save_game::load_save_summary;
+        use crate::save::AUTOSAVE_SLOT;
+
+        let area = centered_rect(60, 40, frame.area());
+        frame.render_widget(Clear, area);
+        let autosave = load_save_summary(AUTOSAVE_SLOT).ok();
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(Span::styled("Hollowdeep crashed last time.", Style::default().fg(Color::White))),
+            Line::from(""),
+            Line::from(Span::styled("A crash report was written to", Style::default().fg(Color::Gray))),
+            Line::from(Span::styled(report.display().to_string(), Style::default().fg(Color::DarkGray))),
+            Line::from(""),
+        ];
+        match autosave {
+            Some(summary) => lines.push(Line::from(Span::styled(
+                format!("[Enter] Load the autosave ({}, level {})", floor_name(summary.floor), summary.level),
+                Style::default().fg(Color::Yellow),
+            ))),
+            None => lines.push(Line::from(Span::styled("No autosave to load", Style::default().fg(Color::DarkGray)))),
         }
+        lines.push(Line::from(Span::styled("[Esc] Not now", Style::default().fg(Color::DarkGray))));
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .alignment(ratatui::layout::Alignment::Center)
+                .wrap(ratatui::widgets::Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(" Crashed ")
+                        .border_style(Style::default().fg(Color::Red)),
+                ),
+            area,
+        );
     }
 
-    fn render_difficulty_popup(&self, frame: &mut Frame) {
-        use crate::progression::Difficulty;
+    fn render_difficulty_popup(&self, frame: &mut Frame, game: &Game) {
+        use crate::progression::{CurseSigil, Difficulty};
 
-        let popup_area = centered_rect(50, 50, frame.area());
+        let popup_area = centered_rect(60, 70, frame.area());
         frame.render_widget(Clear, popup_area);
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(" ⚔ Choose Your Fate ⚔ ")
+            .title(if self.boss_rush_selected { " ⚔ Boss Rush ⚔ " } else { " ⚔ Choose Your Fate ⚔ " })
             .border_style(Style::default().fg(Color::Yellow));
 
         let inner = block.inner(popup_area);
@@ -3314,7 +5321,7 @@ impl App {
             (Difficulty::Easy, "Relaxed experience", "Enemy damage -30%, Enemy HP -20%, XP -20%"),
             (Difficulty::Normal, "Balanced challenge", "Standard difficulty"),
             (Difficulty::Hard, "Dangerous depths", "Enemy damage +30%, Enemy HP +25%, XP +20%"),
-            (Difficulty::Nightmare, "True suffering", "Enemy damage +60%, Enemy HP +50%, XP +50%, More enemies"),
+            (Difficulty::Nightmare, "True suffering", "Enemy damage +60%, Enemy HP +50%, XP +50%, More enemies, No death's door"),
         ];
 
         let mut lines: Vec<Line> = Vec::new();
@@ -3354,13 +5361,54 @@ impl App {
                     format!("    {}", desc),
                     Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
                 )));
+                let encumbrance = game.profile().settings.encumbrance_enabled(*diff);
+                lines.push(Line::from(Span::styled(
+                    format!("    Encumbrance: {}", if encumbrance { "On" } else { "Off" }),
+                    Style::default().fg(if encumbrance { Color::Yellow } else { Color::DarkGray }),
+                )));
             }
             lines.push(Line::from(""));
         }
 
+        // Curse sigils stack on top of the difficulty (not in a boss rush)
+        if !self.boss_rush_selected {
+            lines.push(Line::from(Span::styled(
+                "Curse Sigils",
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            )));
+            for (i, sigil) in CurseSigil::ALL.iter().enumerate() {
+                let taken = self.chosen_sigils.has(*sigil);
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("[{}] {} {:<9}", i + 1, if taken { "◆" } else { "◇" }, sigil.name()),
+                        Style::default().fg(if taken { Color::Magenta } else { Color::Gray }),
+                    ),
+                    Span::styled(
+                        format!(" {} (score ×{:.2})", sigil.description(), sigil.score_mult()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+            if !self.chosen_sigils.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    format!("Score ×{:.2}", self.chosen_sigils.score_mult()),
+                    Style::default().fg(Color::Magenta),
+                )));
+            }
+            let adaptive = game.profile().settings.adaptive_difficulty;
+            lines.push(Line::from(Span::styled(
+                format!("Adaptive difficulty: {}", if adaptive { "On" } else { "Off" }),
+                Style::default().fg(if adaptive { Color::Cyan } else { Color::DarkGray }),
+            )));
+        }
+
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
-            "[↑↓] Select  [Enter] Start  [Esc] Cancel",
+            if self.boss_rush_selected {
+                "[↑↓] Select  [W] Toggle encumbrance  [Enter] Start  [Esc] Cancel"
+            } else {
+                "[↑↓] Select  [1-4] Sigils  [A] Adaptive  [W] Encumbrance  [Enter] Start  [Esc] Cancel"
+            },
             Style::default().fg(Color::DarkGray),
         )));
 
@@ -3370,28 +5418,31 @@ impl App {
     }
 
     fn render_playing(&self, frame: &mut Frame, game: &Game, state: &PlayingState) {
-        let area = frame.area();
-
-        // Main layout: sidebar on right
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(40), Constraint::Length(25)])
-            .split(area);
-
-        // Map area with message log at bottom
-        let left_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Min(20), Constraint::Length(7)])
-            .split(chunks[0]);
+        let layout = PlayingLayout::new(frame.area(), self.layout_profile, self.panels(game));
 
         // Render map
-        self.render_map(frame, game, left_chunks[0]);
+        self.render_map(frame, game, layout.map);
+        if game.cutscene().is_some() {
+            self.render_cutscene(frame, game, layout.map);
+        } else {
+            self.render_floor_banner(frame, game, layout.map);
+            self.render_collapse_warning(frame, game, layout.map);
+            self.render_tutorial_prompt(frame, game, layout.map);
+        }
 
-        // Render message log
-        self.render_messages(frame, game, left_chunks[1]);
+        // What can be done from here, then the message log
+        self.render_action_bar(frame, game, layout.action_bar);
+        if let Some(area) = layout.log {
+            self.render_messages(frame, game, area);
+        }
 
-        // Render sidebar
-        self.render_sidebar(frame, game, chunks[1]);
+        // Side panels
+        if let Some(area) = layout.sidebar {
+            self.render_sidebar(frame, game, area);
+        }
+        if let Some(area) = layout.nearby {
+            self.render_nearby(frame, game, area);
+        }
 
         // Render overlay for special states
         match state {
@@ -3399,10 +5450,338 @@ impl App {
             PlayingState::Character => self.render_character_overlay(frame, game),
             PlayingState::MapView => self.render_fullmap_overlay(frame, game),
             PlayingState::Help => self.render_help_overlay(frame),
+            PlayingState::Discoveries => self.render_discoveries_overlay(frame, game),
+            PlayingState::Pickup => self.render_pickup_overlay(frame, game),
             PlayingState::Shrine { shrine_type } => self.render_shrine_overlay(frame, game, *shrine_type),
             PlayingState::Shop { npc_entity } => self.render_shop_overlay(frame, game, *npc_entity),
+            PlayingState::Dice { .. } => self.render_dice_overlay(frame, game),
             _ => {}
         }
+        if let Some(chest) = self.riddle_chest {
+            self.render_riddle_overlay(frame, game, chest);
+        }
+        if let Some(palette) = &self.palette {
+            self.render_palette(frame, palette);
+        }
+    }
+
+    /// The command palette: the query, then the matching commands and
+    /// their keys
+    fn render_palette(&self, frame: &mut Frame, palette: &Palette) {
+        let area = centered_rect(50, 60, frame.area());
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Commands ")
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height < 3 {
+            return;
+        }
+
+        let matches = palette.matches();
+        let rows = (inner.height - 2) as usize;
+        // Scroll to keep the cursor in view
+        let first = palette.cursor.saturating_sub(rows - 1);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("> ", Style::default().fg(Color::Cyan)),
+                Span::styled(palette.query.clone(), Style::default().fg(Color::White)),
+                Span::styled("_", Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(""),
+        ];
+        if matches.is_empty() {
+            lines.push(Line::from(Span::styled("No matching command", Style::default().fg(Color::DarkGray))));
+        }
+        for (i, command) in matches.iter().enumerate().skip(first).take(rows) {
+            let key = command.key.map(|k| format!("[{}]", k)).unwrap_or_default();
+            let name_width = (inner.width as usize).saturating_sub(key.chars().count() + 1);
+            let style = if i == palette.cursor {
+                Style::default().fg(Color::Black).bg(Color::Cyan)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<width$}", command.name, width = name_width), style),
+                Span::styled(format!(" {}", key), Style::default().fg(Color::Yellow)),
+            ]));
+        }
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    /// The tutorial step's prompt along the bottom of the map
+    fn render_tutorial_prompt(&self, frame: &mut Frame, game: &Game, map_area: Rect) {
+        let Some((step, prompt)) = game.tutorial_step() else { return };
+        if !matches!(game.state(), GameState::Playing(PlayingState::Exploring)) || map_area.width < 24 {
+            return;
+        }
+        let total = game.data().tutorial_defs().steps.len();
+        let width = map_area.width.saturating_sub(4).min(64);
+        let text_width = width.saturating_sub(2).max(1) as usize;
+        let text_rows = (prompt.text.chars().count().div_ceil(text_width) as u16).max(1);
+        let height = (text_rows + 3).min(map_area.height);
+        let area = Rect {
+            x: map_area.x + (map_area.width - width) / 2,
+            y: (map_area.y + map_area.height).saturating_sub(height + 1).max(map_area.y),
+            width,
+            height,
+        };
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(Span::styled(prompt.text.clone(), Style::default().fg(Color::White))),
+                Line::from(Span::styled("Esc, then K, skips the tutorial", Style::default().fg(Color::DarkGray))),
+            ])
+            .wrap(ratatui::widgets::Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" Tutorial {}/{}: {} ", step + 1, total, prompt.title))
+                    .border_style(Style::default().fg(Color::Cyan)),
+            ),
+            area,
+        );
+    }
+
+    /// One line of the actions open to the player where they stand. With
+    /// the sidebar folded away it leads with the player's vitals.
+    fn render_action_bar(&self, frame: &mut Frame, game: &Game, area: Rect) {
+        let mut spans = vec![Span::raw(" ")];
+        if self.panels(game).side {
+            if let (Some(hp), Some(mp)) = (game.player_health(), game.player_mana()) {
+                let color = crate::ui::widgets::healthbar::bar_color(hp.percentage());
+                spans.push(Span::styled(format!("HP {}/{} ", hp.current, hp.max), Style::default().fg(color)));
+                spans.push(Span::styled(format!("MP {}/{} ", mp.current, mp.max), Style::default().fg(Color::Blue)));
+                spans.push(Span::styled(format!("F{}  ", game.floor()), Style::default().fg(Color::DarkGray)));
+            }
+        }
+        let vitals = spans.len();
+        for action in game.context_actions() {
+            spans.push(Span::styled(format!("[{}] ", action.key()), Style::default().fg(Color::Yellow)));
+            spans.push(Span::styled(format!("{}  ", action.label()), Style::default().fg(Color::Gray)));
+        }
+        if spans.len() == vitals {
+            spans.push(Span::styled("[?] Help", Style::default().fg(Color::DarkGray)));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    /// Creatures in sight, nearest first, with their condition and intent
+    fn render_nearby(&self, frame: &mut Frame, game: &Game, area: Rect) {
+        let key = self.panel_key(game);
+        self.nearby_cache.borrow_mut().draw(frame.buffer_mut(), area, key, |buf| self.draw_nearby(buf, game, area));
+    }
+
+    fn draw_nearby(&self, buf: &mut ratatui::buffer::Buffer, game: &Game, area: Rect) {
+        use crate::ecs::{AI, Enemy, Health, Name, Renderable};
+        use crate::render::intent::enemy_intent;
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Nearby ")
+            .border_style(Style::default().fg(Color::DarkGray));
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let Some(map) = game.map() else { return };
+        let ascii = self.render_mode == RenderMode::Ascii;
+
+        let mut seen: Vec<(i32, Line)> = game.world()
+            .query::<(&Position, &Name, &Health, &Renderable, &Enemy, Option<&AI>)>()
+            .iter()
+            .filter(|(_, (pos, _, _, _, _, _))| map.get_tile(pos.x, pos.y).is_some_and(|t| t.visible))
+            .map(|(entity, (pos, name, health, renderable, enemy, ai))| {
+                let distance = pos.chebyshev_distance(&self.camera);
+                let intent = ai.and_then(|ai| enemy_intent(ai.state, enemy.archetype, false, distance));
+                let threat = game.threat_of(entity).unwrap_or(crate::game::Threat::Even);
+                let (r, g, b) = renderable.fg;
+                let (tr, tg, tb) = threat.color();
+                let mut spans = vec![
+                    Span::styled(format!("{} ", renderable.glyph), Style::default().fg(Color::Rgb(r, g, b))),
+                    Span::styled(intern(&name.0), Style::default().fg(Color::Rgb(tr, tg, tb))),
+                    Span::styled(
+                        format!(" {}", crate::game::condition(health.current, health.max)),
+                        Style::default().fg(crate::ui::widgets::healthbar::bar_color(health.percentage())),
+                    ),
+                    Span::styled(format!(" {}", threat.label()), Style::default().fg(Color::Rgb(tr, tg, tb))),
+                ];
+                if let Some(intent) = intent {
+                    let (r, g, b) = intent.color();
+                    spans.push(Span::styled(format!(" {}", intent.glyph(ascii)), Style::default().fg(Color::Rgb(r, g, b))));
+                }
+                (distance, Line::from(spans))
+            })
+            .collect();
+        seen.sort_by_key(|(distance, _)| *distance);
+
+        let lines: Vec<Line> = if seen.is_empty() {
+            vec![Line::from(Span::styled("Nothing in sight", Style::default().fg(Color::DarkGray)))]
+        } else {
+            seen.into_iter().map(|(_, line)| line).collect()
+        };
+        Paragraph::new(lines).render(inner, buf);
+    }
+
+    /// A puzzle chest's riddle and the answers to pick from
+    fn render_riddle_overlay(&self, frame: &mut Frame, game: &Game, chest: hecs::Entity) {
+        use crate::ecs::{Chest, ChestKind};
+
+        let riddle = game.world().get::<&Chest>(chest).ok().and_then(|c| match c.kind {
+            ChestKind::Puzzle(i) => crate::entities::RIDDLES.get(i).copied(),
+            _ => None,
+        });
+        let Some(riddle) = riddle else { return };
+
+        let area = centered_rect(50, 35, frame.area());
+        frame.render_widget(Clear, area);
+
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "A voice rises from the lock:",
+                Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(riddle.question, Style::default().fg(Color::White))),
+            Line::from(""),
+        ];
+        for (i, answer) in riddle.answers.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("[{}] ", i + 1), Style::default().fg(Color::Yellow)),
+                Span::raw(*answer),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "A wrong answer seals it forever.  [Esc] Back away",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(ratatui::widgets::Wrap { trim: true })
+                .block(Block::default()
+                    .borders(Borders::ALL)
+                    .title(" ⊡ Puzzle Chest ⊡ ")
+                    .border_style(Style::default().fg(Color::Rgb(200, 170, 255)))),
+            area,
+        );
+    }
+
+    /// Banners and dialogue of the cutscene playing over the map
+    fn render_cutscene(&self, frame: &mut Frame, game: &Game, area: Rect) {
+        let Some(scene) = game.cutscene() else { return };
+        let hint = Line::from(Span::styled("[Enter] Continue  [Esc] Skip", Style::default().fg(Color::DarkGray)));
+
+        if let Some((title, text)) = scene.banner() {
+            let mut lines = vec![Line::from(Span::styled(
+                title.to_string(),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ))];
+            if !text.is_empty() {
+                lines.push(Line::from(Span::styled(text.to_string(), Style::default().fg(Color::Gray))));
+            }
+            let width = (lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 4).min(area.width);
+            let height = (lines.len() as u16 + 2).min(area.height);
+            let rect = Rect {
+                x: area.x + area.width.saturating_sub(width) / 2,
+                y: area.y + area.height.saturating_sub(height) / 3,
+                width,
+                height,
+            };
+            frame.render_widget(Clear, rect);
+            frame.render_widget(
+                Paragraph::new(lines)
+                    .alignment(ratatui::layout::Alignment::Center)
+                    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Yellow))),
+                rect,
+            );
+        }
+
+        // Dialogue sits along the bottom of the map, with the controls under it
+        let height = 5.min(area.height);
+        let rect = Rect {
+            x: area.x + 2.min(area.width),
+            y: area.y + area.height.saturating_sub(height + 1),
+            width: area.width.saturating_sub(4),
+            height,
+        };
+        let lines = match scene.dialogue() {
+            Some((speaker, line)) => vec![
+                Line::from(Span::styled(speaker.to_string(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
+                Line::from(line.to_string()),
+                hint,
+            ],
+            None => vec![Line::from(""), Line::from(""), hint],
+        };
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(ratatui::widgets::Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray))),
+            rect,
+        );
+    }
+
+    /// Announce a new floor, and anything that has befallen it, across the
+    /// top of the map
+    fn render_floor_banner(&self, frame: &mut Frame, game: &Game, area: Rect) {
+        let Some(banner) = game.floor_banner() else { return };
+
+        let mut lines = vec![Line::from(Span::styled(
+            banner.title.clone(),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ))];
+        if let Some((name, announcement)) = &banner.event {
+            lines.push(Line::from(Span::styled(
+                name.clone(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::from(Span::styled(announcement.clone(), Style::default().fg(Color::Gray))));
+        }
+
+        let text_width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+        let width = (text_width + 4).min(area.width.saturating_sub(2));
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let rect = Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + area.height.saturating_sub(height).min(2),
+            width,
+            height,
+        };
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .alignment(ratatui::layout::Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray))),
+            rect,
+        );
+    }
+
+    /// Turns left to escape a collapsing floor, along the bottom of the map
+    fn render_collapse_warning(&self, frame: &mut Frame, game: &Game, area: Rect) {
+        let Some(collapse) = game.collapse() else { return };
+        if game.floor_banner().is_some() || area.height < 6 {
+            return;
+        }
+        let urgent = collapse.turns_left <= 10;
+        let text = format!(" THE FLOOR IS COLLAPSING - {} turns to reach the stairs ", collapse.turns_left);
+        let width = (text.chars().count() as u16).min(area.width.saturating_sub(2));
+        let rect = Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + 1,
+            width,
+            height: 1,
+        };
+        let style = if urgent {
+            Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Red).bg(Color::Black).add_modifier(Modifier::BOLD)
+        };
+        frame.render_widget(Clear, rect);
+        frame.render_widget(Paragraph::new(Span::styled(text, style)), rect);
     }
 
     fn render_map(&self, frame: &mut Frame, game: &Game, area: Rect) {
@@ -3432,7 +5811,7 @@ impl App {
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(format!(" {} - Floor {} {} ", map.biome.name(), map.floor_number, mode_indicator))
+            .title(format!(" {} - {} {} ", map.biome.name(), floor_name(map.floor_number), mode_indicator))
             .border_style(Style::default().fg(border_color));
 
         let inner = block.inner(area);
@@ -3442,8 +5821,13 @@ impl App {
         let view_width = inner.width as i32;
         let view_height = inner.height as i32;
 
-        let cam_x = self.camera.x - view_width / 2;
-        let cam_y = self.camera.y - view_height / 2;
+        // A cutscene takes the camera while it plays
+        let view = game.cutscene().map_or(self.camera, |scene| scene.camera);
+        // A collapsing floor jolts the view
+        let (jolt_x, jolt_y) = crate::game::shake_offset(game.screen_shake());
+        let cam_x = view.x - view_width / 2 + jolt_x;
+        let cam_y = view.y - view_height / 2 + jolt_y;
+        self.map_view.set(Some((inner, cam_x, cam_y)));
 
         // Render tiles using the tile renderer with biome colors
         for screen_y in 0..view_height {
@@ -3463,10 +5847,18 @@ impl App {
                 if let Some(tile) = map.get_tile(map_x, map_y) {
                     if tile.explored {
                         // Use biome-specific glyph variation based on position
-                        let ch = self.get_biome_glyph(tile.tile_type, &biome_config, map_x, map_y);
+                        let mut ch = self.get_biome_glyph(tile.tile_type, &biome_config, map_x, map_y);
 
                         // Use biome-aware colors for ambient lighting
-                        let fg = self.tile_renderer.tile_fg_color_biome(tile.tile_type, tile.visible, ambient);
+                        let mut fg = self.tile_renderer.tile_fg_color_biome(tile.tile_type, tile.visible, ambient);
+
+                        // Blood and battle damage show over the floor, dimmed out of sight
+                        if let Some(decal) = map.decal_at(Position::new(map_x, map_y)) {
+                            let (r, g, b) = decal.color(map.biome);
+                            let dim = if tile.visible { 1.0 } else { 0.4 };
+                            ch = decal.glyph(self.render_mode == RenderMode::Ascii);
+                            fg = Color::Rgb((r as f32 * dim) as u8, (g as f32 * dim) as u8, (b as f32 * dim) as u8);
+                        }
                         let bg = self.tile_renderer.tile_bg_color_biome(tile.tile_type, tile.visible, ambient);
 
                         buf[(cell_x, cell_y)].set_char(ch);
@@ -3496,11 +5888,42 @@ impl App {
 
         // Render all entities with Position and Renderable
         // Query for enemies with health to color by HP
-        use crate::ecs::{Position, Renderable, Health, Enemy};
-        for (_, (pos, renderable, maybe_health, maybe_enemy)) in game.world()
-            .query::<(&Position, &Renderable, Option<&Health>, Option<&Enemy>)>()
+        use crate::ecs::{Position, Renderable, Health, Enemy, GroundItem, AI, AIState};
+        use crate::save::LootFilterMode;
+        // Watching eyes reveal enemies through walls
+        let xray_range = game.player()
+            .and_then(|p| game.world().get::<&crate::progression::Mutations>(p).ok())
+            .map(|m| m.xray_range())
+            .unwrap_or(0);
+
+        // Corpses lie under everything else
+        for (_, (pos, renderable)) in game.world()
+            .query::<(&Position, &Renderable)>()
+            .with::<&crate::entities::Corpse>()
             .iter()
         {
+            let (screen_x, screen_y) = (pos.x - cam_x, pos.y - cam_y);
+            let on_screen = screen_x >= 0 && screen_x < view_width && screen_y >= 0 && screen_y < view_height;
+            if on_screen && map.get_tile(pos.x, pos.y).is_some_and(|t| t.visible) {
+                let (r, g, b) = renderable.fg;
+                let buf = frame.buffer_mut();
+                buf[(inner.x + screen_x as u16, inner.y + screen_y as u16)].set_char(renderable.glyph);
+                buf[(inner.x + screen_x as u16, inner.y + screen_y as u16)].set_fg(Color::Rgb(r, g, b));
+            }
+        }
+
+        for (_, (pos, renderable, maybe_health, maybe_enemy, maybe_loot, maybe_ai)) in game.world()
+            .query::<(&Position, &Renderable, Option<&Health>, Option<&Enemy>, Option<&GroundItem>, Option<&AI>)>()
+            .without::<&crate::entities::Corpse>()
+            .iter()
+        {
+            let filtered = maybe_loot
+                .map(|gi| game.profile().settings.loot_filter_for(&gi.item))
+                .unwrap_or(LootFilterMode::Off);
+            if filtered == LootFilterMode::Hide {
+                continue;
+            }
+
             // Check if entity is in view
             let screen_x = pos.x - cam_x;
             let screen_y = pos.y - cam_y;
@@ -3511,12 +5934,26 @@ impl App {
 
                 // Check if tile is visible
                 if let Some(tile) = map.get_tile(pos.x, pos.y) {
-                    if tile.visible {
+                    let sensed = !tile.visible
+                        && maybe_enemy.is_some()
+                        && pos.chebyshev_distance(&self.camera) <= xray_range;
+                    if sensed {
                         let buf = frame.buffer_mut();
                         buf[(cell_x, cell_y)].set_char(renderable.glyph);
+                        buf[(cell_x, cell_y)].set_fg(Color::Rgb(120, 60, 160));
+                    } else if tile.visible {
+                        let glyph = maybe_loot
+                            .map_or(renderable.glyph, |gi| game.discoveries().disguise(&gi.item).glyph);
+                        let buf = frame.buffer_mut();
+                        buf[(cell_x, cell_y)].set_char(glyph);
 
                         // Color enemies by health percentage
-                        let fg_color = if maybe_enemy.is_some() {
+                        let asleep = maybe_ai.is_some_and(|ai| ai.state == AIState::Sleeping);
+                        let fg_color = if maybe_enemy.is_some() && asleep {
+                            // Sleepers are drawn dim
+                            let (r, g, b) = renderable.fg;
+                            Color::Rgb(r / 2, g / 2, b / 2)
+                        } else if maybe_enemy.is_some() {
                             if let Some(hp) = maybe_health {
                                 let pct = hp.percentage();
                                 if pct > 0.6 {
@@ -3532,12 +5969,73 @@ impl App {
                             } else {
                                 Color::Rgb(renderable.fg.0, renderable.fg.1, renderable.fg.2)
                             }
+                        } else if filtered == LootFilterMode::Flag {
+                            // Junk caught by the loot filter fades into the floor
+                            Color::DarkGray
                         } else {
                             Color::Rgb(renderable.fg.0, renderable.fg.1, renderable.fg.2)
                         };
 
-                        buf[(cell_x, cell_y)].set_fg(fg_color);
-                    }
+                        buf[(cell_x, cell_y)].set_fg(fg_color);
+                    }
+                }
+            }
+        }
+
+        // Piles of several items get a stack marker in the colour of the best one
+        let mut scratch = self.map_scratch.borrow_mut();
+        let piles = &mut scratch.piles;
+        piles.clear();
+        for (_, (pos, ground)) in game.world().query::<(&Position, &GroundItem)>().iter() {
+            if game.profile().settings.loot_filter_for(&ground.item) == LootFilterMode::Hide {
+                continue;
+            }
+            let pile = piles.entry(*pos).or_insert((0, ground.item.rarity));
+            pile.0 += 1;
+            if ground.item.rarity.sort_value() > pile.1.sort_value() {
+                pile.1 = ground.item.rarity;
+            }
+        }
+        for (&pos, &(count, rarity)) in piles.iter() {
+            let screen_x = pos.x - cam_x;
+            let screen_y = pos.y - cam_y;
+            let on_screen = screen_x >= 0 && screen_x < view_width && screen_y >= 0 && screen_y < view_height;
+            let visible = map.get_tile(pos.x, pos.y).is_some_and(|t| t.visible);
+            if count < 2 || !on_screen || !visible || game.is_blocked_by_entity(pos) {
+                continue;
+            }
+            let stack_char = match self.render_mode {
+                RenderMode::Ascii => '&',
+                _ => '≡',
+            };
+            let (r, g, b) = rarity.color();
+            let buf = frame.buffer_mut();
+            buf[(inner.x + screen_x as u16, inner.y + screen_y as u16)].set_char(stack_char);
+            buf[(inner.x + screen_x as u16, inner.y + screen_y as u16)].set_fg(Color::Rgb(r, g, b));
+        }
+        drop(scratch);
+
+        self.render_enemy_overlays(frame, game, inner, (cam_x, cam_y), (view_width, view_height));
+
+        // Damage and healing numbers rise from visible tiles
+        for text in game.floating_texts() {
+            if !map.get_tile(text.pos.x, text.pos.y).is_some_and(|t| t.visible) {
+                continue;
+            }
+            let screen_y = text.pos.y - cam_y - 1 - text.rise();
+            let start_x = text.pos.x - cam_x - text.text.chars().count() as i32 / 2;
+            if screen_y < 0 || screen_y >= view_height {
+                continue;
+            }
+            let (r, g, b) = text.color;
+            let buf = frame.buffer_mut();
+            for (i, ch) in text.text.chars().enumerate() {
+                let screen_x = start_x + i as i32;
+                if screen_x >= 0 && screen_x < view_width {
+                    let cell = &mut buf[(inner.x + screen_x as u16, inner.y + screen_y as u16)];
+                    cell.set_char(ch);
+                    cell.set_fg(Color::Rgb(r, g, b));
+                    cell.set_style(Style::default().add_modifier(Modifier::BOLD));
                 }
             }
         }
@@ -3562,7 +6060,136 @@ impl App {
         }
 
         // Render minimap overlay in top-right corner
-        self.render_minimap(frame, game, inner);
+        if !game.profile().settings.zen_mode {
+            self.render_minimap(frame, game, inner);
+        }
+        self.render_boss_banner(frame, game, inner);
+        self.render_examine_tooltip(frame, game, inner, (cam_x, cam_y));
+    }
+
+    /// Mini health bars above visible damaged enemies, intent icons over
+    /// them (or beside, when a bar is in the way) and their worst status
+    /// effect on the other side, as picked in the settings
+    fn render_enemy_overlays(&self, frame: &mut Frame, game: &Game, inner: Rect, cam: (i32, i32), view: (i32, i32)) {
+        use crate::ecs::{AI, Enemy, Health, StatusEffects};
+        use crate::render::intent::{enemy_intent, most_pressing, status_glyph};
+        use crate::save::EnemyOverlays;
+        use crate::ui::widgets::healthbar::{bar_color, mini_bar_glyph};
+
+        let settings = &game.profile().settings;
+        let density = settings.enemy_overlays;
+        let Some(map) = game.map() else { return };
+        if density == EnemyOverlays::Off && !settings.enemy_health_bars {
+            return;
+        }
+        let ascii = self.render_mode == RenderMode::Ascii;
+
+        let mut scratch = self.map_scratch.borrow_mut();
+        let marks = &mut scratch.marks;
+        marks.clear();
+        for (_, (pos, enemy, ai, health, effects, necromancer, boss)) in game.world()
+            .query::<(&Position, &Enemy, &AI, &Health, Option<&StatusEffects>, Option<&crate::entities::Necromancer>, Option<&crate::entities::BossComponent>)>()
+            .iter()
+        {
+            if !map.get_tile(pos.x, pos.y).is_some_and(|t| t.visible) {
+                continue;
+            }
+
+            // Bosses get the banner along the top instead
+            let has_bar = settings.enemy_health_bars && boss.is_none() && health.current < health.max;
+            if has_bar {
+                let fraction = health.percentage();
+                marks.push((Position::new(pos.x, pos.y - 1), mini_bar_glyph(fraction, ascii), bar_color(fraction)));
+            }
+            if density == EnemyOverlays::Off {
+                continue;
+            }
+
+            let spell_ready = necromancer.is_some_and(|n| n.cooldown == 0);
+            let distance = pos.chebyshev_distance(&self.camera);
+            if let Some(intent) = enemy_intent(ai.state, enemy.archetype, spell_ready, distance) {
+                let (r, g, b) = intent.color();
+                let at = if has_bar { Position::new(pos.x - 1, pos.y) } else { Position::new(pos.x, pos.y - 1) };
+                marks.push((at, intent.glyph(ascii), Color::Rgb(r, g, b)));
+            }
+            if density == EnemyOverlays::Full {
+                let shown = effects.and_then(|e| most_pressing(e.effects.iter().map(|s| s.effect_type)));
+                if let Some(effect) = shown {
+                    let (glyph, (r, g, b)) = status_glyph(effect, ascii);
+                    marks.push((Position::new(pos.x + 1, pos.y), glyph, Color::Rgb(r, g, b)));
+                }
+            }
+        }
+
+        // Icons never cover the player or anything standing on the map
+        for &(pos, glyph, color) in marks.iter() {
+            let (screen_x, screen_y) = (pos.x - cam.0, pos.y - cam.1);
+            let on_screen = screen_x >= 0 && screen_x < view.0 && screen_y >= 0 && screen_y < view.1;
+            if !on_screen || pos == self.camera || game.is_blocked_by_entity(pos) {
+                continue;
+            }
+            let buf = frame.buffer_mut();
+            buf[(inner.x + screen_x as u16, inner.y + screen_y as u16)].set_char(glyph);
+            buf[(inner.x + screen_x as u16, inner.y + screen_y as u16)].set_fg(color);
+        }
+    }
+
+    /// Description of the cell under the look cursor, or else under the
+    /// mouse, in a small panel beside it
+    fn render_examine_tooltip(&self, frame: &mut Frame, game: &Game, inner: Rect, cam: (i32, i32)) {
+        if !matches!(game.state(), GameState::Playing(PlayingState::Exploring)) || game.cutscene().is_some() {
+            return;
+        }
+        let Some(target) = self.look_cursor.or(self.hover_cell) else { return };
+        let (screen_x, screen_y) = (target.x - cam.0, target.y - cam.1);
+        if screen_x < 0 || screen_y < 0 || screen_x >= inner.width as i32 || screen_y >= inner.height as i32 {
+            return;
+        }
+        let (cell_x, cell_y) = (inner.x + screen_x as u16, inner.y + screen_y as u16);
+
+        // The look cursor is drawn as a highlighted cell
+        if self.look_cursor.is_some() {
+            let buf = frame.buffer_mut();
+            buf[(cell_x, cell_y)].set_style(Style::default().add_modifier(Modifier::REVERSED));
+        }
+
+        let mut lines = match game.describe_cell(target) {
+            Some(cell) => cell.lines(),
+            None => vec!["Unexplored".to_string()],
+        };
+        if self.wizard && self.look_cursor.is_some() {
+            lines.extend(crate::ui::inspector::inspect(game.world(), target));
+        }
+        let width = (lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 4).min(inner.width);
+        let height = (lines.len() as u16 + 2).min(inner.height);
+        // Beside the cell, flipping to the other side near the edges
+        let x = if cell_x + 2 + width <= inner.x + inner.width {
+            cell_x + 2
+        } else {
+            cell_x.saturating_sub(width + 1).max(inner.x)
+        };
+        let y = cell_y.min((inner.y + inner.height).saturating_sub(height)).max(inner.y);
+        let rect = Rect { x, y, width, height };
+
+        let text: Vec<Line> = lines.into_iter().enumerate()
+            .map(|(i, line)| {
+                let color = if i == 0 { Color::White } else { Color::Gray };
+                Line::from(Span::styled(line, Style::default().fg(color)))
+            })
+            .collect();
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(text).block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray))),
+            rect,
+        );
+    }
+
+    /// The boss being fought, across the top of the map until it dies or
+    /// loses track of the player
+    fn render_boss_banner(&self, frame: &mut Frame, game: &Game, inner: Rect) {
+        if let Some(fight) = game.active_boss() {
+            crate::ui::widgets::healthbar::render_boss_bar(frame, inner, &fight, self.render_mode == RenderMode::Ascii);
+        }
     }
 
     /// Render a minimap in the corner of the map area
@@ -3678,7 +6305,7 @@ impl App {
                 } else if let Some(tile) = map.get_tile(map_x, map_y) {
                     if tile.explored {
                         let (ch, fg) = match tile.tile_type {
-                            TileType::Wall => ('█', Color::Rgb(60, 50, 50)),
+                            TileType::Wall | TileType::SecretDoor => ('█', Color::Rgb(60, 50, 50)),
                             TileType::Floor | TileType::Corridor => {
                                 if tile.visible {
                                     ('·', Color::Rgb(80, 80, 100))
@@ -3688,10 +6315,24 @@ impl App {
                             }
                             TileType::StairsDown => ('>', Color::Rgb(100, 200, 100)),
                             TileType::StairsUp => ('<', Color::Rgb(100, 100, 200)),
-                            TileType::DoorClosed | TileType::DoorOpen => ('+', Color::Rgb(139, 90, 43)),
-                            TileType::ShrineSkill | TileType::ShrineEnchant | TileType::ShrineRest | TileType::ShrineCorruption => ('☼', Color::Rgb(150, 100, 200)),
+                            TileType::Teleporter => ('◎', Color::Rgb(140, 110, 255)),
+                            TileType::PressurePlate => ('^', Color::Rgb(150, 140, 120)),
+                            TileType::Lever | TileType::LeverPulled => ('/', Color::Rgb(190, 160, 90)),
+                            TileType::Portcullis => ('#', Color::Rgb(150, 150, 165)),
+                            TileType::PortcullisOpen => ('.', Color::Rgb(90, 90, 100)),
+                            TileType::TollGate => ('#', Color::Rgb(210, 170, 60)),
+                            TileType::TollGateOpen => ('.', Color::Rgb(110, 95, 50)),
+                            TileType::DoorClosed | TileType::DoorOpen | TileType::DoorLocked => ('+', Color::Rgb(139, 90, 43)),
+                            t if t.is_shrine() => ('☼', Color::Rgb(150, 100, 200)),
+                            t if t.is_altar() => ('Ψ', Color::Rgb(180, 60, 60)),
                             TileType::Lava => ('~', Color::Rgb(200, 60, 20)),
+                            TileType::DeepWater => ('~', Color::Rgb(50, 90, 170)),
+                            TileType::Bridge => ('=', Color::Rgb(150, 110, 70)),
+                            TileType::FleshWall => ('█', Color::Rgb(90, 35, 45)),
+                            TileType::TornFlesh => ('·', Color::Rgb(140, 50, 60)),
+                            TileType::Oil => ('~', Color::Rgb(95, 85, 45)),
                             TileType::Pit => ('○', Color::Rgb(30, 30, 30)),
+                            TileType::BloodPool => ('~', Color::Rgb(170, 20, 40)),
                             TileType::Torch | TileType::Brazier => ('*', Color::Rgb(200, 150, 50)),
                             _ => (' ', Color::Rgb(30, 30, 40)),
                         };
@@ -3711,7 +6352,7 @@ impl App {
         let hash = ((x.wrapping_mul(7) ^ y.wrapping_mul(13)).abs() as usize) % 97;
 
         match tile_type {
-            TileType::Wall => {
+            TileType::Wall | TileType::SecretDoor => {
                 // Vary wall glyphs based on biome config
                 if !config.wall_glyphs.is_empty() {
                     let idx = hash % config.wall_glyphs.len();
@@ -3772,8 +6413,30 @@ impl App {
         frame.render_widget(para, area);
     }
 
+    /// What the side panels show comes down to: they're drawn afresh when
+    /// the player presses a key, a turn passes, a message comes in or a
+    /// clock on show moves on
+    fn panel_key(&self, game: &Game) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (self.input_revision, self.camera, self.layout_profile == LayoutProfile::Compact).hash(&mut hasher);
+        (game.run_clock().turns, game.run_clock().seconds(), game.boss_rush().map(|rush| rush.seconds())).hash(&mut hasher);
+        (game.messages().len(), game.messages().last().map(|m| m.timestamp.to_bits())).hash(&mut hasher);
+        // Resting and channelling fill their bars in real time
+        game.rest_progress().map(|(rest, progress)| (rest.turns, (progress * 10.0).round() as u32)).hash(&mut hasher);
+        game.channel().map(|channel| (channel.turns, (channel.progress() * 10.0).round() as u32)).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The status sidebar, from the cache while nothing on it has changed
     fn render_sidebar(&self, frame: &mut Frame, game: &Game, area: Rect) {
-        use crate::ecs::{EquipmentComponent, StatusEffects, StatusEffectType};
+        let key = self.panel_key(game);
+        self.sidebar_cache.borrow_mut().draw(frame.buffer_mut(), area, key, |buf| self.draw_sidebar(buf, game, area));
+    }
+
+    fn draw_sidebar(&self, buf: &mut ratatui::buffer::Buffer, game: &Game, area: Rect) {
+        use crate::ecs::{StatusEffects, StatusEffectType};
 
         let block = Block::default()
             .borders(Borders::ALL)
@@ -3781,7 +6444,7 @@ impl App {
             .border_style(Style::default().fg(Color::DarkGray));
 
         let inner = block.inner(area);
-        frame.render_widget(block, area);
+        block.render(area, buf);
 
         // Get real player stats
         let health = game.player_health().unwrap_or(crate::ecs::Health::new(100));
@@ -3790,18 +6453,11 @@ impl App {
         let stats = game.player_stats().unwrap_or(crate::ecs::Stats::player_base());
         let xp = game.player_experience().unwrap_or(crate::ecs::Experience::new());
 
-        // Get equipment bonuses for HP/MP
-        let (eq_hp, eq_mp) = if let Some(player) = game.player() {
-            game.world().get::<&EquipmentComponent>(player)
-                .map(|eq| (eq.equipment.hp_bonus(), eq.equipment.mp_bonus()))
-                .unwrap_or((0, 0))
-        } else {
-            (0, 0)
-        };
-
-        // Calculate effective max values
-        let effective_max_hp = health.max + eq_hp;
-        let effective_max_mp = mana.max + eq_mp;
+        // Effective max values, with gear and set bonuses
+        let (effective_max_hp, effective_max_mp) = game.player_derived_stats()
+            .map(|d| (d.max_hp, d.max_mp))
+            .unwrap_or((health.max, mana.max));
+        let (eq_hp, eq_mp) = (effective_max_hp - health.max, effective_max_mp - mana.max);
 
         // HP color based on percentage (using effective max)
         let hp_pct = health.current as f32 / effective_max_hp as f32;
@@ -3840,6 +6496,39 @@ impl App {
                 Span::raw("SP: "),
                 Span::styled(format!("{}/{}", stamina.current, stamina.max), Style::default().fg(Color::Yellow)),
             ]),
+            if let Some((rest, progress)) = game.rest_progress() {
+                let filled = (progress * 10.0).round() as usize;
+                Line::from(vec![
+                    Span::styled("Rest ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                    Span::styled(
+                        format!("[{}{}] {}t", "#".repeat(filled), "-".repeat(10 - filled), rest.turns),
+                        Style::default().fg(if rest.fed_turns > 0 { Color::Green } else { Color::Cyan }),
+                    ),
+                ])
+            } else if let Some(channel) = game.channel() {
+                let filled = (channel.progress() * 10.0).round() as usize;
+                Line::from(vec![
+                    Span::styled(format!("{} ", channel.kind.label()), Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
+                    Span::styled(
+                        format!("[{}{}] {}/{}", "#".repeat(filled), "-".repeat(10 - filled), channel.turns, channel.turns_needed),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                ])
+            } else if game.is_exhausted() {
+                Line::from(Span::styled("EXHAUSTED", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)))
+            } else if game.is_overloaded() {
+                Line::from(Span::styled("OVERLOADED", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)))
+            } else if game.is_sprinting() {
+                Line::from(vec![
+                    Span::styled("Sprint ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!("-{}/turn", game.sprint_cost()), Style::default().fg(Color::Yellow)),
+                ])
+            } else {
+                Line::from(vec![
+                    Span::styled("Walk ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(format!("[s] sprint {}/turn", game.sprint_cost()), Style::default().fg(Color::DarkGray)),
+                ])
+            },
             Line::from(""),
             Line::from(Span::styled(format!("Level {}", xp.level), Style::default().fg(Color::Cyan))),
             Line::from(vec![
@@ -3847,9 +6536,36 @@ impl App {
                 Span::raw(format!("{}/{}", xp.current_xp, xp.xp_to_next)),
             ]),
             Line::from(""),
+            Line::from(
+                [
+                    Span::styled("Floor ", Style::default().fg(Color::Gray)),
+                    Span::styled(format!("{}", game.floor()), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                ]
+                .into_iter()
+                // A boss rush keeps its tally and clock beside the floor
+                .chain(game.boss_rush().map(|rush| Span::styled(
+                    format!(
+                        "  Rush {}/{} {}",
+                        rush.bosses_defeated(),
+                        crate::game::BOSS_RUSH_ORDER.len(),
+                        crate::game::format_rush_time(rush.seconds()),
+                    ),
+                    Style::default().fg(Color::Rgb(220, 120, 60)),
+                )))
+                // Otherwise the time spent on this floor
+                .chain(game.boss_rush().is_none().then(|| Span::styled(
+                    format!("  {}", crate::game::format_run_time(game.run_clock().floor_seconds())),
+                    Style::default().fg(Color::DarkGray),
+                )))
+                .collect::<Vec<_>>(),
+            ),
             Line::from(vec![
-                Span::styled("Floor ", Style::default().fg(Color::Gray)),
-                Span::styled(format!("{}", game.floor()), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                Span::styled("Turn ", Style::default().fg(Color::Gray)),
+                Span::raw(format!("{}  ", game.run_clock().turns)),
+                Span::styled(
+                    crate::game::format_run_time(game.run_clock().seconds()),
+                    Style::default().fg(Color::DarkGray),
+                ),
             ]),
             Line::from(Span::styled(
                 game.biome().name(),
@@ -3866,6 +6582,10 @@ impl App {
                     crate::progression::Difficulty::Hard => Color::Yellow,
                     crate::progression::Difficulty::Nightmare => Color::Red,
                 })),
+                Span::styled(
+                    if game.ng_plus() > 0 { format!(" NG+{}", game.ng_plus()) } else { String::new() },
+                    Style::default().fg(Color::Magenta),
+                ),
             ]),
             Line::from(""),
             Line::from(vec![
@@ -3882,6 +6602,10 @@ impl App {
             ]),
         ];
 
+        if game.speedrunning() {
+            lines.extend(speedrun_split_lines(game));
+        }
+
         // Add status effects section
         if let Some(player) = game.player() {
             if let Ok(status) = game.world().get::<&StatusEffects>(player) {
@@ -3900,6 +6624,7 @@ impl App {
                             StatusEffectType::Burn => ("🔥", Color::Red, false),
                             StatusEffectType::Bleed => ("💉", Color::Red, false),
                             StatusEffectType::Slow => ("🐌", Color::Blue, false),
+                            StatusEffectType::Stun => ("✶", Color::Yellow, false),
                             StatusEffectType::Weakness => ("↓", Color::Magenta, false),
                             StatusEffectType::Curse => ("☽", Color::Rgb(100, 50, 100), false),
                             // Buffs
@@ -3947,7 +6672,7 @@ impl App {
             .query::<(&Position, &crate::ecs::Name, &crate::ecs::Health, &crate::ecs::Enemy)>()
             .iter()
             .filter(|(_, (pos, _, _, _))| pos.chebyshev_distance(&player_pos) <= 8)
-            .map(|(_, (pos, name, hp, _))| (pos.chebyshev_distance(&player_pos), name.0.clone(), *hp))
+            .map(|(_, (pos, name, hp, _))| (pos.chebyshev_distance(&player_pos), intern(&name.0), *hp))
             .collect();
 
         nearby_enemies.sort_by_key(|(dist, _, _)| *dist);
@@ -3999,6 +6724,7 @@ impl App {
                         lines.push(Line::from(vec![
                             Span::styled(format!("[{}]", i + 1), key_style),
                             Span::styled(format!("{}", skill.icon), skill_style),
+                            Span::styled(skill.rank_label(), Style::default().fg(Color::Yellow)),
                             Span::styled(cd_text, Style::default().fg(Color::Red)),
                         ]));
                     }
@@ -4006,6 +6732,42 @@ impl App {
             }
         }
 
+        // Consumable belt
+        if let Some(player) = game.player() {
+            if let Ok(inv) = game.world().get::<&crate::ecs::InventoryComponent>(player) {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled("Belt", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))));
+
+                for slot in 0..crate::items::BELT_SLOTS {
+                    let key = Span::styled(format!("[{}]", slot + 6), Style::default().fg(Color::Yellow));
+                    let Some(name) = inv.inventory.belt().get(slot) else {
+                        lines.push(Line::from(vec![
+                            key.style(Style::default().fg(Color::DarkGray)),
+                            Span::styled(" -", Style::default().fg(Color::DarkGray)),
+                        ]));
+                        continue;
+                    };
+                    let count = inv.inventory.count_named(name);
+                    let glyph = inv.inventory.index_of_named(name)
+                        .and_then(|i| inv.inventory.get(i))
+                        .map(|item| game.discoveries().disguise(item).glyph)
+                        .unwrap_or(' ');
+                    let name = game.discoveries().appearance(name).unwrap_or(name);
+                    let (name_style, count_style) = if count == 0 {
+                        (Style::default().fg(Color::DarkGray), Style::default().fg(Color::Red))
+                    } else {
+                        (Style::default().fg(Color::White), Style::default().fg(Color::Cyan))
+                    };
+                    lines.push(Line::from(vec![
+                        key,
+                        Span::styled(format!("{} ", glyph), name_style),
+                        Span::styled(format!("x{} ", count), count_style),
+                        Span::styled(truncate_name(name, 10), name_style),
+                    ]));
+                }
+            }
+        }
+
         // Controls section
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled("Controls", Style::default().fg(Color::DarkGray))));
@@ -4016,7 +6778,7 @@ impl App {
         lines.push(Line::from(Span::styled("[>] Descend", Style::default().fg(Color::DarkGray))));
 
         let para = Paragraph::new(lines);
-        frame.render_widget(para, inner);
+        para.render(inner, buf);
     }
 
     fn render_inventory_overlay(&self, frame: &mut Frame, game: &Game) {
@@ -4060,7 +6822,9 @@ impl App {
         // Get player data
         let player = game.player();
 
-        if self.inventory_tab == 0 {
+        if self.inventory_tab == 0 && self.inventory_grid_mode {
+            self.render_grid_tab(frame, game, player, layout[1]);
+        } else if self.inventory_tab == 0 {
             // Items tab
             self.render_items_tab(frame, game, player, layout[1]);
         } else {
@@ -4069,10 +6833,12 @@ impl App {
         }
 
         // Help bar
-        let help = if self.inventory_tab == 0 {
-            "[Tab] Switch | [↑↓] Navigate | [Enter] Use/Equip | [D]estroy | [S]ort | [Esc] Close"
+        let help = if self.inventory_tab == 0 && self.inventory_grid_mode {
+            "[Tab] Switch | [←↑↓→] Move | [Space] Pick up/Place | [R]otate | [Enter] Use/Equip | [F] Lock | [D]estroy | [S]ort | [V] List | [Esc] Close"
+        } else if self.inventory_tab == 0 {
+            "[Tab] Switch | [↑↓] Navigate | [Enter] Use/Equip | [6-9] Belt | [F] Lock | [D]estroy | [S]ort | [V] Grid | [Esc] Close"
         } else {
-            "[Tab] Switch | [↑↓] Navigate | [Enter] Unequip | [Esc] Close"
+            "[Tab] Switch | [↑↓] Navigate | [Enter] Unequip | [F] Lock | [Esc] Close"
         };
         let help_para = Paragraph::new(help)
             .style(Style::default().fg(Color::DarkGray))
@@ -4080,6 +6846,76 @@ impl App {
         frame.render_widget(help_para, layout[2]);
     }
 
+    fn render_grid_tab(&self, frame: &mut Frame, game: &Game, player: Option<hecs::Entity>, area: Rect) {
+        use crate::ecs::InventoryComponent;
+        use crate::items::GRID_WIDTH;
+
+        let player = match player {
+            Some(p) => p,
+            None => return,
+        };
+
+        let inv = match game.world().get::<&InventoryComponent>(player) {
+            Ok(i) => i,
+            Err(_) => return,
+        };
+
+        // Grid on the left (2 columns per cell), details on the right
+        let (cols, rows) = inv.inventory.grid_size();
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(GRID_WIDTH as u16 * 2 + 4), Constraint::Min(20)])
+            .split(area);
+        let grid_area = Rect {
+            height: layout[0].height.min(rows as u16 + 2),
+            ..layout[0]
+        };
+
+        let title = format!(" Pack {}x{} ", cols, rows);
+        frame.render_widget(
+            GridInventoryWidget::new(&inv.inventory)
+                .cursor(self.grid_cursor)
+                .selected_item(self.grid_held)
+                .title(&title)
+                .discoveries(game.discoveries()),
+            grid_area,
+        );
+
+        // Status under the grid: what's held, gold and weight
+        let mut status = Vec::new();
+        if let Some(held) = self.grid_held.and_then(|id| inv.inventory.get_by_id(id)) {
+            status.push(Line::from(Span::styled(
+                format!("Holding {}", game.discoveries().disguise(held).name),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        status.push(Line::from(vec![
+            Span::styled("Gold: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{}", inv.inventory.gold()), Style::default().fg(Color::Yellow)),
+        ]));
+        if let Some((weight, capacity)) = game.encumbrance() {
+            status.push(Line::from(Span::styled(
+                format!("Weight: {}/{}", weight, capacity),
+                Style::default().fg(if weight > capacity { Color::Red } else { Color::DarkGray }),
+            )));
+        }
+        let status_area = Rect {
+            y: grid_area.y + grid_area.height,
+            height: layout[0].height.saturating_sub(grid_area.height),
+            ..layout[0]
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+
+        // Details for the held item, else the one under the cursor
+        let shown = self.grid_held
+            .and_then(|id| inv.inventory.get_by_id(id))
+            .or_else(|| inv.inventory.get_at_grid(self.grid_cursor.x, self.grid_cursor.y));
+        if let Some(item) = shown {
+            let value = game.data().economy().value(item);
+            render_item_details(&game.discoveries().disguise(item), value, layout[1], frame.buffer_mut());
+        }
+    }
+
     fn render_items_tab(&self, frame: &mut Frame, game: &Game, player: Option<hecs::Entity>, area: Rect) {
         use crate::ecs::InventoryComponent;
 
@@ -4125,6 +6961,23 @@ impl App {
             Span::styled(sort_mode_name, Style::default().fg(Color::Cyan)),
             Span::styled(new_indicator, Style::default().fg(Color::Green)),
         ]));
+        if let Some((weight, capacity)) = game.encumbrance() {
+            let overloaded = weight > capacity;
+            let mut spans = vec![
+                Span::styled("Weight: ", Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    format!("{}/{}", weight, capacity),
+                    Style::default().fg(if overloaded { Color::Red } else { Color::White }),
+                ),
+            ];
+            if overloaded {
+                spans.push(Span::styled(
+                    "  OVERLOADED - enemies get extra turns",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
         lines.push(Line::from(""));
 
         if items.is_empty() {
@@ -4149,6 +7002,8 @@ impl App {
 
                 let stack_str = if item.stack_count > 1 {
                     format!(" (x{})", item.stack_count)
+                } else if item.wand.is_some() {
+                    format!(" ({}/{})", item.charges, item.max_charges())
                 } else {
                     String::new()
                 };
@@ -4156,12 +7011,19 @@ impl App {
                 // "NEW" indicator for recently picked up items
                 let new_str = if item.is_new { " NEW" } else { "" };
 
+                // Belt slot badge
+                let belt_str = inv.inventory.belt().slot_of(&item.name)
+                    .map(|slot| format!(" [{}]", slot + 6))
+                    .unwrap_or_default();
+
                 // Truncate item name to fit (max 18 chars for name + stack + new info)
-                let display_name = truncate_name(&item.name, 18);
+                let display_name = truncate_name(&game.discoveries().disguise(item).name, 18);
 
                 lines.push(Line::from(vec![
                     Span::raw(prefix),
+                    Span::styled(item.lock_marker(), Style::default().fg(Color::Yellow)),
                     Span::styled(format!("{}{}", display_name, stack_str), style),
+                    Span::styled(belt_str, Style::default().fg(Color::Yellow)),
                     Span::styled(new_str, Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
                 ]));
             }
@@ -4183,6 +7045,9 @@ impl App {
         if let Some(item) = items.get(self.inventory_cursor) {
             use crate::ecs::EquipmentComponent;
 
+            let shown = game.discoveries().disguise(item);
+            let item: &crate::items::Item = &shown;
+
             let rarity_color = item.rarity.color();
             let mut detail_lines: Vec<Line> = Vec::new();
 
@@ -4271,6 +7136,12 @@ impl App {
                         ]));
                     }
                 }
+                if let Some(tags) = item.synergy_summary() {
+                    detail_lines.push(Line::from(vec![
+                        Span::styled("  Synergy: ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(tags, Style::default().fg(Color::Magenta)),
+                    ]));
+                }
 
                 // ══════════════════════════════════════
                 // SECTION 2: CURRENTLY EQUIPPED (what you'll replace)
@@ -4363,6 +7234,16 @@ impl App {
                         Span::styled(format!("{}", item.total_armor()), Style::default().fg(Color::Blue)),
                     ]));
                 }
+                if let Some(charges) = item.charge_label() {
+                    detail_lines.push(Line::from(vec![
+                        Span::styled("Bolt: ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(format!("{} damage", item.zap_damage()), Style::default().fg(Color::Red)),
+                    ]));
+                    detail_lines.push(Line::from(vec![
+                        Span::styled("Charges: ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(charges, Style::default().fg(Color::Cyan)),
+                    ]));
+                }
 
                 // Affixes with descriptions
                 if !item.affixes.is_empty() {
@@ -4378,6 +7259,12 @@ impl App {
                         )));
                     }
                 }
+                if let Some(tags) = item.synergy_summary() {
+                    detail_lines.push(Line::from(vec![
+                        Span::styled("Synergy: ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(tags, Style::default().fg(Color::Magenta)),
+                    ]));
+                }
 
                 // Consumable effect
                 if let Some(effect) = &item.consumable_effect {
@@ -4386,6 +7273,14 @@ impl App {
                         ConsumableEffect::HealHP(n) => format!("Heals {} HP", n),
                         ConsumableEffect::RestoreMP(n) => format!("Restores {} MP", n),
                         ConsumableEffect::RestoreSP(n) => format!("Restores {} SP", n),
+                        ConsumableEffect::Mutate => "Grants a random mutation".to_string(),
+                        ConsumableEffect::Ration => format!(
+                            "Feeds {} turns of rest (+{} HP/turn)",
+                            crate::game::TURNS_PER_RATION, crate::game::FED_HEAL_BONUS,
+                        ),
+                        ConsumableEffect::ExpandPack(n) => format!("Adds {} row(s) to your pack", n),
+                        ConsumableEffect::TreatInjuries => "Treats all injuries".to_string(),
+                        ConsumableEffect::Return => "Returns you to the floor's entrance".to_string(),
                         _ => "Special effect".to_string(),
                     };
                     detail_lines.push(Line::from(""));
@@ -4410,7 +7305,7 @@ impl App {
             detail_lines.push(Line::from(""));
             detail_lines.push(Line::from(vec![
                 Span::styled("Value: ", Style::default().fg(Color::DarkGray)),
-                Span::styled(format!("{} gold", item.value), Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{} gold", game.data().economy().value(item)), Style::default().fg(Color::Yellow)),
             ]));
 
             let detail_para = Paragraph::new(detail_lines);
@@ -4488,6 +7383,7 @@ impl App {
                 lines.push(Line::from(vec![
                     Span::raw(prefix),
                     Span::styled(format!("{} ", slot_indicator), Style::default().fg(Color::DarkGray)),
+                    Span::styled(item.lock_marker(), Style::default().fg(Color::Yellow)),
                     Span::styled(display_name, style),
                     Span::styled(new_str, Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
                 ]));
@@ -4618,6 +7514,12 @@ impl App {
                     )));
                 }
             }
+            if let Some(tags) = item.synergy_summary() {
+                detail_lines.push(Line::from(Span::styled(
+                    format!("Synergy: {}", tags),
+                    Style::default().fg(Color::Magenta),
+                )));
+            }
 
             let detail_para = Paragraph::new(detail_lines);
             frame.render_widget(detail_para, layout[1]);
@@ -4627,7 +7529,6 @@ impl App {
     fn render_character_overlay(&self, frame: &mut Frame, game: &Game) {
         use crate::ecs::{EquipmentComponent, Health, Mana, Stamina, Stats, Experience, StatPoints, SkillsComponent};
         use crate::items::{EquipSlot, AffixType};
-        use crate::combat::{crit_chance, dodge_chance};
         use crate::progression::SkillCost;
 
         let area = fullscreen_overlay(frame.area());
@@ -4666,33 +7567,34 @@ impl App {
         let skills = game.world().get::<&SkillsComponent>(player).ok();
         let equipment = game.world().get::<&EquipmentComponent>(player).ok();
 
-        // Equipment bonuses
-        let (eq_str, eq_dex, eq_int, eq_vit, weapon_dmg, total_armor, crit_bonus, eq_hp, eq_mp) =
-            if let Some(eq) = &equipment {
-                (eq.equipment.strength_bonus(), eq.equipment.dexterity_bonus(),
-                 eq.equipment.intelligence_bonus(), eq.equipment.vitality_bonus(),
-                 eq.equipment.weapon_damage(), eq.equipment.total_armor(),
-                 eq.equipment.weapon_crit_bonus(), eq.equipment.hp_bonus(), eq.equipment.mp_bonus())
-            } else { (0, 0, 0, 0, 2, 0, 0.0, 0, 0) };
-
-        let effective_max_hp = health.max + eq_hp;
-        let effective_max_mp = mana.max + eq_mp;
-        let eff_str = base_stats.strength + eq_str;
-        let eff_dex = base_stats.dexterity + eq_dex;
-        let _eff_int = base_stats.intelligence + eq_int;
-        let eff_vit = base_stats.vitality + eq_vit;
-        let total_crit = crit_chance(eff_dex) + crit_bonus;
-        let total_dodge = dodge_chance(eff_dex);
-        let phys_damage = 2 + eff_str / 2 + weapon_dmg;
-        let effective_armor = eff_vit / 4 + total_armor;
-        let damage_reduction = effective_armor as f32 / (effective_armor as f32 + 20.0) * 100.0;
+        // Derived stats, with gear, sets, mutations and buffs applied
+        let Some(derived) = game.player_derived_stats() else { return };
+        let sources = derived.sources;
+        let (effective_max_hp, effective_max_mp) = (derived.max_hp, derived.max_mp);
+        let (eq_hp, eq_mp) = (derived.max_hp - health.max, derived.max_mp - mana.max);
+        let (eq_str, eq_dex, eq_int, eq_vit) =
+            (sources.gear.strength, sources.gear.dexterity, sources.gear.intelligence, sources.gear.vitality);
+        let total_crit = sources.crit().total;
+        let total_dodge = sources.dodge().total;
+        let phys_damage = sources.damage().total.round() as i32;
+        let total_armor = sources.armor().total as i32;
+        let damage_reduction = sources.damage_reduction().total;
+        let weight_class = equipment.as_ref()
+            .map(|e| e.equipment.weight_class())
+            .unwrap_or(crate::items::WeightClass::Light);
+        let (two_handed, block_chance) = equipment.as_ref()
+            .map(|e| (e.equipment.is_two_handed(), e.equipment.block_chance()))
+            .unwrap_or((false, 0));
+        let offhand_damage = derived.offhand_bonuses()
+            .map(|b| (crate::combat::damage::base_physical_damage(sources.strength()) + b.weapon_damage) * (100 + b.damage_percent) / 100)
+            .unwrap_or(0);
 
         // Get all combat bonuses from equipment
         let fire_dmg = equipment.as_ref().map(|e| e.equipment.stat_bonus(AffixType::FireDamage)).unwrap_or(0);
         let ice_dmg = equipment.as_ref().map(|e| e.equipment.stat_bonus(AffixType::IceDamage)).unwrap_or(0);
         let lightning_dmg = equipment.as_ref().map(|e| e.equipment.stat_bonus(AffixType::LightningDamage)).unwrap_or(0);
         let poison_dmg = equipment.as_ref().map(|e| e.equipment.stat_bonus(AffixType::PoisonDamage)).unwrap_or(0);
-        let lifesteal = equipment.as_ref().map(|e| e.equipment.stat_bonus(AffixType::LifeSteal)).unwrap_or(0);
+        let lifesteal = derived.lifesteal.round() as i32;
         let bonus_crit_dmg = equipment.as_ref().map(|e| e.equipment.stat_bonus(AffixType::BonusCritDamage)).unwrap_or(0);
         let fire_res = equipment.as_ref().map(|e| e.equipment.stat_bonus(AffixType::FireResist)).unwrap_or(0);
         let ice_res = equipment.as_ref().map(|e| e.equipment.stat_bonus(AffixType::IceResist)).unwrap_or(0);
@@ -4723,6 +7625,8 @@ impl App {
             Span::styled(" Unequip ", Style::default().fg(Color::DarkGray)),
             Span::styled("[1-4]", Style::default().fg(Color::Yellow)),
             Span::styled(" +Stats ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[F]", Style::default().fg(Color::Yellow)),
+            Span::styled(" Formulas ", Style::default().fg(Color::DarkGray)),
             Span::styled("[C/Esc]", Style::default().fg(Color::Yellow)),
             Span::styled(" Close", Style::default().fg(Color::DarkGray)),
         ]);
@@ -4878,24 +7782,37 @@ impl App {
         // === COMBAT STATS ROW (HORIZONTAL LAYOUT) ===
         let mut combat_lines: Vec<Line> = Vec::new();
 
-        // Row 1: Header with main combat stats
+        // Row 1: Header with main combat stats; in info mode, the one being
+        // explained is highlighted (damage, armor, reduction, crit, dodge)
+        let stat_label = |label: &'static str, index: usize| {
+            let style = Style::default().fg(Color::Gray);
+            if self.stat_info == Some(index) {
+                Span::styled(label, style.add_modifier(Modifier::REVERSED))
+            } else {
+                Span::styled(label, style)
+            }
+        };
         combat_lines.push(Line::from(vec![
             Span::styled("─── COMBAT ", Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
             Span::styled("│ ", Style::default().fg(Color::DarkGray)),
-            Span::styled("Phys ", Style::default().fg(Color::Gray)),
+            stat_label("Phys ", 0),
             Span::styled(format!("{}", phys_damage), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
             Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
-            Span::styled("Armor ", Style::default().fg(Color::Gray)),
+            stat_label("Armor ", 1),
             Span::styled(format!("{}", total_armor), Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)),
-            Span::styled(format!(" ({:.0}%)", damage_reduction), Style::default().fg(Color::DarkGray)),
+            if self.stat_info == Some(2) {
+                Span::styled(format!(" ({:.0}%)", damage_reduction), Style::default().fg(Color::Gray).add_modifier(Modifier::REVERSED))
+            } else {
+                Span::styled(format!(" ({:.0}%)", damage_reduction), Style::default().fg(Color::DarkGray))
+            },
             Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
-            Span::styled("Crit ", Style::default().fg(Color::Gray)),
+            stat_label("Crit ", 3),
             Span::styled(format!("{:.1}%", total_crit), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
             if bonus_crit_dmg > 0 { Span::styled(format!(" +{}%dmg", bonus_crit_dmg), Style::default().fg(Color::Yellow)) } else { Span::raw("") },
             Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
-            Span::styled("Dodge ", Style::default().fg(Color::Gray)),
+            stat_label("Dodge ", 4),
             Span::styled(format!("{:.1}%", total_dodge), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-            if lifesteal > 0 { Span::styled(format!(" │ Steal {}%", lifesteal * 5), Style::default().fg(Color::Magenta)) } else { Span::raw("") },
+            if lifesteal > 0 { Span::styled(format!(" │ Steal {}%", lifesteal), Style::default().fg(Color::Magenta)) } else { Span::raw("") },
         ]));
 
         // Row 2: Elemental damage and resistances
@@ -4934,6 +7851,43 @@ impl App {
             Span::styled(format!("+{}%", magic_find), Style::default().fg(if magic_find > 0 { Color::Magenta } else { Color::DarkGray })),
         ]));
 
+        // Row 4: How the hands are filled
+        let wield_spans = if two_handed {
+            vec![Span::styled("Two-handed weapon", Style::default().fg(Color::Gray))]
+        } else if offhand_damage > 0 {
+            vec![
+                Span::styled("Dual wield ", Style::default().fg(Color::Gray)),
+                Span::styled(format!("off-hand {}", offhand_damage), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" (-{} DEX)", crate::items::DUAL_WIELD_DEX_PENALTY), Style::default().fg(Color::DarkGray)),
+            ]
+        } else if block_chance > 0 {
+            vec![
+                Span::styled("Block ", Style::default().fg(Color::Gray)),
+                Span::styled(format!("{}%", block_chance), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            ]
+        } else {
+            vec![Span::styled("One-handed", Style::default().fg(Color::DarkGray))]
+        };
+        let mut wield_line = vec![
+            Span::styled("─── WIELD ", Style::default().fg(Color::DarkGray)),
+            Span::styled("  │ ", Style::default().fg(Color::DarkGray)),
+        ];
+        wield_line.extend(wield_spans);
+        let weight_color = match weight_class {
+            crate::items::WeightClass::Light => Color::Green,
+            crate::items::WeightClass::Medium => Color::Yellow,
+            crate::items::WeightClass::Heavy => Color::Red,
+        };
+        wield_line.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+        wield_line.push(Span::styled("Armor ", Style::default().fg(Color::Gray)));
+        wield_line.push(Span::styled(weight_class.name(), Style::default().fg(weight_color).add_modifier(Modifier::BOLD)));
+        wield_line.push(Span::styled(
+            format!(" (noise {:+}, SP regen x{:.2}, dash {:+})",
+                weight_class.noise(), weight_class.stamina_regen_multiplier(), weight_class.movement_range_modifier()),
+            Style::default().fg(Color::DarkGray),
+        ));
+        combat_lines.push(Line::from(wield_line));
+
         frame.render_widget(Paragraph::new(combat_lines), rows[2]);
 
         // === BOTTOM ROW: Equipment+Skills (left) | Item Details (right) ===
@@ -4945,12 +7899,13 @@ impl App {
             ])
             .split(rows[3]);
 
-        // Split left column into Equipment (top) and Skills (bottom)
+        // Split left column into Equipment, Skills and Synergies
         let left_rows = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(12),  // Equipment (9 slots + header/footer)
-                Constraint::Min(6),      // Skills
+                Constraint::Length(9),   // Skills
+                Constraint::Min(3),      // Active synergies
             ])
             .split(bottom_cols[0]);
 
@@ -4984,6 +7939,20 @@ impl App {
                     Span::styled(format!("{:<9}", slot_names[i]), Style::default().fg(Color::Gray)),
                     Span::styled(display_name, name_style),
                 ])
+            } else if *slot == EquipSlot::OffHand && equipment.as_ref().is_some_and(|e| e.equipment.is_two_handed()) {
+                Line::from(vec![
+                    Span::styled("║ ", Style::default().fg(Color::Yellow)),
+                    Span::styled(prefix, prefix_style),
+                    Span::styled(format!("{:<9}", slot_names[i]), Style::default().fg(Color::DarkGray)),
+                    Span::styled("- two-handed -", Style::default().fg(Color::Gray)),
+                ])
+            } else if game.mutation_blocking(*slot).is_some() {
+                Line::from(vec![
+                    Span::styled("║ ", Style::default().fg(Color::Yellow)),
+                    Span::styled(prefix, prefix_style),
+                    Span::styled(format!("{:<9}", slot_names[i]), Style::default().fg(Color::DarkGray)),
+                    Span::styled("- mutated -", Style::default().fg(Color::Rgb(150, 70, 200))),
+                ])
             } else {
                 Line::from(vec![
                     Span::styled("║ ", Style::default().fg(Color::Yellow)),
@@ -5001,8 +7970,11 @@ impl App {
         // --- ITEM DETAILS COLUMN ---
         let mut detail_lines: Vec<Line> = Vec::new();
 
-        // Check if in equip selection mode
-        if self.equip_selection_mode && self.character_slot < NUM_EQUIP_SLOTS {
+        let breakdown = self.stat_info
+            .and_then(|i| game.player_derived_stats().and_then(|d| d.sources.breakdowns().into_iter().nth(i)));
+        if let Some(breakdown) = breakdown {
+            detail_lines = stat_breakdown_lines(&breakdown);
+        } else if self.equip_selection_mode && self.character_slot < NUM_EQUIP_SLOTS {
             use crate::ecs::InventoryComponent;
             let current_slot = slot_order[self.character_slot];
 
@@ -5014,7 +7986,7 @@ impl App {
             let matching_items: Vec<(usize, crate::items::Item)> = game.world()
                 .get::<&InventoryComponent>(player)
                 .map(|inv| inv.inventory.items().into_iter().enumerate()
-                    .filter(|(_, item)| item.equip_slot == Some(current_slot))
+                    .filter(|(_, item)| item.fits_slot(current_slot))
                     .map(|(i, item)| (i, item.clone())).collect())
                 .unwrap_or_default();
 
@@ -5083,6 +8055,13 @@ impl App {
                         ]));
                     }
                 }
+                if let Some(tags) = item.synergy_summary() {
+                    detail_lines.push(Line::from(vec![
+                        Span::styled("║ ", Style::default().fg(Color::Yellow)),
+                        Span::styled("Synergy: ", Style::default().fg(Color::Gray)),
+                        Span::styled(tags, Style::default().fg(Color::Magenta)),
+                    ]));
+                }
 
                 // Enhancements
                 if item.enchantment_level > 0 || item.awakening_level > 0 || item.corruption_level > 0 {
@@ -5128,7 +8107,7 @@ impl App {
 
                 detail_lines.push(Line::from(vec![
                     Span::styled("║ ", Style::default().fg(Color::Yellow)),
-                    Span::styled(format!("Value: {} gold", item.value), Style::default().fg(Color::Yellow)),
+                    Span::styled(format!("Value: {} gold", game.data().economy().value(item)), Style::default().fg(Color::Yellow)),
                 ]));
             } else {
                 detail_lines.push(Line::from(vec![
@@ -5153,11 +8132,15 @@ impl App {
                 detail_lines.push(Line::from(vec![
                     Span::styled("║ ", Style::default().fg(Color::Magenta)),
                     Span::styled(format!("{} ", skill.icon), Style::default().fg(Color::Rgb(color.0, color.1, color.2))),
-                    Span::styled(&skill.name, Style::default().fg(Color::Rgb(color.0, color.1, color.2)).add_modifier(Modifier::BOLD)),
+                    Span::styled(skill.display_name(), Style::default().fg(Color::Rgb(color.0, color.1, color.2)).add_modifier(Modifier::BOLD)),
                 ]));
                 detail_lines.push(Line::from(vec![
                     Span::styled("║ ", Style::default().fg(Color::Magenta)),
                     Span::styled(skill.rarity.name(), Style::default().fg(Color::Rgb(color.0, color.1, color.2))),
+                    Span::styled(
+                        format!(" - Rank {}/{}", skill.rank, crate::progression::MAX_SKILL_RANK),
+                        Style::default().fg(Color::Yellow),
+                    ),
                 ]));
                 detail_lines.push(Line::from(vec![
                     Span::styled("║ ", Style::default().fg(Color::Magenta)),
@@ -5194,7 +8177,64 @@ impl App {
             detail_lines.push(Line::from(Span::styled("╚═══════════════════════════════════════╝", Style::default().fg(Color::Magenta))));
         }
 
-        frame.render_widget(Paragraph::new(detail_lines), bottom_cols[1]);
+        // --- MUTATIONS PANEL (below item details) ---
+        let mutations = game.world()
+            .get::<&crate::progression::Mutations>(player)
+            .map(|m| m.mutations.clone())
+            .unwrap_or_default();
+        let mutation_height = if mutations.is_empty() { 3 } else { mutations.len() as u16 * 2 + 2 };
+        let injuries = game.world()
+            .get::<&crate::progression::Injuries>(player)
+            .map(|i| i.injuries.clone())
+            .unwrap_or_default();
+        let injury_height = if injuries.is_empty() { 0 } else { injuries.len() as u16 + 2 };
+        let right_rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(8),                   // Item details
+                Constraint::Length(mutation_height),  // Mutations
+                Constraint::Length(injury_height),    // Injuries
+            ])
+            .split(bottom_cols[1]);
+
+        frame.render_widget(Paragraph::new(detail_lines), right_rows[0]);
+
+        let mutation_color = Color::Rgb(150, 70, 200);
+        let mut mutation_lines: Vec<Line> = Vec::new();
+        mutation_lines.push(Line::from(Span::styled("╔═══ MUTATIONS ═════════════════════════╗", Style::default().fg(mutation_color))));
+        if mutations.is_empty() {
+            mutation_lines.push(Line::from(vec![
+                Span::styled("║ ", Style::default().fg(mutation_color)),
+                Span::styled("Your body is still your own", Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+        for mutation in &mutations {
+            mutation_lines.push(Line::from(vec![
+                Span::styled("║ ", Style::default().fg(mutation_color)),
+                Span::styled(mutation.name(), Style::default().fg(mutation_color).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("  {}", mutation.benefit()), Style::default().fg(Color::Green)),
+            ]));
+            mutation_lines.push(Line::from(vec![
+                Span::styled("║   ", Style::default().fg(mutation_color)),
+                Span::styled(mutation.drawback(), Style::default().fg(Color::Red)),
+            ]));
+        }
+        mutation_lines.push(Line::from(Span::styled("╚═══════════════════════════════════════╝", Style::default().fg(mutation_color))));
+        frame.render_widget(Paragraph::new(mutation_lines), right_rows[1]);
+
+        if !injuries.is_empty() {
+            let mut injury_lines: Vec<Line> = Vec::new();
+            injury_lines.push(Line::from(Span::styled("╔═══ INJURIES ══════════════════════════╗", Style::default().fg(Color::Red))));
+            for injury in &injuries {
+                injury_lines.push(Line::from(vec![
+                    Span::styled("║ ", Style::default().fg(Color::Red)),
+                    Span::styled(injury.name(), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!("  {}", injury.effect()), Style::default().fg(Color::Gray)),
+                ]));
+            }
+            injury_lines.push(Line::from(Span::styled("╚═══════════════════════════════════════╝", Style::default().fg(Color::Red))));
+            frame.render_widget(Paragraph::new(injury_lines), right_rows[2]);
+        }
 
         // --- SKILLS COLUMN ---
         let mut skill_lines: Vec<Line> = Vec::new();
@@ -5234,6 +8274,7 @@ impl App {
                         Span::styled(format!("[{}] ", i+1), Style::default().fg(Color::Yellow)),
                         Span::styled(format!("{} ", skill.icon), skill_style),
                         Span::styled(truncate_name(&skill.name, 10), skill_style),
+                        Span::styled(format!(" {}", skill.rank_label()), Style::default().fg(Color::Yellow)),
                         Span::styled(cd_text, Style::default().fg(Color::Red)),
                         Span::styled(format!(" {}", cost_str), Style::default().fg(Color::DarkGray)),
                     ]));
@@ -5262,9 +8303,178 @@ impl App {
                 ]));
             }
         }
-        skill_lines.push(Line::from(Span::styled("╚═══════════════════════════╝", Style::default().fg(Color::Magenta))));
+        skill_lines.push(Line::from(Span::styled("╚═══════════════════════════╝", Style::default().fg(Color::Magenta))));
+
+        frame.render_widget(Paragraph::new(skill_lines), left_rows[1]);
+
+        // --- ACTIVE SYNERGIES ---
+        let progress = equipment.as_ref()
+            .map(|eq| crate::items::synergy_progress(&eq.equipment.synergy_tags()))
+            .unwrap_or_default();
+        let mut synergy_lines: Vec<Line> = Vec::new();
+        synergy_lines.push(Line::from(Span::styled("╔═══ ACTIVE SYNERGIES ══════╗", Style::default().fg(Color::Cyan))));
+        if progress.is_empty() {
+            synergy_lines.push(Line::from(vec![
+                Span::styled("║ ", Style::default().fg(Color::Cyan)),
+                Span::styled("Wear matching tags to build sets", Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+        for p in &progress {
+            let active = p.tier_level > 0;
+            let style = if active {
+                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            synergy_lines.push(Line::from(vec![
+                Span::styled("║ ", Style::default().fg(Color::Cyan)),
+                Span::styled(format!("{}/{} ", p.item_count, p.goal()), style),
+                Span::styled(p.synergy.name, style),
+                Span::styled(format!(" ({})", p.synergy.tag.name()), Style::default().fg(Color::DarkGray)),
+            ]));
+            if let Some(tier) = p.synergy.active_tier(p.item_count) {
+                let bonuses: Vec<String> = tier.bonuses.iter().map(|b| b.describe()).collect();
+                synergy_lines.push(Line::from(vec![
+                    Span::styled("║   ", Style::default().fg(Color::Cyan)),
+                    Span::styled(bonuses.join(", "), Style::default().fg(Color::Green)),
+                ]));
+            }
+        }
+        synergy_lines.push(Line::from(Span::styled("╚═══════════════════════════╝", Style::default().fg(Color::Cyan))));
+
+        frame.render_widget(Paragraph::new(synergy_lines), left_rows[2]);
+    }
+
+    fn render_pickup_overlay(&self, frame: &mut Frame, game: &Game) {
+        use crate::ecs::{GroundItem, InventoryComponent};
+
+        let area = centered_rect(50, 50, frame.area());
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Pick Up ")
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let inventory = game.player()
+            .and_then(|p| game.world().get::<&InventoryComponent>(p).ok());
+        let free = inventory.as_ref().map(|inv| inv.inventory.free_cells()).unwrap_or(0);
+        let capacity = inventory.as_ref().map(|inv| inv.inventory.capacity()).unwrap_or(0);
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Pack space: ", Style::default().fg(Color::Gray)),
+                Span::styled(format!("{} / {} cells free", free, capacity), Style::default().fg(Color::White)),
+            ]),
+            Line::from(""),
+        ];
+
+        let encumbered = game.encumbrance().is_some();
+        let mut marked_cells = 0;
+        let mut marked_weight = 0;
+        let mut marked_count = 0;
+        for (i, (entity, marked)) in self.pickup_choices.iter().enumerate() {
+            let Ok(ground) = game.world().get::<&GroundItem>(*entity) else { continue };
+            let item: &crate::items::Item = &game.discoveries().disguise(&ground.item);
+            let cells = item.grid_size.0 as usize * item.grid_size.1 as usize;
+            if *marked {
+                marked_cells += cells;
+                marked_weight += item.weight();
+                marked_count += 1;
+            }
+
+            let is_selected = i == self.pickup_cursor;
+            let (r, g, b) = item.rarity.color();
+            let name_style = if is_selected {
+                Style::default().fg(Color::Rgb(r, g, b)).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Rgb(r, g, b))
+            };
+            let mut spans = vec![
+                Span::styled(if is_selected { "> " } else { "  " }, Style::default().fg(Color::Yellow)),
+                Span::styled(if *marked { "[x] " } else { "[ ] " }, Style::default().fg(Color::White)),
+                Span::styled(item.name.clone(), name_style),
+                Span::styled(format!(" [{}]", item.rarity.name()), Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("  {}x{}", item.grid_size.0, item.grid_size.1), Style::default().fg(Color::Gray)),
+            ];
+            if encumbered {
+                spans.push(Span::styled(format!("  wt {}", item.weight()), Style::default().fg(Color::Gray)));
+            }
+            if !inventory.as_ref().is_some_and(|inv| inv.inventory.can_fit(item)) {
+                spans.push(Span::styled("  no room", Style::default().fg(Color::Red)));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        lines.push(Line::from(""));
+        let load_color = if marked_cells > free { Color::Red } else { Color::Green };
+        let mut summary = vec![
+            Span::styled(format!("Marked: {} item(s), ", marked_count), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{} cells", marked_cells), Style::default().fg(load_color)),
+        ];
+        if let Some((weight, capacity)) = game.encumbrance() {
+            let after = weight + marked_weight;
+            summary.push(Span::styled(
+                format!("  Weight {}/{}", after, capacity),
+                Style::default().fg(if after > capacity { Color::Red } else { Color::Green }),
+            ));
+        }
+        lines.push(Line::from(summary));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "[Space] Mark  [A] Mark all  [Enter/G] Take  [Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+
+    fn render_discoveries_overlay(&self, frame: &mut Frame, game: &Game) {
+        let area = centered_rect(50, 50, frame.area());
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Discoveries ")
+            .border_style(Style::default().fg(Color::Magenta));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let entries = game.discoveries().entries();
+        let known = entries.iter().filter(|(_, name)| name.is_some()).count();
+        let mut lines: Vec<Line> = vec![
+            Line::from(Span::styled(
+                format!("Identified {} of {} this run", known, entries.len()),
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+        ];
+        for (appearance, name) in entries {
+            let (glyph, glyph_color) = if appearance.starts_with("Scroll") {
+                ('?', Color::Yellow)
+            } else {
+                ('!', Color::Cyan)
+            };
+            let mut spans = vec![
+                Span::styled(format!("{} ", glyph), Style::default().fg(glyph_color)),
+                Span::styled(format!("{:<30}", appearance), Style::default().fg(Color::White)),
+            ];
+            match name {
+                Some(name) => spans.push(Span::styled(name, Style::default().fg(Color::Green))),
+                None => spans.push(Span::styled("???", Style::default().fg(Color::DarkGray))),
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Drink, read or pay a merchant to identify. [Esc] Close",
+            Style::default().fg(Color::DarkGray),
+        )));
 
-        frame.render_widget(Paragraph::new(skill_lines), left_rows[1]);
+        frame.render_widget(Paragraph::new(lines), inner);
     }
 
     fn render_fullmap_overlay(&self, frame: &mut Frame, game: &Game) {
@@ -5285,7 +8495,7 @@ impl App {
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(format!(" Map - Floor {} ", floor))
+            .title(format!(" Map - {} ", floor_name(floor)))
             .border_style(Style::default().fg(Color::Green));
 
         let inner = block.inner(area);
@@ -5368,21 +8578,42 @@ impl App {
                         // Normal tile based on type
                         use crate::world::TileType;
                         match tile.tile_type {
-                            TileType::Wall => ('#', Style::default().fg(Color::Rgb(80, 80, 100))),
+                            TileType::Wall | TileType::SecretDoor => ('#', Style::default().fg(Color::Rgb(80, 80, 100))),
                             TileType::Floor => ('.', Style::default().fg(Color::Rgb(60, 60, 60))),
                             TileType::Corridor => ('.', Style::default().fg(Color::Rgb(50, 50, 50))),
                             TileType::Lava => ('~', Style::default().fg(Color::Rgb(255, 100, 0))),
+                            TileType::DeepWater => ('~', Style::default().fg(Color::Rgb(60, 110, 200))),
+                            TileType::Bridge => ('=', Style::default().fg(Color::Rgb(150, 110, 70))),
+                            TileType::FleshWall => ('#', Style::default().fg(Color::Rgb(170, 70, 85))),
+                            TileType::TornFlesh => (',', Style::default().fg(Color::Rgb(140, 50, 60))),
+                            TileType::Oil => ('~', Style::default().fg(Color::Rgb(95, 85, 45))),
                             TileType::Pit => (' ', Style::default().bg(Color::Rgb(10, 10, 10))),
+                            TileType::BloodPool => ('~', Style::default().fg(Color::Rgb(170, 20, 40))),
                             TileType::DoorClosed => ('+', Style::default().fg(Color::Rgb(139, 90, 43))),
+                            TileType::DoorLocked => ('+', Style::default().fg(Color::Rgb(190, 150, 60))),
                             TileType::DoorOpen => ('/', Style::default().fg(Color::Rgb(139, 90, 43))),
                             TileType::StairsDown => ('>', Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
                             TileType::StairsUp => ('<', Style::default().fg(Color::LightBlue)),
+                            TileType::Teleporter => ('◎', Style::default().fg(Color::Rgb(140, 110, 255))),
+                            TileType::PressurePlate => ('^', Style::default().fg(Color::Rgb(150, 140, 120))),
+                            TileType::Lever => ('/', Style::default().fg(Color::Rgb(190, 160, 90))),
+                            TileType::LeverPulled => ('\\', Style::default().fg(Color::Rgb(190, 160, 90))),
+                            TileType::Portcullis => ('╫', Style::default().fg(Color::Rgb(150, 150, 165))),
+                            TileType::PortcullisOpen => ('·', Style::default().fg(Color::Rgb(90, 90, 100))),
+                            TileType::TollGate => ('╪', Style::default().fg(Color::Rgb(210, 170, 60))),
+                            TileType::TollGateOpen => ('·', Style::default().fg(Color::Rgb(110, 95, 50))),
                             TileType::Torch => ('≈', Style::default().fg(Color::Yellow)),
                             TileType::Brazier => ('Ω', Style::default().fg(Color::Rgb(255, 150, 50))),
                             TileType::ShrineRest => ('♥', Style::default().fg(Color::LightRed)),
                             TileType::ShrineSkill => ('★', Style::default().fg(Color::Magenta)),
                             TileType::ShrineEnchant => ('◆', Style::default().fg(Color::Cyan)),
                             TileType::ShrineCorruption => ('✧', Style::default().fg(Color::Rgb(128, 0, 128))),
+                            TileType::ShrineGamble => ('¢', Style::default().fg(Color::Rgb(255, 215, 0))),
+                            TileType::ShrineSacrifice => ('†', Style::default().fg(Color::Rgb(220, 40, 40))),
+                            TileType::ShrineTransmute => ('⚗', Style::default().fg(Color::Rgb(120, 255, 200))),
+                            TileType::AltarMaw => ('Ψ', Style::default().fg(Color::Rgb(200, 30, 30))),
+                            TileType::AltarWidow => ('Ψ', Style::default().fg(Color::Rgb(190, 180, 170))),
+                            TileType::AltarEye => ('Ψ', Style::default().fg(Color::Rgb(60, 140, 200))),
                             TileType::Bones => (',', Style::default().fg(Color::Rgb(200, 200, 180))),
                             TileType::BloodStain => (',', Style::default().fg(Color::Rgb(100, 30, 30))),
                             TileType::Rubble => (';', Style::default().fg(Color::Rgb(100, 100, 100))),
@@ -5455,6 +8686,22 @@ impl App {
             Span::styled("  Space / .         ", Style::default().fg(Color::White)),
             Span::styled("Wait one turn", Style::default().fg(Color::Gray)),
         ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Shift+R           ", Style::default().fg(Color::White)),
+            Span::styled("Rest until healed (eats rations, any key stops)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Shift+B           ", Style::default().fg(Color::White)),
+            Span::styled("Bandage wounds over a few turns (stops bleeding)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Shift+L           ", Style::default().fg(Color::White)),
+            Span::styled("Pick an adjacent lock (DEX helps, damage interrupts)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Shift+S           ", Style::default().fg(Color::White)),
+            Span::styled("Search nearby walls for secret doors (INT helps)", Style::default().fg(Color::Gray)),
+        ]));
         lines.push(Line::from(vec![
             Span::styled("  E                 ", Style::default().fg(Color::White)),
             Span::styled("Interact (shrines, stairs, NPCs)", Style::default().fg(Color::Gray)),
@@ -5471,9 +8718,73 @@ impl App {
             Span::styled("  1-5               ", Style::default().fg(Color::White)),
             Span::styled("Use skills", Style::default().fg(Color::Gray)),
         ]));
+        lines.push(Line::from(vec![
+            Span::styled("  6-9               ", Style::default().fg(Color::White)),
+            Span::styled("Quick-use belt (assign with 6-9 in inventory)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Shift+D           ", Style::default().fg(Color::White)),
+            Span::styled("Discoveries (potions and scrolls identified)", Style::default().fg(Color::Gray)),
+        ]));
         lines.push(Line::from(vec![
             Span::styled("  G                 ", Style::default().fg(Color::White)),
-            Span::styled("Pick up item", Style::default().fg(Color::Gray)),
+            Span::styled("Pick up item (choose from piles marked &)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  S                 ", Style::default().fg(Color::White)),
+            Span::styled("Toggle sprint (2 tiles per turn, costs SP)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  X / P             ", Style::default().fg(Color::White)),
+            Span::styled("Close adjacent doors / peek through them (bump to open)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  ;                 ", Style::default().fg(Color::White)),
+            Span::styled("Look around (or hover the mouse over the map)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  [ / ]             ", Style::default().fg(Color::White)),
+            Span::styled("Fold away the side panels / message log", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Shift+Z           ", Style::default().fg(Color::White)),
+            Span::styled("Zen mode (map only, with a one-line status strip)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Ctrl+P            ", Style::default().fg(Color::White)),
+            Span::styled("Command palette (search every action by name)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  D + direction     ", Style::default().fg(Color::White)),
+            Span::styled("Disengage (step away without free attacks, costs SP)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Shift+P + dir     ", Style::default().fg(Color::White)),
+            Span::styled("Pull a prop after you as you back away (walk into one to push it)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  T + direction     ", Style::default().fg(Color::White)),
+            Span::styled("Throw the bone pile beside you", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Shift+F + dir     ", Style::default().fg(Color::White)),
+            Span::styled("Pickpocket an NPC or a sleeping enemy (DEX against their perception)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Walk into lever   ", Style::default().fg(Color::White)),
+            Span::styled("Throw it (weigh a plate down with a prop to hold it)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Walk into toll    ", Style::default().fg(Color::White)),
+            Span::styled("Pay the fee to open a toll gate (or take the long way round)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Z + direction     ", Style::default().fg(Color::White)),
+            Span::styled("Zap a wand (or use one from the inventory)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  W + W / 1-5       ", Style::default().fg(Color::White)),
+            Span::styled("Prepare an attack or skill for the first enemy in reach", Style::default().fg(Color::Gray)),
         ]));
         lines.push(Line::from(vec![
             Span::styled("  R                 ", Style::default().fg(Color::White)),
@@ -5543,6 +8854,22 @@ impl App {
             Span::styled("  ☠  ", Style::default().fg(Color::Rgb(200, 50, 100))),
             Span::styled("Corruption Shrine - Curse for power", Style::default().fg(Color::Gray)),
         ]));
+        lines.push(Line::from(vec![
+            Span::styled("  ¢  ", Style::default().fg(Color::Rgb(255, 215, 0))),
+            Span::styled("Gambling Shrine - Pay gold, test your luck", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  †  ", Style::default().fg(Color::Rgb(220, 40, 40))),
+            Span::styled("Sacrifice Shrine - Destroy gear for a stat", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  ⚗  ", Style::default().fg(Color::Rgb(120, 255, 200))),
+            Span::styled("Transmutation Shrine - Reroll affixes, gain corruption", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Ψ  ", Style::default().fg(Color::Rgb(200, 30, 30))),
+            Span::styled("Altar - Worship a god for favor and boons", Style::default().fg(Color::Gray)),
+        ]));
         lines.push(Line::from(""));
 
         // Entities
@@ -5567,6 +8894,14 @@ impl App {
             Span::styled("  +  ", Style::default().fg(Color::Rgb(100, 255, 100))),
             Span::styled("Healer", Style::default().fg(Color::Gray)),
         ]));
+        lines.push(Line::from(vec![
+            Span::styled("  ¤  ", Style::default().fg(Color::Rgb(170, 150, 120))),
+            Span::styled("Caged prisoner (bump to free)", Style::default().fg(Color::Gray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  @  ", Style::default().fg(Color::Rgb(120, 200, 255))),
+            Span::styled("Companion (bump to swap places)", Style::default().fg(Color::Gray)),
+        ]));
         lines.push(Line::from(""));
 
         // Stats
@@ -5688,7 +9023,7 @@ impl App {
             Style::default().fg(Color::DarkGray),
         )));
         lines.push(Line::from(Span::styled(
-            "  • Boss floors (5, 10, 15, 20) have powerful guardians",
+            "  • Boss floors (5, 10, 15, 20, 25) have powerful guardians",
             Style::default().fg(Color::DarkGray),
         )));
         lines.push(Line::from(""));
@@ -5712,6 +9047,13 @@ impl App {
             ShrineType::Enchanting => (" ✦ Enchanting Shrine ✦ ", Color::Rgb(100, 200, 255)),
             ShrineType::Rest => (" ☥ Rest Shrine ☥ ", Color::Rgb(100, 255, 100)),
             ShrineType::Corruption => (" ⛧ Corruption Shrine ⛧ ", Color::Rgb(200, 50, 50)),
+            ShrineType::Gambling => (" ¢ Gambling Shrine ¢ ", Color::Rgb(255, 215, 0)),
+            ShrineType::Sacrifice => (" † Sacrifice Shrine † ", Color::Rgb(220, 40, 40)),
+            ShrineType::Transmutation => (" ⚗ Transmutation Shrine ⚗ ", Color::Rgb(120, 255, 200)),
+            ShrineType::Altar(deity) => {
+                let (r, g, b) = deity.color();
+                (" Ψ Altar Ψ ", Color::Rgb(r, g, b))
+            }
         };
 
         let area = centered_rect(60, 60, frame.area());
@@ -5830,7 +9172,8 @@ impl App {
                     // Use generated shrine skills
                     for (i, skill) in self.shrine_skills.iter().enumerate() {
                         let is_selected = i == self.shrine_skill_cursor;
-                        let already_known = known_skills.contains(&skill.id);
+                        let is_upgrade = skill.rank > 1;
+                        let already_known = !is_upgrade && known_skills.contains(&skill.id);
                         let prefix = if is_selected { "> " } else { "  " };
 
                         // Get rarity color
@@ -5871,11 +9214,16 @@ impl App {
                                 rarity_style
                             };
 
+                            let tag = if is_upgrade {
+                                Span::styled(" [Upgrade]", Style::default().fg(Color::Yellow))
+                            } else {
+                                Span::styled(format!(" [{}]", skill.rarity.name()), rarity_style)
+                            };
                             lines.push(Line::from(vec![
                                 Span::styled(prefix, select_style),
                                 Span::styled(format!("{} ", skill.icon), Style::default().fg(Color::Magenta)),
-                                Span::styled(skill.name.clone(), name_style),
-                                Span::styled(format!(" [{}]", skill.rarity.name()), rarity_style),
+                                Span::styled(skill.display_name(), name_style),
+                                tag,
                             ]));
 
                             // Cost and cooldown
@@ -5903,7 +9251,7 @@ impl App {
 
                     lines.push(Line::from(""));
                     lines.push(Line::from(Span::styled(
-                        "[↑↓] Select   [Enter] Learn   [Esc] Leave",
+                        "[↑↓] Select   [Enter] Learn/Upgrade   [Esc] Leave",
                         Style::default().fg(Color::DarkGray),
                     )));
                 }
@@ -6090,9 +9438,11 @@ impl App {
 
                         let enchantments = if is_weapon { weapon_enchants } else { armor_enchants };
 
+                        let markup = game.shrine_markup();
                         for (i, (affix_type, cost, value, desc)) in enchantments.iter().enumerate() {
+                            let cost = markup.price(*cost);
                             let is_selected = !self.enchant_swap_mode && i == self.enchant_affix_cursor;
-                            let can_afford = gold >= *cost;
+                            let can_afford = gold >= cost;
                             // Check if item has this affix and what value
                             let existing_affix = current_affixes.iter()
                                 .find(|(n, _)| n == affix_type.name())
@@ -6147,7 +9497,7 @@ impl App {
                         // Special option: +1 max enchantment slot (only if rare upgrade available)
                         if self.enchant_upgrade_available {
                             let plus_one_selected = !self.enchant_swap_mode && self.enchant_affix_cursor == 6;
-                            let plus_one_cost = 200u32;
+                            let plus_one_cost = markup.price(200);
                             let can_afford_plus = gold >= plus_one_cost;
                             let plus_style = if plus_one_selected {
                                 Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
@@ -6181,7 +9531,7 @@ impl App {
                                             crate::items::Rarity::Mythic => 6,
                                         };
                                         (item.enchantment_level, item.awakening_level, item.sockets.len(), max_sockets, item.corruption_level,
-                                         item.enchant_cost(), item.awakening_cost(), item.corruption_cost())
+                                         markup.price(item.enchant_cost()), markup.price(item.awakening_cost()), markup.price(item.corruption_cost()))
                                     } else {
                                         (0, 0, 0, 1, 0, 100, 500, 200)
                                     }
@@ -6236,7 +9586,7 @@ impl App {
 
                         // Option: Add Socket
                         let socket_selected = !self.enchant_swap_mode && self.enchant_affix_cursor == base_option + 2;
-                        let socket_cost = 300 + (socket_count as u32 * 200);
+                        let socket_cost = markup.price(300 + (socket_count as u32 * 200));
                         let can_socket = socket_count < max_sockets && gold >= socket_cost;
                         let socket_style = if socket_selected {
                             Style::default().fg(Color::Rgb(200, 200, 255)).add_modifier(Modifier::BOLD)
@@ -6364,12 +9714,263 @@ impl App {
                     Style::default().fg(Color::DarkGray),
                 )));
             }
+            ShrineType::Gambling => {
+                let cost = game.shrine_gamble_cost();
+                let gold = game.player()
+                    .and_then(|p| game.world().get::<&crate::ecs::InventoryComponent>(p).ok())
+                    .map(|inv| inv.inventory.gold())
+                    .unwrap_or(0);
+
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "A grinning idol with a slot for a mouth...",
+                    Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+                )));
+                lines.push(Line::from(""));
+                lines.push(Line::from(vec![
+                    Span::styled("Offering: ", Style::default().fg(Color::White)),
+                    Span::styled(format!("{} gold", cost), Style::default().fg(Color::Yellow)),
+                    Span::styled(format!("   (you have {})", gold), Style::default().fg(Color::DarkGray)),
+                ]));
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "Outcomes: nothing, a curse, double or 5x your gold, a potion, or deep-floor gear",
+                    Style::default().fg(Color::Gray),
+                )));
+                lines.push(Line::from(""));
+                if let Some(outcome) = &self.shrine_last_outcome {
+                    lines.push(Line::from(Span::styled(
+                        outcome.clone(),
+                        Style::default().fg(Color::Rgb(255, 215, 0)).add_modifier(Modifier::BOLD),
+                    )));
+                    lines.push(Line::from(""));
+                }
+                lines.push(Line::from(Span::styled(
+                    "[Enter] Make an offering   [Esc] Leave shrine",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            ShrineType::Sacrifice | ShrineType::Transmutation => {
+                use crate::ecs::EquipmentComponent;
+
+                let flavor = if shrine_type == ShrineType::Sacrifice {
+                    "Offer an equipped item. It is destroyed, but its essence becomes yours."
+                } else {
+                    "Reroll an equipped item's affixes. The item gains one level of corruption."
+                };
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    flavor,
+                    Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+                )));
+                lines.push(Line::from(""));
+
+                let items = self.get_equipped_items_for_enchant(game);
+                if items.is_empty() {
+                    lines.push(Line::from(Span::styled(
+                        "You have nothing equipped.",
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+
+                let equipment = game.player()
+                    .and_then(|p| game.world().get::<&EquipmentComponent>(p).ok());
+                for (i, (slot, _, _, _)) in items.iter().enumerate() {
+                    let Some(item) = equipment.as_ref().and_then(|eq| eq.equipment.get(*slot)) else { continue };
+                    let selected = i == self.shrine_item_cursor;
+                    let prefix = if selected { "► " } else { "  " };
+                    let (r, g, b) = item.rarity.color();
+                    let name_style = if selected {
+                        Style::default().fg(Color::Rgb(r, g, b)).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Rgb(r, g, b))
+                    };
+
+                    let detail = if shrine_type == ShrineType::Sacrifice {
+                        let (stat, amount) = crate::game::sacrifice_boon(item, *slot);
+                        format!("  → +{} {}", amount, stat.name())
+                    } else if crate::game::can_transmute(item) {
+                        format!("  → {{C{}}}", item.corruption_level + 1)
+                    } else {
+                        "  (too corrupted)".to_string()
+                    };
+
+                    lines.push(Line::from(vec![
+                        Span::styled(prefix, Style::default().fg(Color::Yellow)),
+                        Span::styled(item.display_name(), name_style),
+                        Span::styled(detail, Style::default().fg(Color::Gray)),
+                    ]));
+                }
+
+                lines.push(Line::from(""));
+                let action = if shrine_type == ShrineType::Sacrifice { "Sacrifice" } else { "Transmute" };
+                lines.push(Line::from(Span::styled(
+                    format!("[↑↓] Select   [Enter] {}   [Esc] Leave shrine", action),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            ShrineType::Altar(deity) => {
+                use crate::ecs::InventoryComponent;
+
+                let worship = game.worship();
+                let favor = worship.favor(deity);
+                let is_patron = worship.patron == Some(deity);
+
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    format!("{} {}", deity.name(), deity.title()),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                )));
+                lines.push(Line::from(Span::styled(
+                    deity.domain(),
+                    Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+                )));
+                lines.push(Line::from(""));
+
+                let standing = if is_patron {
+                    "Your patron".to_string()
+                } else if let Some(patron) = worship.patron {
+                    format!("You serve {}", patron.name())
+                } else {
+                    "You serve no god".to_string()
+                };
+                let favor_color = if favor < 0 { Color::Red } else { Color::Yellow };
+                lines.push(Line::from(vec![
+                    Span::styled("Favor: ", Style::default().fg(Color::White)),
+                    Span::styled(favor.to_string(), Style::default().fg(favor_color)),
+                    Span::styled(format!("   ({})", standing), Style::default().fg(Color::DarkGray)),
+                ]));
+
+                let thresholds = [crate::game::FAVOR_MINOR_BOON, crate::game::FAVOR_MAJOR_BOON];
+                for (boon, needed) in deity.boons().iter().zip(thresholds) {
+                    let active = is_patron && favor >= needed;
+                    let style = if active {
+                        Style::default().fg(Color::Green)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    };
+                    lines.push(Line::from(Span::styled(
+                        format!("  {} {} ({}) - {}", if active { "✓" } else { "·" }, boon.name(), needed, boon.description()),
+                        style,
+                    )));
+                }
+                lines.push(Line::from(Span::styled(
+                    format!("  At {} favor your patron will spare you from death once.", crate::game::FAVOR_INTERVENTION),
+                    Style::default().fg(Color::DarkGray),
+                )));
+                lines.push(Line::from(""));
+
+                lines.push(Line::from(Span::styled(
+                    "Sacrifice an item:",
+                    Style::default().fg(Color::White),
+                )));
+                let inventory = game.player()
+                    .and_then(|p| game.world().get::<&InventoryComponent>(p).ok());
+                let items = inventory.as_ref().map(|inv| inv.inventory.items()).unwrap_or_default();
+                if items.is_empty() {
+                    lines.push(Line::from(Span::styled(
+                        "  Your pack is empty.",
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+                // Keep the list short; scroll with the cursor
+                let start = self.shrine_item_cursor.saturating_sub(4);
+                for (i, item) in items.iter().enumerate().skip(start).take(8) {
+                    let selected = i == self.shrine_item_cursor;
+                    let (r, g, b) = item.rarity.color();
+                    let name_style = if selected {
+                        Style::default().fg(Color::Rgb(r, g, b)).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Rgb(r, g, b))
+                    };
+                    lines.push(Line::from(vec![
+                        Span::styled(if selected { "► " } else { "  " }, Style::default().fg(Color::Yellow)),
+                        Span::styled(item.display_name(), name_style),
+                        Span::styled(format!("  → +{} favor", deity.item_favor(item)), Style::default().fg(Color::Gray)),
+                    ]));
+                }
+                lines.push(Line::from(""));
+
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "[1] Dedicate   [2] Offer {} gold   [3] Desecrate (+{} gold, angers {})",
+                        game.offering_cost(),
+                        crate::game::desecrate_reward(game.floor()),
+                        deity.name(),
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )));
+                lines.push(Line::from(Span::styled(
+                    "[↑↓] Select   [Enter] Sacrifice item   [Esc] Leave altar",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
         }
 
         let text = Paragraph::new(lines);
         frame.render_widget(text, inner);
     }
 
+    /// The gambler's table: stakes on offer and the last throw
+    fn render_dice_overlay(&self, frame: &mut Frame, game: &Game) {
+        let area = centered_rect(50, 40, frame.area());
+        frame.render_widget(Clear, area);
+        let color = Color::Rgb(255, 120, 160);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" ¤ Dice ¤ ")
+            .border_style(Style::default().fg(color));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let gold = game.player()
+            .and_then(|p| game.world().get::<&crate::ecs::InventoryComponent>(p).ok())
+            .map(|inv| inv.inventory.gold())
+            .unwrap_or(0);
+        let dice = &game.data().gold_sinks().dice;
+
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "Two dice each. High total wins; ties go to the house.",
+                Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+            )),
+            Line::from(Span::styled(
+                format!("A win pays {}x the stake, double sixes {}x.", dice.payout, dice.jackpot),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(""),
+        ];
+
+        let mut stakes = vec![Span::styled("Stake: ", Style::default().fg(Color::White))];
+        for (i, stake) in game.dice_stakes().into_iter().enumerate() {
+            let style = if i == self.dice_stake {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else if gold >= stake {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::Red)
+            };
+            stakes.push(Span::styled(format!(" {}g ", stake), style));
+            stakes.push(Span::raw(" "));
+        }
+        lines.push(Line::from(stakes));
+        lines.push(Line::from(Span::styled(format!("You have {} gold", gold), Style::default().fg(Color::DarkGray))));
+        lines.push(Line::from(""));
+
+        if let Some(throw) = &self.dice_last {
+            let style = if throw.won() { Style::default().fg(Color::Green) } else { Style::default().fg(Color::Red) };
+            lines.push(Line::from(Span::styled(throw.description(), style.add_modifier(Modifier::BOLD))));
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            "[←→] Stake   [Enter] Throw   [Esc] Leave the table",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        frame.render_widget(Paragraph::new(lines).wrap(ratatui::widgets::Wrap { trim: true }), inner);
+    }
+
     fn render_shop_overlay(&self, frame: &mut Frame, game: &Game, npc_entity: hecs::Entity) {
         use crate::entities::NpcComponent;
         use crate::ecs::InventoryComponent;
@@ -6377,9 +9978,13 @@ impl App {
         let area = centered_rect(60, 70, frame.area());
         frame.render_widget(Clear, area);
 
+        let standing = shop_standing(game, npc_entity);
+        // A passing peddler won't wait around forever
+        let peddler = game.world().get::<&crate::entities::Peddler>(npc_entity).ok().map(|p| *p);
+        let title = if peddler.is_some() { " $ Traveling Peddler $ " } else { " $ Merchant $ " };
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(" $ Merchant $ ")
+            .title(title)
             .border_style(Style::default().fg(Color::Rgb(255, 215, 0)));
 
         let inner = block.inner(area);
@@ -6405,11 +10010,25 @@ impl App {
         } else {
             Style::default().fg(Color::DarkGray)
         };
+        let buyback_style = if self.shop_mode == 2 {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let services_style = if self.shop_mode == 3 {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
         lines.push(Line::from(vec![
             Span::styled("[", Style::default().fg(Color::Gray)),
             Span::styled("Buy", buy_style),
             Span::styled("]  [", Style::default().fg(Color::Gray)),
             Span::styled("Sell", sell_style),
+            Span::styled("]  [", Style::default().fg(Color::Gray)),
+            Span::styled(format!("Buyback ({})", self.buyback.len()), buyback_style),
+            Span::styled("]  [", Style::default().fg(Color::Gray)),
+            Span::styled("Services", services_style),
             Span::styled("]", Style::default().fg(Color::Gray)),
             Span::styled("     Press ", Style::default().fg(Color::DarkGray)),
             Span::styled("Tab", Style::default().fg(Color::White)),
@@ -6417,10 +10036,17 @@ impl App {
         ]));
         lines.push(Line::from(""));
 
-        // Gold display
+        // Gold display, and how the guild regards the player
+        let standing_color = match standing {
+            crate::game::Standing::Hunted | crate::game::Standing::Distrusted => Color::LightRed,
+            crate::game::Standing::Neutral => Color::Gray,
+            crate::game::Standing::Trusted | crate::game::Standing::Honored => Color::LightGreen,
+        };
         lines.push(Line::from(vec![
             Span::styled("Your Gold: ", Style::default().fg(Color::Gray)),
             Span::styled(format!("{}", player_gold), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled("     Merchants' Guild: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(standing.name(), Style::default().fg(standing_color)),
         ]));
         lines.push(Line::from(""));
 
@@ -6433,10 +10059,14 @@ impl App {
             lines.push(Line::from(""));
 
             // Get shop items
-            let shop_items: Vec<_> = game.world()
+            let mut shop_items: Vec<_> = game.world()
                 .get::<&NpcComponent>(npc_entity)
                 .map(|npc| npc.shop_items.clone())
                 .unwrap_or_default();
+            for shop_item in &mut shop_items {
+                let shown = game.discoveries().disguise(&shop_item.item).into_owned();
+                shop_item.item = shown;
+            }
 
             if shop_items.is_empty() {
                 lines.push(Line::from(Span::styled(
@@ -6446,7 +10076,8 @@ impl App {
             } else {
                 for (i, shop_item) in shop_items.iter().enumerate() {
                     let is_selected = i == self.shop_selection;
-                    let can_afford = player_gold >= shop_item.buy_price;
+                    let price = standing.buy_price(game.data().economy().buy_price(&shop_item.item, game.floor()));
+                    let can_afford = player_gold >= price;
 
                     let rarity_color = Color::Rgb(
                         shop_item.item.rarity.color().0,
@@ -6496,7 +10127,7 @@ impl App {
                         Span::styled(display_name, name_style),
                     ];
                     line_spans.extend(stats_spans);
-                    line_spans.push(Span::styled(format!(" - {} gold", shop_item.buy_price), price_style));
+                    line_spans.push(Span::styled(format!(" - {} gold", price), price_style));
                     lines.push(Line::from(line_spans));
 
                     // Show item description and affixes for selected item
@@ -6515,6 +10146,18 @@ impl App {
                                 Span::styled(format!("✦ +{} {}", affix.value, affix.affix_type.name()), Style::default().fg(Color::Green)),
                             ]));
                         }
+                        if let Some(tags) = shop_item.item.synergy_summary() {
+                            lines.push(Line::from(vec![
+                                Span::styled("     ", Style::default()),
+                                Span::styled(format!("Synergy: {}", tags), Style::default().fg(Color::Magenta)),
+                            ]));
+                        }
+                        if let Some(charges) = shop_item.item.charge_label() {
+                            lines.push(Line::from(vec![
+                                Span::styled("     ", Style::default()),
+                                Span::styled(format!("Bolt: {} damage, {}", shop_item.item.zap_damage(), charges), Style::default().fg(Color::Cyan)),
+                            ]));
+                        }
 
                         // Show comparison with equipped item
                         if let Some(slot) = shop_item.item.equip_slot {
@@ -6569,28 +10212,122 @@ impl App {
                                         ("─".to_string(), Color::Gray)
                                     };
 
-                                    lines.push(Line::from(vec![
-                                        Span::styled("       🛡 ", Style::default().fg(Color::DarkGray)),
-                                        Span::styled(format!("{}", new_arm), Style::default().fg(Color::Blue)),
-                                        Span::styled(" vs ", Style::default().fg(Color::DarkGray)),
-                                        Span::styled(format!("{}", old_arm), Style::default().fg(Color::Blue)),
-                                        Span::styled(format!(" {}", indicator), Style::default().fg(diff_color).add_modifier(Modifier::BOLD)),
-                                    ]));
-                                }
-                            } else {
-                                lines.push(Line::from(vec![
-                                    Span::styled("     ", Style::default()),
-                                    Span::styled(format!("Slot: {} (empty)", slot.name()), Style::default().fg(Color::DarkGray)),
-                                ]));
-                            }
-                        }
-                    }
+                                    lines.push(Line::from(vec![
+                                        Span::styled("       🛡 ", Style::default().fg(Color::DarkGray)),
+                                        Span::styled(format!("{}", new_arm), Style::default().fg(Color::Blue)),
+                                        Span::styled(" vs ", Style::default().fg(Color::DarkGray)),
+                                        Span::styled(format!("{}", old_arm), Style::default().fg(Color::Blue)),
+                                        Span::styled(format!(" {}", indicator), Style::default().fg(diff_color).add_modifier(Modifier::BOLD)),
+                                    ]));
+                                }
+                            } else {
+                                lines.push(Line::from(vec![
+                                    Span::styled("     ", Style::default()),
+                                    Span::styled(format!("Slot: {} (empty)", slot.name()), Style::default().fg(Color::DarkGray)),
+                                ]));
+                            }
+                        }
+                    }
+                }
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "[↑↓] Select  [Enter] Buy  [Tab] Sell  [Esc] Leave",
+                Style::default().fg(Color::DarkGray),
+            )));
+        } else if self.shop_mode == 2 {
+            // BUYBACK MODE
+            lines.push(Line::from(Span::styled(
+                "Sold This Visit:",
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::from(""));
+
+            if self.buyback.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "  (Nothing sold yet)",
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                )));
+            } else {
+                for (i, (item, price)) in self.buyback.iter().enumerate() {
+                    let item: &crate::items::Item = &game.discoveries().disguise(item);
+                    let is_selected = i == self.buyback_selection;
+                    let (r, g, b) = item.rarity.color();
+                    let can_afford = player_gold >= *price;
+                    let prefix = if is_selected { "> " } else { "  " };
+                    lines.push(Line::from(vec![
+                        Span::styled(prefix, Style::default().fg(if is_selected { Color::Yellow } else { Color::DarkGray })),
+                        Span::styled(format!("{} ", item.glyph), Style::default().fg(Color::Rgb(r, g, b))),
+                        Span::styled(truncate_name(&item.name, 20), Style::default().fg(Color::Rgb(r, g, b))),
+                        Span::styled(
+                            format!(" - {} gold", price),
+                            Style::default().fg(if can_afford { Color::Yellow } else { Color::Red }),
+                        ),
+                    ]));
+                }
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Last {} sales are kept until you leave.", crate::entities::npcs::BUYBACK_SLOTS),
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            )));
+            lines.push(Line::from(Span::styled(
+                "[↑↓] Select  [Enter] Buy back  [Tab] Services  [Esc] Leave",
+                Style::default().fg(Color::DarkGray),
+            )));
+        } else if self.shop_mode == 3 {
+            // SERVICES MODE
+            lines.push(Line::from(Span::styled(
+                "Services:",
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::from(""));
+
+            let offers = Self::service_offers(game);
+            let mut last_service = None;
+            for (i, offer) in offers.iter().enumerate() {
+                if last_service != Some(offer.service) {
+                    last_service = Some(offer.service);
+                    lines.push(Line::from(Span::styled(
+                        offer.service.name(),
+                        Style::default().fg(Color::Cyan),
+                    )));
                 }
+                let is_selected = i == self.service_selection;
+                let can_afford = player_gold >= offer.price;
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        if is_selected { "> " } else { "  " },
+                        Style::default().fg(if is_selected { Color::Yellow } else { Color::DarkGray }),
+                    ),
+                    Span::styled(
+                        truncate_name(&offer.label, 28),
+                        Style::default().fg(if can_afford { Color::White } else { Color::DarkGray }),
+                    ),
+                    Span::styled(
+                        format!(" - {} gold", offer.price),
+                        Style::default().fg(if can_afford { Color::Yellow } else { Color::Red }),
+                    ),
+                ]));
+                if is_selected {
+                    lines.push(Line::from(Span::styled(
+                        format!("     {}", offer.service.description()),
+                        Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+                    )));
+                }
+            }
+            if !offers.iter().any(|o| o.service == crate::entities::npcs::MerchantService::Identify) {
+                lines.push(Line::from(Span::styled(
+                    "  (Nothing to identify)",
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                )));
             }
 
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
-                "[↑↓] Select  [Enter] Buy  [Tab] Sell  [Esc] Leave",
+                "[↑↓] Select  [Enter] Pay  [Tab] Buy  [Esc] Leave",
                 Style::default().fg(Color::DarkGray),
             )));
         } else {
@@ -6614,9 +10351,10 @@ impl App {
                 )));
             } else {
                 for (i, item) in player_items.iter().enumerate() {
+                    let item: &crate::items::Item = &game.discoveries().disguise(item);
                     let is_selected = i == self.sell_selection;
-                    // Calculate sell price (40% of value)
-                    let sell_price = (item.value as f32 * 0.4).max(1.0) as u32;
+                    let sell_price = standing.sell_price(game.data().economy().sell_price(item));
+                    let is_marked = self.sell_marked.contains(&item.id);
 
                     let rarity_color = Color::Rgb(
                         item.rarity.color().0,
@@ -6653,7 +10391,9 @@ impl App {
 
                     let mut line_spans = vec![
                         Span::styled(prefix, selector_style),
+                        Span::styled(if is_marked { "[x] " } else { "[ ] " }, Style::default().fg(if is_marked { Color::Green } else { Color::DarkGray })),
                         Span::styled(format!("{} ", item.glyph), name_style),
+                        Span::styled(item.lock_marker(), Style::default().fg(Color::Yellow)),
                         Span::styled(display_name, name_style),
                     ];
                     line_spans.extend(stats_spans);
@@ -6673,17 +10413,47 @@ impl App {
                                 Span::styled(format!("✦ +{} {}", affix.value, affix.affix_type.name()), Style::default().fg(Color::Green)),
                             ]));
                         }
+                        if let Some(tags) = item.synergy_summary() {
+                            lines.push(Line::from(vec![
+                                Span::styled("     ", Style::default()),
+                                Span::styled(format!("Synergy: {}", tags), Style::default().fg(Color::Magenta)),
+                            ]));
+                        }
+                        if let Some(charges) = item.charge_label() {
+                            lines.push(Line::from(vec![
+                                Span::styled("     ", Style::default()),
+                                Span::styled(format!("Bolt: {} damage, {}", item.zap_damage(), charges), Style::default().fg(Color::Cyan)),
+                            ]));
+                        }
                     }
                 }
             }
 
             lines.push(Line::from(""));
+            if !self.sell_marked.is_empty() {
+                let marked_total: u32 = player_items.iter()
+                    .filter(|i| self.sell_marked.contains(&i.id))
+                    .map(|item| standing.sell_price(game.data().economy().sell_price(item)))
+                    .sum();
+                lines.push(Line::from(Span::styled(
+                    format!("{} marked - {} gold", self.sell_marked.len(), marked_total),
+                    Style::default().fg(Color::Green),
+                )));
+            }
             lines.push(Line::from(Span::styled(
-                "[↑↓] Select  [Enter] Sell  [Tab] Buy  [Esc] Leave",
+                "[↑↓] Select  [Space] Mark  [R] Mark gear below Rare  [Enter] Sell  [Tab] Buyback  [Esc] Leave",
                 Style::default().fg(Color::DarkGray),
             )));
         }
 
+        if let Some(peddler) = peddler {
+            let color = if peddler.turns_left <= crate::entities::PEDDLER_WARNING_TURNS { Color::LightRed } else { Color::DarkGray };
+            lines.push(Line::from(Span::styled(
+                format!("Moving on in {} turns.  [Shift+X] Rob the peddler (they fight back; it taints you)", peddler.turns_left),
+                Style::default().fg(color),
+            )));
+        }
+
         let text = Paragraph::new(lines);
         frame.render_widget(text, inner);
     }
@@ -6691,9 +10461,10 @@ impl App {
     fn render_pause(&self, frame: &mut Frame, game: &Game) {
         // Render game in background
         self.render_playing(frame, game, &PlayingState::Exploring);
+        let settings = &game.profile().settings;
 
         // Overlay pause menu
-        let area = centered_rect(30, 30, frame.area());
+        let area = centered_rect(40, 65, frame.area());
         frame.render_widget(Clear, area);
 
         let block = Block::default()
@@ -6711,6 +10482,64 @@ impl App {
             Line::from(Span::styled("[S] Save Game", Style::default().fg(Color::White))),
             Line::from(""),
             Line::from(Span::styled("[Q] Quit to Menu", Style::default().fg(Color::Gray))),
+            Line::from(Span::styled("[L] Debug log", Style::default().fg(Color::DarkGray))),
+            Line::from(""),
+            Line::from(Span::styled("- Loot -", Style::default().fg(Color::DarkGray))),
+            Line::from(Span::styled(
+                format!("[A] Auto-pickup consumables: {}", if settings.auto_pickup_consumables { "On" } else { "Off" }),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("[G] Auto-pickup gear: {}", settings.auto_pickup_rarity
+                    .map(|r| format!("{}+", r.name()))
+                    .unwrap_or_else(|| "Off".to_string())),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("[F] Loot filter: {}", settings.loot_filter_mode.name()),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("[T] Filter drops below: {}", settings.loot_filter_rarity.name()),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(""),
+            Line::from(Span::styled("- Display -", Style::default().fg(Color::DarkGray))),
+            Line::from(Span::styled(
+                format!("[I] Enemy icons: {}", settings.enemy_overlays.name()),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("[H] Enemy health bars: {}", if settings.enemy_health_bars { "On" } else { "Off" }),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("[D] Damage numbers: {}", if settings.show_damage_numbers { "On" } else { "Off" }),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("[Z] Zen mode: {}", if settings.zen_mode { "On" } else { "Off" }),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("[R] Speedrun splits: {}", if settings.speedrun_mode { "On" } else { "Off" }),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                format!("[N] New player hints: {}", if settings.show_hints { "On" } else { "Off" }),
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                match settings.render_mode {
+                    Some(mode) => format!("[M] Render mode: {}", mode.name()),
+                    None => format!("[M] Render mode: Auto ({})", self.detected_render_mode.name()),
+                },
+                Style::default().fg(Color::Gray),
+            )),
+            Line::from(Span::styled(
+                if game.tutorial_step().is_some() { "[K] Skip tutorial" } else { "" },
+                Style::default().fg(Color::Cyan),
+            )),
         ])
         .alignment(ratatui::layout::Alignment::Center);
 
@@ -6864,7 +10693,7 @@ impl App {
         let playtime_hours = profile.stats.playtime_seconds / 3600;
         let playtime_mins = (profile.stats.playtime_seconds % 3600) / 60;
 
-        let stats_lines = vec![
+        let mut stats_lines = vec![
             Line::from(""),
             Line::from(vec![
                 Span::styled("Total Runs: ", Style::default().fg(Color::Gray)),
@@ -6912,6 +10741,28 @@ impl App {
             ]),
         ];
 
+        // Unlocks that change every run from now on, and what earns them
+        stats_lines.push(Line::from(""));
+        stats_lines.push(Line::from(Span::styled("Unlocks", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))));
+        for unlock in crate::progression::RunUnlock::ALL {
+            if profile.has_run_unlock(unlock) {
+                stats_lines.push(Line::from(vec![
+                    Span::styled("[X] ", Style::default().fg(Color::Green)),
+                    Span::styled(unlock.name(), Style::default().fg(Color::Yellow)),
+                    Span::styled(format!(" - {}", unlock.description()), Style::default().fg(Color::Gray)),
+                ]));
+            } else {
+                let earned_by = achievements.iter()
+                    .find(|a| a.id == unlock.achievement())
+                    .map_or("", |a| a.name);
+                stats_lines.push(Line::from(vec![
+                    Span::styled("[ ] ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(unlock.name(), Style::default().fg(Color::White)),
+                    Span::styled(format!(" - earned by {}", earned_by), Style::default().fg(Color::DarkGray)),
+                ]));
+            }
+        }
+
         let stats_para = Paragraph::new(stats_lines);
         frame.render_widget(stats_para, stats_inner);
 
@@ -6973,26 +10824,231 @@ impl App {
         frame.render_widget(achievements_para, achievements_inner);
     }
 
-    fn render_game_over(&self, frame: &mut Frame, floor: u32, cause: &str) {
+    fn render_leaderboard(&self, frame: &mut Frame, game: &Game) {
+        let area = frame.area();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" HIGH SCORES ")
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let entries = &game.profile().leaderboard.entries;
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{:<4} {:>8}  {:>5}  {:<10} {:>5} {:>6}  {}", "#", "Score", "Floor", "Difficulty", "Kills", "Gold", "Fate"),
+                Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+
+        if entries.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "No runs recorded yet. Descend and make your mark.",
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            )));
+        }
+
+        for (i, entry) in entries.iter().enumerate() {
+            let rank_color = match i {
+                0 => Color::Rgb(255, 215, 0),
+                1 => Color::Rgb(192, 192, 192),
+                2 => Color::Rgb(205, 127, 50),
+                _ => Color::White,
+            };
+            let fate_color = if entry.won { Color::Green } else { Color::DarkGray };
+
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<4} {:>8}  ", i + 1, entry.score), Style::default().fg(rank_color)),
+                Span::styled(
+                    if entry.boss_rush { format!("{:>5}  ", "Rush") } else { format!("{:>5}  ", entry.floor) },
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(format!("{:<10} ", entry.difficulty.name()), Style::default().fg(Color::White)),
+                Span::styled(format!("{:>5} {:>6}  ", entry.kills, entry.gold), Style::default().fg(Color::Gray)),
+                Span::styled(truncate_name(&entry.cause, 30), Style::default().fg(fate_color)),
+            ]));
+        }
+
+        if let Some(deepest) = &game.profile().leaderboard.deepest {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "Deepest descent: {} ({}, {} points) - {}",
+                    floor_name(deepest.floor), deepest.difficulty.name(), deepest.score, truncate_name(&deepest.cause, 30)
+                ),
+                Style::default().fg(Color::Red),
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "[Esc] Back to Menu",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let para = Paragraph::new(lines).alignment(ratatui::layout::Alignment::Center);
+        frame.render_widget(para, inner);
+    }
+
+    /// The latest lines of the log, errors and warnings picked out, with
+    /// where the full log is for a bug report
+    fn render_log(&self, frame: &mut Frame, scroll: usize) {
+        let area = frame.area();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" DEBUG LOG ")
+            .border_style(Style::default().fg(Color::DarkGray));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height < 3 {
+            return;
+        }
+
+        let recent = crate::logging::recent_lines();
+        let rows = (inner.height - 2) as usize;
+        let end = recent.len().saturating_sub(scroll);
+        let mut lines: Vec<Line> = recent[end.saturating_sub(rows)..end]
+            .iter()
+            .map(|line| {
+                let color = if line.contains(" ERROR ") {
+                    Color::Red
+                } else if line.contains(" WARN ") {
+                    Color::Yellow
+                } else {
+                    Color::Gray
+                };
+                Line::from(Span::styled(line.clone(), Style::default().fg(color)))
+            })
+            .collect();
+        if recent.is_empty() {
+            lines.push(Line::from(Span::styled("Nothing logged yet", Style::default().fg(Color::DarkGray))));
+        }
+        frame.render_widget(Paragraph::new(lines), Rect { height: inner.height - 2, ..inner });
+
+        let file = crate::logging::current_log_path()
+            .map(|path| format!("Full log: {}", path.display()))
+            .unwrap_or_else(|| "Not writing a log file".to_string());
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(file, Style::default().fg(Color::DarkGray)),
+                Span::styled("   [↑↓/PgUp/PgDn] Scroll  [Esc] Back", Style::default().fg(Color::DarkGray)),
+            ])),
+            Rect { y: inner.y + inner.height - 1, height: 1, ..inner },
+        );
+    }
+
+    /// Build the score summary lines shown after a run ends
+    fn score_summary_lines(&self, game: &Game) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+
+        // A boss rush shows its own clock
+        let clock = game.run_clock();
+        lines.push(Line::from(Span::styled(
+            if game.boss_rush().is_some() {
+                format!("Turns: {}", clock.turns)
+            } else {
+                format!("Turns: {}  Time: {}", clock.turns, crate::game::format_run_time(clock.seconds()))
+            },
+            Style::default().fg(Color::Cyan),
+        )));
+        if game.speedrunning() && !clock.splits.is_empty() {
+            for row in clock.splits.chunks(5) {
+                let mut spans = Vec::new();
+                for split in row {
+                    spans.push(Span::styled(format!(" F{} ", split.floor), Style::default().fg(Color::Gray)));
+                    spans.push(Span::styled(
+                        crate::game::format_run_time(split.at),
+                        Style::default().fg(split_color(split.delta())),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+            if let Some(best) = game.profile().best_run_time {
+                lines.push(Line::from(Span::styled(
+                    format!("Best time: {}", crate::game::format_run_time(best)),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+        lines.push(Line::from(""));
+
+        if let Some((score, rank)) = game.last_score() {
+            lines.push(Line::from(Span::styled(
+                format!("Score: {}", score.total),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )));
+            let breakdown = if game.boss_rush().is_some() {
+                format!("Bosses {} + Time {} + Conduct {}", score.kill_points, score.time_points, score.conduct_points)
+            } else {
+                format!(
+                    "Floors {} + Kills {} + Gold {} + Conduct {}",
+                    score.floor_points, score.kill_points, score.gold_points, score.conduct_points
+                )
+            };
+            lines.push(Line::from(Span::styled(breakdown, Style::default().fg(Color::DarkGray))));
+            if !score.sigils.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    format!("Curse sigils: {} (×{:.2})", score.sigils.names(), score.sigils.score_mult()),
+                    Style::default().fg(Color::Magenta),
+                )));
+            }
+            if let Some(adaptive) = game.adaptive() {
+                lines.push(Line::from(Span::styled(
+                    format!("Adaptive difficulty: {}", adaptive.describe()),
+                    Style::default().fg(Color::Cyan),
+                )));
+                for shift in adaptive.shifts() {
+                    lines.push(Line::from(Span::styled(
+                        format!("  F{}: {} ({})", shift.floor, shift.describe(), shift.reason),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+            }
+            if !score.conducts.is_empty() {
+                let names: Vec<&str> = score.conducts.iter().map(|c| c.name()).collect();
+                lines.push(Line::from(Span::styled(
+                    format!("Conducts: {}", names.join(", ")),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+            if let Some(rank) = rank {
+                lines.push(Line::from(Span::styled(
+                    format!("New high score! Rank #{}", rank),
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                )));
+            }
+        }
+
+        lines
+    }
+
+    fn render_game_over(&self, frame: &mut Frame, game: &Game, floor: u32, cause: &str) {
         let area = frame.area();
 
-        let text = vec![
+        let mut text = vec![
             Line::from(""),
             Line::from(Span::styled(
                 "YOU HAVE FALLEN",
                 Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
-            Line::from(format!("Reached Floor: {}", floor)),
+            Line::from(format!("Reached: {}", floor_name(floor))),
             Line::from(""),
             Line::from(Span::styled(cause, Style::default().fg(Color::DarkGray))),
             Line::from(""),
-            Line::from(""),
-            Line::from(Span::styled(
-                "Press [Enter] to continue",
-                Style::default().fg(Color::Gray),
-            )),
         ];
+        text.extend(self.score_summary_lines(game));
+        text.push(Line::from(""));
+        text.push(self.run_card_line());
+        text.push(Line::from(Span::styled(
+            "Press [Enter] to continue",
+            Style::default().fg(Color::Gray),
+        )));
 
         let para = Paragraph::new(text)
             .alignment(ratatui::layout::Alignment::Center)
@@ -7001,26 +11057,100 @@ impl App {
         frame.render_widget(para, area);
     }
 
-    fn render_victory(&self, frame: &mut Frame) {
+    /// Where the run card went, or how to save one
+    fn run_card_line(&self) -> Line<'static> {
+        match &self.run_card {
+            Some(path) => Line::from(Span::styled(
+                format!("Run card saved to {}", path.display()),
+                Style::default().fg(Color::Green),
+            )),
+            None => Line::from(Span::styled("Press [C] to save a run card", Style::default().fg(Color::Gray))),
+        }
+    }
+
+    fn render_victory(&self, frame: &mut Frame, game: &Game) {
         let area = frame.area();
 
-        let text = vec![
+        let mut text = vec![
             Line::from(""),
             Line::from(Span::styled(
                 "VICTORY",
                 Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
-            Line::from("You have conquered the Hollowdeep!"),
-            Line::from(""),
-            Line::from(Span::styled(
+        ];
+        match game.boss_rush() {
+            Some(rush) => {
+                text.push(Line::from("Every guardian of the Hollowdeep has fallen!"));
+                text.push(Line::from(Span::styled(
+                    format!("Time: {}", crate::game::format_rush_time(rush.seconds())),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+            None => match game.epilogue() {
+                Some(epilogue) => {
+                    text.push(Line::from(Span::styled(
+                        epilogue.title.clone(),
+                        Style::default().fg(Color::Magenta).add_modifier(Modifier::ITALIC),
+                    )));
+                    for paragraph in &epilogue.paragraphs {
+                        text.push(Line::from(""));
+                        text.push(Line::from(paragraph.clone()));
+                    }
+                }
+                None => text.push(Line::from("You have conquered the Hollowdeep!")),
+            },
+        }
+        text.push(Line::from(""));
+        text.extend(self.score_summary_lines(game));
+        text.push(Line::from(""));
+
+        if let Some(cursor) = self.ng_plus_cursor {
+            text.push(Line::from(Span::styled(
+                format!("New Game+{}: choose one item to carry down", game.ng_plus() + 1),
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            )));
+            text.push(Line::from("Your learned skills come with you."));
+            text.push(Line::from(""));
+            let kept = ng_plus_keepsakes(game);
+            if kept.is_empty() {
+                text.push(Line::from(Span::styled("(nothing equipped)", Style::default().fg(Color::DarkGray))));
+            }
+            for (i, (_, item)) in kept.iter().enumerate() {
+                let style = if i == cursor {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                } else {
+                    let (r, g, b) = item.rarity.color();
+                    Style::default().fg(Color::Rgb(r, g, b))
+                };
+                text.push(Line::from(Span::styled(format!(" {} ", item.display_name()), style)));
+            }
+            text.push(Line::from(""));
+            text.push(Line::from(Span::styled(
+                "[↑↓] Choose  [Enter] Descend  [Esc] Back",
+                Style::default().fg(Color::Gray),
+            )));
+        } else {
+            if game.boss_rush().is_none() {
+                text.push(Line::from(Span::styled(
+                    "Press [N] for New Game+",
+                    Style::default().fg(Color::Magenta),
+                )));
+                text.push(Line::from(Span::styled(
+                    "Press [D] to go on into the endless depths",
+                    Style::default().fg(Color::Red),
+                )));
+            }
+            text.push(self.run_card_line());
+            text.push(Line::from(Span::styled(
                 "Press [Enter] to continue",
                 Style::default().fg(Color::Gray),
-            )),
-        ];
+            )));
+        }
 
         let para = Paragraph::new(text)
             .alignment(ratatui::layout::Alignment::Center)
+            .wrap(ratatui::widgets::Wrap { trim: true })
             .block(Block::default().borders(Borders::ALL));
 
         frame.render_widget(para, area);
@@ -7043,6 +11173,116 @@ impl Default for App {
     }
 }
 
+/// Colour of a split by how it compares to the personal best
+fn split_color(delta: Option<i64>) -> Color {
+    match delta {
+        Some(d) if d < 0 => Color::Green,
+        Some(d) if d > 0 => Color::Red,
+        _ => Color::White,
+    }
+}
+
+/// The last few floor splits and the one running, against personal bests
+fn speedrun_split_lines(game: &Game) -> Vec<Line<'static>> {
+    let clock = game.run_clock();
+    let split_line = |floor: u32, at: u32, delta: Option<i64>, running: bool| {
+        let mut spans = vec![
+            Span::styled(
+                format!("F{:<3}", floor),
+                if running { Style::default().fg(Color::Yellow) } else { Style::default().fg(Color::Gray) },
+            ),
+            Span::raw(format!("{:>7} ", crate::game::format_run_time(at))),
+        ];
+        spans.extend(delta.map(|d| Span::styled(crate::game::format_split_delta(d), Style::default().fg(split_color(Some(d))))));
+        Line::from(spans)
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled("Splits", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
+    ];
+    let shown = clock.splits.len().saturating_sub(3);
+    for split in &clock.splits[shown..] {
+        lines.push(split_line(split.floor, split.at, split.delta(), false));
+    }
+    let best = game.profile().best_splits.get(&game.floor()).copied();
+    lines.push(split_line(
+        game.floor(),
+        clock.seconds(),
+        best.map(|b| clock.seconds() as i64 - b as i64),
+        true,
+    ));
+    lines
+}
+
+/// The character sheet's working for a derived stat: its formula, then
+/// each part and what they add up to
+fn stat_breakdown_lines(breakdown: &crate::combat::StatBreakdown) -> Vec<Line<'static>> {
+    let border = Style::default().fg(Color::Cyan);
+    let value = |v: f32| if breakdown.percent { format!("{:+.1}%", v) } else { format!("{:+}", v.round() as i32) };
+
+    let mut lines = vec![
+        Line::from(Span::styled(format!("╔═══ {} ", breakdown.name.to_uppercase()), border)),
+        Line::from(vec![
+            Span::styled("║ ", border),
+            Span::styled(breakdown.formula, Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC)),
+        ]),
+        Line::from(Span::styled("║", border)),
+    ];
+    for part in &breakdown.parts {
+        let color = if part.value < 0.0 { Color::Red } else { Color::White };
+        lines.push(Line::from(vec![
+            Span::styled("║ ", border),
+            Span::styled(format!("{:<18}", part.source), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{:>8}", value(part.value)), Style::default().fg(color)),
+        ]));
+    }
+    let total = if breakdown.percent { format!("{:.1}%", breakdown.total) } else { format!("{}", breakdown.total.round() as i32) };
+    lines.push(Line::from(vec![
+        Span::styled("║ ", border),
+        Span::styled(format!("{:<18}", "Total"), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+        Span::styled(format!("{:>8}", total), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+    ]));
+    lines.push(Line::from(Span::styled("║ [↑↓] Stat  [F/Esc] Back", Style::default().fg(Color::DarkGray))));
+    lines.push(Line::from(Span::styled("╚═══════════════════════════════════════╝", border)));
+    lines
+}
+
+/// The run just finished, summed up for its run card
+fn run_card(game: &Game) -> screenshot::RunCard {
+    let (floor, epitaph) = match game.state() {
+        GameState::GameOver { floor_reached, cause_of_death } => (*floor_reached, Some(cause_of_death.clone())),
+        _ => (game.floor(), None),
+    };
+    let clock = game.run_clock();
+    let run = game.run_stats();
+    let mut stats = vec![
+        ("Reached", format!("{} ({})", floor_name(floor), game.biome().name())),
+        ("Difficulty", game.difficulty().name().to_string()),
+        ("Turns", clock.turns.to_string()),
+        ("Time", crate::game::format_run_time(clock.seconds())),
+        ("Kills", format!("{} ({} bosses)", run.kills, run.bosses_killed)),
+        ("Gold", run.gold_collected.to_string()),
+    ];
+    if let Some((score, _)) = game.last_score() {
+        stats.push(("Score", score.total.to_string()));
+    }
+    stats.push(("Seed", game.seed().to_string()));
+    screenshot::RunCard { victory: epitaph.is_none(), epitaph, stats }
+}
+
+/// Equipped items the player can carry into New Game Plus
+fn ng_plus_keepsakes(game: &Game) -> Vec<(crate::items::EquipSlot, crate::items::Item)> {
+    let Some(equipment) = game.player()
+        .and_then(|p| game.world().get::<&crate::ecs::EquipmentComponent>(p).ok())
+    else {
+        return Vec::new();
+    };
+    crate::items::EquipSlot::all().iter()
+        .filter_map(|slot| equipment.equipment.get(*slot).map(|item| (*slot, item.clone())))
+        .collect()
+}
+
 /// Create a centered rectangle
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -0,0 +1,94 @@
+//! Entity inspector
+//!
+//! A wizard mode tool (start with `--wizard`): the look cursor lists every
+//! component of whatever stands under it, with live values. It reads the
+//! world through the same component registry saves use, plus the player's
+//! own components and the ones worked out afresh on load.
+
+use hecs::World;
+
+use crate::combat::Resistances;
+use crate::ecs::{ComponentRegistry, Experience, Mana, Position, Stamina, StatPoints};
+use crate::entities::Peddler;
+use crate::progression::{Injuries, Mutations};
+use crate::save::save_game::level_registry;
+
+/// Longest a component's line gets before it's cut short
+const MAX_LINE: usize = 60;
+
+/// Every component the inspector can show
+fn registry() -> ComponentRegistry {
+    level_registry()
+        .register::<Mana>("mana")
+        .register::<Stamina>("stamina")
+        .register::<Experience>("experience")
+        .register::<StatPoints>("stat_points")
+        .register::<Mutations>("mutations")
+        .register::<Injuries>("injuries")
+        .register::<Resistances>("resistances")
+        .register::<Peddler>("peddler")
+}
+
+/// A line for each entity at a position and each of its components
+pub fn inspect(world: &World, pos: Position) -> Vec<String> {
+    let registry = registry();
+    let mut entities: Vec<_> = world.query::<&Position>()
+        .iter()
+        .filter(|(_, p)| **p == pos)
+        .map(|(entity, _)| entity)
+        .collect();
+    entities.sort_by_key(|entity| entity.id());
+
+    let mut lines = Vec::new();
+    for entity in entities {
+        let Ok(entity_ref) = world.entity(entity) else { continue };
+        lines.push(format!("Entity {}", entity.id()));
+        let components = match registry.snapshot_entity(&entity_ref) {
+            Ok(components) => components,
+            Err(e) => {
+                lines.push(format!("  unreadable: {}", e));
+                continue;
+            }
+        };
+        let unregistered = entity_ref.component_types().count().saturating_sub(components.len());
+        for (name, value) in components {
+            lines.push(shorten(format!("  {}: {}", name, value)));
+        }
+        if unregistered > 0 {
+            lines.push(format!("  +{} unregistered", unregistered));
+        }
+    }
+    lines
+}
+
+fn shorten(line: String) -> String {
+    if line.chars().count() <= MAX_LINE {
+        return line;
+    }
+    let mut short: String = line.chars().take(MAX_LINE - 1).collect();
+    short.push('…');
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{Health, Name, Velocity};
+
+    #[test]
+    fn test_inspector_lists_live_components() {
+        let mut world = World::new();
+        let pos = Position::new(5, 5);
+        let mut health = Health::new(30);
+        health.current = 7;
+        world.spawn((pos, Name::new("Bone Hound"), health, Velocity::default()));
+        world.spawn((Position::new(6, 5), Name::new("Elsewhere")));
+
+        let lines = inspect(&world, pos);
+        assert!(lines[0].starts_with("Entity "));
+        assert!(lines.contains(&"  name: \"Bone Hound\"".to_string()));
+        assert!(lines.iter().any(|l| l.starts_with("  health: ") && l.contains("\"current\":7")));
+        assert_eq!(lines.last().unwrap(), "  +1 unregistered");
+        assert!(!lines.iter().any(|l| l.contains("Elsewhere")));
+    }
+}
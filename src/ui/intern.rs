@@ -0,0 +1,30 @@
+//! Interned names
+//!
+//! Enemy names are drawn in several panels, and a `Span` owning its text
+//! means a fresh `String` for each one, each time it's drawn. Interning
+//! gives every distinct name one `&'static str` for the life of the
+//! program. Only names from the data files should go through here (enemy
+//! and boss names), so the set stays small; don't intern generated text
+//! such as item names with their affixes.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+thread_local! {
+    static NAMES: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::new());
+}
+
+/// The one copy of `name`, made on first sight
+pub fn intern(name: &str) -> &'static str {
+    NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        match names.get(name) {
+            Some(interned) => interned,
+            None => {
+                let interned: &'static str = Box::leak(name.into());
+                names.insert(interned);
+                interned
+            }
+        }
+    })
+}
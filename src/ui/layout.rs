@@ -0,0 +1,132 @@
+//! Layout profiles
+//!
+//! How the playing screen is carved up depends on the terminal's width:
+//! compact terminals fold the sidebar away and shrink the log, wide ones
+//! gain a second side panel listing what's nearby. The side panels and the
+//! log can also be collapsed by hand to give the map more room.
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+/// Terminals narrower than this get the compact layout
+pub const COMPACT_BELOW_WIDTH: u16 = 100;
+/// Terminals at least this wide get the wide layout
+pub const WIDE_FROM_WIDTH: u16 = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutProfile {
+    Compact,
+    Standard,
+    /// Side panels on both sides of the map
+    Wide,
+}
+
+impl LayoutProfile {
+    /// Profile for a terminal of this many columns
+    pub fn for_width(width: u16) -> Self {
+        if width < COMPACT_BELOW_WIDTH {
+            LayoutProfile::Compact
+        } else if width >= WIDE_FROM_WIDTH {
+            LayoutProfile::Wide
+        } else {
+            LayoutProfile::Standard
+        }
+    }
+
+    fn sidebar_width(&self) -> u16 {
+        match self {
+            LayoutProfile::Compact => 22,
+            LayoutProfile::Standard => 25,
+            LayoutProfile::Wide => 30,
+        }
+    }
+
+    fn log_height(&self) -> u16 {
+        match self {
+            LayoutProfile::Compact => 4,
+            LayoutProfile::Standard => 7,
+            LayoutProfile::Wide => 9,
+        }
+    }
+}
+
+/// Panels folded away by hand
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollapsedPanels {
+    /// The sidebar, and the nearby panel on wide terminals
+    pub side: bool,
+    pub log: bool,
+}
+
+/// Where each part of the playing screen goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayingLayout {
+    pub map: Rect,
+    pub action_bar: Rect,
+    pub log: Option<Rect>,
+    pub sidebar: Option<Rect>,
+    /// Creatures in sight, on wide terminals
+    pub nearby: Option<Rect>,
+}
+
+impl PlayingLayout {
+    pub fn new(area: Rect, profile: LayoutProfile, collapsed: CollapsedPanels) -> Self {
+        let show_side = !collapsed.side;
+        let show_nearby = show_side && profile == LayoutProfile::Wide;
+
+        let mut columns = Vec::new();
+        if show_nearby {
+            columns.push(Constraint::Length(profile.sidebar_width()));
+        }
+        columns.push(Constraint::Min(40));
+        if show_side {
+            columns.push(Constraint::Length(profile.sidebar_width()));
+        }
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(columns)
+            .split(area);
+        let (nearby, centre, sidebar) = match (show_nearby, show_side) {
+            (true, _) => (Some(chunks[0]), chunks[1], Some(chunks[2])),
+            (false, true) => (None, chunks[0], Some(chunks[1])),
+            (false, false) => (None, chunks[0], None),
+        };
+
+        let log_height = if collapsed.log { 0 } else { profile.log_height() };
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(10), Constraint::Length(1), Constraint::Length(log_height)])
+            .split(centre);
+
+        Self {
+            map: rows[0],
+            action_bar: rows[1],
+            log: (!collapsed.log).then_some(rows[2]),
+            sidebar,
+            nearby,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_profiles() {
+        assert_eq!(LayoutProfile::for_width(80), LayoutProfile::Compact);
+        assert_eq!(LayoutProfile::for_width(120), LayoutProfile::Standard);
+        assert_eq!(LayoutProfile::for_width(200), LayoutProfile::Wide);
+
+        // Wide terminals get a panel either side of the map
+        let wide = PlayingLayout::new(Rect::new(0, 0, 200, 50), LayoutProfile::Wide, CollapsedPanels::default());
+        assert_eq!(wide.nearby.map(|r| r.x), Some(0));
+        assert_eq!(wide.sidebar.map(|r| r.x + r.width), Some(200));
+        assert_eq!(wide.map.x, 30);
+
+        // Collapsing everything leaves the map the full width
+        let folded = CollapsedPanels { side: true, log: true };
+        let compact = PlayingLayout::new(Rect::new(0, 0, 80, 24), LayoutProfile::Compact, folded);
+        assert!(compact.sidebar.is_none() && compact.log.is_none());
+        assert_eq!((compact.map.width, compact.map.height), (80, 23));
+    }
+}
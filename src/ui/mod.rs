@@ -6,5 +6,10 @@ pub mod app;
 pub mod screens;
 pub mod widgets;
 pub mod input;
+pub mod layout;
+pub mod palette;
+pub mod inspector;
+pub mod panel_cache;
+pub mod intern;
 
 pub use app::App;
@@ -0,0 +1,197 @@
+//! Command palette
+//!
+//! Every action open to the player while exploring, with the key it's bound
+//! to, narrowed down by a fuzzy search. Running a command from the palette
+//! presses its key, so the palette doubles as help; a few settings that
+//! otherwise live in the pause menu can be reached from here too.
+
+use crossterm::event::KeyCode;
+
+/// What running a command does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteAction {
+    /// Press a key while exploring
+    Key(KeyCode),
+    SaveGame,
+    CycleEnemyIcons,
+    ToggleHealthBars,
+    ToggleSpeedrun,
+    ToggleHints,
+    ToggleAutoPickupConsumables,
+    CycleLootFilter,
+    /// Write the current floor out as text and PNG
+    ExportFloorPreview,
+}
+
+/// An entry in the palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaletteCommand {
+    pub name: &'static str,
+    /// Key shown beside the name, if the action has one
+    pub key: Option<&'static str>,
+    pub action: PaletteAction,
+}
+
+const fn key(name: &'static str, label: &'static str, code: KeyCode) -> PaletteCommand {
+    PaletteCommand { name, key: Some(label), action: PaletteAction::Key(code) }
+}
+
+const fn unbound(name: &'static str, action: PaletteAction) -> PaletteCommand {
+    PaletteCommand { name, key: None, action }
+}
+
+/// Every command, in the order shown before anything is typed
+pub const COMMANDS: &[PaletteCommand] = &[
+    key("Move north", "k", KeyCode::Char('k')),
+    key("Move south", "j", KeyCode::Char('j')),
+    key("Move west", "h", KeyCode::Char('h')),
+    key("Move east", "l", KeyCode::Char('l')),
+    key("Move north-west", "y", KeyCode::Char('y')),
+    key("Move north-east", "u", KeyCode::Char('u')),
+    key("Move south-west", "b", KeyCode::Char('b')),
+    key("Move south-east", "n", KeyCode::Char('n')),
+    key("Wait a turn", ".", KeyCode::Char('.')),
+    key("Rest until recovered", "R", KeyCode::Char('R')),
+    key("Bind wounds", "B", KeyCode::Char('B')),
+    key("Pick lock", "L", KeyCode::Char('L')),
+    key("Search for secret doors", "S", KeyCode::Char('S')),
+    key("Descend stairs", ">", KeyCode::Char('>')),
+    key("Interact (shrine, altar, NPC)", "e", KeyCode::Char('e')),
+    key("Pick up items", "g", KeyCode::Char('g')),
+    key("Toggle sprint", "s", KeyCode::Char('s')),
+    key("Close door", "x", KeyCode::Char('x')),
+    key("Peek through door", "p", KeyCode::Char('p')),
+    key("Disengage", "d", KeyCode::Char('d')),
+    key("Prepare attack or skill", "w", KeyCode::Char('w')),
+    key("Zap wand", "z", KeyCode::Char('z')),
+    key("Pull prop", "P", KeyCode::Char('P')),
+    key("Throw prop", "t", KeyCode::Char('t')),
+    key("Steal", "F", KeyCode::Char('F')),
+    key("Look around", ";", KeyCode::Char(';')),
+    key("Use skill 1", "1", KeyCode::Char('1')),
+    key("Use skill 2", "2", KeyCode::Char('2')),
+    key("Use skill 3", "3", KeyCode::Char('3')),
+    key("Use skill 4", "4", KeyCode::Char('4')),
+    key("Use skill 5", "5", KeyCode::Char('5')),
+    key("Use belt slot 1", "6", KeyCode::Char('6')),
+    key("Use belt slot 2", "7", KeyCode::Char('7')),
+    key("Use belt slot 3", "8", KeyCode::Char('8')),
+    key("Use belt slot 4", "9", KeyCode::Char('9')),
+    key("Inventory", "i", KeyCode::Char('i')),
+    key("Character sheet", "c", KeyCode::Char('c')),
+    key("Full map", "m", KeyCode::Char('m')),
+    key("Discoveries", "D", KeyCode::Char('D')),
+    key("Help", "?", KeyCode::Char('?')),
+    key("Pause menu", "Esc", KeyCode::Esc),
+    key("Cycle render mode", "r", KeyCode::Char('r')),
+    key("Collapse side panels", "[", KeyCode::Char('[')),
+    key("Collapse message log", "]", KeyCode::Char(']')),
+    key("Zen mode", "Z", KeyCode::Char('Z')),
+    unbound("Save game", PaletteAction::SaveGame),
+    unbound("Cycle enemy icons", PaletteAction::CycleEnemyIcons),
+    unbound("Toggle enemy health bars", PaletteAction::ToggleHealthBars),
+    unbound("Toggle speedrun splits", PaletteAction::ToggleSpeedrun),
+    unbound("Toggle new player hints", PaletteAction::ToggleHints),
+    unbound("Toggle auto-pickup of consumables", PaletteAction::ToggleAutoPickupConsumables),
+    unbound("Cycle loot filter", PaletteAction::CycleLootFilter),
+];
+
+/// Commands only offered in wizard mode, after the rest
+pub const WIZARD_COMMANDS: &[PaletteCommand] = &[
+    unbound("Export floor preview", PaletteAction::ExportFloorPreview),
+];
+
+/// How well a query matches a name, or None if its letters don't all appear
+/// in order. Runs of letters and letters starting words score higher.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut from = 0;
+    let mut last: Option<usize> = None;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = from + name[from..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if last.is_some_and(|l| l + 1 == found) {
+            score += 3;
+        }
+        if found == 0 || !name[found - 1].is_alphanumeric() {
+            score += 5;
+        }
+        last = Some(found);
+        from = found + 1;
+    }
+    Some(score)
+}
+
+/// The palette while it's open
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Palette {
+    pub query: String,
+    /// Index into the current matches
+    pub cursor: usize,
+    /// Offer the wizard mode commands too
+    pub wizard: bool,
+}
+
+impl Palette {
+    /// Commands matching the query, best first
+    pub fn matches(&self) -> Vec<&'static PaletteCommand> {
+        let wizard = if self.wizard { WIZARD_COMMANDS } else { &[] };
+        let mut scored: Vec<(i32, &PaletteCommand)> = COMMANDS.iter()
+            .chain(wizard)
+            .filter_map(|command| fuzzy_score(&self.query, command.name).map(|score| (score, command)))
+            .collect();
+        // Stable, so equal scores keep the list order
+        scored.sort_by_key(|(score, _)| -score);
+        scored.into_iter().map(|(_, command)| command).collect()
+    }
+
+    /// The command under the cursor
+    pub fn selected(&self) -> Option<&'static PaletteCommand> {
+        self.matches().get(self.cursor).copied()
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        self.query.push(c);
+        self.cursor = 0;
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.cursor = 0;
+    }
+
+    /// Move the cursor, wrapping around the matches
+    pub fn move_cursor(&mut self, delta: i32) {
+        let count = self.matches().len() as i32;
+        if count > 0 {
+            self.cursor = (self.cursor as i32 + delta).rem_euclid(count) as usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_search() {
+        assert_eq!(fuzzy_score("xyz", "Close door"), None);
+        // Word starts beat letters buried mid-word
+        assert!(fuzzy_score("cd", "Close door") > fuzzy_score("cd", "Cycle render mode"));
+
+        let mut palette = Palette::default();
+        assert_eq!(palette.matches().len(), COMMANDS.len());
+        let wizard = Palette { wizard: true, ..Palette::default() };
+        assert_eq!(wizard.matches().len(), COMMANDS.len() + WIZARD_COMMANDS.len());
+        for c in "pick".chars() {
+            palette.type_char(c);
+        }
+        let names: Vec<&str> = palette.matches().iter().map(|c| c.name).collect();
+        assert_eq!(&names[..2], &["Pick lock", "Pick up items"]);
+        assert_eq!(palette.selected().map(|c| c.key), Some(Some("L")));
+
+        palette.move_cursor(-1);
+        assert_eq!(palette.cursor, names.len() - 1);
+    }
+}
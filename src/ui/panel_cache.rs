@@ -0,0 +1,82 @@
+//! Panel caching
+//!
+//! The side panels are rebuilt from the world line by line, and most frames
+//! nothing they show has changed. A panel drawn once is kept with a key
+//! summing up what it showed; while the key holds, later frames copy the
+//! cells across instead of building it again.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+/// One panel as it was last drawn
+#[derive(Debug, Default)]
+pub struct PanelCache {
+    /// What the kept cells were drawn from, and where
+    drawn: Option<(u64, Rect)>,
+    cells: Buffer,
+}
+
+impl PanelCache {
+    /// Put the panel in `area` of `buf`. If `key` and `area` are what it was
+    /// last drawn with the kept cells are copied; otherwise `draw` draws it
+    /// afresh, and the result is kept. Returns whether the cache was used.
+    pub fn draw(&mut self, buf: &mut Buffer, area: Rect, key: u64, draw: impl FnOnce(&mut Buffer)) -> bool {
+        let hit = self.drawn == Some((key, area));
+        if !hit {
+            self.cells.resize(area);
+            self.cells.reset();
+            draw(&mut self.cells);
+            self.drawn = Some((key, area));
+        }
+
+        let area = area.intersection(buf.area);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf[(x, y)].clone_from(&self.cells[(x, y)]);
+            }
+        }
+        hit
+    }
+
+    /// Forget the panel, so the next frame draws it afresh
+    pub fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::widgets::{Paragraph, Widget};
+
+    #[test]
+    fn test_panel_is_redrawn_only_when_its_key_changes() {
+        let screen = Rect::new(0, 0, 20, 4);
+        let panel = Rect::new(10, 1, 8, 2);
+        let mut cache = PanelCache::default();
+        let mut draws = 0;
+        let frame = |key: u64, text: &str, cache: &mut PanelCache, draws: &mut i32| {
+            let mut buf = Buffer::empty(screen);
+            cache.draw(&mut buf, panel, key, |cells| {
+                *draws += 1;
+                Paragraph::new(text.to_string()).render(panel, cells);
+            });
+            buf
+        };
+
+        let first = frame(1, "HP 30", &mut cache, &mut draws);
+        // Same key: the kept cells are used, whatever would be drawn now
+        let second = frame(1, "HP 12", &mut cache, &mut draws);
+        assert_eq!(draws, 1);
+        assert_eq!(first, second);
+        assert_eq!(second[(10, 1)].symbol(), "H");
+
+        let third = frame(2, "HP 12", &mut cache, &mut draws);
+        assert_eq!(draws, 2);
+        assert_eq!(third[(13, 1)].symbol(), "1");
+
+        cache.invalidate();
+        frame(2, "HP 12", &mut cache, &mut draws);
+        assert_eq!(draws, 3);
+    }
+}
@@ -1,6 +1,6 @@
 //! Grid inventory widget for ratatui
 //!
-//! Renders an 8x6 grid-based inventory (RE4 style).
+//! Renders an 8-wide grid-based inventory (RE4 style); bag upgrades add rows.
 
 use ratatui::{
     buffer::Buffer,
@@ -9,7 +9,7 @@ use ratatui::{
     widgets::{Block, Borders, Widget},
 };
 
-use crate::items::{Inventory, ItemId, Item, Rarity, GRID_WIDTH, GRID_HEIGHT};
+use crate::items::{Discoveries, Inventory, ItemId, Item, Rarity, GRID_WIDTH};
 
 /// Grid cursor position
 #[derive(Debug, Clone, Copy, Default)]
@@ -30,9 +30,9 @@ impl GridCursor {
         }
     }
 
-    /// Move cursor down
-    pub fn move_down(&mut self) {
-        if (self.y as usize) < GRID_HEIGHT - 1 {
+    /// Move cursor down, staying within `rows` rows
+    pub fn move_down(&mut self, rows: usize) {
+        if (self.y as usize) + 1 < rows {
             self.y += 1;
         }
     }
@@ -58,6 +58,7 @@ pub struct GridInventoryWidget<'a> {
     cursor: GridCursor,
     selected_item: Option<ItemId>,
     title: &'a str,
+    discoveries: Option<&'a Discoveries>,
 }
 
 impl<'a> GridInventoryWidget<'a> {
@@ -67,6 +68,7 @@ impl<'a> GridInventoryWidget<'a> {
             cursor: GridCursor::default(),
             selected_item: None,
             title: "Inventory",
+            discoveries: None,
         }
     }
 
@@ -85,6 +87,12 @@ impl<'a> GridInventoryWidget<'a> {
         self
     }
 
+    /// Show unidentified potions and scrolls by their appearance
+    pub fn discoveries(mut self, discoveries: &'a Discoveries) -> Self {
+        self.discoveries = Some(discoveries);
+        self
+    }
+
     /// Get the cell character and style for a given position
     fn cell_style(&self, x: u8, y: u8) -> (char, Style) {
         let is_cursor = self.cursor.x == x && self.cursor.y == y;
@@ -100,7 +108,7 @@ impl<'a> GridInventoryWidget<'a> {
                 let color = rarity_color(item.rarity);
 
                 let glyph = if is_origin {
-                    item.glyph
+                    self.discoveries.map_or(item.glyph, |d| d.disguise(item).glyph)
                 } else {
                     '█' // Fill character for multi-cell items
                 };
@@ -145,7 +153,7 @@ impl<'a> Widget for GridInventoryWidget<'a> {
         block.render(area, buf);
 
         // Render grid cells
-        for y in 0..GRID_HEIGHT {
+        for y in 0..self.inventory.cells().len() {
             for x in 0..GRID_WIDTH {
                 let (glyph, style) = self.cell_style(x as u8, y as u8);
 
@@ -173,7 +181,7 @@ pub fn rarity_color(rarity: Rarity) -> Color {
 }
 
 /// Render item details panel
-pub fn render_item_details(item: &Item, area: Rect, buf: &mut Buffer) {
+pub fn render_item_details(item: &Item, value: u32, area: Rect, buf: &mut Buffer) {
     let block = Block::default()
         .title("Item Details")
         .borders(Borders::ALL)
@@ -188,7 +196,7 @@ pub fn render_item_details(item: &Item, area: Rect, buf: &mut Buffer) {
     let name_style = Style::default()
         .fg(rarity_color(item.rarity))
         .add_modifier(Modifier::BOLD);
-    let name_line = format!("{}", item.name);
+    let name_line = format!("{}{}", item.lock_marker(), item.name);
     if y < inner.y + inner.height {
         buf.set_string(inner.x, y, &name_line, name_style);
         y += 1;
@@ -221,6 +229,13 @@ pub fn render_item_details(item: &Item, area: Rect, buf: &mut Buffer) {
         y += 1;
     }
 
+    if let Some(charges) = item.charge_label() {
+        if y < inner.y + inner.height {
+            buf.set_string(inner.x, y, format!("Bolt: {} damage, {}", item.zap_damage(), charges), Style::default().fg(Color::Cyan));
+            y += 1;
+        }
+    }
+
     // Affixes
     for affix in &item.affixes {
         if y >= inner.y + inner.height {
@@ -231,10 +246,18 @@ pub fn render_item_details(item: &Item, area: Rect, buf: &mut Buffer) {
         y += 1;
     }
 
+    // Synergy tags
+    if let Some(tags) = item.synergy_summary() {
+        if y < inner.y + inner.height {
+            buf.set_string(inner.x, y, format!("Synergy: {}", tags), Style::default().fg(Color::Magenta));
+            y += 1;
+        }
+    }
+
     // Value
     y += 1;
     if y < inner.y + inner.height {
-        buf.set_string(inner.x, y, &format!("Value: {} gold", item.value), Style::default().fg(Color::Yellow));
+        buf.set_string(inner.x, y, &format!("Value: {} gold", value), Style::default().fg(Color::Yellow));
         y += 1;
     }
 
@@ -1,2 +1,92 @@
 //! Health bar widget
-pub fn placeholder() {}
+//!
+//! One-cell mini bars drawn above damaged enemies on the map, and the boss
+//! bar stretched across the top of the map during a boss fight.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use crate::entities::BossFight;
+
+/// Block for a mini bar, lower the less health is left
+pub fn mini_bar_glyph(fraction: f32, ascii: bool) -> char {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    const ASCII: [char; 4] = ['.', '-', '=', '#'];
+    let fraction = fraction.clamp(0.0, 1.0);
+    if ascii {
+        ASCII[((fraction * ASCII.len() as f32).ceil() as usize).clamp(1, ASCII.len()) - 1]
+    } else {
+        BLOCKS[((fraction * BLOCKS.len() as f32).ceil() as usize).clamp(1, BLOCKS.len()) - 1]
+    }
+}
+
+/// Colour of a bar by the share of health left
+pub fn bar_color(fraction: f32) -> Color {
+    if fraction > 0.6 {
+        Color::Rgb(90, 200, 90)
+    } else if fraction > 0.3 {
+        Color::Rgb(255, 200, 100)
+    } else {
+        Color::Rgb(255, 80, 80)
+    }
+}
+
+/// Boss name, phase, health and enrage countdown across one row
+pub fn render_boss_bar(frame: &mut Frame, area: Rect, fight: &BossFight, ascii: bool) {
+    if area.width < 20 || area.height == 0 {
+        return;
+    }
+    let row = Rect { height: 1, ..area };
+    let (current, max) = (fight.health.current.max(0), fight.health.max);
+    let fraction = if max > 0 { current as f32 / max as f32 } else { 0.0 };
+    let label = format!(" {}  Phase {} ", fight.boss_type.name(), fight.phase);
+    let numbers = format!(" {}/{} ", current, max);
+    let (enrage, enrage_style) = match fight.enrage_in {
+        Some(turns) => (
+            format!("Enrage {} ", turns),
+            Style::default().fg(if turns <= 10 { Color::Rgb(255, 160, 60) } else { Color::DarkGray }),
+        ),
+        None => (
+            "ENRAGED ".to_string(),
+            Style::default().fg(Color::Rgb(255, 60, 40)).add_modifier(Modifier::BOLD),
+        ),
+    };
+    let width = (row.width as usize)
+        .saturating_sub(label.chars().count() + numbers.chars().count() + enrage.chars().count() + 2);
+    let filled = ((fraction * width as f32).round() as usize).min(width);
+    let (full, empty) = if ascii { ('#', '-') } else { ('█', '░') };
+
+    frame.render_widget(Clear, row);
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(label, Style::default().fg(Color::Rgb(255, 90, 60)).add_modifier(Modifier::BOLD)),
+            Span::styled("[", Style::default().fg(Color::DarkGray)),
+            Span::styled(full.to_string().repeat(filled), Style::default().fg(bar_color(fraction))),
+            Span::styled(empty.to_string().repeat(width - filled), Style::default().fg(Color::Rgb(70, 30, 30))),
+            Span::styled("]", Style::default().fg(Color::DarkGray)),
+            Span::styled(numbers, Style::default().fg(Color::Gray)),
+            Span::styled(enrage, enrage_style),
+        ]))
+        .style(Style::default().bg(Color::Rgb(20, 8, 8))),
+        row,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mini_bar_glyph() {
+        assert_eq!(mini_bar_glyph(1.0, false), '█');
+        assert_eq!(mini_bar_glyph(0.5, false), '▄');
+        assert_eq!(mini_bar_glyph(0.01, false), '▁');
+        assert_eq!(mini_bar_glyph(0.0, true), '.');
+        assert_eq!(mini_bar_glyph(0.9, true), '#');
+    }
+}
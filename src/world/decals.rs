@@ -0,0 +1,114 @@
+//! Battle-damage decals
+//!
+//! Fighting marks the floor: blood where blows land, scorch marks where fire
+//! burns, and piles of bone where the dead have rotted away. Decals live on
+//! the map so they stay put between visits and are saved with it. They are
+//! purely cosmetic and take their colour from the biome they're left in.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{Biome, Map, TileType};
+use crate::ecs::Position;
+
+/// A mark left on the floor. Later kinds cover earlier ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Decal {
+    Blood,
+    Scorch,
+    Bones,
+}
+
+impl Decal {
+    pub fn glyph(&self, ascii: bool) -> char {
+        match (self, ascii) {
+            (Decal::Blood, false) => '∴',
+            (Decal::Blood, true) => ',',
+            (Decal::Scorch, false) => '░',
+            (Decal::Scorch, true) => ':',
+            (Decal::Bones, false) => '⁂',
+            (Decal::Bones, true) => ';',
+        }
+    }
+
+    /// How the decal is described when examining a tile
+    pub fn name(&self) -> &'static str {
+        match self {
+            Decal::Blood => "Spattered with blood",
+            Decal::Scorch => "Scorched black",
+            Decal::Bones => "Strewn with bones",
+        }
+    }
+
+    /// Colour of the decal in a biome
+    pub fn color(&self, biome: Biome) -> (u8, u8, u8) {
+        match (self, biome) {
+            (Decal::Blood, Biome::SunkenCatacombs) => (110, 25, 25),
+            (Decal::Blood, Biome::BleedingCrypts) => (170, 15, 35),
+            (Decal::Blood, Biome::HollowCathedral) => (125, 35, 55),
+            (Decal::Blood, Biome::FleshGardens) => (150, 30, 45),
+            (Decal::Blood, Biome::TheAbyss) => (85, 25, 100),
+            (Decal::Scorch, Biome::SunkenCatacombs) => (60, 55, 45),
+            (Decal::Scorch, Biome::BleedingCrypts) => (75, 40, 30),
+            (Decal::Scorch, Biome::HollowCathedral) => (85, 75, 65),
+            (Decal::Scorch, Biome::FleshGardens) => (70, 40, 40),
+            (Decal::Scorch, Biome::TheAbyss) => (55, 45, 75),
+            (Decal::Bones, Biome::SunkenCatacombs) => (185, 180, 155),
+            (Decal::Bones, Biome::BleedingCrypts) => (200, 170, 150),
+            (Decal::Bones, Biome::HollowCathedral) => (215, 210, 195),
+            (Decal::Bones, Biome::FleshGardens) => (210, 180, 170),
+            (Decal::Bones, Biome::TheAbyss) => (155, 145, 175),
+        }
+    }
+
+    /// Whether a decal shows on a tile: bare floor only, not walls, hazards
+    /// or furniture
+    pub fn fits(tile: TileType) -> bool {
+        matches!(
+            tile,
+            TileType::Floor
+                | TileType::Corridor
+                | TileType::Rubble
+                | TileType::Bones
+                | TileType::BloodStain
+                | TileType::Cobweb
+                | TileType::Cracks
+                | TileType::Moss
+                | TileType::Ashes
+                | TileType::Grime
+        )
+    }
+}
+
+/// Leave a decal at a position and spatter it onto some of the tiles around
+pub fn splatter(map: &mut Map, center: Position, decal: Decal, spread: usize, rng: &mut impl Rng) {
+    map.add_decal(center, decal);
+    for _ in 0..spread {
+        let pos = Position::new(center.x + rng.gen_range(-1..=1), center.y + rng.gen_range(-1..=1));
+        map.add_decal(pos, decal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decals_layer() {
+        let mut map = Map::new(5, 5, 1, Biome::BleedingCrypts);
+        map.set_tile(1, 1, TileType::Floor);
+        map.set_tile(2, 1, TileType::Floor);
+
+        // Walls take no decals
+        map.add_decal(Position::new(0, 0), Decal::Blood);
+        assert_eq!(map.decal_at(Position::new(0, 0)), None);
+
+        // Bones cover blood, but blood doesn't cover bones
+        map.add_decal(Position::new(1, 1), Decal::Blood);
+        map.add_decal(Position::new(1, 1), Decal::Bones);
+        map.add_decal(Position::new(1, 1), Decal::Blood);
+        assert_eq!(map.decal_at(Position::new(1, 1)), Some(Decal::Bones));
+        map.add_decal(Position::new(2, 1), Decal::Scorch);
+        assert_eq!(map.decal_at(Position::new(2, 1)), Some(Decal::Scorch));
+    }
+}
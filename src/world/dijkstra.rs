@@ -0,0 +1,99 @@
+//! Dijkstra maps
+//!
+//! Distance from every tile of a map to a goal, so a hunter anywhere on the
+//! floor can follow the slope down to its prey. Doors and hazards count as
+//! passable: whatever follows one of these maps smashes through the former
+//! and walks over the latter.
+
+use super::Map;
+use crate::ecs::Position;
+use std::collections::VecDeque;
+
+/// Steps from each tile to the goal
+pub struct DijkstraMap {
+    width: i32,
+    /// None for tiles the goal can't be reached from
+    distances: Vec<Option<u32>>,
+}
+
+impl DijkstraMap {
+    /// Distances to a goal over every tile a hunter can get through
+    pub fn new(map: &Map, goal: Position) -> Self {
+        let mut distances = vec![None; map.tiles.len()];
+        let passable = |x: i32, y: i32| map.get_tile(x, y).is_some_and(|t| {
+            t.tile_type.is_walkable() || t.tile_type.is_breachable() || t.tile_type.is_hazard()
+        });
+
+        if map.in_bounds(goal.x, goal.y) {
+            distances[map.xy_to_idx(goal.x, goal.y)] = Some(0);
+            let mut queue = VecDeque::from([goal]);
+            while let Some(pos) = queue.pop_front() {
+                let dist = distances[map.xy_to_idx(pos.x, pos.y)].unwrap_or(0);
+                for (dx, dy) in NEIGHBORS {
+                    let (x, y) = (pos.x + dx, pos.y + dy);
+                    if passable(x, y) && distances[map.xy_to_idx(x, y)].is_none() {
+                        distances[map.xy_to_idx(x, y)] = Some(dist + 1);
+                        queue.push_back(Position::new(x, y));
+                    }
+                }
+            }
+        }
+
+        Self { width: map.width, distances }
+    }
+
+    /// Steps from a tile to the goal, if it can be reached at all
+    pub fn distance(&self, pos: Position) -> Option<u32> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width {
+            return None;
+        }
+        self.distances.get((pos.y * self.width + pos.x) as usize).copied().flatten()
+    }
+
+    /// Neighbouring tiles that lead closer to the goal, nearest first
+    pub fn downhill(&self, from: Position) -> Vec<Position> {
+        let Some(here) = self.distance(from) else { return Vec::new() };
+        let mut steps: Vec<(u32, Position)> = NEIGHBORS.iter()
+            .map(|(dx, dy)| Position::new(from.x + dx, from.y + dy))
+            .filter_map(|pos| self.distance(pos).map(|d| (d, pos)))
+            .filter(|(d, _)| *d < here)
+            .collect();
+        steps.sort_by_key(|(d, _)| *d);
+        steps.into_iter().map(|(_, pos)| pos).collect()
+    }
+
+    /// The reachable tile furthest from the goal
+    pub fn furthest(&self) -> Option<Position> {
+        self.distances.iter()
+            .enumerate()
+            .filter_map(|(i, d)| d.map(|d| (d, i as i32)))
+            .max_by_key(|(d, _)| *d)
+            .map(|(_, i)| Position::new(i % self.width, i / self.width))
+    }
+}
+
+/// Eight-way steps, cardinals first
+const NEIGHBORS: [(i32, i32); 8] = [(0, -1), (1, 0), (0, 1), (-1, 0), (1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dijkstra_map() {
+        let map = Map::test_map();
+        let goal = map.start_pos;
+        let dijkstra = DijkstraMap::new(&map, goal);
+        assert_eq!(dijkstra.distance(goal), Some(0));
+
+        // Walking downhill from the far end always arrives
+        let mut pos = dijkstra.furthest().unwrap();
+        let start = dijkstra.distance(pos).unwrap();
+        assert!(start > 0);
+        for _ in 0..start {
+            pos = dijkstra.downhill(pos)[0];
+        }
+        assert_eq!(pos, goal);
+        assert!(dijkstra.downhill(goal).is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! Boss arena generator
+//!
+//! Boss floors are laid out by hand rather than left to chance: an
+//! antechamber to gather in, one corridor in, and an oval hall mirrored
+//! both ways around the boss's dais. A ring of the biome's hazard splits
+//! the hall, open at the four points of the compass, and alcoves cut into
+//! the walls hold the boss's adds.
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use crate::ecs::Position;
+use crate::world::{Map, Biome, TileType};
+use super::biomes::HazardType;
+
+/// Arena hall radius across, chosen per floor within this range
+const HALL_RADIUS_X: (i32, i32) = (20, 25);
+/// Arena hall radius down
+const HALL_RADIUS_Y: (i32, i32) = (11, 14);
+/// How far out the hazard ring lies, as a share of the hall's radius
+const RING_BAND: (f32, f32) = (0.55, 0.66);
+/// Half the width of the gaps left in the ring
+const RING_GAP: i32 = 1;
+
+/// Generate a boss floor. The stairs sit on the dais at the far end of the
+/// hall, where the boss is spawned; `map.alcoves` marks where adds wait.
+pub fn generate_arena(rng: &mut StdRng, floor: u32, biome: Biome) -> Map {
+    let width = 80;
+    let height = 50;
+    let mut map = Map::new(width, height, floor, biome);
+
+    let (cx, cy) = (width / 2, 20);
+    let rx = rng.gen_range(HALL_RADIUS_X.0..=HALL_RADIUS_X.1);
+    let ry = rng.gen_range(HALL_RADIUS_Y.0..=HALL_RADIUS_Y.1);
+    let ring = ring_hazard(biome);
+    let radius = |dx: i32, dy: i32| {
+        let (fx, fy) = (dx as f32 / rx as f32, dy as f32 / ry as f32);
+        (fx * fx + fy * fy).sqrt()
+    };
+
+    // The hall, with its hazard ring
+    for dy in -ry..=ry {
+        for dx in -rx..=rx {
+            let r = radius(dx, dy);
+            if r >= 1.0 {
+                continue;
+            }
+            let gap = dx.abs() <= RING_GAP || dy.abs() <= RING_GAP;
+            let tile = if (RING_BAND.0..RING_BAND.1).contains(&r) && !gap { ring } else { TileType::Floor };
+            map.set_tile(cx + dx, cy + dy, tile);
+        }
+    }
+
+    // Pillars and braziers, mirrored into every quarter
+    let pillar = (rx / 3, ry / 3);
+    let brazier = (rx * 3 / 4, ry / 2);
+    for (sx, sy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+        map.set_tile(cx + sx * pillar.0, cy + sy * pillar.1, TileType::Wall);
+        map.set_tile(cx + sx * brazier.0, cy + sy * brazier.1, TileType::Brazier);
+    }
+
+    // Alcoves off either side, level with the braziers
+    let alcove_dy = ry / 2;
+    let edge = (1..=rx).rev().find(|dx| radius(*dx, alcove_dy) < 1.0).unwrap_or(rx);
+    for (sx, sy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+        let y = cy + sy * alcove_dy;
+        for dx in edge + 1..=edge + 2 {
+            map.set_tile(cx + sx * dx, y, TileType::Corridor);
+        }
+        let centre = Position::new(cx + sx * (edge + 4), y);
+        carve_rect(&mut map, centre, 1, 1, TileType::Floor);
+        map.alcoves.push(centre);
+    }
+
+    // One way in: a corridor down to the antechamber
+    let hall_bottom = cy + ry;
+    for y in hall_bottom..hall_bottom + 4 {
+        for x in cx - 1..=cx + 1 {
+            map.set_tile(x, y, TileType::Corridor);
+        }
+    }
+    let antechamber = Position::new(cx, hall_bottom + 7);
+    carve_rect(&mut map, antechamber, 5, 3, TileType::Floor);
+    map.set_tile(antechamber.x - 4, antechamber.y - 2, TileType::Torch);
+    map.set_tile(antechamber.x + 4, antechamber.y - 2, TileType::Torch);
+    map.start_pos = Position::new(cx, antechamber.y + 1);
+
+    let dais = Position::new(cx, cy - ry + 2);
+    map.set_tile(dais.x, dais.y, TileType::StairsDown);
+    map.exit_pos = Some(dais);
+
+    map
+}
+
+/// What the ring is made of. Never pits: falling in would drop the player
+/// past the boss.
+fn ring_hazard(biome: Biome) -> TileType {
+    match biome.config().primary_hazard {
+        HazardType::Lava => TileType::Lava,
+        _ => TileType::BloodPool,
+    }
+}
+
+/// Fill the rectangle `half_w` and `half_h` tiles either side of `centre`
+fn carve_rect(map: &mut Map, centre: Position, half_w: i32, half_h: i32, tile: TileType) {
+    for y in centre.y - half_h..=centre.y + half_h {
+        for x in centre.x - half_w..=centre.x + half_w {
+            map.set_tile(x, y, tile);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::generation::check::{check_map, reachable};
+
+    #[test]
+    fn test_arena_is_symmetrical() {
+        for seed in 0..5 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let map = generate_arena(&mut rng, 5, Biome::SunkenCatacombs);
+            let cx = map.width / 2;
+            for y in 0..map.height {
+                for dx in 1..cx {
+                    let left = map.get_tile(cx - dx, y).map(|t| t.tile_type);
+                    let right = map.get_tile(cx + dx, y).map(|t| t.tile_type);
+                    assert_eq!(left, right, "seed {} row {} differs {} either side", seed, y, dx);
+                }
+            }
+            assert_eq!(map.alcoves.len(), 4);
+            let reach = reachable(&map, map.start_pos);
+            assert!(map.alcoves.iter().all(|a| reach[map.xy_to_idx(a.x, a.y)]));
+            assert!(check_map(&map).is_empty(), "{:?}", check_map(&map));
+        }
+    }
+}
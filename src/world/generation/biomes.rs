@@ -3,6 +3,7 @@
 //! Each biome has distinct visual themes, enemy types, and generation parameters.
 
 use crate::world::{Biome, TileType};
+use crate::entities::Prop;
 
 /// Configuration for a specific biome
 #[derive(Debug, Clone)]
@@ -31,6 +32,18 @@ pub struct BiomeConfig {
     pub hazard_chance: f32,
     /// Hazard type preference
     pub primary_hazard: HazardType,
+    /// What may cut across a floor in a line
+    pub river: RiverType,
+    /// Chance a floor gets its river
+    pub river_chance: f32,
+    /// Share of narrow passages grown shut with living flesh
+    pub flesh_growth: f32,
+    /// Most heart nodes a floor grows
+    pub heart_nodes: usize,
+    /// Movable props set out on a floor
+    pub props: &'static [Prop],
+    /// Most props a floor gets
+    pub prop_count: usize,
     /// Primary decoration types for this biome
     pub decorations: &'static [TileType],
     /// Decoration density (chance per floor tile)
@@ -41,6 +54,28 @@ pub struct BiomeConfig {
     pub floor_glyphs: &'static [char],
 }
 
+/// Lines cut across a floor that only bridges get over cleanly
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RiverType {
+    /// Too deep to wade
+    Water,
+    /// Wadeable, at the cost of corruption
+    Blood,
+    /// A drop to the floor below
+    Chasm,
+}
+
+impl RiverType {
+    /// The tile the river is made of
+    pub fn tile(&self) -> TileType {
+        match self {
+            RiverType::Water => TileType::DeepWater,
+            RiverType::Blood => TileType::BloodPool,
+            RiverType::Chasm => TileType::Pit,
+        }
+    }
+}
+
 /// Types of environmental hazards
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HazardType {
@@ -67,6 +102,12 @@ impl Biome {
                 light_modifier: 1.0,
                 hazard_chance: 0.01,
                 primary_hazard: HazardType::Pit,
+                river: RiverType::Water,
+                river_chance: 0.3,
+                flesh_growth: 0.0,
+                heart_nodes: 0,
+                props: &[Prop::BonePile, Prop::BonePile, Prop::Statue],
+                prop_count: 6,
                 decorations: &[TileType::Bones, TileType::Rubble, TileType::Cobweb, TileType::Cracks],
                 decoration_density: 0.04,
                 wall_glyphs: &['#', '▓', '█', '▒'],
@@ -85,6 +126,12 @@ impl Biome {
                 light_modifier: 0.9,
                 hazard_chance: 0.03,
                 primary_hazard: HazardType::Corruption,
+                river: RiverType::Blood,
+                river_chance: 0.35,
+                flesh_growth: 0.0,
+                heart_nodes: 0,
+                props: &[Prop::BonePile, Prop::Brazier, Prop::Brazier],
+                prop_count: 6,
                 decorations: &[TileType::BloodStain, TileType::Bones, TileType::Grime],
                 decoration_density: 0.06,
                 wall_glyphs: &['#', '▓', '░', '▒'],
@@ -103,11 +150,41 @@ impl Biome {
                 light_modifier: 1.1,
                 hazard_chance: 0.02,
                 primary_hazard: HazardType::Pit,
+                river: RiverType::Chasm,
+                river_chance: 0.3,
+                flesh_growth: 0.0,
+                heart_nodes: 0,
+                props: &[Prop::Statue, Prop::Statue, Prop::Brazier],
+                prop_count: 7,
                 decorations: &[TileType::Rubble, TileType::Cracks, TileType::Cobweb],
                 decoration_density: 0.03,
                 wall_glyphs: &['#', '█', '▓', '╬'],
                 floor_glyphs: &['.', '·', '○', '∙'],
             },
+            Biome::FleshGardens => BiomeConfig {
+                name: "Flesh Gardens",
+                description: "Something planted itself here and grew. The walls breathe, and close behind you.",
+                wall_color: (120, 50, 60),
+                wall_color_alt: (100, 40, 50),
+                floor_color: (60, 28, 32),
+                floor_color_alt: (50, 22, 26),
+                ambient_color: (80, 30, 40),
+                corridor_color: (40, 18, 22),
+                cave_factor: 0.6,  // Grown, not built
+                light_modifier: 0.85,
+                hazard_chance: 0.02,
+                primary_hazard: HazardType::Corruption,
+                river: RiverType::Blood,
+                river_chance: 0.25,
+                flesh_growth: 0.15,
+                heart_nodes: 3,
+                props: &[Prop::BonePile],
+                prop_count: 4,
+                decorations: &[TileType::BloodStain, TileType::Moss, TileType::Bones, TileType::Grime],
+                decoration_density: 0.05,
+                wall_glyphs: &['#', '▓', '▒', '█'],
+                floor_glyphs: &['.', '·', ',', '∘'],
+            },
             Biome::TheAbyss => BiomeConfig {
                 name: "The Abyss",
                 description: "Reality itself breaks down here. Eldritch horrors lurk in the endless dark.",
@@ -121,6 +198,12 @@ impl Biome {
                 light_modifier: 0.7,
                 hazard_chance: 0.05,
                 primary_hazard: HazardType::Lava,
+                river: RiverType::Chasm,
+                river_chance: 0.4,
+                flesh_growth: 0.0,
+                heart_nodes: 0,
+                props: &[Prop::Brazier, Prop::Statue],
+                prop_count: 5,
                 decorations: &[TileType::Ashes, TileType::Cracks, TileType::Grime],
                 decoration_density: 0.05,
                 wall_glyphs: &['#', '▓', '█', '░'],
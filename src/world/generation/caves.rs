@@ -242,6 +242,17 @@ fn add_cave_shrines(rng: &mut StdRng, map: &mut Map, floor: u32) {
     if floor >= 3 && rng.gen_bool(0.3 + (floor as f64 * 0.02).min(0.3)) {
         available_types.push(TileType::ShrineCorruption);
     }
+    // Gambling shrines need a little gold in the player's pocket
+    if floor >= 2 && rng.gen_bool(0.35) {
+        available_types.push(TileType::ShrineGamble);
+    }
+    // Sacrifice and transmutation shrines want decent gear to work with
+    if floor >= 4 && rng.gen_bool(0.25) {
+        available_types.push(TileType::ShrineSacrifice);
+    }
+    if floor >= 6 && rng.gen_bool(0.25) {
+        available_types.push(TileType::ShrineTransmute);
+    }
     available_types.shuffle(rng);
 
     let mut placed_positions: Vec<Position> = Vec::new();
@@ -297,6 +308,11 @@ fn add_cave_decorations(rng: &mut StdRng, map: &mut Map, biome: Biome) {
                         map.set_tile(x, y, TileType::Torch);
                     }
                 }
+                Biome::FleshGardens => {
+                    if rng.gen_bool(0.03) {
+                        map.set_tile(x, y, TileType::Moss);
+                    }
+                }
                 Biome::TheAbyss => {
                     if rng.gen_bool(0.02) {
                         map.set_tile(x, y, TileType::Rubble);
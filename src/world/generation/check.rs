@@ -0,0 +1,262 @@
+//! Generation invariants
+//!
+//! What every generated floor must satisfy: stairs down the player can walk
+//! to, no shrine, altar, elite room, teleporter, heart node, lever, pressure plate, vault or chest
+//! sealed away from the start, no prop wedged in a passage, hazards no denser than the biome allows (boss floors aside),
+//! and no more shrines or chests than the floor hands out. `check_generation` runs the generators over
+//! many seeds and reports each failing seed so it can be reproduced; the
+//! `--gen-check N` mode and the tests below use it.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::ecs::Position;
+use crate::world::{Map, TileType, Trigger};
+use super::{biome_for_floor, generate_floor};
+
+/// Hazards may come out this many times denser than the biome's chance
+/// before it counts as a fault (small floors roll unevenly)
+const HAZARD_DENSITY_SLACK: f32 = 2.0;
+/// Most shrines a floor ever gets
+pub const MAX_SHRINES: usize = 3;
+/// Most altars a floor ever gets
+const MAX_ALTARS: usize = 1;
+/// How far from the start chests are placed (as the game does)
+const CHEST_MIN_DISTANCE: i32 = 6;
+
+/// First floor of each biome; a run checks the five floors from each
+const BIOME_FIRST_FLOORS: [u32; 5] = [1, 6, 11, 16, 21];
+
+/// A floor that broke an invariant, with how to generate it again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenFailure {
+    pub seed: u64,
+    pub floor: u32,
+    pub problems: Vec<String>,
+}
+
+impl GenFailure {
+    pub fn describe(&self) -> String {
+        format!(
+            "seed {} floor {} ({}): {}",
+            self.seed,
+            self.floor,
+            biome_for_floor(self.floor).name(),
+            self.problems.join("; "),
+        )
+    }
+}
+
+/// Can the player get onto this tile from a neighbour? Doors can be opened
+/// (a key lies somewhere for every locked one), living flesh torn, secret
+/// doors found, portcullises raised and lava waded through, but a pit drops
+/// the player to the next floor.
+pub fn passable(tile: TileType) -> bool {
+    tile.is_walkable() || tile.is_breachable() || matches!(tile, TileType::Lava | TileType::SecretDoor | TileType::Portcullis)
+}
+
+/// Which tiles the player can reach from `from`, by tile index
+pub fn reachable(map: &Map, from: Position) -> Vec<bool> {
+    let mut seen = vec![false; map.tiles.len()];
+    if !map.in_bounds(from.x, from.y) {
+        return seen;
+    }
+    seen[map.xy_to_idx(from.x, from.y)] = true;
+    let mut stack = vec![from];
+    while let Some(pos) = stack.pop() {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (x, y) = (pos.x + dx, pos.y + dy);
+                let open = map.get_tile(x, y).is_some_and(|t| passable(t.tile_type));
+                if open && !seen[map.xy_to_idx(x, y)] {
+                    seen[map.xy_to_idx(x, y)] = true;
+                    stack.push(Position::new(x, y));
+                }
+            }
+        }
+    }
+    seen
+}
+
+/// Every invariant a generated map breaks
+pub fn check_map(map: &Map) -> Vec<String> {
+    let mut problems = Vec::new();
+    let reach = reachable(map, map.start_pos);
+    let reached = |pos: Position| map.in_bounds(pos.x, pos.y) && reach[map.xy_to_idx(pos.x, pos.y)];
+
+    if !map.is_walkable(map.start_pos.x, map.start_pos.y) {
+        problems.push(format!("start {:?} is not walkable", (map.start_pos.x, map.start_pos.y)));
+    }
+    match map.exit_pos {
+        None => problems.push("no stairs down".to_string()),
+        Some(exit) if map.get_tile(exit.x, exit.y).map(|t| t.tile_type) != Some(TileType::StairsDown) => {
+            problems.push(format!("exit {:?} has no stairs", (exit.x, exit.y)));
+        }
+        Some(exit) if !reached(exit) => problems.push(format!("stairs {:?} unreachable", (exit.x, exit.y))),
+        Some(_) => {}
+    }
+
+    let mut shrines = Vec::new();
+    let mut altars = 0;
+    let mut hazards = 0;
+    let mut open = 0;
+    for y in 0..map.height {
+        for x in 0..map.width {
+            let Some(tile) = map.get_tile(x, y).map(|t| t.tile_type) else { continue };
+            let pos = Position::new(x, y);
+            if tile.is_shrine() || tile.is_altar() {
+                if !reached(pos) {
+                    problems.push(format!("{} at {:?} sealed off", tile.name(), (x, y)));
+                }
+                if tile.is_shrine() {
+                    shrines.push(tile);
+                } else {
+                    altars += 1;
+                }
+            }
+            // A river or chasm is laid deliberately, not scattered
+            if tile.is_hazard() && !map.river.contains(&pos) {
+                hazards += 1;
+            }
+            if passable(tile) || tile.is_hazard() {
+                open += 1;
+            }
+        }
+    }
+    for pad in map.teleporters.iter().flat_map(|(a, b)| [*a, *b]) {
+        if map.get_tile(pad.x, pad.y).map(|t| t.tile_type) != Some(TileType::Teleporter) {
+            problems.push(format!("teleporter {:?} has no pad", (pad.x, pad.y)));
+        } else if !reached(pad) {
+            problems.push(format!("teleporter {:?} sealed off", (pad.x, pad.y)));
+        }
+    }
+    for heart in &map.hearts {
+        if !map.is_walkable(heart.x, heart.y) {
+            problems.push(format!("heart node {:?} has no ground to grow on", (heart.x, heart.y)));
+        } else if !reached(*heart) {
+            problems.push(format!("heart node {:?} sealed off", (heart.x, heart.y)));
+        }
+    }
+    // Props block the way until shoved, so they must leave room around them
+    for (pos, prop) in &map.props {
+        if !map.is_walkable(pos.x, pos.y) {
+            problems.push(format!("{} at {:?} set on nothing", prop.name(), (pos.x, pos.y)));
+        } else if map.is_narrow_passage(*pos) {
+            problems.push(format!("{} at {:?} blocks a passage", prop.name(), (pos.x, pos.y)));
+        }
+    }
+    // A mechanism is no use if the player can't get to it
+    for mechanism in &map.mechanisms {
+        let at = mechanism.at;
+        let (tile, usable) = match mechanism.trigger {
+            Trigger::Lever => {
+                let mut around = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| Position::new(at.x + dx, at.y + dy)));
+                (TileType::Lever, around.any(reached))
+            }
+            Trigger::Plate => (TileType::PressurePlate, reached(at)),
+        };
+        if map.get_tile(at.x, at.y).map(|t| t.tile_type) != Some(tile) {
+            problems.push(format!("{} {:?} missing", tile.name(), (at.x, at.y)));
+        } else if !usable {
+            problems.push(format!("{} {:?} sealed off", tile.name(), (at.x, at.y)));
+        }
+    }
+    for vault in &map.vaults {
+        if !reached(*vault) {
+            problems.push(format!("vault at {:?} sealed off", (vault.x, vault.y)));
+        }
+    }
+    // A hazard may sit on an elite room's centre; the room is open if
+    // anything around it can be reached
+    for room in map.elite_rooms() {
+        let around = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| Position::new(room.x + dx, room.y + dy)));
+        if !around.into_iter().any(reached) {
+            problems.push(format!("elite room at {:?} sealed off", (room.x, room.y)));
+        }
+    }
+
+    if shrines.len() > MAX_SHRINES {
+        problems.push(format!("{} shrines (at most {})", shrines.len(), MAX_SHRINES));
+    }
+    let mut kinds = shrines.clone();
+    kinds.sort_by_key(|t| *t as u8);
+    kinds.dedup();
+    if kinds.len() < shrines.len() {
+        problems.push("duplicate shrine types".to_string());
+    }
+    if altars > MAX_ALTARS {
+        problems.push(format!("{} altars (at most {})", altars, MAX_ALTARS));
+    }
+
+    // Boss floors lay their hazards out by hand
+    if crate::entities::BossType::is_boss_floor(map.floor_number) {
+        return problems;
+    }
+    let config = map.biome.config();
+    let density = hazards as f32 / open.max(1) as f32;
+    let allowed = config.hazard_chance * HAZARD_DENSITY_SLACK;
+    if density > allowed {
+        problems.push(format!("hazard density {:.1}% over {:.1}%", density * 100.0, allowed * 100.0));
+    }
+    problems
+}
+
+/// Place the floor's chests the way the game does and check them: as many
+/// as the floor hands out (room permitting), all reachable
+pub fn check_chests(map: &Map, floor: u32, rng: &mut StdRng) -> Vec<String> {
+    use crate::ecs::Chest;
+
+    let mut world = hecs::World::new();
+    let positions = map.get_spawn_positions(CHEST_MIN_DISTANCE);
+    crate::entities::spawn_chests_for_floor(&mut world, floor, map.biome, &positions, rng);
+
+    let mut problems = Vec::new();
+    let (min, max) = crate::entities::chest_count_range(floor);
+    let count = world.query::<&Chest>().iter().count();
+    if count > max || count < min.min(positions.len()) {
+        problems.push(format!("{} chests (expected {}-{})", count, min, max));
+    }
+    let reach = reachable(map, map.start_pos);
+    for (_, (pos, _)) in world.query::<(&Position, &Chest)>().iter() {
+        if !reach[map.xy_to_idx(pos.x, pos.y)] {
+            problems.push(format!("chest at {:?} sealed off", (pos.x, pos.y)));
+        }
+    }
+    problems
+}
+
+/// Generate one floor from a seed and check it
+pub fn check_seed(seed: u64, floor: u32) -> Option<GenFailure> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let map = generate_floor(&mut rng, floor, biome_for_floor(floor));
+    let mut problems = check_map(&map);
+    problems.extend(check_chests(&map, floor, &mut rng));
+    (!problems.is_empty()).then_some(GenFailure { seed, floor, problems })
+}
+
+/// Generate `count` floors per biome, seeds 0 to `count - 1`, cycling
+/// through each biome's floors, and return the ones that fail
+pub fn check_generation(count: u64) -> Vec<GenFailure> {
+    BIOME_FIRST_FLOORS.iter()
+        .flat_map(|first| (0..count).filter_map(move |seed| check_seed(seed, first + (seed % 5) as u32)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_invariants() {
+        // The checker notices stairs walled off from the start
+        let mut map = Map::test_map();
+        assert!(check_map(&map).is_empty());
+        for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+            map.set_tile(50 + dx, 32 + dy, TileType::Wall);
+        }
+        assert_eq!(check_map(&map), vec!["stairs (50, 32) unreachable".to_string()]);
+
+        let failures = check_generation(8);
+        let report: Vec<String> = failures.iter().map(GenFailure::describe).collect();
+        assert!(failures.is_empty(), "{}", report.join("\n"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Connectivity repair
+//!
+//! Generators connect their rooms as they go, but hazards laid afterwards
+//! can still cut a corridor (a pit across both lanes), and caves can leave
+//! pockets the cellular pass never opened. After generation, everything
+//! the player can walk on is flood-filled from the start; each region left
+//! over gets the cheapest corridor back to the rest, dug through as few
+//! walls and pits as possible.
+
+use std::collections::VecDeque;
+use crate::ecs::Position;
+use crate::world::{Map, TileType};
+use super::check::{passable, reachable};
+
+/// What the repair pass had to do to a floor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectivityRepair {
+    /// Regions that were cut off from the start
+    pub regions: usize,
+    /// Walls and hazards dug out to join them back up
+    pub tiles_carved: usize,
+}
+
+/// Reconnect every region the player can't reach from the start
+pub fn ensure_connected(map: &mut Map) -> ConnectivityRepair {
+    let mut repair = ConnectivityRepair::default();
+    loop {
+        let reach = reachable(map, map.start_pos);
+        let orphan = (0..map.tiles.len()).find(|&i| !reach[i] && passable(map.tiles[i].tile_type) && !on_border(map, i));
+        let Some(orphan) = orphan else { break };
+
+        let path = cheapest_path(map, &reach, orphan);
+        if path.is_empty() {
+            break;
+        }
+        for pos in path {
+            if !passable(map.get_tile(pos.x, pos.y).map_or(TileType::Wall, |t| t.tile_type)) {
+                map.set_tile(pos.x, pos.y, TileType::Corridor);
+                repair.tiles_carved += 1;
+            }
+        }
+        repair.regions += 1;
+    }
+    repair
+}
+
+fn on_border(map: &Map, idx: usize) -> bool {
+    let (x, y) = map.idx_to_xy(idx);
+    x <= 0 || y <= 0 || x >= map.width - 1 || y >= map.height - 1
+}
+
+/// Tiles to dig from the reached part of the map to `target`, moving
+/// orthogonally and never through the outer wall. Open tiles cost nothing
+/// to cross and anything else costs one, so the fewest tiles get carved.
+fn cheapest_path(map: &Map, reach: &[bool], target: usize) -> Vec<Position> {
+    let mut cost = vec![u32::MAX; map.tiles.len()];
+    let mut came_from = vec![usize::MAX; map.tiles.len()];
+    let mut queue = VecDeque::new();
+    for (i, reached) in reach.iter().enumerate() {
+        if *reached {
+            cost[i] = 0;
+            queue.push_back(i);
+        }
+    }
+
+    while let Some(i) = queue.pop_front() {
+        if i == target {
+            break;
+        }
+        let (x, y) = map.idx_to_xy(i);
+        for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx <= 0 || ny <= 0 || nx >= map.width - 1 || ny >= map.height - 1 {
+                continue;
+            }
+            let next = map.xy_to_idx(nx, ny);
+            let step = if passable(map.tiles[next].tile_type) { 0 } else { 1 };
+            if cost[i] + step < cost[next] {
+                cost[next] = cost[i] + step;
+                came_from[next] = i;
+                if step == 0 {
+                    queue.push_front(next);
+                } else {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    let mut path = Vec::new();
+    let mut at = target;
+    while came_from[at] != usize::MAX {
+        let (x, y) = map.idx_to_xy(at);
+        path.push(Position::new(x, y));
+        at = came_from[at];
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_connected() {
+        let mut map = Map::test_map();
+        assert_eq!(ensure_connected(&mut map), ConnectivityRepair::default());
+
+        // Wall the stairs in, two tiles thick
+        for y in 30..=34 {
+            for x in 48..=52 {
+                if (x, y) != (50, 32) {
+                    map.set_tile(x, y, TileType::Wall);
+                }
+            }
+        }
+        let repair = ensure_connected(&mut map);
+        assert_eq!(repair, ConnectivityRepair { regions: 1, tiles_carved: 2 });
+        assert!(reachable(&map, map.start_pos)[map.xy_to_idx(50, 32)]);
+    }
+}
@@ -0,0 +1,143 @@
+//! Living flesh
+//!
+//! The Flesh Gardens grow over their own passages. Some of the narrow ways
+//! through a floor are grown shut with living flesh, which has to be torn
+//! through and slowly heals behind whoever did, and a few pulsating heart
+//! nodes take root out in the open, away from the start. Biomes with no
+//! flesh growth and no hearts are left alone.
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::ecs::Position;
+use crate::world::{Map, TileType};
+use super::biomes::BiomeConfig;
+
+/// Flesh keeps this far from the start and the stairs
+const LANDMARK_CLEARANCE: i32 = 4;
+/// Growths keep this far from each other, so a passage is plugged once
+const GROWTH_SPACING: i32 = 4;
+/// Hearts keep at least this far from the start
+const HEART_CLEARANCE: i32 = 10;
+/// Hearts keep this far from each other
+const HEART_SPACING: i32 = 8;
+
+/// Grow flesh over the floor's passages and plant its heart nodes
+pub fn overgrow(rng: &mut StdRng, map: &mut Map, config: &BiomeConfig) {
+    grow_flesh(rng, map, config.flesh_growth);
+    plant_hearts(rng, map, config.heart_nodes);
+}
+
+/// Plug a share of the narrow passages with living flesh
+fn grow_flesh(rng: &mut StdRng, map: &mut Map, growth: f32) {
+    if growth <= 0.0 {
+        return;
+    }
+    let landmarks: Vec<Position> = std::iter::once(map.start_pos).chain(map.exit_pos).collect();
+    let mut passages: Vec<Position> = open_ground(map)
+        .into_iter()
+        .filter(|pos| map.is_narrow_passage(*pos))
+        .filter(|pos| landmarks.iter().all(|l| pos.chebyshev_distance(l) > LANDMARK_CLEARANCE))
+        .collect();
+    let wanted = (passages.len() as f32 * growth).round() as usize;
+    passages.shuffle(rng);
+
+    let mut growths: Vec<Position> = Vec::new();
+    for pos in passages {
+        if growths.len() >= wanted {
+            break;
+        }
+        if growths.iter().all(|g| g.chebyshev_distance(&pos) >= GROWTH_SPACING) {
+            map.set_tile(pos.x, pos.y, TileType::FleshWall);
+            growths.push(pos);
+        }
+    }
+}
+
+/// Root up to `most` heart nodes in open rooms, well away from the start
+fn plant_hearts(rng: &mut StdRng, map: &mut Map, most: usize) {
+    if most == 0 {
+        return;
+    }
+    let mut spots: Vec<Position> = open_ground(map)
+        .into_iter()
+        .filter(|pos| !map.is_narrow_passage(*pos))
+        .filter(|pos| pos.chebyshev_distance(&map.start_pos) >= HEART_CLEARANCE && Some(*pos) != map.exit_pos)
+        .collect();
+    spots.shuffle(rng);
+
+    let wanted = rng.gen_range(1..=most);
+    for pos in spots {
+        if map.hearts.len() >= wanted {
+            break;
+        }
+        if map.hearts.iter().all(|h| h.chebyshev_distance(&pos) >= HEART_SPACING) {
+            map.hearts.push(pos);
+        }
+    }
+}
+
+/// Plain floor and corridor outside any secret room
+fn open_ground(map: &Map) -> Vec<Position> {
+    map.get_walkable_positions()
+        .into_iter()
+        .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| matches!(t.tile_type, TileType::Floor | TileType::Corridor)))
+        .filter(|pos| !map.is_secret_room(*pos))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::Biome;
+    use crate::world::generation::check::reachable;
+
+    #[test]
+    fn test_overgrowth() {
+        // Two rooms joined by a long corridor
+        let mut map = Map::new(70, 20, 16, Biome::FleshGardens);
+        for (x1, x2) in [(1, 15), (50, 68)] {
+            for y in 1..19 {
+                for x in x1..=x2 {
+                    map.set_tile(x, y, TileType::Floor);
+                }
+            }
+        }
+        for x in 16..50 {
+            map.set_tile(x, 10, TileType::Corridor);
+        }
+        map.start_pos = Position::new(3, 3);
+        map.exit_pos = Some(Position::new(66, 17));
+        map.set_tile(66, 17, TileType::StairsDown);
+
+        let mut config = Biome::FleshGardens.config();
+        config.flesh_growth = 1.0;
+        let mut rng = StdRng::seed_from_u64(5);
+        overgrow(&mut rng, &mut map, &config);
+
+        // The corridor is grown over, but never so close together that a
+        // plug is more than one tile thick
+        let growths: Vec<i32> = (16..50).filter(|x| map.get_tile(*x, 10).unwrap().tile_type == TileType::FleshWall).collect();
+        assert!(!growths.is_empty());
+        assert!(growths.windows(2).all(|w| w[1] - w[0] >= GROWTH_SPACING));
+
+        // Flesh can be torn through, so the stairs stay within reach
+        assert!(reachable(&map, map.start_pos)[map.xy_to_idx(66, 17)]);
+
+        // Hearts grow in the open, well away from the start
+        assert!((1..=config.heart_nodes).contains(&map.hearts.len()));
+        for heart in &map.hearts {
+            assert!(heart.chebyshev_distance(&map.start_pos) >= HEART_CLEARANCE);
+            assert!(map.is_walkable(heart.x, heart.y));
+        }
+
+        // Other biomes grow nothing
+        let mut plain = map.clone();
+        plain.hearts.clear();
+        let before = plain.tiles.iter().filter(|t| t.tile_type == TileType::FleshWall).count();
+        overgrow(&mut rng, &mut plain, &Biome::HollowCathedral.config());
+        assert!(plain.hearts.is_empty());
+        assert_eq!(plain.tiles.iter().filter(|t| t.tile_type == TileType::FleshWall).count(), before);
+    }
+}
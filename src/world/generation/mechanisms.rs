@@ -0,0 +1,197 @@
+//! Puzzle vaults and trap plates
+//!
+//! Now and then a small loot vault is dug into the rock behind a portcullis.
+//! Either a lever set into a wall somewhere nearby raises it, or a pressure
+//! plate does, with a bone pile left a few steps off to shove onto the plate
+//! and hold it down. Deeper floors also hide a plate or two that loose darts
+//! or a gout of flame on whoever steps on them.
+//!
+//! This runs after connectivity repair, on tiles already known to be
+//! reachable, so no carving can cut through a lever or a vault afterwards.
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::ecs::Position;
+use crate::entities::Prop;
+use crate::world::{Map, Mechanism, Trap, TileType, Trigger, Wiring, VAULT_RADIUS};
+use super::check::reachable;
+use super::secrets::solid_rock;
+
+/// Chance a floor has a puzzle vault
+const VAULT_CHANCE: f64 = 0.35;
+/// Spots tried for the vault before giving up
+const PLACEMENT_ATTEMPTS: usize = 300;
+/// Chance the vault's gate is worked by a plate rather than a lever
+const PLATE_GATE_CHANCE: f64 = 0.4;
+/// How far from the gate its lever may be set
+const LEVER_DISTANCE: std::ops::RangeInclusive<i32> = 4..=12;
+/// How far from the vault's approach its plate may be set
+const PLATE_DISTANCE: std::ops::RangeInclusive<i32> = 2..=6;
+/// First floor with trap plates
+const TRAP_MIN_FLOOR: u32 = 3;
+/// Most trap plates on a floor
+const MAX_TRAP_PLATES: usize = 2;
+/// Mechanisms keep this far from the start and the stairs
+const LANDMARK_CLEARANCE: i32 = 3;
+
+const CARDINALS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Maybe dig a loot vault behind a portcullis, wired to a lever or a plate
+pub fn add_vault(rng: &mut StdRng, map: &mut Map) {
+    if !rng.gen_bool(VAULT_CHANCE) {
+        return;
+    }
+    let reach = reachable(map, map.start_pos);
+
+    for _ in 0..PLACEMENT_ATTEMPTS {
+        let from = Position::new(rng.gen_range(1..map.width - 1), rng.gen_range(1..map.height - 1));
+        if !open_ground(map, &reach, from) {
+            continue;
+        }
+        let (dx, dy) = CARDINALS[rng.gen_range(0..4)];
+        let gate = Position::new(from.x + dx, from.y + dy);
+        let centre = Position::new(from.x + dx * (VAULT_RADIUS + 2), from.y + dy * (VAULT_RADIUS + 2));
+        if !solid_rock(map, centre, VAULT_RADIUS + 1) {
+            continue;
+        }
+
+        let mechanism = if rng.gen_bool(PLATE_GATE_CHANCE) {
+            plate_for(rng, map, &reach, from, gate)
+        } else {
+            lever_for(rng, map, &reach, centre, gate)
+        };
+        let Some(mechanism) = mechanism else { continue };
+
+        for y in centre.y - VAULT_RADIUS..=centre.y + VAULT_RADIUS {
+            for x in centre.x - VAULT_RADIUS..=centre.x + VAULT_RADIUS {
+                map.set_tile(x, y, TileType::Floor);
+            }
+        }
+        map.set_tile(gate.x, gate.y, TileType::Portcullis);
+        let tile = match mechanism.trigger {
+            Trigger::Lever => TileType::Lever,
+            Trigger::Plate => TileType::PressurePlate,
+        };
+        map.set_tile(mechanism.at.x, mechanism.at.y, tile);
+        map.mechanisms.push(mechanism);
+        map.vaults.push(centre);
+        return;
+    }
+}
+
+/// Set a few trap plates about the floor, from the third floor down
+pub fn add_trap_plates(rng: &mut StdRng, map: &mut Map) {
+    if map.floor_number < TRAP_MIN_FLOOR {
+        return;
+    }
+    let reach = reachable(map, map.start_pos);
+    let mut spots: Vec<Position> = map.get_walkable_positions()
+        .into_iter()
+        .filter(|pos| open_ground(map, &reach, *pos))
+        .collect();
+    spots.shuffle(rng);
+
+    for pos in spots.into_iter().take(rng.gen_range(0..=MAX_TRAP_PLATES)) {
+        let trap = if rng.gen_bool(0.5) { Trap::Darts } else { Trap::Flames };
+        map.set_tile(pos.x, pos.y, TileType::PressurePlate);
+        map.mechanisms.push(Mechanism::plate(pos, Wiring::Trap(trap)));
+    }
+}
+
+/// Plain reachable floor, clear of the landmarks and anything already set down
+fn open_ground(map: &Map, reach: &[bool], pos: Position) -> bool {
+    map.get_tile(pos.x, pos.y).is_some_and(|t| matches!(t.tile_type, TileType::Floor | TileType::Corridor))
+        && reach[map.xy_to_idx(pos.x, pos.y)]
+        && pos.chebyshev_distance(&map.start_pos) > LANDMARK_CLEARANCE
+        && map.exit_pos.is_none_or(|exit| pos.chebyshev_distance(&exit) > LANDMARK_CLEARANCE)
+        && !map.has_prop(pos)
+        && !map.is_secret_room(pos)
+        && !map.is_vault(pos)
+        && !map.hearts.contains(&pos)
+        && map.teleporter_exit(pos).is_none()
+        && !map.mechanisms.iter().any(|m| m.at == pos)
+}
+
+/// A wall a little way off the gate, with open ground in front of it to
+/// pull it from
+fn lever_for(rng: &mut StdRng, map: &Map, reach: &[bool], centre: Position, gate: Position) -> Option<Mechanism> {
+    let mut walls = Vec::new();
+    for y in 1..map.height - 1 {
+        for x in 1..map.width - 1 {
+            let pos = Position::new(x, y);
+            let wall = map.get_tile(x, y).is_some_and(|t| t.tile_type == TileType::Wall);
+            if !wall || !LEVER_DISTANCE.contains(&pos.chebyshev_distance(&gate))
+                || pos.chebyshev_distance(&centre) <= VAULT_RADIUS + 1
+            {
+                continue;
+            }
+            let faced = CARDINALS.iter().any(|(dx, dy)| open_ground(map, reach, Position::new(x + dx, y + dy)));
+            if faced {
+                walls.push(pos);
+            }
+        }
+    }
+    walls.choose(rng).map(|at| Mechanism::lever(*at, vec![gate]))
+}
+
+/// A plate near the vault's approach, with a bone pile lined up two steps
+/// off it and room behind the pile to shove it on
+fn plate_for(rng: &mut StdRng, map: &mut Map, reach: &[bool], from: Position, gate: Position) -> Option<Mechanism> {
+    let mut layouts = Vec::new();
+    for y in from.y - PLATE_DISTANCE.end()..=from.y + PLATE_DISTANCE.end() {
+        for x in from.x - PLATE_DISTANCE.end()..=from.x + PLATE_DISTANCE.end() {
+            let plate = Position::new(x, y);
+            if !PLATE_DISTANCE.contains(&plate.chebyshev_distance(&from)) {
+                continue;
+            }
+            for (dx, dy) in CARDINALS {
+                let line: Vec<Position> = (0..4).map(|i| Position::new(x - dx * i, y - dy * i)).collect();
+                let clear = line.iter().all(|pos| {
+                    map.in_bounds(pos.x, pos.y) && *pos != gate && open_ground(map, reach, *pos)
+                });
+                if clear && !map.is_narrow_passage(line[2]) {
+                    layouts.push((plate, line[2]));
+                }
+            }
+        }
+    }
+    let &(plate, weight) = layouts.choose(rng)?;
+    map.props.push((weight, Prop::BonePile));
+    Some(Mechanism::plate(plate, Wiring::Gates(vec![gate])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::Biome;
+    use crate::world::generation::check::check_map;
+
+    #[test]
+    fn test_puzzle_vaults() {
+        // A corridor through solid rock has room either side
+        let mut map = Map::new(40, 30, 6, Biome::BleedingCrypts);
+        for x in 1..39 {
+            map.set_tile(x, 15, TileType::Corridor);
+        }
+        map.start_pos = Position::new(1, 15);
+        map.exit_pos = Some(Position::new(38, 15));
+        map.set_tile(38, 15, TileType::StairsDown);
+
+        let mut rng = StdRng::seed_from_u64(2);
+        while map.vaults.is_empty() {
+            add_vault(&mut rng, &mut map);
+        }
+        add_trap_plates(&mut rng, &mut map);
+        assert!(check_map(&map).is_empty(), "{:?}", check_map(&map));
+
+        // Exactly one gate, wired to the vault's one mechanism
+        let gates: Vec<usize> = (0..map.tiles.len()).filter(|&i| map.tiles[i].tile_type == TileType::Portcullis).collect();
+        assert_eq!(gates.len(), 1);
+        let (x, y) = map.idx_to_xy(gates[0]);
+        let wired = map.mechanisms.iter().filter(|m| m.wiring == Wiring::Gates(vec![Position::new(x, y)])).count();
+        assert_eq!(wired, 1);
+        assert!(map.mechanisms.iter().all(|m| !map.is_vault(m.at)));
+    }
+}
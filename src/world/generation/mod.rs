@@ -3,24 +3,53 @@
 //! Different generators for various biomes.
 
 pub mod rooms;
+pub mod arena;
 pub mod caves;
 pub mod biomes;
 pub mod templates;
+pub mod check;
+pub mod connectivity;
+pub mod secrets;
+pub mod teleporters;
+pub mod rivers;
+pub mod garden;
+pub mod props;
+pub mod mechanisms;
+pub mod tolls;
 
-pub use biomes::{BiomeConfig, HazardType};
+pub use biomes::{BiomeConfig, HazardType, RiverType};
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 use super::{Map, Biome};
 
+/// The deepest floor of the Hollowdeep proper; endless mode goes on below it
+pub const FINAL_FLOOR: u32 = 25;
+/// Mixed into a depth's number to pick its biomes
+const DEPTH_BIOME_SEED: u64 = 0x0D3E_9B10_3E5A_11CE;
+
 /// Generate a floor based on biome type
 pub fn generate_floor(rng: &mut StdRng, floor: u32, biome: Biome) -> Map {
+    use crate::entities::BossType;
+
     let config = biome.config();
 
     // Use cave_factor to probabilistically choose generator
     // This creates variety within biomes
     let use_caves = rng.gen_bool(config.cave_factor as f64);
 
+    // Boss floors get an arena, and the last one its own set piece. They
+    // are dressed by hand, so skip the random hazards and decorations.
+    match BossType::for_floor(floor) {
+        Some(BossType::VoidHarbinger) => return templates::final_floor(floor),
+        Some(_) => {
+            let mut map = arena::generate_arena(rng, floor, biome);
+            ensure_stairs_exist(&mut map);
+            return map;
+        }
+        None => {}
+    }
+
     let mut map = if use_caves {
         caves::generate_caves(rng, floor, biome)
     } else {
@@ -31,15 +60,49 @@ pub fn generate_floor(rng: &mut StdRng, floor: u32, biome: Biome) -> Map {
     // If no exit was placed, find a valid position far from start
     ensure_stairs_exist(&mut map);
 
+    // Maybe run a river or chasm across the floor, with a few bridges
+    rivers::add_river(rng, &mut map, &config);
+
     // Add biome-specific hazards
     add_hazards(rng, &mut map, &config);
 
     // Add biome-specific decorations for visual variety
     add_biome_decorations(rng, &mut map, &config);
 
+    // Maybe raise an altar to one of the gods
+    add_altar(rng, &mut map);
+
+    // Maybe hide a treasure room behind a secret door
+    secrets::add_secret_room(rng, &mut map);
+
+    // Link the far reaches of large floors with teleporter pads
+    teleporters::add_teleporters(rng, &mut map);
+
+    // Let living flesh grow over passages and heart nodes take root
+    garden::overgrow(rng, &mut map, &config);
+
+    // Set out bone piles, braziers and statues to shove around
+    props::add_props(rng, &mut map, &config);
+
     // SAFETY: Double-check stairs weren't overwritten by hazards/decorations
     ensure_stairs_exist(&mut map);
 
+    // SAFETY: Dig back to anything hazards or the generator cut off
+    let repair = connectivity::ensure_connected(&mut map);
+    if repair.regions > 0 {
+        log::info!(
+            "Reconnected {} region(s) on floor {} by carving {} tile(s)",
+            repair.regions, floor, repair.tiles_carved,
+        );
+    }
+
+    // Wire up a puzzle vault and trap plates on the now-connected floor
+    mechanisms::add_vault(rng, &mut map);
+    mechanisms::add_trap_plates(rng, &mut map);
+
+    // Maybe bar the quickest way to the stairs with a toll gate
+    tolls::add_toll_gate(rng, &mut map);
+
     map
 }
 
@@ -106,7 +169,7 @@ fn add_hazards(rng: &mut StdRng, map: &mut Map, config: &BiomeConfig) {
     let hazard_tile = match config.primary_hazard {
         HazardType::Lava => TileType::Lava,
         HazardType::Pit => TileType::Pit,
-        HazardType::Corruption => TileType::BloodStain,
+        HazardType::Corruption => TileType::BloodPool,
         HazardType::None => return,
     };
 
@@ -122,7 +185,7 @@ fn add_hazards(rng: &mut StdRng, map: &mut Map, config: &BiomeConfig) {
             if Some(pos) == map.exit_pos {
                 continue;
             }
-            if !map.is_walkable(x, y) {
+            if !map.is_walkable(x, y) || map.get_tile(x, y).is_some_and(|t| t.tile_type == TileType::Bridge) {
                 continue;
             }
 
@@ -171,12 +234,78 @@ fn add_biome_decorations(rng: &mut StdRng, map: &mut Map, config: &BiomeConfig)
     }
 }
 
-/// Get the biome for a given floor number
+/// Chance for a floor to contain an altar
+const ALTAR_CHANCE: f64 = 0.5;
+
+/// Place an altar to a random god on a plain floor tile away from the start
+fn add_altar(rng: &mut StdRng, map: &mut Map) {
+    use rand::seq::SliceRandom;
+    use super::TileType;
+    use crate::ecs::Position;
+
+    if !rng.gen_bool(ALTAR_CHANCE) {
+        return;
+    }
+
+    let mut candidates: Vec<Position> = Vec::new();
+    for y in 1..map.height - 1 {
+        for x in 1..map.width - 1 {
+            let pos = Position::new(x, y);
+            let is_floor = map.get_tile(x, y).is_some_and(|t| t.tile_type == TileType::Floor);
+            if is_floor
+                && pos.distance(&map.start_pos) >= 6
+                && Some(pos) != map.exit_pos
+                && !map.is_narrow_passage(pos)
+            {
+                candidates.push(pos);
+            }
+        }
+    }
+
+    let altars = [TileType::AltarMaw, TileType::AltarWidow, TileType::AltarEye];
+    if let (Some(pos), Some(altar)) = (candidates.choose(rng), altars.choose(rng)) {
+        map.set_tile(pos.x, pos.y, *altar);
+    }
+}
+
+/// Get the biome for a given floor number. Each depth below the final
+/// floor takes the look of a biome picked at random, the same one every time
+/// that depth is generated.
 pub fn biome_for_floor(floor: u32) -> Biome {
     match floor {
-        1..=5 => Biome::SunkenCatacombs,
+        0..=5 => Biome::SunkenCatacombs,
         6..=10 => Biome::BleedingCrypts,
         11..=15 => Biome::HollowCathedral,
-        _ => Biome::TheAbyss,
+        16..=20 => Biome::FleshGardens,
+        21..=FINAL_FLOOR => Biome::TheAbyss,
+        _ => depth_biomes(floor).0,
     }
 }
+
+/// A second biome whose creatures roam a depth floor alongside its own
+pub fn mashup_biome(floor: u32) -> Option<Biome> {
+    endless_depth(floor).map(|_| depth_biomes(floor).1)
+}
+
+/// How far below the final floor an endless run has gone, if it has
+pub fn endless_depth(floor: u32) -> Option<u32> {
+    floor.checked_sub(FINAL_FLOOR).filter(|depth| *depth > 0)
+}
+
+/// "Floor N" in the Hollowdeep, "Depth N" below it
+pub fn floor_name(floor: u32) -> String {
+    match endless_depth(floor) {
+        Some(depth) => format!("Depth {}", depth),
+        None => format!("Floor {}", floor),
+    }
+}
+
+/// Two different biomes for a depth floor, scrambled from its number
+fn depth_biomes(floor: u32) -> (Biome, Biome) {
+    use rand::seq::SliceRandom;
+
+    let mut rng = StdRng::seed_from_u64(DEPTH_BIOME_SEED ^ floor as u64);
+    let mut biomes = Biome::ALL;
+    biomes.shuffle(&mut rng);
+    (biomes[0], biomes[1])
+}
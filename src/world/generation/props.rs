@@ -0,0 +1,117 @@
+//! Prop placement
+//!
+//! Sets a few movable props out in each floor's rooms: bone piles, standing
+//! braziers and statues, by biome. Props stay out of narrow passages and
+//! away from the start and the stairs, so none of them can wall off the way
+//! before the player moves it. Some braziers stand beside a slick of spilled
+//! oil, waiting to be knocked into it.
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::ecs::Position;
+use crate::entities::Prop;
+use crate::world::{Map, TileType};
+use super::biomes::BiomeConfig;
+
+/// Props keep this far from the start and the stairs
+const LANDMARK_CLEARANCE: i32 = 4;
+/// Props keep this far from each other
+const PROP_SPACING: i32 = 3;
+/// Chance a brazier has oil spilled beside it
+const OIL_CHANCE: f64 = 0.5;
+/// Most tiles one slick of oil covers
+const OIL_SPREAD: usize = 8;
+
+/// Set out the floor's props, and spill oil by some of its braziers
+pub fn add_props(rng: &mut StdRng, map: &mut Map, config: &BiomeConfig) {
+    if config.props.is_empty() || config.prop_count == 0 {
+        return;
+    }
+    let mut spots = prop_spots(map);
+    spots.shuffle(rng);
+
+    let wanted = rng.gen_range(config.prop_count / 2..=config.prop_count);
+    for pos in spots {
+        if map.props.len() >= wanted {
+            break;
+        }
+        let spilled = map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type == TileType::Oil);
+        if spilled || map.props.iter().any(|(p, _)| p.chebyshev_distance(&pos) < PROP_SPACING) {
+            continue;
+        }
+        let Some(&prop) = config.props.choose(rng) else { return };
+        map.props.push((pos, prop));
+        if prop == Prop::Brazier && rng.gen_bool(OIL_CHANCE) {
+            spill_oil(rng, map, pos);
+        }
+    }
+}
+
+/// Open room floor clear of the landmarks and anything else set down
+fn prop_spots(map: &Map) -> Vec<Position> {
+    let landmarks: Vec<Position> = std::iter::once(map.start_pos).chain(map.exit_pos).collect();
+    map.get_walkable_positions()
+        .into_iter()
+        .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type == TileType::Floor))
+        .filter(|pos| !map.is_narrow_passage(*pos) && !map.is_secret_room(*pos))
+        .filter(|pos| landmarks.iter().all(|l| pos.chebyshev_distance(l) > LANDMARK_CLEARANCE))
+        .filter(|pos| map.teleporter_exit(*pos).is_none() && !map.hearts.contains(pos))
+        .collect()
+}
+
+/// Wander a slick of oil across the floor next to a brazier
+fn spill_oil(rng: &mut StdRng, map: &mut Map, brazier: Position) {
+    let mut at = brazier;
+    for _ in 0..OIL_SPREAD {
+        let next = Position::new(at.x + rng.gen_range(-1..=1), at.y + rng.gen_range(-1..=1));
+        let bare = map.get_tile(next.x, next.y).is_some_and(|t| t.tile_type == TileType::Floor)
+            && next != map.start_pos
+            && Some(next) != map.exit_pos
+            && !map.has_prop(next);
+        if bare {
+            map.set_tile(next.x, next.y, TileType::Oil);
+            at = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::Biome;
+
+    #[test]
+    fn test_props_stay_out_of_the_way() {
+        // One open room, with a corridor running off it
+        let mut map = Map::new(40, 20, 6, Biome::BleedingCrypts);
+        for y in 1..19 {
+            for x in 1..25 {
+                map.set_tile(x, y, TileType::Floor);
+            }
+        }
+        for x in 25..39 {
+            map.set_tile(x, 10, TileType::Corridor);
+        }
+        map.start_pos = Position::new(3, 3);
+        map.exit_pos = Some(Position::new(38, 10));
+
+        let mut config = Biome::BleedingCrypts.config();
+        config.props = &[Prop::Brazier];
+        config.prop_count = 6;
+        let mut rng = StdRng::seed_from_u64(3);
+        add_props(&mut rng, &mut map, &config);
+
+        assert!((3..=6).contains(&map.props.len()));
+        for (i, (pos, _)) in map.props.iter().enumerate() {
+            assert_eq!(map.get_tile(pos.x, pos.y).unwrap().tile_type, TileType::Floor);
+            assert!(pos.chebyshev_distance(&map.start_pos) > LANDMARK_CLEARANCE);
+            assert!(map.props[i + 1..].iter().all(|(other, _)| other.chebyshev_distance(pos) >= PROP_SPACING));
+        }
+
+        // Oil only ever soaks the room's floor
+        let oil: Vec<usize> = (0..map.tiles.len()).filter(|&i| map.tiles[i].tile_type == TileType::Oil).collect();
+        assert!(oil.iter().all(|&i| map.idx_to_xy(i).0 < 25));
+    }
+}
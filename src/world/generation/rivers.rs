@@ -0,0 +1,172 @@
+//! Rivers and chasms
+//!
+//! Some floors get a line of water, blood or sheer drop running from one
+//! edge of the map to the other. It only floods open ground, so in a rooms
+//! layout it cuts through whichever rooms and corridors lie in its way and in
+//! caves through the caverns. Bridges are then thrown over wherever the
+//! ground runs straight across: first where a bank would otherwise be cut
+//! off, then a few more so there's a choice of crossing, but never so many
+//! that the river stops mattering. Anything shoved off a bridge is lost.
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::ecs::Position;
+use crate::world::{Map, TileType};
+use super::biomes::BiomeConfig;
+use super::check::passable;
+
+/// Most bridges a river gets, unless more are needed to reach every bank
+const MAX_BRIDGES: usize = 3;
+/// Chance the course steps sideways at each tile along
+const DRIFT_CHANCE: f64 = 0.35;
+/// The course keeps this far from the start and the stairs
+const STAIRS_CLEARANCE: i32 = 3;
+/// Bridges keep this far from each other
+const BRIDGE_SPACING: i32 = 5;
+/// Courses tried before the floor goes without
+const PLACEMENT_ATTEMPTS: usize = 10;
+
+/// Maybe run the biome's river across the floor and bridge it
+pub fn add_river(rng: &mut StdRng, map: &mut Map, config: &BiomeConfig) {
+    if !rng.gen_bool(config.river_chance as f64) {
+        return;
+    }
+
+    let Some(course) = (0..PLACEMENT_ATTEMPTS)
+        .map(|_| plot_course(rng, map))
+        .find(|course| keeps_clear(map, course))
+    else {
+        return;
+    };
+
+    let water = config.river.tile();
+    for pos in course {
+        let open = map.get_tile(pos.x, pos.y).is_some_and(|t| t.is_walkable() || t.tile_type.is_shut_door());
+        if open {
+            map.set_tile(pos.x, pos.y, water);
+            map.river.push(pos);
+        }
+    }
+    throw_bridges(rng, map, water);
+}
+
+/// A 4-connected line from one edge of the map to the other, wandering
+/// sideways as it goes, so nothing can slip between its tiles diagonally
+fn plot_course(rng: &mut StdRng, map: &Map) -> Vec<Position> {
+    let vertical = rng.gen_bool(0.5);
+    let (length, breadth) = if vertical { (map.height, map.width) } else { (map.width, map.height) };
+    let at = |along: i32, across: i32| if vertical { Position::new(across, along) } else { Position::new(along, across) };
+
+    let mut across = rng.gen_range(breadth / 4..breadth * 3 / 4);
+    let mut course = Vec::new();
+    for along in 1..length - 1 {
+        course.push(at(along, across));
+        if rng.gen_bool(DRIFT_CHANCE) {
+            across = (across + if rng.gen_bool(0.5) { 1 } else { -1 }).clamp(2, breadth - 3);
+            course.push(at(along, across));
+        }
+    }
+    course
+}
+
+/// Does the course stay clear of the start and the stairs?
+fn keeps_clear(map: &Map, course: &[Position]) -> bool {
+    let landmarks: Vec<Position> = std::iter::once(map.start_pos).chain(map.exit_pos).collect();
+    course.iter().all(|pos| landmarks.iter().all(|l| pos.chebyshev_distance(l) > STAIRS_CLEARANCE))
+}
+
+/// Bridge the river where a bank would be cut off, then a few more times
+/// for choice
+fn throw_bridges(rng: &mut StdRng, map: &mut Map, water: TileType) {
+    let mut spans: Vec<(Position, Position, Position)> = map.river.iter()
+        .filter_map(|pos| banks(map, *pos, water).map(|(a, b)| (*pos, a, b)))
+        .collect();
+    spans.shuffle(rng);
+
+    let mut bridges: Vec<Position> = Vec::new();
+    loop {
+        let reach = dry_reach(map, water);
+        let reached = |pos: Position| reach[map.xy_to_idx(pos.x, pos.y)];
+        let Some(i) = spans.iter().position(|(_, a, b)| reached(*a) != reached(*b)) else { break };
+        let (pos, _, _) = spans.swap_remove(i);
+        map.set_tile(pos.x, pos.y, TileType::Bridge);
+        bridges.push(pos);
+    }
+
+    let wanted = rng.gen_range(1..=MAX_BRIDGES);
+    for (pos, _, _) in spans {
+        if bridges.len() >= wanted {
+            break;
+        }
+        if bridges.iter().all(|b| b.chebyshev_distance(&pos) >= BRIDGE_SPACING) {
+            map.set_tile(pos.x, pos.y, TileType::Bridge);
+            bridges.push(pos);
+        }
+    }
+}
+
+/// The dry ground either side of a river tile, if it runs straight across
+fn banks(map: &Map, pos: Position, water: TileType) -> Option<(Position, Position)> {
+    let dry = |p: Position| map.get_tile(p.x, p.y).is_some_and(|t| t.tile_type != water && passable(t.tile_type));
+    [((-1, 0), (1, 0)), ((0, -1), (0, 1))].into_iter()
+        .map(|((ax, ay), (bx, by))| (Position::new(pos.x + ax, pos.y + ay), Position::new(pos.x + bx, pos.y + by)))
+        .find(|(a, b)| dry(*a) && dry(*b))
+}
+
+/// Which tiles the player can reach from the start without crossing the river
+fn dry_reach(map: &Map, water: TileType) -> Vec<bool> {
+    let mut seen = vec![false; map.tiles.len()];
+    if !map.in_bounds(map.start_pos.x, map.start_pos.y) {
+        return seen;
+    }
+    seen[map.xy_to_idx(map.start_pos.x, map.start_pos.y)] = true;
+    let mut stack = vec![map.start_pos];
+    while let Some(pos) = stack.pop() {
+        for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+            let (x, y) = (pos.x + dx, pos.y + dy);
+            let open = map.get_tile(x, y).is_some_and(|t| t.tile_type != water && passable(t.tile_type));
+            if open && !seen[map.xy_to_idx(x, y)] {
+                seen[map.xy_to_idx(x, y)] = true;
+                stack.push(Position::new(x, y));
+            }
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::Biome;
+    use crate::world::generation::check::reachable;
+
+    #[test]
+    fn test_river_bridges() {
+        let mut map = Map::new(60, 40, 2, Biome::SunkenCatacombs);
+        for y in 1..39 {
+            for x in 1..59 {
+                map.set_tile(x, y, TileType::Floor);
+            }
+        }
+        map.start_pos = Position::new(3, 3);
+        map.exit_pos = Some(Position::new(56, 36));
+        map.set_tile(56, 36, TileType::StairsDown);
+
+        let mut config = Biome::SunkenCatacombs.config();
+        config.river_chance = 1.0;
+        let mut rng = StdRng::seed_from_u64(3);
+        add_river(&mut rng, &mut map, &config);
+        assert!(!map.river.is_empty());
+
+        // The stairs can be reached, but only over the bridges
+        let bridges: Vec<usize> = (0..map.tiles.len()).filter(|i| map.tiles[*i].tile_type == TileType::Bridge).collect();
+        assert!((1..=MAX_BRIDGES).contains(&bridges.len()));
+        assert!(reachable(&map, map.start_pos)[map.xy_to_idx(56, 36)]);
+        for i in bridges {
+            map.tiles[i].tile_type = TileType::DeepWater;
+        }
+        assert!(!reachable(&map, map.start_pos)[map.xy_to_idx(56, 36)]);
+    }
+}
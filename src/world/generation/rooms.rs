@@ -7,6 +7,11 @@ use rand::rngs::StdRng;
 use crate::ecs::Position;
 use crate::world::{Map, Biome, TileType};
 
+/// Chance a doorway gets a door hung in it
+const DOOR_CHANCE: f64 = 0.35;
+/// Chance a door is locked (from floor 2 onward)
+const LOCKED_DOOR_CHANCE: f64 = 0.15;
+
 /// A rectangular room
 #[derive(Debug, Clone)]
 struct Room {
@@ -116,6 +121,9 @@ pub fn generate_dungeon(rng: &mut StdRng, floor: u32, biome: Biome) -> Map {
     // Add decorations
     add_decorations(rng, &mut map, &rooms, biome);
 
+    // Hang doors where corridors break into rooms
+    add_doors(rng, &mut map, &rooms, floor);
+
     // Add shrines (multiple, different types, not in first or last room)
     if rooms.len() > 2 {
         add_shrines(rng, &mut map, &rooms, floor);
@@ -189,6 +197,63 @@ fn fill_corner(map: &mut Map, x: i32, y: i32) {
     }
 }
 
+/// Hang doors in some of the gaps where corridors meet room walls
+fn add_doors(rng: &mut StdRng, map: &mut Map, rooms: &[Room], floor: u32) {
+    for room in rooms {
+        for doorway in find_doorways(map, room) {
+            if !rng.gen_bool(DOOR_CHANCE) {
+                continue;
+            }
+            let door = if floor >= 2 && rng.gen_bool(LOCKED_DOOR_CHANCE) {
+                TileType::DoorLocked
+            } else {
+                TileType::DoorClosed
+            };
+            for pos in doorway {
+                map.set_tile(pos.x, pos.y, door);
+            }
+        }
+    }
+}
+
+/// Gaps one or two tiles wide in a room's wall with open ground on both sides
+fn find_doorways(map: &Map, room: &Room) -> Vec<Vec<Position>> {
+    let is_open = |pos: Position| {
+        map.get_tile(pos.x, pos.y)
+            .is_some_and(|t| matches!(t.tile_type, TileType::Floor | TileType::Corridor))
+    };
+
+    // Each wall (corners excluded) with its outward direction
+    let walls: [(Vec<Position>, (i32, i32)); 4] = [
+        ((room.x1 + 1..room.x2).map(|x| Position::new(x, room.y1)).collect(), (0, -1)),
+        ((room.x1 + 1..room.x2).map(|x| Position::new(x, room.y2)).collect(), (0, 1)),
+        ((room.y1 + 1..room.y2).map(|y| Position::new(room.x1, y)).collect(), (-1, 0)),
+        ((room.y1 + 1..room.y2).map(|y| Position::new(room.x2, y)).collect(), (1, 0)),
+    ];
+
+    let mut doorways = Vec::new();
+    for (wall, (dx, dy)) in walls {
+        let mut run: Vec<Position> = Vec::new();
+        for pos in wall {
+            let outside = Position::new(pos.x + dx, pos.y + dy);
+            let inside = Position::new(pos.x - dx, pos.y - dy);
+            if is_open(pos) && is_open(outside) && is_open(inside) {
+                run.push(pos);
+                continue;
+            }
+            // A long open stretch is a missing wall, not a doorway
+            if (1..=2).contains(&run.len()) {
+                doorways.push(std::mem::take(&mut run));
+            }
+            run.clear();
+        }
+        if (1..=2).contains(&run.len()) {
+            doorways.push(run);
+        }
+    }
+    doorways
+}
+
 /// Get all valid shrine positions in a room (walkable tiles, not center of doorways, not start/exit)
 fn get_valid_shrine_positions(map: &Map, room: &Room) -> Vec<Position> {
     let mut positions = Vec::new();
@@ -241,6 +306,17 @@ fn add_shrines(rng: &mut StdRng, map: &mut Map, rooms: &[Room], floor: u32) {
     if floor >= 3 && rng.gen_bool(0.3 + (floor as f64 * 0.02).min(0.3)) {
         available_types.push(TileType::ShrineCorruption);
     }
+    // Gambling shrines need a little gold in the player's pocket
+    if floor >= 2 && rng.gen_bool(0.35) {
+        available_types.push(TileType::ShrineGamble);
+    }
+    // Sacrifice and transmutation shrines want decent gear to work with
+    if floor >= 4 && rng.gen_bool(0.25) {
+        available_types.push(TileType::ShrineSacrifice);
+    }
+    if floor >= 6 && rng.gen_bool(0.25) {
+        available_types.push(TileType::ShrineTransmute);
+    }
 
     // Shuffle rooms and shrine types
     let mut shuffled_rooms = middle_rooms.clone();
@@ -264,18 +340,19 @@ fn add_shrines(rng: &mut StdRng, map: &mut Map, rooms: &[Room], floor: u32) {
         // Pick a random valid position
         let shrine_pos = *valid_positions.choose(rng).unwrap();
 
-        // Use the next unique shrine type
-        let shrine_type = available_types[shrines_placed];
-
-        // Check if this room is in an elite zone - override with corruption chance
+        // Elite zones lean towards corruption, unless one is already placed
         let is_elite = map.is_elite_zone(shrine_pos);
-        let final_shrine_type = if is_elite && rng.gen_bool(0.4) && shrine_type != TileType::ShrineCorruption {
-            TileType::ShrineCorruption
-        } else {
-            shrine_type
-        };
+        if is_elite && rng.gen_bool(0.4) {
+            match available_types.iter().position(|t| *t == TileType::ShrineCorruption) {
+                Some(at) if at > shrines_placed => available_types.swap(at, shrines_placed),
+                Some(_) => {}
+                None => available_types[shrines_placed] = TileType::ShrineCorruption,
+            }
+        }
 
-        map.set_tile(shrine_pos.x, shrine_pos.y, final_shrine_type);
+        // Use the next unique shrine type
+        let shrine_type = available_types[shrines_placed];
+        map.set_tile(shrine_pos.x, shrine_pos.y, shrine_type);
         used_rooms.insert(i);
         shrines_placed += 1;
     }
@@ -329,6 +406,15 @@ fn add_decorations(rng: &mut StdRng, map: &mut Map, rooms: &[Room], biome: Biome
                     }
                 }
             }
+            Biome::FleshGardens => {
+                if rng.gen_bool(0.4) {
+                    let x = rng.gen_range(room.x1 + 2..room.x2 - 1);
+                    let y = rng.gen_range(room.y1 + 2..room.y2 - 1);
+                    if !is_protected_position(map, x, y) {
+                        map.set_tile(x, y, TileType::Moss);
+                    }
+                }
+            }
             Biome::TheAbyss => {
                 if rng.gen_bool(0.2) {
                     let x = rng.gen_range(room.x1 + 2..room.x2 - 1);
@@ -0,0 +1,89 @@
+//! Secret rooms
+//!
+//! Now and then a small treasure room is dug into solid rock beside a room
+//! or corridor. Its one way in is a secret door that passes for any other
+//! wall until the player searches for it or happens to notice it.
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use crate::ecs::Position;
+use crate::world::{Map, TileType, SECRET_ROOM_RADIUS};
+
+/// Chance a floor hides a treasure room
+const SECRET_ROOM_CHANCE: f64 = 0.4;
+/// Spots tried for the room before giving up
+const PLACEMENT_ATTEMPTS: usize = 300;
+
+/// Maybe dig a treasure room behind a secret door
+pub fn add_secret_room(rng: &mut StdRng, map: &mut Map) {
+    if !rng.gen_bool(SECRET_ROOM_CHANCE) {
+        return;
+    }
+
+    for _ in 0..PLACEMENT_ATTEMPTS {
+        let from = Position::new(rng.gen_range(1..map.width - 1), rng.gen_range(1..map.height - 1));
+        let plain = map.get_tile(from.x, from.y)
+            .is_some_and(|t| matches!(t.tile_type, TileType::Floor | TileType::Corridor));
+        if !plain || from == map.start_pos || Some(from) == map.exit_pos {
+            continue;
+        }
+
+        let (dx, dy) = [(0, -1), (1, 0), (0, 1), (-1, 0)][rng.gen_range(0..4)];
+        let door = Position::new(from.x + dx, from.y + dy);
+        let centre = Position::new(from.x + dx * (SECRET_ROOM_RADIUS + 2), from.y + dy * (SECRET_ROOM_RADIUS + 2));
+        if !solid_rock(map, centre, SECRET_ROOM_RADIUS + 1) {
+            continue;
+        }
+
+        for y in centre.y - SECRET_ROOM_RADIUS..=centre.y + SECRET_ROOM_RADIUS {
+            for x in centre.x - SECRET_ROOM_RADIUS..=centre.x + SECRET_ROOM_RADIUS {
+                map.set_tile(x, y, TileType::Floor);
+            }
+        }
+        map.set_tile(door.x, door.y, TileType::SecretDoor);
+        map.secret_rooms.push(centre);
+        return;
+    }
+}
+
+/// Is everything within `radius` of `centre` wall, clear of the map's edge?
+pub(super) fn solid_rock(map: &Map, centre: Position, radius: i32) -> bool {
+    let inside = centre.x - radius > 0 && centre.y - radius > 0
+        && centre.x + radius < map.width - 1 && centre.y + radius < map.height - 1;
+    inside && (centre.y - radius..=centre.y + radius).all(|y| {
+        (centre.x - radius..=centre.x + radius).all(|x| map.get_tile(x, y).is_some_and(|t| t.tile_type == TileType::Wall))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::generation::check::reachable;
+    use crate::world::Biome;
+
+    #[test]
+    fn test_secret_room() {
+        // A corridor through solid rock has room either side
+        let mut map = Map::new(30, 30, 3, Biome::SunkenCatacombs);
+        for x in 1..29 {
+            map.set_tile(x, 15, TileType::Corridor);
+        }
+        map.start_pos = Position::new(1, 15);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        while map.secret_rooms.is_empty() {
+            add_secret_room(&mut rng, &mut map);
+        }
+        let room = map.secret_rooms[0];
+        assert!(map.is_walkable(room.x, room.y) && map.is_secret_room(room));
+        let doors: Vec<usize> = (0..map.tiles.len()).filter(|i| map.tiles[*i].tile_type == TileType::SecretDoor).collect();
+        assert_eq!(doors.len(), 1);
+        assert!(reachable(&map, map.start_pos)[map.xy_to_idx(room.x, room.y)]);
+
+        // The door is the only way in
+        let (x, y) = map.idx_to_xy(doors[0]);
+        map.set_tile(x, y, TileType::Wall);
+        assert!(!reachable(&map, map.start_pos)[map.xy_to_idx(room.x, room.y)]);
+    }
+}
@@ -0,0 +1,95 @@
+//! Teleporter pads
+//!
+//! Sprawling floors get linked pairs of pads so the player isn't stuck
+//! retracing a long walk. A pair only goes down where its two ends lie a
+//! long way apart on foot, so compact floors go without.
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::ecs::Position;
+use crate::world::{DijkstraMap, Map, TileType};
+
+/// Most pairs of pads a floor gets
+const MAX_PAIRS: usize = 2;
+/// Steps a pair's pads must be apart on foot to be worth linking
+const MIN_PAD_WALK: u32 = 75;
+/// Pads keep this far from the start, the stairs and each other
+const PAD_CLEARANCE: i32 = 5;
+/// Chance a floor with one pair gets another
+const SECOND_PAIR_CHANCE: f64 = 0.5;
+/// First pads tried for each pair before giving up
+const PLACEMENT_ATTEMPTS: usize = 8;
+
+/// Link far-apart spots on a large floor with pairs of teleporter pads
+pub fn add_teleporters(rng: &mut StdRng, map: &mut Map) {
+    for _ in 0..MAX_PAIRS {
+        let spots = pad_spots(map);
+        let pair = (0..PLACEMENT_ATTEMPTS).find_map(|_| {
+            let from = *spots.choose(rng)?;
+            let walk = DijkstraMap::new(map, from);
+            let far: Vec<Position> = spots.iter()
+                .copied()
+                .filter(|pos| walk.distance(*pos).is_some_and(|d| d >= MIN_PAD_WALK))
+                .collect();
+            far.choose(rng).map(|to| (from, *to))
+        });
+        let Some((from, to)) = pair else { return };
+
+        map.set_tile(from.x, from.y, TileType::Teleporter);
+        map.set_tile(to.x, to.y, TileType::Teleporter);
+        map.teleporters.push((from, to));
+        if !rng.gen_bool(SECOND_PAIR_CHANCE) {
+            return;
+        }
+    }
+}
+
+/// Plain floor out of the way of the start, the stairs and other pads
+fn pad_spots(map: &Map) -> Vec<Position> {
+    let clear_of = |pos: Position, other: Position| pos.chebyshev_distance(&other) >= PAD_CLEARANCE;
+    map.get_walkable_positions()
+        .into_iter()
+        .filter(|pos| map.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type == TileType::Floor))
+        .filter(|pos| !map.is_narrow_passage(*pos) && !map.is_secret_room(*pos))
+        .filter(|pos| clear_of(*pos, map.start_pos) && map.exit_pos.is_none_or(|exit| clear_of(*pos, exit)))
+        .filter(|pos| map.teleporters.iter().all(|(a, b)| clear_of(*pos, *a) && clear_of(*pos, *b)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::Biome;
+
+    #[test]
+    fn test_teleporters() {
+        // A long corridor gets a pair linking its far ends
+        let mut map = Map::new(100, 5, 3, Biome::SunkenCatacombs);
+        for y in 1..4 {
+            for x in 1..99 {
+                map.set_tile(x, y, TileType::Floor);
+            }
+        }
+        map.start_pos = Position::new(1, 2);
+        let mut rng = StdRng::seed_from_u64(7);
+        add_teleporters(&mut rng, &mut map);
+
+        let (a, b) = map.teleporters[0];
+        assert!((a.x - b.x).abs() >= MIN_PAD_WALK as i32);
+        assert_eq!(map.teleporter_exit(a), Some(b));
+        assert_eq!(map.teleporter_exit(b), Some(a));
+        assert_eq!(map.get_tile(b.x, b.y).map(|t| t.tile_type), Some(TileType::Teleporter));
+
+        // A small room gets none
+        let mut small = Map::new(20, 20, 3, Biome::SunkenCatacombs);
+        for y in 1..19 {
+            for x in 1..19 {
+                small.set_tile(x, y, TileType::Floor);
+            }
+        }
+        add_teleporters(&mut rng, &mut small);
+        assert!(small.teleporters.is_empty());
+    }
+}
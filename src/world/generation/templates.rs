@@ -1,4 +1,132 @@
 //! Handcrafted room templates
 
-// Pre-designed special rooms will be added here
-pub fn placeholder() {}
+use crate::world::{Biome, Map, TileType};
+use crate::ecs::Position;
+
+/// The tutorial floor, one room per lesson from west to east. Letters mark
+/// where the game spawns things: `r` an enemy and `!` an item.
+const TUTORIAL_LAYOUT: [&str; 9] = [
+    "##################################################",
+    "#.......#..........#.........#.........#.........#",
+    "#.......#..........#.........#.........#.........#",
+    "#.......#..........#.........#.........#.........#",
+    "#..@..............r.............!.........S....>.#",
+    "#.......#..........#.........#.........#.........#",
+    "#.......#..........#.........#.........#.........#",
+    "#.......#..........#.........#.........#.........#",
+    "##################################################",
+];
+
+/// Build the tutorial floor, with the spots marked for spawns
+pub fn tutorial_floor() -> (Map, Vec<(char, Position)>) {
+    let width = TUTORIAL_LAYOUT[0].len() as i32;
+    let mut map = Map::new(width, TUTORIAL_LAYOUT.len() as i32, 0, Biome::SunkenCatacombs);
+    let mut spawns = Vec::new();
+
+    for (y, row) in TUTORIAL_LAYOUT.iter().enumerate() {
+        for (x, glyph) in row.chars().enumerate() {
+            let pos = Position::new(x as i32, y as i32);
+            let tile = match glyph {
+                '#' => TileType::Wall,
+                '>' => TileType::StairsDown,
+                'S' => TileType::ShrineRest,
+                _ => TileType::Floor,
+            };
+            map.set_tile(pos.x, pos.y, tile);
+            match glyph {
+                '@' => map.start_pos = pos,
+                '>' => map.exit_pos = Some(pos),
+                'r' | '!' => spawns.push((glyph, pos)),
+                _ => {}
+            }
+        }
+    }
+
+    (map, spawns)
+}
+
+/// The last floor of the Abyss, where the Void Harbinger waits on its dais
+/// across a lake of fire. `a` marks an alcove where an add waits.
+const FINAL_LAYOUT: [&str; 28] = [
+    "#########################################",
+    "############.................############",
+    "###...######.B......>......B.######...###",
+    "###.a...............................a.###",
+    "###...######.................######...###",
+    "############.................############",
+    "######~~~~~~~~~~~~~...~~~~~~~~~~~~~######",
+    "######~~~~~~~~~~~~~...~~~~~~~~~~~~~######",
+    "######~~~~~~~~~~~~~...~~~~~~~~~~~~~######",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~.....~~~~~~~~.....~~~~~~~~.....~~###",
+    "###~~..a.........................a..~~###",
+    "###~~.....~~~~~~~~.....~~~~~~~~.....~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "###~~~~~~~~~~~~~~~.....~~~~~~~~~~~~~~~###",
+    "#############...............#############",
+    "#############.T...........T.#############",
+    "#############...............#############",
+    "#############.......@.......#############",
+    "#############...............#############",
+    "#########################################",
+];
+
+/// Build the final floor: a causeway over lava, side platforms for the
+/// Harbinger's adds, and the way out behind its dais
+pub fn final_floor(floor: u32) -> Map {
+    let width = FINAL_LAYOUT[0].len() as i32;
+    let mut map = Map::new(width, FINAL_LAYOUT.len() as i32, floor, Biome::TheAbyss);
+
+    for (y, row) in FINAL_LAYOUT.iter().enumerate() {
+        for (x, glyph) in row.chars().enumerate() {
+            let pos = Position::new(x as i32, y as i32);
+            let tile = match glyph {
+                '#' => TileType::Wall,
+                '~' => TileType::Lava,
+                '>' => TileType::StairsDown,
+                'B' => TileType::Brazier,
+                'T' => TileType::Torch,
+                _ => TileType::Floor,
+            };
+            map.set_tile(pos.x, pos.y, tile);
+            match glyph {
+                '@' => map.start_pos = pos,
+                '>' => map.exit_pos = Some(pos),
+                'a' => map.alcoves.push(pos),
+                _ => {}
+            }
+        }
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tutorial_floor() {
+        let (map, spawns) = tutorial_floor();
+        let exit = map.exit_pos.expect("tutorial floor has stairs");
+        assert!(map.is_walkable(map.start_pos.x, map.start_pos.y));
+        assert!(map.get_tile(exit.x, exit.y).is_some_and(|t| t.tile_type == TileType::StairsDown));
+        assert_eq!(spawns.iter().map(|(glyph, _)| *glyph).collect::<String>(), "r!");
+    }
+
+    #[test]
+    fn test_final_floor() {
+        let map = final_floor(20);
+        assert!(FINAL_LAYOUT.iter().all(|row| row.len() == FINAL_LAYOUT[0].len()));
+        assert_eq!(map.alcoves.len(), 4);
+        assert!(map.exit_pos.is_some());
+        assert!(super::super::check::check_map(&map).is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+//! Toll gates
+//!
+//! From the second floor down, a narrow passage on the quickest way to the
+//! stairs may be barred by a toll gate. Paying opens it; the stingy can
+//! always walk the long way round, since a gate only goes where blocking it
+//! cuts nothing off and the detour is well worth the fee.
+//!
+//! Like the mechanisms, this runs after connectivity repair.
+
+use std::collections::VecDeque;
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::ecs::Position;
+use crate::world::{Map, TileType};
+use super::check::{passable, reachable};
+
+/// First floor with toll gates
+const TOLL_MIN_FLOOR: u32 = 2;
+/// Chance a floor has a toll gate
+const TOLL_CHANCE: f64 = 0.4;
+/// Extra steps the way round must cost for a gate to be worth its fee
+const MIN_DETOUR: i32 = 15;
+/// Passages tried before giving up
+const PLACEMENT_ATTEMPTS: usize = 25;
+/// Gates keep this far from the start and the stairs
+const LANDMARK_CLEARANCE: i32 = 3;
+
+/// Maybe bar a shortcut to the stairs with a toll gate
+pub fn add_toll_gate(rng: &mut StdRng, map: &mut Map) {
+    if map.floor_number < TOLL_MIN_FLOOR || !rng.gen_bool(TOLL_CHANCE) {
+        return;
+    }
+    let Some(exit) = map.exit_pos else { return };
+    let from_start = distances(map, map.start_pos);
+    let from_exit = distances(map, exit);
+    let Some(shortest) = from_start[map.xy_to_idx(exit.x, exit.y)] else { return };
+    let reach = reachable(map, map.start_pos);
+
+    let mut passages: Vec<Position> = map.get_walkable_positions()
+        .into_iter()
+        .filter(|pos| {
+            let idx = map.xy_to_idx(pos.x, pos.y);
+            let on_way = matches!((from_start[idx], from_exit[idx]), (Some(a), Some(b)) if a + b == shortest);
+            on_way && narrow(map, *pos) && clear(map, *pos)
+        })
+        .collect();
+    passages.shuffle(rng);
+
+    for pos in passages.into_iter().take(PLACEMENT_ATTEMPTS) {
+        let ground = map.get_tile(pos.x, pos.y).map_or(TileType::Floor, |t| t.tile_type);
+        map.set_tile(pos.x, pos.y, TileType::TollGate);
+        let detour = distances(map, map.start_pos)[map.xy_to_idx(exit.x, exit.y)];
+        let after = reachable(map, map.start_pos);
+        let cut_off = reach.iter().zip(&after).enumerate()
+            .any(|(idx, (was, is))| *was && !is && idx != map.xy_to_idx(pos.x, pos.y));
+        if !cut_off && detour.is_some_and(|d| d >= shortest + MIN_DETOUR) {
+            return;
+        }
+        map.set_tile(pos.x, pos.y, ground);
+    }
+}
+
+/// Steps from `from` to every tile the player can get to, by tile index
+fn distances(map: &Map, from: Position) -> Vec<Option<i32>> {
+    let mut dist = vec![None; map.tiles.len()];
+    dist[map.xy_to_idx(from.x, from.y)] = Some(0);
+    let mut queue = VecDeque::from([from]);
+    while let Some(pos) = queue.pop_front() {
+        let step = dist[map.xy_to_idx(pos.x, pos.y)].unwrap_or(0) + 1;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (x, y) = (pos.x + dx, pos.y + dy);
+                let open = map.get_tile(x, y).is_some_and(|t| passable(t.tile_type));
+                if open && dist[map.xy_to_idx(x, y)].is_none() {
+                    dist[map.xy_to_idx(x, y)] = Some(step);
+                    queue.push_back(Position::new(x, y));
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// A one-tile-wide passage, walled in on both sides
+fn narrow(map: &Map, pos: Position) -> bool {
+    let wall = |dx: i32, dy: i32| map.get_tile(pos.x + dx, pos.y + dy).is_some_and(|t| t.tile_type == TileType::Wall);
+    (wall(-1, 0) && wall(1, 0)) || (wall(0, -1) && wall(0, 1))
+}
+
+/// Plain ground, clear of the landmarks and anything already set down
+fn clear(map: &Map, pos: Position) -> bool {
+    map.get_tile(pos.x, pos.y).is_some_and(|t| matches!(t.tile_type, TileType::Floor | TileType::Corridor))
+        && pos.chebyshev_distance(&map.start_pos) > LANDMARK_CLEARANCE
+        && map.exit_pos.is_none_or(|exit| pos.chebyshev_distance(&exit) > LANDMARK_CLEARANCE)
+        && !map.has_prop(pos)
+        && !map.is_secret_room(pos)
+        && !map.is_vault(pos)
+        && map.teleporter_exit(pos).is_none()
+        && !map.mechanisms.iter().any(|m| m.at == pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::world::Biome;
+
+    #[test]
+    fn test_toll_gate_bars_the_shortcut_only() {
+        // A straight corridor to the stairs, and a long loop round
+        let mut map = Map::new(40, 24, 5, Biome::SunkenCatacombs);
+        for x in 2..38 {
+            map.set_tile(x, 16, TileType::Corridor);
+            map.set_tile(x, 1, TileType::Corridor);
+        }
+        for y in 1..17 {
+            map.set_tile(2, y, TileType::Corridor);
+            map.set_tile(37, y, TileType::Corridor);
+        }
+        map.start_pos = Position::new(2, 16);
+        map.exit_pos = Some(Position::new(37, 16));
+        let gates = |map: &Map| -> Vec<Position> {
+            (0..map.tiles.len())
+                .filter(|idx| map.tiles[*idx].tile_type == TileType::TollGate)
+                .map(|idx| map.idx_to_xy(idx))
+                .map(|(x, y)| Position::new(x, y))
+                .collect()
+        };
+
+        let gated = (0..20)
+            .map(|seed| {
+                let mut attempt = map.clone();
+                add_toll_gate(&mut StdRng::seed_from_u64(seed), &mut attempt);
+                attempt
+            })
+            .find(|attempt| !gates(attempt).is_empty())
+            .expect("no seed placed a toll gate");
+
+        // One gate, on the shortcut, with the stairs still reachable the
+        // long way round
+        let placed = gates(&gated);
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].y, 16);
+        let exit = gated.exit_pos.unwrap();
+        assert!(reachable(&gated, gated.start_pos)[gated.xy_to_idx(exit.x, exit.y)]);
+    }
+}
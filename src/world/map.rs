@@ -2,10 +2,19 @@
 //!
 //! The 2D grid representing a dungeon floor.
 
+use std::collections::HashMap;
+
+use super::decals::Decal;
+use super::mechanisms::Mechanism;
 use super::tile::{Tile, TileType};
 use crate::ecs::Position;
 use serde::{Deserialize, Serialize};
 
+/// Tiles a secret treasure room reaches either side of its centre
+pub const SECRET_ROOM_RADIUS: i32 = 1;
+/// Tiles a puzzle vault reaches either side of its centre
+pub const VAULT_RADIUS: i32 = 1;
+
 /// A dungeon floor map
 #[derive(Debug, Clone)]
 pub struct Map {
@@ -20,6 +29,29 @@ pub struct Map {
     pub exit_pos: Option<Position>,
     /// Elite room positions (centers) - dangerous but rewarding
     pub elite_rooms: Vec<Position>,
+    /// Boss floor alcoves where the boss's adds wait (only read when the
+    /// floor is first furnished, so not saved)
+    pub alcoves: Vec<Position>,
+    /// Treasure rooms behind secret doors (centres; also only read when
+    /// furnished)
+    pub secret_rooms: Vec<Position>,
+    /// Tiles flooded by the floor's river or chasm (only read by the
+    /// generation checks, so not saved)
+    pub river: Vec<Position>,
+    /// Where heart nodes take root (also only read when furnished)
+    pub hearts: Vec<Position>,
+    /// Bone piles, braziers and statues to set out (also only read when
+    /// furnished; after that they're entities)
+    pub props: Vec<(Position, crate::entities::Prop)>,
+    /// Puzzle vaults behind portcullises (centres; also only read when
+    /// furnished)
+    pub vaults: Vec<Position>,
+    /// Levers and pressure plates, and what each is wired to
+    pub mechanisms: Vec<Mechanism>,
+    /// Linked teleporter pads; each carries the player to the other
+    pub teleporters: Vec<(Position, Position)>,
+    /// Blood, scorch marks and bones left by fighting
+    pub decals: HashMap<Position, Decal>,
 }
 
 /// Biome types for different dungeon zones
@@ -28,6 +60,7 @@ pub enum Biome {
     SunkenCatacombs,
     BleedingCrypts,
     HollowCathedral,
+    FleshGardens,
     TheAbyss,
 }
 
@@ -44,6 +77,15 @@ impl Map {
             start_pos: Position::new(0, 0),
             exit_pos: None,
             elite_rooms: Vec::new(),
+            alcoves: Vec::new(),
+            secret_rooms: Vec::new(),
+            river: Vec::new(),
+            hearts: Vec::new(),
+            props: Vec::new(),
+            vaults: Vec::new(),
+            mechanisms: Vec::new(),
+            teleporters: Vec::new(),
+            decals: HashMap::new(),
         }
     }
 
@@ -63,6 +105,49 @@ impl Map {
         &self.elite_rooms
     }
 
+    /// Is a position inside a secret treasure room?
+    pub fn is_secret_room(&self, pos: Position) -> bool {
+        self.secret_rooms.iter().any(|room| room.chebyshev_distance(&pos) <= SECRET_ROOM_RADIUS)
+    }
+
+    /// Is a position inside a puzzle vault?
+    pub fn is_vault(&self, pos: Position) -> bool {
+        self.vaults.iter().any(|vault| vault.chebyshev_distance(&pos) <= VAULT_RADIUS)
+    }
+
+    /// Is a prop set out at a position?
+    pub fn has_prop(&self, pos: Position) -> bool {
+        self.props.iter().any(|(p, _)| *p == pos)
+    }
+
+    /// Where the teleporter pad at a position leads, if there is one
+    pub fn teleporter_exit(&self, pos: Position) -> Option<Position> {
+        self.teleporters.iter().find_map(|&(a, b)| {
+            if a == pos {
+                Some(b)
+            } else if b == pos {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Leave a decal on a floor tile, unless a stronger one is already there
+    pub fn add_decal(&mut self, pos: Position, decal: Decal) {
+        if !self.get_tile(pos.x, pos.y).is_some_and(|t| Decal::fits(t.tile_type)) {
+            return;
+        }
+        let slot = self.decals.entry(pos).or_insert(decal);
+        *slot = (*slot).max(decal);
+    }
+
+    /// Decal showing at a position
+    pub fn decal_at(&self, pos: Position) -> Option<Decal> {
+        self.decals.get(&pos).copied()
+            .filter(|_| self.get_tile(pos.x, pos.y).is_some_and(|t| Decal::fits(t.tile_type)))
+    }
+
     /// Convert 2D coordinates to 1D index
     #[inline]
     pub fn xy_to_idx(&self, x: i32, y: i32) -> usize {
@@ -109,6 +194,38 @@ impl Map {
         }
     }
 
+    /// Make sure the floor has a shrine of a kind, raising one on open
+    /// ground away from the start (or rededicating another shrine when there
+    /// is no room)
+    pub fn ensure_shrine(&mut self, shrine: TileType, rng: &mut impl rand::Rng) {
+        use rand::seq::SliceRandom;
+
+        if self.tiles.iter().any(|t| t.tile_type == shrine) {
+            return;
+        }
+        let open: Vec<Position> = self.get_npc_spawn_positions(8)
+            .into_iter()
+            .filter(|pos| self.get_tile(pos.x, pos.y).is_some_and(|t| t.tile_type == TileType::Floor))
+            .filter(|pos| !self.has_prop(*pos) && self.exit_pos != Some(*pos))
+            .collect();
+        let spot = open.choose(rng).copied().or_else(|| {
+            self.tiles.iter()
+                .position(|t| t.tile_type.is_shrine())
+                .map(|idx| self.idx_to_xy(idx))
+                .map(|(x, y)| Position::new(x, y))
+        });
+        if let Some(pos) = spot {
+            self.set_tile(pos.x, pos.y, shrine);
+        }
+    }
+
+    /// Tear down every shrine, leaving bare floor
+    pub fn remove_shrines(&mut self) {
+        for tile in self.tiles.iter_mut().filter(|t| t.tile_type.is_shrine()) {
+            tile.tile_type = TileType::Floor;
+        }
+    }
+
     /// Check if a position is walkable
     pub fn is_walkable(&self, x: i32, y: i32) -> bool {
         self.get_tile(x, y).map_or(false, |t| t.is_walkable())
@@ -223,7 +340,8 @@ impl Map {
     pub fn get_spawn_positions(&self, min_dist_from_start: i32) -> Vec<Position> {
         self.get_walkable_positions()
             .into_iter()
-            .filter(|pos| pos.chebyshev_distance(&self.start_pos) >= min_dist_from_start)
+            .filter(|pos| pos.chebyshev_distance(&self.start_pos) >= min_dist_from_start && !self.is_secret_room(*pos) && !self.is_vault(*pos))
+            .filter(|pos| self.teleporter_exit(*pos).is_none() && !self.hearts.contains(pos) && !self.has_prop(*pos))
             .collect()
     }
 
@@ -306,18 +424,32 @@ impl Map {
             .filter(|pos| {
                 pos.chebyshev_distance(&self.start_pos) >= min_dist_from_start
                     && !self.is_narrow_passage(*pos)
+                    && !self.is_secret_room(*pos)
+                    && !self.is_vault(*pos)
+                    && self.teleporter_exit(*pos).is_none()
+                    && !self.hearts.contains(pos)
+                    && !self.has_prop(*pos)
             })
             .collect()
     }
 }
 
 impl Biome {
+    pub const ALL: [Biome; 5] = [
+        Biome::SunkenCatacombs,
+        Biome::BleedingCrypts,
+        Biome::HollowCathedral,
+        Biome::FleshGardens,
+        Biome::TheAbyss,
+    ];
+
     /// Get the biome name for display
     pub fn name(&self) -> &'static str {
         match self {
             Biome::SunkenCatacombs => "Sunken Catacombs",
             Biome::BleedingCrypts => "Bleeding Crypts",
             Biome::HollowCathedral => "Hollow Cathedral",
+            Biome::FleshGardens => "Flesh Gardens",
             Biome::TheAbyss => "The Abyss",
         }
     }
@@ -328,6 +460,7 @@ impl Biome {
             Biome::SunkenCatacombs => (30, 25, 20),
             Biome::BleedingCrypts => (40, 15, 15),
             Biome::HollowCathedral => (25, 25, 35),
+            Biome::FleshGardens => (35, 15, 20),
             Biome::TheAbyss => (10, 10, 20),
         }
     }
@@ -0,0 +1,150 @@
+//! Mechanisms
+//!
+//! Levers and pressure plates, each wired to a portcullis or a trap. A
+//! lever stays where it was last thrown; a plate only works while something
+//! weighs it down, whether that's the player, a monster or a statue shoved
+//! onto it. The wiring lives on the map, so a floor's puzzles keep their
+//! state through a save.
+
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+
+use super::{Map, TileType};
+use crate::ecs::Position;
+
+/// What sets a mechanism off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trigger {
+    Lever,
+    Plate,
+}
+
+/// Traps a plate can spring on whoever steps on it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Trap {
+    /// Poisoned darts from the walls
+    Darts,
+    /// A gout of flame that lights any oil nearby
+    Flames,
+}
+
+/// What a mechanism works
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Wiring {
+    /// Portcullises raised while the mechanism is on
+    Gates(Vec<Position>),
+    /// A trap sprung each time the plate goes down
+    Trap(Trap),
+}
+
+/// A lever or plate and what it's wired to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mechanism {
+    pub trigger: Trigger,
+    /// The lever or plate tile
+    pub at: Position,
+    pub wiring: Wiring,
+    /// Lever thrown, or plate held down
+    pub on: bool,
+}
+
+impl Mechanism {
+    pub fn lever(at: Position, gates: Vec<Position>) -> Self {
+        Self { trigger: Trigger::Lever, at, wiring: Wiring::Gates(gates), on: false }
+    }
+
+    pub fn plate(at: Position, wiring: Wiring) -> Self {
+        Self { trigger: Trigger::Plate, at, wiring, on: false }
+    }
+}
+
+/// Throw the lever at a position. Returns whether it's now on, or None if
+/// there's no lever there.
+pub fn throw_lever(map: &mut Map, pos: Position) -> Option<bool> {
+    let lever = map.mechanisms.iter_mut().find(|m| m.trigger == Trigger::Lever && m.at == pos)?;
+    lever.on = !lever.on;
+    let on = lever.on;
+    map.set_tile(pos.x, pos.y, if on { TileType::LeverPulled } else { TileType::Lever });
+    Some(on)
+}
+
+/// Bring every mechanism up to date: plates go down under anything in
+/// `weighed` and spring their traps as they do, and every portcullis is
+/// raised or lowered to match its mechanism. A gate with something standing
+/// in it stays up until it's clear. Returns the traps sprung.
+pub fn work_mechanisms(map: &mut Map, weighed: &HashSet<Position>) -> Vec<(Trap, Position)> {
+    let mut sprung = Vec::new();
+    let mut gates = Vec::new();
+    for mechanism in &mut map.mechanisms {
+        if mechanism.trigger == Trigger::Plate {
+            let down = weighed.contains(&mechanism.at);
+            if down && !mechanism.on {
+                if let Wiring::Trap(trap) = mechanism.wiring {
+                    sprung.push((trap, mechanism.at));
+                }
+            }
+            mechanism.on = down;
+        }
+        if let Wiring::Gates(wired) = &mechanism.wiring {
+            gates.extend(wired.iter().map(|gate| (*gate, mechanism.on)));
+        }
+    }
+
+    for (gate, raised) in gates {
+        let blocked = weighed.contains(&gate);
+        match map.get_tile(gate.x, gate.y).map(|t| t.tile_type) {
+            Some(TileType::Portcullis) if raised => map.set_tile(gate.x, gate.y, TileType::PortcullisOpen),
+            Some(TileType::PortcullisOpen) if !raised && !blocked => map.set_tile(gate.x, gate.y, TileType::Portcullis),
+            _ => {}
+        }
+    }
+    sprung
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Biome;
+
+    #[test]
+    fn test_levers_and_plates() {
+        let mut map = Map::new(10, 5, 3, Biome::SunkenCatacombs);
+        let gate = Position::new(5, 2);
+        let lever = Position::new(1, 1);
+        let plate = Position::new(3, 3);
+        let trap = Position::new(7, 3);
+        map.set_tile(gate.x, gate.y, TileType::Portcullis);
+        map.set_tile(lever.x, lever.y, TileType::Lever);
+        map.set_tile(plate.x, plate.y, TileType::PressurePlate);
+        map.set_tile(trap.x, trap.y, TileType::PressurePlate);
+        map.mechanisms.push(Mechanism::lever(lever, vec![gate]));
+        map.mechanisms.push(Mechanism::plate(trap, Wiring::Trap(Trap::Darts)));
+        let nothing = HashSet::new();
+
+        // The lever raises the gate and lowers it again
+        assert_eq!(throw_lever(&mut map, lever), Some(true));
+        work_mechanisms(&mut map, &nothing);
+        assert!(map.is_walkable(gate.x, gate.y));
+        assert_eq!(throw_lever(&mut map, lever), Some(false));
+        assert_eq!(map.get_tile(lever.x, lever.y).unwrap().tile_type, TileType::Lever);
+        work_mechanisms(&mut map, &nothing);
+        assert!(!map.is_walkable(gate.x, gate.y));
+        assert_eq!(throw_lever(&mut map, plate), None);
+
+        // A plate holds the gate up only while weighed down, and the gate
+        // won't drop on anyone standing under it
+        map.mechanisms[0] = Mechanism::plate(plate, Wiring::Gates(vec![gate]));
+        work_mechanisms(&mut map, &HashSet::from([plate]));
+        assert!(map.is_walkable(gate.x, gate.y));
+        work_mechanisms(&mut map, &HashSet::from([gate]));
+        assert!(map.is_walkable(gate.x, gate.y));
+        work_mechanisms(&mut map, &nothing);
+        assert!(!map.is_walkable(gate.x, gate.y));
+
+        // A trap springs as its plate goes down, not while it stays down
+        assert_eq!(work_mechanisms(&mut map, &HashSet::from([trap])), vec![(Trap::Darts, trap)]);
+        assert!(work_mechanisms(&mut map, &HashSet::from([trap])).is_empty());
+        work_mechanisms(&mut map, &nothing);
+        assert_eq!(work_mechanisms(&mut map, &HashSet::from([trap])).len(), 1);
+    }
+}
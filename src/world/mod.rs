@@ -5,8 +5,14 @@
 pub mod map;
 pub mod tile;
 pub mod fov;
+pub mod dijkstra;
 pub mod generation;
+pub mod decals;
+pub mod mechanisms;
 
-pub use map::{Map, Biome};
+pub use map::{Map, Biome, SECRET_ROOM_RADIUS, VAULT_RADIUS};
 pub use tile::{Tile, TileType};
 pub use fov::compute_fov;
+pub use dijkstra::DijkstraMap;
+pub use decals::Decal;
+pub use mechanisms::{Mechanism, Trap, Trigger, Wiring, throw_lever, work_mechanisms};
@@ -70,17 +70,33 @@ pub enum TileType {
     // Basic terrain
     Floor,
     Wall,
+    FleshWall, // Living flesh; can be torn through, but grows back
 
     // Special floor types
     Corridor,
     Lava,
     Pit,
+    BloodPool,
+    DeepWater, // Rivers; too deep to wade, and sweeps away anything shoved in
+    Bridge,
+    TornFlesh, // A path torn through living flesh, slowly closing up
+    Oil, // Spilled lamp oil; burns up in one sheet if fire reaches it
 
     // Interactables
     DoorClosed,
     DoorOpen,
+    DoorLocked,
+    SecretDoor, // Passes for a wall until found
     StairsDown,
     StairsUp,
+    Teleporter, // Carries whoever steps on it to its partner pad
+    PressurePlate, // Works whatever it's wired to while something weighs it down
+    Lever, // Set in a wall; pulling it works whatever it's wired to
+    LeverPulled,
+    Portcullis, // Iron bars, raised by a lever or plate and never forced
+    PortcullisOpen,
+    TollGate, // Swings open for a fee, and only for a fee
+    TollGateOpen,
 
     // Decorative (biome-specific floor variations)
     Rubble,
@@ -101,6 +117,14 @@ pub enum TileType {
     ShrineEnchant,
     ShrineRest,
     ShrineCorruption, // Risk/reward: curse for power
+    ShrineGamble,
+    ShrineSacrifice,
+    ShrineTransmute,
+
+    // Altars to the gods
+    AltarMaw,
+    AltarWidow,
+    AltarEye,
 }
 
 impl TileType {
@@ -112,6 +136,12 @@ impl TileType {
                 | TileType::DoorOpen
                 | TileType::StairsDown
                 | TileType::StairsUp
+                | TileType::Teleporter
+                | TileType::PressurePlate
+                | TileType::PortcullisOpen
+                | TileType::TollGateOpen
+                | TileType::TornFlesh
+                | TileType::Oil
                 | TileType::Rubble
                 | TileType::Bones
                 | TileType::BloodStain
@@ -120,30 +150,57 @@ impl TileType {
                 | TileType::Moss
                 | TileType::Ashes
                 | TileType::Grime
+                | TileType::BloodPool
+                | TileType::Bridge
                 | TileType::Torch
                 | TileType::Brazier
                 | TileType::ShrineSkill
                 | TileType::ShrineEnchant
                 | TileType::ShrineRest
                 | TileType::ShrineCorruption
+                | TileType::ShrineGamble
+                | TileType::ShrineSacrifice
+                | TileType::ShrineTransmute
+                | TileType::AltarMaw
+                | TileType::AltarWidow
+                | TileType::AltarEye
         )
     }
 
     pub fn is_transparent(&self) -> bool {
-        !matches!(self, TileType::Wall | TileType::DoorClosed)
+        !matches!(
+            self,
+            TileType::Wall | TileType::FleshWall | TileType::DoorClosed | TileType::DoorLocked | TileType::SecretDoor
+                | TileType::Lever | TileType::LeverPulled
+        )
     }
 
     pub fn glyph(&self) -> char {
         match self {
             TileType::Floor => '.',
-            TileType::Wall => '#',
+            TileType::Wall | TileType::SecretDoor => '#',
+            TileType::FleshWall => '#',
             TileType::Corridor => '.',
             TileType::Lava => '≈',
             TileType::Pit => ' ',
+            TileType::BloodPool => '≈',
+            TileType::DeepWater => '≈',
+            TileType::Bridge => '=',
+            TileType::TornFlesh => ',',
+            TileType::Oil => '≈',
             TileType::DoorClosed => '+',
+            TileType::DoorLocked => '+',
             TileType::DoorOpen => '/',
             TileType::StairsDown => '>',
             TileType::StairsUp => '<',
+            TileType::Teleporter => '◎',
+            TileType::PressurePlate => '^',
+            TileType::Lever => '/',
+            TileType::LeverPulled => '\\',
+            TileType::Portcullis => '╫',
+            TileType::PortcullisOpen => '·',
+            TileType::TollGate => '╪',
+            TileType::TollGateOpen => '·',
             TileType::Rubble => ',',
             TileType::Bones => '%',
             TileType::BloodStain => '·',
@@ -158,20 +215,38 @@ impl TileType {
             TileType::ShrineEnchant => '✦',
             TileType::ShrineRest => '☥',
             TileType::ShrineCorruption => '☠',
+            TileType::ShrineGamble => '¢',
+            TileType::ShrineSacrifice => '†',
+            TileType::ShrineTransmute => '⚗',
+            TileType::AltarMaw | TileType::AltarWidow | TileType::AltarEye => 'Ψ',
         }
     }
 
     pub fn fg_color(&self) -> (u8, u8, u8) {
         match self {
             TileType::Floor => (80, 80, 80),
-            TileType::Wall => (130, 110, 90),
+            TileType::Wall | TileType::SecretDoor => (130, 110, 90),
+            TileType::FleshWall => (170, 70, 85),
             TileType::Corridor => (70, 70, 70),
             TileType::Lava => (255, 100, 0),
             TileType::Pit => (20, 20, 20),
+            TileType::BloodPool => (170, 20, 40),
+            TileType::DeepWater => (60, 110, 200),
+            TileType::Bridge => (150, 110, 70),
+            TileType::TornFlesh => (140, 50, 60),
+            TileType::Oil => (95, 85, 45),
             TileType::DoorClosed => (139, 90, 43),
+            TileType::DoorLocked => (190, 150, 60),
             TileType::DoorOpen => (139, 90, 43),
             TileType::StairsDown => (200, 200, 200),
             TileType::StairsUp => (200, 200, 200),
+            TileType::Teleporter => (140, 110, 255),
+            TileType::PressurePlate => (150, 140, 120),
+            TileType::Lever | TileType::LeverPulled => (190, 160, 90),
+            TileType::Portcullis => (150, 150, 165),
+            TileType::PortcullisOpen => (90, 90, 100),
+            TileType::TollGate => (210, 170, 60),
+            TileType::TollGateOpen => (110, 95, 50),
             TileType::Rubble => (100, 90, 80),
             TileType::Bones => (200, 200, 180),
             TileType::BloodStain => (150, 30, 30),
@@ -186,20 +261,38 @@ impl TileType {
             TileType::ShrineEnchant => (100, 200, 255), // Cyan for enchant shrine
             TileType::ShrineRest => (100, 255, 100),    // Green for rest shrine
             TileType::ShrineCorruption => (180, 50, 100), // Dark red/magenta for corruption
+            TileType::ShrineGamble => (255, 215, 0),      // Gold for gambling shrine
+            TileType::ShrineSacrifice => (220, 40, 40),   // Blood red for sacrifice shrine
+            TileType::ShrineTransmute => (120, 255, 200), // Alchemical green for transmutation
+            TileType::AltarMaw => (200, 30, 30),
+            TileType::AltarWidow => (190, 180, 170),
+            TileType::AltarEye => (60, 140, 200),
         }
     }
 
     pub fn bg_color(&self) -> (u8, u8, u8) {
         match self {
             TileType::Floor => (20, 18, 15),
-            TileType::Wall => (40, 35, 30),
+            TileType::Wall | TileType::SecretDoor => (40, 35, 30),
+            TileType::FleshWall => (55, 20, 28),
             TileType::Corridor => (15, 13, 10),
             TileType::Lava => (80, 20, 0),
             TileType::Pit => (5, 5, 5),
+            TileType::BloodPool => (50, 5, 15),
+            TileType::DeepWater => (10, 25, 60),
+            TileType::Bridge => (30, 22, 15),
+            TileType::TornFlesh => (35, 12, 16),
+            TileType::Oil => (25, 22, 12),
             TileType::DoorClosed => (30, 25, 20),
+            TileType::DoorLocked => (35, 28, 18),
             TileType::DoorOpen => (20, 18, 15),
             TileType::StairsDown => (20, 18, 15),
             TileType::StairsUp => (20, 18, 15),
+            TileType::Teleporter => (25, 15, 45),
+            TileType::PressurePlate => (30, 28, 24),
+            TileType::Lever | TileType::LeverPulled => (40, 35, 30),
+            TileType::Portcullis | TileType::PortcullisOpen => (20, 20, 25),
+            TileType::TollGate | TileType::TollGateOpen => (28, 24, 14),
             TileType::Rubble => (25, 22, 18),
             TileType::Bones => (20, 18, 15),
             TileType::BloodStain => (40, 15, 15),
@@ -214,6 +307,12 @@ impl TileType {
             TileType::ShrineEnchant => (15, 30, 40),
             TileType::ShrineRest => (15, 35, 15),
             TileType::ShrineCorruption => (40, 10, 25), // Dark ominous background
+            TileType::ShrineGamble => (40, 35, 10),
+            TileType::ShrineSacrifice => (40, 10, 10),
+            TileType::ShrineTransmute => (10, 35, 30),
+            TileType::AltarMaw => (40, 8, 8),
+            TileType::AltarWidow => (30, 28, 26),
+            TileType::AltarEye => (8, 20, 35),
         }
     }
 
@@ -223,16 +322,115 @@ impl TileType {
             TileType::Torch => Some(4),
             TileType::Brazier => Some(6),
             TileType::Lava => Some(3),
+            TileType::Teleporter => Some(2),
             TileType::ShrineSkill => Some(3),
             TileType::ShrineEnchant => Some(3),
             TileType::ShrineRest => Some(3),
             TileType::ShrineCorruption => Some(4), // Eerie glow
+            TileType::ShrineGamble => Some(3),
+            TileType::ShrineSacrifice => Some(3),
+            TileType::ShrineTransmute => Some(3),
+            TileType::AltarMaw | TileType::AltarWidow | TileType::AltarEye => Some(2),
             _ => None,
         }
     }
 
     /// Is this a shrine?
     pub fn is_shrine(&self) -> bool {
-        matches!(self, TileType::ShrineSkill | TileType::ShrineEnchant | TileType::ShrineRest | TileType::ShrineCorruption)
+        matches!(
+            self,
+            TileType::ShrineSkill
+                | TileType::ShrineEnchant
+                | TileType::ShrineRest
+                | TileType::ShrineCorruption
+                | TileType::ShrineGamble
+                | TileType::ShrineSacrifice
+                | TileType::ShrineTransmute
+        )
+    }
+
+    /// Is this an altar to one of the gods?
+    pub fn is_altar(&self) -> bool {
+        matches!(self, TileType::AltarMaw | TileType::AltarWidow | TileType::AltarEye)
+    }
+
+    /// Is this a shut door (closed or locked)?
+    pub fn is_shut_door(&self) -> bool {
+        matches!(self, TileType::DoorClosed | TileType::DoorLocked)
+    }
+
+    /// Does this block the way only until it's opened or torn through?
+    pub fn is_breachable(&self) -> bool {
+        self.is_shut_door() || *self == TileType::FleshWall
+    }
+
+    /// Does entering this tile harm whoever steps in?
+    pub fn is_hazard(&self) -> bool {
+        matches!(self, TileType::Lava | TileType::Pit | TileType::BloodPool)
+    }
+
+    /// Does this tile swallow anything shoved into it?
+    pub fn is_lethal(&self) -> bool {
+        matches!(self, TileType::Lava | TileType::Pit | TileType::DeepWater)
+    }
+
+    /// Name used when warning about a hazard
+    pub fn hazard_name(&self) -> &'static str {
+        match self {
+            TileType::Lava => "lava",
+            TileType::Pit => "pit",
+            TileType::BloodPool => "corrupted blood",
+            _ => "hazard",
+        }
+    }
+
+    /// Name shown when examining the tile
+    pub fn name(&self) -> &'static str {
+        match self {
+            TileType::Floor => "Stone floor",
+            TileType::Wall | TileType::SecretDoor => "Wall",
+            TileType::FleshWall => "Living flesh",
+            TileType::Corridor => "Corridor",
+            TileType::Lava => "Lava",
+            TileType::Pit => "Bottomless pit",
+            TileType::BloodPool => "Pool of corrupted blood",
+            TileType::DeepWater => "Deep water",
+            TileType::Bridge => "Bridge",
+            TileType::TornFlesh => "Torn flesh",
+            TileType::Oil => "Slick of oil",
+            TileType::DoorClosed => "Closed door",
+            TileType::DoorOpen => "Open door",
+            TileType::DoorLocked => "Locked door",
+            TileType::StairsDown => "Stairs down",
+            TileType::StairsUp => "Stairs up",
+            TileType::Teleporter => "Teleporter pad",
+            TileType::PressurePlate => "Pressure plate",
+            TileType::Lever => "Lever",
+            TileType::LeverPulled => "Pulled lever",
+            TileType::Portcullis => "Portcullis",
+            TileType::PortcullisOpen => "Raised portcullis",
+            TileType::TollGate => "Toll gate",
+            TileType::TollGateOpen => "Open toll gate",
+            TileType::Rubble => "Rubble",
+            TileType::Bones => "Scattered bones",
+            TileType::BloodStain => "Old bloodstain",
+            TileType::Cobweb => "Cobwebs",
+            TileType::Cracks => "Cracked floor",
+            TileType::Moss => "Moss",
+            TileType::Ashes => "Ashes",
+            TileType::Grime => "Grime",
+            TileType::Torch => "Torch",
+            TileType::Brazier => "Brazier",
+            TileType::ShrineSkill => "Skill Shrine",
+            TileType::ShrineEnchant => "Enchanting Shrine",
+            TileType::ShrineRest => "Rest Shrine",
+            TileType::ShrineCorruption => "Corruption Shrine",
+            TileType::ShrineGamble => "Gambling Shrine",
+            TileType::ShrineSacrifice => "Sacrifice Shrine",
+            TileType::ShrineTransmute => "Transmutation Shrine",
+            TileType::AltarMaw => "Altar of the Red Maw",
+            TileType::AltarWidow => "Altar of the Ashen Widow",
+            TileType::AltarEye => "Altar of the Drowned Eye",
+        }
     }
 }